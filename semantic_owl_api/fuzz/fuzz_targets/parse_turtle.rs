@@ -0,0 +1,5 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use semantic_owl_api::loader::fuzz::fuzz_parse;
+
+fuzz_target!(|data: &[u8]| fuzz_parse(data));
@@ -0,0 +1,261 @@
+//! Annotation audit module checks that terms carry the annotations a curation policy requires
+use crate::analysis::reasoner::sub_properties;
+use crate::analysis::validation::{EntityType, Violation};
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+pub const MISSING_ANNOTATION: &str = "MissingAnnotation";
+pub const DUPLICATE_ANNOTATION: &str = "DuplicateAnnotation";
+
+/// IAO `definition`
+pub const IAO_DEFINITION: &str = "http://purl.obolibrary.org/obo/IAO_0000115";
+/// IAO `has curation status`
+pub const IAO_CURATION_STATUS: &str = "http://purl.obolibrary.org/obo/IAO_0000114";
+pub const CCO_DEFINITION: &str =
+  "http://www.ontologyrepository.com/CommonCoreOntologies/definition";
+pub const CCO_IS_CURATED_IN_ONTOLOGY: &str =
+  "http://www.ontologyrepository.com/CommonCoreOntologies/is_curated_in_ontology";
+
+/// AnnotationPolicy lists the annotations required on the terms of an ontology
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnotationPolicy {
+  // full IRIs of the required annotation properties
+  pub properties: Vec<String>,
+
+  // kinds of the terms audited. e.g -> Class
+  pub entity_types: Vec<EntityType>,
+}
+
+impl AnnotationPolicy {
+  pub fn new(properties: Vec<String>, entity_types: Vec<EntityType>) -> AnnotationPolicy {
+    Self {
+      properties,
+      entity_types,
+    }
+  }
+
+  /// obo requires a label, an IAO definition and an IAO curation status on classes
+  pub fn obo() -> AnnotationPolicy {
+    let properties = [RDFS_LABEL, IAO_DEFINITION, IAO_CURATION_STATUS];
+    Self::new(
+      properties.iter().map(|x| String::from(*x)).collect(),
+      vec![EntityType::Class],
+    )
+  }
+
+  /// cco requires a label, a definition and the ontology curating the term on classes, as the
+  /// Common Core Ontologies do
+  pub fn cco() -> AnnotationPolicy {
+    let properties = [RDFS_LABEL, CCO_DEFINITION, CCO_IS_CURATED_IN_ONTOLOGY];
+    Self::new(
+      properties.iter().map(|x| String::from(*x)).collect(),
+      vec![EntityType::Class],
+    )
+  }
+}
+
+/// EntityAudit lists the required annotations an entity lacks or carries more than once
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EntityAudit {
+  pub iri: String,
+
+  // properties without a value
+  pub missing: Vec<String>,
+
+  // properties with several values in a language, with the statements giving them
+  pub duplicates: BTreeMap<String, Vec<TurtleTriple>>,
+}
+
+impl EntityAudit {
+  pub fn new(
+    iri: String,
+    missing: Vec<String>,
+    duplicates: BTreeMap<String, Vec<TurtleTriple>>,
+  ) -> EntityAudit {
+    Self {
+      iri,
+      missing,
+      duplicates,
+    }
+  }
+}
+
+/// AnnotationAudit lists the entities breaching an annotation policy, ordered by IRI
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AnnotationAudit {
+  // number of entities audited
+  pub audited: usize,
+
+  pub entities: Vec<EntityAudit>,
+}
+
+impl AnnotationAudit {
+  pub fn new(audited: usize, entities: Vec<EntityAudit>) -> AnnotationAudit {
+    Self { audited, entities }
+  }
+
+  /// get returns the audit of the entity with the given IRI, if it breaches the policy
+  pub fn get(&self, iri: &str) -> Option<&EntityAudit> {
+    self.entities.iter().find(|x| x.iri == iri)
+  }
+
+  /// is_clean determines whether every audited entity complies with the policy
+  pub fn is_clean(&self) -> bool {
+    self.entities.is_empty()
+  }
+
+  /// violations returns one `MissingAnnotation` or `DuplicateAnnotation` violation per entity and
+  /// property breaching the policy
+  pub fn violations(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    for entity in self.entities.iter() {
+      for property in entity.missing.iter() {
+        let message = format!("<{}> has no <{}> annotation", entity.iri, property);
+        violations.push(Violation::new(
+          MISSING_ANNOTATION,
+          &entity.iri,
+          message,
+          vec![],
+        ));
+      }
+      for (property, statements) in entity.duplicates.iter() {
+        let message = format!(
+          "<{}> has {} <{}> annotations in the same language",
+          entity.iri,
+          statements.len(),
+          property
+        );
+        violations.push(Violation::new(
+          DUPLICATE_ANNOTATION,
+          &entity.iri,
+          message,
+          statements.clone(),
+        ));
+      }
+    }
+    violations
+  }
+}
+
+impl TurtleDocument {
+  /// annotation_audit checks the entities of the kinds required by a policy against the policy.
+  /// Only the entities this document states something about are audited, leaving out the terms
+  /// it merely refers to, e.g the imported super classes
+  pub fn annotation_audit(&self, policy: &AnnotationPolicy) -> AnnotationAudit {
+    let triples = self.triples();
+    let subjects: BTreeSet<&str> = triples.iter().filter_map(|t| t.subject.as_iri()).collect();
+    let audited: Vec<String> = self
+      .entities()
+      .into_iter()
+      .filter(|(iri, kinds)| {
+        subjects.contains(iri.as_str()) && policy.entity_types.iter().any(|x| kinds.contains(x))
+      })
+      .map(|(iri, _)| iri)
+      .collect();
+    let properties: Vec<(&String, BTreeSet<String>)> = policy
+      .properties
+      .iter()
+      .map(|p| (p, sub_properties(&triples, p)))
+      .collect();
+
+    // statements giving a value to a required property, per entity and property
+    let mut values: BTreeMap<(&str, &str), Vec<&TurtleTriple>> = BTreeMap::new();
+    for t in triples.iter() {
+      let (subject, predicate) = match (t.subject.as_iri(), t.predicate.as_iri()) {
+        (Some(s), Some(p)) => (s, p),
+        _ => continue,
+      };
+      for (property, found) in properties.iter() {
+        if found.contains(predicate) {
+          values.entry((subject, property)).or_default().push(t);
+        }
+      }
+    }
+
+    let mut entities = vec![];
+    for iri in audited.iter() {
+      let mut missing = vec![];
+      let mut duplicates = BTreeMap::new();
+      for (property, _) in properties.iter() {
+        let statements = match values.get(&(iri.as_str(), property.as_str())) {
+          Some(x) => x,
+          None => {
+            missing.push(String::clone(property));
+            continue;
+          }
+        };
+        let mut languages: BTreeMap<Option<&str>, Vec<TurtleTriple>> = BTreeMap::new();
+        for t in statements.iter() {
+          let language = match &t.object {
+            TurtleTerm::Literal { language, .. } => language.as_deref(),
+            _ => None,
+          };
+          languages.entry(language).or_default().push((*t).clone());
+        }
+        let duplicated: Vec<TurtleTriple> = languages
+          .into_iter()
+          .filter(|(_, x)| x.len() > 1)
+          .flat_map(|(_, x)| x)
+          .collect();
+        if !duplicated.is_empty() {
+          duplicates.insert(String::clone(property), duplicated);
+        }
+      }
+      if !missing.is_empty() || !duplicates.is_empty() {
+        entities.push(EntityAudit::new(iri.clone(), missing, duplicates));
+      }
+    }
+    AnnotationAudit::new(audited.len(), entities)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+
+skos:prefLabel rdfs:subPropertyOf rdfs:label .
+cco:Agent a owl:Class ; skos:prefLabel "Agent"@en ; rdfs:label "Agent"@fr ;
+  rdfs:subClassOf obo:BFO_0000040 ;
+  cco:definition "A material entity that bears an agent capability" ;
+  cco:is_curated_in_ontology <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> .
+cco:Person a owl:Class ; rdfs:label "Person"@en , "Human"@en ; rdfs:subClassOf cco:Agent .
+cco:has_part a owl:ObjectProperty .
+"#;
+
+  #[test]
+  fn should_audit_annotations() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let audit = document.annotation_audit(&AnnotationPolicy::cco());
+    // the imported BFO class and the property are not audited
+    assert_eq!(audit.audited, 2);
+    assert_eq!(audit.entities.len(), 1);
+    let person = "http://www.ontologyrepository.com/CommonCoreOntologies/Person";
+    let entity = audit.get(person).unwrap();
+    assert_eq!(
+      entity.missing,
+      vec![CCO_DEFINITION, CCO_IS_CURATED_IN_ONTOLOGY]
+    );
+    assert_eq!(entity.duplicates[RDFS_LABEL].len(), 2);
+
+    let rules: Vec<String> = audit.violations().into_iter().map(|v| v.rule).collect();
+    assert_eq!(
+      rules,
+      vec![MISSING_ANNOTATION, MISSING_ANNOTATION, DUPLICATE_ANNOTATION]
+    );
+    let policy = AnnotationPolicy::new(vec![String::from(RDFS_LABEL)], vec![EntityType::Class]);
+    assert!(!document.annotation_audit(&policy).is_clean());
+    Ok(())
+  }
+}
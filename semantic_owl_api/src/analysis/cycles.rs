@@ -0,0 +1,145 @@
+//! Cycles module finds the cycles of the `rdfs:subClassOf` and `rdfs:subPropertyOf` hierarchies
+use crate::analysis::validation::Violation;
+use crate::declarations::turtle::{TurtleDocument, TurtleTriple};
+use crate::vocab::rdfs;
+use petgraph::algo::tarjan_scc;
+use petgraph::graphmap::DiGraphMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+const RDFS_SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const RDFS_SUB_PROPERTY_OF: &str = rdfs::SUB_PROPERTY_OF.as_str();
+
+pub const CYCLIC_SUB_CLASS_OF: &str = "CyclicSubClassOf";
+pub const CYCLIC_SUB_PROPERTY_OF: &str = "CyclicSubPropertyOf";
+
+impl TurtleDocument {
+  /// hierarchy_cycles reports one cycle for each group of named classes, and of properties, which
+  /// are sub classes, or sub properties, of one another. A class stated to be a sub class of
+  /// itself is not reported
+  /// example
+  /// `CyclicSubClassOf: <A> -> <B> -> <A>`
+  pub fn hierarchy_cycles(&self) -> Vec<Violation> {
+    let triples = self.triples();
+    let mut violations = cycles(&triples, RDFS_SUB_CLASS_OF, CYCLIC_SUB_CLASS_OF);
+    violations.append(&mut cycles(
+      &triples,
+      RDFS_SUB_PROPERTY_OF,
+      CYCLIC_SUB_PROPERTY_OF,
+    ));
+    violations
+  }
+}
+
+// cycles finds the cycles of the hierarchy between the named entities linked by `predicate`
+fn cycles(triples: &[TurtleTriple], predicate: &str, rule: &str) -> Vec<Violation> {
+  // the first statement of each edge, e.g -> (Person, Agent) -> Person rdfs:subClassOf Agent
+  let mut edges: BTreeMap<(&str, &str), &TurtleTriple> = BTreeMap::new();
+  for t in triples.iter() {
+    if t.predicate.as_iri() != Some(predicate) {
+      continue;
+    }
+    if let (Some(sub), Some(sup)) = (t.subject.as_iri(), t.object.as_iri()) {
+      if sub != sup {
+        edges.entry((sub, sup)).or_insert(t);
+      }
+    }
+  }
+  let graph: DiGraphMap<&str, ()> = edges.keys().map(|(a, b)| (*a, *b, ())).collect();
+
+  let mut components: Vec<BTreeSet<&str>> = tarjan_scc(&graph)
+    .into_iter()
+    .filter(|x| x.len() > 1)
+    .map(|x| x.into_iter().collect())
+    .collect();
+  components.sort();
+  components
+    .iter()
+    .map(|component| {
+      let path = shortest_cycle(&graph, component);
+      let statements: Vec<TurtleTriple> = path
+        .windows(2)
+        .map(|x| edges[&(x[0], x[1])].clone())
+        .collect();
+      let message = path
+        .iter()
+        .map(|x| format!("<{}>", x))
+        .collect::<Vec<_>>()
+        .join(" -> ");
+      Violation::new(rule, path[0], message, statements)
+    })
+    .collect()
+}
+
+// shortest_cycle returns the shortest path from the first entity of a strongly connected component
+// back to itself, both ends included
+fn shortest_cycle<'a>(
+  graph: &DiGraphMap<&'a str, ()>,
+  component: &BTreeSet<&'a str>,
+) -> Vec<&'a str> {
+  let start = *component.iter().next().unwrap();
+  let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+  let mut queue = VecDeque::from(vec![start]);
+  while let Some(node) = queue.pop_front() {
+    for next in graph.neighbors(node) {
+      if !component.contains(next) || previous.contains_key(next) {
+        continue;
+      }
+      previous.insert(next, node);
+      if next == start {
+        queue.clear();
+        break;
+      }
+      queue.push_back(next);
+    }
+  }
+  // walk back from the start, reached last, to the start
+  let mut path = vec![start];
+  let mut node = previous[start];
+  while node != start {
+    path.push(node);
+    node = previous[node];
+  }
+  path.push(start);
+  path.reverse();
+  path
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/> .
+
+:A rdfs:subClassOf :B .
+:B rdfs:subClassOf :C .
+:C rdfs:subClassOf :A , :D .
+:D rdfs:subClassOf :D .
+:E rdfs:subClassOf :B .
+:p rdfs:subPropertyOf :q .
+:q rdfs:subPropertyOf :p .
+"#;
+
+  #[test]
+  fn should_report_hierarchy_cycles() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let violations = document.hierarchy_cycles();
+    assert_eq!(violations.len(), 2);
+    assert_eq!(
+      violations[0].to_string(),
+      "CyclicSubClassOf: <http://example.org/A> -> <http://example.org/B> -> <http://example.org/C> -> <http://example.org/A>"
+    );
+    let lines: Vec<usize> = violations[0]
+      .statements
+      .iter()
+      .filter_map(|t| t.source_span())
+      .map(|x| x.lines.start)
+      .collect();
+    assert_eq!(lines, vec![4, 5, 6]);
+    assert_eq!(violations[1].rule, CYCLIC_SUB_PROPERTY_OF);
+    assert_eq!(violations[1].iri, "http://example.org/p");
+    assert_eq!(violations[1].statements.len(), 2);
+    Ok(())
+  }
+}
@@ -0,0 +1,214 @@
+//! Deprecation module reports the deprecated terms of an ontology with their replacements
+use crate::analysis::reasoner::PropertyValues;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::owl;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const OWL_DEPRECATED: &str = owl::DEPRECATED.as_str();
+/// IAO `has obsolescence reason`
+pub const IAO_OBSOLESCENCE_REASON: &str = "http://purl.obolibrary.org/obo/IAO_0000231";
+/// IAO `term replaced by`
+pub const IAO_TERM_REPLACED_BY: &str = "http://purl.obolibrary.org/obo/IAO_0100001";
+pub const OBO_IN_OWL_REPLACED_BY: &str = "http://www.geneontology.org/formats/oboInOwl#replacedBy";
+pub const OBO_IN_OWL_CONSIDER: &str = "http://www.geneontology.org/formats/oboInOwl#consider";
+
+/// DeprecatedTerm describes a deprecated entity and what to use instead
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DeprecatedTerm {
+  pub iri: String,
+
+  // english, or untagged, label of the term
+  pub label: Option<String>,
+
+  // terms which replace the deprecated term, from `IAO:0100001` and `oboInOwl:replacedBy`
+  pub replaced_by: Vec<String>,
+
+  // terms suggested as alternatives, from `oboInOwl:consider`
+  pub consider: Vec<String>,
+
+  // reason of the obsolescence, from `IAO:0000231`. Usually an IRI such as `IAO:0000227` (terms merged)
+  pub reason: Option<String>,
+}
+
+impl DeprecatedTerm {
+  pub fn new(
+    iri: String,
+    label: Option<String>,
+    replaced_by: Vec<String>,
+    consider: Vec<String>,
+    reason: Option<String>,
+  ) -> DeprecatedTerm {
+    Self {
+      iri,
+      label,
+      replaced_by,
+      consider,
+      reason,
+    }
+  }
+}
+
+/// DeprecationReport lists the deprecated terms of a document, ordered by IRI
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DeprecationReport {
+  pub terms: Vec<DeprecatedTerm>,
+}
+
+impl DeprecationReport {
+  pub fn new(terms: Vec<DeprecatedTerm>) -> DeprecationReport {
+    Self { terms }
+  }
+
+  /// get returns the deprecated term with the given IRI
+  pub fn get(&self, iri: &str) -> Option<&DeprecatedTerm> {
+    self.terms.iter().find(|x| x.iri == iri)
+  }
+
+  /// replacements maps each deprecated term with a single replacement to that replacement
+  pub fn replacements(&self) -> BTreeMap<&str, &str> {
+    self
+      .terms
+      .iter()
+      .filter(|x| x.replaced_by.len() == 1)
+      .map(|x| (x.iri.as_str(), x.replaced_by[0].as_str()))
+      .collect()
+  }
+
+  pub fn len(&self) -> usize {
+    self.terms.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.terms.is_empty()
+  }
+}
+
+impl TurtleDocument {
+  /// deprecation_report finds the entities annotated with `owl:deprecated true`, or carrying an
+  /// obsolescence reason or a replacement in the OBO style
+  pub fn deprecation_report(&self) -> DeprecationReport {
+    let mut terms: BTreeMap<String, (bool, DeprecatedTerm)> = BTreeMap::new();
+    for t in self.triples() {
+      let (subject, predicate) = match (t.subject.as_iri(), t.predicate.as_iri()) {
+        (Some(s), Some(p)) => (s, p),
+        _ => continue,
+      };
+      let value = match &t.object {
+        TurtleTerm::Iri(x) => x.clone(),
+        TurtleTerm::Literal { value, .. } => value.clone(),
+        TurtleTerm::BlankNode(_) | TurtleTerm::QuotedTriple(_) => continue,
+      };
+      let entry = || DeprecatedTerm {
+        iri: String::from(subject),
+        ..DeprecatedTerm::default()
+      };
+      match predicate {
+        OWL_DEPRECATED if value == "true" || value == "1" => {
+          terms
+            .entry(String::from(subject))
+            .or_insert((false, entry()))
+            .0 = true;
+        }
+        IAO_OBSOLESCENCE_REASON => {
+          let (_, term) = terms
+            .entry(String::from(subject))
+            .or_insert((false, entry()));
+          term.reason = Some(value);
+        }
+        IAO_TERM_REPLACED_BY | OBO_IN_OWL_REPLACED_BY => {
+          let (_, term) = terms
+            .entry(String::from(subject))
+            .or_insert((false, entry()));
+          if !term.replaced_by.contains(&value) {
+            term.replaced_by.push(value);
+          }
+        }
+        OBO_IN_OWL_CONSIDER => {
+          let (_, term) = terms
+            .entry(String::from(subject))
+            .or_insert((false, entry()));
+          if !term.consider.contains(&value) {
+            term.consider.push(value);
+          }
+        }
+        _ => {}
+      }
+    }
+    // `consider` alone does not make a term obsolete
+    let values = PropertyValues::new(self);
+    let terms = terms
+      .into_iter()
+      .filter(|(_, (deprecated, term))| {
+        *deprecated || term.reason.is_some() || !term.replaced_by.is_empty()
+      })
+      .map(|(iri, (_, mut term))| {
+        term.label = values
+          .labels_of(&iri)
+          .best_match(&["en"])
+          .and_then(|x| match x {
+            TurtleTerm::Literal { value, .. } => Some(value.clone()),
+            _ => None,
+          });
+        term
+      })
+      .collect();
+    DeprecationReport::new(terms)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix oboInOwl: <http://www.geneontology.org/formats/oboInOwl#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+obo:GO_0000001 a owl:Class ; rdfs:label "obsolete mitochondrion inheritance"@en ;
+  owl:deprecated true ; obo:IAO_0100001 obo:GO_0048308 ; obo:IAO_0000231 obo:IAO_0000227 .
+obo:GO_0000002 a owl:Class ; owl:deprecated "true"^^<http://www.w3.org/2001/XMLSchema#boolean> ;
+  oboInOwl:consider "GO:0007005" , "GO:0006264" .
+obo:GO_0000003 a owl:Class ; oboInOwl:replacedBy "GO:0000004" .
+obo:GO_0000004 a owl:Class ; owl:deprecated false ; oboInOwl:consider "GO:0000005" .
+"#;
+
+  #[test]
+  fn should_report_deprecated_terms() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let report = document.deprecation_report();
+    assert_eq!(report.len(), 3);
+    let term = report
+      .get("http://purl.obolibrary.org/obo/GO_0000001")
+      .unwrap();
+    assert_eq!(
+      term.label.as_deref(),
+      Some("obsolete mitochondrion inheritance")
+    );
+    assert_eq!(
+      term.replaced_by,
+      vec!["http://purl.obolibrary.org/obo/GO_0048308"]
+    );
+    assert_eq!(
+      term.reason.as_deref(),
+      Some("http://purl.obolibrary.org/obo/IAO_0000227")
+    );
+    let term = report
+      .get("http://purl.obolibrary.org/obo/GO_0000002")
+      .unwrap();
+    assert_eq!(term.consider, vec!["GO:0007005", "GO:0006264"]);
+    assert!(term.replaced_by.is_empty());
+    assert!(report
+      .get("http://purl.obolibrary.org/obo/GO_0000004")
+      .is_none());
+    assert_eq!(
+      report
+        .replacements()
+        .get("http://purl.obolibrary.org/obo/GO_0000003"),
+      Some(&"GO:0000004")
+    );
+    Ok(())
+  }
+}
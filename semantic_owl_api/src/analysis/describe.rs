@@ -0,0 +1,77 @@
+//! Describe module returns the concise bounded description of a term
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use std::collections::{BTreeSet, VecDeque};
+
+impl TurtleDocument {
+  /// describe returns the statements describing a term given as a full IRI, grouped per subject
+  /// starting with the term. It returns no statements if the document says nothing about the term
+  /// example
+  /// `describe(agent, 1)` returns the statements about the agent and its restrictions, along
+  /// with the statements about its super classes
+  pub fn describe(&self, iri: &str, depth: usize) -> Vec<TurtleTriple> {
+    let graph = self.graph();
+    let mut statements = vec![];
+    let mut described: BTreeSet<TurtleTerm> = BTreeSet::new();
+    let mut pending = VecDeque::new();
+    pending.push_back((TurtleTerm::Iri(String::from(iri)), depth));
+    while let Some((node, remaining)) = pending.pop_front() {
+      if !described.insert(node.clone()) {
+        continue;
+      }
+      for t in graph.triples_matching(Some(&node), None, None) {
+        statements.push(t.clone());
+        match &t.object {
+          // blank nodes are always part of the description
+          TurtleTerm::BlankNode(_) => pending.push_back((t.object.clone(), remaining)),
+          TurtleTerm::Iri(_) if remaining > 0 => {
+            pending.push_back((t.object.clone(), remaining - 1))
+          }
+          _ => {}
+        }
+      }
+    }
+    statements
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en ; rdfs:subClassOf cco:Entity ,
+  [ a owl:Restriction ; owl:onProperty cco:has_capability ;
+    owl:someValuesFrom [ owl:unionOf ( cco:Skill cco:Tool ) ] ] .
+cco:Entity a owl:Class ; rdfs:subClassOf cco:Thing .
+cco:Thing a owl:Class .
+cco:Person rdfs:subClassOf cco:Agent .
+"#;
+
+  #[test]
+  fn should_describe_a_term() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let cco = |x: &str| {
+      format!(
+        "http://www.ontologyrepository.com/CommonCoreOntologies/{}",
+        x
+      )
+    };
+    let statements = document.describe(&cco("Agent"), 0);
+    // 4 statements about the agent, 3 about the restriction, 1 about the union and 4 about
+    // the collection
+    assert_eq!(statements.len(), 12);
+    assert_eq!(statements[0].subject.as_iri(), Some(cco("Agent").as_str()));
+    assert!(!statements
+      .iter()
+      .any(|t| t.subject.as_iri() == Some(cco("Entity").as_str())));
+
+    assert_eq!(document.describe(&cco("Agent"), 1).len(), 14);
+    assert_eq!(document.describe(&cco("Agent"), 2).len(), 15);
+    assert!(document.describe(&cco("Skill"), 3).is_empty());
+    Ok(())
+  }
+}
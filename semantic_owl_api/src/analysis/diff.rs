@@ -0,0 +1,422 @@
+//! Diff module compares two versions of a document and renders the changes as a changelog
+use crate::analysis::reasoner::PropertyValues;
+use crate::analysis::validation::EntityType;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::transforms::canonical::canonical_triples;
+use crate::writer::renderer::{CurieRenderer, FragmentRenderer, TermRenderer};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+// nesting depth past which blank nodes are not expanded. Guards against cycles
+const MAX_DEPTH: usize = 8;
+
+/// DocumentDiff lists the triples added and removed between two versions of a document.
+/// Blank nodes carry their canonical labels
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DocumentDiff {
+  pub added: Vec<TurtleTriple>,
+  pub removed: Vec<TurtleTriple>,
+}
+
+impl DocumentDiff {
+  pub fn new(added: Vec<TurtleTriple>, removed: Vec<TurtleTriple>) -> DocumentDiff {
+    Self { added, removed }
+  }
+
+  /// is_empty determines whether both versions hold the same triples
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty()
+  }
+
+  /// changelog groups the changes by the entity they are about, describing each statement with
+  /// the labels of the given languages, most preferred first. Added statements are described
+  /// with the labels of the newer version and removed ones with the labels of the older version
+  /// example
+  /// `changelog(&older, &newer, &["en", "*"])`
+  pub fn changelog(
+    &self,
+    older: &TurtleDocument,
+    newer: &TurtleDocument,
+    languages: &[&str],
+  ) -> Changelog {
+    let before = Version::new(older, languages);
+    let after = Version::new(newer, languages);
+
+    let mut changes: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+    for (version, triples, is_added) in
+      [(&after, &self.added, true), (&before, &self.removed, false)]
+    {
+      for t in triples.iter() {
+        // statements about nested blank nodes are described with the statement holding them
+        if t.subject.is_blank_node() && version.parents.contains_key(&t.subject) {
+          continue;
+        }
+        let iri = String::from(t.subject.as_iri().unwrap_or_default());
+        let statement = match t.subject.is_blank_node() {
+          true => format!(
+            "{} {} {}",
+            version.describe(&t.subject, 0),
+            version.describe(&t.predicate, 0),
+            version.describe(&t.object, 0)
+          ),
+          false => format!(
+            "{} {}",
+            version.describe(&t.predicate, 0),
+            version.describe(&t.object, 0)
+          ),
+        };
+        let entry = changes.entry(iri).or_default();
+        match is_added {
+          true => entry.0.push(statement),
+          false => entry.1.push(statement),
+        }
+      }
+    }
+
+    let mut entities: Vec<EntityChanges> = changes
+      .into_iter()
+      .map(|(iri, (mut added, mut removed))| {
+        added.sort();
+        removed.sort();
+        let status = match (before.is_described(&iri), after.is_described(&iri)) {
+          (false, true) => ChangeStatus::Added,
+          (true, false) => ChangeStatus::Removed,
+          _ => ChangeStatus::Modified,
+        };
+        let kind = after.kind_of(&iri).or_else(|| before.kind_of(&iri));
+        let name = match iri.is_empty() {
+          true => String::new(),
+          false => after
+            .label_of(&iri)
+            .or_else(|| before.label_of(&iri))
+            .unwrap_or_else(|| after.describe_iri(&iri)),
+        };
+        EntityChanges::new(iri, kind, name, status, added, removed)
+      })
+      .collect();
+    // named entities by kind and name, general axioms last
+    entities.sort_by(|a, b| {
+      (a.iri.is_empty(), a.kind.is_none(), a.kind, &a.name).cmp(&(
+        b.iri.is_empty(),
+        b.kind.is_none(),
+        b.kind,
+        &b.name,
+      ))
+    });
+    Changelog::new(entities)
+  }
+}
+
+/// ChangeStatus tells whether an entity was introduced, dropped or modified by a new version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeStatus {
+  Added,
+  Removed,
+  Modified,
+}
+
+/// EntityChanges are the changes of the statements about an entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityChanges {
+  // IRI of the entity. Empty for the axioms about no entity, e.g `owl:AllDisjointClasses`
+  pub iri: String,
+
+  // kind of the entity in the newer version, or in the older one if it was removed
+  pub kind: Option<EntityType>,
+
+  // label of the entity, or its prefixed name when it has none. e.g -> Armored Fighting Vehicle
+  pub name: String,
+
+  pub status: ChangeStatus,
+
+  // the statements added and removed, described with labels. e.g -> subClassOf 'Vehicle'
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+}
+
+impl EntityChanges {
+  pub fn new(
+    iri: String,
+    kind: Option<EntityType>,
+    name: String,
+    status: ChangeStatus,
+    added: Vec<String>,
+    removed: Vec<String>,
+  ) -> EntityChanges {
+    Self {
+      iri,
+      kind,
+      name,
+      status,
+      added,
+      removed,
+    }
+  }
+
+  /// title names the entity with its kind
+  /// example
+  /// `Class 'Armored Fighting Vehicle'`
+  pub fn title(&self) -> String {
+    if self.iri.is_empty() {
+      return String::from("General axioms");
+    }
+    let kind = self
+      .kind
+      .map(|x| x.to_string())
+      .unwrap_or_else(|| String::from("entity"));
+    let mut chars = kind.chars();
+    let kind: String = chars
+      .next()
+      .map(|x| x.to_uppercase().chain(chars).collect())
+      .unwrap_or_default();
+    format!("{} '{}'", kind, self.name)
+  }
+}
+
+/// Changelog lists the changes between two versions of a document, grouped by entity
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Changelog {
+  pub entities: Vec<EntityChanges>,
+}
+
+impl Changelog {
+  pub fn new(entities: Vec<EntityChanges>) -> Changelog {
+    Self { entities }
+  }
+
+  /// to_markdown writes the changelog as a Markdown list, one entry per entity
+  /// example
+  /// `- Class 'Armored Fighting Vehicle' (new)` followed by `  - added subClassOf 'Vehicle'`
+  pub fn to_markdown(&self) -> String {
+    let mut out = String::new();
+    for entity in self.entities.iter() {
+      let status = match entity.status {
+        ChangeStatus::Added => " (new)",
+        ChangeStatus::Removed => " (removed)",
+        ChangeStatus::Modified => "",
+      };
+      out.push_str(&format!("- {}{}\n", entity.title(), status));
+      for (action, statements) in [("added", &entity.added), ("removed", &entity.removed)] {
+        for s in statements.iter() {
+          out.push_str(&format!("  - {} {}\n", action, s));
+        }
+      }
+    }
+    out
+  }
+
+  /// to_json returns the changelog as JSON, with the title of each entity
+  pub fn to_json(&self) -> serde_json::Value {
+    let entities: Vec<serde_json::Value> = self
+      .entities
+      .iter()
+      .map(|x| {
+        let mut value = serde_json::to_value(x).unwrap_or_default();
+        value["title"] = serde_json::Value::String(x.title());
+        value
+      })
+      .collect();
+    serde_json::json!({ "entities": entities })
+  }
+}
+
+/// formats the changelog as Markdown
+impl fmt::Display for Changelog {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.to_markdown())
+  }
+}
+
+// Version is a version of a document along with what it takes to describe its statements
+struct Version<'a> {
+  values: PropertyValues,
+  languages: &'a [&'a str],
+  curie: CurieRenderer,
+  entities: BTreeMap<String, BTreeSet<EntityType>>,
+  subjects: BTreeSet<TurtleTerm>,
+  predicates: BTreeSet<String>,
+
+  // statements of the canonical triples by subject
+  statements: HashMap<TurtleTerm, Vec<TurtleTriple>>,
+
+  // the subject of the statement each blank node is nested in
+  parents: HashMap<TurtleTerm, TurtleTerm>,
+}
+
+impl<'a> Version<'a> {
+  fn new(document: &'a TurtleDocument, languages: &'a [&'a str]) -> Version<'a> {
+    let triples = canonical_triples(&document.triples());
+    let mut statements: HashMap<TurtleTerm, Vec<TurtleTriple>> = HashMap::new();
+    let mut parents: HashMap<TurtleTerm, TurtleTerm> = HashMap::new();
+    for t in triples.into_iter() {
+      if t.object.is_blank_node() {
+        parents
+          .entry(t.object.clone())
+          .or_insert_with(|| t.subject.clone());
+      }
+      statements.entry(t.subject.clone()).or_default().push(t);
+    }
+    Self {
+      values: PropertyValues::new(document),
+      languages,
+      curie: CurieRenderer::new(document.prefix_map()),
+      entities: document.entities(),
+      subjects: statements.keys().cloned().collect(),
+      predicates: statements
+        .values()
+        .flatten()
+        .filter_map(|t| t.predicate.as_iri().map(String::from))
+        .collect(),
+      statements,
+      parents,
+    }
+  }
+
+  // is_described determines whether the version states something about an entity
+  fn is_described(&self, iri: &str) -> bool {
+    self.subjects.contains(&TurtleTerm::Iri(String::from(iri)))
+  }
+
+  fn kind_of(&self, iri: &str) -> Option<EntityType> {
+    self
+      .entities
+      .get(iri)
+      .and_then(|x| x.iter().next().copied())
+  }
+
+  fn label_of(&self, iri: &str) -> Option<String> {
+    match self.values.labels_of(iri).best_match(self.languages) {
+      Some(TurtleTerm::Literal { value, .. }) => Some(value.clone()),
+      _ => None,
+    }
+  }
+
+  // describe_iri writes an entity as its quoted label, or its prefixed name when it has none
+  fn describe_iri(&self, iri: &str) -> String {
+    match self.label_of(iri) {
+      Some(x) => format!("'{}'", x),
+      None => self.curie.render_iri(iri),
+    }
+  }
+
+  // describe writes a term with labels. Predicates without a label are written as their
+  // fragment, e.g `subClassOf`, and nested blank nodes are expanded as `[ p o ; .. ]`
+  fn describe(&self, term: &TurtleTerm, depth: usize) -> String {
+    match term {
+      TurtleTerm::Iri(x) if self.label_of(x).is_none() && self.predicates.contains(x.as_str()) => {
+        FragmentRenderer.render_iri(x)
+      }
+      TurtleTerm::Iri(x) => self.describe_iri(x),
+      TurtleTerm::BlankNode(_) if depth < MAX_DEPTH => {
+        let statements: Vec<String> = self
+          .statements
+          .get(term)
+          .into_iter()
+          .flatten()
+          .map(|t| {
+            format!(
+              "{} {}",
+              self.describe(&t.predicate, depth + 1),
+              self.describe(&t.object, depth + 1)
+            )
+          })
+          .collect();
+        format!("[ {} ]", statements.join(" ; "))
+      }
+      t => self.curie.render(t),
+    }
+  }
+}
+
+impl TurtleDocument {
+  /// diff returns the triples a newer version of the document adds and removes
+  pub fn diff(&self, newer: &TurtleDocument) -> DocumentDiff {
+    let before: BTreeSet<TurtleTriple> = canonical_triples(&self.triples()).into_iter().collect();
+    let after: BTreeSet<TurtleTriple> = canonical_triples(&newer.triples()).into_iter().collect();
+    DocumentDiff::new(
+      after.difference(&before).cloned().collect(),
+      before.difference(&after).cloned().collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const OLDER: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Vehicle a owl:Class ; rdfs:label "Vehicle"@en .
+cco:Tank a owl:Class ; rdfs:label "Tank"@en ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Turret ] .
+cco:Turret a owl:Class .
+"#;
+
+  const NEWER: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Vehicle a owl:Class ; rdfs:label "Vehicle"@en .
+cco:Tank a owl:Class ; rdfs:label "Tank"@en ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Turret ] .
+cco:ArmoredFightingVehicle a owl:Class ; rdfs:label "Armored Fighting Vehicle"@en ;
+  rdfs:subClassOf cco:Vehicle .
+cco:has_part a owl:ObjectProperty ; rdfs:label "has part"@en .
+cco:Turret a owl:Class ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Gun ] .
+"#;
+
+  #[test]
+  fn should_render_changelog_with_labels() -> std::io::Result<()> {
+    let older = load_turtle_document_from_str(OLDER)?;
+    let newer = load_turtle_document_from_str(NEWER)?;
+    assert!(older.diff(&older).is_empty());
+
+    let diff = older.diff(&newer);
+    // the restriction on cco:Tank is the same anonymous expression in both versions
+    assert!(diff.removed.is_empty());
+    let changelog = diff.changelog(&older, &newer, &["en"]);
+    let titles: Vec<String> = changelog.entities.iter().map(|x| x.title()).collect();
+    assert_eq!(
+      titles,
+      vec![
+        "Class 'Armored Fighting Vehicle'",
+        "Class 'cco:Turret'",
+        "Object property 'has part'"
+      ]
+    );
+    let afv = &changelog.entities[0];
+    assert_eq!(afv.status, ChangeStatus::Added);
+    assert!(afv.added.contains(&String::from("subClassOf 'Vehicle'")));
+    assert_eq!(
+      changelog.entities[1].added,
+      vec!["subClassOf [ type owl:Restriction ; onProperty 'has part' ; someValuesFrom cco:Gun ]"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<String>>()
+    );
+
+    let markdown = changelog.to_markdown();
+    assert!(markdown.starts_with("- Class 'Armored Fighting Vehicle' (new)\n"));
+    assert!(markdown.contains("  - added subClassOf 'Vehicle'\n"));
+    let json = changelog.to_json();
+    assert_eq!(
+      json["entities"][2]["title"],
+      serde_json::json!("Object property 'has part'")
+    );
+
+    // removals are described with the labels of the older version
+    let reverted = newer.diff(&older).changelog(&newer, &older, &["en"]);
+    assert_eq!(reverted.entities[0].status, ChangeStatus::Removed);
+    assert!(reverted.entities[0]
+      .removed
+      .contains(&String::from("label \"Armored Fighting Vehicle\"@en")));
+    Ok(())
+  }
+}
@@ -0,0 +1,233 @@
+//! Entities module lists the entities of a document by kind
+use crate::analysis::validation::{EntityType, ENTITY_TYPES};
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::{owl, rdf, rdfs, xsd};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_CLASS: &str = rdfs::CLASS.as_str();
+const OWL_THING: &str = owl::THING.as_str();
+
+// namespaces of the reserved vocabularies
+const RESERVED_NAMESPACES: [&str; 4] = [
+  rdf::NAMESPACE,
+  rdfs::NAMESPACE,
+  owl::NAMESPACE,
+  xsd::NAMESPACE,
+];
+
+// predicates whose subject and object are entities of a kind
+const TYPED_BY_PREDICATE: [(&str, EntityType); 9] = [
+  (rdfs::SUB_CLASS_OF.as_str(), EntityType::Class),
+  (owl::EQUIVALENT_CLASS.as_str(), EntityType::Class),
+  (owl::DISJOINT_WITH.as_str(), EntityType::Class),
+  (owl::COMPLEMENT_OF.as_str(), EntityType::Class),
+  (owl::INVERSE_OF.as_str(), EntityType::ObjectProperty),
+  (
+    owl::PROPERTY_DISJOINT_WITH.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (
+    owl::PROPERTY_CHAIN_AXIOM.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (owl::SAME_AS.as_str(), EntityType::NamedIndividual),
+  (owl::DIFFERENT_FROM.as_str(), EntityType::NamedIndividual),
+];
+
+// predicates whose object is a list of entities of a kind
+const TYPED_LISTS: [(&str, EntityType); 6] = [
+  (owl::UNION_OF.as_str(), EntityType::Class),
+  (owl::INTERSECTION_OF.as_str(), EntityType::Class),
+  (owl::DISJOINT_UNION_OF.as_str(), EntityType::Class),
+  (owl::ONE_OF.as_str(), EntityType::NamedIndividual),
+  (owl::MEMBERS.as_str(), EntityType::NamedIndividual),
+  (owl::DISTINCT_MEMBERS.as_str(), EntityType::NamedIndividual),
+];
+
+/// is_reserved_iri determines whether an IRI belongs to the RDF, RDFS, OWL or XSD vocabularies
+pub fn is_reserved_iri(iri: &str) -> bool {
+  RESERVED_NAMESPACES.iter().any(|x| iri.starts_with(x))
+}
+
+impl TurtleDocument {
+  /// entities returns the kinds of each entity of the document, keyed by IRI.
+  /// A punned IRI, e.g a class also used as an individual, has several kinds
+  pub fn entities(&self) -> BTreeMap<String, BTreeSet<EntityType>> {
+    let graph = self.graph();
+    let mut entities: BTreeMap<String, BTreeSet<EntityType>> = BTreeMap::new();
+    let mut add = |term: &TurtleTerm, kind: EntityType| insert(&mut entities, term, kind);
+
+    // declarations, class assertions and the uses of the vocabulary
+    for t in graph.triples() {
+      let predicate = t.predicate.as_iri().unwrap_or_default();
+      if predicate == RDF_TYPE {
+        match t.object.as_iri() {
+          Some(RDFS_CLASS) => add(&t.subject, EntityType::Class),
+          Some(OWL_THING) => add(&t.subject, EntityType::NamedIndividual),
+          Some(o) if !is_reserved_iri(o) => {
+            add(&t.object, EntityType::Class);
+            add(&t.subject, EntityType::NamedIndividual);
+          }
+          Some(o) => {
+            if let Some((_, kind)) = ENTITY_TYPES.iter().find(|(x, _)| *x == o) {
+              add(&t.subject, *kind);
+            }
+          }
+          None => {}
+        }
+      } else if let Some((_, kind)) = TYPED_BY_PREDICATE.iter().find(|(x, _)| *x == predicate) {
+        add(&t.subject, *kind);
+        add(&t.object, *kind);
+      } else if let Some((_, kind)) = TYPED_LISTS.iter().find(|(x, _)| *x == predicate) {
+        for member in graph.list(&t.object).unwrap_or_default() {
+          add(member, *kind);
+        }
+      }
+    }
+
+    // assertions of the properties found above
+    let is_a = |term: &TurtleTerm, kind: EntityType| -> bool {
+      let kinds = term.as_iri().and_then(|x| entities.get(x));
+      kinds.map(|x| x.contains(&kind)).unwrap_or(false)
+    };
+    let mut asserted: Vec<&TurtleTerm> = vec![];
+    for t in graph.triples() {
+      if is_a(&t.predicate, EntityType::ObjectProperty) {
+        asserted.push(&t.subject);
+        asserted.push(&t.object);
+      } else if is_a(&t.predicate, EntityType::DataProperty) {
+        asserted.push(&t.subject);
+      }
+    }
+    for term in asserted {
+      insert(&mut entities, term, EntityType::NamedIndividual);
+    }
+    entities
+  }
+
+  /// entities_of returns the IRIs of the entities of a kind, sorted
+  pub fn entities_of(&self, kind: EntityType) -> impl Iterator<Item = String> {
+    self
+      .entities()
+      .into_iter()
+      .filter(move |(_, kinds)| kinds.contains(&kind))
+      .map(|(iri, _)| iri)
+  }
+
+  /// classes returns the IRIs of the classes declared or used in the document
+  pub fn classes(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::Class)
+  }
+
+  /// datatypes returns the IRIs of the datatypes declared in the document
+  pub fn datatypes(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::Datatype)
+  }
+
+  /// object_properties returns the IRIs of the object properties declared or used in the document
+  pub fn object_properties(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::ObjectProperty)
+  }
+
+  /// data_properties returns the IRIs of the data properties declared in the document
+  pub fn data_properties(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::DataProperty)
+  }
+
+  /// annotation_properties returns the IRIs of the annotation properties declared in the document
+  pub fn annotation_properties(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::AnnotationProperty)
+  }
+
+  /// individuals returns the IRIs of the named individuals declared or used in the document
+  pub fn individuals(&self) -> impl Iterator<Item = String> {
+    self.entities_of(EntityType::NamedIndividual)
+  }
+}
+
+// insert adds a kind to the entity named by a term. Blank nodes, literals and reserved IRIs are skipped
+fn insert(
+  entities: &mut BTreeMap<String, BTreeSet<EntityType>>,
+  term: &TurtleTerm,
+  kind: EntityType,
+) {
+  if let Some(iri) = term.as_iri() {
+    if !is_reserved_iri(iri) {
+      entities.entry(String::from(iri)).or_default().insert(kind);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::analysis::validation::EntityType;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  // iris returns the IRIs written with the empty prefix
+  fn iris(x: impl Iterator<Item = String>) -> Vec<String> {
+    x.map(|x| x.replace("http://example.org/", ":")).collect()
+  }
+
+  const DOCUMENT: &str = r#"
+@prefix : <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+<http://example.org/ontology> a owl:Ontology .
+:Agent a owl:Class ; rdfs:subClassOf :Entity ; rdfs:label "Agent" .
+:Person owl:equivalentClass [ owl:intersectionOf ( :Agent :Organism ) ] .
+:has_part a owl:ObjectProperty ; owl:inverseOf :part_of .
+:has_age a owl:DatatypeProperty ; rdfs:range xsd:integer .
+:definition a owl:AnnotationProperty .
+:Percent a rdfs:Datatype .
+:JohnDoe a :Person ; :has_part :Heart ; :has_age 42 .
+:Team a owl:Class , owl:NamedIndividual .
+"#;
+
+  #[test]
+  fn should_list_entities_by_kind() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    assert_eq!(
+      iris(document.classes()),
+      vec![":Agent", ":Entity", ":Organism", ":Person", ":Team"]
+    );
+    assert_eq!(
+      iris(document.object_properties()),
+      vec![":has_part", ":part_of"]
+    );
+    assert_eq!(iris(document.data_properties()), vec![":has_age"]);
+    assert_eq!(iris(document.annotation_properties()), vec![":definition"]);
+    assert_eq!(iris(document.datatypes()), vec![":Percent"]);
+    assert_eq!(
+      iris(document.individuals()),
+      vec![":Heart", ":JohnDoe", ":Team"]
+    );
+    // the ontology and the reserved vocabularies are not entities
+    let entities = document.entities();
+    assert!(!entities.contains_key("http://example.org/ontology"));
+    assert!(!entities.keys().any(|x| x.starts_with("http://www.w3.org/")));
+    assert_eq!(entities["http://example.org/Team"].len(), 2);
+    Ok(())
+  }
+
+  #[test]
+  fn should_list_entities_of_ontology() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/AgentOntology.ttl");
+    let document = load_turtle_document_from_str(&std::fs::read_to_string(path)?)?;
+    let stats = document.stats();
+    // declared entities are all listed, used ones may add to them
+    assert!(document.classes().count() >= stats.classes);
+    assert!(document.object_properties().count() >= stats.object_properties);
+    assert_eq!(
+      document.entities_of(EntityType::Class).count(),
+      document.classes().count()
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,261 @@
+//! Expressivity module estimates the description logic an ontology needs
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::{owl, rdf, rdfs, xsd};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS: &str = rdfs::NAMESPACE;
+const OWL: &str = owl::NAMESPACE;
+const XSD: &str = xsd::NAMESPACE;
+
+/// DlConstruct is a construct of OWL which extends the expressivity of the basic description
+/// logic `AL`, named after the letter it adds to the name of the logic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DlConstruct {
+  // C -> owl:complementOf
+  Complement,
+  // U -> owl:unionOf, owl:disjointUnionOf
+  Union,
+  // E -> owl:someValuesFrom
+  Existential,
+  // + -> owl:TransitiveProperty
+  Transitive,
+  // H -> rdfs:subPropertyOf, owl:equivalentProperty
+  RoleHierarchy,
+  // R -> property chains, disjoint properties, reflexive, irreflexive and asymmetric properties, owl:hasSelf
+  ComplexRoles,
+  // O -> owl:oneOf, owl:hasValue with an individual
+  Nominals,
+  // I -> owl:inverseOf, inverse functional and symmetric properties
+  Inverse,
+  // F -> owl:FunctionalProperty
+  Functional,
+  // N -> owl:cardinality, owl:minCardinality, owl:maxCardinality
+  Cardinality,
+  // Q -> owl:qualifiedCardinality, owl:minQualifiedCardinality, owl:maxQualifiedCardinality
+  QualifiedCardinality,
+  // (D) -> data properties, datatypes and data ranges
+  Datatypes,
+}
+
+impl DlConstruct {
+  /// letter returns the letter naming the construct in the name of a description logic
+  pub fn letter(&self) -> &'static str {
+    match self {
+      DlConstruct::Complement => "C",
+      DlConstruct::Union => "U",
+      DlConstruct::Existential => "E",
+      DlConstruct::Transitive => "+",
+      DlConstruct::RoleHierarchy => "H",
+      DlConstruct::ComplexRoles => "R",
+      DlConstruct::Nominals => "O",
+      DlConstruct::Inverse => "I",
+      DlConstruct::Functional => "F",
+      DlConstruct::Cardinality => "N",
+      DlConstruct::QualifiedCardinality => "Q",
+      DlConstruct::Datatypes => "(D)",
+    }
+  }
+}
+
+// constructs identified by the predicate of a triple
+const CONSTRUCT_PREDICATES: [(&str, DlConstruct); 17] = [
+  ("complementOf", DlConstruct::Complement),
+  ("unionOf", DlConstruct::Union),
+  ("disjointUnionOf", DlConstruct::Union),
+  ("someValuesFrom", DlConstruct::Existential),
+  ("equivalentProperty", DlConstruct::RoleHierarchy),
+  ("propertyChainAxiom", DlConstruct::ComplexRoles),
+  ("propertyDisjointWith", DlConstruct::ComplexRoles),
+  ("hasSelf", DlConstruct::ComplexRoles),
+  ("oneOf", DlConstruct::Nominals),
+  ("inverseOf", DlConstruct::Inverse),
+  ("cardinality", DlConstruct::Cardinality),
+  ("minCardinality", DlConstruct::Cardinality),
+  ("maxCardinality", DlConstruct::Cardinality),
+  ("qualifiedCardinality", DlConstruct::QualifiedCardinality),
+  ("minQualifiedCardinality", DlConstruct::QualifiedCardinality),
+  ("maxQualifiedCardinality", DlConstruct::QualifiedCardinality),
+  ("onDataRange", DlConstruct::Datatypes),
+];
+
+// constructs identified by the type of a subject
+const CONSTRUCT_TYPES: [(&str, DlConstruct); 11] = [
+  ("TransitiveProperty", DlConstruct::Transitive),
+  ("ReflexiveProperty", DlConstruct::ComplexRoles),
+  ("IrreflexiveProperty", DlConstruct::ComplexRoles),
+  ("AsymmetricProperty", DlConstruct::ComplexRoles),
+  ("AllDisjointProperties", DlConstruct::ComplexRoles),
+  ("InverseFunctionalProperty", DlConstruct::Inverse),
+  ("SymmetricProperty", DlConstruct::Inverse),
+  ("FunctionalProperty", DlConstruct::Functional),
+  ("DatatypeProperty", DlConstruct::Datatypes),
+  ("DataRange", DlConstruct::Datatypes),
+  ("Datatype", DlConstruct::Datatypes),
+];
+
+/// Expressivity is the estimated description logic of an ontology
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Expressivity {
+  // name of the description logic. e.g -> ALC, SHIN(D), SROIQ(D)
+  pub name: String,
+
+  // number of uses of each construct found
+  pub constructs: BTreeMap<DlConstruct, usize>,
+}
+
+impl Expressivity {
+  pub fn new(name: &str, constructs: BTreeMap<DlConstruct, usize>) -> Expressivity {
+    Self {
+      name: String::from(name),
+      constructs,
+    }
+  }
+
+  /// from_constructs names the description logic of a set of constructs. `ALC` with transitive
+  /// properties is named `S`, `R` subsumes `H`, and `Q` subsumes `N` which subsumes `F`
+  pub fn from_constructs(constructs: BTreeMap<DlConstruct, usize>) -> Expressivity {
+    let has = |x: DlConstruct| constructs.contains_key(&x);
+    let complement =
+      has(DlConstruct::Complement) || (has(DlConstruct::Union) && has(DlConstruct::Existential));
+    let mut name = match (complement, has(DlConstruct::Transitive)) {
+      (true, true) => String::from("S"),
+      (true, false) => String::from("ALC"),
+      (false, _) => {
+        let mut name = String::from("AL");
+        for x in [DlConstruct::Union, DlConstruct::Existential] {
+          if has(x) {
+            name.push_str(x.letter());
+          }
+        }
+        name
+      }
+    };
+    let groups: [&[DlConstruct]; 5] = [
+      &[DlConstruct::ComplexRoles, DlConstruct::RoleHierarchy],
+      &[DlConstruct::Nominals],
+      &[DlConstruct::Inverse],
+      &[
+        DlConstruct::QualifiedCardinality,
+        DlConstruct::Cardinality,
+        DlConstruct::Functional,
+      ],
+      &[DlConstruct::Datatypes],
+    ];
+    // the first construct found in each group names it
+    for group in groups.iter() {
+      if let Some(x) = group.iter().find(|x| has(**x)) {
+        name.push_str(x.letter());
+      }
+    }
+    if has(DlConstruct::Transitive) && !complement {
+      name.push_str(DlConstruct::Transitive.letter());
+    }
+    Self { name, constructs }
+  }
+}
+
+impl TurtleDocument {
+  /// expressivity returns the description logic the constructs used by the document require,
+  /// with the number of uses of each construct
+  pub fn expressivity(&self) -> Expressivity {
+    let mut constructs: BTreeMap<DlConstruct, usize> = BTreeMap::new();
+    for t in self.triples().iter() {
+      let predicate = t.predicate.as_iri().unwrap_or_default();
+      let found = match predicate.strip_prefix(OWL) {
+        Some("hasValue") if t.object.is_literal() => Some(DlConstruct::Datatypes),
+        Some("hasValue") => Some(DlConstruct::Nominals),
+        Some("someValuesFrom") | Some("allValuesFrom") if is_datatype(&t.object) => {
+          Some(DlConstruct::Datatypes)
+        }
+        Some(x) => CONSTRUCT_PREDICATES
+          .iter()
+          .find(|(p, _)| *p == x)
+          .map(|(_, c)| *c),
+        None if predicate == format!("{}subPropertyOf", RDFS) => Some(DlConstruct::RoleHierarchy),
+        None if predicate == format!("{}range", RDFS) && is_datatype(&t.object) => {
+          Some(DlConstruct::Datatypes)
+        }
+        None if predicate == RDF_TYPE => {
+          let kind = t.object.as_iri().unwrap_or_default();
+          let local = kind.strip_prefix(OWL).or_else(|| kind.strip_prefix(RDFS));
+          CONSTRUCT_TYPES
+            .iter()
+            .find(|(x, _)| Some(*x) == local)
+            .map(|(_, c)| *c)
+        }
+        None => None,
+      };
+      if let Some(x) = found {
+        *constructs.entry(x).or_default() += 1;
+      }
+    }
+    Expressivity::from_constructs(constructs)
+  }
+}
+
+// is_datatype determines whether a term is an XSD datatype or `rdfs:Literal`
+fn is_datatype(term: &TurtleTerm) -> bool {
+  match term.as_iri() {
+    Some(x) => x.starts_with(XSD) || x == format!("{}Literal", RDFS),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix : <http://example.org/> .
+
+:Person a owl:Class ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty :has_parent ; owl:someValuesFrom :Person ] ,
+    [ a owl:Restriction ; owl:onProperty :has_parent ; owl:maxCardinality 2 ] .
+:Orphan owl:equivalentClass [ owl:complementOf :Parent ] .
+:has_ancestor a owl:ObjectProperty , owl:TransitiveProperty .
+:has_parent rdfs:subPropertyOf :has_ancestor .
+:has_child owl:inverseOf :has_parent .
+:age a owl:DatatypeProperty ; rdfs:range xsd:integer .
+"#;
+
+  #[test]
+  fn should_estimate_expressivity() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let expressivity = document.expressivity();
+    assert_eq!(expressivity.name, "SHIN(D)");
+    assert_eq!(expressivity.constructs[&DlConstruct::Datatypes], 2);
+    assert_eq!(expressivity.constructs[&DlConstruct::Transitive], 1);
+    assert!(!expressivity
+      .constructs
+      .contains_key(&DlConstruct::QualifiedCardinality));
+
+    let name =
+      |x: &[DlConstruct]| Expressivity::from_constructs(x.iter().map(|x| (*x, 1)).collect()).name;
+    assert_eq!(name(&[]), "AL");
+    assert_eq!(name(&[DlConstruct::Union, DlConstruct::Existential]), "ALC");
+    assert_eq!(
+      name(&[DlConstruct::Existential, DlConstruct::Functional]),
+      "ALEF"
+    );
+    assert_eq!(name(&[DlConstruct::Transitive]), "AL+");
+    assert_eq!(
+      name(&[
+        DlConstruct::Complement,
+        DlConstruct::Transitive,
+        DlConstruct::ComplexRoles,
+        DlConstruct::RoleHierarchy,
+        DlConstruct::Nominals,
+        DlConstruct::Inverse,
+        DlConstruct::QualifiedCardinality,
+        DlConstruct::Datatypes
+      ]),
+      "SROIQ(D)"
+    );
+    Ok(())
+  }
+}
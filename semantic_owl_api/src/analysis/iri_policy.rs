@@ -0,0 +1,318 @@
+//! IRI policy module checks the IRIs of entities against the conventions of a repository
+use crate::analysis::entities::is_reserved_iri;
+use crate::analysis::validation::{EntityType, Violation};
+use crate::declarations::turtle::TurtleDocument;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const IRI_OUTSIDE_NAMESPACE: &str = "IriOutsideNamespace";
+pub const MALFORMED_LOCAL_ID: &str = "MalformedLocalId";
+pub const FORBIDDEN_CHARACTER: &str = "ForbiddenCharacter";
+
+/// OBO_NAMESPACE is the namespace of the OBO Foundry ontologies
+pub const OBO_NAMESPACE: &str = "http://purl.obolibrary.org/obo/";
+
+// character replacing the forbidden ones in suggested IRIs
+const REPLACEMENT: char = '_';
+
+/// LocalIdPattern is the form of numeric local IDs, a fixed prefix followed by digits
+/// example
+/// `LocalIdPattern::new("CCO_", 7)` matches `CCO_0000123`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalIdPattern {
+  // e.g -> CCO_
+  pub prefix: String,
+
+  // number of digits, zero padded. 0 means any number of digits
+  pub digits: usize,
+}
+
+impl LocalIdPattern {
+  pub fn new(prefix: &str, digits: usize) -> LocalIdPattern {
+    Self {
+      prefix: String::from(prefix),
+      digits,
+    }
+  }
+
+  /// id returns the number of a local ID matching the pattern
+  /// example
+  /// `CCO_0000123` returns Option of `123`
+  pub fn id(&self, local: &str) -> Option<u64> {
+    let digits = local.strip_prefix(self.prefix.as_str())?;
+    let valid = !digits.is_empty()
+      && digits.chars().all(|c| c.is_ascii_digit())
+      && (self.digits == 0 || digits.len() == self.digits);
+    match valid {
+      true => digits.parse().ok(),
+      false => None,
+    }
+  }
+
+  /// format returns the local ID of a number
+  pub fn format(&self, id: u64) -> String {
+    format!("{}{:0width$}", self.prefix, id, width = self.digits)
+  }
+}
+
+/// IriPolicy lists the conventions the IRIs of the entities of an ontology follow
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct IriPolicy {
+  // namespace every IRI starts with. e.g -> http://purl.obolibrary.org/obo/
+  pub namespace: Option<String>,
+
+  // form of the local names, i.e the IRIs without their namespace
+  pub local_id: Option<LocalIdPattern>,
+
+  // characters local names must not hold. e.g -> ' ', '%'
+  pub forbidden_characters: Vec<char>,
+
+  // kinds of the entities checked. Empty means every kind
+  pub entity_types: Vec<EntityType>,
+}
+
+impl IriPolicy {
+  pub fn new(
+    namespace: Option<String>,
+    local_id: Option<LocalIdPattern>,
+    forbidden_characters: Vec<char>,
+    entity_types: Vec<EntityType>,
+  ) -> IriPolicy {
+    Self {
+      namespace,
+      local_id,
+      forbidden_characters,
+      entity_types,
+    }
+  }
+
+  /// obo requires the OBO namespace and local IDs made of an ID space and seven digits, as the OBO
+  /// Foundry principles do
+  /// example
+  /// `IriPolicy::obo("CCO")` accepts `http://purl.obolibrary.org/obo/CCO_0000123`
+  pub fn obo(id_space: &str) -> IriPolicy {
+    Self::new(
+      Some(String::from(OBO_NAMESPACE)),
+      Some(LocalIdPattern::new(&format!("{}_", id_space), 7)),
+      vec![],
+      vec![],
+    )
+  }
+
+  // split returns the namespace and the local name of an IRI. The local name follows the namespace
+  // of the policy when the IRI is in it, or the last `#` or `/` otherwise
+  fn split<'a>(&self, iri: &'a str) -> (&'a str, &'a str) {
+    if let Some(ns) = self.namespace.as_deref().filter(|x| iri.starts_with(*x)) {
+      return iri.split_at(ns.len());
+    }
+    match iri.rfind(['#', '/']) {
+      Some(idx) => iri.split_at(idx + 1),
+      None => ("", iri),
+    }
+  }
+}
+
+/// NonConformantIri is the IRI of an entity breaching a policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NonConformantIri {
+  pub iri: String,
+
+  // rules broken. e.g -> [IriOutsideNamespace, MalformedLocalId]
+  pub rules: Vec<String>,
+
+  // IRI conforming to the policy
+  pub suggestion: String,
+}
+
+impl NonConformantIri {
+  pub fn new(iri: String, rules: Vec<String>, suggestion: String) -> NonConformantIri {
+    Self {
+      iri,
+      rules,
+      suggestion,
+    }
+  }
+}
+
+/// IriAudit lists the entities whose IRI breaches a policy, ordered by IRI
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct IriAudit {
+  // number of entities checked
+  pub audited: usize,
+
+  pub entities: Vec<NonConformantIri>,
+}
+
+impl IriAudit {
+  pub fn new(audited: usize, entities: Vec<NonConformantIri>) -> IriAudit {
+    Self { audited, entities }
+  }
+
+  /// get returns the audit of the entity with the given IRI, if it breaches the policy
+  pub fn get(&self, iri: &str) -> Option<&NonConformantIri> {
+    self.entities.iter().find(|x| x.iri == iri)
+  }
+
+  /// is_clean determines whether every entity checked complies with the policy
+  pub fn is_clean(&self) -> bool {
+    self.entities.is_empty()
+  }
+
+  /// violations returns one violation per entity and rule broken, suggesting the conformant IRI
+  pub fn violations(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    for entity in self.entities.iter() {
+      for rule in entity.rules.iter() {
+        let breach = match rule.as_str() {
+          IRI_OUTSIDE_NAMESPACE => "is outside the namespace of the ontology",
+          MALFORMED_LOCAL_ID => "does not have a well-formed local ID",
+          _ => "holds a forbidden character",
+        };
+        let message = format!(
+          "<{}> {}, use <{}> instead",
+          entity.iri, breach, entity.suggestion
+        );
+        violations.push(Violation::new(rule, &entity.iri, message, vec![]));
+      }
+    }
+    violations
+  }
+}
+
+impl TurtleDocument {
+  /// iri_audit checks the IRIs of the entities of the document against a policy. Only the entities
+  /// this document states something about are checked, leaving out the terms it merely refers to,
+  /// e.g the imported super classes
+  pub fn iri_audit(&self, policy: &IriPolicy) -> IriAudit {
+    let triples = self.triples();
+    let subjects: BTreeSet<&str> = triples.iter().filter_map(|t| t.subject.as_iri()).collect();
+    let entities = self.entities();
+    let audited: Vec<&String> = entities
+      .iter()
+      .filter(|(iri, kinds)| {
+        subjects.contains(iri.as_str())
+          && !is_reserved_iri(iri)
+          && (policy.entity_types.is_empty()
+            || policy.entity_types.iter().any(|x| kinds.contains(x)))
+      })
+      .map(|(iri, _)| iri)
+      .collect();
+
+    // IDs are allocated after the highest one in use, whatever the kind of the entity
+    let mut next_id = entities
+      .keys()
+      .filter_map(|iri| {
+        let (ns, local) = policy.split(iri);
+        let in_namespace = policy.namespace.as_deref().is_none_or(|x| x == ns);
+        policy.local_id.as_ref()?.id(local).filter(|_| in_namespace)
+      })
+      .max()
+      .map_or(1, |x| x + 1);
+
+    let mut non_conformant = vec![];
+    for iri in audited.iter() {
+      let (ns, local) = policy.split(iri);
+      let mut rules = vec![];
+      let mut namespace = ns;
+      let mut suggested = String::from(local);
+      if let Some(x) = policy.namespace.as_deref().filter(|x| *x != ns) {
+        rules.push(String::from(IRI_OUTSIDE_NAMESPACE));
+        namespace = x;
+      }
+      if local.contains(policy.forbidden_characters.as_slice()) {
+        rules.push(String::from(FORBIDDEN_CHARACTER));
+        suggested = local.replace(
+          policy.forbidden_characters.as_slice(),
+          &REPLACEMENT.to_string(),
+        );
+      }
+      if let Some(pattern) = policy.local_id.as_ref().filter(|x| x.id(local).is_none()) {
+        rules.push(String::from(MALFORMED_LOCAL_ID));
+        suggested = pattern.format(next_id);
+        next_id += 1;
+      }
+      if rules.is_empty() {
+        continue;
+      }
+      // a suggestion must not name another entity
+      let mut suggestion = format!("{}{}", namespace, suggested);
+      if let Some(pattern) = policy.local_id.as_ref() {
+        while entities.contains_key(&suggestion) {
+          suggestion = format!("{}{}", namespace, pattern.format(next_id));
+          next_id += 1;
+        }
+      }
+      non_conformant.push(NonConformantIri::new(String::clone(iri), rules, suggestion));
+    }
+    IriAudit::new(audited.len(), non_conformant)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix ex: <http://example.org/> .
+
+obo:CCO_0000001 a owl:Class ; rdfs:subClassOf obo:BFO_0000040 .
+obo:CCO_0000041 a owl:Class ; rdfs:subClassOf obo:CCO_0000001 .
+obo:Person a owl:Class ; rdfs:subClassOf obo:CCO_0000001 .
+ex:CCO_0000002 a owl:ObjectProperty .
+ex:CCO_0000041 a owl:ObjectProperty .
+<http://purl.obolibrary.org/obo/CCO_00000%2042> a owl:NamedIndividual .
+"#;
+
+  #[test]
+  fn should_audit_iris() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let audit = document.iri_audit(&IriPolicy::obo("CCO"));
+    // the imported BFO class is not checked
+    assert_eq!(audit.audited, 6);
+    let suggestions: Vec<(&str, &str)> = audit
+      .entities
+      .iter()
+      .map(|x| (x.iri.as_str(), x.suggestion.as_str()))
+      .collect();
+    assert_eq!(
+      suggestions,
+      vec![
+        (
+          "http://example.org/CCO_0000002",
+          "http://purl.obolibrary.org/obo/CCO_0000002"
+        ),
+        // the ID of the property is taken by a class
+        (
+          "http://example.org/CCO_0000041",
+          "http://purl.obolibrary.org/obo/CCO_0000042"
+        ),
+        (
+          "http://purl.obolibrary.org/obo/CCO_00000%2042",
+          "http://purl.obolibrary.org/obo/CCO_0000043"
+        ),
+        (
+          "http://purl.obolibrary.org/obo/Person",
+          "http://purl.obolibrary.org/obo/CCO_0000044"
+        ),
+      ]
+    );
+    assert_eq!(
+      audit.violations()[0].to_string(),
+      "IriOutsideNamespace: <http://example.org/CCO_0000002> is outside the namespace of the ontology, use <http://purl.obolibrary.org/obo/CCO_0000002> instead"
+    );
+
+    let policy = IriPolicy::new(None, None, vec!['%'], vec![EntityType::NamedIndividual]);
+    let audit = document.iri_audit(&policy);
+    assert_eq!(audit.audited, 1);
+    assert_eq!(audit.entities[0].rules, vec![FORBIDDEN_CHARACTER]);
+    assert_eq!(
+      audit.entities[0].suggestion,
+      "http://purl.obolibrary.org/obo/CCO_00000_2042"
+    );
+    Ok(())
+  }
+}
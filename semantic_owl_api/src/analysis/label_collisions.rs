@@ -0,0 +1,256 @@
+//! Label collisions module finds distinct entities sharing a label
+use crate::analysis::reasoner::sub_properties;
+use crate::analysis::validation::Violation;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+pub const LABEL_COLLISION: &str = "LabelCollision";
+pub const LABEL_VARIANT: &str = "LabelVariant";
+
+/// CollisionOptions tunes which labels collide
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionOptions {
+  // full IRI of the label property. e.g -> http://www.w3.org/2000/01/rdf-schema#label
+  pub property: String,
+
+  // labels only collide within a language, so `"Agent"@en` and `"Agent"@fr` do not collide
+  pub per_language: bool,
+
+  // labels differing only by case or whitespace collide
+  pub variants: bool,
+}
+
+impl CollisionOptions {
+  pub fn new(property: &str, per_language: bool, variants: bool) -> CollisionOptions {
+    Self {
+      property: String::from(property),
+      per_language,
+      variants,
+    }
+  }
+}
+
+/// `rdfs:label` values collide within a language, along with their case and whitespace variants
+impl Default for CollisionOptions {
+  fn default() -> Self {
+    Self::new(RDFS_LABEL, true, true)
+  }
+}
+
+/// LabelCluster is a set of distinct entities sharing a label
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct LabelCluster {
+  // IRIs of the entities, ordered
+  pub iris: Vec<String>,
+
+  // distinct labels of the cluster, in the order they were stated. e.g -> ["Agent", "agent"]
+  pub labels: Vec<String>,
+
+  // language shared by the labels. `None` for untagged labels, or when languages are ignored
+  pub language: Option<String>,
+
+  // statements giving the labels
+  pub statements: Vec<TurtleTriple>,
+}
+
+impl LabelCluster {
+  pub fn new(
+    iris: Vec<String>,
+    labels: Vec<String>,
+    language: Option<String>,
+    statements: Vec<TurtleTriple>,
+  ) -> LabelCluster {
+    Self {
+      iris,
+      labels,
+      language,
+      statements,
+    }
+  }
+
+  /// is_exact determines whether the entities share the very same label, rather than variants of it
+  pub fn is_exact(&self) -> bool {
+    self.labels.len() == 1
+  }
+}
+
+/// LabelCollisions lists the clusters of entities sharing a label, ordered by label
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct LabelCollisions {
+  pub clusters: Vec<LabelCluster>,
+}
+
+impl LabelCollisions {
+  pub fn new(clusters: Vec<LabelCluster>) -> LabelCollisions {
+    Self { clusters }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.clusters.is_empty()
+  }
+
+  /// violations returns one `LabelCollision` or `LabelVariant` violation per cluster, reported
+  /// on its first entity
+  pub fn violations(&self) -> Vec<Violation> {
+    let mut violations = vec![];
+    for cluster in self.clusters.iter() {
+      let iris: Vec<String> = cluster.iris.iter().map(|x| format!("<{}>", x)).collect();
+      let (rule, message) = match cluster.is_exact() {
+        true => (
+          LABEL_COLLISION,
+          format!(
+            "{} share the label {:?}",
+            iris.join(", "),
+            cluster.labels[0]
+          ),
+        ),
+        false => (
+          LABEL_VARIANT,
+          format!(
+            "{} have labels differing only by case or whitespace: {:?}",
+            iris.join(", "),
+            cluster.labels
+          ),
+        ),
+      };
+      violations.push(Violation::new(
+        rule,
+        &cluster.iris[0],
+        message,
+        cluster.statements.clone(),
+      ));
+    }
+    violations
+  }
+}
+
+// normalize returns the form of a label its case and whitespace variants share. e.g -> agent role
+fn normalize(label: &str) -> String {
+  let words: Vec<&str> = label.split_whitespace().collect();
+  words.join(" ").to_lowercase()
+}
+
+impl TurtleDocument {
+  /// label_collisions returns the clusters of distinct entities sharing a label
+  pub fn label_collisions(&self, options: &CollisionOptions) -> LabelCollisions {
+    let triples = self.triples();
+    let properties = sub_properties(&triples, &options.property);
+
+    // label statements per compared label and language
+    let mut groups: BTreeMap<(String, Option<String>), Vec<&TurtleTriple>> = BTreeMap::new();
+    for t in triples.iter() {
+      match (t.subject.as_iri(), t.predicate.as_iri()) {
+        (Some(_), Some(p)) if properties.contains(p) => {}
+        _ => continue,
+      }
+      let (value, language) = match &t.object {
+        TurtleTerm::Literal {
+          value, language, ..
+        } => (value, language),
+        _ => continue,
+      };
+      let label = match options.variants {
+        true => normalize(value),
+        false => value.clone(),
+      };
+      let language = match options.per_language {
+        true => language.as_ref().map(|x| x.to_ascii_lowercase()),
+        false => None,
+      };
+      groups.entry((label, language)).or_default().push(t);
+    }
+
+    let mut clusters = vec![];
+    for ((_, language), statements) in groups.into_iter() {
+      let iris: BTreeSet<&str> = statements
+        .iter()
+        .filter_map(|t| t.subject.as_iri())
+        .collect();
+      if iris.len() < 2 {
+        continue;
+      }
+      let mut labels: Vec<String> = vec![];
+      for t in statements.iter() {
+        if let TurtleTerm::Literal { value, .. } = &t.object {
+          if !labels.contains(value) {
+            labels.push(value.clone());
+          }
+        }
+      }
+      clusters.push(LabelCluster::new(
+        iris.into_iter().map(String::from).collect(),
+        labels,
+        language,
+        statements.into_iter().cloned().collect(),
+      ));
+    }
+    LabelCollisions::new(clusters)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+
+skos:prefLabel rdfs:subPropertyOf rdfs:label .
+cco:Agent rdfs:label "Agent"@en , "Agent"@fr .
+obo:Agent skos:prefLabel "Agent"@en .
+cco:Role rdfs:label "Agent role"@en .
+obo:Role rdfs:label "agent  Role "@en .
+cco:Person rdfs:label "Person"@en ; rdfs:label "person"@en .
+cco:Personne rdfs:label "Person"@fr .
+"#;
+
+  #[test]
+  fn should_find_label_collisions() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let cco = "http://www.ontologyrepository.com/CommonCoreOntologies/";
+    let collisions = document.label_collisions(&CollisionOptions::default());
+    // an entity does not collide with itself
+    assert_eq!(collisions.clusters.len(), 2);
+    let agent = &collisions.clusters[0];
+    assert_eq!(
+      agent.iris,
+      vec![
+        String::from("http://purl.obolibrary.org/obo/Agent"),
+        format!("{}Agent", cco)
+      ]
+    );
+    assert_eq!(agent.language.as_deref(), Some("en"));
+    assert!(agent.is_exact());
+    assert_eq!(agent.statements.len(), 2);
+    assert_eq!(
+      collisions.clusters[1].labels,
+      vec!["Agent role", "agent  Role "]
+    );
+
+    let rules: Vec<String> = collisions
+      .violations()
+      .into_iter()
+      .map(|v| v.rule)
+      .collect();
+    assert_eq!(rules, vec![LABEL_COLLISION, LABEL_VARIANT]);
+
+    let options = CollisionOptions::new(RDFS_LABEL, false, false);
+    let collisions = document.label_collisions(&options);
+    let labels: Vec<&str> = collisions
+      .clusters
+      .iter()
+      .map(|x| x.labels[0].as_str())
+      .collect();
+    assert_eq!(labels, vec!["Agent", "Person"]);
+    assert_eq!(collisions.clusters[0].statements.len(), 3);
+    Ok(())
+  }
+}
@@ -0,0 +1,179 @@
+//! Labels module gives access to the literal annotations of an entity
+use crate::analysis::reasoner::PropertyValues;
+use crate::declarations::language_tag::{matches_language_range, truncate_language_range};
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+
+const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+/// Labels are the literal values of an annotation of an entity, in the order they were stated
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Labels {
+  pub literals: Vec<TurtleTerm>,
+}
+
+impl Labels {
+  pub fn new(literals: Vec<TurtleTerm>) -> Labels {
+    Self { literals }
+  }
+
+  /// lang returns the labels whose language tag matches a basic language range
+  /// example
+  /// `lang("en")` keeps `"Agent"@en` and `"Agent"@en-GB`, `lang("*")` keeps all tagged labels
+  pub fn lang(&self, range: &str) -> Labels {
+    let literals = self
+      .literals
+      .iter()
+      .filter(|x| match language_of(x) {
+        Some(tag) => matches_language_range(tag, range),
+        None => false,
+      })
+      .cloned()
+      .collect();
+    Labels::new(literals)
+  }
+
+  /// untagged returns the labels without a language tag
+  pub fn untagged(&self) -> Labels {
+    let literals = self
+      .literals
+      .iter()
+      .filter(|x| language_of(x).is_none())
+      .cloned()
+      .collect();
+    Labels::new(literals)
+  }
+
+  /// values returns the lexical values of the labels
+  pub fn values(&self) -> Vec<&str> {
+    self
+      .literals
+      .iter()
+      .filter_map(|x| match x {
+        TurtleTerm::Literal { value, .. } => Some(value.as_str()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// best_match returns the label which best fits a list of language preferences, most preferred first.
+  /// Each preference is looked up as in RFC 4647 section 3.4: an exact match first, then
+  /// the preference with its last subtag removed, and so on. When no preference matches, the first
+  /// untagged label is returned
+  /// example
+  /// `best_match(&["en-GB", "fr"])` prefers `"Colour"@en-GB`, then `"Color"@en`, then `"Couleur"@fr`
+  pub fn best_match(&self, preferences: &[&str]) -> Option<&TurtleTerm> {
+    for preference in preferences.iter() {
+      if *preference == "*" {
+        if let Some(x) = self.literals.iter().find(|x| language_of(x).is_some()) {
+          return Some(x);
+        }
+        continue;
+      }
+      let mut range = Some(*preference);
+      while let Some(r) = range {
+        let found = self.literals.iter().find(|x| match language_of(x) {
+          Some(tag) => tag.eq_ignore_ascii_case(r),
+          None => false,
+        });
+        if found.is_some() {
+          return found;
+        }
+        range = truncate_language_range(r);
+      }
+    }
+    self.literals.iter().find(|x| language_of(x).is_none())
+  }
+
+  pub fn len(&self) -> usize {
+    self.literals.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.literals.is_empty()
+  }
+}
+
+fn language_of(term: &TurtleTerm) -> Option<&str> {
+  match term {
+    TurtleTerm::Literal { language, .. } => language.as_deref(),
+    _ => None,
+  }
+}
+
+impl PropertyValues {
+  /// annotations_of returns the literal values of an annotation property of an entity, both given as full IRIs.
+  /// Values asserted with a sub-property of `property` are included
+  pub fn annotations_of(&self, iri: &str, property: &str) -> Labels {
+    let literals = self
+      .values_of(iri, property)
+      .into_iter()
+      .filter(|x| x.is_literal())
+      .collect();
+    Labels::new(literals)
+  }
+
+  /// labels_of returns the `rdfs:label` values of an entity given as a full IRI
+  pub fn labels_of(&self, iri: &str) -> Labels {
+    self.annotations_of(iri, RDFS_LABEL)
+  }
+}
+
+impl TurtleDocument {
+  /// annotations_of returns the literal values of an annotation property of an entity, both given as full IRIs.
+  /// Values asserted with a sub-property of `property` are included
+  /// example
+  /// `annotations_of("http://example.org/Agent", "http://www.w3.org/2004/02/skos/core#definition")`
+  pub fn annotations_of(&self, iri: &str, property: &str) -> Labels {
+    PropertyValues::new(self).annotations_of(iri, property)
+  }
+
+  /// labels_of returns the `rdfs:label` values of an entity given as a full IRI
+  /// example
+  /// `labels_of("http://example.org/Agent").lang("en")`
+  pub fn labels_of(&self, iri: &str) -> Labels {
+    self.annotations_of(iri, RDFS_LABEL)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Colour rdfs:label "Color"@en , "Colour"@en-GB , "Couleur"@fr , "colour" ;
+          rdfs:comment "not a label"@en .
+"#;
+
+  #[test]
+  fn should_filter_labels_by_language() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let labels = document.labels_of("http://example.org/Colour");
+    assert_eq!(labels.len(), 4);
+    assert_eq!(labels.lang("en").values(), vec!["Color", "Colour"]);
+    assert_eq!(labels.lang("en-gb").values(), vec!["Colour"]);
+    assert_eq!(labels.lang("*").len(), 3);
+    assert_eq!(labels.untagged().values(), vec!["colour"]);
+    assert!(document.labels_of("http://example.org/Unknown").is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn should_pick_best_matching_label() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let labels = document.labels_of("http://example.org/Colour");
+    let value = |preferences: &[&str]| -> String {
+      let best = labels.best_match(preferences);
+      best.map(|x| x.to_string()).unwrap_or_default()
+    };
+    assert_eq!(value(&["en-GB", "fr"]), "\"Colour\"@en-GB");
+    assert_eq!(value(&["en-US", "fr"]), "\"Color\"@en");
+    assert_eq!(value(&["de", "fr"]), "\"Couleur\"@fr");
+    assert_eq!(value(&["de"]), "\"colour\"");
+    Ok(())
+  }
+}
@@ -0,0 +1,18 @@
+pub mod annotation_audit;
+pub mod cycles;
+pub mod deprecation;
+pub mod describe;
+pub mod diff;
+pub mod entities;
+pub mod expressivity;
+pub mod iri_policy;
+pub mod label_collisions;
+pub mod labels;
+pub mod namespaces;
+pub mod outline;
+pub mod reasoner;
+pub mod shacl;
+pub mod signature;
+pub mod stats;
+pub mod validation;
+pub mod validator;
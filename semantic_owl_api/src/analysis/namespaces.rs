@@ -0,0 +1,128 @@
+//! Namespaces module counts the IRIs of a document by namespace
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// NamespaceUsage counts the occurrences of the IRIs of a namespace in the triples of a document
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct NamespaceUsage {
+  // number of occurrences as subject, predicate and object of a triple
+  pub subjects: usize,
+  pub predicates: usize,
+  pub objects: usize,
+
+  // number of occurrences of each IRI of the namespace.
+  // e.g -> http://www.w3.org/2002/07/owl#Class -> 12
+  pub iris: BTreeMap<String, usize>,
+}
+
+impl NamespaceUsage {
+  pub fn new(
+    subjects: usize,
+    predicates: usize,
+    objects: usize,
+    iris: BTreeMap<String, usize>,
+  ) -> NamespaceUsage {
+    Self {
+      subjects,
+      predicates,
+      objects,
+      iris,
+    }
+  }
+
+  /// total returns the number of occurrences of the namespace in any position
+  pub fn total(&self) -> usize {
+    self.subjects + self.predicates + self.objects
+  }
+}
+
+/// namespace_of returns the namespace of an IRI, i.e the IRI up to and including its last `#` or
+/// `/`, or its last `:` for IRIs with neither.
+/// example
+/// `namespace_of("http://www.w3.org/2002/07/owl#Class")` is `http://www.w3.org/2002/07/owl#`
+pub fn namespace_of(iri: &str) -> &str {
+  match iri.rfind(['#', '/']).or_else(|| iri.rfind(':')) {
+    Some(idx) => &iri[..=idx],
+    None => iri,
+  }
+}
+
+impl TurtleDocument {
+  /// namespace_usage returns, for each namespace IRI, the number of occurrences of its IRIs as
+  /// subject, predicate and object of the triples of the document. Blank nodes and literals are
+  /// not counted
+  pub fn namespace_usage(&self) -> BTreeMap<String, NamespaceUsage> {
+    let mut usage: BTreeMap<String, NamespaceUsage> = BTreeMap::new();
+    for t in self.triples().iter() {
+      if let Some(x) = count(&mut usage, &t.subject) {
+        x.subjects += 1;
+      }
+      if let Some(x) = count(&mut usage, &t.predicate) {
+        x.predicates += 1;
+      }
+      if let Some(x) = count(&mut usage, &t.object) {
+        x.objects += 1;
+      }
+    }
+    usage
+  }
+}
+
+// count counts an occurrence of the IRI of a term and returns the usage of its namespace. Terms
+// which are not IRIs are not counted
+fn count<'a>(
+  usage: &'a mut BTreeMap<String, NamespaceUsage>,
+  term: &TurtleTerm,
+) -> Option<&'a mut NamespaceUsage> {
+  let iri = term.as_iri()?;
+  let entry = usage.entry(String::from(namespace_of(iri))).or_default();
+  *entry.iris.entry(String::from(iri)).or_default() += 1;
+  Some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+
+cco:Agent a owl:Class .
+cco:Person a owl:Class ;
+  rdfs:subClassOf cco:Agent ;
+  <http://purl.org/dc/terms/creator> "jdoe" ;
+  rdfs:seeAlso <urn:isbn:0451450523> .
+"#;
+
+  #[test]
+  fn should_count_namespace_usage() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let usage = document.namespace_usage();
+    assert_eq!(
+      usage.keys().collect::<Vec<_>>(),
+      vec![
+        "http://purl.org/dc/terms/",
+        "http://www.ontologyrepository.com/CommonCoreOntologies/",
+        "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+        "http://www.w3.org/2000/01/rdf-schema#",
+        "http://www.w3.org/2002/07/owl#",
+        "urn:isbn:"
+      ]
+    );
+    let cco = &usage["http://www.ontologyrepository.com/CommonCoreOntologies/"];
+    assert_eq!((cco.subjects, cco.predicates, cco.objects), (5, 0, 1));
+    assert_eq!(
+      cco.iris["http://www.ontologyrepository.com/CommonCoreOntologies/Person"],
+      4
+    );
+    let owl = &usage["http://www.w3.org/2002/07/owl#"];
+    assert_eq!(owl.total(), 2);
+    assert_eq!(owl.objects, 2);
+    assert_eq!(usage["http://purl.org/dc/terms/"].predicates, 1);
+    assert_eq!(namespace_of("urn:isbn:0451450523"), "urn:isbn:");
+    Ok(())
+  }
+}
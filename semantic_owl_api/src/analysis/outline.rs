@@ -0,0 +1,122 @@
+//! Outline module groups the statements of a document by subject
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::load::{load_block, statement_spans};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+/// SubjectNode is a subject of a document along with the statements describing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubjectNode {
+  pub subject: TurtleTerm,
+
+  // byte ranges of the statements whose subject is the node, in document order
+  pub spans: Vec<Range<usize>>,
+}
+
+impl SubjectNode {
+  pub fn new(subject: TurtleTerm, spans: Vec<Range<usize>>) -> SubjectNode {
+    Self { subject, spans }
+  }
+}
+
+/// outline returns the subjects of the statements of turtle content, in the order they first
+/// appear, each with the spans of all statements describing it. Statements about a subject
+/// written with an undeclared prefix are left out
+pub fn outline(content: &str) -> std::io::Result<Vec<SubjectNode>> {
+  let mut map = PrefixMap::new();
+  let mut blank_node_count = 0;
+  let mut nodes: Vec<SubjectNode> = vec![];
+  let mut positions: HashMap<TurtleTerm, usize> = HashMap::new();
+  for span in statement_spans(content)? {
+    let statement = load_block(&content[span.clone()], &mut blank_node_count)?;
+    for h in statement.headers.iter() {
+      h.declare(&mut map);
+    }
+    // the first body item of a statement is its subject, the others are nested nodes
+    let subject = statement
+      .body
+      .front()
+      .and_then(|x| x.subject.as_deref())
+      .and_then(|x| map.resolve_term(x));
+    let subject = match subject {
+      Some(x) => x,
+      None => continue,
+    };
+    match positions.get(&subject) {
+      Some(idx) => nodes[*idx].spans.push(span),
+      None => {
+        positions.insert(subject.clone(), nodes.len());
+        nodes.push(SubjectNode::new(subject, vec![span]));
+      }
+    }
+  }
+  Ok(nodes)
+}
+
+impl TurtleDocument {
+  /// subjects returns the distinct subjects of the statements of the document, in the order they
+  /// first appear. Blank nodes used as objects, e.g the nested blank nodes and collections of a
+  /// statement, are not listed. `outline` also gives the spans of the statements of each subject
+  pub fn subjects(&self) -> Vec<TurtleTerm> {
+    let triples = self.triples();
+    let nested: BTreeSet<&TurtleTerm> = triples
+      .iter()
+      .map(|t| &t.object)
+      .filter(|x| x.is_blank_node())
+      .collect();
+    let mut seen = BTreeSet::new();
+    let mut subjects = vec![];
+    for t in triples.iter() {
+      if !nested.contains(&t.subject) && seen.insert(&t.subject) {
+        subjects.push(t.subject.clone());
+      }
+    }
+    subjects
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix : <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+:Agent a owl:Class ;
+  owl:equivalentClass [ owl:unionOf ( :Person :Organization ) ] .
+
+# a second statement about the agent
+:Agent :definition "An agent" .
+[ a owl:Axiom ; owl:annotatedSource :Agent ] .
+:Person a owl:Class .
+"#;
+
+  fn iri(x: &str) -> TurtleTerm {
+    TurtleTerm::Iri(format!("http://example.org/{}", x))
+  }
+
+  #[test]
+  fn should_outline_statements_by_subject() -> std::io::Result<()> {
+    let nodes = outline(DOCUMENT)?;
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(nodes[0].subject, iri("Agent"));
+    assert_eq!(nodes[0].spans.len(), 2);
+    assert!(DOCUMENT[nodes[0].spans[1].clone()].starts_with(":Agent :definition"));
+    assert!(nodes[1].subject.is_blank_node());
+    assert_eq!(nodes[2].subject, iri("Person"));
+    assert_eq!(
+      &DOCUMENT[nodes[2].spans[0].clone()],
+      ":Person a owl:Class ."
+    );
+
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let subjects: Vec<TurtleTerm> = nodes.into_iter().map(|x| x.subject).collect();
+    assert_eq!(document.subjects().len(), subjects.len());
+    assert_eq!(document.subjects()[0], subjects[0]);
+    assert!(outline("@prefix : <http://example.org/> .\n:Agent a").is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,302 @@
+//! Reasoner module answers queries over the asserted axioms and their structural consequences
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::{owl, rdfs};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+const RDFS_SUB_PROPERTY_OF: &str = rdfs::SUB_PROPERTY_OF.as_str();
+const RDFS_SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const OWL_EQUIVALENT_CLASS: &str = owl::EQUIVALENT_CLASS.as_str();
+
+/// sub_properties returns the properties whose values are values of `property` in a set of triples:
+/// `property` itself and its direct and indirect sub-properties
+pub(crate) fn sub_properties(triples: &[TurtleTriple], property: &str) -> BTreeSet<String> {
+  let mut found = BTreeSet::new();
+  let mut queue = VecDeque::new();
+  found.insert(String::from(property));
+  queue.push_back(String::from(property));
+  while let Some(current) = queue.pop_front() {
+    for t in triples.iter() {
+      let sub = match (t.subject.as_iri(), t.predicate.as_iri(), t.object.as_iri()) {
+        (Some(s), Some(RDFS_SUB_PROPERTY_OF), Some(o)) if o == current => s,
+        _ => continue,
+      };
+      // properties in a cycle are equivalent and visited once
+      if found.insert(String::from(sub)) {
+        queue.push_back(String::from(sub));
+      }
+    }
+  }
+  found
+}
+
+/// PropertyValues indexes the triples of a document along with the closure of its property
+/// hierarchy, so that the values of many entities are looked up without going over the document
+/// each time. Build it once and query it:
+/// ```
+/// use semantic_owl_api::analysis::reasoner::PropertyValues;
+/// use semantic_owl_api::loader::load::load_turtle_document_from_str;
+///
+/// let document = load_turtle_document_from_str(
+///   "<http://example.org/Agent> <http://www.w3.org/2000/01/rdf-schema#label> \"Agent\" .",
+/// )?;
+/// let values = PropertyValues::new(&document);
+/// let label = "http://www.w3.org/2000/01/rdf-schema#label";
+/// assert_eq!(values.values_of("http://example.org/Agent", label).len(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PropertyValues {
+  graph: TripleGraph,
+
+  // direct and indirect sub-properties, itself included, of each property having sub-properties
+  sub_properties: HashMap<String, BTreeSet<String>>,
+}
+
+impl PropertyValues {
+  /// new indexes the triples of a document and closes its `rdfs:subPropertyOf` hierarchy
+  pub fn new(document: &TurtleDocument) -> PropertyValues {
+    let graph = document.graph();
+    let mut direct: HashMap<&str, Vec<&str>> = HashMap::new();
+    let predicate = TurtleTerm::Iri(String::from(RDFS_SUB_PROPERTY_OF));
+    for t in graph.triples_matching(None, Some(&predicate), None) {
+      if let (Some(s), Some(o)) = (t.subject.as_iri(), t.object.as_iri()) {
+        direct.entry(o).or_default().push(s);
+      }
+    }
+    let mut sub_properties = HashMap::new();
+    for property in direct.keys() {
+      let mut found = BTreeSet::new();
+      let mut queue = VecDeque::from(vec![*property]);
+      found.insert(String::from(*property));
+      while let Some(current) = queue.pop_front() {
+        for sub in direct.get(current).into_iter().flatten() {
+          // properties in a cycle are equivalent and visited once
+          if found.insert(String::from(*sub)) {
+            queue.push_back(sub);
+          }
+        }
+      }
+      sub_properties.insert(String::from(*property), found);
+    }
+    Self {
+      graph,
+      sub_properties,
+    }
+  }
+
+  /// values_of returns the values, IRIs, blank nodes or literals, of a property of an entity,
+  /// both given as full IRIs, in the order they were stated. Values asserted with a sub-property
+  /// of `property` are included
+  pub fn values_of(&self, iri: &str, property: &str) -> Vec<TurtleTerm> {
+    let is_sub_property = |p: &str| match self.sub_properties.get(property) {
+      Some(x) => x.contains(p),
+      None => p == property,
+    };
+    let subject = TurtleTerm::Iri(String::from(iri));
+    let mut values: Vec<TurtleTerm> = vec![];
+    for t in self.graph.triples_matching(Some(&subject), None, None) {
+      if t.predicate.as_iri().is_some_and(is_sub_property) && !values.contains(&t.object) {
+        values.push(t.object.clone());
+      }
+    }
+    values
+  }
+}
+
+impl TurtleDocument {
+  /// sub_properties_of returns the direct and indirect sub-properties of a property given as a full IRI,
+  /// excluding the property itself
+  /// example
+  /// with `skos:prefLabel rdfs:subPropertyOf rdfs:label`, `sub_properties_of(RDFS_LABEL)` contains `skos:prefLabel`
+  pub fn sub_properties_of(&self, property: &str) -> BTreeSet<String> {
+    let mut found = sub_properties(&self.triples(), property);
+    found.remove(property);
+    found
+  }
+
+  /// super_properties_of returns the direct and indirect super-properties of a property given as a full IRI,
+  /// excluding the property itself
+  pub fn super_properties_of(&self, property: &str) -> BTreeSet<String> {
+    let triples = self.triples();
+    let mut found = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(String::from(property));
+    while let Some(current) = queue.pop_front() {
+      for t in triples.iter() {
+        let sup = match (t.subject.as_iri(), t.predicate.as_iri(), t.object.as_iri()) {
+          (Some(s), Some(RDFS_SUB_PROPERTY_OF), Some(o)) if s == current => o,
+          _ => continue,
+        };
+        if sup != property && found.insert(String::from(sup)) {
+          queue.push_back(String::from(sup));
+        }
+      }
+    }
+    found
+  }
+
+  /// is_subclass_of determines whether a class is a sub class of another, both given as full IRIs,
+  /// following the `rdfs:subClassOf` and `owl:equivalentClass` axioms between named classes.
+  /// A class is a sub class of itself
+  pub fn is_subclass_of(&self, class: &str, super_class: &str) -> bool {
+    self.subclass_justification(class, super_class).is_some()
+  }
+
+  /// subclass_justification returns the shortest chain of asserted axioms from which
+  /// `is_subclass_of(class, super_class)` follows, in order from `class` to `super_class`.
+  /// The chain of a class and itself is empty. It returns `None` if the classes are not related
+  /// example
+  /// with `ex:Tank rdfs:subClassOf ex:AFV` and `ex:AFV owl:equivalentClass ex:ArmoredVehicle`,
+  /// `subclass_justification(ex:Tank, ex:ArmoredVehicle)` returns both statements
+  pub fn subclass_justification(
+    &self,
+    class: &str,
+    super_class: &str,
+  ) -> Option<Vec<TurtleTriple>> {
+    let triples = self.triples();
+    // the statements leading to each super class of `class`. Equivalences are followed both ways
+    let mut edges: BTreeMap<&str, Vec<(&str, &TurtleTriple)>> = BTreeMap::new();
+    for t in triples.iter() {
+      match (t.subject.as_iri(), t.predicate.as_iri(), t.object.as_iri()) {
+        (Some(s), Some(RDFS_SUB_CLASS_OF), Some(o)) => edges.entry(s).or_default().push((o, t)),
+        (Some(s), Some(OWL_EQUIVALENT_CLASS), Some(o)) => {
+          edges.entry(s).or_default().push((o, t));
+          edges.entry(o).or_default().push((s, t));
+        }
+        _ => {}
+      }
+    }
+
+    // breadth first, so the first chain found is a shortest one
+    let mut reached: BTreeMap<&str, Option<(&str, &TurtleTriple)>> = BTreeMap::new();
+    let mut queue = VecDeque::new();
+    reached.insert(class, None);
+    queue.push_back(class);
+    while let Some(current) = queue.pop_front() {
+      if current == super_class {
+        let mut chain = vec![];
+        let mut node = current;
+        while let Some(Some((previous, t))) = reached.get(node) {
+          chain.push(TurtleTriple::clone(t));
+          node = previous;
+        }
+        chain.reverse();
+        return Some(chain);
+      }
+      for (next, t) in edges.get(current).into_iter().flatten() {
+        if !reached.contains_key(next) {
+          reached.insert(next, Some((current, t)));
+          queue.push_back(next);
+        }
+      }
+    }
+    None
+  }
+
+  /// values_of returns the values, IRIs, blank nodes or literals, of a property of an entity, both given
+  /// as full IRIs. Values asserted with a sub-property of `property` are included. Use
+  /// `PropertyValues` to look up the values of several entities
+  /// example
+  /// `values_of("http://example.org/JohnDoe", "http://example.org/is_part_of")`
+  pub fn values_of(&self, iri: &str, property: &str) -> Vec<TurtleTerm> {
+    PropertyValues::new(self).values_of(iri, property)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+
+skos:prefLabel a owl:AnnotationProperty ; rdfs:subPropertyOf rdfs:label .
+ex:shortLabel a owl:AnnotationProperty ; rdfs:subPropertyOf skos:prefLabel .
+ex:has_part a owl:ObjectProperty .
+ex:has_component a owl:ObjectProperty ; rdfs:subPropertyOf ex:has_part .
+ex:has_member a owl:ObjectProperty ; rdfs:subPropertyOf ex:has_component .
+ex:has_component rdfs:subPropertyOf ex:has_member .
+
+ex:Crew rdfs:label "crew"@en ; skos:prefLabel "ship's crew"@en ; ex:shortLabel "crew"@en ;
+  ex:has_member ex:Captain ; ex:has_component ex:Captain , ex:Mate .
+"#;
+
+  #[test]
+  fn should_find_values_of_sub_properties() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let labels = document.labels_of("http://example.org/Crew");
+    assert_eq!(labels.values(), vec!["crew", "ship's crew"]);
+    let parts = document.values_of("http://example.org/Crew", "http://example.org/has_part");
+    assert_eq!(parts.len(), 2);
+    let members = document.values_of("http://example.org/Crew", "http://example.org/has_member");
+    assert_eq!(members.len(), 2);
+
+    let values = super::PropertyValues::new(&document);
+    assert_eq!(
+      values.values_of("http://example.org/Crew", "http://example.org/has_part"),
+      parts
+    );
+    assert!(values
+      .values_of("http://example.org/Crew", "http://example.org/unknown")
+      .is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn should_justify_sub_classes() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Tank rdfs:subClassOf ex:AFV , [ owl:onProperty ex:has_part ; owl:someValuesFrom ex:Turret ] .
+ex:ArmoredVehicle owl:equivalentClass ex:AFV .
+ex:ArmoredVehicle rdfs:subClassOf ex:Vehicle .
+ex:AFV rdfs:subClassOf ex:Vehicle .
+"#,
+    )?;
+    let ex = |x: &str| format!("http://example.org/{}", x);
+    assert!(document.is_subclass_of(&ex("Tank"), &ex("Vehicle")));
+    assert!(!document.is_subclass_of(&ex("Vehicle"), &ex("Tank")));
+    assert!(!document.is_subclass_of(&ex("Tank"), &ex("Turret")));
+
+    let chain = document
+      .subclass_justification(&ex("Tank"), &ex("ArmoredVehicle"))
+      .unwrap();
+    let subjects: Vec<&str> = chain.iter().filter_map(|t| t.subject.as_iri()).collect();
+    assert_eq!(subjects, vec![ex("Tank"), ex("ArmoredVehicle")]);
+    // the shortest chain is returned
+    let chain = document
+      .subclass_justification(&ex("Tank"), &ex("Vehicle"))
+      .unwrap();
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[1].subject.as_iri(), Some(ex("AFV").as_str()));
+    assert_eq!(
+      document.subclass_justification(&ex("Tank"), &ex("Tank")),
+      Some(vec![])
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_close_property_hierarchy() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let subs = document.sub_properties_of("http://www.w3.org/2000/01/rdf-schema#label");
+    assert_eq!(subs.len(), 2);
+    assert!(subs.contains("http://example.org/shortLabel"));
+    let supers = document.super_properties_of("http://example.org/has_member");
+    assert_eq!(
+      supers.into_iter().collect::<Vec<String>>(),
+      vec![
+        "http://example.org/has_component",
+        "http://example.org/has_part"
+      ]
+    );
+    Ok(())
+  }
+}
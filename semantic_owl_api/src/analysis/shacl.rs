@@ -0,0 +1,796 @@
+//! SHACL module validates data documents against SHACL shapes
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::load::load_turtle_document;
+use crate::vocab::{owl, rdf, rdfs};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const RDFS_CLASS: &str = rdfs::CLASS.as_str();
+const OWL_CLASS: &str = owl::CLASS.as_str();
+
+const SH_NODE_SHAPE: &str = "http://www.w3.org/ns/shacl#NodeShape";
+const SH_PROPERTY: &str = "http://www.w3.org/ns/shacl#property";
+const SH_PATH: &str = "http://www.w3.org/ns/shacl#path";
+const SH_INVERSE_PATH: &str = "http://www.w3.org/ns/shacl#inversePath";
+const SH_MESSAGE: &str = "http://www.w3.org/ns/shacl#message";
+const SH_DEACTIVATED: &str = "http://www.w3.org/ns/shacl#deactivated";
+const SH_TARGET_CLASS: &str = "http://www.w3.org/ns/shacl#targetClass";
+const SH_TARGET_NODE: &str = "http://www.w3.org/ns/shacl#targetNode";
+const SH_TARGET_SUBJECTS_OF: &str = "http://www.w3.org/ns/shacl#targetSubjectsOf";
+const SH_TARGET_OBJECTS_OF: &str = "http://www.w3.org/ns/shacl#targetObjectsOf";
+const SH_CLASS: &str = "http://www.w3.org/ns/shacl#class";
+const SH_DATATYPE: &str = "http://www.w3.org/ns/shacl#datatype";
+const SH_NODE_KIND: &str = "http://www.w3.org/ns/shacl#nodeKind";
+const SH_MIN_COUNT: &str = "http://www.w3.org/ns/shacl#minCount";
+const SH_MAX_COUNT: &str = "http://www.w3.org/ns/shacl#maxCount";
+const SH_MIN_LENGTH: &str = "http://www.w3.org/ns/shacl#minLength";
+const SH_MAX_LENGTH: &str = "http://www.w3.org/ns/shacl#maxLength";
+const SH_HAS_VALUE: &str = "http://www.w3.org/ns/shacl#hasValue";
+const SH_IN: &str = "http://www.w3.org/ns/shacl#in";
+const SH_IRI: &str = "http://www.w3.org/ns/shacl#IRI";
+const SH_BLANK_NODE: &str = "http://www.w3.org/ns/shacl#BlankNode";
+const SH_LITERAL: &str = "http://www.w3.org/ns/shacl#Literal";
+const SH_BLANK_NODE_OR_IRI: &str = "http://www.w3.org/ns/shacl#BlankNodeOrIRI";
+const SH_BLANK_NODE_OR_LITERAL: &str = "http://www.w3.org/ns/shacl#BlankNodeOrLiteral";
+const SH_IRI_OR_LITERAL: &str = "http://www.w3.org/ns/shacl#IRIOrLiteral";
+
+const SH: &str = "http://www.w3.org/ns/shacl#";
+
+// parameters of the SHACL core constraint components which are not supported
+const UNSUPPORTED_PARAMETERS: [&str; 24] = [
+  "minExclusive",
+  "minInclusive",
+  "maxExclusive",
+  "maxInclusive",
+  "pattern",
+  "flags",
+  "languageIn",
+  "uniqueLang",
+  "equals",
+  "disjoint",
+  "lessThan",
+  "lessThanOrEquals",
+  "not",
+  "and",
+  "or",
+  "xone",
+  "node",
+  "qualifiedValueShape",
+  "qualifiedMinCount",
+  "qualifiedMaxCount",
+  "qualifiedValueShapesDisjoint",
+  "closed",
+  "ignoredProperties",
+  "sparql",
+];
+
+/// Target selects the focus nodes of a shape in a data document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Target {
+  // instances of a class and of its sub classes
+  Class(String),
+  Node(TurtleTerm),
+  // subjects of the statements using a predicate
+  SubjectsOf(String),
+  // objects of the statements using a predicate
+  ObjectsOf(String),
+}
+
+/// ShaclPath is the path from a focus node to the values of a property shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ShaclPath {
+  // e.g -> sh:path ex:name
+  Predicate(String),
+  // e.g -> sh:path [ sh:inversePath ex:parent ]
+  Inverse(String),
+}
+
+impl fmt::Display for ShaclPath {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ShaclPath::Predicate(x) => write!(f, "<{}>", x),
+      ShaclPath::Inverse(x) => write!(f, "^<{}>", x),
+    }
+  }
+}
+
+/// Constraint is a constraint of a shape on its value nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Constraint {
+  Class(String),
+  Datatype(String),
+  // one of the `sh:nodeKind` values. e.g -> http://www.w3.org/ns/shacl#IRI
+  NodeKind(String),
+  MinCount(usize),
+  MaxCount(usize),
+  MinLength(usize),
+  MaxLength(usize),
+  HasValue(TurtleTerm),
+  In(Vec<TurtleTerm>),
+}
+
+impl Constraint {
+  /// component returns the IRI of the constraint component. e.g -> `sh:MinCountConstraintComponent`
+  pub fn component(&self) -> String {
+    let name = match self {
+      Constraint::Class(_) => "Class",
+      Constraint::Datatype(_) => "Datatype",
+      Constraint::NodeKind(_) => "NodeKind",
+      Constraint::MinCount(_) => "MinCount",
+      Constraint::MaxCount(_) => "MaxCount",
+      Constraint::MinLength(_) => "MinLength",
+      Constraint::MaxLength(_) => "MaxLength",
+      Constraint::HasValue(_) => "HasValue",
+      Constraint::In(_) => "In",
+    };
+    format!("http://www.w3.org/ns/shacl#{}ConstraintComponent", name)
+  }
+}
+
+/// PropertyShape constrains the values reached from a focus node through a path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyShape {
+  pub id: TurtleTerm,
+  pub path: ShaclPath,
+  pub constraints: Vec<Constraint>,
+
+  // `sh:message` of the shape, reported instead of the default messages
+  pub message: Option<String>,
+}
+
+impl PropertyShape {
+  pub fn new(
+    id: TurtleTerm,
+    path: ShaclPath,
+    constraints: Vec<Constraint>,
+    message: Option<String>,
+  ) -> PropertyShape {
+    Self {
+      id,
+      path,
+      constraints,
+      message,
+    }
+  }
+}
+
+/// NodeShape constrains the focus nodes it targets and their properties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeShape {
+  pub id: TurtleTerm,
+  pub targets: Vec<Target>,
+  pub constraints: Vec<Constraint>,
+  pub properties: Vec<PropertyShape>,
+  pub message: Option<String>,
+}
+
+impl NodeShape {
+  pub fn new(
+    id: TurtleTerm,
+    targets: Vec<Target>,
+    constraints: Vec<Constraint>,
+    properties: Vec<PropertyShape>,
+    message: Option<String>,
+  ) -> NodeShape {
+    Self {
+      id,
+      targets,
+      constraints,
+      properties,
+      message,
+    }
+  }
+}
+
+/// ShaclResult is a constraint broken by a value node of a focus node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShaclResult {
+  pub focus_node: TurtleTerm,
+
+  // path of the property shape. `None` for the constraints of a node shape
+  pub path: Option<ShaclPath>,
+
+  // value breaking the constraint. `None` for cardinality constraints
+  pub value: Option<TurtleTerm>,
+
+  // shape declaring the constraint
+  pub shape: TurtleTerm,
+
+  // IRI of the constraint component. e.g -> http://www.w3.org/ns/shacl#MinCountConstraintComponent
+  pub component: String,
+
+  pub message: String,
+}
+
+impl ShaclResult {
+  pub fn new(
+    focus_node: TurtleTerm,
+    path: Option<ShaclPath>,
+    value: Option<TurtleTerm>,
+    shape: TurtleTerm,
+    component: String,
+    message: String,
+  ) -> ShaclResult {
+    Self {
+      focus_node,
+      path,
+      value,
+      shape,
+      component,
+      message,
+    }
+  }
+}
+
+/// ShaclReport lists the results of a validation, in the order of the shapes and focus nodes
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ShaclReport {
+  pub results: Vec<ShaclResult>,
+}
+
+impl ShaclReport {
+  pub fn new(results: Vec<ShaclResult>) -> ShaclReport {
+    Self { results }
+  }
+
+  /// conforms determines whether the data conforms to the shapes
+  pub fn conforms(&self) -> bool {
+    self.results.is_empty()
+  }
+}
+
+/// Shapes are the node shapes of a shapes graph
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Shapes {
+  pub node_shapes: Vec<NodeShape>,
+}
+
+/// load_shapes loads the shapes of a turtle document
+pub fn load_shapes(path: &str) -> std::io::Result<Shapes> {
+  Shapes::from_document(&load_turtle_document(path)?)
+}
+
+impl Shapes {
+  pub fn new(node_shapes: Vec<NodeShape>) -> Shapes {
+    Self { node_shapes }
+  }
+
+  /// from_document reads the node shapes of a shapes graph: the subjects typed `sh:NodeShape`
+  /// or declaring a target. Deactivated shapes are left out.
+  /// It will return an error if a property shape has no supported path, a constraint has a
+  /// malformed value, e.g a `sh:minCount` which is not an integer, or a constraint is not
+  /// supported, e.g `sh:pattern`
+  pub fn from_document(document: &TurtleDocument) -> std::io::Result<Shapes> {
+    let graph = document.graph();
+    let targets = [
+      SH_TARGET_CLASS,
+      SH_TARGET_NODE,
+      SH_TARGET_SUBJECTS_OF,
+      SH_TARGET_OBJECTS_OF,
+    ];
+    let mut ids: Vec<&TurtleTerm> = vec![];
+    let mut seen: HashSet<&TurtleTerm> = HashSet::new();
+    for t in graph.triples() {
+      let is_shape = match (t.predicate.as_iri(), t.object.as_iri()) {
+        (Some(RDF_TYPE), Some(SH_NODE_SHAPE)) => true,
+        (Some(p), _) => targets.contains(&p),
+        _ => false,
+      };
+      if is_shape && seen.insert(&t.subject) {
+        ids.push(&t.subject);
+      }
+    }
+
+    let mut node_shapes = vec![];
+    for id in ids.into_iter().filter(|x| !is_deactivated(&graph, x)) {
+      let mut targets = vec![];
+      for (p, o) in statements_of(&graph, id) {
+        match (p, o.as_iri()) {
+          (SH_TARGET_CLASS, Some(x)) => targets.push(Target::Class(String::from(x))),
+          (SH_TARGET_NODE, _) => targets.push(Target::Node(o.clone())),
+          (SH_TARGET_SUBJECTS_OF, Some(x)) => targets.push(Target::SubjectsOf(String::from(x))),
+          (SH_TARGET_OBJECTS_OF, Some(x)) => targets.push(Target::ObjectsOf(String::from(x))),
+          // a shape which is a class targets its instances
+          (RDF_TYPE, Some(RDFS_CLASS)) | (RDF_TYPE, Some(OWL_CLASS)) => {
+            if let Some(x) = id.as_iri() {
+              targets.push(Target::Class(String::from(x)));
+            }
+          }
+          _ => {}
+        }
+      }
+      let mut properties = vec![];
+      for property in graph.triples_matching(Some(id), None, None) {
+        if property.predicate.as_iri() != Some(SH_PROPERTY)
+          || is_deactivated(&graph, &property.object)
+        {
+          continue;
+        }
+        properties.push(property_shape(&graph, &property.object)?);
+      }
+      node_shapes.push(NodeShape::new(
+        id.clone(),
+        targets,
+        constraints_of(&graph, id)?,
+        properties,
+        message_of(&graph, id),
+      ));
+    }
+    Ok(Shapes::new(node_shapes))
+  }
+
+  /// validate validates a data document against the shapes
+  pub fn validate(&self, data: &TurtleDocument) -> ShaclReport {
+    let graph = data.graph();
+    let mut validator = Validator {
+      graph: &graph,
+      sub_classes: HashMap::new(),
+      results: vec![],
+    };
+    for shape in self.node_shapes.iter() {
+      for focus in validator.focus_nodes(&shape.targets) {
+        let values = vec![focus.clone()];
+        validator.check(
+          &focus,
+          &shape.id,
+          None,
+          &values,
+          &shape.constraints,
+          &shape.message,
+        );
+        for property in shape.properties.iter() {
+          let values = validator.values(&focus, &property.path);
+          validator.check(
+            &focus,
+            &property.id,
+            Some(&property.path),
+            &values,
+            &property.constraints,
+            &property.message,
+          );
+        }
+      }
+    }
+    ShaclReport::new(validator.results)
+  }
+}
+
+// property_shape reads the property shape `id`
+fn property_shape(graph: &TripleGraph, id: &TurtleTerm) -> std::io::Result<PropertyShape> {
+  let path = match graph.object(id, SH_PATH) {
+    Some(TurtleTerm::Iri(x)) => ShaclPath::Predicate(x.clone()),
+    Some(node) => match graph.object(node, SH_INVERSE_PATH) {
+      Some(TurtleTerm::Iri(x)) => ShaclPath::Inverse(x.clone()),
+      _ => return Err(malformed_shape(id, "its path is not supported")),
+    },
+    None => return Err(malformed_shape(id, "it has no sh:path")),
+  };
+  if graph.object(id, SH_PROPERTY).is_some() {
+    return Err(unsupported_constraint(id, SH_PROPERTY));
+  }
+  Ok(PropertyShape::new(
+    id.clone(),
+    path,
+    constraints_of(graph, id)?,
+    message_of(graph, id),
+  ))
+}
+
+// constraints_of reads the constraints of a shape. Constraints which are not supported are
+// reported as errors. Property shapes are read apart
+fn constraints_of(graph: &TripleGraph, id: &TurtleTerm) -> std::io::Result<Vec<Constraint>> {
+  let mut constraints = vec![];
+  for (p, o) in statements_of(graph, id) {
+    let count = || match o {
+      TurtleTerm::Literal { value, .. } => value
+        .parse::<usize>()
+        .map_err(|_| malformed_shape(id, &format!("<{}> is not an integer", p))),
+      _ => Err(malformed_shape(id, &format!("<{}> is not an integer", p))),
+    };
+    let iri = || match o.as_iri() {
+      Some(x) => Ok(String::from(x)),
+      None => Err(malformed_shape(id, &format!("<{}> is not an IRI", p))),
+    };
+    let constraint = match p {
+      SH_CLASS => Constraint::Class(iri()?),
+      SH_DATATYPE => Constraint::Datatype(iri()?),
+      SH_NODE_KIND => Constraint::NodeKind(iri()?),
+      SH_MIN_COUNT => Constraint::MinCount(count()?),
+      SH_MAX_COUNT => Constraint::MaxCount(count()?),
+      SH_MIN_LENGTH => Constraint::MinLength(count()?),
+      SH_MAX_LENGTH => Constraint::MaxLength(count()?),
+      SH_HAS_VALUE => Constraint::HasValue(o.clone()),
+      SH_IN => match graph.list(o) {
+        Some(members) => Constraint::In(members.into_iter().cloned().collect()),
+        None => return Err(malformed_shape(id, "its sh:in is not a list")),
+      },
+      SH_PROPERTY => continue,
+      p => match unsupported_parameter(p) {
+        Some(p) => return Err(unsupported_constraint(id, p)),
+        None => continue,
+      },
+    };
+    constraints.push(constraint);
+  }
+  Ok(constraints)
+}
+
+// statements_of returns the predicates and objects of the statements about a node
+fn statements_of<'a>(
+  graph: &'a TripleGraph,
+  node: &'a TurtleTerm,
+) -> impl Iterator<Item = (&'a str, &'a TurtleTerm)> {
+  graph
+    .triples_matching(Some(node), None, None)
+    .filter_map(|t| Some((t.predicate.as_iri()?, &t.object)))
+}
+
+fn message_of(graph: &TripleGraph, id: &TurtleTerm) -> Option<String> {
+  match graph.object(id, SH_MESSAGE) {
+    Some(TurtleTerm::Literal { value, .. }) => Some(value.clone()),
+    _ => None,
+  }
+}
+
+fn is_deactivated(graph: &TripleGraph, id: &TurtleTerm) -> bool {
+  matches!(graph.object(id, SH_DEACTIVATED), Some(TurtleTerm::Literal { value, .. }) if value == "true")
+}
+
+// unsupported_parameter returns a predicate if it is the parameter of a constraint which is not
+// supported. e.g -> http://www.w3.org/ns/shacl#pattern
+fn unsupported_parameter(p: &str) -> Option<&str> {
+  let name = p.strip_prefix(SH)?;
+  UNSUPPORTED_PARAMETERS.contains(&name).then_some(p)
+}
+
+fn unsupported_constraint(id: &TurtleTerm, parameter: &str) -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::Unsupported,
+    format!(
+      "the shape {} uses <{}>, which is not supported",
+      id, parameter
+    ),
+  )
+}
+
+fn malformed_shape(id: &TurtleTerm, reason: &str) -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::InvalidData,
+    format!("the shape {} is malformed: {}", id, reason),
+  )
+}
+
+struct Validator<'a> {
+  graph: &'a TripleGraph,
+
+  // classes and their direct and indirect sub classes in the data
+  sub_classes: HashMap<String, BTreeSet<String>>,
+
+  results: Vec<ShaclResult>,
+}
+
+impl<'a> Validator<'a> {
+  // focus_nodes returns the nodes targeted by a shape, in the order they are found
+  fn focus_nodes(&mut self, targets: &[Target]) -> Vec<TurtleTerm> {
+    let mut nodes: Vec<TurtleTerm> = vec![];
+    let mut seen: HashSet<TurtleTerm> = HashSet::new();
+    for target in targets.iter() {
+      let found: Vec<TurtleTerm> = match target {
+        Target::Node(x) => vec![x.clone()],
+        Target::Class(class) => {
+          let classes = self.sub_classes_of(class).clone();
+          self
+            .graph
+            .triples()
+            .filter(|t| t.predicate.as_iri() == Some(RDF_TYPE))
+            .filter(|t| t.object.as_iri().map(|x| classes.contains(x)) == Some(true))
+            .map(|t| t.subject.clone())
+            .collect()
+        }
+        Target::SubjectsOf(p) => self
+          .graph
+          .triples()
+          .filter(|t| t.predicate.as_iri() == Some(p.as_str()))
+          .map(|t| t.subject.clone())
+          .collect(),
+        Target::ObjectsOf(p) => self
+          .graph
+          .triples()
+          .filter(|t| t.predicate.as_iri() == Some(p.as_str()))
+          .map(|t| t.object.clone())
+          .collect(),
+      };
+      for node in found {
+        if seen.insert(node.clone()) {
+          nodes.push(node);
+        }
+      }
+    }
+    nodes
+  }
+
+  // sub_classes_of returns a class and its direct and indirect sub classes
+  fn sub_classes_of(&mut self, class: &str) -> &BTreeSet<String> {
+    let graph = self.graph;
+    self
+      .sub_classes
+      .entry(String::from(class))
+      .or_insert_with(|| {
+        let mut found = BTreeSet::new();
+        let mut pending = vec![String::from(class)];
+        while let Some(current) = pending.pop() {
+          if !found.insert(current.clone()) {
+            continue;
+          }
+          let current = TurtleTerm::Iri(current);
+          for t in graph.triples_matching(None, None, Some(&current)) {
+            if let (Some(RDFS_SUB_CLASS_OF), Some(sub)) = (t.predicate.as_iri(), t.subject.as_iri())
+            {
+              pending.push(String::from(sub));
+            }
+          }
+        }
+        found
+      })
+  }
+
+  // values returns the value nodes reached from a focus node through a path
+  fn values(&self, focus: &TurtleTerm, path: &ShaclPath) -> Vec<TurtleTerm> {
+    let mut values: Vec<TurtleTerm> = vec![];
+    let mut seen: HashSet<&TurtleTerm> = HashSet::new();
+    let found = match path {
+      ShaclPath::Predicate(p) => self
+        .graph
+        .triples_matching(Some(focus), None, None)
+        .filter(|t| t.predicate.as_iri() == Some(p.as_str()))
+        .map(|t| &t.object)
+        .collect::<Vec<&TurtleTerm>>(),
+      ShaclPath::Inverse(p) => self
+        .graph
+        .triples_matching(None, None, Some(focus))
+        .filter(|t| t.predicate.as_iri() == Some(p.as_str()))
+        .map(|t| &t.subject)
+        .collect(),
+    };
+    for v in found {
+      if seen.insert(v) {
+        values.push(v.clone());
+      }
+    }
+    values
+  }
+
+  // check records the results of the constraints the value nodes break
+  fn check(
+    &mut self,
+    focus: &TurtleTerm,
+    shape: &TurtleTerm,
+    path: Option<&ShaclPath>,
+    values: &[TurtleTerm],
+    constraints: &[Constraint],
+    message: &Option<String>,
+  ) {
+    for constraint in constraints.iter() {
+      let broken: Vec<(Option<&TurtleTerm>, String)> = match constraint {
+        Constraint::MinCount(n) if values.len() < *n => {
+          vec![(
+            None,
+            format!("has {} values, less than {}", values.len(), n),
+          )]
+        }
+        Constraint::MaxCount(n) if values.len() > *n => {
+          vec![(
+            None,
+            format!("has {} values, more than {}", values.len(), n),
+          )]
+        }
+        Constraint::HasValue(x) if !values.contains(x) => {
+          vec![(None, format!("does not have the value {}", x))]
+        }
+        Constraint::MinCount(_) | Constraint::MaxCount(_) | Constraint::HasValue(_) => vec![],
+        constraint => {
+          let mut broken = vec![];
+          for v in values.iter() {
+            if let Some(reason) = self.breaks(constraint, v) {
+              broken.push((Some(v), reason));
+            }
+          }
+          broken
+        }
+      };
+      for (value, reason) in broken {
+        let message = match message {
+          Some(x) => x.clone(),
+          None => match (path, value) {
+            (Some(_), Some(v)) => format!("{} of {} {}", v, focus, reason),
+            (Some(path), None) => format!("{} {} {}", focus, path, reason),
+            (None, _) => format!("{} {}", focus, reason),
+          },
+        };
+        self.results.push(ShaclResult::new(
+          focus.clone(),
+          path.cloned(),
+          value.cloned(),
+          shape.clone(),
+          constraint.component(),
+          message,
+        ));
+      }
+    }
+  }
+
+  // breaks returns the reason a value node breaks a value type, range or length constraint
+  fn breaks(&mut self, constraint: &Constraint, value: &TurtleTerm) -> Option<String> {
+    match constraint {
+      Constraint::Class(class) => {
+        let classes = self.sub_classes_of(class).clone();
+        let is_instance = self
+          .graph
+          .triples_matching(Some(value), None, None)
+          .filter(|t| t.predicate.as_iri() == Some(RDF_TYPE))
+          .any(|t| t.object.as_iri().map(|x| classes.contains(x)) == Some(true));
+        match is_instance {
+          true => None,
+          false => Some(format!("is not an instance of <{}>", class)),
+        }
+      }
+      Constraint::Datatype(datatype) => match value {
+        TurtleTerm::Literal { datatype: x, .. } if x == datatype => None,
+        _ => Some(format!("is not a literal of datatype <{}>", datatype)),
+      },
+      Constraint::NodeKind(kind) => {
+        let (iri, blank, literal) = (
+          matches!(value, TurtleTerm::Iri(_)),
+          value.is_blank_node(),
+          value.is_literal(),
+        );
+        let conforms = match kind.as_str() {
+          SH_IRI => iri,
+          SH_BLANK_NODE => blank,
+          SH_LITERAL => literal,
+          SH_BLANK_NODE_OR_IRI => blank || iri,
+          SH_BLANK_NODE_OR_LITERAL => blank || literal,
+          SH_IRI_OR_LITERAL => iri || literal,
+          _ => true,
+        };
+        match conforms {
+          true => None,
+          false => Some(format!("is not of node kind <{}>", kind)),
+        }
+      }
+      Constraint::MinLength(n) | Constraint::MaxLength(n) => {
+        let length = match value {
+          TurtleTerm::Iri(x) => x.chars().count(),
+          TurtleTerm::Literal { value, .. } => value.chars().count(),
+          _ => return Some(String::from("has no length")),
+        };
+        match (constraint, length) {
+          (Constraint::MinLength(_), l) if l < *n => {
+            Some(format!("is {} characters long, less than {}", l, n))
+          }
+          (Constraint::MaxLength(_), l) if l > *n => {
+            Some(format!("is {} characters long, more than {}", l, n))
+          }
+          _ => None,
+        }
+      }
+      Constraint::In(members) => match members.contains(value) {
+        true => None,
+        false => Some(String::from("is not one of the allowed values")),
+      },
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const SHAPES: &str = r#"@prefix sh: <http://www.w3.org/ns/shacl#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix ex: <http://example.org/> .
+
+ex:PersonShape a sh:NodeShape ;
+  sh:targetClass ex:Person ;
+  sh:nodeKind sh:IRI ;
+  sh:property [ sh:path ex:name ; sh:minCount 1 ; sh:maxCount 1 ; sh:datatype xsd:string ] ;
+  sh:property [ sh:path ex:employer ; sh:class ex:Organization ; sh:message "employers are organizations" ] ;
+  sh:property [ sh:path [ sh:inversePath ex:member ] ; sh:in ( ex:Team ex:Club ) ] ;
+  sh:property [ sh:path ex:nickname ; sh:maxCount 0 ; sh:deactivated true ] .
+"#;
+
+  const DATA: &str = r#"@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Employee rdfs:subClassOf ex:Person .
+ex:Company rdfs:subClassOf ex:Organization .
+ex:Acme a ex:Company .
+ex:Alice a ex:Person ; ex:name "Alice" ; ex:employer ex:Acme ; ex:nickname "Al" .
+ex:Bob a ex:Employee ; ex:name "Bob" , "Robert" ; ex:employer ex:Alice .
+ex:Team ex:member ex:Alice .
+ex:Gang ex:member ex:Bob .
+_:x a ex:Person ; ex:name 42 .
+"#;
+
+  #[test]
+  fn should_validate_data_against_shapes() -> std::io::Result<()> {
+    let shapes = Shapes::from_document(&load_turtle_document_from_str(SHAPES)?)?;
+    assert_eq!(shapes.node_shapes.len(), 1);
+    assert_eq!(shapes.node_shapes[0].properties.len(), 3);
+    let report = shapes.validate(&load_turtle_document_from_str(DATA)?);
+    assert!(!report.conforms());
+    let results: Vec<(String, String)> = report
+      .results
+      .iter()
+      .map(|r| {
+        let component = r.component.rsplit('#').next().unwrap_or_default();
+        (r.focus_node.to_string(), String::from(component))
+      })
+      .collect();
+    let bob = String::from("<http://example.org/Bob>");
+    assert_eq!(
+      results,
+      vec![
+        (bob.clone(), String::from("MaxCountConstraintComponent")),
+        (bob.clone(), String::from("ClassConstraintComponent")),
+        (bob, String::from("InConstraintComponent")),
+        (
+          String::from("_:x"),
+          String::from("NodeKindConstraintComponent")
+        ),
+        (
+          String::from("_:x"),
+          String::from("DatatypeConstraintComponent")
+        ),
+      ]
+    );
+    assert_eq!(report.results[1].message, "employers are organizations");
+    assert_eq!(
+      report.results[1].path,
+      Some(ShaclPath::Predicate(String::from(
+        "http://example.org/employer"
+      )))
+    );
+
+    let malformed = SHAPES.replace("sh:minCount 1", "sh:minCount \"one\"");
+    assert!(Shapes::from_document(&load_turtle_document_from_str(&malformed)?).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn should_reject_unsupported_constraints() -> std::io::Result<()> {
+    for constraint in [
+      "sh:pattern \"^A\"",
+      "sh:minInclusive 1",
+      "sh:node ex:NameShape",
+      "sh:or ( [ sh:datatype xsd:string ] )",
+    ] {
+      let shapes = SHAPES.replace(
+        "sh:minCount 1 ;",
+        &format!("sh:minCount 1 ; {} ;", constraint),
+      );
+      let err = Shapes::from_document(&load_turtle_document_from_str(&shapes)?).unwrap_err();
+      assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    }
+    let shapes = SHAPES.replace(
+      "sh:nodeKind sh:IRI ;",
+      "sh:nodeKind sh:IRI ; sh:closed true ;",
+    );
+    let err = Shapes::from_document(&load_turtle_document_from_str(&shapes)?).unwrap_err();
+    assert!(err
+      .to_string()
+      .contains("<http://www.w3.org/ns/shacl#closed>"));
+    // property shapes are only supported on node shapes
+    let nested = "sh:property [ sh:path ex:first ; sh:minCount 1 ] ;";
+    let shapes = SHAPES.replace("sh:minCount 1 ;", &format!("sh:minCount 1 ; {}", nested));
+    let err = Shapes::from_document(&load_turtle_document_from_str(&shapes)?).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    Ok(())
+  }
+}
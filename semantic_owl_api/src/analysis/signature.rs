@@ -0,0 +1,291 @@
+//! Signature module extracts the entities the statements of an ontology refer to
+use crate::analysis::entities::is_reserved_iri;
+use crate::analysis::validation::EntityType;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::manager::OntologyManager;
+use crate::vocab::{owl, rdf, rdfs, xsd};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const OWL_IMPORTS: &str = owl::IMPORTS.as_str();
+const OWL_VERSION_IRI: &str = owl::VERSION_IRI.as_str();
+
+const XSD_NAMESPACE: &str = xsd::NAMESPACE;
+
+// entities of the reserved vocabularies
+const BUILT_IN_ENTITIES: [(&str, EntityType); 17] = [
+  (owl::THING.as_str(), EntityType::Class),
+  (owl::NOTHING.as_str(), EntityType::Class),
+  (
+    owl::TOP_OBJECT_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (
+    owl::BOTTOM_OBJECT_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (owl::TOP_DATA_PROPERTY.as_str(), EntityType::DataProperty),
+  (owl::BOTTOM_DATA_PROPERTY.as_str(), EntityType::DataProperty),
+  (rdfs::LABEL.as_str(), EntityType::AnnotationProperty),
+  (rdfs::COMMENT.as_str(), EntityType::AnnotationProperty),
+  (rdfs::SEE_ALSO.as_str(), EntityType::AnnotationProperty),
+  (rdfs::IS_DEFINED_BY.as_str(), EntityType::AnnotationProperty),
+  (owl::VERSION_INFO.as_str(), EntityType::AnnotationProperty),
+  (owl::DEPRECATED.as_str(), EntityType::AnnotationProperty),
+  (owl::PRIOR_VERSION.as_str(), EntityType::AnnotationProperty),
+  (
+    owl::BACKWARD_COMPATIBLE_WITH.as_str(),
+    EntityType::AnnotationProperty,
+  ),
+  (
+    owl::INCOMPATIBLE_WITH.as_str(),
+    EntityType::AnnotationProperty,
+  ),
+  (rdfs::LITERAL.as_str(), EntityType::Datatype),
+  (rdf::LANG_STRING.as_str(), EntityType::Datatype),
+];
+
+/// Imports tells whether the signature of an ontology includes the signatures of its imports closure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Imports {
+  Included,
+  Excluded,
+}
+
+/// Signature is the set of entities an ontology refers to, with their kinds.
+/// IRIs whose kind is neither declared nor implied by their use have no kind
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Signature {
+  pub entities: BTreeMap<String, BTreeSet<EntityType>>,
+}
+
+impl Signature {
+  pub fn new(entities: BTreeMap<String, BTreeSet<EntityType>>) -> Signature {
+    Self { entities }
+  }
+
+  /// iris returns the IRIs of the signature, sorted
+  pub fn iris(&self) -> BTreeSet<&str> {
+    self.entities.keys().map(|x| x.as_str()).collect()
+  }
+
+  /// of_kind returns the IRIs of the entities of a kind, sorted
+  pub fn of_kind(&self, kind: EntityType) -> BTreeSet<&str> {
+    self
+      .entities
+      .iter()
+      .filter(|(_, kinds)| kinds.contains(&kind))
+      .map(|(iri, _)| iri.as_str())
+      .collect()
+  }
+
+  /// untyped returns the IRIs whose kind is unknown
+  pub fn untyped(&self) -> BTreeSet<&str> {
+    self
+      .entities
+      .iter()
+      .filter(|(_, kinds)| kinds.is_empty())
+      .map(|(iri, _)| iri.as_str())
+      .collect()
+  }
+
+  pub fn contains(&self, iri: &str) -> bool {
+    self.entities.contains_key(iri)
+  }
+
+  /// extend adds the entities of another signature, merging the kinds of the entities in both
+  pub fn extend(&mut self, other: Signature) {
+    for (iri, kinds) in other.entities {
+      self.entities.entry(iri).or_default().extend(kinds);
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.entities.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entities.is_empty()
+  }
+}
+
+impl TurtleDocument {
+  /// signature returns the entities the statements of the document refer to
+  pub fn signature(&self) -> Signature {
+    let entities = self.entities();
+    let id = self.ontology_id();
+    let header: BTreeSet<&str> = id
+      .ontology_iri
+      .iter()
+      .chain(id.version_iri.iter())
+      .map(|x| x.as_str())
+      .collect();
+    let is_annotation = |term: &TurtleTerm| -> bool {
+      term
+        .as_iri()
+        .map(|x| kinds_of(&entities, x).contains(&EntityType::AnnotationProperty))
+        .unwrap_or(false)
+    };
+
+    let mut signature = Signature::default();
+    for t in self.triples() {
+      let predicate = t.predicate.as_iri().unwrap_or_default();
+      let mut terms = vec![&t.subject, &t.predicate];
+      // the imports and version of the ontology, and IRI values of annotations, are not entities
+      if predicate != OWL_IMPORTS && predicate != OWL_VERSION_IRI && !is_annotation(&t.predicate) {
+        terms.push(&t.object);
+      }
+      for term in terms {
+        let iri = match term {
+          TurtleTerm::Iri(x) => x.as_str(),
+          _ => continue,
+        };
+        if header.contains(iri) || (is_reserved_iri(iri) && built_in_kind(iri).is_none()) {
+          continue;
+        }
+        signature
+          .entities
+          .insert(String::from(iri), kinds_of(&entities, iri));
+      }
+      if let TurtleTerm::Literal { datatype, .. } = &t.object {
+        signature
+          .entities
+          .insert(datatype.clone(), kinds_of(&entities, datatype));
+      }
+    }
+    signature
+  }
+}
+
+impl OntologyManager {
+  /// signature returns the signature of a loaded ontology, given as a full IRI, and optionally
+  /// of its imports closure. An ontology which is not loaded has an empty signature
+  pub fn signature(&self, iri: &str, imports: Imports) -> Signature {
+    let ontologies = match imports {
+      Imports::Included => self.import_closure(iri),
+      Imports::Excluded => vec![String::from(iri)],
+    };
+    let mut signature = Signature::default();
+    for document in ontologies.iter().filter_map(|x| self.document(x)) {
+      signature.extend(document.signature());
+    }
+    signature
+  }
+}
+
+// built_in_kind returns the kind of an entity of the reserved vocabularies
+fn built_in_kind(iri: &str) -> Option<EntityType> {
+  match BUILT_IN_ENTITIES.iter().find(|(x, _)| *x == iri) {
+    Some((_, kind)) => Some(*kind),
+    None if iri.starts_with(XSD_NAMESPACE) => Some(EntityType::Datatype),
+    None => None,
+  }
+}
+
+// kinds_of returns the kinds of an entity, declared, implied or built in
+fn kinds_of(entities: &BTreeMap<String, BTreeSet<EntityType>>, iri: &str) -> BTreeSet<EntityType> {
+  let mut kinds = entities.get(iri).cloned().unwrap_or_default();
+  kinds.extend(built_in_kind(iri));
+  kinds
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::iri_mapper::SimpleIriMapper;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix : <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+<http://example.org/ontology> a owl:Ontology ;
+  owl:versionIRI <http://example.org/ontology/1.0> ;
+  owl:imports <http://example.org/upper> .
+:definition a owl:AnnotationProperty .
+:Agent a owl:Class ;
+  rdfs:subClassOf :Entity , [ a owl:Restriction ; owl:onProperty :has_part ; owl:someValuesFrom owl:Thing ] ;
+  rdfs:label "Agent"@en ;
+  rdfs:seeAlso <http://example.org/documentation> ;
+  :definition "An agent"^^xsd:string .
+:JohnDoe :has_age 42 .
+"#;
+
+  #[test]
+  fn should_extract_signature() -> std::io::Result<()> {
+    let signature = load_turtle_document_from_str(DOCUMENT)?.signature();
+    assert_eq!(
+      signature.iris(),
+      vec![
+        "http://example.org/Agent",
+        "http://example.org/Entity",
+        "http://example.org/JohnDoe",
+        "http://example.org/definition",
+        "http://example.org/has_age",
+        "http://example.org/has_part",
+        "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString",
+        "http://www.w3.org/2000/01/rdf-schema#label",
+        "http://www.w3.org/2000/01/rdf-schema#seeAlso",
+        "http://www.w3.org/2001/XMLSchema#integer",
+        "http://www.w3.org/2001/XMLSchema#string",
+        "http://www.w3.org/2002/07/owl#Thing",
+      ]
+      .into_iter()
+      .collect()
+    );
+    assert_eq!(
+      signature.of_kind(EntityType::Class),
+      vec![
+        "http://example.org/Agent",
+        "http://example.org/Entity",
+        "http://www.w3.org/2002/07/owl#Thing"
+      ]
+      .into_iter()
+      .collect()
+    );
+    assert_eq!(
+      signature.untyped(),
+      vec![
+        "http://example.org/JohnDoe",
+        "http://example.org/has_age",
+        "http://example.org/has_part"
+      ]
+      .into_iter()
+      .collect()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_extract_signature_of_imports_closure() -> std::io::Result<()> {
+    let directory =
+      std::env::temp_dir().join(format!("semantic_owl_signature_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    let root = directory.join("root.ttl");
+    let upper = directory.join("upper.ttl");
+    std::fs::write(&root, DOCUMENT)?;
+    std::fs::write(
+      &upper,
+      "<http://example.org/upper> a <http://www.w3.org/2002/07/owl#Ontology> .\n<http://example.org/Entity> a <http://www.w3.org/2002/07/owl#Class> .\n<http://example.org/Continuant> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/Entity> .",
+    )?;
+    let mut mapper = SimpleIriMapper::new();
+    mapper.insert("http://example.org/upper", upper.to_str().unwrap());
+    let mut manager = OntologyManager::new();
+    manager.add_iri_mapper(Box::new(mapper));
+    let key = manager.load(root.to_str().unwrap());
+    std::fs::remove_dir_all(&directory)?;
+    let key = key?;
+
+    let excluded = manager.signature(&key, Imports::Excluded);
+    let included = manager.signature(&key, Imports::Included);
+    assert!(!excluded.contains("http://example.org/Continuant"));
+    assert!(included.contains("http://example.org/Continuant"));
+    assert_eq!(included.len(), excluded.len() + 1);
+    assert!(manager
+      .signature("http://example.org/unknown", Imports::Included)
+      .is_empty());
+    Ok(())
+  }
+}
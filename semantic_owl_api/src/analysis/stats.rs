@@ -0,0 +1,233 @@
+//! Stats module profiles the content of a loaded turtle document
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::{owl, rdf, rdfs};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_CLASS: &str = rdfs::CLASS.as_str();
+const OWL_CLASS: &str = owl::CLASS.as_str();
+const OWL_OBJECT_PROPERTY: &str = owl::OBJECT_PROPERTY.as_str();
+const OWL_DATATYPE_PROPERTY: &str = owl::DATATYPE_PROPERTY.as_str();
+const OWL_ANNOTATION_PROPERTY: &str = owl::ANNOTATION_PROPERTY.as_str();
+const OWL_NAMED_INDIVIDUAL: &str = owl::NAMED_INDIVIDUAL.as_str();
+
+// axiom types which are identified by their predicate alone
+const AXIOM_PREDICATES: [(&str, &str); 14] = [
+  (rdfs::SUB_CLASS_OF.as_str(), "SubClassOf"),
+  (rdfs::SUB_PROPERTY_OF.as_str(), "SubPropertyOf"),
+  (rdfs::DOMAIN.as_str(), "PropertyDomain"),
+  (rdfs::RANGE.as_str(), "PropertyRange"),
+  (owl::EQUIVALENT_CLASS.as_str(), "EquivalentClasses"),
+  (owl::EQUIVALENT_PROPERTY.as_str(), "EquivalentProperties"),
+  (owl::DISJOINT_WITH.as_str(), "DisjointClasses"),
+  (owl::INVERSE_OF.as_str(), "InverseObjectProperties"),
+  (owl::PROPERTY_CHAIN_AXIOM.as_str(), "SubPropertyChainOf"),
+  (owl::DISJOINT_UNION_OF.as_str(), "DisjointUnion"),
+  (owl::SAME_AS.as_str(), "SameIndividual"),
+  (owl::DIFFERENT_FROM.as_str(), "DifferentIndividuals"),
+  (owl::HAS_KEY.as_str(), "HasKey"),
+  (owl::IMPORTS.as_str(), "Import"),
+];
+
+/// DocumentStats is a profile of the content of a document. It is serializable as JSON
+/// which makes it suitable for dashboards and for comparing releases of an ontology
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DocumentStats {
+  // number of resolved triples
+  pub triples: usize,
+
+  // number of distinct subjects, blank nodes included
+  pub subjects: usize,
+
+  // number of entities declared by their type. Example -> cco:Agent rdf:type owl:Class
+  pub classes: usize,
+  pub object_properties: usize,
+  pub data_properties: usize,
+  pub annotation_properties: usize,
+  pub individuals: usize,
+
+  // number of blank nodes, e.g restrictions and axiom annotations
+  pub blank_nodes: usize,
+
+  // number of literal objects
+  pub literals: usize,
+
+  // number of axioms per axiom type. Example -> SubClassOf: 120
+  pub axiom_types: BTreeMap<String, usize>,
+
+  // number of literals per language tag. Literals without a language tag are not counted
+  pub literals_per_language: BTreeMap<String, usize>,
+
+  // number of IRIs, in any position, which belong to the namespace of a prefix declared by the document
+  pub prefix_usage: BTreeMap<String, usize>,
+}
+
+impl TurtleDocument {
+  /// stats returns counts of the triples, entities, axiom types, literals and prefix usage of the document
+  pub fn stats(&self) -> DocumentStats {
+    let map = self.prefix_map();
+    let triples = self.triples();
+    let mut stats = DocumentStats {
+      triples: triples.len(),
+      ..DocumentStats::default()
+    };
+
+    // entities by their declared type
+    let mut declared: BTreeMap<&str, BTreeSet<&TurtleTerm>> = BTreeMap::new();
+    for t in triples.iter() {
+      if t.predicate.as_iri() == Some(RDF_TYPE) && !t.subject.is_blank_node() {
+        if let Some(kind) = t.object.as_iri() {
+          declared.entry(kind).or_default().insert(&t.subject);
+        }
+      }
+    }
+    let count = |kinds: &[&str]| -> usize {
+      let mut entities: BTreeSet<&TurtleTerm> = BTreeSet::new();
+      for k in kinds {
+        if let Some(x) = declared.get(k) {
+          entities.extend(x.iter());
+        }
+      }
+      entities.len()
+    };
+    stats.classes = count(&[OWL_CLASS, RDFS_CLASS]);
+    stats.object_properties = count(&[OWL_OBJECT_PROPERTY]);
+    stats.data_properties = count(&[OWL_DATATYPE_PROPERTY]);
+    stats.annotation_properties = count(&[OWL_ANNOTATION_PROPERTY]);
+    stats.individuals = count(&[OWL_NAMED_INDIVIDUAL]);
+    let has_type = |term: &TurtleTerm, kind: &str| -> bool {
+      declared
+        .get(kind)
+        .map(|x| x.contains(term))
+        .unwrap_or(false)
+    };
+
+    let mut subjects: BTreeSet<&TurtleTerm> = BTreeSet::new();
+    let mut blank_nodes: BTreeSet<&TurtleTerm> = BTreeSet::new();
+    for t in triples.iter() {
+      subjects.insert(&t.subject);
+      for term in [&t.subject, &t.object] {
+        if term.is_blank_node() {
+          blank_nodes.insert(term);
+        }
+      }
+
+      // axiom types
+      let predicate = t.predicate.as_iri().unwrap_or_default();
+      let axiom = match AXIOM_PREDICATES.iter().find(|(p, _)| *p == predicate) {
+        Some((_, axiom)) => Some(*axiom),
+        None if predicate == RDF_TYPE => match t.object.as_iri() {
+          _ if t.subject.is_blank_node() => None,
+          Some(x) if x.starts_with(owl::NAMESPACE) => Some("Declaration"),
+          Some(x) if x.starts_with(rdfs::NAMESPACE) => Some("Declaration"),
+          _ => Some("ClassAssertion"),
+        },
+        None if t.subject.is_blank_node() => None,
+        None if has_type(&t.predicate, OWL_OBJECT_PROPERTY) => Some("ObjectPropertyAssertion"),
+        None if has_type(&t.predicate, OWL_DATATYPE_PROPERTY) => Some("DataPropertyAssertion"),
+        None if has_type(&t.predicate, OWL_ANNOTATION_PROPERTY) || t.object.is_literal() => {
+          Some("AnnotationAssertion")
+        }
+        None => None,
+      };
+      if let Some(axiom) = axiom {
+        *stats.axiom_types.entry(String::from(axiom)).or_default() += 1;
+      }
+
+      // literals
+      if let TurtleTerm::Literal { language, .. } = &t.object {
+        stats.literals += 1;
+        if let Some(l) = language {
+          *stats
+            .literals_per_language
+            .entry(l.to_lowercase())
+            .or_default() += 1;
+        }
+      }
+
+      // prefix usage
+      let datatype = match &t.object {
+        TurtleTerm::Literal { datatype, .. } => Some(datatype.as_str()),
+        _ => None,
+      };
+      let iris = [
+        t.subject.as_iri(),
+        t.predicate.as_iri(),
+        t.object.as_iri(),
+        datatype,
+      ];
+      for iri in iris.iter().flatten() {
+        if let Some(curie) = map.shorten(iri) {
+          let prefix = curie.split(':').next().unwrap_or_default();
+          *stats.prefix_usage.entry(String::from(prefix)).or_default() += 1;
+        }
+      }
+    }
+    stats.subjects = subjects.len();
+    stats.blank_nodes = blank_nodes.len();
+    stats
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+cco:definition rdf:type owl:AnnotationProperty .
+cco:has_part rdf:type owl:ObjectProperty .
+cco:has_value rdf:type owl:DatatypeProperty .
+cco:Agent rdf:type owl:Class ;
+          rdfs:label "Agent"@en , "Agent"@fr ;
+          rdfs:subClassOf [ rdf:type owl:Restriction ;
+                            owl:onProperty cco:has_part ;
+                            owl:someValuesFrom cco:Agent ] .
+cco:Person rdf:type owl:Class ;
+           rdfs:subClassOf cco:Agent ;
+           cco:definition "A person"@EN .
+cco:Bob rdf:type owl:NamedIndividual , cco:Person ;
+        cco:has_value "42"^^xsd:integer .
+"#;
+
+  #[test]
+  fn should_compute_document_stats() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let stats = document.stats();
+    assert_eq!(stats.triples, 16);
+    assert_eq!(stats.classes, 2);
+    assert_eq!(stats.object_properties, 1);
+    assert_eq!(stats.data_properties, 1);
+    assert_eq!(stats.annotation_properties, 1);
+    assert_eq!(stats.individuals, 1);
+    assert_eq!(stats.blank_nodes, 1);
+    assert_eq!(stats.literals, 4);
+    assert_eq!(stats.axiom_types.get("SubClassOf"), Some(&2));
+    assert_eq!(stats.axiom_types.get("Declaration"), Some(&6));
+    assert_eq!(stats.axiom_types.get("ClassAssertion"), Some(&1));
+    assert_eq!(stats.axiom_types.get("AnnotationAssertion"), Some(&3));
+    assert_eq!(stats.axiom_types.get("DataPropertyAssertion"), Some(&1));
+    assert_eq!(stats.literals_per_language.get("en"), Some(&2));
+    assert_eq!(stats.literals_per_language.get("fr"), Some(&1));
+    assert_eq!(stats.prefix_usage.get("xsd"), Some(&1));
+    assert_eq!(stats.prefix_usage.get("cco"), Some(&19));
+    Ok(())
+  }
+
+  #[test]
+  fn should_serialize_stats_as_json() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let stats = document.stats();
+    let json = serde_json::to_string(&stats)?;
+    let parsed: DocumentStats = serde_json::from_str(&json)?;
+    assert_eq!(parsed, stats);
+    Ok(())
+  }
+}
@@ -0,0 +1,359 @@
+//! Validation module checks a document against the restrictions of OWL 2 DL
+use crate::declarations::provenance::SourceSpan;
+use crate::declarations::std_prefix_names::StdPrefixes;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::loader::parsers::ttl_pname_parser::is_prefixed_name;
+use crate::loader::parsers::ttl_statement_parser::split_literal;
+use crate::vocab::{owl, rdf, rdfs};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+// entity types implied by a declared type. Property characteristics other than
+// `owl:FunctionalProperty` only apply to object properties
+pub(crate) const ENTITY_TYPES: [(&str, EntityType); 12] = [
+  (owl::CLASS.as_str(), EntityType::Class),
+  (rdfs::DATATYPE.as_str(), EntityType::Datatype),
+  (owl::OBJECT_PROPERTY.as_str(), EntityType::ObjectProperty),
+  (
+    owl::TRANSITIVE_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (owl::SYMMETRIC_PROPERTY.as_str(), EntityType::ObjectProperty),
+  (
+    owl::ASYMMETRIC_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (owl::REFLEXIVE_PROPERTY.as_str(), EntityType::ObjectProperty),
+  (
+    owl::IRREFLEXIVE_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (
+    owl::INVERSE_FUNCTIONAL_PROPERTY.as_str(),
+    EntityType::ObjectProperty,
+  ),
+  (owl::DATATYPE_PROPERTY.as_str(), EntityType::DataProperty),
+  (
+    owl::ANNOTATION_PROPERTY.as_str(),
+    EntityType::AnnotationProperty,
+  ),
+  (owl::NAMED_INDIVIDUAL.as_str(), EntityType::NamedIndividual),
+];
+
+// pairs of entity types an IRI cannot have at once (OWL 2 structural specification, section 5.9)
+pub(crate) const ILLEGAL_PUNS: [(EntityType, EntityType); 4] = [
+  (EntityType::Class, EntityType::Datatype),
+  (EntityType::ObjectProperty, EntityType::DataProperty),
+  (EntityType::ObjectProperty, EntityType::AnnotationProperty),
+  (EntityType::DataProperty, EntityType::AnnotationProperty),
+];
+
+pub const ILLEGAL_PUNNING: &str = "IllegalPunning";
+pub const UNUSED_PREFIX: &str = "UnusedPrefix";
+pub const UNDECLARED_PREFIX: &str = "UndeclaredPrefix";
+
+/// EntityType is the kind of entity an IRI is declared as, or used as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EntityType {
+  Class,
+  Datatype,
+  ObjectProperty,
+  DataProperty,
+  AnnotationProperty,
+  NamedIndividual,
+}
+
+impl fmt::Display for EntityType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      EntityType::Class => "class",
+      EntityType::Datatype => "datatype",
+      EntityType::ObjectProperty => "object property",
+      EntityType::DataProperty => "data property",
+      EntityType::AnnotationProperty => "annotation property",
+      EntityType::NamedIndividual => "named individual",
+    };
+    write!(f, "{}", name)
+  }
+}
+
+/// Violation is a breach of a validation rule by an entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+  // name of the broken rule, e.g `IllegalPunning`
+  pub rule: String,
+
+  // IRI of the offending entity
+  pub iri: String,
+
+  pub message: String,
+
+  // statements causing the violation
+  pub statements: Vec<TurtleTriple>,
+}
+
+impl Violation {
+  pub fn new(rule: &str, iri: &str, message: String, statements: Vec<TurtleTriple>) -> Violation {
+    Self {
+      rule: String::from(rule),
+      iri: String::from(iri),
+      message,
+      statements,
+    }
+  }
+}
+
+/// formats the violation as `rule: message`
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.rule, self.message)
+  }
+}
+
+/// PrefixDiagnostic is a prefix declared but never used, or used but never declared. Statements
+/// using an undeclared prefix are parsed, but state no triple
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrefixDiagnostic {
+  // name of the broken rule, `UnusedPrefix` or `UndeclaredPrefix`
+  pub rule: String,
+
+  // e.g -> skos
+  pub prefix: String,
+
+  pub message: String,
+
+  // location of the declaration of an unused prefix, or of the statement using an undeclared one
+  pub span: Option<SourceSpan>,
+
+  // quick fix. e.g -> add `@prefix skos: <http://www.w3.org/2004/02/skos/core#> .`
+  pub suggestion: Option<String>,
+}
+
+impl PrefixDiagnostic {
+  pub fn new(
+    rule: &str,
+    prefix: &str,
+    message: String,
+    span: Option<SourceSpan>,
+    suggestion: Option<String>,
+  ) -> PrefixDiagnostic {
+    Self {
+      rule: String::from(rule),
+      prefix: String::from(prefix),
+      message,
+      span,
+      suggestion,
+    }
+  }
+}
+
+/// formats the diagnostic as `rule: message`
+impl fmt::Display for PrefixDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.rule, self.message)
+  }
+}
+
+impl TurtleDocument {
+  /// prefix_diagnostics reports the prefixes declared but used by no statement, and the prefixed
+  /// names whose prefix is not declared, once per statement. The declaration suggested for an
+  /// undeclared prefix is taken from the registry
+  pub fn prefix_diagnostics(&self, registry: &StdPrefixes) -> Vec<PrefixDiagnostic> {
+    let declared: BTreeSet<String> = self
+      .headers
+      .iter()
+      .filter(|h| !h.is_base)
+      .map(|h| h.prefix_namespace.clone().unwrap_or_default())
+      .collect();
+    let mut used: BTreeSet<String> = BTreeSet::new();
+    let mut diagnostics = vec![];
+    for item in self.body.iter() {
+      let mut terms: Vec<&str> = vec![];
+      terms.extend(item.subject.as_deref());
+      for p in item.predicate.iter() {
+        terms.extend(p.raw_predicate_object.as_deref());
+        terms.extend(p.object.iter().filter_map(|o| o.raw_object.as_deref()));
+      }
+      let mut undeclared: BTreeSet<String> = BTreeSet::new();
+      for term in terms {
+        let prefix = match prefix_of(term) {
+          Some(x) => x,
+          None => continue,
+        };
+        if !declared.contains(&prefix) && undeclared.insert(prefix.clone()) {
+          let message = format!(
+            "`{}` uses the prefix `{}:` which is not declared",
+            term, prefix
+          );
+          let suggestion = registry
+            .namespace(&prefix)
+            .map(|ns| format!("add `@prefix {}: <{}> .`", prefix, ns));
+          diagnostics.push(PrefixDiagnostic::new(
+            UNDECLARED_PREFIX,
+            &prefix,
+            message,
+            item.span.clone(),
+            suggestion,
+          ));
+        }
+        used.insert(prefix);
+      }
+    }
+
+    let mut unused = vec![];
+    for h in self.headers.iter().filter(|h| !h.is_base) {
+      let prefix = h.prefix_namespace.clone().unwrap_or_default();
+      if used.contains(&prefix) {
+        continue;
+      }
+      let message = format!("the prefix `{}:` is declared but never used", prefix);
+      let suggestion = h
+        .raw_header
+        .as_deref()
+        .map(|x| format!("remove `{}`", x.trim()));
+      unused.push(PrefixDiagnostic::new(
+        UNUSED_PREFIX,
+        &prefix,
+        message,
+        h.span.clone(),
+        suggestion,
+      ));
+    }
+    unused.append(&mut diagnostics);
+    unused
+  }
+
+  /// punning_violations reports the IRIs declared as two entity types which OWL 2 DL does not allow
+  /// to share an IRI: class and datatype, or two different kinds of property
+  pub fn punning_violations(&self) -> Vec<Violation> {
+    let mut declarations: BTreeMap<String, Vec<(EntityType, TurtleTriple)>> = BTreeMap::new();
+    for t in self.triples() {
+      if t.predicate.as_iri() != Some(RDF_TYPE) {
+        continue;
+      }
+      let (iri, declared) = match (&t.subject, t.object.as_iri()) {
+        (TurtleTerm::Iri(s), Some(o)) => (s.clone(), o),
+        _ => continue,
+      };
+      if let Some((_, entity_type)) = ENTITY_TYPES.iter().find(|(x, _)| *x == declared) {
+        declarations.entry(iri).or_default().push((*entity_type, t));
+      }
+    }
+    let mut violations = vec![];
+    for (iri, declared) in declarations.iter() {
+      for (a, b) in ILLEGAL_PUNS.iter() {
+        let statements: Vec<TurtleTriple> = declared
+          .iter()
+          .filter(|(x, _)| x == a || x == b)
+          .map(|(_, t)| t.clone())
+          .collect();
+        let has = |x: &EntityType| declared.iter().any(|(y, _)| y == x);
+        if has(a) && has(b) {
+          let message = format!("<{}> is declared both as {} and as {}", iri, a, b);
+          violations.push(Violation::new(ILLEGAL_PUNNING, iri, message, statements));
+        }
+      }
+    }
+    violations
+  }
+}
+
+// prefix_of returns the prefix of a prefixed name, or of the datatype of a literal
+fn prefix_of(term: &str) -> Option<String> {
+  let name = match split_literal(term) {
+    Some((_, datatype, _)) => datatype?,
+    None => String::from(term),
+  };
+  match is_prefixed_name(&name) {
+    true => name.find(':').map(|idx| String::from(&name[..idx])),
+    false => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Temperature a owl:Class , rdfs:Datatype .
+ex:hasPart a owl:TransitiveProperty .
+ex:hasPart a owl:DatatypeProperty , owl:FunctionalProperty .
+ex:Agent a owl:Class , owl:NamedIndividual .
+ex:label a owl:AnnotationProperty , owl:FunctionalProperty .
+"#;
+
+  #[test]
+  fn should_report_illegal_punning() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let violations = document.punning_violations();
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0].iri, "http://example.org/Temperature");
+    assert_eq!(
+      violations[0].to_string(),
+      "IllegalPunning: <http://example.org/Temperature> is declared both as class and as datatype"
+    );
+    assert_eq!(violations[0].statements.len(), 2);
+    assert_eq!(violations[1].iri, "http://example.org/hasPart");
+    assert_eq!(
+      violations[1].message,
+      "<http://example.org/hasPart> is declared both as object property and as data property"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_report_unused_and_undeclared_prefixes() -> std::io::Result<()> {
+    let content = r#"@prefix ex: <http://example.org/> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix dc: <http://purl.org/dc/elements/1.1/> .
+
+ex:Agent ex:age "42"^^xsd:integer .
+ex:Person skos:prefLabel "person" ;
+  skos:altLabel "human" ;
+  foo:bar ex:Agent .
+"#;
+    let document = load_turtle_document_from_str(content)?;
+    let diagnostics = document.prefix_diagnostics(&StdPrefixes::default());
+    let found: Vec<(&str, &str)> = diagnostics
+      .iter()
+      .map(|x| (x.rule.as_str(), x.prefix.as_str()))
+      .collect();
+    assert_eq!(
+      found,
+      vec![
+        (UNUSED_PREFIX, "rdfs"),
+        (UNUSED_PREFIX, "dc"),
+        (UNDECLARED_PREFIX, "skos"),
+        (UNDECLARED_PREFIX, "foo")
+      ]
+    );
+    assert_eq!(diagnostics[1].span.as_ref().map(|x| x.lines.start), Some(4));
+    assert_eq!(
+      diagnostics[1].suggestion.as_deref(),
+      Some("remove `@prefix dc: <http://purl.org/dc/elements/1.1/> .`")
+    );
+    assert_eq!(
+      diagnostics[2].to_string(),
+      "UndeclaredPrefix: `skos:prefLabel` uses the prefix `skos:` which is not declared"
+    );
+    assert_eq!(
+      diagnostics[2].span.as_ref().map(|x| x.lines.clone()),
+      Some(7..10)
+    );
+    assert_eq!(
+      diagnostics[2].suggestion.as_deref(),
+      Some("add `@prefix skos: <http://www.w3.org/2004/02/skos/core#> .`")
+    );
+    assert_eq!(diagnostics[3].suggestion, None);
+    Ok(())
+  }
+}
@@ -0,0 +1,190 @@
+//! Validator module runs a set of validation rules over a document
+use crate::analysis::validation::{EntityType, Violation, ILLEGAL_PUNNING};
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{TurtleDocument, TurtleTriple};
+
+/// HIERARCHY_CYCLE is the name of the built-in rule reporting `CyclicSubClassOf` and
+/// `CyclicSubPropertyOf` violations
+pub const HIERARCHY_CYCLE: &str = "HierarchyCycle";
+
+/// ValidationRule is a check of a document. Rules are shared by the threads validating documents
+pub trait ValidationRule: Send + Sync {
+  /// name returns the name of the rule, which its violations are reported with.
+  /// example
+  /// `IllegalPunning`
+  fn name(&self) -> &str;
+
+  /// check returns the violations of the rule by the document of the context
+  fn check(&self, context: &ValidationContext) -> Vec<Violation>;
+
+  /// violation creates a violation of the rule by an entity, formatted as the violations of the
+  /// rules of the crate
+  fn violation(&self, iri: &str, message: String, statements: Vec<TurtleTriple>) -> Violation {
+    Violation::new(self.name(), iri, message, statements)
+  }
+}
+
+/// ValidationContext is the document being validated, with its triples resolved once for every rule
+pub struct ValidationContext<'a> {
+  pub document: &'a TurtleDocument,
+
+  // triples of the document, in document order
+  pub triples: Vec<TurtleTriple>,
+
+  // the same triples, indexed for lookups
+  pub graph: TripleGraph,
+}
+
+impl<'a> ValidationContext<'a> {
+  pub fn new(document: &'a TurtleDocument) -> ValidationContext<'a> {
+    let triples = document.triples();
+    let graph = triples.iter().cloned().collect();
+    Self {
+      document,
+      triples,
+      graph,
+    }
+  }
+
+  /// classes returns the IRIs of the classes of the document, declared or used as such
+  pub fn classes(&self) -> impl Iterator<Item = String> + '_ {
+    self.document.entities_of(EntityType::Class)
+  }
+}
+
+/// Validator runs rules in the order they are registered
+#[derive(Default)]
+pub struct Validator {
+  rules: Vec<Box<dyn ValidationRule>>,
+}
+
+impl Validator {
+  /// new creates a validator without any rule
+  pub fn new() -> Validator {
+    Self::default()
+  }
+
+  /// with_builtin_rules creates a validator running the rules of the crate: illegal punning and
+  /// cycles of the class and property hierarchies
+  pub fn with_builtin_rules() -> Validator {
+    let mut validator = Self::new();
+    validator.register(Box::new(PunningRule));
+    validator.register(Box::new(HierarchyCycleRule));
+    validator
+  }
+
+  /// register adds a rule run after the ones already registered
+  pub fn register(&mut self, rule: Box<dyn ValidationRule>) {
+    self.rules.push(rule);
+  }
+
+  /// rules returns the names of the rules registered
+  pub fn rules(&self) -> Vec<&str> {
+    self.rules.iter().map(|x| x.name()).collect()
+  }
+
+  /// validate runs every rule over a document and returns their violations, rule after rule.
+  /// A violation without a rule name is given the name of the rule reporting it
+  pub fn validate(&self, document: &TurtleDocument) -> Vec<Violation> {
+    let context = ValidationContext::new(document);
+    let mut violations = vec![];
+    for rule in self.rules.iter() {
+      for mut v in rule.check(&context) {
+        if v.rule.is_empty() {
+          v.rule = String::from(rule.name());
+        }
+        violations.push(v);
+      }
+    }
+    violations
+  }
+}
+
+// PunningRule reports the IRIs declared as two entity types OWL 2 DL does not allow to share an IRI
+struct PunningRule;
+
+impl ValidationRule for PunningRule {
+  fn name(&self) -> &str {
+    ILLEGAL_PUNNING
+  }
+
+  fn check(&self, context: &ValidationContext) -> Vec<Violation> {
+    context.document.punning_violations()
+  }
+}
+
+// HierarchyCycleRule reports the cycles of the `rdfs:subClassOf` and `rdfs:subPropertyOf`
+// hierarchies, named after the hierarchy
+struct HierarchyCycleRule;
+
+impl ValidationRule for HierarchyCycleRule {
+  fn name(&self) -> &str {
+    HIERARCHY_CYCLE
+  }
+
+  fn check(&self, context: &ValidationContext) -> Vec<Violation> {
+    context.document.hierarchy_cycles()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::analysis::cycles::CYCLIC_SUB_CLASS_OF;
+  use crate::declarations::turtle::TurtleTerm;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::vocab::rdfs;
+
+  const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/> .
+
+:Agent a owl:Class ; rdfs:label "Agent" ; rdfs:subClassOf :Person .
+:Person a owl:Class ; rdfs:subClassOf :Agent .
+:name a owl:ObjectProperty , owl:DatatypeProperty .
+"#;
+
+  struct RequiresLabel;
+
+  impl ValidationRule for RequiresLabel {
+    fn name(&self) -> &str {
+      "RequiresLabel"
+    }
+
+    fn check(&self, context: &ValidationContext) -> Vec<Violation> {
+      context
+        .classes()
+        .filter(|x| {
+          let subject = TurtleTerm::Iri(x.clone());
+          context.graph.object(&subject, RDFS_LABEL).is_none()
+        })
+        .map(|x| self.violation(&x, format!("<{}> has no label", x), vec![]))
+        .collect()
+    }
+  }
+
+  #[test]
+  fn should_run_registered_rules() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let mut validator = Validator::with_builtin_rules();
+    validator.register(Box::new(RequiresLabel));
+    assert_eq!(
+      validator.rules(),
+      vec![ILLEGAL_PUNNING, HIERARCHY_CYCLE, "RequiresLabel"]
+    );
+    let violations = validator.validate(&document);
+    let rules: Vec<&str> = violations.iter().map(|v| v.rule.as_str()).collect();
+    assert_eq!(
+      rules,
+      vec![ILLEGAL_PUNNING, CYCLIC_SUB_CLASS_OF, "RequiresLabel"]
+    );
+    assert_eq!(
+      violations[2].to_string(),
+      "RequiresLabel: <http://example.org/Person> has no label"
+    );
+    assert!(Validator::new().validate(&document).is_empty());
+    Ok(())
+  }
+}
@@ -0,0 +1,251 @@
+//! Axiom annotation module reads and writes the annotations of axioms
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::vocab::{owl, rdf};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const OWL_AXIOM: &str = owl::AXIOM.as_str();
+const OWL_ANNOTATED_SOURCE: &str = owl::ANNOTATED_SOURCE.as_str();
+const OWL_ANNOTATED_PROPERTY: &str = owl::ANNOTATED_PROPERTY.as_str();
+const OWL_ANNOTATED_TARGET: &str = owl::ANNOTATED_TARGET.as_str();
+
+// prefix of the labels of the blank nodes reifying axioms written by `annotate`
+const AXIOM_NODE_PREFIX: &str = "axiom";
+
+/// Annotation is the value of an annotation property, e.g the source of a definition
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Annotation {
+  // e.g -> http://www.ontologyrepository.com/CommonCoreOntologies/definition_source
+  pub property: String,
+
+  pub value: TurtleTerm,
+}
+
+impl Annotation {
+  pub fn new(property: &str, value: TurtleTerm) -> Annotation {
+    Self {
+      property: String::from(property),
+      value,
+    }
+  }
+}
+
+/// AnnotatedTriple is a triple along with the annotations of its `owl:Axiom` node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotatedTriple {
+  pub triple: TurtleTriple,
+  pub annotations: Vec<Annotation>,
+}
+
+impl AnnotatedTriple {
+  pub fn new(triple: TurtleTriple, annotations: Vec<Annotation>) -> AnnotatedTriple {
+    Self {
+      triple,
+      annotations,
+    }
+  }
+}
+
+impl TurtleDocument {
+  /// annotated_triples returns the triples annotated by the `owl:Axiom` nodes of the document, in
+  /// document order. Nodes missing their source, property or target are ignored
+  pub fn annotated_triples(&self) -> Vec<AnnotatedTriple> {
+    let triples = self.triples();
+    let graph: TripleGraph = triples.iter().cloned().collect();
+    let nodes = triples
+      .iter()
+      .filter(|t| t.predicate.as_iri() == Some(RDF_TYPE) && t.object.as_iri() == Some(OWL_AXIOM))
+      .map(|t| &t.subject);
+    let mut annotated = vec![];
+    for node in nodes {
+      let (source, property, target) = match (
+        graph.object(node, OWL_ANNOTATED_SOURCE),
+        graph.object(node, OWL_ANNOTATED_PROPERTY),
+        graph.object(node, OWL_ANNOTATED_TARGET),
+      ) {
+        (Some(s), Some(p @ TurtleTerm::Iri(_)), Some(o)) => (s, p, o),
+        _ => continue,
+      };
+      let annotations = graph
+        .triples_matching(Some(node), None, None)
+        .filter_map(|t| match t.predicate.as_iri() {
+          Some(RDF_TYPE)
+          | Some(OWL_ANNOTATED_SOURCE)
+          | Some(OWL_ANNOTATED_PROPERTY)
+          | Some(OWL_ANNOTATED_TARGET) => None,
+          Some(p) => Some(Annotation::new(p, t.object.clone())),
+          None => None,
+        })
+        .collect();
+      let triple = TurtleTriple::new(source.clone(), property.clone(), target.clone());
+      annotated.push(AnnotatedTriple::new(triple, annotations));
+    }
+    annotated
+  }
+
+  /// axiom_annotations_of returns the annotations of a triple, gathered from all of its `owl:Axiom` nodes
+  pub fn axiom_annotations_of(&self, triple: &TurtleTriple) -> Vec<Annotation> {
+    self
+      .annotated_triples()
+      .into_iter()
+      .filter(|x| &x.triple == triple)
+      .flat_map(|x| x.annotations)
+      .collect()
+  }
+
+  /// annotate writes an `owl:Axiom` node annotating a triple. The triple itself is not asserted
+  /// by the node and is usually stated elsewhere in the document
+  pub fn annotate(&mut self, triple: &TurtleTriple, annotations: &[Annotation]) {
+    self.annotate_all(&[AnnotatedTriple::new(triple.clone(), annotations.to_vec())]);
+  }
+
+  /// annotate_all writes an `owl:Axiom` node for each annotated triple, like `annotate` does for
+  /// one. The document is read once, whatever the number of triples
+  pub fn annotate_all(&mut self, annotated: &[AnnotatedTriple]) {
+    let labels: BTreeSet<String> = self
+      .triples()
+      .into_iter()
+      .flat_map(|t| vec![t.subject, t.object])
+      .filter_map(|x| match x {
+        TurtleTerm::BlankNode(label) => Some(label),
+        _ => None,
+      })
+      .collect();
+    let mut nodes = (0..)
+      .map(|n| format!("{}{}", AXIOM_NODE_PREFIX, n))
+      .filter(|x| !labels.contains(x));
+
+    for AnnotatedTriple {
+      triple,
+      annotations,
+    } in annotated.iter()
+    {
+      let node = nodes.next().unwrap_or_default();
+      let mut predicates = vec![
+        (
+          String::from(RDF_TYPE),
+          TurtleTerm::Iri(String::from(OWL_AXIOM)),
+        ),
+        (String::from(OWL_ANNOTATED_SOURCE), triple.subject.clone()),
+        (
+          String::from(OWL_ANNOTATED_PROPERTY),
+          triple.predicate.clone(),
+        ),
+        (String::from(OWL_ANNOTATED_TARGET), triple.object.clone()),
+      ];
+      for a in annotations.iter() {
+        predicates.push((a.property.clone(), a.value.clone()));
+      }
+      let mut item = TurtleBodyItem {
+        subject: Some(format!("_:{}", node)),
+        predicate: VecDeque::new(),
+        span: None,
+      };
+      for (property, value) in predicates {
+        let mut predicate = TurtlePredicate::from_raw(&format!("<{}>", property));
+        predicate
+          .object
+          .push_back(TurtleObject::from_raw(&value.to_string()));
+        item.predicate.push_back(predicate);
+      }
+      self.body.push_back(item);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::writer::format::{format_turtle, Style};
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ;
+  rdfs:subClassOf obo:BFO_0000040 ;
+  cco:definition "A Material Entity that is capable of performing Planned Acts"@en .
+
+[ a owl:Axiom ;
+  owl:annotatedSource cco:Agent ;
+  owl:annotatedProperty cco:definition ;
+  owl:annotatedTarget "A Material Entity that is capable of performing Planned Acts"@en ;
+  cco:definition_source "https://www.merriam-webster.com/dictionary/agent" ;
+  rdfs:comment "adapted" ] .
+[ a owl:Axiom ; owl:annotatedSource cco:Agent ; owl:annotatedProperty rdfs:subClassOf ] .
+"#;
+
+  fn iri(x: &str) -> TurtleTerm {
+    TurtleTerm::Iri(String::from(x))
+  }
+
+  #[test]
+  fn should_read_axiom_annotations() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let annotated = document.annotated_triples();
+    // the node without a target is ignored
+    assert_eq!(annotated.len(), 1);
+    let triple = &annotated[0].triple;
+    assert_eq!(
+      triple.predicate,
+      iri("http://www.ontologyrepository.com/CommonCoreOntologies/definition")
+    );
+    assert_eq!(
+      annotated[0].annotations,
+      vec![
+        Annotation::new(
+          "http://www.ontologyrepository.com/CommonCoreOntologies/definition_source",
+          TurtleTerm::Literal {
+            value: String::from("https://www.merriam-webster.com/dictionary/agent"),
+            datatype: String::from("http://www.w3.org/2001/XMLSchema#string"),
+            language: None,
+          }
+        ),
+        Annotation::new(
+          "http://www.w3.org/2000/01/rdf-schema#comment",
+          TurtleTerm::Literal {
+            value: String::from("adapted"),
+            datatype: String::from("http://www.w3.org/2001/XMLSchema#string"),
+            language: None,
+          }
+        ),
+      ]
+    );
+    assert_eq!(document.axiom_annotations_of(triple).len(), 2);
+    Ok(())
+  }
+
+  #[test]
+  fn should_write_axiom_annotations() -> std::io::Result<()> {
+    let mut document = load_turtle_document_from_str(DOCUMENT)?;
+    let triple = TurtleTriple::new(
+      iri("http://www.ontologyrepository.com/CommonCoreOntologies/Agent"),
+      iri("http://www.w3.org/2000/01/rdf-schema#subClassOf"),
+      iri("http://purl.obolibrary.org/obo/BFO_0000040"),
+    );
+    let annotation = Annotation::new(
+      "http://www.w3.org/2000/01/rdf-schema#seeAlso",
+      iri("http://example.org/agent"),
+    );
+    let annotations = vec![annotation];
+    document.annotate(&triple, &annotations);
+    document.annotate(&triple, &annotations);
+    assert_eq!(document.annotated_triples().len(), 3);
+    assert_eq!(document.axiom_annotations_of(&triple).len(), 2);
+
+    // annotations survive a round trip through the writer, which may reorder statements
+    let formatted = format_turtle(&document, &Style::protege());
+    let reloaded = load_turtle_document_from_str(&formatted)?.annotated_triples();
+    let annotated = document.annotated_triples();
+    assert_eq!(reloaded.len(), annotated.len());
+    assert!(annotated.iter().all(|x| reloaded.contains(x)));
+    Ok(())
+  }
+}
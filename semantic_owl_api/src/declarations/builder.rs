@@ -0,0 +1,306 @@
+//! Builder module constructs ontologies programmatically
+use crate::declarations::std_prefix_names::{
+  get_owl_prefix, get_rdf_prefix, get_rdfs_prefix, get_xsd_prefix, OwlStdPrefix,
+};
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleHeaderItem, TurtleObject, TurtlePredicate, TurtleTerm,
+};
+use crate::vocab::{rdf, xsd};
+use std::collections::VecDeque;
+
+const RDF_LANG_STRING: &str = rdf::LANG_STRING.as_str();
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+/// OntologyBuilder accumulates the statements of an ontology. Statements which precede the first
+/// entity apply to the ontology itself
+#[derive(Debug, Clone)]
+pub struct OntologyBuilder {
+  document: TurtleDocument,
+}
+
+impl OntologyBuilder {
+  /// new starts an ontology, given as a full IRI, with the `rdf`, `rdfs`, `owl` and `xsd` prefixes declared
+  pub fn new(iri: &str) -> OntologyBuilder {
+    let mut builder = Self {
+      document: TurtleDocument::new(),
+    };
+    for std_prefix in [
+      get_rdf_prefix(),
+      get_rdfs_prefix(),
+      get_owl_prefix(),
+      get_xsd_prefix(),
+    ] {
+      let p = match std_prefix {
+        OwlStdPrefix::Rdf(p)
+        | OwlStdPrefix::Rdfs(p)
+        | OwlStdPrefix::Xsd(p)
+        | OwlStdPrefix::Owl(p) => p,
+      };
+      builder = builder.prefix(
+        p.prefix_name.trim_end_matches(':'),
+        &p.prefix_iri[1..p.prefix_iri.len() - 1],
+      );
+    }
+    builder.subject(iri, "owl:Ontology")
+  }
+
+  /// prefix declares, or redeclares, the namespace of a prefix
+  /// example
+  /// `prefix("cco", "http://www.ontologyrepository.com/CommonCoreOntologies/")`
+  pub fn prefix(mut self, prefix: &str, namespace: &str) -> Self {
+    let raw = format!("@prefix {}: <{}> .", prefix, namespace);
+    let header = TurtleHeaderItem::new(
+      false,
+      prefix.is_empty(),
+      Some(String::from(prefix)),
+      Some(format!("<{}>", namespace)),
+      Some(raw),
+    );
+    let headers = &mut self.document.headers;
+    match headers
+      .iter_mut()
+      .find(|h| !h.is_base && h.prefix_namespace.as_deref() == Some(prefix))
+    {
+      Some(h) => *h = header,
+      None => headers.push_back(header),
+    }
+    self
+  }
+
+  /// class starts the statement of an `owl:Class`
+  pub fn class(self, iri: &str) -> Self {
+    self.subject(iri, "owl:Class")
+  }
+
+  /// object_property starts the statement of an `owl:ObjectProperty`
+  pub fn object_property(self, iri: &str) -> Self {
+    self.subject(iri, "owl:ObjectProperty")
+  }
+
+  /// data_property starts the statement of an `owl:DatatypeProperty`
+  pub fn data_property(self, iri: &str) -> Self {
+    self.subject(iri, "owl:DatatypeProperty")
+  }
+
+  /// annotation_property starts the statement of an `owl:AnnotationProperty`
+  pub fn annotation_property(self, iri: &str) -> Self {
+    self.subject(iri, "owl:AnnotationProperty")
+  }
+
+  /// named_individual starts the statement of an `owl:NamedIndividual`
+  pub fn named_individual(self, iri: &str) -> Self {
+    self.subject(iri, "owl:NamedIndividual")
+  }
+
+  /// individual starts the statement of an `owl:NamedIndividual` of a class
+  pub fn individual(self, iri: &str, class: &str) -> Self {
+    self.named_individual(iri).fact("rdf:type", class)
+  }
+
+  /// label adds an untagged `rdfs:label`
+  pub fn label(self, value: &str) -> Self {
+    self.annotation("rdfs:label", value, None)
+  }
+
+  /// label_lang adds an `rdfs:label` in a language
+  /// example
+  /// `label_lang("Agent", "en")` adds `rdfs:label "Agent"@en`
+  pub fn label_lang(self, value: &str, language: &str) -> Self {
+    self.annotation("rdfs:label", value, Some(language))
+  }
+
+  /// comment adds an untagged `rdfs:comment`
+  pub fn comment(self, value: &str) -> Self {
+    self.annotation("rdfs:comment", value, None)
+  }
+
+  /// annotation adds a string literal, with an optional language tag, as the value of a property
+  pub fn annotation(self, property: &str, value: &str, language: Option<&str>) -> Self {
+    let datatype = match language {
+      Some(_) => RDF_LANG_STRING,
+      None => XSD_STRING,
+    };
+    let literal = TurtleTerm::Literal {
+      value: String::from(value),
+      datatype: String::from(datatype),
+      language: language.map(String::from),
+    };
+    self.add(property, literal.to_string())
+  }
+
+  /// literal adds a typed literal as the value of a property
+  /// example
+  /// `literal("cco:has_integer_value", "42", "xsd:integer")` adds `cco:has_integer_value "42"^^xsd:integer`
+  pub fn literal(self, property: &str, value: &str, datatype: &str) -> Self {
+    let literal = TurtleTerm::Literal {
+      value: String::from(value),
+      datatype: String::from(XSD_STRING),
+      language: None,
+    };
+    let raw = format!("{}^^{}", literal, raw_resource(datatype));
+    self.add(property, raw)
+  }
+
+  /// subclass_of adds an `rdfs:subClassOf` axiom to the current class
+  pub fn subclass_of(self, class: &str) -> Self {
+    self.fact("rdfs:subClassOf", class)
+  }
+
+  /// equivalent_to adds an `owl:equivalentClass` axiom to the current class
+  pub fn equivalent_to(self, class: &str) -> Self {
+    self.fact("owl:equivalentClass", class)
+  }
+
+  /// disjoint_with adds an `owl:disjointWith` axiom to the current class
+  pub fn disjoint_with(self, class: &str) -> Self {
+    self.fact("owl:disjointWith", class)
+  }
+
+  /// sub_property_of adds an `rdfs:subPropertyOf` axiom to the current property
+  pub fn sub_property_of(self, property: &str) -> Self {
+    self.fact("rdfs:subPropertyOf", property)
+  }
+
+  /// domain adds an `rdfs:domain` axiom to the current property
+  pub fn domain(self, class: &str) -> Self {
+    self.fact("rdfs:domain", class)
+  }
+
+  /// range adds an `rdfs:range` axiom to the current property
+  pub fn range(self, class: &str) -> Self {
+    self.fact("rdfs:range", class)
+  }
+
+  /// fact adds a resource as the value of a property
+  /// example
+  /// `fact("cco:is_part_of", "cco:Organization")`
+  pub fn fact(self, property: &str, object: &str) -> Self {
+    let raw = raw_resource(object);
+    self.add(property, raw)
+  }
+
+  /// build returns the document of the ontology. Terms with an undeclared prefix are reported as
+  /// `std::io::ErrorKind::InvalidData`
+  pub fn build(self) -> std::io::Result<TurtleDocument> {
+    let map = self.document.prefix_map();
+    for item in self.document.body.iter() {
+      let terms = item
+        .subject
+        .iter()
+        .chain(item.predicate.iter().flat_map(|p| {
+          p.raw_predicate_object
+            .iter()
+            .chain(p.object.iter().filter_map(|o| o.raw_object.as_ref()))
+        }));
+      for raw in terms {
+        if map.resolve_term(raw).is_none() {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} uses an undeclared prefix", raw),
+          ));
+        }
+      }
+    }
+    Ok(self.document)
+  }
+
+  // subject starts a statement declaring the type of an entity
+  fn subject(mut self, iri: &str, entity_type: &str) -> Self {
+    let item = TurtleBodyItem {
+      subject: Some(raw_resource(iri)),
+      predicate: VecDeque::new(),
+      span: None,
+    };
+    self.document.body.push_back(item);
+    self.fact("rdf:type", entity_type)
+  }
+
+  // add appends an object to a predicate of the current statement
+  fn add(mut self, property: &str, object: String) -> Self {
+    let property = raw_resource(property);
+    let item = match self.document.body.back_mut() {
+      Some(x) => x,
+      None => return self,
+    };
+    let object = TurtleObject::from_raw(&object);
+    match item
+      .predicate
+      .iter_mut()
+      .find(|p| p.raw_predicate_object.as_deref() == Some(property.as_str()))
+    {
+      Some(p) if !p.object.contains(&object) => p.object.push_back(object),
+      Some(_) => {}
+      None => {
+        let mut p = TurtlePredicate::from_raw(&property);
+        p.object.push_back(object);
+        item.predicate.push_back(p);
+      }
+    }
+    self
+  }
+}
+
+// raw_resource returns the turtle form of a term given as a prefixed name, an IRI in
+// angle brackets or an absolute IRI
+fn raw_resource(term: &str) -> String {
+  match term.contains("://") && !term.starts_with('<') {
+    true => format!("<{}>", term),
+    false => String::from(term),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_build_ontology() -> std::io::Result<()> {
+    let document =
+      OntologyBuilder::new("http://www.ontologyrepository.com/CommonCoreOntologies/AgentOntology")
+        .label_lang("Agent Ontology", "en")
+        .prefix(
+          "cco",
+          "http://www.ontologyrepository.com/CommonCoreOntologies/",
+        )
+        .class("cco:Agent")
+        .label("Agent")
+        .label("Agent")
+        .comment("An \"agent\" acts")
+        .subclass_of("http://purl.obolibrary.org/obo/BFO_0000040")
+        .object_property("cco:is_agent_in")
+        .domain("cco:Agent")
+        .individual("cco:JohnDoe", "cco:Agent")
+        .literal("cco:has_age", "42", "xsd:integer")
+        .build()?;
+    let triples = document.triples();
+    assert_eq!(triples.len(), 11);
+    let id = document.ontology_id();
+    assert_eq!(
+      id.ontology_iri.as_deref(),
+      Some("http://www.ontologyrepository.com/CommonCoreOntologies/AgentOntology")
+    );
+    let agent = "http://www.ontologyrepository.com/CommonCoreOntologies/Agent";
+    assert_eq!(document.labels_of(agent).values(), vec!["Agent"]);
+    assert_eq!(
+      document
+        .annotations_of(agent, "http://www.w3.org/2000/01/rdf-schema#comment")
+        .values(),
+      vec!["An \"agent\" acts"]
+    );
+    assert!(triples.iter().any(|t| t.object
+      == TurtleTerm::Literal {
+        value: String::from("42"),
+        datatype: String::from("http://www.w3.org/2001/XMLSchema#integer"),
+        language: None,
+      }));
+    Ok(())
+  }
+
+  #[test]
+  fn should_reject_undeclared_prefix() {
+    let result = OntologyBuilder::new("http://example.org/onto")
+      .class("ex:Agent")
+      .build();
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+  }
+}
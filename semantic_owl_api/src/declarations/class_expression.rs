@@ -0,0 +1,500 @@
+//! Class expression module models OWL 2 class expressions and data ranges
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::TurtleTerm;
+use crate::vocab::{owl, rdf, rdfs, xsd, Iri};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_DATATYPE: &str = rdfs::DATATYPE.as_str();
+const OWL_DATATYPE_PROPERTY: &str = owl::DATATYPE_PROPERTY.as_str();
+
+// datatypes outside of the XSD namespace
+const OTHER_DATATYPES: [Iri; 5] = [
+  rdfs::LITERAL,
+  rdf::PLAIN_LITERAL,
+  rdf::LANG_STRING,
+  rdf::XML_LITERAL,
+  owl::REAL,
+];
+
+// nesting depth beyond which an expression is considered malformed
+const MAX_DEPTH: usize = 64;
+
+/// FacetRestriction constrains the values of a datatype with a facet
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FacetRestriction {
+  // e.g -> http://www.w3.org/2001/XMLSchema#minInclusive
+  pub facet: String,
+
+  // e.g -> "0"^^xsd:decimal
+  pub value: TurtleTerm,
+}
+
+impl FacetRestriction {
+  pub fn new(facet: String, value: TurtleTerm) -> FacetRestriction {
+    Self { facet, value }
+  }
+}
+
+/// DataRange is a named datatype or a datatype whose values are restricted by facets.
+/// IRIs are absolute and stored without angle brackets
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DataRange {
+  Datatype(String),
+
+  // e.g -> [ a rdfs:Datatype ; owl:onDatatype xsd:decimal ;
+  //          owl:withRestrictions ( [ xsd:minInclusive 0 ] [ xsd:maxInclusive 100 ] ) ]
+  DatatypeRestriction {
+    datatype: String,
+    restrictions: Vec<FacetRestriction>,
+  },
+}
+
+impl DataRange {
+  /// as_datatype returns the IRI of the range if it is a named datatype
+  pub fn as_datatype(&self) -> Option<&str> {
+    match self {
+      DataRange::Datatype(x) => Some(x),
+      _ => None,
+    }
+  }
+}
+
+/// ClassExpression is a named class or an anonymous class built from other expressions.
+/// IRIs are absolute and stored without angle brackets
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ClassExpression {
+  Class(String),
+  ObjectIntersectionOf(Vec<ClassExpression>),
+  ObjectUnionOf(Vec<ClassExpression>),
+  ObjectComplementOf(Box<ClassExpression>),
+
+  // the IRIs of the enumerated individuals
+  ObjectOneOf(Vec<String>),
+
+  ObjectSomeValuesFrom {
+    property: String,
+    filler: Box<ClassExpression>,
+  },
+  ObjectAllValuesFrom {
+    property: String,
+    filler: Box<ClassExpression>,
+  },
+  ObjectHasValue {
+    property: String,
+    individual: String,
+  },
+  ObjectHasSelf {
+    property: String,
+  },
+
+  // a cardinality without filler is unqualified, i.e qualified by `owl:Thing`
+  ObjectMinCardinality {
+    cardinality: u32,
+    property: String,
+    filler: Option<Box<ClassExpression>>,
+  },
+  ObjectMaxCardinality {
+    cardinality: u32,
+    property: String,
+    filler: Option<Box<ClassExpression>>,
+  },
+  ObjectExactCardinality {
+    cardinality: u32,
+    property: String,
+    filler: Option<Box<ClassExpression>>,
+  },
+
+  DataSomeValuesFrom {
+    property: String,
+    range: DataRange,
+  },
+  DataAllValuesFrom {
+    property: String,
+    range: DataRange,
+  },
+  DataHasValue {
+    property: String,
+    value: TurtleTerm,
+  },
+  DataMinCardinality {
+    cardinality: u32,
+    property: String,
+    range: Option<DataRange>,
+  },
+  DataMaxCardinality {
+    cardinality: u32,
+    property: String,
+    range: Option<DataRange>,
+  },
+  DataExactCardinality {
+    cardinality: u32,
+    property: String,
+    range: Option<DataRange>,
+  },
+}
+
+impl ClassExpression {
+  /// as_class returns the IRI of the expression if it is a named class
+  pub fn as_class(&self) -> Option<&str> {
+    match self {
+      ClassExpression::Class(x) => Some(x),
+      _ => None,
+    }
+  }
+
+  /// is_anonymous determines whether the expression is not a named class
+  pub fn is_anonymous(&self) -> bool {
+    self.as_class().is_none()
+  }
+}
+
+/// parse_class_expression reconstructs the class expression denoted by a term of a graph.
+/// It returns `None` if the term is a literal or a blank node which is not a well-formed expression
+/// example
+/// the blank node of `[ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Velocity ]`
+/// returns `ObjectSomeValuesFrom { property: cco:has_part, filler: Class(cco:Velocity) }`
+pub fn parse_class_expression(graph: &TripleGraph, term: &TurtleTerm) -> Option<ClassExpression> {
+  ClassExpressionParser::new(graph).parse(term)
+}
+
+/// ClassExpressionParser parses the class expressions of a graph. It is created once per graph
+/// since it looks up the data properties declared in the graph
+pub struct ClassExpressionParser<'a> {
+  graph: &'a TripleGraph,
+  data_properties: BTreeSet<&'a str>,
+}
+
+impl<'a> ClassExpressionParser<'a> {
+  pub fn new(graph: &'a TripleGraph) -> ClassExpressionParser<'a> {
+    let data_properties = graph
+      .triples()
+      .filter(|t| {
+        t.predicate.as_iri() == Some(RDF_TYPE) && t.object.as_iri() == Some(OWL_DATATYPE_PROPERTY)
+      })
+      .filter_map(|t| t.subject.as_iri())
+      .collect();
+    Self {
+      graph,
+      data_properties,
+    }
+  }
+
+  /// parse returns the class expression denoted by a term
+  pub fn parse(&self, term: &TurtleTerm) -> Option<ClassExpression> {
+    self.expression(term, 0)
+  }
+
+  fn expression(&self, term: &TurtleTerm, depth: usize) -> Option<ClassExpression> {
+    if depth > MAX_DEPTH {
+      return None;
+    }
+    let node = match term {
+      TurtleTerm::Iri(x) => return Some(ClassExpression::Class(x.clone())),
+      TurtleTerm::BlankNode(_) => term,
+      TurtleTerm::Literal { .. } | TurtleTerm::QuotedTriple(_) => return None,
+    };
+    let operands = |predicate: Iri| -> Option<Vec<ClassExpression>> {
+      let list = self.graph.list(self.owl(node, predicate)?)?;
+      list
+        .into_iter()
+        .map(|x| self.expression(x, depth + 1))
+        .collect()
+    };
+    if self.owl(node, owl::ON_PROPERTY).is_some() {
+      return self.restriction(node, depth);
+    }
+    if self.owl(node, owl::INTERSECTION_OF).is_some() {
+      return operands(owl::INTERSECTION_OF).map(ClassExpression::ObjectIntersectionOf);
+    }
+    if self.owl(node, owl::UNION_OF).is_some() {
+      return operands(owl::UNION_OF).map(ClassExpression::ObjectUnionOf);
+    }
+    if let Some(x) = self.owl(node, owl::COMPLEMENT_OF) {
+      let operand = self.expression(x, depth + 1)?;
+      return Some(ClassExpression::ObjectComplementOf(Box::new(operand)));
+    }
+    if let Some(x) = self.owl(node, owl::ONE_OF) {
+      let individuals = self
+        .graph
+        .list(x)?
+        .into_iter()
+        .map(|x| x.as_iri().map(String::from))
+        .collect::<Option<Vec<String>>>()?;
+      return Some(ClassExpression::ObjectOneOf(individuals));
+    }
+    None
+  }
+
+  fn restriction(&self, node: &TurtleTerm, depth: usize) -> Option<ClassExpression> {
+    let property = String::from(self.owl(node, owl::ON_PROPERTY)?.as_iri()?);
+    let filler = |x: &TurtleTerm| self.expression(x, depth + 1).map(Box::new);
+    let is_data = self.data_properties.contains(property.as_str())
+      || self.owl(node, owl::ON_DATA_RANGE).is_some();
+
+    if let Some(x) = self.owl(node, owl::SOME_VALUES_FROM) {
+      return match is_data || self.is_datatype(x) {
+        true => Some(ClassExpression::DataSomeValuesFrom {
+          property,
+          range: self.data_range(x)?,
+        }),
+        false => Some(ClassExpression::ObjectSomeValuesFrom {
+          property,
+          filler: filler(x)?,
+        }),
+      };
+    }
+    if let Some(x) = self.owl(node, owl::ALL_VALUES_FROM) {
+      return match is_data || self.is_datatype(x) {
+        true => Some(ClassExpression::DataAllValuesFrom {
+          property,
+          range: self.data_range(x)?,
+        }),
+        false => Some(ClassExpression::ObjectAllValuesFrom {
+          property,
+          filler: filler(x)?,
+        }),
+      };
+    }
+    if let Some(x) = self.owl(node, owl::HAS_VALUE) {
+      return match x {
+        TurtleTerm::Literal { .. } => Some(ClassExpression::DataHasValue {
+          property,
+          value: x.clone(),
+        }),
+        _ => Some(ClassExpression::ObjectHasValue {
+          property,
+          individual: String::from(x.as_iri()?),
+        }),
+      };
+    }
+    if self.owl(node, owl::HAS_SELF).is_some() {
+      return Some(ClassExpression::ObjectHasSelf { property });
+    }
+
+    // cardinalities, qualified with `owl:onClass` or `owl:onDataRange`, or unqualified
+    for (kind, qualified, unqualified) in [
+      ("min", owl::MIN_QUALIFIED_CARDINALITY, owl::MIN_CARDINALITY),
+      ("max", owl::MAX_QUALIFIED_CARDINALITY, owl::MAX_CARDINALITY),
+      ("exact", owl::QUALIFIED_CARDINALITY, owl::CARDINALITY),
+    ] {
+      let cardinality = match self
+        .owl(node, qualified)
+        .or_else(|| self.owl(node, unqualified))
+      {
+        Some(TurtleTerm::Literal { value, .. }) => value.parse::<u32>().ok()?,
+        Some(_) => return None,
+        None => continue,
+      };
+      if is_data {
+        let range = match self.owl(node, owl::ON_DATA_RANGE) {
+          Some(x) => Some(self.data_range(x)?),
+          None => None,
+        };
+        return Some(match kind {
+          "min" => ClassExpression::DataMinCardinality {
+            cardinality,
+            property,
+            range,
+          },
+          "max" => ClassExpression::DataMaxCardinality {
+            cardinality,
+            property,
+            range,
+          },
+          _ => ClassExpression::DataExactCardinality {
+            cardinality,
+            property,
+            range,
+          },
+        });
+      }
+      let filler = match self.owl(node, owl::ON_CLASS) {
+        Some(x) => Some(filler(x)?),
+        None => None,
+      };
+      return Some(match kind {
+        "min" => ClassExpression::ObjectMinCardinality {
+          cardinality,
+          property,
+          filler,
+        },
+        "max" => ClassExpression::ObjectMaxCardinality {
+          cardinality,
+          property,
+          filler,
+        },
+        _ => ClassExpression::ObjectExactCardinality {
+          cardinality,
+          property,
+          filler,
+        },
+      });
+    }
+    None
+  }
+
+  /// data_range returns the data range denoted by a term, a named datatype or the blank node of a
+  /// datatype restriction. It returns `None` if the term is a literal, a quoted triple or a malformed
+  /// restriction
+  /// example
+  /// the blank node of `[ a rdfs:Datatype ; owl:onDatatype xsd:integer ; owl:withRestrictions ( [ xsd:minInclusive 0 ] ) ]`
+  /// returns `DatatypeRestriction { datatype: xsd:integer, restrictions: [xsd:minInclusive 0] }`
+  pub fn data_range(&self, term: &TurtleTerm) -> Option<DataRange> {
+    let node = match term {
+      TurtleTerm::Iri(x) => return Some(DataRange::Datatype(x.clone())),
+      TurtleTerm::BlankNode(_) => term,
+      TurtleTerm::Literal { .. } | TurtleTerm::QuotedTriple(_) => return None,
+    };
+    let datatype = String::from(self.owl(node, owl::ON_DATATYPE)?.as_iri()?);
+    let restrictions = self
+      .graph
+      .list(self.owl(node, owl::WITH_RESTRICTIONS)?)?
+      .into_iter()
+      .map(|x| {
+        // each member is a blank node stating a single facet
+        let t = self.graph.triples_matching(Some(x), None, None).next()?;
+        match (&t.predicate, &t.object) {
+          (TurtleTerm::Iri(facet), value @ TurtleTerm::Literal { .. }) => {
+            Some(FacetRestriction::new(facet.clone(), value.clone()))
+          }
+          _ => None,
+        }
+      })
+      .collect::<Option<Vec<FacetRestriction>>>()?;
+    Some(DataRange::DatatypeRestriction {
+      datatype,
+      restrictions,
+    })
+  }
+
+  // owl returns the object of a node for a predicate of the OWL namespace
+  fn owl(&self, node: &TurtleTerm, predicate: Iri) -> Option<&'a TurtleTerm> {
+    self.graph.object(node, predicate.as_str())
+  }
+
+  /// is_datatype determines whether a term denotes a data range rather than a class
+  pub fn is_datatype(&self, term: &TurtleTerm) -> bool {
+    match term {
+      TurtleTerm::Iri(x) => {
+        x.starts_with(xsd::NAMESPACE)
+          || OTHER_DATATYPES.iter().any(|d| d == x)
+          || self.graph.object(term, RDF_TYPE).and_then(|x| x.as_iri()) == Some(RDFS_DATATYPE)
+      }
+      TurtleTerm::BlankNode(_) => self.owl(term, owl::ON_DATATYPE).is_some(),
+      TurtleTerm::Literal { .. } | TurtleTerm::QuotedTriple(_) => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:age a owl:DatatypeProperty .
+ex:A owl:equivalentClass [ a owl:Class ;
+                           owl:intersectionOf ( ex:B [ a owl:Restriction ; owl:onProperty ex:p ; owl:someValuesFrom ex:C ] ) ] .
+ex:A rdfs:subClassOf [ a owl:Restriction ;
+                       owl:onProperty ex:q ;
+                       owl:minQualifiedCardinality "2"^^xsd:nonNegativeInteger ;
+                       owl:onClass [ a owl:Class ; owl:complementOf ex:D ]
+                     ] .
+ex:A rdfs:subClassOf [ a owl:Class ; owl:oneOf ( ex:i ex:j ) ] .
+ex:A rdfs:subClassOf [ a owl:Restriction ; owl:onProperty ex:age ; owl:maxCardinality "1"^^xsd:nonNegativeInteger ] .
+ex:A rdfs:subClassOf [ a owl:Restriction ; owl:onProperty ex:name ; owl:allValuesFrom xsd:string ] .
+ex:A rdfs:subClassOf [ a owl:Restriction ; owl:onProperty ex:age ;
+                       owl:someValuesFrom [ a rdfs:Datatype ; owl:onDatatype xsd:integer ;
+                                            owl:withRestrictions ( [ xsd:minInclusive 0 ] [ xsd:maxExclusive 150 ] ) ] ] .
+ex:A rdfs:subClassOf [ a owl:Restriction ; owl:onProperty ex:p ] .
+"#;
+
+  fn iri(x: &str) -> String {
+    format!("http://example.org/{}", x)
+  }
+
+  fn class(x: &str) -> ClassExpression {
+    ClassExpression::Class(iri(x))
+  }
+
+  #[test]
+  fn should_parse_class_expressions() -> std::io::Result<()> {
+    let graph = load_turtle_document_from_str(DOCUMENT)?.graph();
+    let parser = ClassExpressionParser::new(&graph);
+    let objects_of = |predicate: &str| -> Vec<Option<ClassExpression>> {
+      let predicate = TurtleTerm::Iri(String::from(predicate));
+      graph
+        .triples_matching(None, Some(&predicate), None)
+        .map(|t| parser.parse(&t.object))
+        .collect()
+    };
+    let equivalent = objects_of("http://www.w3.org/2002/07/owl#equivalentClass");
+    assert_eq!(
+      equivalent,
+      vec![Some(ClassExpression::ObjectIntersectionOf(vec![
+        class("B"),
+        ClassExpression::ObjectSomeValuesFrom {
+          property: iri("p"),
+          filler: Box::new(class("C")),
+        }
+      ]))]
+    );
+    let parents = objects_of("http://www.w3.org/2000/01/rdf-schema#subClassOf");
+    assert_eq!(
+      parents,
+      vec![
+        Some(ClassExpression::ObjectMinCardinality {
+          cardinality: 2,
+          property: iri("q"),
+          filler: Some(Box::new(ClassExpression::ObjectComplementOf(Box::new(
+            class("D")
+          )))),
+        }),
+        Some(ClassExpression::ObjectOneOf(vec![iri("i"), iri("j")])),
+        Some(ClassExpression::DataMaxCardinality {
+          cardinality: 1,
+          property: iri("age"),
+          range: None,
+        }),
+        Some(ClassExpression::DataAllValuesFrom {
+          property: iri("name"),
+          range: DataRange::Datatype(String::from("http://www.w3.org/2001/XMLSchema#string")),
+        }),
+        Some(ClassExpression::DataSomeValuesFrom {
+          property: iri("age"),
+          range: DataRange::DatatypeRestriction {
+            datatype: String::from("http://www.w3.org/2001/XMLSchema#integer"),
+            restrictions: vec![
+              FacetRestriction::new(
+                String::from("http://www.w3.org/2001/XMLSchema#minInclusive"),
+                TurtleTerm::Literal {
+                  value: String::from("0"),
+                  datatype: String::from("http://www.w3.org/2001/XMLSchema#integer"),
+                  language: None,
+                }
+              ),
+              FacetRestriction::new(
+                String::from("http://www.w3.org/2001/XMLSchema#maxExclusive"),
+                TurtleTerm::Literal {
+                  value: String::from("150"),
+                  datatype: String::from("http://www.w3.org/2001/XMLSchema#integer"),
+                  language: None,
+                }
+              ),
+            ],
+          },
+        }),
+        // a restriction without constraint is malformed
+        None,
+      ]
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,235 @@
+//! Curie module converts between prefixed names and full IRIs outside the loader
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::std_prefix_names::StdPrefixes;
+use crate::declarations::turtle::TurtleDocument;
+use crate::loader::parsers::ttl_pname_parser::is_prefixed_name;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+// largest edit distance between a prefix and a suggested one
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// UnknownPrefix is the error of a CURIE whose prefix is not declared. It is carried by the
+/// `std::io::Error` returned by `CurieResolver::try_resolve`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPrefix {
+  pub curie: String,
+  pub prefix: String,
+
+  // prefixes likely intended, the closest first. Declared prefixes come before the registered
+  // ones at the same distance. e.g -> [cco, obo]
+  pub suggestions: Vec<String>,
+}
+
+impl Display for UnknownPrefix {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "`{}` uses the prefix `{}:` which is not declared",
+      self.curie, self.prefix
+    )?;
+    match self.suggestions.as_slice() {
+      [] => Ok(()),
+      x => write!(f, ", did you mean `{}:`?", x.join(":`, `")),
+    }
+  }
+}
+
+impl std::error::Error for UnknownPrefix {}
+
+/// CurieResolver resolves CURIEs into full IRIs, and shortens full IRIs into CURIEs, using the
+/// prefixes of a prefix map. The prefix map is shared by the clones of the resolver
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CurieResolver {
+  pub map: Arc<PrefixMap>,
+}
+
+impl CurieResolver {
+  pub fn new(map: PrefixMap) -> CurieResolver {
+    Self { map: Arc::new(map) }
+  }
+
+  /// from_document creates a resolver using the prefixes and the base IRI declared by a document
+  pub fn from_document(document: &TurtleDocument) -> CurieResolver {
+    Self::new(document.prefix_map())
+  }
+
+  /// from_registry creates a resolver using the prefixes of a registry
+  /// example
+  /// `CurieResolver::from_registry(&StdPrefixes::default())`
+  pub fn from_registry(registry: &StdPrefixes) -> CurieResolver {
+    Self::new(registry.prefix_map())
+  }
+
+  /// resolve returns the full IRI of a CURIE. IRIs in angle brackets are resolved against the
+  /// base IRI of the map
+  /// example
+  /// `owl:Class` returns Option of `http://www.w3.org/2002/07/owl#Class`
+  pub fn resolve(&self, curie: &str) -> Option<String> {
+    let curie = curie.trim();
+    match curie.starts_with('<') && curie.ends_with('>') {
+      true => Some(self.map.resolve_iri(curie)),
+      false => self.map.expand(curie),
+    }
+  }
+
+  /// try_resolve returns the full IRI of a CURIE, like `resolve`. When the prefix of the CURIE is
+  /// not declared, the error carries an `UnknownPrefix` suggesting the declared and registered
+  /// prefixes differing only by case or by a few characters
+  /// example
+  /// `Owl:Class` returns an error suggesting `owl`
+  pub fn try_resolve(&self, curie: &str, registry: &StdPrefixes) -> std::io::Result<String> {
+    if let Some(iri) = self.resolve(curie) {
+      return Ok(iri);
+    }
+    let curie = curie.trim();
+    let prefix = match curie.find(':') {
+      Some(idx) => &curie[..idx],
+      None => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidInput,
+          format!("{} is not a CURIE", curie),
+        ))
+      }
+    };
+    let error = UnknownPrefix {
+      curie: String::from(curie),
+      prefix: String::from(prefix),
+      suggestions: self.suggest_prefixes(prefix, registry),
+    };
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, error))
+  }
+
+  /// suggest_prefixes returns the declared and registered prefixes close to a prefix, the closest
+  /// first. Prefixes differing only by case are the only ones suggested when there are any
+  /// example
+  /// `skso` returns `[skos]`
+  pub fn suggest_prefixes(&self, prefix: &str, registry: &StdPrefixes) -> Vec<String> {
+    let lowercase = prefix.to_lowercase();
+    let declared = self.map.prefixes.keys().map(|x| (x, 0));
+    let registered = registry.prefixes.keys().map(|x| (x, 1));
+    let mut candidates: Vec<(usize, usize, &String)> = declared
+      .chain(registered)
+      .filter(|(x, _)| x.as_str() != prefix)
+      .filter_map(|(x, source)| {
+        let distance = edit_distance(&lowercase, &x.to_lowercase());
+        let close = distance <= MAX_SUGGESTION_DISTANCE && distance < x.chars().count().max(1);
+        close.then_some((distance, source, x))
+      })
+      .collect();
+    candidates.sort();
+    // a prefix differing only by case is the one intended
+    if candidates.first().is_some_and(|(d, _, _)| *d == 0) {
+      candidates.retain(|(d, _, _)| *d == 0);
+    }
+    let mut suggestions: Vec<String> = vec![];
+    for (_, _, x) in candidates {
+      if !suggestions.contains(x) {
+        suggestions.push(x.clone());
+      }
+    }
+    suggestions
+  }
+
+  /// shorten returns the CURIE of a full IRI using the longest matching namespace. IRIs whose
+  /// local name can not be written in a prefixed name are not shortened
+  /// example
+  /// `http://www.w3.org/2002/07/owl#Class` returns Option of `owl:Class`
+  pub fn shorten(&self, iri: &str) -> Option<String> {
+    self.map.shorten(iri).filter(|x| is_prefixed_name(x))
+  }
+}
+
+// edit_distance returns the Levenshtein distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous: Vec<usize> = (0..=b.len()).collect();
+  for (i, x) in a.chars().enumerate() {
+    let mut current = vec![i + 1];
+    for (j, y) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(x != *y);
+      current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@base <http://example.org/agents> .
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+cco:Agent a owl:Class .
+"#;
+
+  #[test]
+  fn should_resolve_and_shorten_curies() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let resolver = CurieResolver::from_document(&document);
+    let agent = "http://www.ontologyrepository.com/CommonCoreOntologies/Agent";
+    assert_eq!(resolver.resolve("cco:Agent").as_deref(), Some(agent));
+    assert_eq!(
+      resolver.resolve("<#Person>").as_deref(),
+      Some("http://example.org/agents#Person")
+    );
+    assert_eq!(resolver.resolve("skos:prefLabel"), None);
+    assert_eq!(resolver.shorten(agent).as_deref(), Some("cco:Agent"));
+    assert_eq!(
+      resolver.shorten("http://www.ontologyrepository.com/CommonCoreOntologies/a b"),
+      None
+    );
+
+    let registry = CurieResolver::from_registry(&StdPrefixes::default());
+    let handles: Vec<_> = (0..4)
+      .map(|_| {
+        let resolver = registry.clone();
+        std::thread::spawn(move || {
+          let iri = resolver.resolve("skos:prefLabel").unwrap();
+          resolver.shorten(&iri)
+        })
+      })
+      .collect();
+    for handle in handles {
+      assert_eq!(handle.join().unwrap().as_deref(), Some("skos:prefLabel"));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn should_suggest_prefixes_of_unknown_curies() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let resolver = CurieResolver::from_document(&document);
+    let registry = StdPrefixes::default();
+    assert_eq!(
+      resolver.try_resolve("cco:Agent", &registry)?,
+      "http://www.ontologyrepository.com/CommonCoreOntologies/Agent"
+    );
+
+    let error = resolver.try_resolve("OWL:Class", &registry).unwrap_err();
+    let unknown = error
+      .get_ref()
+      .and_then(|x| x.downcast_ref::<UnknownPrefix>())
+      .unwrap();
+    assert_eq!(unknown.prefix, "OWL");
+    assert_eq!(unknown.suggestions, vec!["owl"]);
+    assert_eq!(
+      error.to_string(),
+      "`OWL:Class` uses the prefix `OWL:` which is not declared, did you mean `owl:`?"
+    );
+
+    // registered prefixes are suggested after the declared ones
+    assert_eq!(
+      resolver.suggest_prefixes("co", &registry),
+      vec!["cco", "obo"]
+    );
+    assert_eq!(resolver.suggest_prefixes("skso", &registry), vec!["skos"]);
+    assert!(resolver.suggest_prefixes("schema", &registry).is_empty());
+    assert!(resolver.try_resolve("Agent", &registry).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,266 @@
+//! Graph module holds the triples of a document in an indexed, in-memory graph
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::rdf;
+use std::collections::{BTreeSet, HashMap};
+
+const RDF_FIRST: &str = rdf::FIRST.as_str();
+const RDF_REST: &str = rdf::REST.as_str();
+const RDF_NIL: &str = rdf::NIL.as_str();
+
+/// TripleGraph is a set of triples indexed by subject, predicate and object
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TripleGraph {
+  triples: Vec<TurtleTriple>,
+
+  // positions in `triples` per term
+  by_subject: HashMap<TurtleTerm, Vec<usize>>,
+  by_predicate: HashMap<TurtleTerm, Vec<usize>>,
+  by_object: HashMap<TurtleTerm, Vec<usize>>,
+}
+
+impl TripleGraph {
+  pub fn new() -> TripleGraph {
+    Self::default()
+  }
+
+  /// insert adds a triple to the graph. It returns `false` if the graph already holds the triple
+  pub fn insert(&mut self, triple: TurtleTriple) -> bool {
+    if self.contains(&triple.subject, &triple.predicate, &triple.object) {
+      return false;
+    }
+    let idx = self.triples.len();
+    for (index, term) in [
+      (&mut self.by_subject, &triple.subject),
+      (&mut self.by_predicate, &triple.predicate),
+      (&mut self.by_object, &triple.object),
+    ] {
+      index.entry(term.clone()).or_default().push(idx);
+    }
+    self.triples.push(triple);
+    true
+  }
+
+  /// remove removes a triple from the graph, the last triple of the graph taking its place. It
+  /// returns `false` if the graph does not hold the triple
+  pub fn remove(&mut self, triple: &TurtleTriple) -> bool {
+    let idx = match self.position(triple) {
+      Some(x) => x,
+      None => return false,
+    };
+    let last = self.triples.len() - 1;
+    let removed = self.triples.swap_remove(idx);
+    unindex(&mut self.by_subject, &removed.subject, idx);
+    unindex(&mut self.by_predicate, &removed.predicate, idx);
+    unindex(&mut self.by_object, &removed.object, idx);
+    if idx != last {
+      let moved = &self.triples[idx];
+      for (index, term) in [
+        (&mut self.by_subject, &moved.subject),
+        (&mut self.by_predicate, &moved.predicate),
+        (&mut self.by_object, &moved.object),
+      ] {
+        if let Some(x) = index
+          .get_mut(term)
+          .and_then(|x| x.iter_mut().find(|x| **x == last))
+        {
+          *x = idx;
+        }
+      }
+    }
+    true
+  }
+
+  // position returns the position of a triple in `triples`
+  fn position(&self, triple: &TurtleTriple) -> Option<usize> {
+    self
+      .by_subject
+      .get(&triple.subject)?
+      .iter()
+      .copied()
+      .find(|x| self.triples[*x] == *triple)
+  }
+
+  /// len returns the number of triples of the graph
+  pub fn len(&self) -> usize {
+    self.triples.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.triples.is_empty()
+  }
+
+  /// triples returns the triples of the graph in the order they were inserted, unless triples
+  /// were removed since
+  pub fn triples(&self) -> impl Iterator<Item = &TurtleTriple> {
+    self.triples.iter()
+  }
+
+  /// triples_matching returns the triples matching a pattern, where `None` matches any term
+  /// example
+  /// `triples_matching(Some(&agent), Some(&rdfs_label), None)` returns the labels of the agent
+  pub fn triples_matching<'a>(
+    &'a self,
+    subject: Option<&'a TurtleTerm>,
+    predicate: Option<&'a TurtleTerm>,
+    object: Option<&'a TurtleTerm>,
+  ) -> Box<dyn Iterator<Item = &'a TurtleTriple> + 'a> {
+    // start from the smallest index among the bound positions
+    let candidates = [
+      subject.map(|x| self.by_subject.get(x)),
+      predicate.map(|x| self.by_predicate.get(x)),
+      object.map(|x| self.by_object.get(x)),
+    ];
+    let smallest = candidates
+      .iter()
+      .flatten()
+      .min_by_key(|x| x.map(|v| v.len()).unwrap_or(0));
+    let matches = move |t: &&TurtleTriple| {
+      subject.map(|x| t.subject == *x).unwrap_or(true)
+        && predicate.map(|x| t.predicate == *x).unwrap_or(true)
+        && object.map(|x| t.object == *x).unwrap_or(true)
+    };
+    match smallest {
+      None => Box::new(self.triples.iter()),
+      Some(None) => Box::new(std::iter::empty()),
+      Some(Some(positions)) => Box::new(
+        positions
+          .iter()
+          .map(move |idx| &self.triples[*idx])
+          .filter(matches),
+      ),
+    }
+  }
+
+  /// contains determines whether the graph holds a triple
+  pub fn contains(
+    &self,
+    subject: &TurtleTerm,
+    predicate: &TurtleTerm,
+    object: &TurtleTerm,
+  ) -> bool {
+    self
+      .triples_matching(Some(subject), Some(predicate), Some(object))
+      .next()
+      .is_some()
+  }
+
+  /// subjects returns the distinct subjects of the graph
+  pub fn subjects(&self) -> BTreeSet<&TurtleTerm> {
+    self.by_subject.keys().collect()
+  }
+
+  /// predicates returns the distinct predicates of the graph
+  pub fn predicates(&self) -> BTreeSet<&TurtleTerm> {
+    self.by_predicate.keys().collect()
+  }
+
+  /// objects returns the distinct objects of the graph
+  pub fn objects(&self) -> BTreeSet<&TurtleTerm> {
+    self.by_object.keys().collect()
+  }
+
+  /// object returns an object of a subject and predicate, if any
+  pub fn object(&self, subject: &TurtleTerm, predicate: &str) -> Option<&TurtleTerm> {
+    self
+      .by_subject
+      .get(subject)?
+      .iter()
+      .map(|idx| &self.triples[*idx])
+      .find(|t| t.predicate.as_iri() == Some(predicate))
+      .map(|t| &t.object)
+  }
+
+  /// list returns the members of the RDF collection starting at `head`. It returns `None` if
+  /// `head` is not a well-formed, acyclic collection
+  /// example
+  /// `( cco:Agent cco:Person )` returns the two classes
+  pub fn list(&self, head: &TurtleTerm) -> Option<Vec<&TurtleTerm>> {
+    let mut members = vec![];
+    let mut visited = BTreeSet::new();
+    let mut node = head;
+    while node.as_iri() != Some(RDF_NIL) {
+      if !visited.insert(node) {
+        return None;
+      }
+      members.push(self.object(node, RDF_FIRST)?);
+      node = self.object(node, RDF_REST)?;
+    }
+    Some(members)
+  }
+}
+
+// unindex removes a position from the positions of a term, along with the term once it has none
+fn unindex(index: &mut HashMap<TurtleTerm, Vec<usize>>, term: &TurtleTerm, idx: usize) {
+  if let Some(positions) = index.get_mut(term) {
+    positions.retain(|x| *x != idx);
+    if positions.is_empty() {
+      index.remove(term);
+    }
+  }
+}
+
+impl std::iter::FromIterator<TurtleTriple> for TripleGraph {
+  fn from_iter<I: IntoIterator<Item = TurtleTriple>>(iter: I) -> Self {
+    let mut graph = TripleGraph::new();
+    for t in iter {
+      graph.insert(t);
+    }
+    graph
+  }
+}
+
+impl TurtleDocument {
+  /// graph returns the resolved triples of the document as an indexed graph
+  pub fn graph(&self) -> TripleGraph {
+    self.triples().into_iter().collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en , "Agent"@en .
+cco:Person a owl:Class ; rdfs:subClassOf cco:Agent ; rdfs:label "Person"@en .
+"#;
+
+  fn iri(x: &str) -> TurtleTerm {
+    TurtleTerm::Iri(String::from(x))
+  }
+
+  #[test]
+  fn should_match_triple_patterns() -> std::io::Result<()> {
+    let graph = load_turtle_document_from_str(DOCUMENT)?.graph();
+    // the duplicated label is stored once
+    assert_eq!(graph.len(), 5);
+    let agent = iri("http://www.ontologyrepository.com/CommonCoreOntologies/Agent");
+    let label = iri("http://www.w3.org/2000/01/rdf-schema#label");
+    let class = iri("http://www.w3.org/2002/07/owl#Class");
+    assert_eq!(graph.triples_matching(Some(&agent), None, None).count(), 2);
+    assert_eq!(graph.triples_matching(None, Some(&label), None).count(), 2);
+    assert_eq!(graph.triples_matching(None, None, Some(&class)).count(), 2);
+    assert_eq!(
+      graph
+        .triples_matching(Some(&agent), Some(&label), Some(&class))
+        .count(),
+      0
+    );
+    assert_eq!(
+      graph
+        .triples_matching(Some(&iri("http://example.org/x")), None, None)
+        .count(),
+      0
+    );
+    assert_eq!(graph.triples_matching(None, None, None).count(), 5);
+    assert_eq!(graph.subjects().len(), 2);
+    assert_eq!(graph.predicates().len(), 3);
+    assert!(graph.objects().contains(&agent));
+    Ok(())
+  }
+}
@@ -0,0 +1,443 @@
+//! Index module keeps the indexes of a document up to date as it is edited
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+const SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const LABEL: &str = rdfs::LABEL.as_str();
+
+/// Change is an edit of the statements of a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change {
+  Add(TurtleTriple),
+  Remove(TurtleTriple),
+}
+
+impl Change {
+  /// inverse returns the change undoing this one
+  pub fn inverse(&self) -> Change {
+    match self {
+      Change::Add(t) => Change::Remove(t.clone()),
+      Change::Remove(t) => Change::Add(t.clone()),
+    }
+  }
+}
+
+/// OntologyIndex indexes the triples, the named class hierarchy and the `rdfs:label` values of a
+/// document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OntologyIndex {
+  graph: TripleGraph,
+
+  // named super classes and sub classes per class
+  parents: BTreeMap<String, BTreeSet<String>>,
+  children: BTreeMap<String, BTreeSet<String>>,
+
+  // labels per entity, in the order they were added
+  labels: BTreeMap<String, Vec<TurtleTerm>>,
+
+  // entities per lowercased label value
+  by_label: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl OntologyIndex {
+  pub fn new() -> OntologyIndex {
+    Self::default()
+  }
+
+  /// from_document indexes the triples of a document
+  pub fn from_document(document: &TurtleDocument) -> OntologyIndex {
+    let mut index = OntologyIndex::new();
+    for t in document.triples() {
+      index.insert(t);
+    }
+    index
+  }
+
+  /// apply updates the indexes with a change. It returns `false` if the change is a no-op, i.e it
+  /// adds a triple already indexed or removes a triple which is not
+  pub fn apply(&mut self, change: &Change) -> bool {
+    match change {
+      Change::Add(t) => self.insert(t.clone()),
+      Change::Remove(t) => self.remove(t),
+    }
+  }
+
+  /// insert indexes a triple. It returns `false` if the triple is already indexed
+  pub fn insert(&mut self, triple: TurtleTriple) -> bool {
+    if !self.graph.insert(triple.clone()) {
+      return false;
+    }
+    let subject = match triple.subject.as_iri() {
+      Some(x) => String::from(x),
+      None => return true,
+    };
+    match (triple.predicate.as_iri(), &triple.object) {
+      (Some(SUB_CLASS_OF), TurtleTerm::Iri(parent)) => {
+        let parents = self.parents.entry(subject.clone()).or_default();
+        parents.insert(parent.clone());
+        let children = self.children.entry(parent.clone()).or_default();
+        children.insert(subject);
+      }
+      (Some(LABEL), TurtleTerm::Literal { value, .. }) => {
+        let values = self.by_label.entry(value.to_lowercase()).or_default();
+        values.insert(subject.clone());
+        let labels = self.labels.entry(subject).or_default();
+        labels.push(triple.object.clone());
+      }
+      _ => {}
+    }
+    true
+  }
+
+  /// remove removes a triple from the indexes. It returns `false` if the triple is not indexed
+  pub fn remove(&mut self, triple: &TurtleTriple) -> bool {
+    if !self.graph.remove(triple) {
+      return false;
+    }
+    let subject = match triple.subject.as_iri() {
+      Some(x) => x,
+      None => return true,
+    };
+    match (triple.predicate.as_iri(), &triple.object) {
+      (Some(SUB_CLASS_OF), TurtleTerm::Iri(parent)) => {
+        remove_edge(&mut self.parents, subject, parent);
+        remove_edge(&mut self.children, parent, subject);
+      }
+      (Some(LABEL), TurtleTerm::Literal { value, .. }) => {
+        let key = value.to_lowercase();
+        let labels = self.labels.entry(String::from(subject)).or_default();
+        labels.retain(|x| *x != triple.object);
+        // the entity keeps the key while another of its labels has it, e.g in another language
+        let shared = labels.iter().any(|x| match x {
+          TurtleTerm::Literal { value, .. } => value.to_lowercase() == key,
+          _ => false,
+        });
+        if labels.is_empty() {
+          self.labels.remove(subject);
+        }
+        if !shared {
+          remove_edge(&mut self.by_label, &key, subject);
+        }
+      }
+      _ => {}
+    }
+    true
+  }
+
+  /// graph returns the triples of the document indexed by subject, predicate and object
+  pub fn graph(&self) -> &TripleGraph {
+    &self.graph
+  }
+
+  /// super_classes returns the named classes a class, given as a full IRI, is a sub class of
+  pub fn super_classes(&self, iri: &str) -> Vec<&str> {
+    edges(&self.parents, iri)
+  }
+
+  /// sub_classes returns the named classes which are a sub class of a class given as a full IRI
+  pub fn sub_classes(&self, iri: &str) -> Vec<&str> {
+    edges(&self.children, iri)
+  }
+
+  /// labels_of returns the `rdfs:label` values of an entity given as a full IRI
+  pub fn labels_of(&self, iri: &str) -> &[TurtleTerm] {
+    self.labels.get(iri).map(|x| x.as_slice()).unwrap_or(&[])
+  }
+
+  /// find_by_label returns the entities with a label, whatever its case or language
+  /// example
+  /// `find_by_label("agent")` returns the entities labeled `"Agent"@en`
+  pub fn find_by_label(&self, label: &str) -> Vec<&str> {
+    edges(&self.by_label, &label.to_lowercase())
+  }
+}
+
+fn edges<'a>(index: &'a BTreeMap<String, BTreeSet<String>>, key: &str) -> Vec<&'a str> {
+  match index.get(key) {
+    Some(values) => values.iter().map(|x| x.as_str()).collect(),
+    None => vec![],
+  }
+}
+
+fn remove_edge(index: &mut BTreeMap<String, BTreeSet<String>>, key: &str, value: &str) {
+  if let Some(values) = index.get_mut(key) {
+    values.remove(value);
+    if values.is_empty() {
+      index.remove(key);
+    }
+  }
+}
+
+/// IndexedDocument is a document edited along with its indexes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexedDocument {
+  pub document: TurtleDocument,
+  pub index: OntologyIndex,
+
+  // prefix map of the document, which edits leave as it is
+  map: PrefixMap,
+
+  // positions in the body of the document of the statements of each triple, in ascending order
+  statements: HashMap<TurtleTriple, BTreeSet<usize>>,
+}
+
+impl IndexedDocument {
+  /// new indexes a document
+  pub fn new(document: TurtleDocument) -> IndexedDocument {
+    let index = OntologyIndex::from_document(&document);
+    let map = document.prefix_map();
+    let mut statements: HashMap<TurtleTriple, BTreeSet<usize>> = HashMap::new();
+    for (idx, item) in document.body.iter().enumerate() {
+      for t in item_triples(item, &map) {
+        statements.entry(t).or_default().insert(idx);
+      }
+    }
+    Self {
+      document,
+      index,
+      map,
+      statements,
+    }
+  }
+
+  /// apply edits the document and updates its indexes. It returns `false` if the change is a
+  /// no-op, in which case the document is left as it is
+  pub fn apply(&mut self, change: &Change) -> bool {
+    if !self.index.apply(change) {
+      return false;
+    }
+    match change {
+      Change::Add(t) => {
+        self.document.insert_triple(t);
+        let idx = self.document.body.len() - 1;
+        self.statements.entry(t.clone()).or_default().insert(idx);
+      }
+      Change::Remove(t) => self.remove_statements(t),
+    }
+    true
+  }
+
+  // remove_statements removes a triple from the statements stating it, then the statements left
+  // without objects. Only the positions after a removed statement are updated
+  fn remove_statements(&mut self, triple: &TurtleTriple) {
+    let positions = self.statements.remove(triple).unwrap_or_default();
+    let mut emptied = vec![];
+    for idx in positions {
+      if let Some(item) = self.document.body.get_mut(idx) {
+        if remove_objects(item, triple, &self.map) && item.predicate.is_empty() {
+          emptied.push(idx);
+        }
+      }
+    }
+    if emptied.is_empty() {
+      return;
+    }
+    for idx in emptied.iter().rev() {
+      self.document.body.remove(*idx);
+    }
+    for positions in self.statements.values_mut() {
+      if positions.range(emptied[0]..).next().is_some() {
+        *positions = positions
+          .iter()
+          .map(|x| x - emptied.partition_point(|idx| idx < x))
+          .collect();
+      }
+    }
+  }
+}
+
+// item_triples returns the triples a statement states with terms written in place, i.e those
+// `remove_objects` can remove. Nested blank nodes and collections are left out
+fn item_triples(item: &TurtleBodyItem, map: &PrefixMap) -> Vec<TurtleTriple> {
+  let subject = match item.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+    Some(x) => x,
+    None => return vec![],
+  };
+  let mut triples = vec![];
+  for p in item.predicate.iter() {
+    let predicate = match p.raw_predicate_object.as_deref() {
+      Some(x) => map.resolve_term(x),
+      None => None,
+    };
+    let predicate = match predicate {
+      Some(x) => x,
+      None => continue,
+    };
+    for o in p.object.iter() {
+      if let Some(object) = o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+        triples.push(TurtleTriple::new(
+          subject.clone(),
+          predicate.clone(),
+          object,
+        ));
+      }
+    }
+  }
+  triples
+}
+
+// remove_objects removes the objects of a triple from a statement, along with the predicates left
+// without objects. It returns `false` if the statement does not state the triple
+fn remove_objects(item: &mut TurtleBodyItem, triple: &TurtleTriple, map: &PrefixMap) -> bool {
+  let resolves_to = |raw: Option<&str>, term: &TurtleTerm| {
+    raw.and_then(|x| map.resolve_term(x)).as_ref() == Some(term)
+  };
+  if !resolves_to(item.subject.as_deref(), &triple.subject) {
+    return false;
+  }
+  let mut removed = false;
+  for p in item.predicate.iter_mut() {
+    if !resolves_to(p.raw_predicate_object.as_deref(), &triple.predicate) {
+      continue;
+    }
+    let count = p.object.len();
+    p.object
+      .retain(|o| !resolves_to(o.raw_object.as_deref(), &triple.object));
+    removed |= p.object.len() != count;
+  }
+  if removed {
+    item.predicate.retain(|p| !p.object.is_empty());
+  }
+  removed
+}
+
+impl TurtleDocument {
+  /// insert_triple appends a statement of a triple to the document. Its terms are written in full
+  pub fn insert_triple(&mut self, triple: &TurtleTriple) {
+    let mut predicate = TurtlePredicate::from_raw(&triple.predicate.to_string());
+    predicate
+      .object
+      .push_back(TurtleObject::from_raw(&triple.object.to_string()));
+    self.body.push_back(TurtleBodyItem {
+      subject: Some(triple.subject.to_string()),
+      predicate: VecDeque::from(vec![predicate]),
+      span: None,
+    });
+  }
+
+  /// remove_triple removes every statement of a triple from the document. Statements left without
+  /// objects are removed. It returns `false` if the document does not state the triple.
+  /// Every statement is visited, `IndexedDocument` only visits the statements of the triple
+  pub fn remove_triple(&mut self, triple: &TurtleTriple) -> bool {
+    let map = self.prefix_map();
+    let mut removed = false;
+    self.body.retain_mut(|item| {
+      if !remove_objects(item, triple, &map) {
+        return true;
+      }
+      removed = true;
+      !item.predicate.is_empty()
+    });
+    removed
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::vocab::{owl, rdf};
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en , "Agent"@fr .
+cco:Person a owl:Class ; rdfs:subClassOf cco:Agent ; rdfs:label "Person"@en .
+"#;
+
+  fn cco(x: &str) -> String {
+    format!(
+      "http://www.ontologyrepository.com/CommonCoreOntologies/{}",
+      x
+    )
+  }
+
+  #[test]
+  fn should_update_indexes_on_change() -> std::io::Result<()> {
+    let mut editor = IndexedDocument::new(load_turtle_document_from_str(DOCUMENT)?);
+    let (agent, person) = (cco("Agent"), cco("Person"));
+    assert_eq!(editor.index.sub_classes(&agent), vec![person.as_str()]);
+    assert_eq!(editor.index.find_by_label("AGENT"), vec![agent.as_str()]);
+
+    let subclass = TurtleTriple::new(
+      TurtleTerm::Iri(person.clone()),
+      rdfs::SUB_CLASS_OF.into(),
+      TurtleTerm::Iri(agent.clone()),
+    );
+    assert!(editor.apply(&Change::Remove(subclass.clone())));
+    assert!(!editor.apply(&Change::Remove(subclass.clone())));
+    assert!(editor.index.super_classes(&person).is_empty());
+    assert_eq!(editor.document.body[1].predicate.len(), 2);
+
+    let label = |value: &str, language: &str| TurtleTerm::Literal {
+      value: String::from(value),
+      datatype: String::from("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"),
+      language: Some(String::from(language)),
+    };
+    let english = TurtleTriple::new(
+      TurtleTerm::Iri(agent.clone()),
+      rdfs::LABEL.into(),
+      label("Agent", "en"),
+    );
+    // the french label still names the agent
+    assert!(editor.apply(&Change::Remove(english)));
+    assert_eq!(editor.index.find_by_label("agent"), vec![agent.as_str()]);
+    assert_eq!(editor.index.labels_of(&agent), &[label("Agent", "fr")]);
+
+    let role = TurtleTriple::new(
+      TurtleTerm::Iri(cco("Role")),
+      rdfs::SUB_CLASS_OF.into(),
+      TurtleTerm::Iri(agent.clone()),
+    );
+    assert!(editor.apply(&Change::Add(role.clone())));
+    assert!(!editor.apply(&Change::Add(role)));
+    assert_eq!(editor.index.sub_classes(&agent), vec![cco("Role").as_str()]);
+
+    // the indexes match the ones of the edited document
+    assert_eq!(editor.index.graph().len(), editor.document.graph().len());
+    let rebuilt = OntologyIndex::from_document(&editor.document);
+    assert_eq!(
+      rebuilt.sub_classes(&agent),
+      editor.index.sub_classes(&agent)
+    );
+    assert_eq!(rebuilt.labels_of(&agent), editor.index.labels_of(&agent));
+    Ok(())
+  }
+
+  #[test]
+  fn should_track_statement_positions() -> std::io::Result<()> {
+    let mut editor = IndexedDocument::new(load_turtle_document_from_str(DOCUMENT)?);
+    let (agent, person) = (cco("Agent"), cco("Person"));
+    let class = |x: &str| {
+      TurtleTriple::new(
+        TurtleTerm::Iri(x.to_string()),
+        rdf::TYPE.into(),
+        owl::CLASS.into(),
+      )
+    };
+    let role = cco("Role");
+    assert!(editor.apply(&Change::Add(class(&role))));
+
+    // emptying the first statements moves the ones after it
+    for t in editor.document.triples() {
+      if t.subject.as_iri() == Some(agent.as_str()) {
+        assert!(editor.apply(&Change::Remove(t)));
+      }
+    }
+    assert_eq!(editor.document.body.len(), 2);
+    assert!(editor.apply(&Change::Remove(class(&role))));
+    assert!(editor.apply(&Change::Remove(class(&person))));
+    assert_eq!(editor.document.body.len(), 1);
+    assert_eq!(editor.document.graph().len(), 2);
+    assert_eq!(editor.document.triples().len(), editor.index.graph().len());
+    Ok(())
+  }
+}
@@ -0,0 +1,206 @@
+//! Individual module maps application values into OWL individuals
+use crate::declarations::builder::OntologyBuilder;
+use crate::vocab::xsd;
+use serde::{Deserialize, Serialize};
+
+const XSD: &str = xsd::NAMESPACE;
+
+/// OwlValue is the value of a property assertion: a typed literal for data properties or
+/// the IRI of another individual for object properties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OwlValue {
+  // datatype is a full IRI, e.g `http://www.w3.org/2001/XMLSchema#integer`
+  Literal { value: String, datatype: String },
+
+  // prefixed name or IRI of the individual
+  Individual(String),
+}
+
+impl OwlValue {
+  /// individual creates the value of an object property assertion
+  /// example
+  /// `OwlValue::individual("cco:Acme")`
+  pub fn individual(iri: &str) -> OwlValue {
+    OwlValue::Individual(String::from(iri))
+  }
+}
+
+macro_rules! impl_from_for_owl_value {
+  ($($t:ty => $datatype:expr),* $(,)?) => {
+    $(
+      impl From<$t> for OwlValue {
+        fn from(x: $t) -> OwlValue {
+          OwlValue::Literal {
+            value: x.to_string(),
+            datatype: format!("{}{}", XSD, $datatype),
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_from_for_owl_value!(
+  &str => "string",
+  String => "string",
+  bool => "boolean",
+  i8 => "byte",
+  i16 => "short",
+  i32 => "int",
+  i64 => "long",
+  u8 => "unsignedByte",
+  u16 => "unsignedShort",
+  u32 => "unsignedInt",
+  u64 => "unsignedLong",
+);
+
+// floating point values are written in the lexical space of `xsd:float` and `xsd:double`, where
+// infinities are `INF` and `-INF` rather than the `inf` and `-inf` of `to_string`
+macro_rules! impl_from_float_for_owl_value {
+  ($($t:ty => $datatype:expr),* $(,)?) => {
+    $(
+      impl From<$t> for OwlValue {
+        fn from(x: $t) -> OwlValue {
+          let value = match x {
+            x if x == <$t>::INFINITY => String::from("INF"),
+            x if x == <$t>::NEG_INFINITY => String::from("-INF"),
+            x => x.to_string(),
+          };
+          OwlValue::Literal {
+            value,
+            datatype: format!("{}{}", XSD, $datatype),
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_from_float_for_owl_value!(
+  f32 => "float",
+  f64 => "double",
+);
+
+/// ToOwlIndividual converts a value into an OWL individual
+pub trait ToOwlIndividual {
+  /// individual_iri returns the prefixed name or IRI of the individual
+  fn individual_iri(&self) -> String;
+
+  /// classes returns the classes the individual is an instance of
+  fn classes(&self) -> Vec<String>;
+
+  /// assertions returns the property assertions of the individual as pairs of property and value.
+  /// A property with several values appears once per value
+  fn assertions(&self) -> Vec<(String, OwlValue)>;
+}
+
+impl OntologyBuilder {
+  /// add_individual starts the statement of an `owl:NamedIndividual` from a value
+  pub fn add_individual<T: ToOwlIndividual + ?Sized>(self, x: &T) -> Self {
+    let mut builder = self.named_individual(&x.individual_iri());
+    for class in x.classes() {
+      builder = builder.fact("rdf:type", &class);
+    }
+    for (property, value) in x.assertions() {
+      builder = match value {
+        OwlValue::Literal { value, datatype } => builder.literal(&property, &value, &datatype),
+        OwlValue::Individual(iri) => builder.fact(&property, &iri),
+      };
+    }
+    builder
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::declarations::turtle::TurtleTerm;
+
+  struct Person {
+    id: u32,
+    name: String,
+    age: u8,
+    employer: Option<String>,
+  }
+
+  impl ToOwlIndividual for Person {
+    fn individual_iri(&self) -> String {
+      format!("ex:person{}", self.id)
+    }
+
+    fn classes(&self) -> Vec<String> {
+      vec![String::from("cco:Person")]
+    }
+
+    fn assertions(&self) -> Vec<(String, OwlValue)> {
+      let mut assertions = vec![
+        (String::from("ex:name"), OwlValue::from(self.name.as_str())),
+        (String::from("ex:age"), OwlValue::from(self.age)),
+      ];
+      if let Some(x) = &self.employer {
+        assertions.push((String::from("ex:employer"), OwlValue::individual(x)));
+      }
+      assertions
+    }
+  }
+
+  #[test]
+  fn should_write_floats_in_their_lexical_space() {
+    let lexical = |x: OwlValue| match x {
+      OwlValue::Literal { value, .. } => value,
+      OwlValue::Individual(iri) => iri,
+    };
+    assert_eq!(lexical(OwlValue::from(f64::INFINITY)), "INF");
+    assert_eq!(lexical(OwlValue::from(f64::NEG_INFINITY)), "-INF");
+    assert_eq!(lexical(OwlValue::from(f32::INFINITY)), "INF");
+    assert_eq!(lexical(OwlValue::from(f32::NAN)), "NaN");
+    assert_eq!(lexical(OwlValue::from(1.5f64)), "1.5");
+  }
+
+  #[test]
+  fn should_add_individuals() -> std::io::Result<()> {
+    let people = [
+      Person {
+        id: 1,
+        name: String::from("Jane \"JD\" Doe"),
+        age: 42,
+        employer: Some(String::from("ex:Acme")),
+      },
+      Person {
+        id: 2,
+        name: String::from("John Doe"),
+        age: 7,
+        employer: None,
+      },
+    ];
+    let mut builder = OntologyBuilder::new("http://example.org/people")
+      .prefix("ex", "http://example.org/")
+      .prefix(
+        "cco",
+        "http://www.ontologyrepository.com/CommonCoreOntologies/",
+      );
+    for p in people.iter() {
+      builder = builder.add_individual(p);
+    }
+    let document = builder.build()?;
+    let triples = document.triples();
+    // the ontology, 2 x 2 types, 2 x 2 data property assertions and an object property assertion
+    assert_eq!(triples.len(), 10);
+    assert!(triples.iter().any(|t| t.object
+      == TurtleTerm::Literal {
+        value: String::from("Jane \"JD\" Doe"),
+        datatype: String::from("http://www.w3.org/2001/XMLSchema#string"),
+        language: None,
+      }));
+    assert!(triples.iter().any(|t| t.object
+      == TurtleTerm::Literal {
+        value: String::from("42"),
+        datatype: String::from("http://www.w3.org/2001/XMLSchema#unsignedByte"),
+        language: None,
+      }));
+    assert!(triples
+      .iter()
+      .any(|t| t.object.as_iri() == Some("http://example.org/Acme")));
+    Ok(())
+  }
+}
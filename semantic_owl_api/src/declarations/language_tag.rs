@@ -0,0 +1,205 @@
+//! Language tag module validates and matches BCP 47 language tags
+use std::collections::BTreeSet;
+
+// grandfathered tags which do not follow the regular syntax
+const IRREGULAR_TAGS: [&str; 17] = [
+  "en-GB-oed",
+  "i-ami",
+  "i-bnn",
+  "i-default",
+  "i-enochian",
+  "i-hak",
+  "i-klingon",
+  "i-lux",
+  "i-mingo",
+  "i-navajo",
+  "i-pwn",
+  "i-tao",
+  "i-tay",
+  "i-tsu",
+  "sgn-BE-FR",
+  "sgn-BE-NL",
+  "sgn-CH-DE",
+];
+
+/// is_valid_language_tag determines whether a tag is well-formed according to BCP 47.
+/// Matching is case insensitive
+/// example
+/// `en`, `en-GB`, `zh-Hant-TW`, `de-CH-1901`, `x-private` are valid. `englishlanguage`, `en-` are not
+pub fn is_valid_language_tag(tag: &str) -> bool {
+  if IRREGULAR_TAGS.iter().any(|x| x.eq_ignore_ascii_case(tag)) {
+    return true;
+  }
+  let subtags: Vec<&str> = tag.split('-').collect();
+  if subtags
+    .iter()
+    .any(|x| x.is_empty() || !x.chars().all(|c| c.is_ascii_alphanumeric()))
+  {
+    return false;
+  }
+  if is_private_use_singleton(subtags[0]) {
+    return is_private_use(&subtags[1..]);
+  }
+
+  // language
+  let language = subtags[0];
+  if !is_alpha(language) || !(2..=8).contains(&language.len()) {
+    return false;
+  }
+  let mut i = 1;
+
+  // up to three extended language subtags follow a two or three letter language
+  if language.len() <= 3 {
+    let mut extlang = 0;
+    while i < subtags.len() && extlang < 3 && subtags[i].len() == 3 && is_alpha(subtags[i]) {
+      i += 1;
+      extlang += 1;
+    }
+  }
+
+  // script
+  if i < subtags.len() && subtags[i].len() == 4 && is_alpha(subtags[i]) {
+    i += 1;
+  }
+
+  // region
+  if i < subtags.len() && is_region(subtags[i]) {
+    i += 1;
+  }
+
+  // variants, which must not repeat
+  let mut variants: BTreeSet<String> = BTreeSet::new();
+  while i < subtags.len() && is_variant(subtags[i]) {
+    if !variants.insert(subtags[i].to_ascii_lowercase()) {
+      return false;
+    }
+    i += 1;
+  }
+
+  // extensions, whose singletons must not repeat
+  let mut singletons: BTreeSet<String> = BTreeSet::new();
+  while i < subtags.len() && subtags[i].len() == 1 && !is_private_use_singleton(subtags[i]) {
+    if !singletons.insert(subtags[i].to_ascii_lowercase()) {
+      return false;
+    }
+    i += 1;
+    let start = i;
+    while i < subtags.len() && (2..=8).contains(&subtags[i].len()) {
+      i += 1;
+    }
+    if i == start {
+      return false;
+    }
+  }
+
+  // private use
+  if i < subtags.len() && is_private_use_singleton(subtags[i]) {
+    return is_private_use(&subtags[i + 1..]);
+  }
+  i == subtags.len()
+}
+
+/// matches_language_range determines whether a tag matches a basic language range (RFC 4647 section 3.3.1).
+/// Matching is case insensitive and `*` matches any tag
+/// example
+/// `en-GB` matches the ranges `en-GB`, `en` and `*` but not `en-US` or `e`
+pub fn matches_language_range(tag: &str, range: &str) -> bool {
+  if range == "*" {
+    return true;
+  }
+  let tag = tag.to_ascii_lowercase();
+  let range = range.to_ascii_lowercase();
+  tag == range || tag.starts_with(&format!("{}-", range))
+}
+
+/// truncate_language_range removes the last subtag of a range, as well as any single character
+/// subtag left at its end (RFC 4647 section 3.4)
+/// example
+/// `zh-Hant-CN-x-private` returns Option of `zh-Hant-CN`, `en` returns None
+pub fn truncate_language_range(range: &str) -> Option<&str> {
+  let mut truncated = &range[..range.rfind('-')?];
+  while let Some(idx) = truncated.rfind('-') {
+    if truncated.len() - idx - 1 > 1 {
+      break;
+    }
+    truncated = &truncated[..idx];
+  }
+  Some(truncated)
+}
+
+fn is_alpha(subtag: &str) -> bool {
+  subtag.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_region(subtag: &str) -> bool {
+  (subtag.len() == 2 && is_alpha(subtag))
+    || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_variant(subtag: &str) -> bool {
+  (5..=8).contains(&subtag.len())
+    || (subtag.len() == 4 && subtag.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn is_private_use_singleton(subtag: &str) -> bool {
+  subtag.eq_ignore_ascii_case("x")
+}
+
+fn is_private_use(subtags: &[&str]) -> bool {
+  !subtags.is_empty() && subtags.iter().all(|x| (1..=8).contains(&x.len()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_validate_language_tags() {
+    let valid = [
+      "en",
+      "EN-gb",
+      "zh-Hant-TW",
+      "zh-yue-HK",
+      "es-419",
+      "de-CH-1901",
+      "sl-rozaj-biske",
+      "en-a-bbb-x-a-ccc",
+      "x-whatever",
+      "i-klingon",
+      "qaa-Qaaa-QM-x-southern",
+    ];
+    for tag in valid.iter() {
+      assert!(is_valid_language_tag(tag), "{}", tag);
+    }
+    let invalid = [
+      "",
+      "e",
+      "en-",
+      "englishlanguage",
+      "de-419-DE",
+      "a-DE",
+      "ar-a-aaa-b-bbb-a-ccc",
+      "de-CH-1901-1901",
+      "en-x",
+      "en_GB",
+    ];
+    for tag in invalid.iter() {
+      assert!(!is_valid_language_tag(tag), "{}", tag);
+    }
+  }
+
+  #[test]
+  fn should_match_language_ranges() {
+    assert!(matches_language_range("en-GB", "en"));
+    assert!(matches_language_range("en-GB", "EN-gb"));
+    assert!(matches_language_range("fr", "*"));
+    assert!(!matches_language_range("en-GB", "en-US"));
+    assert!(!matches_language_range("en", "e"));
+    assert_eq!(
+      truncate_language_range("zh-Hant-CN-x-private"),
+      Some("zh-Hant-CN")
+    );
+    assert_eq!(truncate_language_range("en-GB"), Some("en"));
+    assert_eq!(truncate_language_range("en"), None);
+  }
+}
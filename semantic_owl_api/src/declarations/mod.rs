@@ -1,3 +1,17 @@
+pub mod axiom_annotation;
+pub mod builder;
+pub mod class_expression;
+pub mod curie;
+pub mod graph;
+pub mod index;
+pub mod individual;
+pub mod language_tag;
+pub mod ontology;
 pub mod owl;
+pub mod prefix_map;
+pub mod provenance;
+pub mod session;
+pub mod shared;
 pub mod std_prefix_names;
+pub mod template;
 pub mod turtle;
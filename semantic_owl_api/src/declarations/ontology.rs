@@ -0,0 +1,362 @@
+//! Ontology module manages the identity of the ontology a document describes
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm,
+};
+use crate::vocab::{owl, rdf};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const OWL_ONTOLOGY: &str = owl::ONTOLOGY.as_str();
+const OWL_VERSION_IRI: &str = owl::VERSION_IRI.as_str();
+const OWL_IMPORTS: &str = owl::IMPORTS.as_str();
+const OWL_VERSION_INFO: &str = owl::VERSION_INFO.as_str();
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+/// OntologyId identifies an ontology by its IRI and, optionally, the IRI of its version.
+/// Both IRIs are absolute and stored without angle brackets
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct OntologyId {
+  pub ontology_iri: Option<String>,
+  pub version_iri: Option<String>,
+}
+
+impl OntologyId {
+  pub fn new(ontology_iri: Option<String>, version_iri: Option<String>) -> OntologyId {
+    Self {
+      ontology_iri,
+      version_iri,
+    }
+  }
+
+  /// is_anonymous determines whether the ontology has no IRI
+  pub fn is_anonymous(&self) -> bool {
+    self.ontology_iri.is_none()
+  }
+}
+
+/// is_release_date determines whether a string is a date of the form `YYYY-MM-DD`
+pub fn is_release_date(x: &str) -> bool {
+  let parts: Vec<&str> = x.split('-').collect();
+  let valid =
+    |part: &str, len: usize| part.len() == len && part.chars().all(|c| c.is_ascii_digit());
+  match parts.as_slice() {
+    [year, month, day] if valid(year, 4) && valid(month, 2) && valid(day, 2) => {
+      let month: u8 = month.parse().unwrap_or(0);
+      let day: u8 = day.parse().unwrap_or(0);
+      (1..=12).contains(&month) && (1..=31).contains(&day)
+    }
+    _ => false,
+  }
+}
+
+/// dated_version_iri returns the version IRI of a release made on `date`. The date replaces the
+/// date segment of the path of `iri`, or is inserted before its last segment when it has none
+/// example
+/// `http://example.org/2021-03-01/AgentOntology` with `2022-01-31` returns `http://example.org/2022-01-31/AgentOntology`
+/// `http://example.org/AgentOntology` with `2022-01-31` returns `http://example.org/2022-01-31/AgentOntology`
+pub fn dated_version_iri(iri: &str, date: &str) -> String {
+  let mut segments: Vec<&str> = iri.split('/').collect();
+  if let Some(idx) = segments.iter().position(|x| is_release_date(x)) {
+    segments[idx] = date;
+    return segments.join("/");
+  }
+  // keep `scheme://authority` intact
+  match segments.len() {
+    n if n > 3 => {
+      segments.insert(n - 1, date);
+      segments.join("/")
+    }
+    _ => format!("{}/{}", iri.trim_end_matches('/'), date),
+  }
+}
+
+impl TurtleDocument {
+  /// ontology_id returns the IRI and version IRI of the first `owl:Ontology` subject of the document
+  pub fn ontology_id(&self) -> OntologyId {
+    let triples = self.triples();
+    let ontology_iri = triples
+      .iter()
+      .find(|t| {
+        t.subject.as_iri().is_some()
+          && t.predicate.as_iri() == Some(RDF_TYPE)
+          && t.object.as_iri() == Some(OWL_ONTOLOGY)
+      })
+      .and_then(|t| t.subject.as_iri().map(String::from));
+    let version_iri = ontology_iri.as_ref().and_then(|iri| {
+      triples
+        .iter()
+        .find(|t| {
+          t.subject.as_iri() == Some(iri.as_str()) && t.predicate.as_iri() == Some(OWL_VERSION_IRI)
+        })
+        .and_then(|t| t.object.as_iri().map(String::from))
+    });
+    OntologyId::new(ontology_iri, version_iri)
+  }
+
+  /// imports returns the IRIs of the ontologies imported by the ontology of the document, in document order
+  pub fn imports(&self) -> Vec<String> {
+    let ontology_iri = match self.ontology_id().ontology_iri {
+      Some(x) => x,
+      None => return vec![],
+    };
+    let mut imports: Vec<String> = vec![];
+    for t in self.triples() {
+      if t.subject.as_iri() != Some(ontology_iri.as_str())
+        || t.predicate.as_iri() != Some(OWL_IMPORTS)
+      {
+        continue;
+      }
+      if let Some(iri) = t.object.as_iri() {
+        if !imports.iter().any(|x| x == iri) {
+          imports.push(String::from(iri));
+        }
+      }
+    }
+    imports
+  }
+
+  /// version_info returns the `owl:versionInfo` of the ontology of the document, if any.
+  /// example
+  /// `owl:versionInfo "2020ab"` returns `2020ab`
+  pub fn version_info(&self) -> Option<String> {
+    let ontology_iri = self.ontology_id().ontology_iri?;
+    self.triples().into_iter().find_map(|t| match t.object {
+      TurtleTerm::Literal { value, .. }
+        if t.subject.as_iri() == Some(ontology_iri.as_str())
+          && t.predicate.as_iri() == Some(OWL_VERSION_INFO) =>
+      {
+        Some(value)
+      }
+      _ => None,
+    })
+  }
+
+  /// set_ontology_iri renames the `owl:Ontology` subject of the document, keeping its statements.
+  /// If the document has no ontology statement, `<iri> a owl:Ontology .` is added
+  pub fn set_ontology_iri(&mut self, iri: &str) {
+    let map = self.prefix_map();
+    let raw = format!("<{}>", iri);
+    match self.ontology_id().ontology_iri {
+      Some(current) => {
+        for item in self.body.iter_mut() {
+          if is_subject(item, &current, &map) {
+            item.subject = Some(raw.clone());
+          }
+        }
+      }
+      None => {
+        let item = body_item(&raw, RDF_TYPE, &format!("<{}>", OWL_ONTOLOGY));
+        self.body.push_front(item);
+      }
+    }
+  }
+
+  /// set_version_iri replaces the `owl:versionIRI` of the ontology, or removes it when `None`.
+  /// An anonymous ontology cannot have a version IRI
+  pub fn set_version_iri(&mut self, version_iri: Option<&str>) -> std::io::Result<()> {
+    let map = self.prefix_map();
+    let ontology_iri = match self.ontology_id().ontology_iri {
+      Some(x) => x,
+      None if version_iri.is_none() => return Ok(()),
+      None => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidInput,
+          "an anonymous ontology cannot have a version IRI",
+        ))
+      }
+    };
+    let is_version_iri = |p: &TurtlePredicate| match p
+      .raw_predicate_object
+      .as_deref()
+      .and_then(|x| map.resolve_term(x))
+    {
+      Some(TurtleTerm::Iri(x)) => x == OWL_VERSION_IRI,
+      _ => false,
+    };
+    for item in self.body.iter_mut() {
+      if is_subject(item, &ontology_iri, &map) {
+        item.predicate.retain(|p| !is_version_iri(p));
+      }
+    }
+    let version_iri = match version_iri {
+      Some(x) => x,
+      None => return Ok(()),
+    };
+    let mut predicate = TurtlePredicate::from_raw(&format!("<{}>", OWL_VERSION_IRI));
+    predicate
+      .object
+      .push_back(TurtleObject::from_raw(&format!("<{}>", version_iri)));
+    if let Some(item) = self
+      .body
+      .iter_mut()
+      .find(|x| is_subject(x, &ontology_iri, &map))
+    {
+      item.predicate.push_back(predicate);
+    }
+    Ok(())
+  }
+
+  /// bump_version sets the version IRI of the ontology to the one of a release made on `date`,
+  /// of the form `YYYY-MM-DD`. The new version IRI is derived from the current one if any,
+  /// otherwise from the ontology IRI, and is returned
+  pub fn bump_version(&mut self, date: &str) -> std::io::Result<String> {
+    if !is_release_date(date) {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("{} is not a date of the form YYYY-MM-DD", date),
+      ));
+    }
+    let id = self.ontology_id();
+    let current = match id.version_iri.or(id.ontology_iri) {
+      Some(x) => x,
+      None => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidInput,
+          "an anonymous ontology cannot have a version IRI",
+        ))
+      }
+    };
+    let version_iri = dated_version_iri(&current, date);
+    self.set_version_iri(Some(&version_iri))?;
+    Ok(version_iri)
+  }
+}
+
+// is_subject determines whether the subject of a statement resolves to `iri`
+fn is_subject(item: &TurtleBodyItem, iri: &str, map: &PrefixMap) -> bool {
+  match item.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+    Some(TurtleTerm::Iri(x)) => x == iri,
+    _ => false,
+  }
+}
+
+// body_item creates a statement of a single triple from the raw forms of its terms
+fn body_item(subject: &str, predicate: &str, object: &str) -> TurtleBodyItem {
+  let mut p = TurtlePredicate::from_raw(&format!("<{}>", predicate));
+  p.object.push_back(TurtleObject::from_raw(object));
+  let mut predicates = VecDeque::new();
+  predicates.push_back(p);
+  TurtleBodyItem {
+    subject: Some(String::from(subject)),
+    predicate: predicates,
+    span: None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+<http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> a owl:Ontology ;
+  rdfs:label "Agent Ontology"@en ;
+  owl:versionIRI <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/2021-03-01/AgentOntology> .
+cco:Agent a owl:Class .
+"#;
+
+  #[test]
+  fn should_read_ontology_id() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let id = document.ontology_id();
+    assert_eq!(
+      id.ontology_iri.as_deref(),
+      Some("http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology")
+    );
+    assert_eq!(
+      id.version_iri.as_deref(),
+      Some("http://www.ontologyrepository.com/CommonCoreOntologies/Mid/2021-03-01/AgentOntology")
+    );
+    assert!(TurtleDocument::new().ontology_id().is_anonymous());
+    Ok(())
+  }
+
+  #[test]
+  fn should_update_ontology_id() -> std::io::Result<()> {
+    let mut document = load_turtle_document_from_str(DOCUMENT)?;
+    document.set_ontology_iri("http://example.org/AgentOntology");
+    let version = document.bump_version("2022-01-31")?;
+    assert_eq!(
+      version,
+      "http://www.ontologyrepository.com/CommonCoreOntologies/Mid/2022-01-31/AgentOntology"
+    );
+    let id = document.ontology_id();
+    assert_eq!(
+      id,
+      OntologyId::new(
+        Some(String::from("http://example.org/AgentOntology")),
+        Some(version)
+      )
+    );
+    // the other statements of the ontology are kept
+    assert_eq!(
+      document
+        .labels_of("http://example.org/AgentOntology")
+        .values(),
+      vec!["Agent Ontology"]
+    );
+    assert_eq!(document.triples().len(), 4);
+    document.set_version_iri(None)?;
+    assert_eq!(document.ontology_id().version_iri, None);
+    assert!(document.bump_version("2022-13-01").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn should_declare_missing_ontology() -> std::io::Result<()> {
+    let mut document = TurtleDocument::new();
+    assert!(document
+      .set_version_iri(Some("http://example.org/1.0"))
+      .is_err());
+    document.set_ontology_iri("http://example.org/onto");
+    assert_eq!(
+      document.bump_version("2022-01-31")?,
+      "http://example.org/2022-01-31/onto"
+    );
+    assert_eq!(document.triples().len(), 2);
+    Ok(())
+  }
+
+  #[test]
+  fn should_derive_dated_version_iri() {
+    assert_eq!(
+      dated_version_iri(
+        "http://purl.obolibrary.org/obo/go/releases/2021-01-01/go.owl",
+        "2022-01-31"
+      ),
+      "http://purl.obolibrary.org/obo/go/releases/2022-01-31/go.owl"
+    );
+    assert_eq!(
+      dated_version_iri("http://example.org", "2022-01-31"),
+      "http://example.org/2022-01-31"
+    );
+    assert!(is_release_date("2022-01-31"));
+    assert!(!is_release_date("2022-1-31"));
+  }
+
+  #[test]
+  fn should_read_imports() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/InformationEntityOntology.ttl");
+    let document = crate::loader::load::load_turtle_document(path.to_str().unwrap())?;
+    assert_eq!(
+      document.imports(),
+      vec![
+        "http://www.ontologyrepository.com/CommonCoreOntologies/Mid/GeospatialOntology",
+        "http://www.ontologyrepository.com/CommonCoreOntologies/Mid/TimeOntology",
+      ]
+    );
+    assert!(load_turtle_document_from_str(DOCUMENT)?
+      .imports()
+      .is_empty());
+    Ok(())
+  }
+}
@@ -1,3 +1,37 @@
+//! Owl module models the axioms of an OWL 2 ontology and maps turtle documents into them
+use crate::declarations::axiom_annotation::Annotation;
+use crate::declarations::class_expression::{ClassExpression, ClassExpressionParser, DataRange};
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::ontology::OntologyId;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::{owl, rdf, rdfs};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const OWL_EQUIVALENT_CLASS: &str = owl::EQUIVALENT_CLASS.as_str();
+const OWL_DISJOINT_WITH: &str = owl::DISJOINT_WITH.as_str();
+const OWL_ALL_DISJOINT_CLASSES: &str = owl::ALL_DISJOINT_CLASSES.as_str();
+const OWL_DISJOINT_UNION_OF: &str = owl::DISJOINT_UNION_OF.as_str();
+const RDFS_SUB_PROPERTY_OF: &str = rdfs::SUB_PROPERTY_OF.as_str();
+const RDFS_DOMAIN: &str = rdfs::DOMAIN.as_str();
+const RDFS_RANGE: &str = rdfs::RANGE.as_str();
+const OWL_INVERSE_OF: &str = owl::INVERSE_OF.as_str();
+const OWL_PROPERTY_CHAIN_AXIOM: &str = owl::PROPERTY_CHAIN_AXIOM.as_str();
+const OWL_SAME_AS: &str = owl::SAME_AS.as_str();
+const OWL_DIFFERENT_FROM: &str = owl::DIFFERENT_FROM.as_str();
+const OWL_ALL_DIFFERENT: &str = owl::ALL_DIFFERENT.as_str();
+const OWL_MEMBERS: &str = owl::MEMBERS.as_str();
+const OWL_DISTINCT_MEMBERS: &str = owl::DISTINCT_MEMBERS.as_str();
+const OWL_NEGATIVE_PROPERTY_ASSERTION: &str = owl::NEGATIVE_PROPERTY_ASSERTION.as_str();
+const OWL_SOURCE_INDIVIDUAL: &str = owl::SOURCE_INDIVIDUAL.as_str();
+const OWL_ASSERTION_PROPERTY: &str = owl::ASSERTION_PROPERTY.as_str();
+const OWL_TARGET_INDIVIDUAL: &str = owl::TARGET_INDIVIDUAL.as_str();
+const OWL_TARGET_VALUE: &str = owl::TARGET_VALUE.as_str();
+
+/// OwlSyntax is a serialization of an ontology document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OwlSyntax {
   Functional,
   Turtle,
@@ -7,5 +41,1127 @@ pub enum OwlSyntax {
 }
 
 pub trait RDFDocumentMapperToOwl {
-  fn map_to_owl(&self);
+  /// map_to_owl maps the statements of a document into the axioms of an ontology.
+  /// Statements which do not form a supported axiom are ignored
+  fn map_to_owl(&self) -> OwlOntology;
+}
+
+/// Reasoning tells whether a query over the axioms of an ontology includes the consequences
+/// drawn from the property hierarchy, or the asserted axioms only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reasoning {
+  Enabled,
+  Disabled,
+}
+
+/// PropertyCharacteristic is a characteristic of an object property. Only `Functional`
+/// applies to data properties as well
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PropertyCharacteristic {
+  Functional,
+  InverseFunctional,
+  Transitive,
+  Symmetric,
+  Asymmetric,
+  Reflexive,
+  Irreflexive,
+}
+
+const PROPERTY_CHARACTERISTICS: [(&str, PropertyCharacteristic); 7] = [
+  (
+    owl::FUNCTIONAL_PROPERTY.as_str(),
+    PropertyCharacteristic::Functional,
+  ),
+  (
+    owl::INVERSE_FUNCTIONAL_PROPERTY.as_str(),
+    PropertyCharacteristic::InverseFunctional,
+  ),
+  (
+    owl::TRANSITIVE_PROPERTY.as_str(),
+    PropertyCharacteristic::Transitive,
+  ),
+  (
+    owl::SYMMETRIC_PROPERTY.as_str(),
+    PropertyCharacteristic::Symmetric,
+  ),
+  (
+    owl::ASYMMETRIC_PROPERTY.as_str(),
+    PropertyCharacteristic::Asymmetric,
+  ),
+  (
+    owl::REFLEXIVE_PROPERTY.as_str(),
+    PropertyCharacteristic::Reflexive,
+  ),
+  (
+    owl::IRREFLEXIVE_PROPERTY.as_str(),
+    PropertyCharacteristic::Irreflexive,
+  ),
+];
+
+impl PropertyCharacteristic {
+  /// from_iri returns the characteristic declared by a type, given as a full IRI
+  /// example
+  /// `http://www.w3.org/2002/07/owl#TransitiveProperty` returns Option of `Transitive`
+  pub fn from_iri(iri: &str) -> Option<PropertyCharacteristic> {
+    PROPERTY_CHARACTERISTICS
+      .iter()
+      .find(|(x, _)| *x == iri)
+      .map(|(_, c)| *c)
+  }
+
+  /// iri returns the full IRI of the type declaring the characteristic
+  pub fn iri(&self) -> &'static str {
+    PROPERTY_CHARACTERISTICS
+      .iter()
+      .find(|(_, c)| c == self)
+      .map(|(x, _)| *x)
+      .unwrap_or_default()
+  }
+}
+
+/// Axiom is a statement of an ontology. IRIs are absolute and stored without angle brackets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Axiom {
+  // e.g -> cco:has_part rdf:type owl:TransitiveProperty .
+  PropertyCharacteristic {
+    property: String,
+    characteristic: PropertyCharacteristic,
+  },
+
+  // e.g -> cco:Acceleration rdfs:subClassOf [ a owl:Restriction ; ... ] .
+  SubClassOf {
+    sub_class: ClassExpression,
+    super_class: ClassExpression,
+  },
+
+  // e.g -> cco:Agent owl:equivalentClass [ owl:unionOf ( ... ) ] .
+  EquivalentClasses(Vec<ClassExpression>),
+
+  // classes which share no instance, stated pairwise or all at once.
+  // e.g -> cco:Person owl:disjointWith cco:Organization . or
+  //        [ a owl:AllDisjointClasses ; owl:members ( cco:Person cco:Organization cco:Vehicle ) ] .
+  DisjointClasses(Vec<ClassExpression>),
+
+  // a class which is the union of pairwise disjoint classes.
+  // e.g -> cco:Sex owl:disjointUnionOf ( cco:Female cco:Male ) .
+  DisjointUnion {
+    class: String,
+    members: Vec<ClassExpression>,
+  },
+
+  // e.g -> cco:has_mother rdfs:subPropertyOf cco:has_parent .
+  SubPropertyOf {
+    sub_property: String,
+    super_property: String,
+  },
+
+  // e.g -> cco:has_part owl:inverseOf cco:part_of .
+  InverseObjectProperties {
+    property: String,
+    inverse: String,
+  },
+
+  // chains with an anonymous inverse property are not mapped.
+  // e.g -> obo:RO_0002170 owl:propertyChainAxiom ( obo:BFO_0000050 obo:RO_0002170 ) .
+  SubPropertyChainOf {
+    chain: Vec<String>,
+    super_property: String,
+  },
+
+  // e.g -> cco:has_parent rdfs:domain cco:Person .
+  PropertyDomain {
+    property: String,
+    domain: ClassExpression,
+  },
+
+  // a named datatype range reads as a named class expression.
+  // e.g -> cco:has_parent rdfs:range cco:Person . or cco:has_age rdfs:range xsd:integer .
+  PropertyRange {
+    property: String,
+    range: ClassExpression,
+  },
+
+  // a data property range restricted with facets.
+  // e.g -> cco:has_percentage rdfs:range [ a rdfs:Datatype ; owl:onDatatype xsd:decimal ;
+  //                                         owl:withRestrictions ( [ xsd:maxInclusive 100 ] ) ] .
+  DataPropertyRange {
+    property: String,
+    range: DataRange,
+  },
+
+  // e.g -> cco:Percentage owl:equivalentClass [ a rdfs:Datatype ; owl:onDatatype xsd:decimal ;
+  //                                             owl:withRestrictions ( [ xsd:minInclusive 0 ] ) ] .
+  DatatypeDefinition {
+    datatype: String,
+    range: DataRange,
+  },
+
+  // e.g -> cco:JohnDoe owl:sameAs cco:JDoe .
+  SameIndividual(Vec<String>),
+
+  // e.g -> [ a owl:AllDifferent ; owl:members ( cco:JohnDoe cco:JaneDoe ) ] .
+  DifferentIndividuals(Vec<String>),
+
+  // e.g -> [ a owl:NegativePropertyAssertion ; owl:sourceIndividual cco:JohnDoe ;
+  //          owl:assertionProperty cco:is_parent_of ; owl:targetIndividual cco:JaneDoe ] .
+  NegativeObjectPropertyAssertion {
+    property: String,
+    source: String,
+    target: String,
+  },
+
+  // e.g -> [ a owl:NegativePropertyAssertion ; owl:sourceIndividual cco:JohnDoe ;
+  //          owl:assertionProperty cco:has_age ; owl:targetValue 42 ] .
+  NegativeDataPropertyAssertion {
+    property: String,
+    source: String,
+    value: TurtleTerm,
+  },
+}
+
+impl Axiom {
+  /// is_general_class_axiom determines whether the axiom is a general class axiom, i.e a class
+  /// axiom about no named class: a subclass axiom whose sub-class is anonymous, or an equivalence
+  /// or disjointness of anonymous class expressions only
+  /// example
+  /// `[ owl:onProperty cco:has_part ; owl:someValuesFrom cco:Wheel ] rdfs:subClassOf cco:Vehicle`
+  pub fn is_general_class_axiom(&self) -> bool {
+    match self {
+      Axiom::SubClassOf { sub_class, .. } => sub_class.is_anonymous(),
+      Axiom::EquivalentClasses(members) | Axiom::DisjointClasses(members) => {
+        members.iter().all(|x| x.is_anonymous())
+      }
+      _ => false,
+    }
+  }
+
+  /// disjoint_pairs expands a disjointness axiom into the pairs of class expressions it states to
+  /// be disjoint, in the order of its members. Other axioms have no pair
+  /// example
+  /// `DisjointClasses([A, B, C])` returns `[(A, B), (A, C), (B, C)]`
+  pub fn disjoint_pairs(&self) -> Vec<(&ClassExpression, &ClassExpression)> {
+    let members = match self {
+      Axiom::DisjointClasses(members) | Axiom::DisjointUnion { members, .. } => members,
+      _ => return vec![],
+    };
+    let mut pairs = vec![];
+    for (idx, a) in members.iter().enumerate() {
+      for b in members.iter().skip(idx + 1) {
+        if a != b {
+          pairs.push((a, b));
+        }
+      }
+    }
+    pairs
+  }
+}
+
+/// AxiomAnnotations are the annotations of an axiom, read from its `owl:Axiom` nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxiomAnnotations {
+  pub axiom: Axiom,
+  pub annotations: Vec<Annotation>,
+}
+
+impl AxiomAnnotations {
+  pub fn new(axiom: Axiom, annotations: Vec<Annotation>) -> AxiomAnnotations {
+    Self { axiom, annotations }
+  }
+}
+
+/// OwlOntology is the identity and the axioms of an ontology
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct OwlOntology {
+  pub id: OntologyId,
+  pub axioms: Vec<Axiom>,
+
+  // annotations of the annotated axioms, one entry per annotated axiom
+  #[serde(default)]
+  pub annotations: Vec<AxiomAnnotations>,
+}
+
+impl OwlOntology {
+  pub fn new(id: OntologyId, axioms: Vec<Axiom>) -> OwlOntology {
+    Self {
+      id,
+      axioms,
+      annotations: vec![],
+    }
+  }
+
+  /// annotations_of returns the annotations of an axiom
+  pub fn annotations_of(&self, axiom: &Axiom) -> Vec<&Annotation> {
+    self
+      .annotations
+      .iter()
+      .filter(|x| &x.axiom == axiom)
+      .flat_map(|x| x.annotations.iter())
+      .collect()
+  }
+
+  /// annotate adds annotations to an axiom, which is added to the ontology if missing
+  pub fn annotate(&mut self, axiom: Axiom, annotations: Vec<Annotation>) {
+    if !self.axioms.contains(&axiom) {
+      self.axioms.push(axiom.clone());
+    }
+    match self.annotations.iter_mut().find(|x| x.axiom == axiom) {
+      Some(x) => x.annotations.extend(annotations),
+      None => self
+        .annotations
+        .push(AxiomAnnotations::new(axiom, annotations)),
+    }
+  }
+
+  /// characteristics_of returns the characteristics of a property given as a full IRI
+  pub fn characteristics_of(&self, property: &str) -> BTreeSet<PropertyCharacteristic> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::PropertyCharacteristic {
+          property: p,
+          characteristic,
+        } if p == property => Some(*characteristic),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// super_classes_of returns the asserted super classes, named or anonymous, of a class given as a full IRI
+  pub fn super_classes_of(&self, class: &str) -> Vec<&ClassExpression> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::SubClassOf {
+          sub_class,
+          super_class,
+        } if sub_class.as_class() == Some(class) => Some(super_class),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// equivalent_classes_of returns the class expressions asserted equivalent to a class given as a full IRI
+  pub fn equivalent_classes_of(&self, class: &str) -> Vec<&ClassExpression> {
+    let mut found = vec![];
+    for x in self.axioms.iter() {
+      if let Axiom::EquivalentClasses(members) = x {
+        if members.iter().any(|m| m.as_class() == Some(class)) {
+          found.extend(members.iter().filter(|m| m.as_class() != Some(class)));
+        }
+      }
+    }
+    found
+  }
+
+  /// general_class_axioms returns the general class axioms of the ontology, which no named class
+  /// refers to and which are easily overlooked when browsing an ontology class by class
+  pub fn general_class_axioms(&self) -> Vec<&Axiom> {
+    self
+      .axioms
+      .iter()
+      .filter(|x| x.is_general_class_axiom())
+      .collect()
+  }
+
+  /// datatype_definition returns the data range defining a custom datatype given as a full IRI
+  pub fn datatype_definition(&self, datatype: &str) -> Option<&DataRange> {
+    self.axioms.iter().find_map(|x| match x {
+      Axiom::DatatypeDefinition { datatype: d, range } if d == datatype => Some(range),
+      _ => None,
+    })
+  }
+
+  /// data_ranges_of returns the data ranges restricted with facets of a property given as a full
+  /// IRI. Named datatype ranges are returned by `ranges_of`
+  pub fn data_ranges_of(&self, property: &str) -> Vec<&DataRange> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::DataPropertyRange { property: p, range } if p == property => Some(range),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// disjoint_classes_of returns the class expressions asserted disjoint with a class given as a
+  /// full IRI, by any disjointness axiom
+  pub fn disjoint_classes_of(&self, class: &str) -> Vec<&ClassExpression> {
+    let mut found = vec![];
+    for (a, b) in self.disjoint_pairs() {
+      let other = match (a.as_class(), b.as_class()) {
+        (Some(x), _) if x == class => b,
+        (_, Some(x)) if x == class => a,
+        _ => continue,
+      };
+      if !found.contains(&other) {
+        found.push(other);
+      }
+    }
+    found
+  }
+
+  /// disjoint_pairs returns the pairs of class expressions stated disjoint by the disjointness
+  /// axioms of the ontology, so that n-ary axioms are checked pair by pair
+  pub fn disjoint_pairs(&self) -> Vec<(&ClassExpression, &ClassExpression)> {
+    self
+      .axioms
+      .iter()
+      .flat_map(|x| x.disjoint_pairs())
+      .collect()
+  }
+
+  /// are_disjoint determines whether two classes, given as full IRIs, are asserted disjoint
+  pub fn are_disjoint(&self, a: &str, b: &str) -> bool {
+    self
+      .disjoint_classes_of(a)
+      .iter()
+      .any(|x| x.as_class() == Some(b))
+  }
+
+  /// same_individuals returns the individuals asserted, directly or through other individuals,
+  /// to be the same as an individual given as a full IRI. The individual itself is excluded
+  pub fn same_individuals(&self, individual: &str) -> BTreeSet<&str> {
+    let mut found: BTreeSet<&str> = BTreeSet::new();
+    let mut queue = vec![individual];
+    while let Some(current) = queue.pop() {
+      for x in self.axioms.iter() {
+        let members = match x {
+          Axiom::SameIndividual(members) if members.iter().any(|m| m == current) => members,
+          _ => continue,
+        };
+        for m in members.iter() {
+          if m != individual && found.insert(m) {
+            queue.push(m);
+          }
+        }
+      }
+    }
+    found
+  }
+
+  /// different_from returns the individuals asserted to be different from an individual given as a full IRI
+  pub fn different_from(&self, individual: &str) -> BTreeSet<&str> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::DifferentIndividuals(members) if members.iter().any(|m| m == individual) => {
+          Some(members)
+        }
+        _ => None,
+      })
+      .flatten()
+      .map(|x| x.as_str())
+      .filter(|x| *x != individual)
+      .collect()
+  }
+
+  /// negative_property_assertions_of returns the negative property assertions whose source is
+  /// an individual given as a full IRI
+  pub fn negative_property_assertions_of(&self, individual: &str) -> Vec<&Axiom> {
+    self
+      .axioms
+      .iter()
+      .filter(|x| match x {
+        Axiom::NegativeObjectPropertyAssertion { source, .. }
+        | Axiom::NegativeDataPropertyAssertion { source, .. } => source == individual,
+        _ => false,
+      })
+      .collect()
+  }
+
+  /// has_characteristic determines whether a property, given as a full IRI, has a characteristic
+  pub fn has_characteristic(&self, property: &str, characteristic: PropertyCharacteristic) -> bool {
+    self.characteristics_of(property).contains(&characteristic)
+  }
+
+  pub fn is_functional(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Functional)
+  }
+
+  pub fn is_inverse_functional(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::InverseFunctional)
+  }
+
+  pub fn is_transitive(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Transitive)
+  }
+
+  pub fn is_symmetric(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Symmetric)
+  }
+
+  pub fn is_asymmetric(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Asymmetric)
+  }
+
+  pub fn is_reflexive(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Reflexive)
+  }
+
+  pub fn is_irreflexive(&self, property: &str) -> bool {
+    self.has_characteristic(property, PropertyCharacteristic::Irreflexive)
+  }
+
+  /// super_properties_of returns the direct and indirect super-properties of a property given as
+  /// a full IRI, excluding the property itself
+  pub fn super_properties_of(&self, property: &str) -> BTreeSet<&str> {
+    let mut found: BTreeSet<&str> = BTreeSet::new();
+    let mut queue = vec![property];
+    while let Some(current) = queue.pop() {
+      for x in self.axioms.iter() {
+        if let Axiom::SubPropertyOf {
+          sub_property,
+          super_property,
+        } = x
+        {
+          // properties in a cycle are visited once
+          if sub_property == current && super_property != property && found.insert(super_property) {
+            queue.push(super_property);
+          }
+        }
+      }
+    }
+    found
+  }
+
+  /// inverse_of returns the properties asserted to be inverses of a property given as a full IRI,
+  /// whichever side of `owl:inverseOf` they are on
+  /// example
+  /// with `cco:has_part owl:inverseOf cco:part_of`, `inverse_of(cco:part_of)` contains `cco:has_part`
+  pub fn inverse_of(&self, property: &str) -> BTreeSet<&str> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::InverseObjectProperties {
+          property: p,
+          inverse,
+        } if p == property => Some(inverse),
+        Axiom::InverseObjectProperties {
+          property: p,
+          inverse,
+        } if inverse == property => Some(p),
+        _ => None,
+      })
+      .map(|x| x.as_str())
+      .collect()
+  }
+
+  /// chains_implying returns the property chains whose composition is asserted to be a
+  /// sub-property of a property given as a full IRI, in the order of the properties of each chain
+  /// example
+  /// with `obo:RO_0002170 owl:propertyChainAxiom ( obo:BFO_0000050 obo:RO_0002170 )`,
+  /// `chains_implying(obo:RO_0002170)` returns `[[obo:BFO_0000050, obo:RO_0002170]]`
+  pub fn chains_implying(&self, property: &str) -> Vec<&[String]> {
+    self
+      .axioms
+      .iter()
+      .filter_map(|x| match x {
+        Axiom::SubPropertyChainOf {
+          chain,
+          super_property,
+        } if super_property == property => Some(chain.as_slice()),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// domains_of returns the domains of a property given as a full IRI. With reasoning enabled, the
+  /// domains of its super-properties follow since they constrain the property as well
+  /// example
+  /// with `cco:has_mother rdfs:subPropertyOf cco:has_parent`, the domains of `cco:has_mother`
+  /// include the domains of `cco:has_parent`
+  pub fn domains_of(&self, property: &str, reasoning: Reasoning) -> Vec<&ClassExpression> {
+    self.property_axioms(property, reasoning, |x| match x {
+      Axiom::PropertyDomain { property, domain } => Some((property, domain)),
+      _ => None,
+    })
+  }
+
+  /// ranges_of returns the ranges of a property given as a full IRI. With reasoning enabled, the
+  /// ranges of its super-properties follow
+  pub fn ranges_of(&self, property: &str, reasoning: Reasoning) -> Vec<&ClassExpression> {
+    self.property_axioms(property, reasoning, |x| match x {
+      Axiom::PropertyRange { property, range } => Some((property, range)),
+      _ => None,
+    })
+  }
+
+  // property_axioms returns the class expressions of the axioms about a property, then those
+  // about its super-properties with reasoning enabled, without duplicates
+  fn property_axioms<'a>(
+    &'a self,
+    property: &str,
+    reasoning: Reasoning,
+    select: impl Fn(&'a Axiom) -> Option<(&'a String, &'a ClassExpression)>,
+  ) -> Vec<&'a ClassExpression> {
+    let mut properties = vec![property];
+    if reasoning == Reasoning::Enabled {
+      properties.extend(self.super_properties_of(property));
+    }
+    let mut found: Vec<&ClassExpression> = vec![];
+    for current in properties {
+      for (p, x) in self.axioms.iter().filter_map(&select) {
+        if p == current && !found.contains(&x) {
+          found.push(x);
+        }
+      }
+    }
+    found
+  }
+}
+
+impl RDFDocumentMapperToOwl for TurtleDocument {
+  fn map_to_owl(&self) -> OwlOntology {
+    let graph = self.graph();
+    let parser = ClassExpressionParser::new(&graph);
+    let mut axioms = vec![];
+    for t in graph.triples() {
+      if let Some(axiom) = triple_axiom(&graph, &parser, t) {
+        if !axioms.contains(&axiom) {
+          axioms.push(axiom);
+        }
+      }
+    }
+    let mut ontology = OwlOntology::new(self.ontology_id(), axioms);
+    // the target of an annotated axiom may be a copy of the blank node of the asserted triple,
+    // expressions are compared by structure so both map to the same axiom
+    for annotated in self.annotated_triples() {
+      if let Some(axiom) = triple_axiom(&graph, &parser, &annotated.triple) {
+        ontology.annotate(axiom, annotated.annotations);
+      }
+    }
+    ontology
+  }
+}
+
+// triple_axiom maps a triple, and the nodes it refers to, into an axiom
+fn triple_axiom(
+  graph: &TripleGraph,
+  parser: &ClassExpressionParser,
+  t: &TurtleTriple,
+) -> Option<Axiom> {
+  let axiom = match (t.predicate.as_iri(), &t.object) {
+    (Some(RDF_TYPE), TurtleTerm::Iri(o)) => match o.as_str() {
+      OWL_ALL_DIFFERENT => all_different(graph, &t.subject)?,
+      OWL_ALL_DISJOINT_CLASSES => {
+        let members = graph.object(&t.subject, OWL_MEMBERS)?;
+        Axiom::DisjointClasses(class_list(graph, parser, members)?)
+      }
+      OWL_NEGATIVE_PROPERTY_ASSERTION => negative_property_assertion(graph, &t.subject)?,
+      _ => Axiom::PropertyCharacteristic {
+        property: String::from(t.subject.as_iri()?),
+        characteristic: PropertyCharacteristic::from_iri(o)?,
+      },
+    },
+    (Some(OWL_SAME_AS), TurtleTerm::Iri(o)) => {
+      Axiom::SameIndividual(vec![String::from(t.subject.as_iri()?), o.clone()])
+    }
+    (Some(OWL_DIFFERENT_FROM), TurtleTerm::Iri(o)) => {
+      Axiom::DifferentIndividuals(vec![String::from(t.subject.as_iri()?), o.clone()])
+    }
+    (Some(RDFS_SUB_CLASS_OF), _) => Axiom::SubClassOf {
+      sub_class: parser.parse(&t.subject)?,
+      super_class: parser.parse(&t.object)?,
+    },
+    (Some(OWL_DISJOINT_WITH), _) => {
+      Axiom::DisjointClasses(vec![parser.parse(&t.subject)?, parser.parse(&t.object)?])
+    }
+    (Some(OWL_DISJOINT_UNION_OF), _) => Axiom::DisjointUnion {
+      class: String::from(t.subject.as_iri()?),
+      members: class_list(graph, parser, &t.object)?,
+    },
+    (Some(OWL_EQUIVALENT_CLASS), TurtleTerm::BlankNode(_)) if parser.is_datatype(&t.object) => {
+      Axiom::DatatypeDefinition {
+        datatype: String::from(t.subject.as_iri()?),
+        range: parser.data_range(&t.object)?,
+      }
+    }
+    (Some(OWL_EQUIVALENT_CLASS), _) => {
+      Axiom::EquivalentClasses(vec![parser.parse(&t.subject)?, parser.parse(&t.object)?])
+    }
+    (Some(RDFS_SUB_PROPERTY_OF), TurtleTerm::Iri(o)) => Axiom::SubPropertyOf {
+      sub_property: String::from(t.subject.as_iri()?),
+      super_property: o.clone(),
+    },
+    (Some(OWL_INVERSE_OF), TurtleTerm::Iri(o)) => Axiom::InverseObjectProperties {
+      property: String::from(t.subject.as_iri()?),
+      inverse: o.clone(),
+    },
+    (Some(OWL_PROPERTY_CHAIN_AXIOM), _) => property_chain(graph, t)?,
+    (Some(RDFS_DOMAIN), _) => Axiom::PropertyDomain {
+      property: String::from(t.subject.as_iri()?),
+      domain: parser.parse(&t.object)?,
+    },
+    (Some(RDFS_RANGE), TurtleTerm::BlankNode(_)) if parser.is_datatype(&t.object) => {
+      Axiom::DataPropertyRange {
+        property: String::from(t.subject.as_iri()?),
+        range: parser.data_range(&t.object)?,
+      }
+    }
+    (Some(RDFS_RANGE), _) => Axiom::PropertyRange {
+      property: String::from(t.subject.as_iri()?),
+      range: parser.parse(&t.object)?,
+    },
+    _ => return None,
+  };
+  Some(axiom)
+}
+
+// class_list parses the class expressions of an RDF list
+fn class_list(
+  graph: &TripleGraph,
+  parser: &ClassExpressionParser,
+  list: &TurtleTerm,
+) -> Option<Vec<ClassExpression>> {
+  graph
+    .list(list)?
+    .into_iter()
+    .map(|x| parser.parse(x))
+    .collect()
+}
+
+// property_chain maps an `owl:propertyChainAxiom` whose object is the RDF list of the named
+// properties of the chain
+fn property_chain(graph: &TripleGraph, t: &TurtleTriple) -> Option<Axiom> {
+  let chain = graph
+    .list(&t.object)?
+    .into_iter()
+    .map(|x| x.as_iri().map(String::from))
+    .collect::<Option<Vec<String>>>()?;
+  if chain.is_empty() {
+    return None;
+  }
+  Some(Axiom::SubPropertyChainOf {
+    chain,
+    super_property: String::from(t.subject.as_iri()?),
+  })
+}
+
+// all_different maps an `owl:AllDifferent` node listing its members with `owl:members`,
+// or `owl:distinctMembers` in OWL 1
+fn all_different(graph: &TripleGraph, node: &TurtleTerm) -> Option<Axiom> {
+  let list = graph
+    .object(node, OWL_MEMBERS)
+    .or_else(|| graph.object(node, OWL_DISTINCT_MEMBERS))?;
+  let members = graph
+    .list(list)?
+    .into_iter()
+    .map(|x| x.as_iri().map(String::from))
+    .collect::<Option<Vec<String>>>()?;
+  Some(Axiom::DifferentIndividuals(members))
+}
+
+// negative_property_assertion maps an `owl:NegativePropertyAssertion` node. Its target is an
+// individual for object properties and a literal for data properties
+fn negative_property_assertion(graph: &TripleGraph, node: &TurtleTerm) -> Option<Axiom> {
+  let source = String::from(graph.object(node, OWL_SOURCE_INDIVIDUAL)?.as_iri()?);
+  let property = String::from(graph.object(node, OWL_ASSERTION_PROPERTY)?.as_iri()?);
+  if let Some(target) = graph.object(node, OWL_TARGET_INDIVIDUAL) {
+    return Some(Axiom::NegativeObjectPropertyAssertion {
+      property,
+      source,
+      target: String::from(target.as_iri()?),
+    });
+  }
+  match graph.object(node, OWL_TARGET_VALUE)? {
+    value @ TurtleTerm::Literal { .. } => Some(Axiom::NegativeDataPropertyAssertion {
+      property,
+      source,
+      value: value.clone(),
+    }),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+
+obo:BFO_0000050 rdf:type owl:ObjectProperty , owl:TransitiveProperty .
+obo:RO_0002323 a owl:ObjectProperty , owl:SymmetricProperty , owl:IrreflexiveProperty .
+obo:IAO_0000004 a owl:DatatypeProperty , owl:FunctionalProperty .
+"#;
+
+  const CLASSES: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Acceleration a owl:Class ;
+                 rdfs:subClassOf cco:Motion ,
+                                 [ a owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Velocity ] .
+cco:Agent owl:equivalentClass [ a owl:Class ; owl:unionOf ( cco:Person cco:Organization ) ] .
+"#;
+
+  const INDIVIDUALS: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+ex:JohnDoe owl:sameAs ex:JDoe ; owl:differentFrom ex:JaneDoe .
+ex:JohnD owl:sameAs ex:JDoe .
+[ a owl:AllDifferent ; owl:members ( ex:JohnDoe ex:Richard ex:JaneDoe ) ] .
+[ a owl:NegativePropertyAssertion ;
+  owl:sourceIndividual ex:JohnDoe ;
+  owl:assertionProperty ex:is_parent_of ;
+  owl:targetIndividual ex:JaneDoe ] .
+[ a owl:NegativePropertyAssertion ;
+  owl:sourceIndividual ex:JohnDoe ;
+  owl:assertionProperty ex:has_age ;
+  owl:targetValue 42 ] .
+"#;
+
+  const PROPERTIES: &str = r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:has_relative a owl:ObjectProperty ; rdfs:domain ex:Person ; rdfs:range ex:Person .
+ex:has_parent a owl:ObjectProperty ; rdfs:subPropertyOf ex:has_relative ; rdfs:range ex:Person .
+ex:has_mother a owl:ObjectProperty ; rdfs:subPropertyOf ex:has_parent ;
+  rdfs:range [ a owl:Class ; owl:intersectionOf ( ex:Person ex:Female ) ] .
+ex:has_age a owl:DatatypeProperty ; rdfs:domain ex:Person ; rdfs:range xsd:integer .
+"#;
+
+  #[test]
+  fn should_query_domains_and_ranges() -> std::io::Result<()> {
+    let ontology = load_turtle_document_from_str(PROPERTIES)?.map_to_owl();
+    let ex = |x: &str| format!("http://example.org/{}", x);
+    let person = ClassExpression::Class(ex("Person"));
+    assert!(ontology
+      .domains_of(&ex("has_mother"), Reasoning::Disabled)
+      .is_empty());
+    assert_eq!(
+      ontology.domains_of(&ex("has_mother"), Reasoning::Enabled),
+      vec![&person]
+    );
+    assert_eq!(
+      ontology.ranges_of(&ex("has_mother"), Reasoning::Enabled),
+      vec![
+        &ClassExpression::ObjectIntersectionOf(vec![
+          person.clone(),
+          ClassExpression::Class(ex("Female"))
+        ]),
+        &person
+      ]
+    );
+    assert_eq!(
+      ontology
+        .super_properties_of(&ex("has_mother"))
+        .into_iter()
+        .collect::<Vec<&str>>(),
+      vec![ex("has_parent"), ex("has_relative")]
+    );
+    assert_eq!(
+      ontology.ranges_of(&ex("has_age"), Reasoning::Disabled),
+      vec![&ClassExpression::Class(String::from(
+        "http://www.w3.org/2001/XMLSchema#integer"
+      ))]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_inverse_properties_and_chains() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+obo:BFO_0000051 a owl:ObjectProperty ; owl:inverseOf obo:BFO_0000050 .
+obo:RO_0002170 a owl:ObjectProperty ;
+  owl:propertyChainAxiom ( obo:BFO_0000050 obo:RO_0002170 ) ,
+                         ( obo:RO_0002170 obo:BFO_0000050 ) .
+obo:RO_0002131 owl:propertyChainAxiom ( [ owl:inverseOf obo:BFO_0000050 ] obo:RO_0002131 ) .
+"#,
+    )?;
+    let ontology = document.map_to_owl();
+    let obo = |x: &str| format!("http://purl.obolibrary.org/obo/{}", x);
+    assert_eq!(
+      ontology
+        .inverse_of(&obo("BFO_0000050"))
+        .into_iter()
+        .collect::<Vec<&str>>(),
+      vec![obo("BFO_0000051")]
+    );
+    assert_eq!(
+      ontology
+        .inverse_of(&obo("BFO_0000051"))
+        .into_iter()
+        .collect::<Vec<&str>>(),
+      vec![obo("BFO_0000050")]
+    );
+    let chains = ontology.chains_implying(&obo("RO_0002170"));
+    assert_eq!(chains.len(), 2);
+    assert_eq!(chains[0], &[obo("BFO_0000050"), obo("RO_0002170")][..]);
+    assert!(ontology.chains_implying(&obo("RO_0002131")).is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_disjoint_classes() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+cco:Person owl:disjointWith cco:Organization .
+[ a owl:AllDisjointClasses ; owl:members ( cco:Person cco:Vehicle cco:Facility ) ] .
+cco:Sex owl:disjointUnionOf ( cco:Female cco:Male ) .
+"#,
+    )?;
+    let ontology = document.map_to_owl();
+    let cco = |x: &str| {
+      format!(
+        "http://www.ontologyrepository.com/CommonCoreOntologies/{}",
+        x
+      )
+    };
+    assert_eq!(ontology.axioms.len(), 3);
+    assert_eq!(
+      ontology.axioms[2],
+      Axiom::DisjointUnion {
+        class: cco("Sex"),
+        members: vec![
+          ClassExpression::Class(cco("Female")),
+          ClassExpression::Class(cco("Male"))
+        ],
+      }
+    );
+    // one pair for `owl:disjointWith`, three for the 3 members and one for the union
+    assert_eq!(ontology.disjoint_pairs().len(), 5);
+    assert_eq!(
+      ontology
+        .disjoint_classes_of(&cco("Person"))
+        .into_iter()
+        .filter_map(|x| x.as_class())
+        .collect::<Vec<&str>>(),
+      vec![cco("Organization"), cco("Vehicle"), cco("Facility")]
+    );
+    assert!(ontology.are_disjoint(&cco("Facility"), &cco("Vehicle")));
+    assert!(ontology.are_disjoint(&cco("Male"), &cco("Female")));
+    assert!(!ontology.are_disjoint(&cco("Organization"), &cco("Vehicle")));
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_datatype_restrictions() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+ex:Percentage a rdfs:Datatype ;
+  owl:equivalentClass [ a rdfs:Datatype ; owl:onDatatype xsd:decimal ;
+                        owl:withRestrictions ( [ xsd:minInclusive 0.0 ] [ xsd:maxInclusive 100.0 ] ) ] .
+ex:has_code a owl:DatatypeProperty ;
+  rdfs:range [ a rdfs:Datatype ; owl:onDatatype xsd:string ;
+               owl:withRestrictions ( [ xsd:pattern "[A-Z]{3}" ] ) ] .
+"#,
+    )?;
+    let ontology = document.map_to_owl();
+    let range = ontology
+      .datatype_definition("http://example.org/Percentage")
+      .unwrap();
+    match range {
+      DataRange::DatatypeRestriction {
+        datatype,
+        restrictions,
+      } => {
+        assert_eq!(datatype, "http://www.w3.org/2001/XMLSchema#decimal");
+        assert_eq!(restrictions.len(), 2);
+        assert_eq!(
+          restrictions[1].facet,
+          "http://www.w3.org/2001/XMLSchema#maxInclusive"
+        );
+      }
+      _ => panic!("expected a datatype restriction"),
+    }
+    let ranges = ontology.data_ranges_of("http://example.org/has_code");
+    assert_eq!(ranges.len(), 1);
+    assert!(ontology
+      .ranges_of("http://example.org/has_code", Reasoning::Disabled)
+      .is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn should_list_general_class_axioms() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Car rdfs:subClassOf ex:Vehicle ,
+  [ a owl:Restriction ; owl:onProperty ex:has_part ; owl:someValuesFrom ex:Wheel ] .
+[ a owl:Restriction ; owl:onProperty ex:has_part ; owl:someValuesFrom ex:Engine ]
+  rdfs:subClassOf ex:Machine .
+[ a owl:Class ; owl:unionOf ( ex:Car ex:Truck ) ]
+  owl:equivalentClass [ a owl:Class ; owl:intersectionOf ( ex:Vehicle ex:Motorized ) ] .
+"#,
+    )?;
+    let ontology = document.map_to_owl();
+    assert_eq!(ontology.axioms.len(), 4);
+    let axioms = ontology.general_class_axioms();
+    assert_eq!(axioms.len(), 2);
+    match axioms[0] {
+      Axiom::SubClassOf { super_class, .. } => {
+        assert_eq!(super_class.as_class(), Some("http://example.org/Machine"))
+      }
+      _ => panic!("expected a subclass axiom"),
+    }
+    assert!(matches!(axioms[1], Axiom::EquivalentClasses(_)));
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_property_characteristics() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let ontology = document.map_to_owl();
+    assert_eq!(ontology.axioms.len(), 4);
+    assert!(ontology.is_transitive("http://purl.obolibrary.org/obo/BFO_0000050"));
+    assert!(!ontology.is_symmetric("http://purl.obolibrary.org/obo/BFO_0000050"));
+    assert_eq!(
+      ontology
+        .characteristics_of("http://purl.obolibrary.org/obo/RO_0002323")
+        .into_iter()
+        .collect::<Vec<PropertyCharacteristic>>(),
+      vec![
+        PropertyCharacteristic::Symmetric,
+        PropertyCharacteristic::Irreflexive
+      ]
+    );
+    assert!(ontology.is_functional("http://purl.obolibrary.org/obo/IAO_0000004"));
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_class_axioms() -> std::io::Result<()> {
+    let ontology = load_turtle_document_from_str(CLASSES)?.map_to_owl();
+    let cco = |x: &str| {
+      format!(
+        "http://www.ontologyrepository.com/CommonCoreOntologies/{}",
+        x
+      )
+    };
+    let parents = ontology.super_classes_of(&cco("Acceleration"));
+    assert_eq!(parents.len(), 2);
+    assert_eq!(parents[0].as_class(), Some(cco("Motion").as_str()));
+    assert_eq!(
+      parents[1],
+      &ClassExpression::ObjectSomeValuesFrom {
+        property: cco("has_process_part"),
+        filler: Box::new(ClassExpression::Class(cco("Velocity"))),
+      }
+    );
+    assert_eq!(
+      ontology.equivalent_classes_of(&cco("Agent")),
+      vec![&ClassExpression::ObjectUnionOf(vec![
+        ClassExpression::Class(cco("Person")),
+        ClassExpression::Class(cco("Organization"))
+      ])]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_map_individual_axioms() -> std::io::Result<()> {
+    let ontology = load_turtle_document_from_str(INDIVIDUALS)?.map_to_owl();
+    let ex = |x: &str| format!("http://example.org/{}", x);
+    assert_eq!(
+      ontology
+        .same_individuals(&ex("JohnDoe"))
+        .into_iter()
+        .collect::<Vec<&str>>(),
+      vec![ex("JDoe"), ex("JohnD")]
+    );
+    assert_eq!(
+      ontology
+        .different_from(&ex("JohnDoe"))
+        .into_iter()
+        .collect::<Vec<&str>>(),
+      vec![ex("JaneDoe"), ex("Richard")]
+    );
+    assert_eq!(ontology.different_from(&ex("Richard")).len(), 2);
+    assert_eq!(
+      ontology.negative_property_assertions_of(&ex("JohnDoe")),
+      vec![
+        &Axiom::NegativeObjectPropertyAssertion {
+          property: ex("is_parent_of"),
+          source: ex("JohnDoe"),
+          target: ex("JaneDoe"),
+        },
+        &Axiom::NegativeDataPropertyAssertion {
+          property: ex("has_age"),
+          source: ex("JohnDoe"),
+          value: TurtleTerm::Literal {
+            value: String::from("42"),
+            datatype: String::from("http://www.w3.org/2001/XMLSchema#integer"),
+            language: None,
+          },
+        }
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_round_trip_characteristic_iri() {
+    assert_eq!(
+      PropertyCharacteristic::from_iri(PropertyCharacteristic::Asymmetric.iri()),
+      Some(PropertyCharacteristic::Asymmetric)
+    );
+    assert_eq!(
+      PropertyCharacteristic::from_iri("http://www.w3.org/2002/07/owl#Class"),
+      None
+    );
+  }
+
+  #[test]
+  fn should_map_axiom_annotations() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Acceleration rdfs:subClassOf [ a owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Velocity ] .
+[ a owl:Axiom ;
+  owl:annotatedSource cco:Acceleration ;
+  owl:annotatedProperty rdfs:subClassOf ;
+  owl:annotatedTarget [ a owl:Restriction ;
+                        owl:onProperty cco:has_process_part ;
+                        owl:someValuesFrom cco:Velocity ] ;
+  rdfs:comment "the rate of change of velocity" ] .
+"#,
+    )?;
+    let ontology = document.map_to_owl();
+    // the copy of the restriction is the same axiom
+    assert_eq!(ontology.axioms.len(), 1);
+    let annotations = ontology.annotations_of(&ontology.axioms[0]);
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(
+      annotations[0].property,
+      "http://www.w3.org/2000/01/rdf-schema#comment"
+    );
+    Ok(())
+  }
 }
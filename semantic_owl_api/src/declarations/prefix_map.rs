@@ -0,0 +1,561 @@
+//! Prefix map module resolves prefixed names and relative IRIs of a turtle document
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::loader::parsers::ttl_statement_parser::{split_literal, split_quoted_triple};
+use crate::vocab::{rdf, xsd};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+const RDF_LANG_STRING: &str = rdf::LANG_STRING.as_str();
+
+// characters ending the IRI of a JSON-LD term usable as a prefix
+const GEN_DELIMS: [char; 7] = [':', '/', '?', '#', '[', ']', '@'];
+
+/// PrefixMap maps prefix names to namespace IRIs. It optionally holds the base IRI
+/// against which relative IRIs are resolved.
+/// Namespace IRIs and the base are stored without angle brackets.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PrefixMap {
+  pub base: Option<String>,
+  pub prefixes: BTreeMap<String, String>,
+}
+
+impl PrefixMap {
+  pub fn new() -> PrefixMap {
+    Self::default()
+  }
+
+  /// insert adds or replaces the namespace of a prefix.
+  /// example
+  /// `insert("owl", "http://www.w3.org/2002/07/owl#")`
+  pub fn insert(&mut self, prefix: &str, namespace: &str) {
+    self
+      .prefixes
+      .insert(String::from(prefix), String::from(namespace));
+  }
+
+  /// namespace returns the namespace IRI of a prefix
+  pub fn namespace(&self, prefix: &str) -> Option<&str> {
+    self.prefixes.get(prefix).map(|x| x.as_str())
+  }
+
+  /// expand returns the full IRI of a prefixed name
+  /// example
+  /// `owl:Class` returns Option of `http://www.w3.org/2002/07/owl#Class`
+  pub fn expand(&self, prefixed_name: &str) -> Option<String> {
+    let idx = prefixed_name.find(':')?;
+    let (prefix, local) = (&prefixed_name[..idx], &prefixed_name[idx + 1..]);
+    let namespace = self.namespace(prefix)?;
+    Some(format!("{}{}", namespace, unescape_local(local)))
+  }
+
+  /// shorten returns the prefixed name of an IRI using the longest matching namespace
+  /// example
+  /// `http://www.w3.org/2002/07/owl#Class` returns Option of `owl:Class`
+  pub fn shorten(&self, iri: &str) -> Option<String> {
+    self
+      .prefixes
+      .iter()
+      .filter(|(_, ns)| !ns.is_empty() && iri.starts_with(ns.as_str()))
+      .max_by_key(|(_, ns)| ns.len())
+      .map(|(prefix, ns)| format!("{}:{}", prefix, &iri[ns.len()..]))
+  }
+
+  /// resolve_iri resolves an IRI, with or without angle brackets, against the base IRI
+  /// example
+  /// `<#Agent>` with base `http://example.org/agents` returns `http://example.org/agents#Agent`
+  pub fn resolve_iri(&self, iri: &str) -> String {
+    let iri = iri.strip_prefix('<').unwrap_or(iri);
+    let iri = iri.strip_suffix('>').unwrap_or(iri);
+    match &self.base {
+      Some(base) if !has_scheme(iri) => resolve_relative(base, iri),
+      _ => String::from(iri),
+    }
+  }
+
+  /// resolve_term resolves a term as it appears in a turtle document into a `TurtleTerm`
+  /// example
+  /// `"agent in"@en`, `cco:Agent`, `<http://example.org/>`, `_:b0` or `<< cco:JohnDoe a cco:Person >>`
+  pub fn resolve_term(&self, raw: &str) -> Option<TurtleTerm> {
+    let raw = raw.trim();
+    if raw.starts_with("<<") {
+      let (s, p, o) = split_quoted_triple(raw)?;
+      let triple = TurtleTriple::new(
+        self.resolve_term(&s)?,
+        self.resolve_term(&p)?,
+        self.resolve_term(&o)?,
+      );
+      return Some(TurtleTerm::QuotedTriple(Box::new(triple)));
+    }
+    if raw.starts_with('<') {
+      return Some(TurtleTerm::Iri(self.resolve_iri(raw)));
+    }
+    if let Some(label) = raw.strip_prefix("_:") {
+      return Some(TurtleTerm::BlankNode(String::from(label)));
+    }
+    if let Some((value, datatype, language)) = split_literal(raw) {
+      let datatype = match (&datatype, &language) {
+        (Some(d), _) if d.starts_with('<') => self.resolve_iri(d),
+        (Some(d), _) => self.expand(d)?,
+        (None, Some(_)) => String::from(RDF_LANG_STRING),
+        (None, None) => String::from(XSD_STRING),
+      };
+      return Some(TurtleTerm::Literal {
+        value,
+        datatype,
+        language,
+      });
+    }
+    self.expand(raw).map(TurtleTerm::Iri)
+  }
+
+  /// to_sparql writes the base and the prefixes as the prologue of a SPARQL query
+  /// example
+  /// `PREFIX owl: <http://www.w3.org/2002/07/owl#>`
+  pub fn to_sparql(&self) -> String {
+    let mut out = String::new();
+    if let Some(base) = &self.base {
+      out.push_str(&format!("BASE <{}>\n", base));
+    }
+    for (prefix, namespace) in self.prefixes.iter() {
+      out.push_str(&format!("PREFIX {}: <{}>\n", prefix, namespace));
+    }
+    out
+  }
+
+  /// from_sparql reads the `BASE` and `PREFIX` declarations of the prologue of a SPARQL query.
+  /// Reading stops at the first clause which is not a declaration, e.g `SELECT`.
+  /// It will return an error if a declaration is malformed
+  pub fn from_sparql(query: &str) -> std::io::Result<PrefixMap> {
+    let mut map = PrefixMap::new();
+    let mut rest = query.trim_start();
+    loop {
+      if rest.starts_with('#') {
+        rest = rest
+          .split_once('\n')
+          .map(|(_, x)| x)
+          .unwrap_or_default()
+          .trim_start();
+        continue;
+      }
+      let keyword = rest.get(..6).map(|x| x.to_ascii_uppercase());
+      let (prefix, declaration) = match keyword.as_deref() {
+        Some("PREFIX") => {
+          let declaration = rest[6..].trim_start();
+          let (prefix, iri) = declaration
+            .split_once(':')
+            .ok_or_else(|| malformed_declaration(rest))?;
+          (Some(prefix.trim()), iri)
+        }
+        _ if rest.get(..4).map(|x| x.to_ascii_uppercase()).as_deref() == Some("BASE") => {
+          (None, &rest[4..])
+        }
+        _ => return Ok(map),
+      };
+      let declaration = declaration.trim_start();
+      let end = match declaration.starts_with('<') {
+        true => declaration
+          .find('>')
+          .ok_or_else(|| malformed_declaration(rest))?,
+        false => return Err(malformed_declaration(rest)),
+      };
+      let iri = &declaration[1..end];
+      match prefix {
+        Some(prefix) => map.insert(prefix, iri),
+        None => map.base = Some(map.resolve_iri(iri)),
+      }
+      rest = declaration[end + 1..].trim_start();
+    }
+  }
+
+  /// to_json_ld_context writes the base and the prefixes as a JSON-LD `@context` object.
+  /// The namespace of the empty prefix is written as the `@vocab` of the context, since a JSON-LD
+  /// term can not be empty
+  /// example
+  /// `{"@base": "http://example.org/", "owl": "http://www.w3.org/2002/07/owl#"}`
+  pub fn to_json_ld_context(&self) -> Value {
+    let mut context = Map::new();
+    if let Some(base) = &self.base {
+      context.insert(String::from("@base"), Value::from(base.as_str()));
+    }
+    for (prefix, namespace) in self.prefixes.iter() {
+      let term = match prefix.is_empty() {
+        true => String::from("@vocab"),
+        false => prefix.clone(),
+      };
+      context.insert(term, Value::from(namespace.as_str()));
+    }
+    Value::Object(context)
+  }
+
+  /// from_json_ld_context reads the prefixes of a JSON-LD context, given as an `@context` object,
+  /// an array of such objects or a document holding an `@context`. Terms are prefixes when their
+  /// IRI ends with a character such as `/` or `#`, or when they are defined with `"@prefix": true`.
+  /// It will return an error if the context refers to a remote context, which can not be loaded
+  pub fn from_json_ld_context(context: &Value) -> std::io::Result<PrefixMap> {
+    let mut map = PrefixMap::new();
+    let context = context.get("@context").unwrap_or(context);
+    let contexts = match context {
+      Value::Array(x) => x.iter().collect(),
+      x => vec![x],
+    };
+    for context in contexts {
+      let definitions = match context {
+        Value::Object(x) => x,
+        Value::Null => continue,
+        x => {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("the remote context {} can not be loaded", x),
+          ))
+        }
+      };
+      for (term, definition) in definitions.iter() {
+        let iri = match (term.as_str(), definition) {
+          ("@base", Value::String(x)) => {
+            map.base = Some(x.clone());
+            continue;
+          }
+          ("@vocab", Value::String(x)) => {
+            map.insert("", x);
+            continue;
+          }
+          // compact IRIs and keywords are not prefixes
+          (x, _) if x.contains(':') || x.starts_with('@') => continue,
+          (_, Value::String(x)) if x.ends_with(&GEN_DELIMS[..]) => x,
+          (_, Value::Object(x)) if x.get("@prefix") == Some(&Value::Bool(true)) => {
+            match x.get("@id") {
+              Some(Value::String(x)) => x,
+              _ => continue,
+            }
+          }
+          _ => continue,
+        };
+        map.insert(term, iri);
+      }
+    }
+    Ok(map)
+  }
+}
+
+fn malformed_declaration(declaration: &str) -> std::io::Error {
+  let line = declaration.lines().next().unwrap_or_default();
+  std::io::Error::new(
+    std::io::ErrorKind::InvalidData,
+    format!("malformed SPARQL declaration `{}`", line),
+  )
+}
+
+// has_scheme determines whether an IRI is absolute, that is, it begins with a scheme
+pub(crate) fn has_scheme(iri: &str) -> bool {
+  match iri.find(':') {
+    Some(idx) => {
+      let scheme = &iri[..idx];
+      !scheme.is_empty()
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    }
+    None => false,
+  }
+}
+
+// resolve_relative resolves a relative reference against a base IRI as in RFC 3986 section 5.2
+fn resolve_relative(base: &str, iri: &str) -> String {
+  let base = IriParts::new(base);
+  let reference = IriParts::new(iri);
+  let (authority, path, query) = match (reference.authority, reference.path) {
+    (Some(authority), path) => (Some(authority), remove_dot_segments(path), reference.query),
+    (None, "") => (
+      base.authority,
+      String::from(base.path),
+      reference.query.or(base.query),
+    ),
+    (None, path) if path.starts_with('/') => {
+      (base.authority, remove_dot_segments(path), reference.query)
+    }
+    (None, path) => {
+      // merge the reference with the base path up to its last segment
+      let merged = match (base.authority, base.path.rfind('/')) {
+        (Some(_), None) if base.path.is_empty() => format!("/{}", path),
+        (_, Some(idx)) => format!("{}{}", &base.path[..idx + 1], path),
+        (_, None) => String::from(path),
+      };
+      (
+        base.authority,
+        remove_dot_segments(&merged),
+        reference.query,
+      )
+    }
+  };
+  let mut target = String::new();
+  if let Some(scheme) = base.scheme {
+    target.push_str(scheme);
+    target.push(':');
+  }
+  if let Some(authority) = authority {
+    target.push_str("//");
+    target.push_str(authority);
+  }
+  target.push_str(&path);
+  if let Some(query) = query {
+    target.push('?');
+    target.push_str(query);
+  }
+  if let Some(fragment) = reference.fragment {
+    target.push('#');
+    target.push_str(fragment);
+  }
+  target
+}
+
+// IriParts are the components of an IRI reference, as in RFC 3986 section 3
+struct IriParts<'a> {
+  scheme: Option<&'a str>,
+  authority: Option<&'a str>,
+  path: &'a str,
+  query: Option<&'a str>,
+  fragment: Option<&'a str>,
+}
+
+impl<'a> IriParts<'a> {
+  fn new(iri: &'a str) -> IriParts<'a> {
+    let (rest, fragment) = match iri.split_once('#') {
+      Some((rest, fragment)) => (rest, Some(fragment)),
+      None => (iri, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+      Some((rest, query)) => (rest, Some(query)),
+      None => (rest, None),
+    };
+    let (scheme, rest) = match has_scheme(rest) {
+      true => match rest.split_once(':') {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, rest),
+      },
+      false => (None, rest),
+    };
+    let (authority, path) = match rest.strip_prefix("//") {
+      Some(rest) => match rest.find('/') {
+        Some(idx) => (Some(&rest[..idx]), &rest[idx..]),
+        None => (Some(rest), ""),
+      },
+      None => (None, rest),
+    };
+    Self {
+      scheme,
+      authority,
+      path,
+      query,
+      fragment,
+    }
+  }
+}
+
+// remove_dot_segments removes the `.` and `..` segments of a path, as in RFC 3986 section 5.2.4
+// e.g -> /a/b/c/./../../g becomes /a/g
+fn remove_dot_segments(path: &str) -> String {
+  let mut input = path;
+  let mut output = String::new();
+  while !input.is_empty() {
+    if let Some(rest) = input
+      .strip_prefix("../")
+      .or_else(|| input.strip_prefix("./"))
+    {
+      input = rest;
+    } else if input.starts_with("/./") {
+      input = &input[2..];
+    } else if input == "/." {
+      input = "/";
+    } else if input.starts_with("/../") || input == "/.." {
+      input = match input.len() {
+        3 => "/",
+        _ => &input[3..],
+      };
+      let last = output.rfind('/').unwrap_or(0);
+      output.truncate(last);
+    } else if input == "." || input == ".." {
+      input = "";
+    } else {
+      // move the first segment, with its leading `/` if any, to the output
+      let start = usize::from(input.starts_with('/'));
+      let end = input[start..]
+        .find('/')
+        .map(|x| x + start)
+        .unwrap_or(input.len());
+      output.push_str(&input[..end]);
+      input = &input[end..];
+    }
+  }
+  output
+}
+
+// unescape_local removes the `\` of escaped characters in a local name
+fn unescape_local(local: &str) -> String {
+  local.replace('\\', "")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn prefix_map() -> PrefixMap {
+    let mut map = PrefixMap::new();
+    map.base = Some(String::from("http://example.org/agents/AgentOntology"));
+    map.insert("owl", "http://www.w3.org/2002/07/owl#");
+    map.insert("xsd", "http://www.w3.org/2001/XMLSchema#");
+    map.insert("", "http://example.org/agents#");
+    map
+  }
+
+  #[test]
+  fn should_expand_and_shorten_prefixed_names() {
+    let map = prefix_map();
+    assert_eq!(
+      map.expand("owl:Class"),
+      Some(String::from("http://www.w3.org/2002/07/owl#Class"))
+    );
+    assert_eq!(
+      map.expand(":Agent"),
+      Some(String::from("http://example.org/agents#Agent"))
+    );
+    assert_eq!(map.expand("ex:foo\\.bar"), None);
+    assert_eq!(
+      map.shorten("http://www.w3.org/2002/07/owl#Class"),
+      Some(String::from("owl:Class"))
+    );
+    assert_eq!(map.shorten("http://example.com/x"), None);
+  }
+
+  #[test]
+  fn should_resolve_relative_iris() {
+    let map = prefix_map();
+    assert_eq!(
+      map.resolve_iri("<#Agent>"),
+      "http://example.org/agents/AgentOntology#Agent"
+    );
+    assert_eq!(
+      map.resolve_iri("<Person>"),
+      "http://example.org/agents/Person"
+    );
+    assert_eq!(map.resolve_iri("</Person>"), "http://example.org/Person");
+    assert_eq!(map.resolve_iri("<urn:x:y>"), "urn:x:y");
+  }
+
+  #[test]
+  fn should_resolve_rfc_3986_examples() {
+    let mut map = PrefixMap::new();
+    map.base = Some(String::from("http://a/b/c/d;p?q"));
+    // RFC 3986 section 5.4.1, normal examples, then section 5.4.2, abnormal examples
+    let examples = [
+      ("g:h", "g:h"),
+      ("g", "http://a/b/c/g"),
+      ("./g", "http://a/b/c/g"),
+      ("g/", "http://a/b/c/g/"),
+      ("/g", "http://a/g"),
+      ("//g", "http://g"),
+      ("?y", "http://a/b/c/d;p?y"),
+      ("g?y", "http://a/b/c/g?y"),
+      ("#s", "http://a/b/c/d;p?q#s"),
+      ("g#s", "http://a/b/c/g#s"),
+      ("g?y#s", "http://a/b/c/g?y#s"),
+      (";x", "http://a/b/c/;x"),
+      ("g;x", "http://a/b/c/g;x"),
+      ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+      ("", "http://a/b/c/d;p?q"),
+      (".", "http://a/b/c/"),
+      ("./", "http://a/b/c/"),
+      ("..", "http://a/b/"),
+      ("../", "http://a/b/"),
+      ("../g", "http://a/b/g"),
+      ("../..", "http://a/"),
+      ("../../", "http://a/"),
+      ("../../g", "http://a/g"),
+      ("../../../g", "http://a/g"),
+      ("../../../../g", "http://a/g"),
+      ("/./g", "http://a/g"),
+      ("/../g", "http://a/g"),
+      ("g.", "http://a/b/c/g."),
+      (".g", "http://a/b/c/.g"),
+      ("g..", "http://a/b/c/g.."),
+      ("..g", "http://a/b/c/..g"),
+      ("./../g", "http://a/b/g"),
+      ("./g/.", "http://a/b/c/g/"),
+      ("g/./h", "http://a/b/c/g/h"),
+      ("g/../h", "http://a/b/c/h"),
+      ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+      ("g;x=1/../y", "http://a/b/c/y"),
+      ("g?y/./x", "http://a/b/c/g?y/./x"),
+      ("g?y/../x", "http://a/b/c/g?y/../x"),
+      ("g#s/./x", "http://a/b/c/g#s/./x"),
+      ("g#s/../x", "http://a/b/c/g#s/../x"),
+      ("http:g", "http:g"),
+    ];
+    for (reference, target) in examples {
+      assert_eq!(map.resolve_iri(reference), target, "{}", reference);
+    }
+  }
+
+  #[test]
+  fn should_convert_to_sparql_and_json_ld_prefixes() -> std::io::Result<()> {
+    let map = prefix_map();
+    let query = format!("{}# agents\nprefix rdfs:<http://www.w3.org/2000/01/rdf-schema#> SELECT ?s WHERE {{ ?s a owl:Class }}", map.to_sparql());
+    assert!(query.starts_with(
+      "BASE <http://example.org/agents/AgentOntology>\nPREFIX : <http://example.org/agents#>\n"
+    ));
+    let read = PrefixMap::from_sparql(&query)?;
+    assert_eq!(read.base, map.base);
+    assert_eq!(read.prefixes.len(), 4);
+    assert_eq!(
+      read.namespace("rdfs"),
+      Some("http://www.w3.org/2000/01/rdf-schema#")
+    );
+    assert!(PrefixMap::from_sparql("PREFIX owl http://www.w3.org/2002/07/owl#").is_err());
+
+    let context = map.to_json_ld_context();
+    assert_eq!(context["@vocab"], "http://example.org/agents#");
+    assert_eq!(PrefixMap::from_json_ld_context(&context)?, map);
+    let document = serde_json::json!({ "@context": [
+      { "label": "http://www.w3.org/2000/01/rdf-schema#label", "skos": "http://www.w3.org/2004/02/skos/core#" },
+      { "obo": { "@id": "http://purl.obolibrary.org/obo/", "@prefix": true }, "owl:Thing": "http://www.w3.org/2002/07/owl#Thing" }
+    ]});
+    let read = PrefixMap::from_json_ld_context(&document)?;
+    assert_eq!(
+      read.prefixes.keys().collect::<Vec<_>>(),
+      vec!["obo", "skos"]
+    );
+    assert!(PrefixMap::from_json_ld_context(
+      &serde_json::json!({ "@context": "https://schema.org/" })
+    )
+    .is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn should_resolve_terms() {
+    let map = prefix_map();
+    assert_eq!(
+      map.resolve_term("\"v\"^^xsd:anyURI"),
+      Some(TurtleTerm::Literal {
+        value: String::from("v"),
+        datatype: String::from("http://www.w3.org/2001/XMLSchema#anyURI"),
+        language: None
+      })
+    );
+    assert_eq!(
+      map.resolve_term("\"agent\"@en"),
+      Some(TurtleTerm::Literal {
+        value: String::from("agent"),
+        datatype: String::from(RDF_LANG_STRING),
+        language: Some(String::from("en"))
+      })
+    );
+    assert_eq!(
+      map.resolve_term("_:b0"),
+      Some(TurtleTerm::BlankNode(String::from("b0")))
+    );
+    assert_eq!(map.resolve_term("unknown:x"), None);
+  }
+}
@@ -0,0 +1,73 @@
+//! Provenance module records where a loaded document and its statements come from
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// version of the parser which loads documents
+pub const PARSER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// DocumentProvenance describes the artifact a document was loaded from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentProvenance {
+  // path or URL the document was loaded from
+  pub source: String,
+
+  // size in bytes of the artifact as stored, i.e before any decompression
+  pub size: u64,
+
+  // lowercase hexadecimal SHA-256 digest of the artifact as stored
+  pub sha256: String,
+
+  // seconds since the UNIX epoch at which loading completed
+  pub loaded_at: u64,
+
+  // version of `semantic_owl_api` which parsed the document. e.g -> 0.0.1
+  pub parser_version: String,
+}
+
+impl DocumentProvenance {
+  pub fn new(
+    source: &str,
+    size: u64,
+    sha256: &str,
+    loaded_at: u64,
+    parser_version: &str,
+  ) -> DocumentProvenance {
+    Self {
+      source: String::from(source),
+      size,
+      sha256: String::from(sha256),
+      loaded_at,
+      parser_version: String::from(parser_version),
+    }
+  }
+
+  /// loaded_now records an artifact loaded at the current time by this version of the parser
+  pub fn loaded_now(source: &str, size: u64, sha256: &str) -> DocumentProvenance {
+    let loaded_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|x| x.as_secs())
+      .unwrap_or_default();
+    Self::new(source, size, sha256, loaded_at, PARSER_VERSION)
+  }
+}
+
+/// SourceSpan locates a statement in the document it was parsed from. The triples of a statement,
+/// including those of its nested blank nodes and collections, share the span of the statement
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceSpan {
+  // path or URL of the document. `None` for documents loaded from memory
+  pub file: Option<String>,
+
+  // lines of the statement, counted from 1. e.g -> 12..14 for a statement on lines 12 and 13
+  pub lines: Range<usize>,
+
+  // byte range of the statement in the document, from its subject to its terminator
+  pub bytes: Range<usize>,
+}
+
+impl SourceSpan {
+  pub fn new(file: Option<String>, lines: Range<usize>, bytes: Range<usize>) -> SourceSpan {
+    Self { file, lines, bytes }
+  }
+}
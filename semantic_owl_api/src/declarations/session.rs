@@ -0,0 +1,210 @@
+//! Session module groups the changes of an indexed document into transactions
+use crate::declarations::index::{Change, IndexedDocument};
+use crate::declarations::turtle::TurtleDocument;
+
+/// EditSession edits a document with transactions and an undo/redo history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditSession {
+  pub editor: IndexedDocument,
+
+  // changes applied since `begin`. `None` outside a transaction
+  transaction: Option<Vec<Change>>,
+
+  // committed change lists, the latest last
+  undo: Vec<Vec<Change>>,
+  redo: Vec<Vec<Change>>,
+}
+
+impl EditSession {
+  /// new starts a session on a document, with an empty history
+  pub fn new(document: TurtleDocument) -> EditSession {
+    Self {
+      editor: IndexedDocument::new(document),
+      ..Default::default()
+    }
+  }
+
+  /// begin opens a transaction. It fails if a transaction is already open
+  pub fn begin(&mut self) -> std::io::Result<()> {
+    if self.transaction.is_some() {
+      return Err(transaction_error("a transaction is already open"));
+    }
+    self.transaction = Some(vec![]);
+    Ok(())
+  }
+
+  /// apply applies a change to the document, within the open transaction if any. It returns
+  /// `false` if the change is a no-op, in which case it is not recorded
+  pub fn apply(&mut self, change: &Change) -> bool {
+    if !self.editor.apply(change) {
+      return false;
+    }
+    match self.transaction.as_mut() {
+      Some(changes) => changes.push(change.clone()),
+      None => self.record(vec![change.clone()]),
+    }
+    true
+  }
+
+  /// commit closes the open transaction, recording its changes in the history. It fails if no
+  /// transaction is open
+  pub fn commit(&mut self) -> std::io::Result<()> {
+    let changes = self
+      .transaction
+      .take()
+      .ok_or_else(|| transaction_error("no transaction is open"))?;
+    self.record(changes);
+    Ok(())
+  }
+
+  /// rollback closes the open transaction, reverting its changes. It fails if no transaction is
+  /// open
+  pub fn rollback(&mut self) -> std::io::Result<()> {
+    let changes = self
+      .transaction
+      .take()
+      .ok_or_else(|| transaction_error("no transaction is open"))?;
+    self.revert(&changes);
+    Ok(())
+  }
+
+  /// undo reverts the latest committed change list. It returns `false` if there is nothing to
+  /// undo, and fails while a transaction is open
+  pub fn undo(&mut self) -> std::io::Result<bool> {
+    self.check_closed()?;
+    let changes = match self.undo.pop() {
+      Some(x) => x,
+      None => return Ok(false),
+    };
+    self.revert(&changes);
+    self.redo.push(changes);
+    Ok(true)
+  }
+
+  /// redo applies again the latest undone change list. It returns `false` if there is nothing to
+  /// redo, and fails while a transaction is open
+  pub fn redo(&mut self) -> std::io::Result<bool> {
+    self.check_closed()?;
+    let changes = match self.redo.pop() {
+      Some(x) => x,
+      None => return Ok(false),
+    };
+    for change in changes.iter() {
+      self.editor.apply(change);
+    }
+    self.undo.push(changes);
+    Ok(true)
+  }
+
+  pub fn can_undo(&self) -> bool {
+    self.transaction.is_none() && !self.undo.is_empty()
+  }
+
+  pub fn can_redo(&self) -> bool {
+    self.transaction.is_none() && !self.redo.is_empty()
+  }
+
+  /// in_transaction determines whether a transaction is open
+  pub fn in_transaction(&self) -> bool {
+    self.transaction.is_some()
+  }
+
+  /// history returns the committed change lists which can be undone, the oldest first
+  pub fn history(&self) -> &[Vec<Change>] {
+    &self.undo
+  }
+
+  // record adds committed changes to the history. New changes make the undone ones unreachable
+  fn record(&mut self, changes: Vec<Change>) {
+    if changes.is_empty() {
+      return;
+    }
+    self.undo.push(changes);
+    self.redo.clear();
+  }
+
+  // revert applies the inverse of changes, the latest first
+  fn revert(&mut self, changes: &[Change]) {
+    for change in changes.iter().rev() {
+      self.editor.apply(&change.inverse());
+    }
+  }
+
+  fn check_closed(&self) -> std::io::Result<()> {
+    match self.transaction {
+      Some(_) => Err(transaction_error(
+        "the history can not be browsed while a transaction is open",
+      )),
+      None => Ok(()),
+    }
+  }
+}
+
+fn transaction_error(message: &str) -> std::io::Error {
+  std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::vocab::rdfs;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class .
+cco:Person a owl:Class ; rdfs:subClassOf cco:Agent .
+"#;
+
+  #[test]
+  fn should_undo_and_redo_transactions() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let mut session = EditSession::new(document.clone());
+    let cco = |x: &str| {
+      TurtleTerm::Iri(format!(
+        "http://www.ontologyrepository.com/CommonCoreOntologies/{}",
+        x
+      ))
+    };
+    let subclass_of =
+      |x: &str, y: &str| TurtleTriple::new(cco(x), rdfs::SUB_CLASS_OF.into(), cco(y));
+    let agent = "http://www.ontologyrepository.com/CommonCoreOntologies/Agent";
+
+    session.begin()?;
+    assert!(session.begin().is_err());
+    assert!(session.apply(&Change::Remove(subclass_of("Person", "Agent"))));
+    assert!(session.apply(&Change::Add(subclass_of("Role", "Agent"))));
+    assert!(session.undo().is_err());
+    session.rollback()?;
+    assert_eq!(session.editor.document.triples(), document.triples());
+    assert!(!session.can_undo());
+
+    session.begin()?;
+    session.apply(&Change::Add(subclass_of("Role", "Agent")));
+    session.apply(&Change::Add(subclass_of("Organization", "Agent")));
+    session.commit()?;
+    session.apply(&Change::Remove(subclass_of("Person", "Agent")));
+    assert_eq!(session.history().len(), 2);
+    assert_eq!(session.editor.index.sub_classes(agent).len(), 2);
+
+    assert!(session.undo()?);
+    assert!(session.undo()?);
+    assert!(!session.undo()?);
+    assert_eq!(
+      session.editor.index.sub_classes(agent),
+      vec!["http://www.ontologyrepository.com/CommonCoreOntologies/Person"]
+    );
+    assert!(session.redo()?);
+    assert_eq!(session.editor.index.sub_classes(agent).len(), 3);
+
+    // a new change drops the changes left to redo
+    session.apply(&Change::Add(subclass_of("Group", "Agent")));
+    assert!(!session.can_redo());
+    assert!(session.commit().is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,129 @@
+//! Shared module shares documents and ontologies between threads
+use crate::declarations::owl::OwlOntology;
+use crate::declarations::turtle::TurtleDocument;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// SharedDocument publishes versions of a document to many threads
+pub type SharedDocument = SharedHandle<TurtleDocument>;
+
+/// SharedOntology publishes versions of an ontology to many threads
+pub type SharedOntology = SharedHandle<OwlOntology>;
+
+/// SharedHandle holds the current version of a value shared between threads. Readers are only
+/// blocked for as long as it takes to swap a version, never for the duration of an edit
+#[derive(Debug, Default)]
+pub struct SharedHandle<T> {
+  current: RwLock<Arc<T>>,
+
+  // serializes editors so concurrent edits are not lost
+  writer: Mutex<()>,
+}
+
+impl<T: Clone> SharedHandle<T> {
+  pub fn new(value: T) -> SharedHandle<T> {
+    Self {
+      current: RwLock::new(Arc::new(value)),
+      writer: Mutex::new(()),
+    }
+  }
+
+  /// snapshot returns the current version. It is not affected by later edits
+  pub fn snapshot(&self) -> Arc<T> {
+    // versions are swapped whole, so the value is consistent even if a thread panicked holding the lock
+    let current = self.current.read().unwrap_or_else(|e| e.into_inner());
+    current.clone()
+  }
+
+  /// edit applies `f` to a copy of the current version and publishes the copy as the new version.
+  /// The copy is only made when the current version is shared with readers
+  /// example
+  /// `handle.edit(|document| document.set_ontology_iri("http://example.org/ontology"))`
+  pub fn edit<F, R>(&self, f: F) -> R
+  where
+    F: FnOnce(&mut T) -> R,
+  {
+    let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+    let mut next = self.snapshot();
+    let result = f(Arc::make_mut(&mut next));
+    self.publish(next);
+    result
+  }
+
+  /// replace publishes `value` as the new version and returns the previous one
+  pub fn replace(&self, value: T) -> Arc<T> {
+    let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+    self.publish(Arc::new(value))
+  }
+
+  // publish swaps the current version, returning the previous one
+  fn publish(&self, next: Arc<T>) -> Arc<T> {
+    let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *current, next)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::declarations::graph::TripleGraph;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::loader::manager::OntologyManager;
+
+  fn assert_send_sync<T: Send + Sync>() {}
+
+  #[test]
+  fn should_be_send_and_sync() {
+    assert_send_sync::<TurtleDocument>();
+    assert_send_sync::<TripleGraph>();
+    assert_send_sync::<OwlOntology>();
+    assert_send_sync::<OntologyManager>();
+    assert_send_sync::<SharedDocument>();
+    assert_send_sync::<SharedOntology>();
+  }
+
+  #[test]
+  fn should_share_document_versions() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      "<http://example.org/a> <http://example.org/b> <http://example.org/c> .",
+    )?;
+    let handle = Arc::new(SharedDocument::new(document));
+    let before = handle.snapshot();
+
+    let readers: Vec<_> = (0..4)
+      .map(|_| {
+        let handle = handle.clone();
+        std::thread::spawn(move || handle.snapshot().triples().len())
+      })
+      .collect();
+    let editors: Vec<_> = (0..4)
+      .map(|i| {
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+          handle.edit(|document| {
+            let mut more = load_turtle_document_from_str(&format!(
+              "<http://example.org/a> <http://example.org/b> \"{}\" .",
+              i
+            ))
+            .unwrap();
+            document.body.append(&mut more.body);
+          })
+        })
+      })
+      .collect();
+    for reader in readers {
+      let len = reader.join().unwrap();
+      assert!((1..=5).contains(&len));
+    }
+    for editor in editors {
+      editor.join().unwrap();
+    }
+
+    // no edit is lost and earlier snapshots are left untouched
+    assert_eq!(handle.snapshot().triples().len(), 5);
+    assert_eq!(before.triples().len(), 1);
+    let previous = handle.replace(TurtleDocument::new());
+    assert_eq!(previous.triples().len(), 5);
+    assert!(handle.snapshot().triples().is_empty());
+    Ok(())
+  }
+}
@@ -1,3 +1,24 @@
+//! Std prefix names module holds the prefixes of the vocabularies ontologies commonly use
+use crate::declarations::prefix_map::{has_scheme, PrefixMap};
+use crate::declarations::turtle::{TurtleDocument, TurtleHeaderItem, TurtleTerm};
+use crate::loader::parsers::ttl_pname_parser::pname_ns;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// STD_PREFIXES are the prefixes of the common vocabularies, with their namespaces
+pub const STD_PREFIXES: [(&str, &str); 10] = [
+  ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+  ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+  ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+  ("owl", "http://www.w3.org/2002/07/owl#"),
+  ("dc", "http://purl.org/dc/elements/1.1/"),
+  ("dcterms", "http://purl.org/dc/terms/"),
+  ("skos", "http://www.w3.org/2004/02/skos/core#"),
+  ("foaf", "http://xmlns.com/foaf/0.1/"),
+  ("obo", "http://purl.obolibrary.org/obo/"),
+  ("prov", "http://www.w3.org/ns/prov#"),
+];
+
 #[derive(Debug, PartialEq)]
 pub struct PrefixObject<'a> {
   pub prefix_name: &'a str,
@@ -49,9 +70,139 @@ pub fn get_owl_prefix<'a>() -> OwlStdPrefix<'a> {
   })
 }
 
+/// StdPrefixes is a registry of well known prefixes. The default registry holds `STD_PREFIXES`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StdPrefixes {
+  // namespaces keyed by prefix. Example -> `skos` : `http://www.w3.org/2004/02/skos/core#`
+  pub prefixes: BTreeMap<String, String>,
+}
+
+impl Default for StdPrefixes {
+  fn default() -> Self {
+    let prefixes = STD_PREFIXES
+      .iter()
+      .map(|(p, ns)| (String::from(*p), String::from(*ns)))
+      .collect();
+    Self { prefixes }
+  }
+}
+
+impl StdPrefixes {
+  pub fn new() -> StdPrefixes {
+    Self::default()
+  }
+
+  /// register adds a prefix, or replaces the namespace of a registered one. The prefix must be a
+  /// valid `PN_PREFIX` and the namespace an absolute IRI
+  /// example
+  /// `register("cco", "http://www.ontologyrepository.com/CommonCoreOntologies/")`
+  pub fn register(&mut self, prefix: &str, namespace: &str) -> std::io::Result<()> {
+    let valid_prefix =
+      matches!(pname_ns(&format!("{}:", prefix)), Ok((rest, _)) if rest.is_empty());
+    if !valid_prefix || prefix.is_empty() {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("{} is not a valid prefix name", prefix),
+      ));
+    }
+    if !has_scheme(namespace) {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("{} is not an absolute IRI", namespace),
+      ));
+    }
+    self
+      .prefixes
+      .insert(String::from(prefix), String::from(namespace));
+    Ok(())
+  }
+
+  /// namespace returns the namespace of a registered prefix
+  pub fn namespace(&self, prefix: &str) -> Option<&str> {
+    self.prefixes.get(prefix).map(|x| x.as_str())
+  }
+
+  /// prefix returns the prefix registered for a namespace
+  /// example
+  /// `http://purl.org/dc/terms/` returns Option of `dcterms`
+  pub fn prefix(&self, namespace: &str) -> Option<&str> {
+    self
+      .prefixes
+      .iter()
+      .find(|(_, ns)| ns.as_str() == namespace)
+      .map(|(p, _)| p.as_str())
+  }
+
+  /// suggest returns the prefix and namespace registered for the longest namespace of an IRI
+  /// example
+  /// `http://www.w3.org/2004/02/skos/core#prefLabel` returns Option of `(skos, http://www.w3.org/2004/02/skos/core#)`
+  pub fn suggest(&self, iri: &str) -> Option<(&str, &str)> {
+    self
+      .prefixes
+      .iter()
+      .filter(|(_, ns)| iri.starts_with(ns.as_str()))
+      .max_by_key(|(_, ns)| ns.len())
+      .map(|(p, ns)| (p.as_str(), ns.as_str()))
+  }
+
+  /// prefix_map returns a prefix map declaring every registered prefix
+  pub fn prefix_map(&self) -> PrefixMap {
+    let mut map = PrefixMap::new();
+    for (prefix, namespace) in self.prefixes.iter() {
+      map.insert(prefix, namespace);
+    }
+    map
+  }
+}
+
+impl TurtleDocument {
+  /// suggested_prefixes returns the registered prefixes whose namespace the IRIs of the document
+  /// use, but which the document does not declare. Prefix names the document already declares for
+  /// another namespace are not suggested
+  pub fn suggested_prefixes(&self, registry: &StdPrefixes) -> PrefixMap {
+    let declared = self.prefix_map();
+    let mut suggested = PrefixMap::new();
+    for t in self.triples() {
+      for term in [&t.subject, &t.predicate, &t.object] {
+        let iri = match term {
+          TurtleTerm::Iri(x) => x,
+          TurtleTerm::Literal { datatype, .. } => datatype,
+          TurtleTerm::BlankNode(_) | TurtleTerm::QuotedTriple(_) => continue,
+        };
+        let (prefix, namespace) = match registry.suggest(iri) {
+          Some(x) => x,
+          None => continue,
+        };
+        let is_declared = declared.prefixes.values().any(|x| x == namespace)
+          || declared.namespace(prefix).is_some();
+        if !is_declared {
+          suggested.insert(prefix, namespace);
+        }
+      }
+    }
+    suggested
+  }
+
+  /// declare_prefixes adds the declarations of prefixes the document does not declare yet
+  pub fn declare_prefixes(&mut self, prefixes: &PrefixMap) {
+    let declared = self.prefix_map();
+    for (prefix, namespace) in prefixes.prefixes.iter() {
+      if declared.namespace(prefix).is_some() {
+        continue;
+      }
+      self.headers.push_back(TurtleHeaderItem::new(
+        false,
+        prefix.is_empty(),
+        Some(prefix.clone()),
+        Some(format!("<{}>", namespace)),
+        Some(format!("@prefix {}: <{}> .", prefix, namespace)),
+      ));
+    }
+  }
+}
 
 #[cfg(test)]
-mod tests{
+mod tests {
   use super::*;
 
   #[test]
@@ -78,7 +229,65 @@ mod tests{
 
     assert_ne!(prefix1, prefix2);
   }
-}
 
+  #[test]
+  fn should_look_up_registered_prefixes() -> std::io::Result<()> {
+    let mut registry = StdPrefixes::new();
+    assert_eq!(
+      registry.namespace("dcterms"),
+      Some("http://purl.org/dc/terms/")
+    );
+    assert_eq!(registry.prefix("http://www.w3.org/ns/prov#"), Some("prov"));
+    assert_eq!(
+      registry.suggest("http://purl.obolibrary.org/obo/BFO_0000001"),
+      Some(("obo", "http://purl.obolibrary.org/obo/"))
+    );
+    registry.register("bfo", "http://purl.obolibrary.org/obo/BFO_")?;
+    // the longest namespace wins
+    assert_eq!(
+      registry.suggest("http://purl.obolibrary.org/obo/BFO_0000001"),
+      Some(("bfo", "http://purl.obolibrary.org/obo/BFO_"))
+    );
+    assert!(registry
+      .register("not a prefix", "http://example.org/")
+      .is_err());
+    assert!(registry.register("ex", "example").is_err());
+    assert_eq!(
+      registry.prefix_map().expand("skos:prefLabel"),
+      Some(String::from(
+        "http://www.w3.org/2004/02/skos/core#prefLabel"
+      ))
+    );
+    Ok(())
+  }
 
+  #[test]
+  fn should_suggest_prefixes_of_document() -> std::io::Result<()> {
+    use crate::loader::load::load_turtle_document_from_str;
+    use crate::writer::format::{format_turtle, Style};
 
+    let mut document = load_turtle_document_from_str(
+      r#"@prefix dc: <http://example.org/not-dublin-core/> .
+<http://example.org/Agent> <http://www.w3.org/2004/02/skos/core#prefLabel> "agent" ;
+  <http://purl.org/dc/elements/1.1/creator> "CUBRC" ;
+  <http://purl.org/dc/terms/created> "2021-03-01"^^<http://www.w3.org/2001/XMLSchema#date> .
+"#,
+    )?;
+    let suggested = document.suggested_prefixes(&StdPrefixes::new());
+    // `dc` is taken by another namespace
+    assert_eq!(
+      suggested.prefixes.keys().collect::<Vec<_>>(),
+      vec!["dcterms", "skos", "xsd"]
+    );
+    document.declare_prefixes(&suggested);
+    let formatted = format_turtle(&document, &Style::compact());
+    assert!(formatted.contains("skos:prefLabel"));
+    assert!(formatted.contains("dcterms:created \"2021-03-01\"^^xsd:date"));
+    assert!(formatted.contains("<http://purl.org/dc/elements/1.1/creator>"));
+    assert!(document
+      .suggested_prefixes(&StdPrefixes::new())
+      .prefixes
+      .is_empty());
+    Ok(())
+  }
+}
@@ -0,0 +1,323 @@
+//! Template module creates terms in bulk from a table
+use crate::declarations::builder::OntologyBuilder;
+use serde::{Deserialize, Serialize};
+
+/// ColumnKind is the part of a term a column of a template describes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnKind {
+  Id,
+  Label,
+  Type,
+
+  // e.g -> A rdfs:comment, AL rdfs:label@fr or AT cco:has_age^^xsd:integer
+  Annotation {
+    property: String,
+    language: Option<String>,
+    datatype: Option<String>,
+  },
+
+  // e.g -> AI rdfs:seeAlso
+  IriAnnotation(String),
+
+  // predicate stating the cell as the object of the term. e.g -> `rdfs:subClassOf` for SC %
+  Axiom(String),
+
+  Ignored,
+}
+
+/// TemplateColumn is a column of a template, read from its template string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateColumn {
+  pub kind: ColumnKind,
+
+  // separator of the values of a cell. e.g -> `|` for `SC % SPLIT=|`
+  pub split: Option<String>,
+}
+
+// predicates of the axioms stated by the template strings ending with `%`
+const AXIOM_TEMPLATES: [(&str, &str); 6] = [
+  ("SC", "rdfs:subClassOf"),
+  ("EC", "owl:equivalentClass"),
+  ("DC", "owl:disjointWith"),
+  ("SP", "rdfs:subPropertyOf"),
+  ("DOMAIN", "rdfs:domain"),
+  ("RANGE", "rdfs:range"),
+];
+
+impl TemplateColumn {
+  pub fn new(kind: ColumnKind, split: Option<String>) -> TemplateColumn {
+    Self { kind, split }
+  }
+
+  /// parse reads the template string of a column. Unknown template strings are reported as
+  /// `std::io::ErrorKind::InvalidData`
+  /// example
+  /// `SC % SPLIT=|` returns the column of the super classes, separated by `|`
+  pub fn parse(template: &str) -> std::io::Result<TemplateColumn> {
+    let template = template.trim();
+    let (template, split) = match template.rsplit_once(" SPLIT=") {
+      Some((t, s)) => (t.trim(), Some(String::from(s))),
+      None => (template, None),
+    };
+    let words: Vec<&str> = template.split_whitespace().collect();
+    let kind = match words.as_slice() {
+      [] => ColumnKind::Ignored,
+      ["ID"] => ColumnKind::Id,
+      ["LABEL"] => ColumnKind::Label,
+      ["TYPE"] => ColumnKind::Type,
+      ["A", p] => annotation(p, None, None),
+      ["AL", x] => match x.rsplit_once('@') {
+        Some((p, language)) => annotation(p, Some(language), None),
+        None => return Err(invalid_template(template)),
+      },
+      ["AT", x] => match x.split_once("^^") {
+        Some((p, datatype)) => annotation(p, None, Some(datatype)),
+        None => return Err(invalid_template(template)),
+      },
+      ["AI", p] => ColumnKind::IriAnnotation(String::from(*p)),
+      ["I", p, "%"] => ColumnKind::Axiom(String::from(*p)),
+      [key, "%"] => match AXIOM_TEMPLATES.iter().find(|(k, _)| k == key) {
+        Some((_, p)) => ColumnKind::Axiom(String::from(*p)),
+        None => return Err(invalid_template(template)),
+      },
+      _ => return Err(invalid_template(template)),
+    };
+    Ok(Self::new(kind, split))
+  }
+
+  // values returns the values of a cell of the column, trimmed. Empty values are dropped
+  fn values<'a>(&self, cell: &'a str) -> Vec<&'a str> {
+    let values: Vec<&str> = match &self.split {
+      Some(separator) => cell.split(separator.as_str()).collect(),
+      None => vec![cell],
+    };
+    values
+      .into_iter()
+      .map(|x| x.trim())
+      .filter(|x| !x.is_empty())
+      .collect()
+  }
+}
+
+fn annotation(property: &str, language: Option<&str>, datatype: Option<&str>) -> ColumnKind {
+  ColumnKind::Annotation {
+    property: String::from(property),
+    language: language.map(String::from),
+    datatype: datatype.map(String::from),
+  }
+}
+
+fn invalid_template(template: &str) -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::InvalidData,
+    format!("unknown template string `{}`", template),
+  )
+}
+
+/// expand_template adds the terms described by a table, whose cells are separated by `delimiter`,
+/// to an ontology under construction. Terms are written as prefixed names or IRIs, whose prefixes
+/// are checked when the ontology is built. Rows without an `ID` are skipped. It will return an
+/// error when the table has no template row, no `ID` column or an unknown template string
+/// example
+/// `expand_template(builder, "ID,LABEL\nID,LABEL\ncco:Agent,Agent\n", ',')`
+pub fn expand_template(
+  builder: OntologyBuilder,
+  content: &str,
+  delimiter: char,
+) -> std::io::Result<OntologyBuilder> {
+  let rows = read_table(content, delimiter)?;
+  let templates = match rows.get(1) {
+    Some(x) => x,
+    None => {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the table has no template row",
+      ))
+    }
+  };
+  let columns = templates
+    .iter()
+    .map(|x| TemplateColumn::parse(x))
+    .collect::<std::io::Result<Vec<TemplateColumn>>>()?;
+  let cell_of = |row: &[String], kind: &ColumnKind| -> Option<String> {
+    let idx = columns.iter().position(|c| c.kind == *kind)?;
+    row.get(idx).map(|x| String::from(x.trim()))
+  };
+  if !columns.iter().any(|c| c.kind == ColumnKind::Id) {
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      "the template has no ID column",
+    ));
+  }
+
+  let mut builder = builder;
+  for row in rows.iter().skip(2) {
+    let id = match cell_of(row, &ColumnKind::Id) {
+      Some(x) if !x.is_empty() => x,
+      _ => continue,
+    };
+    let kind = cell_of(row, &ColumnKind::Type).unwrap_or_default();
+    builder = match kind.as_str() {
+      "" | "owl:Class" | "class" => builder.class(&id),
+      "owl:ObjectProperty" | "object property" => builder.object_property(&id),
+      "owl:DatatypeProperty" | "data property" => builder.data_property(&id),
+      "owl:AnnotationProperty" | "annotation property" => builder.annotation_property(&id),
+      "owl:NamedIndividual" | "individual" => builder.named_individual(&id),
+      class => builder.individual(&id, class),
+    };
+    for (column, cell) in columns.iter().zip(row.iter()) {
+      for value in column.values(cell) {
+        builder = match &column.kind {
+          ColumnKind::Label => builder.label(value),
+          ColumnKind::Annotation {
+            property,
+            language,
+            datatype: Some(datatype),
+          } if language.is_none() => builder.literal(property, value, datatype),
+          ColumnKind::Annotation {
+            property, language, ..
+          } => builder.annotation(property, value, language.as_deref()),
+          ColumnKind::IriAnnotation(property) | ColumnKind::Axiom(property) => {
+            builder.fact(property, value)
+          }
+          ColumnKind::Id | ColumnKind::Type | ColumnKind::Ignored => builder,
+        };
+      }
+    }
+  }
+  Ok(builder)
+}
+
+/// expand_template_file adds the terms described by a CSV, or TSV when the file extension is
+/// `.tsv`, file to an ontology under construction. See `expand_template`
+pub fn expand_template_file(
+  builder: OntologyBuilder,
+  path: &str,
+) -> std::io::Result<OntologyBuilder> {
+  let content = std::fs::read_to_string(path)?;
+  let delimiter = match path.to_ascii_lowercase().ends_with(".tsv") {
+    true => '\t',
+    false => ',',
+  };
+  expand_template(builder, &content, delimiter)
+}
+
+// read_table splits delimited content into rows of cells. Cells may be quoted with `"`, in which
+// case they may hold delimiters, line breaks and quotes written `""`
+fn read_table(content: &str, delimiter: char) -> std::io::Result<Vec<Vec<String>>> {
+  let mut rows = vec![];
+  let mut row = vec![];
+  let mut cell = String::new();
+  let mut quoted = false;
+  let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+  while let Some(c) = chars.next() {
+    match (quoted, c) {
+      (true, '"') if chars.peek() == Some(&'"') => {
+        chars.next();
+        cell.push('"');
+      }
+      (true, '"') => quoted = false,
+      (true, c) => cell.push(c),
+      (false, '"') if cell.is_empty() => quoted = true,
+      (false, c) if c == delimiter => row.push(std::mem::take(&mut cell)),
+      (false, '\r') => {}
+      (false, '\n') => {
+        row.push(std::mem::take(&mut cell));
+        rows.push(std::mem::take(&mut row));
+      }
+      (false, c) => cell.push(c),
+    }
+  }
+  if quoted {
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      "the table ends within a quoted cell",
+    ));
+  }
+  if !cell.is_empty() || !row.is_empty() {
+    row.push(cell);
+    rows.push(row);
+  }
+  // blank lines describe no term
+  rows.retain(|r| r.iter().any(|x| !x.trim().is_empty()));
+  Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TABLE: &str = "ID,Label,Type,Parent,Definition,Age,Synonyms
+ID,LABEL,TYPE,SC % SPLIT=|,A obo:IAO_0000115,AT ex:age^^xsd:integer,AL ex:synonym@en SPLIT=|
+ex:Agent,Agent,,obo:BFO_0000040,\"A material entity, which acts\",,
+ex:Person,Person,owl:Class,ex:Agent|ex:Organism,\"A \"\"human\"\" agent\",,Human|Individual
+
+ex:JohnDoe,John Doe,ex:Person,,,42,
+";
+
+  #[test]
+  fn should_expand_template() -> std::io::Result<()> {
+    let builder = OntologyBuilder::new("http://example.org/agents")
+      .prefix("ex", "http://example.org/")
+      .prefix("obo", "http://purl.obolibrary.org/obo/");
+    let document = expand_template(builder, TABLE, ',')?.build()?;
+    let person = "http://example.org/Person";
+    assert_eq!(document.labels_of(person).values(), vec!["Person"]);
+    assert_eq!(
+      document
+        .annotations_of(person, "http://purl.obolibrary.org/obo/IAO_0000115")
+        .values(),
+      vec!["A \"human\" agent"]
+    );
+    assert_eq!(
+      document
+        .annotations_of(person, "http://example.org/synonym")
+        .values(),
+      vec!["Human", "Individual"]
+    );
+    let triples = document.triples();
+    let objects = |subject: &str, predicate: &str| -> Vec<String> {
+      triples
+        .iter()
+        .filter(|t| t.subject.as_iri() == Some(subject) && t.predicate.as_iri() == Some(predicate))
+        .map(|t| t.object.to_string())
+        .collect()
+    };
+    assert_eq!(
+      objects(person, "http://www.w3.org/2000/01/rdf-schema#subClassOf"),
+      vec![
+        "<http://example.org/Agent>",
+        "<http://example.org/Organism>"
+      ]
+    );
+    assert_eq!(
+      objects(
+        "http://example.org/JohnDoe",
+        "http://www.w3.org/1999/02/22-rdf-syntax-ns#type"
+      ),
+      vec![
+        "<http://www.w3.org/2002/07/owl#NamedIndividual>",
+        "<http://example.org/Person>"
+      ]
+    );
+    assert_eq!(
+      objects("http://example.org/JohnDoe", "http://example.org/age"),
+      vec!["\"42\"^^<http://www.w3.org/2001/XMLSchema#integer>"]
+    );
+
+    let tsv = "ID\tParent\nID\tSC %\nex:Agent\tex:Entity\n";
+    let builder =
+      OntologyBuilder::new("http://example.org/agents").prefix("ex", "http://example.org/");
+    assert_eq!(
+      expand_template(builder, tsv, '\t')?
+        .build()?
+        .triples()
+        .len(),
+      3
+    );
+    let builder = OntologyBuilder::new("http://example.org/agents");
+    let error = expand_template(builder, "ID,Parent\nID,XY %\n", ',').unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+  }
+}
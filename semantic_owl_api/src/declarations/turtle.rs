@@ -1,7 +1,13 @@
 //! Turtle module defines representaion of turtle documents
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::provenance::{DocumentProvenance, SourceSpan};
+use crate::loader::parsers::ttl_statement_parser::split_literal;
+use crate::vocab::xsd;
 use serde::{Deserialize, Serialize};
 
-use std::{collections::VecDeque, iter::FromIterator};
+use std::{collections::VecDeque, fmt, iter::FromIterator};
+
+const XSD_STRING: &str = xsd::STRING.as_str();
 
 /// StatementKind used to map turtke parse results
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -66,7 +72,7 @@ pub enum StatementKind {
 /// ```ttl
 /// @base <http://example.org/> .
 /// ```
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurtleHeaderItem {
   // determines whether the header item is a `base` or not
   pub is_base: bool,
@@ -84,6 +90,21 @@ pub struct TurtleHeaderItem {
 
   // the raw item string
   pub raw_header: Option<String>,
+
+  // location of the directive in the document it was parsed from. `None` for directives built in code
+  #[serde(default)]
+  pub span: Option<SourceSpan>,
+}
+
+// directives are equal when they declare the same, wherever they are located
+impl PartialEq for TurtleHeaderItem {
+  fn eq(&self, other: &Self) -> bool {
+    self.is_base == other.is_base
+      && self.is_empty == other.is_empty
+      && self.prefix_namespace == other.prefix_namespace
+      && self.prefix_iri == other.prefix_iri
+      && self.raw_header == other.raw_header
+  }
 }
 
 impl TurtleHeaderItem {
@@ -100,6 +121,20 @@ impl TurtleHeaderItem {
       prefix_namespace,
       prefix_iri,
       raw_header,
+      span: None,
+    }
+  }
+
+  /// declare adds the prefix or base of the header item to a prefix map
+  pub fn declare(&self, map: &mut PrefixMap) {
+    let iri = match &self.prefix_iri {
+      Some(x) => x.trim_start_matches('<').trim_end_matches('>'),
+      None => return,
+    };
+    match (self.is_base, &self.prefix_namespace) {
+      (true, _) => map.base = Some(String::from(iri)),
+      (false, Some(ns)) => map.insert(ns, iri),
+      (false, None) => {}
     }
   }
 }
@@ -115,10 +150,49 @@ impl TurtleHeaderItem {
 ///     owl:versionInfo "2020ab" .
 ///```
 ///
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurtleBodyItem {
   pub subject: Option<String>,
   pub predicate: VecDeque<TurtlePredicate>,
+
+  // location of the statement in the document it was parsed from. `None` for statements built in code
+  #[serde(default)]
+  pub span: Option<SourceSpan>,
+}
+
+// statements are equal when they state the same, wherever they are located
+impl PartialEq for TurtleBodyItem {
+  fn eq(&self, other: &Self) -> bool {
+    self.subject == other.subject && self.predicate == other.predicate
+  }
+}
+
+impl TurtleBodyItem {
+  /// resolve appends the triples of the statement, resolved with a prefix map, to `triples`.
+  /// Terms whose prefix is not declared are skipped
+  pub fn resolve(&self, map: &PrefixMap, triples: &mut Vec<TurtleTriple>) {
+    let subject = match self.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+      Some(x) => x,
+      None => return,
+    };
+    for p in self.predicate.iter() {
+      let predicate = match p
+        .raw_predicate_object
+        .as_deref()
+        .and_then(|x| map.resolve_term(x))
+      {
+        Some(x) => x,
+        None => continue,
+      };
+      for o in p.object.iter() {
+        if let Some(object) = o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+          let mut triple = TurtleTriple::new(subject.clone(), predicate.clone(), object);
+          triple.span = self.span.clone();
+          triples.push(triple);
+        }
+      }
+    }
+  }
 }
 
 /// TurtlePredicate is a combination of predicate and object retrieved
@@ -154,6 +228,29 @@ pub struct TurtlePredicate {
   pub object: VecDeque<TurtleObject>,
 }
 
+impl TurtlePredicate {
+  /// from_raw creates a predicate, without objects, from its source form.
+  /// example
+  /// `rdfs:label` or `<http://www.w3.org/2000/01/rdf-schema#label>`
+  pub fn from_raw(raw: &str) -> TurtlePredicate {
+    let (is_iri, namespace, value) = split_raw_resource(raw);
+    Self {
+      raw_predicate_object: Some(String::from(raw)),
+      predicate_is_iri: is_iri,
+      predicate_as_iri_or_literal: if is_iri {
+        Some(String::from(raw))
+      } else {
+        None
+      },
+      predicate_is_literal: false,
+      predicate_as_literal: None,
+      predicate_namespace: namespace,
+      predicate_namespace_value: value,
+      object: VecDeque::new(),
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TurtleObject {
   pub raw_object: Option<String>,
@@ -182,20 +279,228 @@ pub struct TurtleObject {
   pub object_namespace_value: Option<String>,
 }
 
+impl TurtleObject {
+  /// from_raw creates an object from its source form. Blank nodes are recorded with the `_` namespace.
+  /// example
+  /// `"agent in"@en`, `cco:Agent`, `<http://example.org/>` or `_:genid1`
+  pub fn from_raw(raw: &str) -> TurtleObject {
+    let is_literal = split_literal(raw).is_some();
+    let (is_iri, namespace, value) = match is_literal {
+      true => (false, None, None),
+      false => split_raw_resource(raw),
+    };
+    Self {
+      raw_object: Some(String::from(raw)),
+      object_is_iri: is_iri,
+      object_as_iri: if is_iri {
+        Some(String::from(raw))
+      } else {
+        None
+      },
+      object_is_literal: is_literal,
+      object_as_literal: if is_literal {
+        Some(String::from(raw))
+      } else {
+        None
+      },
+      object_namespace: namespace,
+      object_namespace_value: value,
+    }
+  }
+}
+
+// split_raw_resource splits the source form of an IRI, prefixed name or blank node into
+// whether it is an IRI, its namespace and its local value
+fn split_raw_resource(raw: &str) -> (bool, Option<String>, Option<String>) {
+  if raw.starts_with("<<") {
+    return (false, None, None);
+  }
+  if raw.starts_with('<') {
+    return (true, None, None);
+  }
+  match raw.find(':') {
+    Some(idx) => (
+      false,
+      Some(String::from(&raw[..idx])),
+      Some(String::from(&raw[idx + 1..])),
+    ),
+    None => (false, None, None),
+  }
+}
+
+/// TurtleTerm is a resolved RDF term. IRIs are absolute and stored without angle brackets.
+/// Literals always carry a datatype, `xsd:string` for simple literals and `rdf:langString`
+/// for literals with a language tag
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TurtleTerm {
+  Iri(String),
+  BlankNode(String),
+  Literal {
+    value: String,
+    datatype: String,
+    language: Option<String>,
+  },
+
+  // an RDF-star quoted triple, only loaded when `LoaderOptions::rdf_star` is set.
+  // e.g -> << cco:JohnDoe cco:has_age 42 >>
+  QuotedTriple(Box<TurtleTriple>),
+}
+
+impl TurtleTerm {
+  /// as_iri returns the IRI of the term if it is an IRI
+  pub fn as_iri(&self) -> Option<&str> {
+    match self {
+      TurtleTerm::Iri(x) => Some(x),
+      _ => None,
+    }
+  }
+
+  pub fn is_blank_node(&self) -> bool {
+    matches!(self, TurtleTerm::BlankNode(_))
+  }
+
+  pub fn is_literal(&self) -> bool {
+    matches!(self, TurtleTerm::Literal { .. })
+  }
+
+  /// as_quoted_triple returns the triple of the term if it is an RDF-star quoted triple
+  pub fn as_quoted_triple(&self) -> Option<&TurtleTriple> {
+    match self {
+      TurtleTerm::QuotedTriple(x) => Some(x),
+      _ => None,
+    }
+  }
+}
+
+/// formats the term in its N-Triples form
+impl fmt::Display for TurtleTerm {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TurtleTerm::Iri(x) => write!(f, "<{}>", x),
+      TurtleTerm::BlankNode(x) => write!(f, "_:{}", x),
+      TurtleTerm::Literal {
+        value,
+        datatype,
+        language,
+      } => {
+        write!(f, "\"")?;
+        for c in value.chars() {
+          match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            _ => write!(f, "{}", c)?,
+          }
+        }
+        write!(f, "\"")?;
+        match language {
+          Some(l) => write!(f, "@{}", l),
+          None if datatype == XSD_STRING => Ok(()),
+          None => write!(f, "^^<{}>", datatype),
+        }
+      }
+      TurtleTerm::QuotedTriple(t) => write!(f, "<< {} {} {} >>", t.subject, t.predicate, t.object),
+    }
+  }
+}
+
+/// TurtleTriple is a single resolved statement of a turtle document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurtleTriple {
+  pub subject: TurtleTerm,
+  pub predicate: TurtleTerm,
+  pub object: TurtleTerm,
+
+  // location of the statement the triple was parsed from. `None` for triples built in code
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub span: Option<SourceSpan>,
+}
+
+impl TurtleTriple {
+  pub fn new(subject: TurtleTerm, predicate: TurtleTerm, object: TurtleTerm) -> TurtleTriple {
+    Self {
+      subject,
+      predicate,
+      object,
+      span: None,
+    }
+  }
+
+  /// source_span returns the location of the statement the triple was parsed from
+  pub fn source_span(&self) -> Option<&SourceSpan> {
+    self.span.as_ref()
+  }
+
+  // key returns the terms triples are compared and hashed by
+  fn key(&self) -> (&TurtleTerm, &TurtleTerm, &TurtleTerm) {
+    (&self.subject, &self.predicate, &self.object)
+  }
+}
+
+// triples are equal when they hold the same terms, wherever they are located
+impl PartialEq for TurtleTriple {
+  fn eq(&self, other: &Self) -> bool {
+    self.key() == other.key()
+  }
+}
+
+impl Eq for TurtleTriple {}
+
+impl std::hash::Hash for TurtleTriple {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.key().hash(state)
+  }
+}
+
+impl PartialOrd for TurtleTriple {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for TurtleTriple {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    self.key().cmp(&other.key())
+  }
+}
+
+/// formats the triple as an N-Triples statement
+impl fmt::Display for TurtleTriple {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} {} .", self.subject, self.predicate, self.object)
+  }
+}
+
 /// TurtleDocument is the composition of an entire turtle document. It is the sum of turle headers and body items.
 /// A turtle document can be very large. This struct is used to represent such a document
 /// as a summation of it'document atomic structures
-#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TurtleDocument {
   pub headers: VecDeque<TurtleHeaderItem>,
   pub body: VecDeque<TurtleBodyItem>,
+
+  // artifact the document was loaded from. `None` for documents loaded from memory or built in code
+  #[serde(default)]
+  pub provenance: Option<DocumentProvenance>,
+}
+
+// documents are equal when they hold the same statements, wherever and whenever they were loaded from
+impl PartialEq for TurtleDocument {
+  fn eq(&self, other: &Self) -> bool {
+    self.headers == other.headers && self.body == other.body
+  }
 }
 
 impl TurtleDocument {
   pub fn new() -> TurtleDocument {
     let headers: VecDeque<TurtleHeaderItem> = VecDeque::new();
     let body: VecDeque<TurtleBodyItem> = VecDeque::new();
-    Self { headers, body }
+    Self {
+      headers,
+      body,
+      provenance: None,
+    }
   }
 
   /// base_iri returns the IRI of the base prefix
@@ -223,6 +528,26 @@ impl TurtleDocument {
       _ => None,
     }
   }
+
+  /// prefix_map returns the prefixes and base declared in the headers of the document
+  pub fn prefix_map(&self) -> PrefixMap {
+    let mut map = PrefixMap::new();
+    for h in self.headers.iter() {
+      h.declare(&mut map);
+    }
+    map
+  }
+
+  /// triples returns the resolved triples of the document in the order they were stated.
+  /// Terms whose prefix is not declared are skipped
+  pub fn triples(&self) -> Vec<TurtleTriple> {
+    let map = self.prefix_map();
+    let mut triples = vec![];
+    for item in self.body.iter() {
+      item.resolve(&map, &mut triples);
+    }
+    triples
+  }
 }
 
 impl<'a> FromIterator<&'a TurtleHeaderItem> for VecDeque<TurtleHeaderItem> {
@@ -0,0 +1,136 @@
+//! Dependencies module builds the import graph of the ontologies of a manager
+use crate::export::graph::{to_dot, EntityGraph, ExportNode};
+use crate::loader::manager::OntologyManager;
+use crate::loader::version_policy::OntologyVersion;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
+use std::collections::BTreeMap;
+
+const OWL_IMPORTS: &str = "owl:imports";
+
+/// DependencyGraph is the import graph of the ontologies of a manager
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+  // edges go from an ontology to the ontologies it imports
+  pub graph: EntityGraph,
+
+  // versions of the loaded ontologies, keyed by the id of their node
+  pub versions: BTreeMap<String, OntologyVersion>,
+
+  // groups of ontologies importing one another, directly or not, each ordered by IRI.
+  // e.g -> [http://example.org/a, http://example.org/b]
+  pub cycles: Vec<Vec<String>>,
+}
+
+impl DependencyGraph {
+  /// is_acyclic determines whether no ontology imports itself, directly or not
+  pub fn is_acyclic(&self) -> bool {
+    self.cycles.is_empty()
+  }
+
+  /// to_dot renders the graph in the GraphViz DOT language
+  pub fn to_dot(&self) -> String {
+    to_dot(&self.graph)
+  }
+}
+
+impl OntologyManager {
+  /// dependency_graph returns the graph of the imports between the loaded ontologies. Loaded
+  /// ontologies come first, ordered by key, followed by the ontologies imported but not loaded
+  pub fn dependency_graph(&self) -> DependencyGraph {
+    let mut graph = EntityGraph::new();
+    let mut nodes: BTreeMap<String, NodeIndex> = BTreeMap::new();
+    let mut versions = BTreeMap::new();
+    for (key, document) in self.documents.iter() {
+      let version = OntologyVersion::of(key, document);
+      let label = match &version.version_iri {
+        Some(x) => format!("{}\n{}", key, x),
+        None => key.clone(),
+      };
+      nodes.insert(
+        key.clone(),
+        graph.add_node(ExportNode::new(key.clone(), label)),
+      );
+      versions.insert(key.clone(), version);
+    }
+    for key in self.documents.keys() {
+      for import in self.imports_of(key) {
+        let target = *nodes
+          .entry(import.clone())
+          .or_insert_with(|| graph.add_node(ExportNode::new(import.clone(), import)));
+        graph.add_edge(nodes[key], target, String::from(OWL_IMPORTS));
+      }
+    }
+
+    let mut cycles: Vec<Vec<String>> = tarjan_scc(&graph)
+      .into_iter()
+      .filter(|x| x.len() > 1 || graph.contains_edge(x[0], x[0]))
+      .map(|x| {
+        let mut cycle: Vec<String> = x.into_iter().map(|n| graph[n].id.clone()).collect();
+        cycle.sort();
+        cycle
+      })
+      .collect();
+    cycles.sort();
+    DependencyGraph {
+      graph,
+      versions,
+      cycles,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::loader::manager::OntologyManager;
+
+  fn ontology(iri: &str, version: &str, imports: &[&str]) -> String {
+    let imports: Vec<String> = imports
+      .iter()
+      .map(|x| format!(" ; owl:imports <http://example.org/{}>", x))
+      .collect();
+    format!(
+      "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n<http://example.org/{}> a owl:Ontology ; owl:versionIRI <http://example.org/{}/{}>{} .\n",
+      iri,
+      version,
+      iri,
+      imports.concat()
+    )
+  }
+
+  #[test]
+  fn should_export_dependency_graph() -> std::io::Result<()> {
+    let mut manager = OntologyManager::new();
+    for (iri, imports) in [
+      ("all", vec!["mid", "upper"]),
+      ("mid", vec!["upper", "domain"]),
+      ("domain", vec!["mid"]),
+      ("upper", vec!["external"]),
+    ] {
+      let document = load_turtle_document_from_str(&ontology(iri, "2021-03-01", &imports))?;
+      manager
+        .documents
+        .insert(format!("http://example.org/{}", iri), document);
+    }
+    let dependencies = manager.dependency_graph();
+    assert_eq!(dependencies.graph.node_count(), 5);
+    assert_eq!(dependencies.graph.edge_count(), 6);
+    assert_eq!(
+      dependencies.versions["http://example.org/mid"]
+        .version_iri
+        .as_deref(),
+      Some("http://example.org/2021-03-01/mid")
+    );
+    assert!(!dependencies.is_acyclic());
+    assert_eq!(
+      dependencies.cycles,
+      vec![vec!["http://example.org/domain", "http://example.org/mid"]]
+    );
+    let dot = dependencies.to_dot();
+    assert!(dot.contains("label = \"http://example.org/mid\\lhttp://example.org/2021-03-01/mid\""));
+    assert!(dot.contains("label = \"http://example.org/external\""));
+    assert!(dot.contains("label = \"owl:imports\""));
+    Ok(())
+  }
+}
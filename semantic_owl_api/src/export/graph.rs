@@ -0,0 +1,322 @@
+//! Graph module builds graphs of a document for visual inspection
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::{owl, rdf, rdfs};
+use crate::writer::renderer::{CurieRenderer, LabelRenderer, TermRenderer};
+use petgraph::dot::Dot;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDFS_SUB_CLASS_OF: &str = rdfs::SUB_CLASS_OF.as_str();
+const OWL_CLASS: &str = owl::CLASS.as_str();
+const RDFS_CLASS: &str = rdfs::CLASS.as_str();
+
+/// ExportOptions tunes the content of an exported graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+  // maximum distance of a node from the roots of the graph. `None` means no limit
+  pub max_depth: Option<usize>,
+
+  // show the `rdfs:label` of entities instead of their prefixed name
+  pub use_labels: bool,
+
+  // preferred language of labels. Example -> `en`
+  pub language: Option<String>,
+}
+
+impl Default for ExportOptions {
+  fn default() -> Self {
+    Self {
+      max_depth: None,
+      use_labels: true,
+      language: Some(String::from("en")),
+    }
+  }
+}
+
+impl ExportOptions {
+  pub fn new(
+    max_depth: Option<usize>,
+    use_labels: bool,
+    language: Option<String>,
+  ) -> ExportOptions {
+    Self {
+      max_depth,
+      use_labels,
+      language,
+    }
+  }
+
+  /// renderer returns the renderer of the entities of a document: their preferred label when
+  /// `use_labels` is set, falling back to their prefixed name and then their IRI
+  pub fn renderer<'a>(&self, document: &'a TurtleDocument) -> Box<dyn TermRenderer + 'a> {
+    let curie = CurieRenderer::new(document.prefix_map());
+    if !self.use_labels {
+      return Box::new(curie);
+    }
+    let mut languages: Vec<String> = self.language.iter().cloned().collect();
+    languages.push(String::from("*"));
+    Box::new(LabelRenderer::new(document, languages, Box::new(curie)))
+  }
+}
+
+/// ExportNode is a node of an exported graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportNode {
+  // the IRI of an entity or the label of a blank node. Example -> `_:genid1`
+  pub id: String,
+
+  // the text shown for the node
+  pub label: String,
+}
+
+impl ExportNode {
+  pub fn new(id: String, label: String) -> ExportNode {
+    Self { id, label }
+  }
+}
+
+impl fmt::Display for ExportNode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.label)
+  }
+}
+
+/// EntityGraph is a directed graph of entities whose edges are labeled with predicates
+pub type EntityGraph = DiGraph<ExportNode, String>;
+
+/// class_hierarchy returns the graph of the `rdfs:subClassOf` relations between named classes.
+/// Edges go from a class to its super class. With a depth limit, only classes within `max_depth`
+/// sub class steps from a root class, that is a class without a named super class, are kept
+pub fn class_hierarchy(document: &TurtleDocument, options: &ExportOptions) -> EntityGraph {
+  let triples = document.triples();
+  let mut classes: BTreeSet<&str> = BTreeSet::new();
+  let mut sub_classes: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+  let mut has_super_class: BTreeSet<&str> = BTreeSet::new();
+  for t in triples.iter() {
+    match (t.subject.as_iri(), t.predicate.as_iri(), t.object.as_iri()) {
+      (Some(s), Some(RDF_TYPE), Some(OWL_CLASS)) | (Some(s), Some(RDF_TYPE), Some(RDFS_CLASS)) => {
+        classes.insert(s);
+      }
+      (Some(s), Some(RDFS_SUB_CLASS_OF), Some(o)) => {
+        classes.insert(s);
+        classes.insert(o);
+        sub_classes.entry(o).or_default().push(s);
+        has_super_class.insert(s);
+      }
+      _ => {}
+    }
+  }
+
+  // walk down from the roots, recording the depth of each class
+  let mut depths: BTreeMap<&str, usize> = BTreeMap::new();
+  let mut pending: VecDeque<(&str, usize)> = classes
+    .iter()
+    .filter(|c| !has_super_class.contains(*c))
+    .map(|c| (*c, 0))
+    .collect();
+  while let Some((class, depth)) = pending.pop_front() {
+    if depths.contains_key(class) || options.max_depth.map(|m| depth > m).unwrap_or(false) {
+      continue;
+    }
+    depths.insert(class, depth);
+    for sub in sub_classes.get(class).into_iter().flatten() {
+      pending.push_back((sub, depth + 1));
+    }
+  }
+  // classes which only take part in cycles have no root
+  if options.max_depth.is_none() {
+    for c in classes.iter() {
+      depths.entry(c).or_insert(0);
+    }
+  }
+
+  let mut builder = GraphBuilder::new(document, options);
+  for c in depths.keys() {
+    builder.node(&TurtleTerm::Iri(String::from(*c)));
+  }
+  for (sup, subs) in sub_classes.iter() {
+    for sub in subs.iter() {
+      if depths.contains_key(sup) && depths.contains_key(sub) {
+        builder.edge(
+          &TurtleTerm::Iri(String::from(*sub)),
+          RDFS_SUB_CLASS_OF,
+          &TurtleTerm::Iri(String::from(*sup)),
+        );
+      }
+    }
+  }
+  builder.graph
+}
+
+/// neighbourhood returns the graph of the statements linking an entity, given as a full IRI,
+/// to other entities and blank nodes, in both directions. Nodes within `max_depth` steps of the
+/// entity are kept, one step when no limit is set. Literals are left out
+pub fn neighbourhood(document: &TurtleDocument, iri: &str, options: &ExportOptions) -> EntityGraph {
+  let max_depth = options.max_depth.unwrap_or(1);
+  let triples = document.triples();
+  let mut builder = GraphBuilder::new(document, options);
+  let start = TurtleTerm::Iri(String::from(iri));
+  let mut depths: HashMap<TurtleTerm, usize> = HashMap::new();
+  let mut pending: VecDeque<(TurtleTerm, usize)> = VecDeque::from(vec![(start, 0)]);
+  while let Some((term, depth)) = pending.pop_front() {
+    if depths.contains_key(&term) {
+      continue;
+    }
+    depths.insert(term.clone(), depth);
+    builder.node(&term);
+    if depth == max_depth {
+      continue;
+    }
+    for t in triples.iter() {
+      if t.subject == term && !t.object.is_literal() {
+        pending.push_back((t.object.clone(), depth + 1));
+      } else if t.object == term {
+        pending.push_back((t.subject.clone(), depth + 1));
+      }
+    }
+  }
+  for t in triples.iter() {
+    if depths.contains_key(&t.subject) && depths.contains_key(&t.object) {
+      builder.edge(
+        &t.subject,
+        t.predicate.as_iri().unwrap_or_default(),
+        &t.object,
+      );
+    }
+  }
+  builder.graph
+}
+
+/// to_dot renders a graph in the GraphViz DOT language
+pub fn to_dot(graph: &EntityGraph) -> String {
+  format!("{}", Dot::new(graph))
+}
+
+/// to_cytoscape_json renders a graph as Cytoscape.js elements
+/// example
+/// `{"elements":{"nodes":[{"data":{"id":"..","label":".."}}],"edges":[{"data":{"id":"e0","source":"..","target":"..","label":".."}}]}}`
+pub fn to_cytoscape_json(graph: &EntityGraph) -> serde_json::Value {
+  let nodes: Vec<serde_json::Value> = graph
+    .node_indices()
+    .map(|idx| json!({ "data": { "id": graph[idx].id, "label": graph[idx].label } }))
+    .collect();
+  let edges: Vec<serde_json::Value> = graph
+    .edge_indices()
+    .filter_map(|idx| {
+      let (source, target) = graph.edge_endpoints(idx)?;
+      Some(json!({
+        "data": {
+          "id": format!("e{}", idx.index()),
+          "source": graph[source].id,
+          "target": graph[target].id,
+          "label": graph[idx],
+        }
+      }))
+    })
+    .collect();
+  json!({ "elements": { "nodes": nodes, "edges": edges } })
+}
+
+// GraphBuilder adds nodes and edges once, substituting short forms for IRIs
+struct GraphBuilder<'a> {
+  renderer: Box<dyn TermRenderer + 'a>,
+  nodes: HashMap<TurtleTerm, NodeIndex>,
+  edges: BTreeSet<(NodeIndex, String, NodeIndex)>,
+  graph: EntityGraph,
+}
+
+impl<'a> GraphBuilder<'a> {
+  fn new(document: &'a TurtleDocument, options: &'a ExportOptions) -> GraphBuilder<'a> {
+    Self {
+      renderer: options.renderer(document),
+      nodes: HashMap::new(),
+      edges: BTreeSet::new(),
+      graph: EntityGraph::new(),
+    }
+  }
+
+  fn node(&mut self, term: &TurtleTerm) -> NodeIndex {
+    if let Some(idx) = self.nodes.get(term) {
+      return *idx;
+    }
+    let node = match term {
+      TurtleTerm::Iri(iri) => ExportNode::new(iri.clone(), self.renderer.render_iri(iri)),
+      t => ExportNode::new(t.to_string(), t.to_string()),
+    };
+    let idx = self.graph.add_node(node);
+    self.nodes.insert(term.clone(), idx);
+    idx
+  }
+
+  fn edge(&mut self, source: &TurtleTerm, predicate: &str, target: &TurtleTerm) {
+    let (s, o) = (self.node(source), self.node(target));
+    let label = self.renderer.render_iri(predicate);
+    if self.edges.insert((s, label.clone(), o)) {
+      self.graph.add_edge(s, o, label);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Entity a owl:Class .
+cco:Agent a owl:Class ; rdfs:subClassOf cco:Entity ; rdfs:label "Agent"@en , "Agent \"fr\""@fr .
+cco:Person a owl:Class ; rdfs:subClassOf cco:Agent ;
+           rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Agent ] .
+cco:Robot a owl:Class ; rdfs:subClassOf cco:Agent .
+cco:has_part a owl:ObjectProperty ; rdfs:label "has part"@en .
+"#;
+
+  #[test]
+  fn should_export_class_hierarchy() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let graph = class_hierarchy(&document, &ExportOptions::default());
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.edge_count(), 3);
+    let dot = to_dot(&graph);
+    assert!(dot.starts_with("digraph {"));
+    assert!(dot.contains("label = \"Agent\""));
+    assert!(dot.contains("label = \"cco:Person\""));
+
+    let graph = class_hierarchy(&document, &ExportOptions::new(Some(1), false, None));
+    assert_eq!(graph.node_count(), 2);
+    assert_eq!(graph[NodeIndex::new(0)].label, "cco:Agent");
+    Ok(())
+  }
+
+  #[test]
+  fn should_export_neighbourhood() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let iri = "http://www.ontologyrepository.com/CommonCoreOntologies/Person";
+    let graph = neighbourhood(&document, iri, &ExportOptions::default());
+    // the person, owl:Class, the agent and the restriction
+    assert_eq!(graph.node_count(), 4);
+    let graph = neighbourhood(&document, iri, &ExportOptions::new(Some(2), true, None));
+    let labels: Vec<&str> = graph
+      .node_indices()
+      .map(|idx| graph[idx].label.as_str())
+      .collect();
+    assert!(labels.contains(&"has part"));
+    assert!(labels.contains(&"cco:Entity"));
+
+    let json = to_cytoscape_json(&graph);
+    let nodes = json["elements"]["nodes"].as_array().unwrap();
+    let edges = json["elements"]["edges"].as_array().unwrap();
+    assert_eq!(nodes.len(), graph.node_count());
+    assert_eq!(edges.len(), graph.edge_count());
+    assert_eq!(nodes[0]["data"]["id"], iri);
+    Ok(())
+  }
+}
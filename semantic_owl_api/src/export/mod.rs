@@ -0,0 +1,3 @@
+pub mod dependencies;
+pub mod graph;
+pub mod table;
@@ -0,0 +1,281 @@
+//! Table module flattens the annotations of the entities of a document into a table
+use crate::analysis::annotation_audit::IAO_DEFINITION;
+use crate::analysis::labels::Labels;
+use crate::analysis::reasoner::sub_properties;
+use crate::analysis::validation::EntityType;
+use crate::declarations::turtle::TurtleDocument;
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+/// oboInOwl `has_exact_synonym`
+pub const OBO_EXACT_SYNONYM: &str = "http://www.geneontology.org/formats/oboInOwl#hasExactSynonym";
+
+/// TableColumn is a column of an annotation table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableColumn {
+  // e.g -> label
+  pub header: String,
+
+  // full IRI of the annotation property
+  pub property: String,
+
+  // basic language range the values are filtered by. e.g -> en
+  pub language: Option<String>,
+}
+
+impl TableColumn {
+  pub fn new(header: &str, property: &str, language: Option<String>) -> TableColumn {
+    Self {
+      header: String::from(header),
+      property: String::from(property),
+      language,
+    }
+  }
+}
+
+/// TableOptions selects the rows and the columns of an annotation table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableOptions {
+  pub columns: Vec<TableColumn>,
+
+  // kinds of the entities written as rows. e.g -> Class
+  pub entity_types: Vec<EntityType>,
+
+  // joins the values of a cell. e.g -> |
+  pub separator: String,
+}
+
+impl TableOptions {
+  pub fn new(
+    columns: Vec<TableColumn>,
+    entity_types: Vec<EntityType>,
+    separator: &str,
+  ) -> TableOptions {
+    Self {
+      columns,
+      entity_types,
+      separator: String::from(separator),
+    }
+  }
+}
+
+/// the label, the IAO definition and the exact synonyms of classes, properties and individuals
+impl Default for TableOptions {
+  fn default() -> Self {
+    Self::new(
+      vec![
+        TableColumn::new("label", RDFS_LABEL, None),
+        TableColumn::new("definition", IAO_DEFINITION, None),
+        TableColumn::new("synonyms", OBO_EXACT_SYNONYM, None),
+      ],
+      vec![
+        EntityType::Class,
+        EntityType::ObjectProperty,
+        EntityType::DataProperty,
+        EntityType::AnnotationProperty,
+        EntityType::NamedIndividual,
+      ],
+      "|",
+    )
+  }
+}
+
+/// Row holds the values of each column for an entity
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Row {
+  pub iri: String,
+
+  // values of each column, in the order of the columns
+  pub values: Vec<Vec<String>>,
+}
+
+impl Row {
+  pub fn new(iri: String, values: Vec<Vec<String>>) -> Row {
+    Self { iri, values }
+  }
+}
+
+/// AnnotationTable is the annotations of the entities of a document, with a row per entity
+/// ordered by IRI
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AnnotationTable {
+  // headers of the value columns. The IRI column comes first and is headed `iri`
+  pub headers: Vec<String>,
+
+  pub rows: Vec<Row>,
+
+  // joins the values of a cell
+  pub separator: String,
+}
+
+impl AnnotationTable {
+  pub fn new(headers: Vec<String>, rows: Vec<Row>, separator: &str) -> AnnotationTable {
+    Self {
+      headers,
+      rows,
+      separator: String::from(separator),
+    }
+  }
+
+  pub fn to_csv(&self) -> String {
+    self.to_delimited(',')
+  }
+
+  pub fn to_tsv(&self) -> String {
+    self.to_delimited('\t')
+  }
+
+  /// to_delimited writes the table with its cells separated by `delimiter`. Cells holding the
+  /// delimiter, a quote or a line break are quoted with `"`, the way templates are read
+  pub fn to_delimited(&self, delimiter: char) -> String {
+    let mut out = String::new();
+    let headers = std::iter::once("iri").chain(self.headers.iter().map(|x| x.as_str()));
+    write_line(&mut out, headers, delimiter);
+    for row in self.rows.iter() {
+      let cells: Vec<String> = row.values.iter().map(|x| x.join(&self.separator)).collect();
+      let cells = std::iter::once(row.iri.as_str()).chain(cells.iter().map(|x| x.as_str()));
+      write_line(&mut out, cells, delimiter);
+    }
+    out
+  }
+}
+
+fn write_line<'a>(out: &mut String, cells: impl Iterator<Item = &'a str>, delimiter: char) {
+  for (idx, cell) in cells.enumerate() {
+    if idx > 0 {
+      out.push(delimiter);
+    }
+    match cell.contains([delimiter, '"', '\n', '\r']) {
+      true => out.push_str(&format!("\"{}\"", cell.replace('"', "\"\""))),
+      false => out.push_str(cell),
+    }
+  }
+  out.push('\n');
+}
+
+impl TurtleDocument {
+  /// annotation_table returns the values of the annotation properties of the options for the
+  /// entities of the kinds of the options. Values of a cell are in the order they were stated
+  pub fn annotation_table(&self, options: &TableOptions) -> AnnotationTable {
+    let triples = self.triples();
+    let columns: Vec<BTreeSet<String>> = options
+      .columns
+      .iter()
+      .map(|x| sub_properties(&triples, &x.property))
+      .collect();
+
+    // literal values per entity and column
+    let mut literals: BTreeMap<(&str, usize), Vec<_>> = BTreeMap::new();
+    for t in triples.iter().filter(|t| t.object.is_literal()) {
+      let (subject, predicate) = match (t.subject.as_iri(), t.predicate.as_iri()) {
+        (Some(s), Some(p)) => (s, p),
+        _ => continue,
+      };
+      for (idx, properties) in columns.iter().enumerate() {
+        if properties.contains(predicate) {
+          literals
+            .entry((subject, idx))
+            .or_default()
+            .push(t.object.clone());
+        }
+      }
+    }
+
+    let mut rows = vec![];
+    for (iri, kinds) in self.entities().into_iter() {
+      if !options.entity_types.iter().any(|x| kinds.contains(x)) {
+        continue;
+      }
+      let mut values = vec![];
+      for (idx, column) in options.columns.iter().enumerate() {
+        let labels = Labels::new(
+          literals
+            .get(&(iri.as_str(), idx))
+            .cloned()
+            .unwrap_or_default(),
+        );
+        let labels = match &column.language {
+          Some(range) => labels.lang(range),
+          None => labels,
+        };
+        let mut cell: Vec<String> = vec![];
+        for v in labels.values() {
+          if !cell.iter().any(|x| x == v) {
+            cell.push(String::from(v));
+          }
+        }
+        values.push(cell);
+      }
+      rows.push(Row::new(iri, values));
+    }
+    let headers = options.columns.iter().map(|x| x.header.clone()).collect();
+    AnnotationTable::new(headers, rows, &options.separator)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix oboInOwl: <http://www.geneontology.org/formats/oboInOwl#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+@prefix ex: <http://example.org/> .
+
+skos:prefLabel rdfs:subPropertyOf rdfs:label .
+ex:Agent a owl:Class ; skos:prefLabel "Agent"@en ; rdfs:label "Agent"@fr ;
+  obo:IAO_0000115 "An entity that acts, e.g a \"person\", or an organization" ;
+  oboInOwl:hasExactSynonym "Actor" , "Doer" .
+ex:Person a owl:Class ; rdfs:label "Person"@en .
+ex:JohnDoe a ex:Person .
+"#;
+
+  #[test]
+  fn should_export_annotation_table() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let table = document.annotation_table(&TableOptions::default());
+    assert_eq!(table.headers, vec!["label", "definition", "synonyms"]);
+    let iris: Vec<&str> = table.rows.iter().map(|x| x.iri.as_str()).collect();
+    assert_eq!(
+      iris,
+      vec![
+        "http://example.org/Agent",
+        "http://example.org/JohnDoe",
+        "http://example.org/Person"
+      ]
+    );
+    assert_eq!(table.rows[0].values[0], vec!["Agent"]);
+    assert_eq!(table.rows[0].values[2], vec!["Actor", "Doer"]);
+    assert_eq!(
+      table.to_csv(),
+      r#"iri,label,definition,synonyms
+http://example.org/Agent,Agent,"An entity that acts, e.g a ""person"", or an organization",Actor|Doer
+http://example.org/JohnDoe,,,
+http://example.org/Person,Person,,
+"#
+    );
+    assert!(table
+      .to_tsv()
+      .contains("\t\"An entity that acts, e.g a \"\"person\"\", or an organization\"\t"));
+
+    let options = TableOptions::new(
+      vec![TableColumn::new(
+        "label_fr",
+        RDFS_LABEL,
+        Some(String::from("fr")),
+      )],
+      vec![EntityType::Class],
+      ";",
+    );
+    let table = document.annotation_table(&options);
+    assert_eq!(table.rows[0].values[0], vec!["Agent"]);
+    assert!(table.rows[1].values[0].is_empty());
+    Ok(())
+  }
+}
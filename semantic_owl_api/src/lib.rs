@@ -4,5 +4,18 @@
 //! Semantic Owl Api is not a direct one-to-one implementation of owlapi.
 //! While ideas are borrowed, the implementation is not.
 
+pub mod analysis;
 pub mod declarations;
+pub mod export;
 pub mod loader;
+pub mod store;
+pub mod transforms;
+pub mod vocab;
+pub mod writer;
+
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
@@ -0,0 +1,113 @@
+//! Cache module stores parsed documents on disk
+use crate::declarations::turtle::TurtleDocument;
+use crate::transforms::canonical::sha256_hex;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// extension of the files holding cached documents
+pub const CACHE_ENTRY_EXTENSION: &str = "bin";
+
+/// OntologyCache is a directory of parsed documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct OntologyCache {
+  pub directory: PathBuf,
+}
+
+impl OntologyCache {
+  /// new creates a cache stored in `directory`. The directory is created on the first store
+  pub fn new(directory: &str) -> OntologyCache {
+    Self {
+      directory: PathBuf::from(directory),
+    }
+  }
+
+  /// get returns the document parsed from the artifact with checksum `sha256` of the ontology `iri`.
+  /// Missing or unreadable entries, e.g written by another version of the crate, are a miss
+  pub fn get(&self, iri: &str, sha256: &str) -> Option<TurtleDocument> {
+    let bytes = std::fs::read(self.entry_path(iri, sha256)).ok()?;
+    bincode::deserialize(&bytes).ok()
+  }
+
+  /// put stores the document parsed from the artifact with checksum `sha256` of the ontology `iri`
+  pub fn put(&self, iri: &str, sha256: &str, document: &TurtleDocument) -> std::io::Result<()> {
+    let bytes = bincode::serialize(document)
+      .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    std::fs::create_dir_all(&self.directory)?;
+    // entries are written aside then renamed, so readers never see a partial entry
+    let path = self.entry_path(iri, sha256);
+    let partial = path.with_extension(format!("{}.{}", CACHE_ENTRY_EXTENSION, std::process::id()));
+    std::fs::write(&partial, bytes)?;
+    std::fs::rename(&partial, &path)
+  }
+
+  /// clear removes every entry of the cache
+  pub fn clear(&self) -> std::io::Result<()> {
+    if !self.directory.exists() {
+      return Ok(());
+    }
+    for entry in std::fs::read_dir(&self.directory)? {
+      let path = entry?.path();
+      if path.extension().and_then(|x| x.to_str()) == Some(CACHE_ENTRY_EXTENSION) {
+        std::fs::remove_file(path)?;
+      }
+    }
+    Ok(())
+  }
+
+  // entry_path returns the path of the entry of an ontology artifact
+  fn entry_path(&self, iri: &str, sha256: &str) -> PathBuf {
+    let key = sha256_hex(&format!("{}\n{}", iri, sha256));
+    self
+      .directory
+      .join(format!("{}.{}", key, CACHE_ENTRY_EXTENSION))
+  }
+}
+
+/// file_sha256 returns the lowercase hexadecimal SHA-256 digest of a file
+pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+  let mut file = File::open(path)?;
+  let mut hasher = Sha256::new();
+  let mut buffer = [0u8; 8 * 1024];
+  loop {
+    match file.read(&mut buffer)? {
+      0 => break,
+      n => hasher.update(&buffer[..n]),
+    }
+  }
+  Ok(hex_digest(&hasher.finalize()))
+}
+
+/// hex_digest formats a digest as lowercase hexadecimal
+pub fn hex_digest(digest: &[u8]) -> String {
+  digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  #[test]
+  fn should_store_documents() -> std::io::Result<()> {
+    let directory = std::env::temp_dir().join(format!("semantic_owl_cache_{}", std::process::id()));
+    let cache = OntologyCache::new(directory.to_str().unwrap());
+    let iri = "http://example.org/ontology";
+    let document = load_turtle_document_from_str(
+      "@prefix : <http://example.org/> .\n<http://example.org/ontology> a <http://www.w3.org/2002/07/owl#Ontology> .\n:a :b [ :c \"d\"@en ] .",
+    )?;
+    assert_eq!(cache.get(iri, "00"), None);
+    cache.put(iri, "00", &document)?;
+    let cached = cache.get(iri, "00");
+    // a changed artifact is a miss
+    let changed = cache.get(iri, "01");
+    cache.clear()?;
+    let cleared = cache.get(iri, "00");
+    std::fs::remove_dir_all(&directory)?;
+    assert_eq!(cached, Some(document));
+    assert_eq!(changed, None);
+    assert_eq!(cleared, None);
+    Ok(())
+  }
+}
@@ -0,0 +1,69 @@
+//! Cancellation module lets a long load or conversion be aborted from another thread
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Cancelled is the error carried by the `std::io::Error` ending cancelled work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "the operation was cancelled")
+  }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// CancellationToken is shared between the caller and the work it may cancel. Clones of a token
+/// are the same token
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+  cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+  pub fn new() -> CancellationToken {
+    Self::default()
+  }
+
+  /// cancel asks the work holding the token to stop
+  pub fn cancel(&self) {
+    self.cancelled.store(true, Ordering::SeqCst);
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.load(Ordering::SeqCst)
+  }
+
+  /// check returns a cancellation error once the token is cancelled
+  pub fn check(&self) -> std::io::Result<()> {
+    match self.is_cancelled() {
+      true => Err(cancelled()),
+      false => Ok(()),
+    }
+  }
+}
+
+// tokens are equal when they are clones of each other
+impl PartialEq for CancellationToken {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.cancelled, &other.cancelled)
+  }
+}
+
+/// cancelled returns the error ending cancelled work: a `Cancelled` error of kind
+/// `std::io::ErrorKind::Other`. Unlike `Interrupted`, the kind is not one callers retry on
+pub fn cancelled() -> std::io::Error {
+  std::io::Error::other(Cancelled)
+}
+
+/// is_cancelled determines whether an error ended cancelled work, i.e whether it carries `Cancelled`
+pub fn is_cancelled(error: &std::io::Error) -> bool {
+  error.get_ref().is_some_and(|x| x.is::<Cancelled>())
+}
+
+/// check_cancelled returns a cancellation error if an optional token is cancelled
+pub(crate) fn check_cancelled(token: Option<&CancellationToken>) -> std::io::Result<()> {
+  token.map(|x| x.check()).unwrap_or(Ok(()))
+}
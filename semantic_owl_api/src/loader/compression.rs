@@ -0,0 +1,308 @@
+//! Compression module opens documents for the loaders, decompressing them on the fly
+use crate::declarations::provenance::DocumentProvenance;
+use crate::loader::cache::hex_digest;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::SeekFrom;
+use std::rc::Rc;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+
+/// Compression is the compression format of a document, detected from its leading bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+  Gzip,
+  Zip,
+}
+
+impl Compression {
+  /// detect returns the compression format whose magic number starts `head`
+  pub fn detect(head: &[u8]) -> Option<Compression> {
+    if head.starts_with(&GZIP_MAGIC) {
+      Some(Compression::Gzip)
+    } else if head.starts_with(&ZIP_MAGIC) {
+      Some(Compression::Zip)
+    } else {
+      None
+    }
+  }
+}
+
+// DocumentSource is the decompressed content of a document along with what is known about it
+pub(crate) struct DocumentSource {
+  pub reader: Box<dyn Read>,
+
+  // name used to detect the syntax of the document from its extension.
+  // e.g -> `cco.ttl` for `cco.ttl.gz` or the name of the document within a zip archive
+  pub name: String,
+
+  // size in bytes of the decompressed document when known upfront
+  pub size: Option<u64>,
+
+  pub artifact: Artifact,
+}
+
+// Artifact is the document as stored, before any decompression
+pub(crate) struct Artifact {
+  path: String,
+
+  // size in bytes of the stored document
+  size: u64,
+
+  // digest of the stored bytes read so far. It is complete once the document is read to its end
+  digest: Rc<RefCell<Sha256>>,
+}
+
+impl Artifact {
+  // provenance describes the artifact once the document has been read to its end
+  pub fn provenance(&self) -> DocumentProvenance {
+    let sha256 = hex_digest(&self.digest.borrow().clone().finalize());
+    DocumentProvenance::loaded_now(&self.path, self.size, &sha256)
+  }
+}
+
+// DigestReader feeds the bytes read from an artifact to a shared SHA-256 digest
+pub(crate) struct DigestReader<R> {
+  inner: R,
+  digest: Rc<RefCell<Sha256>>,
+}
+
+impl<R: Read> Read for DigestReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.digest.borrow_mut().update(&buf[..n]);
+    Ok(n)
+  }
+}
+
+// open_document opens a document, decompressing it when it is a gzip file or a zip archive
+pub(crate) fn open_document(path: &str) -> std::io::Result<DocumentSource> {
+  let mut file = File::open(path)?;
+  let size = file.metadata()?.len();
+  let mut head = [0u8; 4];
+  let n = read_head(&mut file, &mut head)?;
+  file.seek(SeekFrom::Start(0))?;
+  let digest = Rc::new(RefCell::new(Sha256::new()));
+  let reader = DigestReader {
+    inner: file,
+    digest: digest.clone(),
+  };
+  let (reader, name, decompressed_size) = match Compression::detect(&head[..n]) {
+    None => (
+      Box::new(reader) as Box<dyn Read>,
+      String::from(path),
+      Some(size),
+    ),
+    Some(compression) => decompress(reader, path, compression)?,
+  };
+  Ok(DocumentSource {
+    reader,
+    name,
+    size: decompressed_size,
+    artifact: Artifact {
+      path: String::from(path),
+      size,
+      digest,
+    },
+  })
+}
+
+// Decompressed is the reader of a decompressed document, its name and its size when known upfront
+type Decompressed = (Box<dyn Read>, String, Option<u64>);
+
+// read_head fills `head` with the leading bytes of a file, returning how many were read
+fn read_head(file: &mut File, head: &mut [u8]) -> std::io::Result<usize> {
+  let mut n = 0;
+  while n < head.len() {
+    match file.read(&mut head[n..])? {
+      0 => break,
+      read => n += read,
+    }
+  }
+  Ok(n)
+}
+
+#[cfg(feature = "compression")]
+fn decompress(
+  reader: DigestReader<File>,
+  path: &str,
+  compression: Compression,
+) -> std::io::Result<Decompressed> {
+  match compression {
+    Compression::Gzip => {
+      let name = std::path::Path::new(path)
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .unwrap_or(path);
+      Ok((
+        Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        String::from(name),
+        None,
+      ))
+    }
+    Compression::Zip => {
+      // archives are read with seeks. The whole archive is read, and digested, upfront
+      let mut bytes = vec![];
+      let mut reader = reader;
+      reader.read_to_end(&mut bytes)?;
+      let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(into_io_error)?;
+      let names: Vec<String> = archive
+        .file_names()
+        .filter(|x| !x.ends_with('/'))
+        .map(String::from)
+        .collect();
+      // the first document with an ontology extension, or the only document of the archive
+      let name = names
+        .iter()
+        .find(|x| crate::loader::load::syntax_of_extension(x).is_some())
+        .or_else(|| match names.len() {
+          1 => names.first(),
+          _ => None,
+        })
+        .cloned()
+        .ok_or_else(|| {
+          std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "the archive holds no ontology document",
+          )
+        })?;
+      // entries borrow the archive. The document is decompressed into memory
+      let mut entry = archive.by_name(&name).map_err(into_io_error)?;
+      let mut content = Vec::with_capacity(entry.size() as usize);
+      entry.read_to_end(&mut content)?;
+      let size = content.len() as u64;
+      Ok((Box::new(std::io::Cursor::new(content)), name, Some(size)))
+    }
+  }
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress(
+  _reader: DigestReader<File>,
+  _path: &str,
+  compression: Compression,
+) -> std::io::Result<Decompressed> {
+  Err(std::io::Error::new(
+    std::io::ErrorKind::InvalidInput,
+    format!(
+      "{:?} compressed documents require the `compression` feature",
+      compression
+    ),
+  ))
+}
+
+#[cfg(feature = "compression")]
+fn into_io_error(err: zip::result::ZipError) -> std::io::Error {
+  match err {
+    zip::result::ZipError::Io(err) => err,
+    err => std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_detect_compression() {
+    assert_eq!(
+      Compression::detect(&[0x1f, 0x8b, 0x08, 0x00]),
+      Some(Compression::Gzip)
+    );
+    assert_eq!(Compression::detect(b"PK\x03\x04"), Some(Compression::Zip));
+    assert_eq!(Compression::detect(b"@prefix"), None);
+    assert_eq!(Compression::detect(&[]), None);
+  }
+
+  #[cfg(feature = "compression")]
+  const DOCUMENT: &str = "@prefix : <http://example.org/> .\n:a :b :c .\n";
+
+  // write_temp writes a file named after the test process into the temporary directory
+  fn write_temp(name: &str, content: &[u8]) -> std::io::Result<std::path::PathBuf> {
+    let path = std::env::temp_dir().join(format!("semantic_owl_{}_{}", std::process::id(), name));
+    std::fs::write(&path, content)?;
+    Ok(path)
+  }
+
+  #[cfg(feature = "compression")]
+  #[test]
+  fn should_load_gzip_document() -> std::io::Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    encoder.write_all(DOCUMENT.as_bytes())?;
+    let path = write_temp("document.ttl.gz", &encoder.finish()?)?;
+    let path = path.to_str().unwrap();
+    let source = open_document(path).map(|x| (x.name, x.size));
+    let document = crate::loader::load::load_document(path);
+    let stored_size = std::fs::metadata(path)?.len();
+    std::fs::remove_file(path)?;
+    assert_eq!(
+      source?,
+      (
+        format!("semantic_owl_{}_document.ttl", std::process::id()),
+        None
+      )
+    );
+    let document = document?;
+    // provenance describes the compressed artifact
+    assert_eq!(document.provenance.as_ref().unwrap().size, stored_size);
+    assert_eq!(
+      document,
+      crate::loader::load::load_turtle_document_from_str(DOCUMENT)?
+    );
+    Ok(())
+  }
+
+  #[cfg(feature = "compression")]
+  #[test]
+  fn should_load_zipped_document() -> std::io::Result<()> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    let options = zip::write::FileOptions::default();
+    writer
+      .start_file("README.md", options)
+      .map_err(into_io_error)?;
+    writer.write_all(b"# release")?;
+    writer
+      .start_file("release/cco.ttl", options)
+      .map_err(into_io_error)?;
+    writer.write_all(DOCUMENT.as_bytes())?;
+    let archive = writer.finish().map_err(into_io_error)?.into_inner();
+    let path = write_temp("release.zip", &archive)?;
+    let path = path.to_str().unwrap();
+    let document = crate::loader::load::load_turtle_document(path);
+    std::fs::remove_file(path)?;
+    assert_eq!(
+      document?,
+      crate::loader::load::load_turtle_document_from_str(DOCUMENT)?
+    );
+
+    // archives without an ontology document are rejected
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    writer
+      .start_file("README.md", options)
+      .map_err(into_io_error)?;
+    writer.write_all(b"# release")?;
+    writer
+      .start_file("LICENSE", options)
+      .map_err(into_io_error)?;
+    writer.write_all(b"CC-BY-4.0")?;
+    let archive = writer.finish().map_err(into_io_error)?.into_inner();
+    let path = write_temp("readme.zip", &archive)?;
+    let result = open_document(path.to_str().unwrap()).map(|_| ());
+    std::fs::remove_file(&path)?;
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+  }
+
+  #[cfg(not(feature = "compression"))]
+  #[test]
+  fn should_reject_compressed_document() -> std::io::Result<()> {
+    let path = write_temp("document.ttl.gz", &[0x1f, 0x8b, 0x08, 0x00])?;
+    let result = open_document(path.to_str().unwrap()).map(|_| ());
+    std::fs::remove_file(&path)?;
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    Ok(())
+  }
+}
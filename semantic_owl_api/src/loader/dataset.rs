@@ -0,0 +1,199 @@
+//! Dataset module manages the documents of an `OntologyManager` as named graphs
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::provenance::DocumentProvenance;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::loader::manager::OntologyManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Quad is a triple along with the name of the graph holding it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Quad {
+  // e.g -> http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology
+  pub graph: String,
+
+  pub triple: TurtleTriple,
+}
+
+impl Quad {
+  pub fn new(graph: &str, triple: TurtleTriple) -> Quad {
+    Self {
+      graph: String::from(graph),
+      triple,
+    }
+  }
+}
+
+/// GraphIndex keeps the indexed graph of each document of a dataset by graph name
+#[derive(Debug, Default)]
+pub(crate) struct GraphIndex {
+  graphs: Mutex<HashMap<String, Arc<TripleGraph>>>,
+}
+
+impl GraphIndex {
+  // graph returns the indexed graph of a document, indexing it on first use
+  fn graph(&self, name: &str, document: &TurtleDocument) -> Arc<TripleGraph> {
+    let mut graphs = self.graphs.lock().unwrap_or_else(|x| x.into_inner());
+    let graph = graphs
+      .entry(String::from(name))
+      .or_insert_with(|| Arc::new(document.graph()));
+    Arc::clone(graph)
+  }
+
+  // invalidate drops the graph of a document which is replaced or removed
+  pub(crate) fn invalidate(&mut self, name: &str) {
+    let graphs = self.graphs.get_mut().unwrap_or_else(|x| x.into_inner());
+    graphs.remove(name);
+  }
+}
+
+impl OntologyManager {
+  /// insert_graph adds a document to the dataset as the graph `name`, replacing the graph of the
+  /// same name if any. The replaced document is returned
+  pub fn insert_graph(&mut self, name: &str, document: TurtleDocument) -> Option<TurtleDocument> {
+    self.graphs.invalidate(name);
+    self.documents.insert(String::from(name), document)
+  }
+
+  /// load_graph loads the document at `path` as the graph `name`. Unlike `load`, the imports of the
+  /// document are not loaded
+  /// example
+  /// `load_graph("http://example.org/data", "data/agents.ttl")`
+  pub fn load_graph(&mut self, name: &str, path: &str) -> std::io::Result<()> {
+    self.load_location(path, Some(name)).map(|_| ())
+  }
+
+  /// remove_graph removes a graph from the dataset and returns its document
+  pub fn remove_graph(&mut self, name: &str) -> Option<TurtleDocument> {
+    self.graphs.invalidate(name);
+    self.documents.remove(name)
+  }
+
+  /// reindex_graph drops the index of a graph whose document was changed in place through
+  /// `documents`. It is built again on the next query
+  pub fn reindex_graph(&mut self, name: &str) {
+    self.graphs.invalidate(name);
+  }
+
+  /// graph_names returns the names of the graphs of the dataset, sorted
+  pub fn graph_names(&self) -> impl Iterator<Item = &str> {
+    self.documents.keys().map(|x| x.as_str())
+  }
+
+  /// provenance returns the provenance of a graph. Graphs inserted from memory have none
+  pub fn provenance(&self, name: &str) -> Option<&DocumentProvenance> {
+    self.document(name).and_then(|x| x.provenance.as_ref())
+  }
+
+  /// quads_matching returns the triples of all graphs matching a pattern, where `None` matches
+  /// any term. Quads are ordered by graph name, then by the order of the triples in their graph
+  pub fn quads_matching(
+    &self,
+    subject: Option<&TurtleTerm>,
+    predicate: Option<&TurtleTerm>,
+    object: Option<&TurtleTerm>,
+  ) -> Vec<Quad> {
+    let mut quads = vec![];
+    for (name, document) in self.documents.iter() {
+      let graph = self.graphs.graph(name, document);
+      quads.extend(
+        graph
+          .triples_matching(subject, predicate, object)
+          .map(|t| Quad::new(name, t.clone())),
+      );
+    }
+    quads
+  }
+
+  /// triples_about returns the triples of all graphs whose subject or object is an IRI. Quads are
+  /// ordered by graph name, then the triples about the IRI as subject come before the others
+  pub fn triples_about(&self, iri: &str) -> Vec<Quad> {
+    let term = TurtleTerm::Iri(String::from(iri));
+    let mut quads = vec![];
+    for (name, document) in self.documents.iter() {
+      let graph = self.graphs.graph(name, document);
+      let subjects = graph.triples_matching(Some(&term), None, None);
+      // a triple about the IRI as both subject and object is listed once
+      let objects = graph
+        .triples_matching(None, None, Some(&term))
+        .filter(|t| t.subject != term);
+      quads.extend(subjects.chain(objects).map(|t| Quad::new(name, t.clone())));
+    }
+    quads
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const UPPER: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<http://example.org/upper> a owl:Ontology .
+<http://example.org/Agent> a owl:Class .
+"#;
+
+  const DATA: &str = r#"@prefix : <http://example.org/> .
+:JohnDoe a :Agent ; :knows :JaneDoe .
+:JaneDoe a :Agent .
+"#;
+
+  fn iri(x: &str) -> TurtleTerm {
+    TurtleTerm::Iri(String::from(x))
+  }
+
+  #[test]
+  fn should_query_across_graphs() -> std::io::Result<()> {
+    let path =
+      std::env::temp_dir().join(format!("semantic_owl_dataset_{}.ttl", std::process::id()));
+    std::fs::write(&path, DATA)?;
+    let mut manager = OntologyManager::new();
+    manager.insert_graph(
+      "http://example.org/upper",
+      load_turtle_document_from_str(UPPER)?,
+    );
+    let loaded = manager.load_graph("http://example.org/data", path.to_str().unwrap());
+    std::fs::remove_file(&path)?;
+    loaded?;
+
+    assert_eq!(
+      manager.graph_names().collect::<Vec<_>>(),
+      vec!["http://example.org/data", "http://example.org/upper"]
+    );
+    assert!(manager.provenance("http://example.org/data").is_some());
+    assert!(manager.provenance("http://example.org/upper").is_none());
+
+    let about = manager.triples_about("http://example.org/Agent");
+    let graphs: Vec<&str> = about.iter().map(|x| x.graph.as_str()).collect();
+    assert_eq!(
+      graphs,
+      vec![
+        "http://example.org/data",
+        "http://example.org/data",
+        "http://example.org/upper"
+      ]
+    );
+
+    let knows = iri("http://example.org/knows");
+    let quads = manager.quads_matching(None, Some(&knows), None);
+    assert_eq!(quads.len(), 1);
+    assert_eq!(quads[0].triple.object, iri("http://example.org/JaneDoe"));
+
+    assert!(manager.remove_graph("http://example.org/data").is_some());
+    assert_eq!(manager.triples_about("http://example.org/Agent").len(), 1);
+
+    // a replaced or changed document is indexed again
+    manager.insert_graph(
+      "http://example.org/data",
+      load_turtle_document_from_str(DATA)?,
+    );
+    assert_eq!(manager.triples_about("http://example.org/Agent").len(), 3);
+    if let Some(document) = manager.documents.get_mut("http://example.org/data") {
+      document.body.pop_back();
+    }
+    manager.reindex_graph("http://example.org/data");
+    assert_eq!(manager.triples_about("http://example.org/Agent").len(), 2);
+    Ok(())
+  }
+}
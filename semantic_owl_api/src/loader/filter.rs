@@ -0,0 +1,176 @@
+//! Filter module drops unwanted statements while a document is parsed
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleBodyItem, TurtleTerm, TurtleTriple};
+use crate::vocab::owl;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+const OWL_DEPRECATED: &str = owl::DEPRECATED.as_str();
+
+type TripleFn = dyn Fn(&TurtleTriple) -> bool + Send + Sync;
+type StatementFn = dyn Fn(&[TurtleTriple]) -> bool + Send + Sync;
+
+/// StatementFilter decides which statements of a document are loaded. Clones of a filter are the
+/// same filter
+#[derive(Clone)]
+pub enum StatementFilter {
+  // keeps the triples for which the function returns `true`
+  Triple(Arc<TripleFn>),
+
+  // keeps the statements, i.e a subject with its predicate list and the blank nodes nested in it,
+  // for whose triples the function returns `true`
+  Statement(Arc<StatementFn>),
+}
+
+impl StatementFilter {
+  /// triples creates a filter keeping the triples a function accepts
+  /// example
+  /// `StatementFilter::triples(|t| !t.object.is_literal())`
+  pub fn triples<F: Fn(&TurtleTriple) -> bool + Send + Sync + 'static>(f: F) -> StatementFilter {
+    StatementFilter::Triple(Arc::new(f))
+  }
+
+  /// statements creates a filter keeping the statements whose triples a function accepts
+  pub fn statements<F: Fn(&[TurtleTriple]) -> bool + Send + Sync + 'static>(
+    f: F,
+  ) -> StatementFilter {
+    StatementFilter::Statement(Arc::new(f))
+  }
+
+  /// without_deprecated creates a filter dropping the statements stating `owl:deprecated true`
+  pub fn without_deprecated() -> StatementFilter {
+    Self::statements(|triples| {
+      !triples.iter().any(|t| {
+        t.predicate.as_iri() == Some(OWL_DEPRECATED)
+          && matches!(&t.object, TurtleTerm::Literal { value, .. } if value == "true")
+      })
+    })
+  }
+
+  /// without_namespace creates a filter dropping the triples whose subject, predicate or object
+  /// is an IRI of a namespace
+  /// example
+  /// `StatementFilter::without_namespace("http://purl.obolibrary.org/obo/")`
+  pub fn without_namespace(namespace: &str) -> StatementFilter {
+    let namespace = String::from(namespace);
+    Self::triples(move |t| {
+      [&t.subject, &t.predicate, &t.object].iter().all(|x| {
+        !x.as_iri()
+          .is_some_and(|x| x.starts_with(namespace.as_str()))
+      })
+    })
+  }
+
+  /// apply removes the statements the filter rejects from the body items of a statement, resolved
+  /// with a prefix map
+  pub fn apply(&self, map: &PrefixMap, items: &mut VecDeque<TurtleBodyItem>) {
+    match self {
+      StatementFilter::Triple(f) => {
+        for item in items.iter_mut() {
+          let subject = match item.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+            Some(x) => x,
+            None => continue,
+          };
+          for p in item.predicate.iter_mut() {
+            let predicate = match p
+              .raw_predicate_object
+              .as_deref()
+              .and_then(|x| map.resolve_term(x))
+            {
+              Some(x) => x,
+              None => continue,
+            };
+            p.object.retain(
+              |o| match o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+                Some(object) => f(&TurtleTriple::new(
+                  subject.clone(),
+                  predicate.clone(),
+                  object,
+                )),
+                None => true,
+              },
+            );
+          }
+          item.predicate.retain(|p| !p.object.is_empty());
+        }
+        items.retain(|x| !x.predicate.is_empty());
+      }
+      StatementFilter::Statement(f) => {
+        let mut triples = vec![];
+        for item in items.iter() {
+          item.resolve(map, &mut triples);
+        }
+        if !triples.is_empty() && !f(&triples) {
+          items.clear();
+        }
+      }
+    }
+  }
+}
+
+impl fmt::Debug for StatementFilter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StatementFilter::Triple(_) => write!(f, "StatementFilter::Triple"),
+      StatementFilter::Statement(_) => write!(f, "StatementFilter::Statement"),
+    }
+  }
+}
+
+// filters are equal when they are clones of each other
+impl PartialEq for StatementFilter {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (StatementFilter::Triple(x), StatementFilter::Triple(y)) => Arc::ptr_eq(x, y),
+      (StatementFilter::Statement(x), StatementFilter::Statement(y)) => Arc::ptr_eq(x, y),
+      _ => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::{load_turtle_document_from_str_with_report, LoaderOptions};
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:subClassOf obo:BFO_0000040 ; rdfs:label "Agent"@en .
+cco:Robot a owl:Class ; owl:deprecated true ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Motor ] .
+obo:BFO_0000040 a owl:Class .
+"#;
+
+  fn load(filter: StatementFilter) -> std::io::Result<Vec<TurtleTriple>> {
+    let options = LoaderOptions {
+      statement_filter: Some(filter),
+      ..LoaderOptions::default()
+    };
+    let (document, _) = load_turtle_document_from_str_with_report(DOCUMENT, &options)?;
+    Ok(document.triples())
+  }
+
+  #[test]
+  fn should_filter_statements_on_load() -> std::io::Result<()> {
+    // the deprecated class and its nested restriction are dropped
+    let triples = load(StatementFilter::without_deprecated())?;
+    assert_eq!(triples.len(), 4);
+    assert!(triples.iter().all(|t| !t.subject.is_blank_node()));
+
+    let triples = load(StatementFilter::without_namespace(
+      "http://purl.obolibrary.org/obo/",
+    ))?;
+    assert_eq!(triples.len(), 8);
+    assert!(triples
+      .iter()
+      .all(|t| !t.to_string().contains("BFO_0000040")));
+
+    let triples = load(StatementFilter::triples(|t| t.object.is_literal()))?;
+    assert_eq!(triples.len(), 2);
+    Ok(())
+  }
+}
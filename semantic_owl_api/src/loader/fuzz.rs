@@ -0,0 +1,106 @@
+//! Fuzz module exposes the parsers to fuzzers
+use crate::loader::iri_mapper::CatalogIriMapper;
+use crate::loader::load::{detect_syntax, load_turtle_document_from_str};
+
+/// fuzz_parse parses arbitrary bytes as a turtle document and as an XML catalog, then walks the
+/// triples and the graph of the document. Input which is not UTF-8 is ignored
+pub fn fuzz_parse(input: &[u8]) {
+  let content = match std::str::from_utf8(input) {
+    Ok(x) => x,
+    Err(_) => return,
+  };
+  let _ = detect_syntax("", content);
+  let _ = CatalogIriMapper::from_str(content, "");
+  if let Ok(document) = load_turtle_document_from_str(content) {
+    let _ = document.prefix_map();
+    let graph = document.graph();
+    for triple in graph.triples() {
+      let _ = graph.list(&triple.object);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_not_panic_on_adversarial_input() {
+    let nested = format!(
+      "<http://example.org/s> <http://example.org/p> {} .",
+      "[ <http://example.org/p> ".repeat(100_000)
+    );
+    let collections = format!(
+      "<http://example.org/s> <http://example.org/p> {}{} .",
+      "( ".repeat(100_000),
+      ") ".repeat(100_000)
+    );
+    let inputs = [
+      "",
+      "#",
+      "a #",
+      "@prefix",
+      "@prefix : .",
+      "@base <",
+      "<http://example.org/s> <http://example.org/p> \"é\" # é",
+      "<http://example.org/s> <http://example.org/p> \"unterminated .",
+      "_: _: _: .",
+      "[ ] .",
+      "( ) .",
+      "\"\\u",
+      "<?xml version=\"1.0\"?><catalog><uri name=\"é\" uri=\"&#xFFFFFFFF;\"/>",
+      nested.as_str(),
+      collections.as_str(),
+    ];
+    for input in inputs.iter() {
+      fuzz_parse(input.as_bytes());
+    }
+    fuzz_parse(&[0xff, 0xfe, 0x00]);
+  }
+
+  #[test]
+  fn should_reject_excessive_nesting() {
+    let input = |depth: usize| {
+      format!(
+        "<http://example.org/s> <http://example.org/p> {}<http://example.org/o>{} .",
+        "( ".repeat(depth),
+        " )".repeat(depth)
+      )
+    };
+    let max = crate::loader::parsers::ttl_statement_parser::MAX_NESTING_DEPTH;
+    assert!(load_turtle_document_from_str(&input(max)).is_ok());
+    assert!(load_turtle_document_from_str(&input(max + 1)).is_err());
+  }
+
+  #[test]
+  fn should_not_panic_on_mutated_documents() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/AgentOntology.ttl");
+    let document = std::fs::read(path)?;
+    // xorshift. The mutations are the same on every run
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      state as usize
+    };
+    for _ in 0..200 {
+      let start = next() % document.len();
+      let end = (start + next() % 512).min(document.len());
+      let mut input = document[start..end].to_vec();
+      for _ in 0..next() % 4 {
+        if input.is_empty() {
+          break;
+        }
+        let at = next() % input.len();
+        input[at] = b"[]()<>\"'#.;,:_\\"[next() % 15];
+      }
+      fuzz_parse(&input);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,203 @@
+//! Handler module parses turtle documents in push mode
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleBodyItem, TurtleHeaderItem, TurtleTriple};
+use crate::loader::compression::open_document;
+use crate::loader::load::{read_source, read_str, LoaderOptions, StatementSink};
+use std::collections::VecDeque;
+
+/// TurtleHandler receives the content of a turtle document while it is parsed.
+/// All methods do nothing by default, so a handler only implements the events it needs
+pub trait TurtleHandler {
+  /// on_base is called for a `@base` or `BASE` directive, with the base IRI
+  fn on_base(&mut self, _iri: &str) {}
+
+  /// on_prefix is called for a `@prefix` or `PREFIX` directive.
+  /// example
+  /// `on_prefix("owl", "http://www.w3.org/2002/07/owl#")`
+  fn on_prefix(&mut self, _prefix: &str, _namespace: &str) {}
+
+  /// on_triple is called for each triple of a statement, resolved with the directives seen so far.
+  /// Triples whose terms use an undeclared prefix are skipped
+  fn on_triple(&mut self, _triple: &TurtleTriple) {}
+
+  /// on_comment is called for each line holding only a comment, with the text after the `#` marker.
+  /// Comments within statements are not reported
+  fn on_comment(&mut self, _comment: &str) {}
+
+  /// on_error is called once with the error which stops the parsing, before it is returned
+  fn on_error(&mut self, _error: &std::io::Error) {}
+}
+
+/// parse_turtle_with_handler streams a turtle document, gzip and zip documents included when the
+/// `compression` feature is enabled, into a handler
+pub fn parse_turtle_with_handler<H: TurtleHandler>(
+  path: &str,
+  handler: &mut H,
+) -> std::io::Result<()> {
+  let mut sink = HandlerSink::new(handler);
+  let result = open_document(path)
+    .and_then(|x| read_source(x.reader, x.size, &LoaderOptions::default(), &mut sink))
+    .map(|_| ());
+  sink.report(result)
+}
+
+/// parse_turtle_str_with_handler streams turtle content held in memory into a handler
+pub fn parse_turtle_str_with_handler<H: TurtleHandler>(
+  content: &str,
+  handler: &mut H,
+) -> std::io::Result<()> {
+  let mut sink = HandlerSink::new(handler);
+  let result = read_str(content, &mut sink);
+  sink.report(result)
+}
+
+// HandlerSink resolves the statements loaded into triples and passes them to a handler
+struct HandlerSink<'a, H: TurtleHandler> {
+  handler: &'a mut H,
+
+  // prefixes and base declared so far
+  map: PrefixMap,
+
+  // triples of the statement being passed on. Re-used across statements
+  triples: Vec<TurtleTriple>,
+}
+
+impl<'a, H: TurtleHandler> HandlerSink<'a, H> {
+  fn new(handler: &'a mut H) -> HandlerSink<'a, H> {
+    Self {
+      handler,
+      map: PrefixMap::new(),
+      triples: vec![],
+    }
+  }
+
+  // report passes the error ending the parsing, if any, to the handler
+  fn report(&mut self, result: std::io::Result<()>) -> std::io::Result<()> {
+    if let Err(e) = &result {
+      self.handler.on_error(e);
+    }
+    result
+  }
+}
+
+impl<'a, H: TurtleHandler> StatementSink for HandlerSink<'a, H> {
+  fn header(&mut self, header: TurtleHeaderItem) {
+    header.declare(&mut self.map);
+    let iri = match &header.prefix_iri {
+      Some(x) => x.trim_start_matches('<').trim_end_matches('>'),
+      None => return,
+    };
+    match (header.is_base, &header.prefix_namespace) {
+      (true, _) => self.handler.on_base(iri),
+      (false, Some(prefix)) => self.handler.on_prefix(prefix, iri),
+      (false, None) => {}
+    }
+  }
+
+  fn body(&mut self, items: VecDeque<TurtleBodyItem>) {
+    for item in items.iter() {
+      self.triples.clear();
+      item.resolve(&self.map, &mut self.triples);
+      for t in self.triples.iter() {
+        self.handler.on_triple(t);
+      }
+    }
+  }
+
+  fn comment(&mut self, comment: &str) {
+    self.handler.on_comment(comment);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document;
+
+  #[derive(Default)]
+  struct Counter {
+    prefixes: Vec<(String, String)>,
+    base: Option<String>,
+    triples: Vec<TurtleTriple>,
+    comments: usize,
+    errors: usize,
+  }
+
+  impl TurtleHandler for Counter {
+    fn on_base(&mut self, iri: &str) {
+      self.base = Some(String::from(iri));
+    }
+
+    fn on_prefix(&mut self, prefix: &str, namespace: &str) {
+      self
+        .prefixes
+        .push((String::from(prefix), String::from(namespace)));
+    }
+
+    fn on_triple(&mut self, triple: &TurtleTriple) {
+      self.triples.push(triple.clone());
+    }
+
+    fn on_comment(&mut self, _comment: &str) {
+      self.comments += 1;
+    }
+
+    fn on_error(&mut self, _error: &std::io::Error) {
+      self.errors += 1;
+    }
+  }
+
+  #[test]
+  fn should_stream_document_into_handler() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/AgentOntology.ttl");
+    let path = path.to_str().unwrap();
+    let mut counter = Counter::default();
+    parse_turtle_with_handler(path, &mut counter)?;
+    let document = load_turtle_document(path)?;
+    assert_eq!(counter.triples, document.triples());
+    assert!(counter.base.is_some());
+    assert_eq!(counter.prefixes.len() + 1, document.headers.len());
+    assert!(counter.comments > 0);
+    assert_eq!(counter.errors, 0);
+    Ok(())
+  }
+
+  #[test]
+  fn should_resolve_triples_with_directives_seen_so_far() -> std::io::Result<()> {
+    let content = r#"
+@base <http://example.org/> .
+# agents
+<Agent> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> owl:Class .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<Person> a owl:Class .
+"#;
+    let mut counter = Counter::default();
+    parse_turtle_str_with_handler(content, &mut counter)?;
+    assert_eq!(counter.base.as_deref(), Some("http://example.org/"));
+    assert_eq!(counter.comments, 1);
+    // owl:Class is not yet declared when the first statement is parsed
+    assert_eq!(counter.triples.len(), 1);
+    assert_eq!(
+      counter.triples[0].to_string(),
+      "<http://example.org/Person> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> ."
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_report_errors_to_handler() {
+    let mut counter = Counter::default();
+    let content = "<http://example.org/s> <http://example.org/p> <http://example.org/o> .\n<http://example.org/s> <";
+    assert!(parse_turtle_str_with_handler(content, &mut counter).is_err());
+    assert_eq!(counter.triples.len(), 1);
+    assert_eq!(counter.errors, 1);
+
+    let mut counter = Counter::default();
+    assert!(parse_turtle_with_handler("/not/a/document.ttl", &mut counter).is_err());
+    assert_eq!(counter.errors, 1);
+  }
+}
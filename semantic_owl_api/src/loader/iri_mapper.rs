@@ -0,0 +1,300 @@
+//! IRI mapper module maps the IRIs of ontologies to the locations of their documents
+use crate::declarations::prefix_map::{has_scheme, PrefixMap};
+use crate::loader::parsers::xml_parser::{xml_tags, XmlTag};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// file name Protégé gives to the catalog of an ontology directory
+pub const PROTEGE_CATALOG: &str = "catalog-v001.xml";
+
+/// IriMapper maps ontology IRIs to document locations. Mappers are `Send + Sync`, so managers
+/// holding them can be shared between threads
+pub trait IriMapper: Send + Sync {
+  /// document_iri returns the location, a path or an absolute IRI, of the document of an ontology
+  /// given as a full IRI. `None` means the ontology is not mapped and is loaded from its IRI
+  fn document_iri(&self, ontology_iri: &str) -> Option<String>;
+}
+
+/// SimpleIriMapper maps ontology IRIs to locations one by one
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SimpleIriMapper {
+  pub mappings: BTreeMap<String, String>,
+}
+
+impl SimpleIriMapper {
+  pub fn new() -> SimpleIriMapper {
+    Self::default()
+  }
+
+  /// insert adds or replaces the location of an ontology
+  /// example
+  /// `insert("http://purl.obolibrary.org/obo/bfo.owl", "imports/bfo.owl")`
+  pub fn insert(&mut self, ontology_iri: &str, location: &str) {
+    self
+      .mappings
+      .insert(String::from(ontology_iri), String::from(location));
+  }
+}
+
+impl IriMapper for SimpleIriMapper {
+  fn document_iri(&self, ontology_iri: &str) -> Option<String> {
+    self.mappings.get(ontology_iri).cloned()
+  }
+}
+
+/// CatalogEntry is a mapping entry of an XML catalog. Locations are resolved against the
+/// catalog, or the `xml:base` of their group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CatalogEntry {
+  // e.g -> <uri name="http://purl.obolibrary.org/obo/bfo.owl" uri="imports/bfo.owl"/>
+  Uri {
+    name: String,
+    uri: String,
+  },
+
+  // e.g -> <rewriteURI uriStartString="http://purl.obolibrary.org/obo/" rewritePrefix="obo/"/>
+  RewriteUri {
+    uri_start: String,
+    rewrite_prefix: String,
+  },
+}
+
+/// CatalogIriMapper maps ontology IRIs with the entries of an XML catalog.
+/// As with Protégé, the first `uri` entry naming an IRI wins. Otherwise the `rewriteURI` entry
+/// with the longest matching start rewrites the IRI
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CatalogIriMapper {
+  pub entries: Vec<CatalogEntry>,
+}
+
+impl CatalogIriMapper {
+  pub fn new(entries: Vec<CatalogEntry>) -> CatalogIriMapper {
+    Self { entries }
+  }
+
+  /// from_file reads a catalog and the catalogs it delegates to with `nextCatalog`.
+  /// Relative locations are resolved against the directory of the catalog declaring them
+  /// example
+  /// `from_file("src/ontology/catalog-v001.xml")`
+  pub fn from_file(path: &str) -> std::io::Result<CatalogIriMapper> {
+    let mut entries = vec![];
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![String::from(path)];
+    while let Some(path) = pending.pop() {
+      // catalogs delegating back to each other are read once
+      let key = std::fs::canonicalize(&path).unwrap_or_else(|_| Path::new(&path).to_path_buf());
+      if !visited.insert(key) {
+        continue;
+      }
+      let content = std::fs::read_to_string(&path)?;
+      let base = Path::new(&path)
+        .parent()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+      let (found, next) = parse_catalog(&content, base)?;
+      entries.extend(found);
+      // next catalogs are consulted in document order
+      pending.extend(next.into_iter().rev());
+    }
+    Ok(Self::new(entries))
+  }
+
+  /// from_str reads a catalog held in memory, resolving relative locations against `base`,
+  /// a directory path or an absolute IRI. `nextCatalog` entries are ignored
+  pub fn from_str(content: &str, base: &str) -> std::io::Result<CatalogIriMapper> {
+    let (entries, _) = parse_catalog(content, base)?;
+    Ok(Self::new(entries))
+  }
+}
+
+impl IriMapper for CatalogIriMapper {
+  fn document_iri(&self, ontology_iri: &str) -> Option<String> {
+    let uri = self.entries.iter().find_map(|x| match x {
+      CatalogEntry::Uri { name, uri } if name == ontology_iri => Some(uri.clone()),
+      _ => None,
+    });
+    if uri.is_some() {
+      return uri;
+    }
+    self
+      .entries
+      .iter()
+      .filter_map(|x| match x {
+        CatalogEntry::RewriteUri {
+          uri_start,
+          rewrite_prefix,
+        } => ontology_iri
+          .strip_prefix(uri_start.as_str())
+          .map(|rest| (uri_start.len(), format!("{}{}", rewrite_prefix, rest))),
+        _ => None,
+      })
+      .max_by_key(|(len, _)| *len)
+      .map(|(_, x)| x)
+  }
+}
+
+// parse_catalog returns the entries of a catalog and the resolved locations of its next catalogs
+fn parse_catalog(content: &str, base: &str) -> std::io::Result<(Vec<CatalogEntry>, Vec<String>)> {
+  let tags = match xml_tags(content) {
+    Ok((_, tags)) => tags,
+    Err(_) => {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the catalog is not a well-formed XML document",
+      ))
+    }
+  };
+  let mut entries = vec![];
+  let mut next = vec![];
+  // `xml:base` of the enclosing elements. Elements without one inherit the base of their parent
+  let mut bases = vec![String::from(base)];
+  for tag in tags.iter() {
+    let current = bases.last().cloned().unwrap_or_default();
+    let empty = match tag {
+      XmlTag::Start { empty, .. } => *empty,
+      XmlTag::End(_) => {
+        if bases.len() > 1 {
+          bases.pop();
+        }
+        continue;
+      }
+    };
+    let base = match tag.attribute("xml:base") {
+      Some(x) => resolve_location(&current, x),
+      None => current,
+    };
+    match tag.local_name() {
+      "uri" => {
+        if let (Some(name), Some(uri)) = (tag.attribute("name"), tag.attribute("uri")) {
+          entries.push(CatalogEntry::Uri {
+            name: String::from(name),
+            uri: resolve_location(&base, uri),
+          });
+        }
+      }
+      "rewriteURI" => {
+        if let (Some(start), Some(prefix)) = (
+          tag.attribute("uriStartString"),
+          tag.attribute("rewritePrefix"),
+        ) {
+          entries.push(CatalogEntry::RewriteUri {
+            uri_start: String::from(start),
+            rewrite_prefix: resolve_location(&base, prefix),
+          });
+        }
+      }
+      "nextCatalog" => {
+        if let Some(x) = tag.attribute("catalog") {
+          next.push(resolve_location(&base, x));
+        }
+      }
+      _ => {}
+    }
+    if !empty {
+      bases.push(base);
+    }
+  }
+  Ok((entries, next))
+}
+
+// resolve_location resolves a location against a base which is either a directory path or an absolute IRI.
+// An empty location denotes the base itself
+fn resolve_location(base: &str, location: &str) -> String {
+  if has_scheme(location) || Path::new(location).is_absolute() {
+    return String::from(location);
+  }
+  if has_scheme(base) {
+    let map = PrefixMap {
+      base: Some(String::from(base)),
+      prefixes: BTreeMap::new(),
+    };
+    return map.resolve_iri(location);
+  }
+  match location.is_empty() {
+    true => String::from(base),
+    false => Path::new(base)
+      .join(location)
+      .to_string_lossy()
+      .into_owned(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CATALOG: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<catalog prefer="public" xmlns="urn:oasis:names:tc:entity:xmlns:xml:catalog">
+    <group id="Folder Repository, directory=, recursive=true, Auto-Update=true, version=2" prefer="public" xml:base="">
+        <uri id="Automatically generated entry, Timestamp=1620000000000" name="http://purl.obolibrary.org/obo/bfo.owl" uri="imports/bfo.owl"/>
+    </group>
+    <group xml:base="modules/">
+        <uri name="http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology" uri="AgentOntology.ttl"/>
+    </group>
+    <uri name="http://purl.obolibrary.org/obo/bfo.owl" uri="shadowed.owl"/>
+    <rewriteURI uriStartString="http://purl.obolibrary.org/obo/" rewritePrefix="obo/"/>
+    <rewriteURI uriStartString="http://purl.obolibrary.org/obo/ro/" rewritePrefix="http://example.org/mirror/ro/"/>
+    <nextCatalog catalog="shared/catalog-v001.xml"/>
+</catalog>
+"#;
+
+  #[test]
+  fn should_map_catalog_entries() -> std::io::Result<()> {
+    let mapper = CatalogIriMapper::from_str(CATALOG, "/repo/src/ontology")?;
+    assert_eq!(
+      mapper.document_iri("http://purl.obolibrary.org/obo/bfo.owl"),
+      Some(String::from("/repo/src/ontology/imports/bfo.owl"))
+    );
+    assert_eq!(
+      mapper
+        .document_iri("http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology"),
+      Some(String::from("/repo/src/ontology/modules/AgentOntology.ttl"))
+    );
+    assert_eq!(
+      mapper.document_iri("http://purl.obolibrary.org/obo/iao.owl"),
+      Some(String::from("/repo/src/ontology/obo/iao.owl"))
+    );
+    assert_eq!(
+      mapper.document_iri("http://purl.obolibrary.org/obo/ro/core.owl"),
+      Some(String::from("http://example.org/mirror/ro/core.owl"))
+    );
+    assert_eq!(mapper.document_iri("http://example.org/unknown"), None);
+    Ok(())
+  }
+
+  #[test]
+  fn should_follow_next_catalogs() -> std::io::Result<()> {
+    let root = std::env::temp_dir().join(format!("semantic_owl_catalog_{}", std::process::id()));
+    std::fs::create_dir_all(root.join("shared"))?;
+    let catalog = root.join(PROTEGE_CATALOG);
+    std::fs::write(&catalog, CATALOG)?;
+    std::fs::write(
+      root.join("shared").join(PROTEGE_CATALOG),
+      r#"<catalog><uri name="http://example.org/shared" uri="shared.ttl"/><nextCatalog catalog="../catalog-v001.xml"/></catalog>"#,
+    )?;
+    let mapper = CatalogIriMapper::from_file(catalog.to_str().unwrap());
+    std::fs::remove_dir_all(&root)?;
+    let mapper = mapper?;
+    assert_eq!(mapper.entries.len(), 6);
+    let shared = mapper.document_iri("http://example.org/shared").unwrap();
+    assert!(shared.ends_with("shared.ttl"));
+    assert!(Path::new(&shared).starts_with(root.join("shared")));
+    Ok(())
+  }
+
+  #[test]
+  fn should_reject_malformed_catalog() {
+    assert!(CatalogIriMapper::from_str("<catalog><uri name=", "").is_err());
+  }
+
+  #[test]
+  fn should_map_simple_entries() {
+    let mut mapper = SimpleIriMapper::new();
+    mapper.insert("http://purl.obolibrary.org/obo/bfo.owl", "imports/bfo.owl");
+    assert_eq!(
+      mapper.document_iri("http://purl.obolibrary.org/obo/bfo.owl"),
+      Some(String::from("imports/bfo.owl"))
+    );
+  }
+}
@@ -1,110 +1,912 @@
+use crate::declarations::owl::OwlSyntax;
+use crate::declarations::prefix_map::{has_scheme, PrefixMap};
+use crate::declarations::provenance::SourceSpan;
 use crate::declarations::turtle::*;
+use crate::loader::cancellation::{check_cancelled, CancellationToken};
+use crate::loader::compression::{open_document, DocumentSource};
+use crate::loader::filter::StatementFilter;
 use crate::loader::parsers::ttl_parser::{
-  get_base_iri_from_raw_statement, get_prefix_iri_from_raw_statement, parse_turtle,
+  get_base_iri_from_raw_statement, get_prefix_iri_from_raw_statement,
+  get_prefix_namespace_iri_from_raw_statement, parse_turtle,
 };
+use crate::loader::parsers::ttl_statement_parser::{
+  into_body_items, parse_directive, parse_statement, skip_ws, split_literal, split_quoted_triple,
+};
+use crate::loader::recovery::{find_terminator, ParseDiagnostic};
+use crate::loader::report::{LoadReport, PARSE_PHASE, REWRITE_PHASE, SKOLEMIZE_PHASE};
+
+use crate::transforms::iri_rewrite::{rewrite_iris, IriRewrite};
+use crate::transforms::skolem::skolemize;
 
 use indicatif::ProgressBar;
-use std::convert::TryInto;
-use std::fs::File;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Instant;
+
+/// default capacity of the read buffer. Matches the default of `BufReader`
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// documents smaller than this size (in bytes) are read into memory at once by default
+pub const DEFAULT_PRELOAD_THRESHOLD: u64 = 1024 * 1024;
+
+/// LoaderOptions tunes how a turtle document is read from disk.
+/// The defaults are suitable for most documents. Large documents benefit from
+/// a bigger `read_buffer_size` while small documents are loaded faster when
+/// read into memory at once. Options are set over the defaults
+/// example
+/// `LoaderOptions { read_buffer_size: 64 * 1024, ..LoaderOptions::default() }`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoaderOptions {
+  // capacity in bytes of the buffer used when streaming the document line by line
+  pub read_buffer_size: usize,
+
+  // maximum length in bytes of a single line. A document with a longer line is rejected
+  // with `std::io::ErrorKind::InvalidData`. `None` means no limit
+  pub max_line_length: Option<usize>,
+
+  // documents whose size in bytes is below or equal to the threshold are read into memory
+  // at once instead of being streamed. `None` means documents are always streamed
+  pub preload_threshold: Option<u64>,
+
+  // authority under which blank nodes are skolemized once the document is loaded.
+  // Example -> `http://example.org` mints `<http://example.org/.well-known/genid/{document}/genid1>`,
+  // see `transforms::skolem`.
+  // `None` keeps blank nodes as they are
+  pub skolem_authority: Option<String>,
+
+  // malformed statements are skipped up to their terminator and recorded as diagnostics instead of
+  // failing the load. See `load_turtle_document_with_diagnostics`
+  pub lenient: bool,
+
+  // RDF-star quoted triples, e.g `<< cco:JohnDoe cco:has_age 42 >> cco:source cco:Registry .`, are
+  // loaded as `TurtleTerm::QuotedTriple` terms. Statements holding them are malformed otherwise
+  pub rdf_star: bool,
+
+  // token checked before each line is read. Once it is cancelled the load stops with a
+  // `Cancelled` error, see `loader::cancellation::is_cancelled`. `None` means the load runs to
+  // its end
+  pub cancellation: Option<CancellationToken>,
+
+  // rules rewriting the IRIs of the document once it is loaded, in turn. e.g -> a mirror of
+  // `http://purl.obolibrary.org/obo/`. See `transforms::iri_rewrite`
+  pub iri_rewrites: Vec<IriRewrite>,
+
+  // filter dropping unwanted statements as they are parsed. e.g -> the deprecated terms.
+  // See `loader::filter`. `None` loads every statement
+  pub statement_filter: Option<StatementFilter>,
+}
+
+impl Default for LoaderOptions {
+  fn default() -> Self {
+    Self {
+      read_buffer_size: DEFAULT_READ_BUFFER_SIZE,
+      max_line_length: None,
+      preload_threshold: Some(DEFAULT_PRELOAD_THRESHOLD),
+      skolem_authority: None,
+      lenient: false,
+      rdf_star: false,
+      cancellation: None,
+      iri_rewrites: vec![],
+      statement_filter: None,
+    }
+  }
+}
 
 /// load_turtle_document is the main entry point for loading a turtle document
 /// It will return an error document is not a turtle document
 pub fn load_turtle_document(path: &str) -> std::io::Result<TurtleDocument> {
-  let file = File::open(path)?;
-  let reader = BufReader::new(file);
-  let mut document = TurtleDocument::new();
-
-  let pb = ProgressBar::new(reader.buffer().len().try_into().unwrap());
-
-  for line in reader.lines() {
-    pb.inc(1);
-    let ln = line?;
-    let result = parse_turtle(ln.as_str());
-    if let Ok(result) = result {
-      let (_, kind) = result;
-
-      match kind {
-        // don't anything. just move to the next statement
-        StatementKind::Comment | StatementKind::Whitespace | StatementKind::None => continue,
-
-        // base prefix has been encountered. This should be reached only once
-        StatementKind::BasePrefix => {
-          let header = TurtleHeaderItem::new(
-            true,
-            false,
-            None,
-            get_base_iri_from_raw_statement(&ln),
-            Some(ln),
-          );
-          document.headers.push_back(header);
-          continue;
-        }
+  load_turtle_document_with_options(path, &LoaderOptions::default())
+}
 
-        // a prefix statement has been encountered
-        StatementKind::NormPrefix => match get_prefix_iri_from_raw_statement(&ln) {
-          Some(r) => {
-            let (ns, is_empty) = r;
-            let header = TurtleHeaderItem::new(false, is_empty, Some(ns), None, Some(ln));
-            document.headers.push_back(header);
-            continue;
-          }
-          None => continue,
-        },
+/// number of leading bytes of a document inspected to detect its syntax
+pub const SNIFF_LENGTH: usize = 4 * 1024;
 
-        StatementKind::PartOfPredicateListWithSubject => {
-          println!("part of predicate list with subject found {:?}", ln);
-          continue;
-        }
+/// load_document loads an ontology document whose syntax is detected from its content and
+/// file extension. It will return an error when the syntax can not be detected or is not supported yet
+/// example
+/// `load_document("testdata/turtle/CurrencyUnitOntology.ttl")`
+pub fn load_document(path: &str) -> std::io::Result<TurtleDocument> {
+  load_document_with_options(path, &LoaderOptions::default())
+}
 
-        StatementKind::PartOfPredicateList => {
-          println!("part of predicate list found {:?}", ln);
-          continue;
-        }
+/// load_document_with_options loads an ontology document whose syntax is detected like
+/// `load_document`, using the provided `LoaderOptions` for turtle documents
+pub fn load_document_with_options(
+  path: &str,
+  options: &LoaderOptions,
+) -> std::io::Result<TurtleDocument> {
+  let mut source = open_document(path)?;
+  let mut head = Vec::with_capacity(SNIFF_LENGTH);
+  (&mut source.reader)
+    .take(SNIFF_LENGTH as u64)
+    .read_to_end(&mut head)?;
+  let syntax = detect_syntax(&source.name, &String::from_utf8_lossy(&head));
+  // the inspected bytes are put back in front of the rest of the document
+  source.reader = Box::new(std::io::Cursor::new(head).chain(source.reader));
+  match syntax {
+    Some(OwlSyntax::Turtle) => load_source(source, options).map(|(d, _)| d),
+    Some(syntax) => Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidInput,
+      format!("{:?} documents are not supported yet", syntax),
+    )),
+    None => Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidInput,
+      "the syntax of the provided file could not be detected",
+    )),
+  }
+}
 
-        StatementKind::PartOfObjectListWithPredicate => {
-          println!("part of object list with predicate found {:?}", ln);
-          continue;
-        }
+/// detect_syntax detects the syntax of a document from the start of its content. The file extension
+/// decides when the content is not conclusive, e.g for an empty document
+/// example
+/// `<?xml version="1.0"?>` -> `OwlSyntax::RdfXml`, `Prefix(:=<http://example.org/>)` -> `OwlSyntax::Functional`
+pub fn detect_syntax(path: &str, content: &str) -> Option<OwlSyntax> {
+  sniff_syntax(content).or_else(|| syntax_of_extension(path))
+}
 
-        StatementKind::PartOfObjectListAsLiteral => {
-          println!("part of object list as literal found {:?}", ln);
-          continue;
-        }
+// sniff_syntax detects the syntax from the first meaningful token of the content
+fn sniff_syntax(content: &str) -> Option<OwlSyntax> {
+  let content = skip_ws(content.trim_start_matches('\u{feff}'));
+  if content.starts_with('<') {
+    // skip the XML declaration, comments and DTD to find the root element
+    let root = content
+      .match_indices('<')
+      .map(|(idx, _)| &content[idx..])
+      .find(|x| !x.starts_with("<?") && !x.starts_with("<!"));
+    return match root {
+      Some(x) if x.starts_with("<Ontology") => Some(OwlSyntax::OwlXml),
+      Some(x) if x.starts_with("<rdf:RDF") || content.starts_with("<?xml") => {
+        Some(OwlSyntax::RdfXml)
+      }
+      // a turtle statement starting with an IRI, e.g N-Triples
+      Some(_) => Some(OwlSyntax::Turtle),
+      None => None,
+    };
+  }
+  let keyword: String = content
+    .chars()
+    .take_while(|c| !c.is_whitespace() && *c != '(')
+    .collect();
+  let rest = &content[keyword.len()..];
+  match keyword.as_str() {
+    "Prefix" | "Ontology" if rest.starts_with('(') => Some(OwlSyntax::Functional),
+    "Prefix:" | "Ontology:" => Some(OwlSyntax::Manchester),
+    _ if is_directive(content) => Some(OwlSyntax::Turtle),
+    _ if content.starts_with("_:") || parse_statement(content).is_ok() => Some(OwlSyntax::Turtle),
+    _ => None,
+  }
+}
 
-        StatementKind::PartOfObjectList => {
-          println!("part of object list found {:?}", ln);
-          continue;
-        }
+// syntax_of_extension maps a file extension to its usual syntax. `.owl` is used for every syntax
+// and is read as RDF/XML, the syntax Protégé saves by default
+pub(crate) fn syntax_of_extension(path: &str) -> Option<OwlSyntax> {
+  let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+  match extension.as_str() {
+    "ttl" | "nt" | "n3" => Some(OwlSyntax::Turtle),
+    "rdf" | "owl" | "xml" => Some(OwlSyntax::RdfXml),
+    "owx" => Some(OwlSyntax::OwlXml),
+    "ofn" => Some(OwlSyntax::Functional),
+    "omn" => Some(OwlSyntax::Manchester),
+    _ => None,
+  }
+}
+
+/// load_turtle_document_from_str loads a turtle document held in memory
+pub fn load_turtle_document_from_str(content: &str) -> std::io::Result<TurtleDocument> {
+  load_block(content, &mut 0)
+}
+
+/// load_turtle_document_from_str_lenient loads a turtle document held in memory, skipping the
+/// malformed statements. The statements skipped are returned along with the document
+pub fn load_turtle_document_from_str_lenient(
+  content: &str,
+) -> std::io::Result<(TurtleDocument, Vec<ParseDiagnostic>)> {
+  let mut sink = DocumentSink::default();
+  let mut buffer = StatementBuffer::new(true, false, None);
+  read_lines(content, &mut buffer, &mut sink)?;
+  Ok((sink.document, buffer.diagnostics))
+}
+
+/// load_turtle_document_from_str_with_report loads a turtle document held in memory using the
+/// provided `LoaderOptions`, along with the report of its loading
+pub fn load_turtle_document_from_str_with_report(
+  content: &str,
+  options: &LoaderOptions,
+) -> std::io::Result<(TurtleDocument, LoadReport)> {
+  let start = Instant::now();
+  let mut sink = DocumentSink::default();
+  let mut buffer = StatementBuffer::from_options(options);
+  read_lines(content, &mut buffer, &mut sink)?;
+  let mut report = buffer.report();
+  report.time(PARSE_PHASE, start);
+  let document = skolemize_document(sink.document, options, &mut report);
+  Ok((rewrite_document(document, options, &mut report), report))
+}
+
+// load_block loads turtle content held in memory. Blank nodes generated for nested blank nodes
+// and collections are numbered from `blank_node_count`, which is updated with the number used
+pub(crate) fn load_block(
+  content: &str,
+  blank_node_count: &mut usize,
+) -> std::io::Result<TurtleDocument> {
+  let mut sink = DocumentSink::default();
+  let mut buffer = StatementBuffer {
+    blank_node_count: *blank_node_count,
+    ..StatementBuffer::default()
+  };
+  read_lines(content, &mut buffer, &mut sink)?;
+  *blank_node_count = buffer.blank_node_count;
+  Ok(sink.document)
+}
+
+// read_str passes the lines of turtle content held in memory to a sink
+pub(crate) fn read_str<S: StatementSink>(content: &str, sink: &mut S) -> std::io::Result<()> {
+  read_lines(content, &mut StatementBuffer::default(), sink)
+}
+
+// read_lines passes the lines of turtle content held in memory to a sink through a buffer. Every
+// in-memory entry point loads its content this way, so they all parse alike
+fn read_lines<S: StatementSink>(
+  content: &str,
+  buffer: &mut StatementBuffer,
+  sink: &mut S,
+) -> std::io::Result<()> {
+  for ln in content.split_inclusive('\n') {
+    load_line(sink, buffer, ln)?;
+  }
+  buffer.finish(sink)
+}
+
+// statement_spans splits turtle content into the byte ranges of its directives and statements.
+// Whitespace and comments between statements are not part of any range
+pub(crate) fn statement_spans(content: &str) -> std::io::Result<Vec<Range<usize>>> {
+  match scan_statement_spans(content) {
+    (spans, None) => Ok(spans),
+    (_, Some(_)) => Err(not_a_turtle_document()),
+  }
+}
+
+// scan_statement_spans splits turtle content into the byte ranges of its directives and statements
+// up to the first statement which can not be parsed. The byte offset of that statement is returned
+// along with the ranges found before it
+pub(crate) fn scan_statement_spans(content: &str) -> (Vec<Range<usize>>, Option<usize>) {
+  let mut spans = vec![];
+  let mut pos = 0;
+  loop {
+    let rest = skip_ws(&content[pos..]);
+    if rest.is_empty() {
+      break;
+    }
+    let start = content.len() - rest.len();
+    match statement_span_at(content, start) {
+      Some(span) => {
+        pos = span.end;
+        spans.push(span);
+      }
+      None => return (spans, Some(start)),
+    }
+  }
+  (spans, None)
+}
+
+// statement_span_at returns the byte range of the directive or statement starting at `start`, or
+// `None` if the statement can not be parsed
+pub(crate) fn statement_span_at(content: &str, start: usize) -> Option<Range<usize>> {
+  let rest = &content[start..];
+  let end = match is_directive(rest) {
+    // directives are loaded line by line
+    true => rest.find('\n').map(|x| start + x).unwrap_or(content.len()),
+    false => match parse_statement(rest) {
+      Ok((r, _)) => content.len() - r.len(),
+      Err(_) => return None,
+    },
+  };
+  Some(start..end)
+}
+
+// is_directive determines whether the input starts with a `@prefix`, `@base`, `PREFIX` or `BASE` directive
+fn is_directive(i: &str) -> bool {
+  let keyword: String = i
+    .chars()
+    .take_while(|c| !c.is_whitespace())
+    .collect::<String>()
+    .to_ascii_lowercase();
+  matches!(keyword.as_str(), "@prefix" | "@base" | "prefix" | "base")
+}
+
+/// load_turtle_document_with_options loads a turtle document using the provided `LoaderOptions`.
+/// Gzip files and zip archives are decompressed on the fly when the `compression` feature is enabled
+pub fn load_turtle_document_with_options(
+  path: &str,
+  options: &LoaderOptions,
+) -> std::io::Result<TurtleDocument> {
+  load_source(open_document(path)?, options).map(|(d, _)| d)
+}
+
+/// load_turtle_document_with_diagnostics loads a turtle document using the provided `LoaderOptions`
+/// and returns the statements skipped along with it. Statements are only skipped when
+/// `options.lenient` is set, otherwise a malformed statement fails the load
+pub fn load_turtle_document_with_diagnostics(
+  path: &str,
+  options: &LoaderOptions,
+) -> std::io::Result<(TurtleDocument, Vec<ParseDiagnostic>)> {
+  load_source(open_document(path)?, options).map(|(d, report)| (d, report.skipped))
+}
+
+/// load_turtle_document_with_report loads a turtle document using the provided `LoaderOptions`
+/// along with the report of its loading: warnings, statements and directives skipped in lenient
+/// mode and the time spent in each phase
+pub fn load_turtle_document_with_report(
+  path: &str,
+  options: &LoaderOptions,
+) -> std::io::Result<(TurtleDocument, LoadReport)> {
+  load_source(open_document(path)?, options)
+}
 
-        StatementKind::PartOfCollectionList => {
-          println!("part of collection list found {:?}", ln);
-          continue;
+// load_source loads the turtle document of an opened source along with the report of its loading
+fn load_source(
+  source: DocumentSource,
+  options: &LoaderOptions,
+) -> std::io::Result<(TurtleDocument, LoadReport)> {
+  let start = Instant::now();
+  let mut sink = DocumentSink::default();
+  let mut report = read_source(source.reader, source.size, options, &mut sink)?;
+  report.time(PARSE_PHASE, start);
+  let document = skolemize_document(sink.document, options, &mut report);
+  let mut document = rewrite_document(document, options, &mut report);
+  let provenance = source.artifact.provenance();
+  let headers = document.headers.iter_mut().filter_map(|x| x.span.as_mut());
+  for span in headers.chain(document.body.iter_mut().filter_map(|x| x.span.as_mut())) {
+    span.file = Some(provenance.source.clone());
+  }
+  report.source = Some(provenance.source.clone());
+  document.provenance = Some(provenance);
+  Ok((document, report))
+}
+
+// skolemize_document skolemizes the blank nodes of a loaded document when the options ask for it
+fn skolemize_document(
+  document: TurtleDocument,
+  options: &LoaderOptions,
+  report: &mut LoadReport,
+) -> TurtleDocument {
+  match &options.skolem_authority {
+    Some(authority) => {
+      let start = Instant::now();
+      let document = skolemize(&document, authority);
+      report.time(SKOLEMIZE_PHASE, start);
+      document
+    }
+    None => document,
+  }
+}
+
+// rewrite_document rewrites the IRIs of a loaded document with the rules of the options
+fn rewrite_document(
+  document: TurtleDocument,
+  options: &LoaderOptions,
+  report: &mut LoadReport,
+) -> TurtleDocument {
+  if options.iri_rewrites.is_empty() {
+    return document;
+  }
+  let start = Instant::now();
+  let document = rewrite_iris(&document, &options.iri_rewrites);
+  report.time(REWRITE_PHASE, start);
+  document
+}
+
+// read_source streams the lines of an opened source into a sink. The report of the loading is
+// returned, without timings
+pub(crate) fn read_source<S: StatementSink>(
+  reader: Box<dyn Read>,
+  size: Option<u64>,
+  options: &LoaderOptions,
+  sink: &mut S,
+) -> std::io::Result<LoadReport> {
+  let mut buffer = StatementBuffer::from_options(options);
+
+  // the size of a gzip compressed document is only known once it is decompressed
+  let pb = match size {
+    Some(size) => ProgressBar::new(size),
+    None => ProgressBar::new_spinner(),
+  };
+
+  match (options.preload_threshold, size) {
+    // small document. Read it at once and walk through its lines
+    (Some(threshold), Some(size)) if size <= threshold => {
+      let mut content = String::with_capacity(size as usize);
+      let mut reader = reader;
+      reader.read_to_string(&mut content)?;
+      for ln in content.split_inclusive('\n') {
+        pb.inc(ln.len() as u64);
+        check_line_length(ln.trim_end_matches(['\n', '\r']), options)?;
+        load_line(sink, &mut buffer, ln)?;
+      }
+    }
+
+    // stream the document re-using a single line buffer
+    _ => {
+      let mut reader = BufReader::with_capacity(options.read_buffer_size, reader);
+      let mut line = String::new();
+      loop {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 {
+          break;
         }
+        pb.inc(n as u64);
+        check_line_length(line.trim_end_matches(['\n', '\r']), options)?;
+        load_line(sink, &mut buffer, &line)?;
+      }
+    }
+  }
+
+  buffer.finish(sink)?;
+  pb.finish_and_clear();
+  Ok(buffer.report())
+}
+
+// check_line_length rejects lines longer than the configured limit
+fn check_line_length(ln: &str, options: &LoaderOptions) -> std::io::Result<()> {
+  match options.max_line_length {
+    Some(max) if ln.len() > max => Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      format!("line exceeds the maximum length of {} bytes", max),
+    )),
+    _ => Ok(()),
+  }
+}
+
+// StatementSink receives the directives, statements and comments of the lines loaded,
+// e.g the document being built or the callbacks of a `TurtleHandler`
+pub(crate) trait StatementSink {
+  fn header(&mut self, header: TurtleHeaderItem);
+
+  fn body(&mut self, items: VecDeque<TurtleBodyItem>);
 
-        StatementKind::StatementWithTerminator => {
-          println!("statement with terminator found found {:?}", ln);
+  fn comment(&mut self, _comment: &str) {}
+}
+
+// DocumentSink loads the directives and statements of the lines into a document. A directive
+// applies to the statements which follow it, while the document resolves every statement with the
+// prefixes it declares last, so the terms of the statements loaded before a directive overriding
+// a prefix or the base are written as full IRIs first
+#[derive(Debug, Default)]
+struct DocumentSink {
+  document: TurtleDocument,
+
+  // prefixes and base declared so far
+  scope: PrefixMap,
+
+  // positions in the body of the statements using each prefix, `None` standing for the base
+  uses: HashMap<Option<String>, BTreeSet<usize>>,
+}
+
+impl StatementSink for DocumentSink {
+  fn header(&mut self, header: TurtleHeaderItem) {
+    let key = match (header.is_base, &header.prefix_namespace) {
+      (true, _) => Some(None),
+      (false, Some(ns)) => Some(Some(ns.clone())),
+      (false, None) => None,
+    };
+    if let Some(key) = key {
+      let mut declared = PrefixMap::new();
+      header.declare(&mut declared);
+      let (previous, current) = match &key {
+        Some(ns) => (self.scope.namespace(ns), declared.namespace(ns)),
+        None => (self.scope.base.as_deref(), declared.base.as_deref()),
+      };
+      if current.is_some() && previous != current {
+        let positions = self.uses.remove(&key).unwrap_or_default();
+        let kept: BTreeSet<usize> = positions
+          .into_iter()
+          .filter(|x| pin_scope(&mut self.document.body[*x], &self.scope, &key))
+          .collect();
+        if !kept.is_empty() {
+          self.uses.insert(key, kept);
         }
+        header.declare(&mut self.scope);
+      }
+    }
+    self.document.headers.push_back(header);
+  }
+
+  fn body(&mut self, mut items: VecDeque<TurtleBodyItem>) {
+    let offset = self.document.body.len();
+    for (idx, item) in items.iter().enumerate() {
+      let mut scopes = BTreeSet::new();
+      let predicates = item.predicate.iter();
+      let raw = item.subject.iter().chain(
+        predicates
+          .clone()
+          .filter_map(|p| p.raw_predicate_object.as_ref()),
+      );
+      let objects = predicates.flat_map(|p| p.object.iter().filter_map(|o| o.raw_object.as_ref()));
+      for term in raw.chain(objects) {
+        term_scopes(term, &mut scopes);
+      }
+      for key in scopes {
+        self.uses.entry(key).or_default().insert(offset + idx);
+      }
+    }
+    self.document.body.append(&mut items);
+  }
+}
+
+// pin_scope rewrites the terms of a statement which use the prefix `key`, or relative IRIs when
+// `key` is `None`, into their full form as `scope` resolves them, so they keep the meaning they
+// had when loaded. It returns whether terms `scope` does not resolve still use `key`
+fn pin_scope(item: &mut TurtleBodyItem, scope: &PrefixMap, key: &Option<String>) -> bool {
+  let mut kept = false;
+  let mut pin = |raw: &str| {
+    let mut scopes = BTreeSet::new();
+    term_scopes(raw, &mut scopes);
+    if !scopes.contains(key) {
+      return None;
+    }
+    let term = scope.resolve_term(raw);
+    kept |= term.is_none();
+    term.map(|x| x.to_string())
+  };
+  if let Some(raw) = item.subject.as_deref().and_then(&mut pin) {
+    item.subject = Some(raw);
+  }
+  for p in item.predicate.iter_mut() {
+    if let Some(raw) = p.raw_predicate_object.as_deref().and_then(&mut pin) {
+      let objects = std::mem::take(&mut p.object);
+      *p = TurtlePredicate::from_raw(&raw);
+      p.object = objects;
+    }
+    for o in p.object.iter_mut() {
+      if let Some(raw) = o.raw_object.as_deref().and_then(&mut pin) {
+        *o = TurtleObject::from_raw(&raw);
+      }
+    }
+  }
+  kept
+}
+
+// term_scopes collects the prefixes a term uses, and `None` when it holds a relative IRI
+fn term_scopes(raw: &str, scopes: &mut BTreeSet<Option<String>>) {
+  let raw = raw.trim();
+  if raw.starts_with("<<") {
+    if let Some((s, p, o)) = split_quoted_triple(raw) {
+      for term in [s, p, o] {
+        term_scopes(&term, scopes);
+      }
+    }
+  } else if let Some(iri) = raw.strip_prefix('<') {
+    if !has_scheme(iri.trim_end_matches('>')) {
+      scopes.insert(None);
+    }
+  } else if let Some((_, datatype, _)) = split_literal(raw) {
+    if let Some(datatype) = datatype {
+      term_scopes(&datatype, scopes);
+    }
+  } else if let (false, Some((prefix, _))) = (raw.starts_with("_:"), raw.split_once(':')) {
+    scopes.insert(Some(String::from(prefix)));
+  }
+}
+
+// StatementBuffer collects the lines of a statement until the statement is complete
+#[derive(Debug, Default)]
+struct StatementBuffer {
+  // lines of the statement(s) not yet parsed
+  raw: String,
+
+  // number of blank nodes generated for nested blank nodes and collections
+  blank_node_count: usize,
+
+  // number of lines loaded so far, and byte offset of the last line loaded in the document
+  line: usize,
+  offset: usize,
+
+  // number of bytes loaded so far, line terminators included
+  size: usize,
+
+  // lines held in `raw` as (offset in `raw`, offset in the document, line of the document)
+  lines: VecDeque<(usize, usize, usize)>,
+
+  // statements holding RDF-star quoted triples are malformed unless set
+  rdf_star: bool,
+
+  // token checked before each line
+  cancellation: Option<CancellationToken>,
+
+  // in lenient mode malformed statements are skipped and recorded in `diagnostics`, and unknown
+  // directives in `unknown_directives`
+  lenient: bool,
+  diagnostics: Vec<ParseDiagnostic>,
+  unknown_directives: Vec<ParseDiagnostic>,
+
+  // namespaces of the prefixes declared so far, and whether a base was declared, to warn about
+  // redeclarations
+  prefixes: HashMap<String, Option<String>>,
+  has_base: bool,
+  warnings: Vec<ParseDiagnostic>,
+
+  // filter applied to the statements parsed, with the directives seen so far to resolve them
+  filter: Option<StatementFilter>,
+  scope: PrefixMap,
+}
+
+impl StatementBuffer {
+  fn new(
+    lenient: bool,
+    rdf_star: bool,
+    cancellation: Option<CancellationToken>,
+  ) -> StatementBuffer {
+    Self {
+      lenient,
+      rdf_star,
+      cancellation,
+      ..Self::default()
+    }
+  }
+
+  fn from_options(options: &LoaderOptions) -> StatementBuffer {
+    Self {
+      filter: options.statement_filter.clone(),
+      ..Self::new(
+        options.lenient,
+        options.rdf_star,
+        options.cancellation.clone(),
+      )
+    }
+  }
+
+  fn is_empty(&self) -> bool {
+    skip_ws(&self.raw).is_empty()
+  }
+
+  fn push_line(&mut self, ln: &str) {
+    self
+      .lines
+      .push_back((self.raw.len(), self.offset, self.line));
+    self.raw.push_str(ln);
+    self.raw.push('\n');
+  }
 
-        StatementKind::Terminator => {
-          println!("statement terminator found {:?}", ln);
-          continue;
+  // flush parses the complete statements in the buffer into body items of the sink.
+  // Input of an incomplete statement is kept in the buffer. In lenient mode a statement which
+  // can not be parsed although terminated is skipped up to its terminator
+  fn flush<S: StatementSink>(&mut self, sink: &mut S) {
+    let mut consumed = 0;
+    loop {
+      let rest = skip_ws(&self.raw[consumed..]);
+      if rest.is_empty() {
+        consumed = self.raw.len();
+        break;
+      }
+      let start = self.raw.len() - rest.len();
+      // directives may stand anywhere between statements
+      if let Ok((r, _)) = parse_directive(rest) {
+        consumed = self.raw.len() - r.len();
+        let end = start + self.raw[start..consumed].trim_end().len();
+        if let Some(header) = self.directive(start, end) {
+          sink.header(header);
         }
+        continue;
+      }
+      match parse_statement(rest) {
+        Ok((r, statement)) if self.rdf_star || !statement.has_quoted_triple() => {
+          consumed = self.raw.len() - r.len();
+          let span = self.span(start, start + self.raw[start..consumed].trim_end().len());
+          let mut items = into_body_items(&statement, &mut self.blank_node_count);
+          for item in items.iter_mut() {
+            item.span = Some(span.clone());
+          }
+          if let Some(filter) = &self.filter {
+            filter.apply(&self.scope, &mut items);
+          }
+          if !items.is_empty() {
+            sink.body(items);
+          }
+        }
+        _ => match find_terminator(rest).filter(|_| self.lenient) {
+          Some(end) => {
+            self.skip(start, start + end + 1, "malformed statement");
+            consumed = start + end + 1;
+          }
+          None => {
+            consumed = start;
+            break;
+          }
+        },
+      }
+    }
+    self.raw.drain(..consumed);
 
-        // not parser has passed, meaning the provider document is not a valid turtle document
-        StatementKind::NotATurtle => {
-          return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidInput,
-            "the provided file is not a turtle document",
-          ));
+    // lines consumed are forgotten. A line consumed in part now starts at the start of `raw`
+    while self.lines.len() > 1 && self.lines[1].0 <= consumed {
+      self.lines.pop_front();
+    }
+    if let Some(first) = self.lines.front_mut() {
+      first.1 += consumed - first.0;
+      first.0 = consumed;
+    }
+    for (start, _, _) in self.lines.iter_mut() {
+      *start -= consumed;
+    }
+    if self.raw.is_empty() {
+      self.lines.clear();
+    }
+  }
+
+  // directive returns the header of the directive held in `raw[start..end]`, warning about
+  // redeclarations
+  fn directive(&mut self, start: usize, end: usize) -> Option<TurtleHeaderItem> {
+    let raw = String::from(&self.raw[start..end]);
+    let (line, _) = self.locate(start);
+    let mut header = match get_prefix_iri_from_raw_statement(&raw) {
+      Some((ns, is_empty)) => {
+        let iri = get_prefix_namespace_iri_from_raw_statement(&raw);
+        match self.prefixes.insert(ns.clone(), iri.clone()) {
+          Some(previous) if previous != iri => {
+            self.warn(line, &format!("prefix `{}:` redeclared", ns), &raw)
+          }
+          _ => {}
         }
+        TurtleHeaderItem::new(false, is_empty, Some(ns), iri, Some(raw))
       }
+      None => {
+        let iri = get_base_iri_from_raw_statement(&raw)?;
+        if self.has_base {
+          self.warn(line, "base redeclared", &raw);
+        }
+        self.has_base = true;
+        TurtleHeaderItem::new(true, false, None, Some(iri), Some(raw))
+      }
+    };
+    header.span = Some(self.span(start, end));
+    self.declare(&header);
+    Some(header)
+  }
+
+  // declare brings a directive in scope of the statements which follow, when they are filtered
+  fn declare(&mut self, header: &TurtleHeaderItem) {
+    if self.filter.is_some() {
+      header.declare(&mut self.scope);
     }
   }
 
-  pb.finish_and_clear();
-  Ok(document)
+  // locate returns the line and the offset in the document of the byte at `pos` in `raw`
+  fn locate(&self, pos: usize) -> (usize, usize) {
+    match self.lines.iter().rev().find(|(start, _, _)| *start <= pos) {
+      Some((start, offset, line)) => (*line, offset + pos - start),
+      None => (self.line, self.offset),
+    }
+  }
+
+  // span returns the location in the document of the statement held in `raw[start..end]`
+  fn span(&self, start: usize, end: usize) -> SourceSpan {
+    let (first_line, first_byte) = self.locate(start);
+    let (last_line, last_byte) = self.locate(end.saturating_sub(1).max(start));
+    SourceSpan::new(None, first_line..last_line + 1, first_byte..last_byte + 1)
+  }
+
+  // skip records the statement held in `raw[start..end]` as skipped
+  fn skip(&mut self, start: usize, end: usize, message: &str) {
+    let (line, _) = self.locate(start);
+    let statement = self.raw[start..end].trim_end();
+    self
+      .diagnostics
+      .push(ParseDiagnostic::new(line, message, statement));
+  }
+
+  // warn records an oddity of a statement starting at a line
+  fn warn(&mut self, line: usize, message: &str, statement: &str) {
+    self
+      .warnings
+      .push(ParseDiagnostic::new(line, message, statement.trim()));
+  }
+
+  // report returns the report of the lines loaded so far, without timings
+  fn report(&mut self) -> LoadReport {
+    LoadReport::new(
+      None,
+      self.line,
+      std::mem::take(&mut self.warnings),
+      std::mem::take(&mut self.diagnostics),
+      std::mem::take(&mut self.unknown_directives),
+      vec![],
+    )
+  }
+
+  // finish flushes the buffer at the end of a document. Any input left is not valid turtle.
+  // In lenient mode it is skipped as an unterminated statement
+  fn finish<S: StatementSink>(&mut self, sink: &mut S) -> std::io::Result<()> {
+    self.flush(sink);
+    match (self.is_empty(), self.lenient) {
+      (true, _) => Ok(()),
+      (false, true) => {
+        let start = self.raw.len() - skip_ws(&self.raw).len();
+        self.skip(start, self.raw.len(), "unterminated statement");
+        self.raw.clear();
+        Ok(())
+      }
+      (false, false) => Err(not_a_turtle_document()),
+    }
+  }
+}
+
+pub(crate) fn not_a_turtle_document() -> std::io::Error {
+  std::io::Error::new(
+    std::io::ErrorKind::InvalidInput,
+    "the provided file is not a turtle document",
+  )
+}
+
+// load_line classifies a single line, line terminator included, and passes its content to the sink.
+// Lines which are part of a statement are collected until the statement is terminated
+fn load_line<S: StatementSink>(
+  sink: &mut S,
+  buffer: &mut StatementBuffer,
+  ln: &str,
+) -> std::io::Result<()> {
+  check_cancelled(buffer.cancellation.as_ref())?;
+  buffer.line += 1;
+  buffer.offset = buffer.size;
+  buffer.size += ln.len();
+  let ln = ln.trim_end_matches(['\n', '\r']);
+  let kind = match parse_turtle(ln) {
+    Ok((_, kind)) => kind,
+    Err(_) => StatementKind::NotATurtle,
+  };
+
+  // the line continues a statement which is not yet terminated
+  if !buffer.is_empty() {
+    buffer.push_line(ln);
+    if is_terminated(&kind, ln) {
+      buffer.flush(sink);
+    }
+    return Ok(());
+  }
+
+  match kind {
+    // directives are parsed with the statements, so one may share its line with statements or
+    // comments, or span several lines
+    _ if is_directive(ln.trim_start()) => {
+      buffer.push_line(ln);
+      buffer.flush(sink);
+    }
+
+    // comments are passed on, the comment marker removed
+    StatementKind::Comment => sink.comment(ln.trim().trim_start_matches('#')),
+
+    // don't anything. just move to the next statement
+    StatementKind::Whitespace | StatementKind::None => {}
+
+    // the line starts a statement, or holds one or more complete statements.
+    // A line which can not be classified may still be part of a valid statement,
+    // e.g the last predicate of a blank node property list. The statement parser decides
+    // directives other than `@prefix` and `@base` are skipped in lenient mode
+    _ if buffer.lenient && ln.trim_start().starts_with('@') => {
+      let message = "unknown directive";
+      let diagnostic = ParseDiagnostic::new(buffer.line, message, ln.trim());
+      buffer.unknown_directives.push(diagnostic);
+    }
+
+    kind => {
+      buffer.push_line(ln);
+      if is_terminated(&kind, ln) {
+        buffer.flush(sink);
+      }
+    }
+  }
+  Ok(())
+}
+
+// is_terminated determines whether the line may end a statement
+fn is_terminated(kind: &StatementKind, ln: &str) -> bool {
+  matches!(
+    kind,
+    StatementKind::StatementWithTerminator | StatementKind::Terminator
+  ) || ln.trim_end().ends_with('.')
 }
 
 #[cfg(test)]
@@ -129,4 +931,489 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn should_load_document_with_options() -> std::io::Result<()> {
+    let wd = current_dir()?;
+    let root = wd.parent().unwrap();
+    let root = root.join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let path = root.to_str().unwrap();
+
+    // streamed with a small buffer and preloaded documents are identical
+    let streamed = load_turtle_document_with_options(
+      path,
+      &LoaderOptions {
+        read_buffer_size: 64,
+        preload_threshold: None,
+        ..LoaderOptions::default()
+      },
+    )?;
+    let preloaded = load_turtle_document_with_options(
+      path,
+      &LoaderOptions {
+        read_buffer_size: 64,
+        preload_threshold: Some(u64::MAX),
+        ..LoaderOptions::default()
+      },
+    )?;
+    assert_eq!(streamed, preloaded);
+    assert_ne!(streamed.headers.len(), 0);
+
+    // a line length limit shorter than the longest line rejects the document
+    match load_turtle_document_with_options(
+      path,
+      &LoaderOptions {
+        read_buffer_size: 64,
+        max_line_length: Some(16),
+        preload_threshold: None,
+        ..LoaderOptions::default()
+      },
+    ) {
+      Ok(_) => panic!("did not expect"),
+      Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidData),
+    }
+
+    // skolemized documents hold no blank nodes
+    let options = LoaderOptions {
+      read_buffer_size: 64,
+      preload_threshold: None,
+      skolem_authority: Some(String::from("http://example.org")),
+      ..LoaderOptions::default()
+    };
+    let skolemized = load_turtle_document_with_options(path, &options)?;
+    assert!(skolemized
+      .triples()
+      .iter()
+      .all(|t| !t.subject.is_blank_node()));
+    Ok(())
+  }
+
+  #[test]
+  fn should_skip_malformed_statements_in_lenient_mode() -> std::io::Result<()> {
+    let content = r#"@prefix : <http://example.org/> .
+:Agent a :Class .
+:Person a :Class ; :label "a person. or not" :broken .
+:Organization a :Class .
+:Group :label "group" ,
+  ] .
+:Place a :Class .
+:Event a
+"#;
+    assert!(load_turtle_document_from_str(content).is_err());
+    let (document, diagnostics) = load_turtle_document_from_str_lenient(content)?;
+    let subjects: Vec<String> = document
+      .triples()
+      .iter()
+      .map(|t| t.subject.to_string())
+      .collect();
+    assert_eq!(
+      subjects,
+      vec![
+        "<http://example.org/Agent>",
+        "<http://example.org/Organization>",
+        "<http://example.org/Place>"
+      ]
+    );
+    assert_eq!(
+      diagnostics.iter().map(|x| x.line).collect::<Vec<_>>(),
+      vec![3, 5, 8]
+    );
+    assert_eq!(diagnostics[1].statement, ":Group :label \"group\" ,\n  ] .");
+    assert_eq!(diagnostics[2].message, "unterminated statement");
+
+    let path =
+      std::env::temp_dir().join(format!("semantic_owl_lenient_{}.ttl", std::process::id()));
+    std::fs::write(&path, content)?;
+    let path = path.to_str().unwrap();
+    let strict = load_turtle_document_with_diagnostics(path, &LoaderOptions::default());
+    let options = LoaderOptions {
+      lenient: true,
+      ..LoaderOptions::default()
+    };
+    let lenient = load_turtle_document_with_diagnostics(path, &options);
+    std::fs::remove_file(path)?;
+    assert!(strict.is_err());
+    let (loaded, skipped) = lenient?;
+    assert_eq!(loaded.triples(), document.triples());
+    assert_eq!(skipped, diagnostics);
+    Ok(())
+  }
+
+  #[test]
+  fn should_report_loading() -> std::io::Result<()> {
+    let content = r#"@prefix : <http://example.org/> .
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@version 1.2 .
+:Agent a :Class .
+@prefix : <http://example.org/agents#> .
+:Person a :Class ; :broken .
+[] a :Class .
+"#;
+    let options = LoaderOptions {
+      lenient: true,
+      skolem_authority: Some(String::from("http://example.org")),
+      ..LoaderOptions::default()
+    };
+    let (document, report) = load_turtle_document_from_str_with_report(content, &options)?;
+    assert_eq!(document.triples().len(), 2);
+    assert!(!report.is_clean());
+    assert_eq!(report.lines, 7);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(
+      (report.warnings[0].line, report.warnings[0].message.as_str()),
+      (5, "prefix `:` redeclared")
+    );
+    assert_eq!(report.unknown_directives[0].statement, "@version 1.2 .");
+    assert_eq!(report.skipped.len(), 1);
+    let phases: Vec<&str> = report.timings.iter().map(|x| x.phase.as_str()).collect();
+    assert_eq!(phases, vec![PARSE_PHASE, SKOLEMIZE_PHASE]);
+
+    // unknown directives fail the load unless it is lenient
+    assert!(load_turtle_document_from_str_with_report(content, &LoaderOptions::default()).is_err());
+
+    let wd = current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let (_, report) =
+      load_turtle_document_with_report(path.to_str().unwrap(), &LoaderOptions::default())?;
+    assert!(report.is_clean());
+    assert!(report.source.unwrap().ends_with("CurrencyUnitOntology.ttl"));
+    assert_eq!(report.timings.len(), 1);
+    Ok(())
+  }
+
+  #[test]
+  fn should_cancel_loading() -> std::io::Result<()> {
+    let content = "@prefix : <http://example.org/> .\n:Agent a :Class .\n";
+    let token = CancellationToken::new();
+    let options = LoaderOptions {
+      cancellation: Some(token.clone()),
+      ..LoaderOptions::default()
+    };
+    let (document, _) = load_turtle_document_from_str_with_report(content, &options)?;
+    assert_eq!(document.triples().len(), 1);
+
+    token.cancel();
+    let error = load_turtle_document_from_str_with_report(content, &options).unwrap_err();
+    assert!(crate::loader::cancellation::is_cancelled(&error));
+    let wd = current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let error = load_turtle_document_with_options(path.to_str().unwrap(), &options).unwrap_err();
+    assert!(crate::loader::cancellation::is_cancelled(&error));
+    // cancellation is not an interruption callers would retry on, nor any other error
+    assert_ne!(error.kind(), std::io::ErrorKind::Interrupted);
+    assert!(!crate::loader::cancellation::is_cancelled(
+      &std::io::Error::other("the operation was cancelled")
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn should_load_quoted_triples_on_request() -> std::io::Result<()> {
+    let content = r#"@prefix : <http://example.org/> .
+<< :JohnDoe :has_age 42 >> :source << :Registry a :Agency >> .
+:Agent a :Class .
+"#;
+    // quoted triples are malformed statements unless RDF-star is enabled
+    assert!(load_turtle_document_from_str(content).is_err());
+    let options = LoaderOptions {
+      rdf_star: true,
+      ..LoaderOptions::default()
+    };
+    let (document, report) = load_turtle_document_from_str_with_report(content, &options)?;
+    assert!(report.is_clean());
+    let triples = document.triples();
+    assert_eq!(triples.len(), 2);
+    let quoted = triples[0].subject.as_quoted_triple().unwrap();
+    assert_eq!(
+      quoted.subject,
+      TurtleTerm::Iri(String::from("http://example.org/JohnDoe"))
+    );
+    assert!(quoted.object.is_literal());
+    assert_eq!(
+      triples[0].object.to_string(),
+      "<< <http://example.org/Registry> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/Agency> >>"
+    );
+
+    // the written document loads back into the same triples
+    let written = crate::writer::format::format_turtle(&document, &Default::default());
+    assert!(
+      written.contains("<< :Registry <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> :Agency >>")
+    );
+    let (reloaded, _) = load_turtle_document_from_str_with_report(&written, &options)?;
+    assert_eq!(reloaded.canonical_hash(), document.canonical_hash());
+    Ok(())
+  }
+
+  #[test]
+  fn should_detect_syntax() {
+    assert_eq!(
+      detect_syntax(
+        "a.owl",
+        "<?xml version=\"1.0\"?>\n<!DOCTYPE rdf:RDF [ ]>\n<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"/>"
+      ),
+      Some(OwlSyntax::RdfXml)
+    );
+    assert_eq!(
+      detect_syntax(
+        "a.owl",
+        "<?xml version=\"1.0\"?>\n<Ontology xmlns=\"http://www.w3.org/2002/07/owl#\"/>"
+      ),
+      Some(OwlSyntax::OwlXml)
+    );
+    assert_eq!(
+      detect_syntax("a.owl", "Prefix(:=<http://example.org/>)\nOntology()"),
+      Some(OwlSyntax::Functional)
+    );
+    assert_eq!(
+      detect_syntax(
+        "a.owl",
+        "Prefix: : <http://example.org/>\nOntology: <http://example.org/>"
+      ),
+      Some(OwlSyntax::Manchester)
+    );
+    assert_eq!(
+      detect_syntax("a.owl", "# comment\n@prefix : <http://example.org/> ."),
+      Some(OwlSyntax::Turtle)
+    );
+    assert_eq!(
+      detect_syntax(
+        "a.txt",
+        "<http://example.org/a> <http://example.org/b> \"c\" ."
+      ),
+      Some(OwlSyntax::Turtle)
+    );
+    assert_eq!(detect_syntax("a.ofn", ""), Some(OwlSyntax::Functional));
+    assert_eq!(detect_syntax("a.txt", "lorem ipsum"), None);
+  }
+
+  #[test]
+  fn should_load_document_of_detected_syntax() -> std::io::Result<()> {
+    let wd = current_dir()?;
+    let root = wd.parent().unwrap();
+    let path = root.join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let path = path.to_str().unwrap();
+    assert_eq!(load_document(path)?, load_turtle_document(path)?);
+
+    let path = std::env::temp_dir().join(format!("semantic_owl_detect_{}.owx", std::process::id()));
+    std::fs::write(&path, "<Ontology/>")?;
+    let result = load_document(path.to_str().unwrap());
+    std::fs::remove_file(&path)?;
+    match result {
+      Ok(_) => panic!("did not expect"),
+      Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput),
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn should_record_source_spans() -> std::io::Result<()> {
+    let content = "@prefix : <http://example.org/> .\r\n\r\n:Agent a :Class . :Person a :Class ;\r\n  :label \"person\" ;\r\n  :knows [ :name \"x\" ] .\r\n:Place a :Class .";
+    let spans = |document: &TurtleDocument| -> Vec<(String, SourceSpan)> {
+      document
+        .triples()
+        .into_iter()
+        .map(|t| (t.subject.to_string(), t.source_span().cloned().unwrap()))
+        .collect()
+    };
+    let document = load_turtle_document_from_str(content)?;
+    let found = spans(&document);
+    assert_eq!(found.len(), 6);
+    let (subject, agent) = &found[0];
+    assert_eq!(subject, "<http://example.org/Agent>");
+    assert_eq!((agent.file.as_deref(), agent.lines.clone()), (None, 3..4));
+    assert_eq!(&content[agent.bytes.clone()], ":Agent a :Class .");
+    let person = &found[1].1;
+    assert_eq!(person.lines, 3..6);
+    assert!(content[person.bytes.clone()].starts_with(":Person a :Class ;"));
+    assert!(content[person.bytes.clone()].ends_with("[ :name \"x\" ] ."));
+    // the triples of a nested blank node share the span of their statement
+    assert!(found
+      .iter()
+      .filter(|(s, _)| s.starts_with("_:"))
+      .all(|(_, x)| x == person));
+    let place = &found[5].1;
+    assert_eq!(place.lines, 6..7);
+    assert_eq!(&content[place.bytes.clone()], ":Place a :Class .");
+
+    let path = std::env::temp_dir().join(format!("semantic_owl_spans_{}.ttl", std::process::id()));
+    std::fs::write(&path, content)?;
+    let path = path.to_str().unwrap();
+    let preloaded = load_turtle_document_with_options(path, &LoaderOptions::default());
+    let streamed = LoaderOptions {
+      read_buffer_size: 16,
+      preload_threshold: None,
+      ..LoaderOptions::default()
+    };
+    let streamed = load_turtle_document_with_options(path, &streamed);
+    std::fs::remove_file(path)?;
+    for loaded in [preloaded?, streamed?] {
+      let loaded = spans(&loaded);
+      assert_eq!(loaded.len(), found.len());
+      for ((_, x), (_, y)) in loaded.iter().zip(found.iter()) {
+        assert_eq!(x.file.as_deref(), Some(path));
+        assert_eq!((&x.lines, &x.bytes), (&y.lines, &y.bytes));
+      }
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn should_record_provenance() -> std::io::Result<()> {
+    let wd = current_dir()?;
+    let root = wd.parent().unwrap();
+    let path = root.join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let path = path.to_str().unwrap();
+    let bytes = std::fs::read(path)?;
+    for options in [
+      LoaderOptions::default(),
+      LoaderOptions {
+        read_buffer_size: 64,
+        preload_threshold: None,
+        skolem_authority: Some(String::from("http://example.org")),
+        ..LoaderOptions::default()
+      },
+    ] {
+      let provenance = load_turtle_document_with_options(path, &options)?
+        .provenance
+        .unwrap();
+      assert_eq!(provenance.source, path);
+      assert_eq!(provenance.size, bytes.len() as u64);
+      assert_eq!(
+        provenance.sha256,
+        crate::transforms::canonical::sha256_hex(std::str::from_utf8(&bytes).unwrap())
+      );
+      assert_eq!(
+        provenance.parser_version,
+        crate::declarations::provenance::PARSER_VERSION
+      );
+      assert_ne!(provenance.loaded_at, 0);
+    }
+    assert_eq!(
+      load_document(path)?.provenance.unwrap().size,
+      bytes.len() as u64
+    );
+    assert_eq!(load_turtle_document_from_str("")?.provenance, None);
+    Ok(())
+  }
+
+  #[test]
+  fn should_scope_directives_to_the_statements_which_follow() -> std::io::Result<()> {
+    // two documents concatenated, declaring the same prefix and base for other namespaces
+    let content = r#"@base <http://example.org/agents> .
+@prefix ex: <http://example.org/agents#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+ex:Person rdfs:subClassOf <#Agent> ; ex:note "a"^^ex:text .
+@base <http://example.org/roles> .
+@prefix ex: <http://example.org/roles#> .
+ex:Student rdfs:subClassOf <#Role> .
+"#;
+    let document = load_turtle_document_from_str(content)?;
+    let triples: Vec<String> = document.triples().iter().map(|t| t.to_string()).collect();
+    assert_eq!(
+      triples,
+      vec![
+        "<http://example.org/agents#Person> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/agents#Agent> .",
+        "<http://example.org/agents#Person> <http://example.org/agents#note> \"a\"^^<http://example.org/agents#text> .",
+        "<http://example.org/roles#Student> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/roles#Role> .",
+      ]
+    );
+    // statements which do not depend on the directive overridden are kept as written
+    assert_eq!(
+      document.body[0].predicate[0]
+        .raw_predicate_object
+        .as_deref(),
+      Some("rdfs:subClassOf")
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_scope_interleaved_directives() -> std::io::Result<()> {
+    let content = r#"@prefix ex: <http://example.org/a#> .
+@prefix ey: <http://example.org/b#> .
+ex:s1 ey:p "1"^^ex:n .
+ey:s2 ey:p ex:o2 .
+@prefix ey: <http://example.org/c#> .
+ex:s3 ey:p ex:o3 .
+@prefix ex: <http://example.org/d#> .
+@prefix ex: <http://example.org/d#> .
+ex:s4 ey:p ex:o4 .
+"#;
+    let document = load_turtle_document_from_str(content)?;
+    let triples: Vec<String> = document.triples().iter().map(|t| t.to_string()).collect();
+    assert_eq!(
+      triples,
+      vec![
+        "<http://example.org/a#s1> <http://example.org/b#p> \"1\"^^<http://example.org/a#n> .",
+        "<http://example.org/b#s2> <http://example.org/b#p> <http://example.org/a#o2> .",
+        "<http://example.org/a#s3> <http://example.org/c#p> <http://example.org/a#o3> .",
+        "<http://example.org/d#s4> <http://example.org/c#p> <http://example.org/d#o4> .",
+      ]
+    );
+    // the statements loaded after the last declaration of a prefix keep its prefixed names
+    assert_eq!(document.body[3].subject.as_deref(), Some("ex:s4"));
+    assert_eq!(
+      document.body[2].predicate[0]
+        .raw_predicate_object
+        .as_deref(),
+      Some("ey:p")
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_load_directives_wherever_they_stand() -> std::io::Result<()> {
+    let content = r#"@prefix ex: <http://example.org/> . # agents
+  @prefix   owl:<http://www.w3.org/2002/07/owl#>.
+PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+ex:Agent a owl:Class . @prefix ey: <http://example.org/y/> . @prefix ez:
+  <http://example.org/z/> .
+base <http://example.org/base/>
+ey:Role rdfs:subClassOf ez:Thing, <Local> .
+"#;
+    let (document, report) =
+      load_turtle_document_from_str_with_report(content, &LoaderOptions::default())?;
+    assert!(report.warnings.is_empty());
+    // headers are kept in the order they are declared
+    let prefixes: Vec<Option<&str>> = document
+      .headers
+      .iter()
+      .map(|x| x.prefix_namespace.as_deref())
+      .collect();
+    assert_eq!(
+      prefixes,
+      vec![
+        Some("ex"),
+        Some("owl"),
+        Some("rdfs"),
+        Some("ey"),
+        Some("ez"),
+        None
+      ]
+    );
+    assert_eq!(
+      document.headers[1].raw_header.as_deref(),
+      Some("@prefix   owl:<http://www.w3.org/2002/07/owl#>.")
+    );
+    assert_eq!(
+      document.headers[4].span.as_ref().map(|x| x.lines.clone()),
+      Some(4..6)
+    );
+    let triples: Vec<String> = document.triples().iter().map(|t| t.to_string()).collect();
+    assert_eq!(
+      triples,
+      vec![
+        "<http://example.org/Agent> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> .",
+        "<http://example.org/y/Role> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/z/Thing> .",
+        "<http://example.org/y/Role> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/base/Local> .",
+      ]
+    );
+    Ok(())
+  }
 }
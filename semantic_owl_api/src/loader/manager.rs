@@ -0,0 +1,632 @@
+//! Manager module loads ontologies along with their imports
+use crate::declarations::prefix_map::has_scheme;
+use crate::declarations::provenance::SourceSpan;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::loader::cache::{file_sha256, OntologyCache};
+use crate::loader::dataset::GraphIndex;
+use crate::loader::iri_mapper::IriMapper;
+use crate::loader::load::load_document;
+use crate::loader::version_policy::{LoadReport, OntologyVersion, VersionConflict, VersionPolicy};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// TermPosition is the place of a term in a triple
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TermPosition {
+  Subject,
+  Predicate,
+  Object,
+
+  // datatype of a literal object
+  Datatype,
+}
+
+/// EntityReference is a triple of a loaded ontology mentioning an entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityReference {
+  pub triple: TurtleTriple,
+
+  // places of the entity in the triple, or in the triple it quotes
+  pub positions: Vec<TermPosition>,
+}
+
+impl EntityReference {
+  pub fn new(triple: TurtleTriple, positions: Vec<TermPosition>) -> EntityReference {
+    Self { triple, positions }
+  }
+
+  /// span returns the location of the statement holding the reference, if the document was
+  /// loaded from a file
+  pub fn span(&self) -> Option<&SourceSpan> {
+    self.triple.span.as_ref()
+  }
+}
+
+/// OntologyManager holds the documents of ontologies and of their import closures
+#[derive(Default)]
+pub struct OntologyManager {
+  // loaded documents keyed by ontology IRI. Imported documents are keyed by the IRI they are
+  // imported with and anonymous ontologies by the location they were loaded from. A document
+  // changed in place must be followed by `reindex_graph` for the dataset queries to see the change
+  pub documents: BTreeMap<String, TurtleDocument>,
+
+  // indexed graphs of the documents, built on the first dataset query about them
+  pub(crate) graphs: GraphIndex,
+
+  // mappers consulted in order to locate the document of an imported ontology
+  iri_mappers: Vec<Box<dyn IriMapper>>,
+
+  cache: Option<OntologyCache>,
+
+  // policy settling the conflicts between versions of an ontology within an import closure
+  version_policy: VersionPolicy,
+
+  // maximum number of imports parsed at the same time. `None` means the available parallelism
+  workers: Option<usize>,
+}
+
+impl OntologyManager {
+  pub fn new() -> OntologyManager {
+    Self::default()
+  }
+
+  /// add_iri_mapper adds a mapper consulted after the ones already added
+  pub fn add_iri_mapper(&mut self, mapper: Box<dyn IriMapper>) {
+    self.iri_mappers.push(mapper);
+  }
+
+  /// set_cache stores parsed documents in `cache`, so unchanged documents are not parsed again
+  pub fn set_cache(&mut self, cache: OntologyCache) {
+    self.cache = Some(cache);
+  }
+
+  /// set_workers bounds the number of documents of an import closure parsed at the same time.
+  /// Defaults to the available parallelism
+  pub fn set_workers(&mut self, workers: usize) {
+    self.workers = Some(workers.max(1));
+  }
+
+  /// set_version_policy sets the policy settling the conflicts between versions of an ontology
+  /// within an import closure. Defaults to `VersionPolicy::PreferLocal`
+  pub fn set_version_policy(&mut self, policy: VersionPolicy) {
+    self.version_policy = policy;
+  }
+
+  /// load loads the document at `path` and its import closure. The key of the document is returned,
+  /// i.e its ontology IRI or `path` for an anonymous ontology
+  /// example
+  /// `load("src/ontology/AllCoreOntology.ttl")`
+  pub fn load(&mut self, path: &str) -> std::io::Result<String> {
+    self.load_with_report(path).map(|x| x.key)
+  }
+
+  /// load_with_report loads the document at `path` and its import closure like `load`, and reports
+  /// the documents loaded and the versions chosen when the closure holds several versions of an
+  /// ontology. Under `VersionPolicy::Error` such a closure fails to load
+  pub fn load_with_report(&mut self, path: &str) -> std::io::Result<LoadReport> {
+    let key = self.load_location(path, None)?;
+    let mut versions = BTreeMap::new();
+    let mut report = LoadReport::new(&key, vec![key.clone()], vec![]);
+    self.settle_version(&key, &mut versions, &mut report)?;
+    // the closure is loaded one level at a time. The documents of a level are parsed in parallel
+    // and added in the order they are imported, so the result does not depend on the workers
+    let mut pending = vec![key];
+    while !pending.is_empty() {
+      let mut imports: Vec<String> = vec![];
+      for iri in pending.iter() {
+        for import in self.imports_of(iri) {
+          if !self.documents.contains_key(&import) && !imports.contains(&import) {
+            imports.push(import);
+          }
+        }
+      }
+      let documents = self.fetch_all(&imports)?;
+      pending = vec![];
+      for (key, document) in imports.into_iter().zip(documents) {
+        self.graphs.invalidate(&key);
+        self.documents.insert(key.clone(), document);
+        report.loaded.push(key.clone());
+        // the imports of a version which is not kept are not followed
+        if self.settle_version(&key, &mut versions, &mut report)? {
+          pending.push(key);
+        }
+      }
+    }
+    Ok(report)
+  }
+
+  /// document returns the document of an ontology given as a full IRI
+  pub fn document(&self, iri: &str) -> Option<&TurtleDocument> {
+    self.documents.get(iri)
+  }
+
+  /// imports_of returns the IRIs of the ontologies directly imported by an ontology
+  pub fn imports_of(&self, iri: &str) -> Vec<String> {
+    self.document(iri).map(|x| x.imports()).unwrap_or_default()
+  }
+
+  /// import_closure returns the IRI of an ontology followed by the IRIs of the ontologies it
+  /// imports directly or indirectly, in breadth first order. Ontologies not loaded are not followed
+  pub fn import_closure(&self, iri: &str) -> Vec<String> {
+    let mut closure = vec![];
+    let mut visited = BTreeSet::new();
+    let mut pending = VecDeque::from(vec![String::from(iri)]);
+    while let Some(iri) = pending.pop_front() {
+      if !visited.insert(iri.clone()) {
+        continue;
+      }
+      pending.extend(self.imports_of(&iri));
+      closure.push(iri);
+    }
+    closure
+  }
+
+  /// references_of returns the triples of every loaded ontology mentioning an entity given as a
+  /// full IRI, keyed by ontology. Ontologies not mentioning the entity are left out, and a
+  /// reference in a quoted triple counts as a reference of the triple quoting it
+  /// example
+  /// `references_of("http://www.ontologyrepository.com/CommonCoreOntologies/Agent")`
+  pub fn references_of(&self, iri: &str) -> BTreeMap<String, Vec<EntityReference>> {
+    let mut references = BTreeMap::new();
+    for (key, document) in self.documents.iter() {
+      let found: Vec<EntityReference> = document
+        .triples()
+        .into_iter()
+        .filter_map(|t| {
+          let positions = positions_of(&t, iri);
+          match positions.is_empty() {
+            true => None,
+            false => Some(EntityReference::new(t, positions)),
+          }
+        })
+        .collect();
+      if !found.is_empty() {
+        references.insert(key.clone(), found);
+      }
+    }
+    references
+  }
+
+  // document_location returns the location of the document of an imported ontology
+  fn document_location(&self, iri: &str) -> std::io::Result<String> {
+    if let Some(location) = self.iri_mappers.iter().find_map(|x| x.document_iri(iri)) {
+      return Ok(location);
+    }
+    match iri.strip_prefix("file://") {
+      Some(path) => Ok(String::from(path)),
+      None if !has_scheme(iri) => Ok(String::from(iri)),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("no document is mapped for the ontology <{}>", iri),
+      )),
+    }
+  }
+
+  // settle_version checks the version of a loaded document against the versions of its ontology
+  // loaded before, tracked in `versions` by ontology IRI along with the keys of their documents.
+  // When they differ, the policy picks a version and the documents of every key are replaced by
+  // the document of that version. It returns whether the version of the document is kept
+  fn settle_version(
+    &mut self,
+    key: &str,
+    versions: &mut BTreeMap<String, (OntologyVersion, Vec<String>)>,
+    report: &mut LoadReport,
+  ) -> std::io::Result<bool> {
+    let document = match self.documents.get(key) {
+      Some(x) => x,
+      None => return Ok(true),
+    };
+    let iri = match document.ontology_id().ontology_iri {
+      Some(x) => x,
+      None => return Ok(true),
+    };
+    let candidate = OntologyVersion::of(key, document);
+    let (current, keys) = match versions.get_mut(&iri) {
+      Some(x) => x,
+      None => {
+        versions.insert(iri, (candidate, vec![String::from(key)]));
+        return Ok(true);
+      }
+    };
+    keys.push(String::from(key));
+    if current.version_iri == candidate.version_iri {
+      return Ok(true);
+    }
+    let chosen = match self.version_policy {
+      VersionPolicy::PreferLocal => current.clone(),
+      VersionPolicy::PreferLatest if candidate.is_later_than(current) => candidate.clone(),
+      VersionPolicy::PreferLatest => current.clone(),
+      VersionPolicy::Error => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidData,
+          format!(
+            "the ontology <{}> is imported in two versions, {} and {}",
+            iri,
+            current
+              .version_iri
+              .as_deref()
+              .unwrap_or("without version IRI"),
+            candidate
+              .version_iri
+              .as_deref()
+              .unwrap_or("without version IRI"),
+          ),
+        ))
+      }
+    };
+    report.conflicts.push(VersionConflict::new(
+      &iri,
+      vec![current.clone(), candidate],
+      chosen.clone(),
+    ));
+    if let Some(document) = self.documents.get(&chosen.key).cloned() {
+      for k in keys.iter() {
+        self.graphs.invalidate(k);
+        self.documents.insert(k.clone(), document.clone());
+      }
+    }
+    let kept = chosen.key == key;
+    *current = chosen;
+    Ok(kept)
+  }
+
+  // load_location loads the document at `location`, from the cache when its artifact is unchanged,
+  // and returns its key
+  pub(crate) fn load_location(
+    &mut self,
+    location: &str,
+    iri: Option<&str>,
+  ) -> std::io::Result<String> {
+    let document = self.fetch(location, iri)?;
+    let key = iri
+      .map(String::from)
+      .or_else(|| document.ontology_id().ontology_iri)
+      .unwrap_or_else(|| String::from(location.strip_prefix("file://").unwrap_or(location)));
+    self.graphs.invalidate(&key);
+    self.documents.insert(key.clone(), document);
+    Ok(key)
+  }
+
+  // fetch_all fetches the documents of imported ontologies with a bounded pool of workers. The
+  // documents are returned in the order of `imports`, and the first failure in that order fails
+  // them all
+  fn fetch_all(&self, imports: &[String]) -> std::io::Result<Vec<TurtleDocument>> {
+    let locations = imports
+      .iter()
+      .map(|x| self.document_location(x))
+      .collect::<std::io::Result<Vec<String>>>()?;
+    let workers = self
+      .workers
+      .or_else(|| std::thread::available_parallelism().map(|x| x.get()).ok())
+      .unwrap_or(1)
+      .min(imports.len());
+
+    // workers take the next import to fetch until none is left
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<std::io::Result<TurtleDocument>>>> =
+      Mutex::new(imports.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+      for _ in 0..workers {
+        scope.spawn(|| loop {
+          let idx = next.fetch_add(1, Ordering::SeqCst);
+          let location = match locations.get(idx) {
+            Some(x) => x,
+            None => break,
+          };
+          let document = self.fetch(location, Some(&imports[idx]));
+          results.lock().unwrap()[idx] = Some(document);
+        });
+      }
+    });
+    results
+      .into_inner()
+      .unwrap()
+      .into_iter()
+      .flatten()
+      .collect()
+  }
+
+  // fetch loads the document at `location`, from the cache when its artifact is unchanged
+  fn fetch(&self, location: &str, iri: Option<&str>) -> std::io::Result<TurtleDocument> {
+    let location = location.strip_prefix("file://").unwrap_or(location);
+    let cache = match &self.cache {
+      Some(x) => x,
+      None => return load_document(location),
+    };
+    let sha256 = file_sha256(Path::new(location))?;
+    let cache_key = iri.unwrap_or(location);
+    match cache.get(cache_key, &sha256) {
+      Some(document) => Ok(document),
+      None => {
+        let document = load_document(location)?;
+        // the cache is an optimisation. Failing to store an entry does not fail the load
+        let _ = cache.put(cache_key, &sha256, &document);
+        Ok(document)
+      }
+    }
+  }
+}
+
+// positions_of returns the places of an entity in a triple, quoted triples included
+fn positions_of(triple: &TurtleTriple, iri: &str) -> Vec<TermPosition> {
+  let mut positions = BTreeSet::new();
+  let places = [
+    (&triple.subject, TermPosition::Subject),
+    (&triple.predicate, TermPosition::Predicate),
+    (&triple.object, TermPosition::Object),
+  ];
+  for (term, position) in places {
+    match term {
+      TurtleTerm::Iri(x) if x == iri => {
+        positions.insert(position);
+      }
+      TurtleTerm::Literal { datatype, .. } if datatype == iri => {
+        positions.insert(TermPosition::Datatype);
+      }
+      TurtleTerm::QuotedTriple(t) => positions.extend(positions_of(t, iri)),
+      _ => {}
+    }
+  }
+  positions.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::iri_mapper::SimpleIriMapper;
+
+  const ROOT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<http://example.org/root> a owl:Ontology ;
+  owl:imports <http://example.org/a> , <http://example.org/b> .
+"#;
+
+  const A: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<http://example.org/a> a owl:Ontology ;
+  owl:imports <http://example.org/b> .
+<http://example.org/A> a owl:Class .
+"#;
+
+  const B: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<http://example.org/b> a owl:Ontology ;
+  owl:imports <http://example.org/a> .
+<http://example.org/B> a owl:Class .
+"#;
+
+  // manager returns a manager locating `a` and `b` in `directory`
+  fn manager(directory: &Path) -> OntologyManager {
+    let mut mapper = SimpleIriMapper::new();
+    for name in ["a", "b"] {
+      let path = directory.join(format!("{}.ttl", name));
+      mapper.insert(
+        &format!("http://example.org/{}", name),
+        path.to_str().unwrap(),
+      );
+    }
+    let mut manager = OntologyManager::new();
+    manager.add_iri_mapper(Box::new(mapper));
+    manager
+  }
+
+  #[test]
+  fn should_load_import_closure() -> std::io::Result<()> {
+    let directory =
+      std::env::temp_dir().join(format!("semantic_owl_manager_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    for (name, content) in [("root", ROOT), ("a", A), ("b", B)] {
+      std::fs::write(directory.join(format!("{}.ttl", name)), content)?;
+    }
+    let root = directory.join("root.ttl");
+    let root = root.to_str().unwrap();
+    let cache = OntologyCache::new(directory.join("cache").to_str().unwrap());
+
+    let mut loaded = manager(&directory);
+    loaded.set_cache(cache.clone());
+    let key = loaded.load(root)?;
+    let entries = std::fs::read_dir(&cache.directory)?.count();
+
+    // a second load is served by the cache, until an artifact changes
+    let mut cached = manager(&directory);
+    cached.set_cache(cache.clone());
+    cached.load(root)?;
+    let unchanged = std::fs::read_dir(&cache.directory)?.count();
+    std::fs::write(directory.join("b.ttl"), B.replace("owl:Class", "owl:Thing"))?;
+    let mut changed = manager(&directory);
+    changed.set_cache(cache.clone());
+    changed.load(root)?;
+    let after_change = std::fs::read_dir(&cache.directory)?.count();
+
+    let missing = OntologyManager::new().load(root).map(|_| ());
+    std::fs::remove_dir_all(&directory)?;
+
+    assert_eq!(key, "http://example.org/root");
+    assert_eq!(
+      loaded.import_closure(&key),
+      vec![
+        "http://example.org/root",
+        "http://example.org/a",
+        "http://example.org/b"
+      ]
+    );
+    assert_eq!(
+      loaded.imports_of("http://example.org/b"),
+      vec!["http://example.org/a"]
+    );
+    assert_eq!(loaded.documents, cached.documents);
+    assert_eq!(entries, 3);
+    assert_eq!(unchanged, 3);
+    assert_eq!(after_change, 4);
+    assert_ne!(
+      changed.document("http://example.org/b"),
+      loaded.document("http://example.org/b")
+    );
+    assert_eq!(missing.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+    Ok(())
+  }
+
+  #[test]
+  fn should_load_imports_in_parallel() -> std::io::Result<()> {
+    let directory =
+      std::env::temp_dir().join(format!("semantic_owl_parallel_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    // the root imports twelve modules, each importing the next one
+    let mut mapper = SimpleIriMapper::new();
+    let mut imports = vec![];
+    for idx in 0..12 {
+      let iri = format!("http://example.org/module{}", idx);
+      let content = format!(
+        "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n<{}> a owl:Ontology ;\n  owl:imports <http://example.org/module{}> .\n",
+        iri,
+        (idx + 1) % 12
+      );
+      let path = directory.join(format!("module{}.ttl", idx));
+      std::fs::write(&path, content)?;
+      mapper.insert(&iri, path.to_str().unwrap());
+      imports.push(format!("<{}>", iri));
+    }
+    let root = directory.join("root.ttl");
+    std::fs::write(
+      &root,
+      format!(
+        "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n<http://example.org/root> a owl:Ontology ;\n  owl:imports {} .\n",
+        imports.join(" , ")
+      ),
+    )?;
+
+    let mut reports = vec![];
+    let mut documents = vec![];
+    for workers in [1, 4] {
+      let mut manager = OntologyManager::new();
+      manager.add_iri_mapper(Box::new(mapper.clone()));
+      manager.set_workers(workers);
+      reports.push(manager.load_with_report(root.to_str().unwrap())?);
+      documents.push(manager.documents);
+    }
+    std::fs::remove_dir_all(&directory)?;
+
+    assert_eq!(documents[0].len(), 13);
+    assert_eq!(documents[0], documents[1]);
+    assert_eq!(reports[0], reports[1]);
+    assert_eq!(reports[1].loaded[1], "http://example.org/module0");
+    assert_eq!(reports[1].loaded[12], "http://example.org/module11");
+    Ok(())
+  }
+
+  // upper returns a release of an upper ontology
+  fn upper(release: &str, info: &str) -> String {
+    format!(
+      r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+<http://example.org/upper> a owl:Ontology ;
+  owl:versionIRI <http://example.org/upper/{}> ;
+  owl:versionInfo "{}" .
+<http://example.org/Entity{}> a owl:Class .
+"#,
+      release, info, info
+    )
+  }
+
+  #[test]
+  fn should_settle_version_conflicts() -> std::io::Result<()> {
+    let directory =
+      std::env::temp_dir().join(format!("semantic_owl_versions_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    let documents = [
+      (
+        "root",
+        String::from("@prefix owl: <http://www.w3.org/2002/07/owl#> .\n<http://example.org/root> a owl:Ontology ; owl:imports <http://example.org/upper/2019> , <http://example.org/upper/2021> ."),
+      ),
+      ("2019", upper("2019", "1.9")),
+      ("2021", upper("2021", "1.10")),
+    ];
+    let mut mapper = SimpleIriMapper::new();
+    for (name, content) in documents.iter() {
+      let path = directory.join(format!("{}.ttl", name));
+      std::fs::write(&path, content)?;
+      mapper.insert(
+        &format!("http://example.org/upper/{}", name),
+        path.to_str().unwrap(),
+      );
+    }
+    let root = directory.join("root.ttl");
+    let load = |policy: VersionPolicy| {
+      let mut manager = OntologyManager::new();
+      manager.add_iri_mapper(Box::new(mapper.clone()));
+      manager.set_version_policy(policy);
+      manager
+        .load_with_report(root.to_str().unwrap())
+        .map(|report| (report, manager))
+    };
+    let latest = load(VersionPolicy::PreferLatest);
+    let local = load(VersionPolicy::PreferLocal);
+    let error = load(VersionPolicy::Error).map(|_| ());
+    std::fs::remove_dir_all(&directory)?;
+
+    let (report, manager) = latest?;
+    assert_eq!(report.key, "http://example.org/root");
+    assert_eq!(report.loaded.len(), 3);
+    assert_eq!(report.conflicts.len(), 1);
+    let conflict = &report.conflicts[0];
+    assert_eq!(conflict.ontology_iri, "http://example.org/upper");
+    assert_eq!(conflict.versions.len(), 2);
+    assert_eq!(conflict.chosen.version_info.as_deref(), Some("1.10"));
+    // both imports resolve to the version chosen
+    assert_eq!(
+      manager.document("http://example.org/upper/2019"),
+      manager.document("http://example.org/upper/2021")
+    );
+
+    let (report, manager) = local?;
+    assert_eq!(
+      report.conflicts[0].chosen.key,
+      "http://example.org/upper/2019"
+    );
+    assert_eq!(
+      manager
+        .document("http://example.org/upper/2021")
+        .and_then(|x| x.version_info()),
+      Some(String::from("1.9"))
+    );
+
+    assert_eq!(error.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+  }
+
+  #[test]
+  fn should_find_references_across_ontologies() -> std::io::Result<()> {
+    let directory =
+      std::env::temp_dir().join(format!("semantic_owl_references_{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    for (name, content) in [("root", ROOT), ("a", A), ("b", B)] {
+      std::fs::write(directory.join(format!("{}.ttl", name)), content)?;
+    }
+    let mut manager = manager(&directory);
+    let loaded = manager.load(directory.join("root.ttl").to_str().unwrap());
+    std::fs::remove_dir_all(&directory)?;
+    loaded?;
+
+    let references = manager.references_of("http://example.org/a");
+    assert_eq!(
+      references.keys().collect::<Vec<&String>>(),
+      vec![
+        "http://example.org/a",
+        "http://example.org/b",
+        "http://example.org/root"
+      ]
+    );
+    let a = &references["http://example.org/a"];
+    assert_eq!(a.len(), 2);
+    assert!(a.iter().all(|x| x.positions == vec![TermPosition::Subject]));
+    let b = &references["http://example.org/b"][0];
+    assert_eq!(b.positions, vec![TermPosition::Object]);
+    let span = b.span().unwrap();
+    assert!(span.file.as_deref().unwrap().ends_with("b.ttl"));
+    assert_eq!(span.lines, 2..4);
+
+    assert!(manager
+      .references_of("http://example.org/Unknown")
+      .is_empty());
+    Ok(())
+  }
+}
@@ -1,2 +1,18 @@
+pub mod cache;
+pub mod cancellation;
+pub(crate) mod compression;
+pub mod dataset;
+pub mod filter;
+pub mod fuzz;
+pub mod handler;
+pub mod iri_mapper;
 pub mod load;
-mod parsers;
+pub mod manager;
+pub mod parsers;
+pub mod recovery;
+pub mod report;
+pub mod scan;
+#[cfg(feature = "sparql")]
+pub mod sparql;
+pub mod version_policy;
+pub mod watch;
@@ -1 +1,4 @@
-pub(crate) mod ttl_parser;
+pub mod ttl_parser;
+pub(crate) mod ttl_pname_parser;
+pub mod ttl_statement_parser;
+pub(crate) mod xml_parser;
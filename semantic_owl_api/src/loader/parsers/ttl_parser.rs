@@ -1,4 +1,6 @@
 use crate::declarations::turtle::StatementKind;
+use crate::loader::parsers::ttl_pname_parser::is_prefixed_name;
+use crate::loader::parsers::ttl_statement_parser::parse_directive;
 use nom::{
   branch::alt,
   bytes::complete::tag,
@@ -14,15 +16,25 @@ trait TurtleInput: InputTake {
   fn take_right(&self, count: usize) -> (Self, Self);
 }
 
-impl<'a> TurtleInput for &'a str {
+impl TurtleInput for &str {
   // return byte index
   fn take_right(&self, count: usize) -> (Self, Self) {
     (&self[..count], &self[count..])
   }
 }
 
-///  parse_turtle is the main entry point for parsing turtle documents
-pub(crate) fn parse_turtle(input: &str) -> IResult<(), StatementKind> {
+///  parse_turtle classifies the first line of turtle content, returning the content following
+///  the line break. It is the entry point of the line oriented loader
+pub fn parse_turtle(input: &str) -> IResult<&str, StatementKind> {
+  let (line, rest) = match input.find('\n') {
+    Some(idx) => (&input[..idx], &input[idx + 1..]),
+    None => (input, ""),
+  };
+  Ok((rest, classify_line(line)))
+}
+
+// classify_line determines the kind of statement, or of part of a statement, a line holds
+fn classify_line(input: &str) -> StatementKind {
   // check if the statement is a comment or a valid statement that either
   // has a valid ending
   let input = input.trim_end(); // remove any tail whitespace
@@ -30,7 +42,7 @@ pub(crate) fn parse_turtle(input: &str) -> IResult<(), StatementKind> {
     // parse tail comments
     Ok(elements) => {
       let (_, right_elm) = elements;
-      parse_turtle(right_elm)
+      classify_line(right_elm)
     }
     Err(_) => match alt((
       is_a_comment,
@@ -42,26 +54,26 @@ pub(crate) fn parse_turtle(input: &str) -> IResult<(), StatementKind> {
       Ok(elements) => {
         let (_, right_elm) = elements;
         match Some(right_elm) {
-          Some(x) if x.starts_with('#') && x.len() == 0x1 => Ok(((), StatementKind::Comment)), // parse comments
-          Some(x) if x.is_empty() => Ok(((), StatementKind::Whitespace)), // parse whitespaces
+          Some(x) if x.starts_with('#') && x.len() == 0x1 => StatementKind::Comment, // parse comments
+          Some("") => StatementKind::Whitespace, // parse whitespaces
           Some(x) if (x.starts_with('.') || x.ends_with('.')) && x.len() == 0x1 => {
-            Ok(((), StatementKind::Terminator))
+            StatementKind::Terminator
           } // parse final end of a statement
           _ => match alt((is_a_norm_prefix, is_a_base_prefix, statement_ending))(input) {
             Ok(elements) => {
               let (_, right_elm) = elements;
               match Some(right_elm) {
-                Some(x) if x.starts_with("@prefix") => Ok(((), StatementKind::NormPrefix)), // parse norm prefix
-                Some(x) if x.starts_with("@base") => Ok(((), StatementKind::BasePrefix)), // parse base prefix
+                Some(x) if x.starts_with("@prefix") => StatementKind::NormPrefix, // parse norm prefix
+                Some(x) if x.starts_with("@base") => StatementKind::BasePrefix, // parse base prefix
                 Some(x)
                   if x.ends_with('.') && !x.starts_with("@prefix") && !x.starts_with("@base") =>
                 {
-                  Ok(((), StatementKind::StatementWithTerminator))
+                  StatementKind::StatementWithTerminator
                 } // parse end of a statement
-                _ => Ok(((), StatementKind::NotATurtle)),
+                _ => StatementKind::NotATurtle,
               }
             }
-            Err(_) => Ok(((), StatementKind::NotATurtle)),
+            Err(_) => StatementKind::NotATurtle,
           },
         }
       }
@@ -72,34 +84,34 @@ pub(crate) fn parse_turtle(input: &str) -> IResult<(), StatementKind> {
             Some(x)
               if (x.starts_with('[') && x.ends_with(';')) || has_tail_collection_ending(x) =>
             {
-              Ok(((), StatementKind::PartOfCollectionList))
+              StatementKind::PartOfCollectionList
             } // parse part of collection list
 
             Some(x) if x.ends_with(';') && has_subject_in_predicate(x) && !is_a_literal(x) => {
-              Ok(((), StatementKind::PartOfPredicateListWithSubject))
+              StatementKind::PartOfPredicateListWithSubject
             } // parse part of predicate list with subject
 
             Some(x) if x.ends_with(';') && !has_subject_in_predicate(x) && !is_a_literal(x) => {
-              Ok(((), StatementKind::PartOfPredicateList))
+              StatementKind::PartOfPredicateList
             } // parse part of predicate list
 
             Some(x) if x.ends_with(',') && has_predicate_in_object(x) && !is_a_literal(x) => {
-              Ok(((), StatementKind::PartOfObjectListWithPredicate))
+              StatementKind::PartOfObjectListWithPredicate
             } // parse part of object list with predicate
 
             Some(x) if x.ends_with(',') && !has_predicate_in_object(x) && !is_a_literal(x) => {
-              Ok(((), StatementKind::PartOfObjectList))
+              StatementKind::PartOfObjectList
             } // parse part of object list
 
             Some(x) if !has_predicate_in_object(x) && is_a_literal(x) => {
-              Ok(((), StatementKind::PartOfObjectListAsLiteral))
+              StatementKind::PartOfObjectListAsLiteral
             }
 
-            Some(x) if x.ends_with('.') => Ok(((), StatementKind::StatementWithTerminator)), // parse end of a statement
-            _ => Ok(((), StatementKind::NotATurtle)),
+            Some(x) if x.ends_with('.') => StatementKind::StatementWithTerminator, // parse end of a statement
+            _ => StatementKind::NotATurtle,
           }
         }
-        Err(_) => Ok(((), StatementKind::NotATurtle)),
+        Err(_) => StatementKind::NotATurtle,
       },
     },
   }
@@ -187,28 +199,23 @@ where
       Some(count) => match Some(count) {
         // catch empty input. Input should have a lenght longer or equal to two
         Some(count) if count >= 0x2 => match input.iter_elements().nth(count - 0x1) {
-          Some(last_elm) => {
-            if last_elm.as_char() == ',' || last_elm.as_char() == ';' {
-              match input.iter_elements().nth(count - 0x2) {
-                Some(b_elm) => match b_elm.as_char() == ' ' {
-                  true => Ok(input.take_right(0x0)),
-                  false => {
-                    let e: ErrorKind = ErrorKind::IsNot;
-                    Err(NomErr::Error(Error::from_error_kind(input, e)))
-                  }
-                },
-                None => {
+          Some(last_elm) if last_elm.as_char() == ',' || last_elm.as_char() == ';' => {
+            match input.iter_elements().nth(count - 0x2) {
+              Some(b_elm) => match b_elm.as_char() == ' ' {
+                true => Ok(input.take_right(0x0)),
+                false => {
                   let e: ErrorKind = ErrorKind::IsNot;
                   Err(NomErr::Error(Error::from_error_kind(input, e)))
                 }
+              },
+              None => {
+                let e: ErrorKind = ErrorKind::IsNot;
+                Err(NomErr::Error(Error::from_error_kind(input, e)))
               }
-            } else {
-              let e: ErrorKind = ErrorKind::IsNot;
-              Err(NomErr::Error(Error::from_error_kind(input, e)))
             }
           }
 
-          None => {
+          _ => {
             let e: ErrorKind = ErrorKind::IsNot;
             Err(NomErr::Error(Error::from_error_kind(input, e)))
           }
@@ -294,85 +301,117 @@ fn find_and_trim_tail_comment(input: &str) -> IResult<&str, &str> {
   }
 }
 
+// trim_tail_comment returns the part of a line before its tail comment, if the line has one. A
+// `#` starts a comment only outside IRIs and quoted literals, and when it is not escaped
 fn trim_tail_comment(x: &str) -> Option<&str> {
-  if x.is_empty() {
-    return None;
-  }
-  let mut ss = vec![];
-  for (idx, c) in x.chars().enumerate() {
-    let n = x.chars().nth(idx + 1)?;
-    if c == '#' && idx != 0x0 && (n == ' ' || n == '#') {
-      let l = x.split_at(idx);
-      let res: &str = l.0;
-      ss.push(res.trim_end());
-      break;
+  // delimiter of the literal being read, e.g -> `"` or `"""`
+  let mut quote: Option<&str> = None;
+  let mut in_iri = false;
+  let mut idx = 0;
+  while let Some(c) = x[idx..].chars().next() {
+    let rest = &x[idx..];
+    let mut step = c.len_utf8();
+    match (quote, c) {
+      // escaped characters, e.g -> `\"` in a literal or `\#` in a local name
+      (_, '\\') => step += rest[1..].chars().next().map(|x| x.len_utf8()).unwrap_or(0),
+      (Some(q), _) if rest.starts_with(q) => {
+        step = q.len();
+        quote = None;
+      }
+      (Some(_), _) => {}
+      (None, '>') if in_iri => in_iri = false,
+      (None, _) if in_iri => {}
+      (None, '<') => in_iri = true,
+      (None, '"') | (None, '\'') => {
+        let q = match rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+          true => &rest[..3],
+          false => &rest[..1],
+        };
+        step = q.len();
+        quote = Some(q);
+      }
+      (None, '#') if idx != 0x0 => return Some(x[..idx].trim_end()),
+      _ => {}
     }
+    idx += step;
   }
-  Some(ss[0])
+  None
 }
 
-// given a base staement of the form @base <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> .
+// given a base statement of the form @base <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> .
+// or BASE <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology>,
 // `get_base_iri_from_raw_statement` returns an Option of `<http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology>`
 pub(crate) fn get_base_iri_from_raw_statement(raw: &str) -> Option<String> {
-  let x = raw.strip_prefix("@base")?;
-  let x = x.strip_suffix('.')?;
-  let x = x.trim();
-  Some(String::from(x))
+  match parse_directive(raw) {
+    Ok((_, directive)) if directive.prefix.is_none() => Some(String::from(directive.iri)),
+    _ => None,
+  }
 }
 
 // given a prefix statement of the form @prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> . or
-// @prefix : <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology#> .,
-// `get_prefix_iri_from_raw_statement` returns an Option of `cco`
+// PREFIX : <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology#>,
+// `get_prefix_iri_from_raw_statement` returns an Option of `cco`, and whether the prefix is empty
 pub(crate) fn get_prefix_iri_from_raw_statement(raw: &str) -> Option<(String, bool)> {
-  let x = raw.strip_prefix("@prefix")?;
-  let x = x.strip_suffix('.')?;
-  let x = x.trim();
-  let x: Vec<&str> = x.split(':').collect();
-  let x = x[0x0];
-  Some((String::from(x), x.is_empty()))
+  let (_, directive) = parse_directive(raw).ok()?;
+  let prefix = directive.prefix?;
+  Some((String::from(prefix), prefix.is_empty()))
+}
+
+// given a prefix statement of the form @prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+// `get_prefix_namespace_iri_from_raw_statement` returns an Option of `<http://www.ontologyrepository.com/CommonCoreOntologies/>`
+pub(crate) fn get_prefix_namespace_iri_from_raw_statement(raw: &str) -> Option<String> {
+  match parse_directive(raw) {
+    Ok((_, directive)) if directive.prefix.is_some() => Some(String::from(directive.iri)),
+    _ => None,
+  }
 }
 
 // given a statement of the form -> owl:someValuesFrom cco:Velocity ] ;
 // returns the `true`
 fn has_tail_collection_ending(raw: &str) -> bool {
-  let x = raw.trim();
-  let x: Vec<&str> = x.split_whitespace().collect();
-  x[x.len() - 0x1] == ";" && x[x.len() - 0x2] == "]"
+  let x: Vec<&str> = raw.split_whitespace().collect();
+  match x.len() {
+    n if n >= 0x2 => x[n - 0x1] == ";" && x[n - 0x2] == "]",
+    _ => false,
+  }
 }
 
-fn has_subject_in_predicate(x: &str) -> bool {
-  let n: Vec<&str> = x.split(' ').collect();
-  let second_part = n[0x1];
-  let n1: Vec<&str> = second_part.split(':').collect();
+// is_a_resource determines whether a token is a term that can take the place of a
+// subject, predicate or object. That is, a well-formed prefixed name, an IRI or the `a` keyword
+fn is_a_resource(token: &str) -> bool {
+  token == "a" || is_prefixed_name(token) || (token.starts_with('<') && token.ends_with('>'))
+}
 
-  if n1.len() != 0x2 || (n1.len() == 0x2 && n.len() == 0x3) {
-    false
-  } else {
-    true
+// given a statement of the form -> cco:doctrinal_source rdf:type owl:AnnotationProperty ;
+// returns `true` since the statement begins with a subject followed by a predicate
+fn has_subject_in_predicate(x: &str) -> bool {
+  let n: Vec<&str> = x.split_whitespace().collect();
+  match n.get(0x1) {
+    Some(second_part) => is_a_resource(second_part) && n.len() != 0x3,
+    None => false,
   }
 }
 
+// given a statement of the form -> obo:IAO_0000112 "my body has part my brain"@en ,
+// returns `true` since the object list is preceded by its predicate
 fn has_predicate_in_object(x: &str) -> bool {
-  let n: Vec<&str> = x.split(' ').collect();
-  let first_part = n[0x0];
-  let next_part = n[0x1];
-  let n1: Vec<&str> = first_part.split(':').collect();
-  if n1.len() != 0x2 || next_part == "," {
-    false
-  } else {
-    true
+  let n: Vec<&str> = x.split_whitespace().collect();
+  match (n.first(), n.get(0x1)) {
+    (Some(first_part), Some(next_part)) => is_a_resource(first_part) && *next_part != ",",
+    _ => false,
   }
 }
 
+// given a statement of the form -> "my body has part my brain"@en ,
+// returns `true` since the statement begins with a literal
 fn is_a_literal(x: &str) -> bool {
-  let n: Vec<&str> = x.split(' ').collect();
-  let first_part = n[0x0];
-  let n1: Vec<&str> = first_part.split(':').collect();
-  if n1.len() == 0x1 && (x.ends_with(',') || x.ends_with(';')) {
-    true
-  } else {
-    false
-  }
+  let first_part = x.split_whitespace().next().unwrap_or_default();
+  let is_literal = first_part.starts_with('"')
+    || first_part.starts_with('\'')
+    || first_part.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-')
+    || first_part == "true"
+    || first_part == "false";
+  is_literal && (x.ends_with(',') || x.ends_with(';'))
 }
 
 #[cfg(test)]
@@ -381,59 +420,68 @@ mod tests {
 
   #[test]
   fn should_know_statement_is_a_literal() {
-    assert_eq!(is_a_literal("obo:IAO_0000112 \"my body has part my brain (continuant parthood, two material entities)\"@en ,")  , false);
-    assert_eq!(
-      is_a_literal(
-        "\"my body has part my brain (continuant parthood, two material entities)\"@en ,"
-      ),
-      true
-    );
-    assert_eq!(
-      is_a_literal(
-        "\"my body has part my brain (continuant parthood, two material entities)\"@en ;"
-      ),
-      true
-    )
+    assert!(!is_a_literal("obo:IAO_0000112 \"my body has part my brain (continuant parthood, two material entities)\"@en ,"));
+    assert!(is_a_literal(
+      "\"my body has part my brain (continuant parthood, two material entities)\"@en ,"
+    ));
+    assert!(is_a_literal(
+      "\"my body has part my brain (continuant parthood, two material entities)\"@en ;"
+    ))
   }
 
   #[test]
   fn should_know_statement_has_predicate_in_object() {
-    assert_eq!(has_predicate_in_object("obo:IAO_0000112 \"my body has part my brain (continuant parthood, two material entities)\"@en ,"),true);
+    assert!(has_predicate_in_object("obo:IAO_0000112 \"my body has part my brain (continuant parthood, two material entities)\"@en ,"));
 
-    assert_eq!(
-      has_predicate_in_object(
-        "\"my body has part my brain (continuant parthood, two material entities)\"@en ,"
-      ),
-      false
-    );
+    assert!(!has_predicate_in_object(
+      "\"my body has part my brain (continuant parthood, two material entities)\"@en ,"
+    ));
 
-    assert_eq!(has_predicate_in_object("obo:BFO_0000004 ,"), false)
+    assert!(!has_predicate_in_object("obo:BFO_0000004 ,"))
   }
 
   #[test]
   fn should_know_statement_has_subject_in_predicate() {
-    assert_eq!(
-      has_subject_in_predicate("cco:doctrinal_source rdf:type owl:AnnotationProperty ;"),
-      true
-    );
-    assert_eq!(has_subject_in_predicate("cco:definition \"A Process Profile that is the rate of change of the Velocity of an object.\"@en ;"), false  );
-    assert_eq!(
-      has_subject_in_predicate("rdfs:subClassOf obo:BFO_0000015 ;"),
-      false
-    )
+    assert!(has_subject_in_predicate(
+      "cco:doctrinal_source rdf:type owl:AnnotationProperty ;"
+    ));
+    assert!(!has_subject_in_predicate("cco:definition \"A Process Profile that is the rate of change of the Velocity of an object.\"@en ;")  );
+    assert!(!has_subject_in_predicate(
+      "rdfs:subClassOf obo:BFO_0000015 ;"
+    ))
+  }
+
+  #[test]
+  fn should_classify_statements_with_complex_local_names() {
+    assert!(has_subject_in_predicate("ex:foo.bar a owl:Class ;"));
+    assert!(has_subject_in_predicate(
+      "obo:GO_0008150 rdfs:subClassOf obo:BFO_0000015 ;"
+    ));
+    assert!(!has_subject_in_predicate(
+      "             rdfs:subPropertyOf obo:RO_0000056 ;"
+    ));
+    assert!(has_predicate_in_object(
+      "             rdf:type owl:FunctionalProperty ,"
+    ));
+    assert!(has_predicate_in_object("ex:a%20b ex:foo\\,bar ,"));
+    assert!(!has_predicate_in_object("ex:foo. ex:bar ,"));
+    assert!(!is_a_literal(
+      "             rdfs:subPropertyOf obo:RO_0000056 ;"
+    ));
+    assert!(is_a_literal(
+      "\"http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology\"^^xsd:anyURI ;"
+    ));
   }
 
   #[test]
   fn should_know_statement_has_tail_collection_ending() {
-    assert_eq!(
-      has_tail_collection_ending("owl:someValuesFrom cco:Velocity ] ;"),
-      true
-    );
+    assert!(has_tail_collection_ending(
+      "owl:someValuesFrom cco:Velocity ] ;"
+    ));
 
-    assert_eq!(
-      has_tail_collection_ending("owl:someValuesFrom cco:Velocity  ;"),
-      false
-    )
+    assert!(!has_tail_collection_ending(
+      "owl:someValuesFrom cco:Velocity  ;"
+    ))
   }
 
   #[test]
@@ -460,6 +508,22 @@ mod tests {
     )
   }
 
+  #[test]
+  fn should_return_prefix_namespace_iri_from_statement() {
+    assert_eq!(
+      get_prefix_namespace_iri_from_raw_statement(
+        "@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> ."
+      ),
+      Some(String::from(
+        "<http://www.ontologyrepository.com/CommonCoreOntologies/>"
+      ))
+    );
+    assert_eq!(
+      get_prefix_namespace_iri_from_raw_statement("@prefix cco: ."),
+      None
+    );
+  }
+
   #[test]
   fn should_return_base_iri_from_statement() {
     assert_eq!(
@@ -578,6 +642,27 @@ mod tests {
     )
   }
 
+  #[test]
+  fn should_not_find_tail_comment_in_iris_and_literals() {
+    assert_eq!(
+      trim_tail_comment("<http://ex.org/foo#Bar> a owl:Class ."),
+      None
+    );
+    assert_eq!(
+      trim_tail_comment("<http://ex.org/foo#Bar> a owl:Class . # Bar"),
+      Some("<http://ex.org/foo#Bar> a owl:Class .")
+    );
+    assert_eq!(
+      trim_tail_comment(":a rdfs:label \"issue # 12\" .#labels"),
+      Some(":a rdfs:label \"issue # 12\" .")
+    );
+    assert_eq!(
+      trim_tail_comment(":a rdfs:label \"a \\\" # b\" , '''c # d''' ."),
+      None
+    );
+    assert_eq!(trim_tail_comment(":a :b :c\\#d ."), None);
+  }
+
   #[test]
   fn should_know_statement_has_tail_comment0() {
     assert_eq!(find_and_trim_tail_comment("@base <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> . # a comment at the tail of statement"), 
@@ -807,18 +892,26 @@ mod tests {
     );
   }
 
+  #[test]
+  fn should_return_the_lines_following_the_first() {
+    let input = "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n:Agent a owl:Class .";
+    let (rest, kind) = parse_turtle(input).unwrap();
+    assert_eq!(kind, StatementKind::NormPrefix);
+    assert_eq!(
+      parse_turtle(rest),
+      Ok(("", StatementKind::StatementWithTerminator))
+    );
+  }
+
   #[test]
   fn should_know_to_correctly_parse_turtle_statements0() {
     let res = parse_turtle(
       "@prefix : <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology#> .",
     );
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::NormPrefix;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::NormPrefix;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
@@ -827,26 +920,20 @@ mod tests {
     let res = parse_turtle(
       "@base <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> .",
     );
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::BasePrefix;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::BasePrefix;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements2() {
     let res = parse_turtle("#################################################################");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Comment;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Comment;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
@@ -854,13 +941,10 @@ mod tests {
   fn should_know_to_correctly_parse_turtle_statements3() {
     let res = parse_turtle("#    Object Properties");
 
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Comment;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Comment;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
@@ -868,13 +952,10 @@ mod tests {
   fn should_know_to_correctly_parse_turtle_statements4() {
     let res = parse_turtle("###  http://www.ontologyrepository.com/CommonCoreOntologies/agent_in");
 
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Comment;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Comment;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
@@ -882,164 +963,128 @@ mod tests {
   fn should_know_to_correctly_parse_turtle_statements5() {
     let res = parse_turtle("###  http://www.ontologyrepository.com/CommonCoreOntologies/agent_in");
 
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Comment;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Comment;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements6() {
     let res = parse_turtle("");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Whitespace;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Whitespace;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements7() {
     let res = parse_turtle("        ");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::Whitespace;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::Whitespace;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements8() {
     let res = parse_turtle("@base <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> . # a comment at the tail of statement");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        let res1 = StatementKind::BasePrefix;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      let res1 = StatementKind::BasePrefix;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements9() {
     let res = parse_turtle(".");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::Terminator;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::Terminator;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements10() {
     let res = parse_turtle("rdfs:subClassOf <http://purl.bioontology.org/ontology/AIR/U000097> ;");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfPredicateList;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfPredicateList;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements11() {
     let res = parse_turtle("cco:process_precedes rdf:type owl:ObjectProperty ;");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfPredicateListWithSubject;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfPredicateListWithSubject;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements12() {
     let res = parse_turtle("umls:hasSTY <http://purl.bioontology.org/ontology/STY/T047> .");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::StatementWithTerminator;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::StatementWithTerminator;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements13() {
     let res = parse_turtle("obo:RO_0040042 obo:BFO_0000002 ,");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfObjectListWithPredicate;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfObjectListWithPredicate;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements14() {
     let res = parse_turtle("obo:BFO_0000004 ,");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfObjectList;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfObjectList;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements15() {
     let res = parse_turtle("\"my stomach has part my stomach cavity (continuant parthood, material entity has part immaterial entity)\"@en ,");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfObjectListAsLiteral;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfObjectListAsLiteral;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 
   #[test]
   fn should_know_to_correctly_parse_turtle_statements16() {
     let res = parse_turtle("\"my stomach has part my stomach cavity (continuant parthood, material entity has part immaterial entity)\"@en ;");
-    match res {
-      Ok(elm) => {
-        let (_, res0) = elm;
-        println!("{:?}", res0);
-        let res1 = StatementKind::PartOfObjectListAsLiteral;
-        assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
-      }
-      Err(_) => {}
+    if let Ok(elm) = res {
+      let (_, res0) = elm;
+      println!("{:?}", res0);
+      let res1 = StatementKind::PartOfObjectListAsLiteral;
+      assert_eq!(std::mem::discriminant(&res0), std::mem::discriminant(&res1));
     }
   }
 }
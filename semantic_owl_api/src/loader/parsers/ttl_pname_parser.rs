@@ -0,0 +1,255 @@
+//! Parsers for prefixed names as defined by the Turtle grammar
+use nom::{
+  error::{ErrorKind, ParseError},
+  Err as NomErr, IResult,
+};
+
+/// PrefixedName is a `PNAME_LN` or `PNAME_NS` split into its prefix and local parts.
+/// Example: `obo:GO_0008150` has the prefix `obo` and the local name `GO_0008150`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PrefixedName<'a> {
+  pub prefix: &'a str,
+  pub local: &'a str,
+}
+
+fn error<O>(i: &str, e: ErrorKind) -> IResult<&str, O> {
+  Err(NomErr::Error(nom::error::Error::from_error_kind(i, e)))
+}
+
+// [163s] PN_CHARS_BASE
+fn is_pn_chars_base(c: char) -> bool {
+  matches!(c,
+    'A'..='Z'
+    | 'a'..='z'
+    | '\u{00C0}'..='\u{00D6}'
+    | '\u{00D8}'..='\u{00F6}'
+    | '\u{00F8}'..='\u{02FF}'
+    | '\u{0370}'..='\u{037D}'
+    | '\u{037F}'..='\u{1FFF}'
+    | '\u{200C}'..='\u{200D}'
+    | '\u{2070}'..='\u{218F}'
+    | '\u{2C00}'..='\u{2FEF}'
+    | '\u{3001}'..='\u{D7FF}'
+    | '\u{F900}'..='\u{FDCF}'
+    | '\u{FDF0}'..='\u{FFFD}'
+    | '\u{10000}'..='\u{EFFFF}')
+}
+
+// [164s] PN_CHARS_U
+fn is_pn_chars_u(c: char) -> bool {
+  is_pn_chars_base(c) || c == '_'
+}
+
+// [166s] PN_CHARS
+fn is_pn_chars(c: char) -> bool {
+  is_pn_chars_u(c)
+    || matches!(c,
+      '-' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}')
+}
+
+// [173s] PN_LOCAL_ESC
+fn is_pn_local_esc(c: char) -> bool {
+  matches!(
+    c,
+    '_'
+      | '~'
+      | '.'
+      | '-'
+      | '!'
+      | '$'
+      | '&'
+      | '\''
+      | '('
+      | ')'
+      | '*'
+      | '+'
+      | ','
+      | ';'
+      | '='
+      | '/'
+      | '?'
+      | '#'
+      | '@'
+      | '%'
+  )
+}
+
+/// plx returns the byte length of a `PLX` (`PERCENT` or `PN_LOCAL_ESC`) at the start of the input
+fn plx(i: &str) -> Option<usize> {
+  let mut chars = i.chars();
+  match chars.next()? {
+    '%' => {
+      let h1 = chars.next()?;
+      let h2 = chars.next()?;
+      if h1.is_ascii_hexdigit() && h2.is_ascii_hexdigit() {
+        Some(3)
+      } else {
+        None
+      }
+    }
+    '\\' => match chars.next()? {
+      c if is_pn_local_esc(c) => Some(2),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// pn_prefix parses a `PN_PREFIX`
+/// example:
+///  `obo` in `obo:GO_0008150`
+pub(crate) fn pn_prefix(i: &str) -> IResult<&str, &str> {
+  match i.chars().next() {
+    Some(c) if is_pn_chars_base(c) => {
+      let mut end = c.len_utf8();
+      // the last character consumed which is not a `.`
+      let mut last = end;
+      for c in i[end..].chars() {
+        if is_pn_chars(c) {
+          end += c.len_utf8();
+          last = end;
+        } else if c == '.' {
+          end += 1;
+        } else {
+          break;
+        }
+      }
+      // a prefix can not end with a `.`
+      Ok((&i[last..], &i[..last]))
+    }
+    _ => error(i, ErrorKind::Alpha),
+  }
+}
+
+/// pn_local parses a `PN_LOCAL`
+/// example:
+///  `GO_0008150` in `obo:GO_0008150` or `foo.bar` in `ex:foo.bar`
+pub(crate) fn pn_local(i: &str) -> IResult<&str, &str> {
+  let mut end = match i.chars().next() {
+    Some(c) if is_pn_chars_u(c) || c == ':' || c.is_ascii_digit() => c.len_utf8(),
+    Some(_) => match plx(i) {
+      Some(n) => n,
+      None => return error(i, ErrorKind::AlphaNumeric),
+    },
+    None => return error(i, ErrorKind::Eof),
+  };
+  let mut last = end;
+  while let Some(c) = i[end..].chars().next() {
+    if is_pn_chars(c) || c == ':' {
+      end += c.len_utf8();
+      last = end;
+    } else if c == '.' {
+      end += 1;
+    } else if let Some(n) = plx(&i[end..]) {
+      end += n;
+      last = end;
+    } else {
+      break;
+    }
+  }
+  // a local name can not end with a `.`
+  Ok((&i[last..], &i[..last]))
+}
+
+/// pname_ns parses a `PNAME_NS`, returning the prefix without the trailing `:`
+/// example:
+///  `cco:` or `:`
+pub(crate) fn pname_ns(i: &str) -> IResult<&str, &str> {
+  let (rest, prefix) = match pn_prefix(i) {
+    Ok(r) => r,
+    Err(_) => (i, ""),
+  };
+  match rest.strip_prefix(':') {
+    Some(rest) => Ok((rest, prefix)),
+    None => error(i, ErrorKind::Char),
+  }
+}
+
+/// pname_ln parses a `PNAME_LN`
+/// example:
+///  `cco:agent_in` or `:Bent`
+pub(crate) fn pname_ln(i: &str) -> IResult<&str, PrefixedName<'_>> {
+  let (rest, prefix) = pname_ns(i)?;
+  let (rest, local) = pn_local(rest)?;
+  Ok((rest, PrefixedName { prefix, local }))
+}
+
+/// prefixed_name parses a `PrefixedName` which is either a `PNAME_LN` or a bare `PNAME_NS`
+pub(crate) fn prefixed_name(i: &str) -> IResult<&str, PrefixedName<'_>> {
+  match pname_ln(i) {
+    Ok(r) => Ok(r),
+    Err(_) => {
+      let (rest, prefix) = pname_ns(i)?;
+      Ok((rest, PrefixedName { prefix, local: "" }))
+    }
+  }
+}
+
+/// is_prefixed_name determines whether the whole token is a well-formed prefixed name
+pub(crate) fn is_prefixed_name(token: &str) -> bool {
+  matches!(prefixed_name(token), Ok((rest, _)) if rest.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_parse_pn_prefix() {
+    assert_eq!(pn_prefix("obo:GO_0008150"), Ok((":GO_0008150", "obo")));
+    assert_eq!(pn_prefix("a.b:c"), Ok((":c", "a.b")));
+    assert_eq!(pn_prefix("ab.:c"), Ok((".:c", "ab")));
+    assert!(pn_prefix("_ab:c").is_err());
+    assert!(pn_prefix("1ab:c").is_err());
+  }
+
+  #[test]
+  fn should_parse_pn_local() {
+    assert_eq!(pn_local("GO_0008150 ;"), Ok((" ;", "GO_0008150")));
+    assert_eq!(pn_local("foo.bar ."), Ok((" .", "foo.bar")));
+    assert_eq!(pn_local("foo."), Ok((".", "foo")));
+    assert_eq!(pn_local("a%20b"), Ok(("", "a%20b")));
+    assert_eq!(pn_local("a\\,b ,"), Ok((" ,", "a\\,b")));
+    assert_eq!(pn_local("0001:x"), Ok(("", "0001:x")));
+    assert!(pn_local("%zz").is_err());
+    assert!(pn_local("-a").is_err());
+  }
+
+  #[test]
+  fn should_parse_prefixed_names() {
+    assert_eq!(
+      pname_ln("obo:GO_0008150"),
+      Ok((
+        "",
+        PrefixedName {
+          prefix: "obo",
+          local: "GO_0008150"
+        }
+      ))
+    );
+    assert_eq!(
+      pname_ln(":Bent ."),
+      Ok((
+        " .",
+        PrefixedName {
+          prefix: "",
+          local: "Bent"
+        }
+      ))
+    );
+    assert_eq!(pname_ns("cco: <x>"), Ok((" <x>", "cco")));
+    assert!(pname_ln("\"literal\"").is_err());
+    assert!(pname_ln("<http://example.org/a>").is_err());
+  }
+
+  #[test]
+  fn should_know_token_is_a_prefixed_name() {
+    assert!(is_prefixed_name("ex:foo.bar"));
+    assert!(is_prefixed_name("obo:GO_0008150"));
+    assert!(is_prefixed_name("owl:"));
+    assert!(is_prefixed_name("ex:a:b"));
+    assert!(!is_prefixed_name("ex:foo."));
+    assert!(!is_prefixed_name("\"http://example.org\"^^xsd:anyURI"));
+    assert!(!is_prefixed_name("foo"));
+  }
+}
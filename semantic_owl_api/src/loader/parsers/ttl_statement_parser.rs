@@ -0,0 +1,1065 @@
+//! Parsers for complete turtle statements
+use crate::declarations::turtle::{TurtleBodyItem, TurtleObject, TurtlePredicate};
+use crate::loader::parsers::ttl_pname_parser::{pn_local, pname_ns, prefixed_name};
+use nom::{
+  error::{ErrorKind, ParseError},
+  Err as NomErr, IResult,
+};
+use std::collections::VecDeque;
+#[cfg(any(test, feature = "lsp"))]
+use std::ops::Range;
+
+pub(crate) const RDF_TYPE: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>";
+pub(crate) const RDF_FIRST: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#first>";
+pub(crate) const RDF_REST: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#rest>";
+pub(crate) const RDF_NIL: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#nil>";
+
+// prefix of the labels generated for nested blank nodes and collections
+const GENERATED_BLANK_NODE_PREFIX: &str = "genid";
+
+/// maximum nesting of blank node property lists and collections within a statement. Deeper
+/// statements are rejected rather than exhausting the stack
+pub(crate) const MAX_NESTING_DEPTH: usize = 128;
+
+/// RawTerm is a term of a statement as it appears in the source
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RawTerm<'a> {
+  // e.g -> <http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology>
+  Iri(&'a str),
+
+  // e.g -> cco:agent_in
+  PrefixedName(&'a str),
+
+  // e.g -> _:b0
+  BlankNode(&'a str),
+
+  // e.g -> "agent in"@en
+  Literal(&'a str),
+
+  // the `a` keyword, short for `rdf:type`
+  TypeKeyword,
+
+  // e.g -> [ rdf:type owl:Restriction ; owl:onProperty cco:has_process_part ]
+  BlankNodePropertyList(Vec<RawPredicateObjects<'a>>),
+
+  // e.g -> ( cco:Agent cco:Person )
+  Collection(Vec<RawTerm<'a>>),
+
+  // RDF-star quoted triple of a subject, a predicate and an object.
+  // e.g -> << cco:JohnDoe cco:has_age 42 >>
+  QuotedTriple(Box<[RawTerm<'a>; 3]>),
+}
+
+impl RawTerm<'_> {
+  // has_quoted_triple determines whether the term is, or holds, a quoted triple
+  fn has_quoted_triple(&self) -> bool {
+    match self {
+      RawTerm::QuotedTriple(_) => true,
+      RawTerm::BlankNodePropertyList(predicates) => predicates
+        .iter()
+        .any(|(p, objects)| p.has_quoted_triple() || objects.iter().any(|o| o.has_quoted_triple())),
+      RawTerm::Collection(members) => members.iter().any(|x| x.has_quoted_triple()),
+      _ => false,
+    }
+  }
+}
+
+/// RawPredicateObjects is a predicate with its list of objects
+pub(crate) type RawPredicateObjects<'a> = (RawTerm<'a>, Vec<RawTerm<'a>>);
+
+/// RawStatement is a single, terminated turtle statement
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RawStatement<'a> {
+  pub subject: RawTerm<'a>,
+  pub predicates: Vec<RawPredicateObjects<'a>>,
+}
+
+impl RawStatement<'_> {
+  /// has_quoted_triple determines whether the statement holds an RDF-star quoted triple, which
+  /// plain turtle does not allow
+  pub(crate) fn has_quoted_triple(&self) -> bool {
+    self.subject.has_quoted_triple()
+      || self
+        .predicates
+        .iter()
+        .any(|(p, objects)| p.has_quoted_triple() || objects.iter().any(|o| o.has_quoted_triple()))
+  }
+}
+
+/// RawDirective is a prefix or base directive
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RawDirective<'a> {
+  // prefix declared, without its colon. `None` for a base. e.g -> cco
+  pub prefix: Option<&'a str>,
+
+  // e.g -> <http://www.ontologyrepository.com/CommonCoreOntologies/>
+  pub iri: &'a str,
+}
+
+fn error<O>(i: &str, e: ErrorKind) -> IResult<&str, O> {
+  Err(NomErr::Error(nom::error::Error::from_error_kind(i, e)))
+}
+
+/// skip_ws consumes whitespace and comments
+pub(crate) fn skip_ws(i: &str) -> &str {
+  let mut i = i.trim_start();
+  while i.starts_with('#') {
+    i = match i.find(['\n', '\r']) {
+      Some(idx) => i[idx..].trim_start(),
+      None => "",
+    };
+  }
+  i
+}
+
+/// iri_ref parses an `IRIREF`, returning it including the angle brackets
+/// example:
+///  <http://www.w3.org/2002/07/owl#>
+pub(crate) fn iri_ref(i: &str) -> IResult<&str, &str> {
+  if !i.starts_with('<') {
+    return error(i, ErrorKind::Char);
+  }
+  for (idx, c) in i.char_indices().skip(1) {
+    match c {
+      '>' => return Ok((&i[idx + 1..], &i[..idx + 1])),
+      '<' | '"' | '{' | '}' | '|' | '^' | '`' => return error(i, ErrorKind::Verify),
+      c if c <= ' ' => return error(i, ErrorKind::Verify),
+      _ => {}
+    }
+  }
+  error(i, ErrorKind::Eof)
+}
+
+fn prefixed_name_token(i: &str) -> IResult<&str, &str> {
+  let (rest, _) = prefixed_name(i)?;
+  Ok((rest, &i[..i.len() - rest.len()]))
+}
+
+/// blank_node_label parses a `BLANK_NODE_LABEL`
+/// example:
+///  _:genid1
+fn blank_node_label(i: &str) -> IResult<&str, &str> {
+  match i.strip_prefix("_:") {
+    Some(r) => {
+      let (rest, _) = pn_local(r)?;
+      Ok((rest, &i[..i.len() - rest.len()]))
+    }
+    None => error(i, ErrorKind::Tag),
+  }
+}
+
+/// string_literal parses any of the four quoted string forms, returning the whole quoted string
+fn string_literal(i: &str) -> IResult<&str, &str> {
+  let quote = match i.chars().next() {
+    Some(c) if c == '"' || c == '\'' => c,
+    _ => return error(i, ErrorKind::Char),
+  };
+  let long: String = std::iter::repeat_n(quote, 3).collect();
+  let (delimiter, start) = if i.starts_with(long.as_str()) {
+    (long.as_str(), 3)
+  } else {
+    (&i[..1], 1)
+  };
+  let mut escaped = false;
+  for (idx, c) in i[start..].char_indices() {
+    let idx = idx + start;
+    if escaped {
+      escaped = false;
+    } else if c == '\\' {
+      escaped = true;
+    } else if delimiter.len() == 1 && (c == '\n' || c == '\r') {
+      return error(i, ErrorKind::Verify);
+    } else if i[idx..].starts_with(delimiter) {
+      let mut end = idx + delimiter.len();
+      // a long string may end with up to two more quotes belonging to its content
+      while delimiter.len() == 3 && i[end..].starts_with(quote) && end < idx + 5 {
+        end += 1;
+      }
+      return Ok((&i[end..], &i[..end]));
+    }
+  }
+  error(i, ErrorKind::Eof)
+}
+
+/// lang_tag parses a `LANGTAG` without the leading `@`
+fn lang_tag(i: &str) -> IResult<&str, &str> {
+  let end = i
+    .char_indices()
+    .find(|(idx, c)| !(c.is_ascii_alphabetic() || (*idx > 0 && (c.is_ascii_digit() || *c == '-'))))
+    .map(|(idx, _)| idx)
+    .unwrap_or_else(|| i.len());
+  if end == 0 || i[..end].ends_with('-') {
+    return error(i, ErrorKind::Alpha);
+  }
+  Ok((&i[end..], &i[..end]))
+}
+
+/// rdf_literal parses a `RDFLiteral`, that is a string with an optional language tag or datatype
+fn rdf_literal(i: &str) -> IResult<&str, &str> {
+  let (rest, _) = string_literal(i)?;
+  let rest = if let Some(r) = rest.strip_prefix('@') {
+    lang_tag(r)?.0
+  } else if let Some(r) = rest.strip_prefix("^^") {
+    match iri_ref(r) {
+      Ok((r, _)) => r,
+      Err(_) => prefixed_name_token(r)?.0,
+    }
+  } else {
+    rest
+  };
+  Ok((rest, &i[..i.len() - rest.len()]))
+}
+
+/// numeric_literal parses an `INTEGER`, `DECIMAL` or `DOUBLE`
+fn numeric_literal(i: &str) -> IResult<&str, &str> {
+  let bytes = i.as_bytes();
+  let mut idx = 0;
+  if idx < bytes.len() && (bytes[idx] == b'+' || bytes[idx] == b'-') {
+    idx += 1;
+  }
+  let int_start = idx;
+  while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+    idx += 1;
+  }
+  let mut digits = idx - int_start;
+  if idx + 1 < bytes.len() && bytes[idx] == b'.' && bytes[idx + 1].is_ascii_digit() {
+    idx += 1;
+    while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+      idx += 1;
+      digits += 1;
+    }
+  }
+  if digits == 0 {
+    return error(i, ErrorKind::Digit);
+  }
+  if idx < bytes.len() && (bytes[idx] == b'e' || bytes[idx] == b'E') {
+    let mut e = idx + 1;
+    if e < bytes.len() && (bytes[e] == b'+' || bytes[e] == b'-') {
+      e += 1;
+    }
+    let exp_start = e;
+    while e < bytes.len() && bytes[e].is_ascii_digit() {
+      e += 1;
+    }
+    if e > exp_start {
+      idx = e;
+    }
+  }
+  Ok((&i[idx..], &i[..idx]))
+}
+
+/// is_name_boundary determines whether a keyword ends at the start of the input
+fn is_name_boundary(i: &str) -> bool {
+  match i.chars().next() {
+    Some(c) => !(c.is_alphanumeric() || c == '_' || c == '-' || c == ':'),
+    None => true,
+  }
+}
+
+fn boolean_literal(i: &str) -> IResult<&str, &str> {
+  for keyword in ["true", "false"] {
+    if let Some(rest) = i.strip_prefix(keyword) {
+      if is_name_boundary(rest) {
+        return Ok((rest, keyword));
+      }
+    }
+  }
+  error(i, ErrorKind::Tag)
+}
+
+fn literal(i: &str) -> IResult<&str, &str> {
+  rdf_literal(i)
+    .or_else(|_| numeric_literal(i))
+    .or_else(|_| boolean_literal(i))
+}
+
+/// iri parses either an `IRIREF` or a `PrefixedName`
+fn iri(i: &str) -> IResult<&str, RawTerm<'_>> {
+  match iri_ref(i) {
+    Ok((rest, x)) => Ok((rest, RawTerm::Iri(x))),
+    Err(_) => {
+      let (rest, x) = prefixed_name_token(i)?;
+      Ok((rest, RawTerm::PrefixedName(x)))
+    }
+  }
+}
+
+// nested returns the depth of a structure nested in a structure at `depth`, or an error once
+// `MAX_NESTING_DEPTH` is exceeded
+fn nested(i: &str, depth: usize) -> Result<usize, NomErr<nom::error::Error<&str>>> {
+  match depth < MAX_NESTING_DEPTH {
+    true => Ok(depth + 1),
+    false => Err(NomErr::Failure(nom::error::Error::from_error_kind(
+      i,
+      ErrorKind::TooLarge,
+    ))),
+  }
+}
+
+fn blank_node_property_list(i: &str, depth: usize) -> IResult<&str, RawTerm<'_>> {
+  let rest = match i.strip_prefix('[') {
+    Some(r) => skip_ws(r),
+    None => return error(i, ErrorKind::Char),
+  };
+  let depth = nested(i, depth)?;
+  // ANON
+  if let Some(r) = rest.strip_prefix(']') {
+    return Ok((r, RawTerm::BlankNodePropertyList(vec![])));
+  }
+  let (rest, predicates) = predicate_object_list(rest, depth)?;
+  match skip_ws(rest).strip_prefix(']') {
+    Some(r) => Ok((r, RawTerm::BlankNodePropertyList(predicates))),
+    None => error(rest, ErrorKind::Char),
+  }
+}
+
+fn collection(i: &str, depth: usize) -> IResult<&str, RawTerm<'_>> {
+  let mut rest = match i.strip_prefix('(') {
+    Some(r) => r,
+    None => return error(i, ErrorKind::Char),
+  };
+  let depth = nested(i, depth)?;
+  let mut items = vec![];
+  loop {
+    rest = skip_ws(rest);
+    if let Some(r) = rest.strip_prefix(')') {
+      return Ok((r, RawTerm::Collection(items)));
+    }
+    let (r, item) = object(rest, depth)?;
+    items.push(item);
+    rest = r;
+  }
+}
+
+// quoted_triple parses an RDF-star `quotedTriple`, whose terms are IRIs, blank nodes, literals or
+// quoted triples. Nested blank nodes other than `[]` are not allowed
+// example:
+//  << cco:JohnDoe cco:has_age 42 >>
+fn quoted_triple(i: &str, depth: usize) -> IResult<&str, RawTerm<'_>> {
+  let rest = match i.strip_prefix("<<") {
+    Some(r) => skip_ws(r),
+    None => return error(i, ErrorKind::Tag),
+  };
+  let depth = nested(i, depth)?;
+  let (rest, s) = quoted_term(rest, depth, false)?;
+  let (rest, p) = verb(skip_ws(rest))?;
+  let (rest, o) = quoted_term(skip_ws(rest), depth, true)?;
+  match skip_ws(rest).strip_prefix(">>") {
+    Some(r) => Ok((r, RawTerm::QuotedTriple(Box::new([s, p, o])))),
+    None => error(rest, ErrorKind::Tag),
+  }
+}
+
+// quoted_term parses the subject, or the object, of a quoted triple
+fn quoted_term(i: &str, depth: usize, is_object: bool) -> IResult<&str, RawTerm<'_>> {
+  if i.starts_with("<<") {
+    return quoted_triple(i, depth);
+  }
+  if let Ok((rest, x)) = blank_node_label(i) {
+    return Ok((rest, RawTerm::BlankNode(x)));
+  }
+  if let Some(r) = i.strip_prefix('[') {
+    return match skip_ws(r).strip_prefix(']') {
+      Some(r) => Ok((r, RawTerm::BlankNodePropertyList(vec![]))),
+      None => error(i, ErrorKind::Char),
+    };
+  }
+  if is_object {
+    if let Ok((rest, x)) = literal(i) {
+      return Ok((rest, RawTerm::Literal(x)));
+    }
+  }
+  iri(i)
+}
+
+fn subject(i: &str) -> IResult<&str, RawTerm<'_>> {
+  if i.starts_with("<<") {
+    return quoted_triple(i, 0);
+  }
+  if let Ok((rest, x)) = blank_node_label(i) {
+    return Ok((rest, RawTerm::BlankNode(x)));
+  }
+  iri(i).or_else(|_| collection(i, 0))
+}
+
+fn verb(i: &str) -> IResult<&str, RawTerm<'_>> {
+  if let Some(rest) = i.strip_prefix('a') {
+    if is_name_boundary(rest) {
+      return Ok((rest, RawTerm::TypeKeyword));
+    }
+  }
+  iri(i)
+}
+
+fn object(i: &str, depth: usize) -> IResult<&str, RawTerm<'_>> {
+  if i.starts_with("<<") {
+    return quoted_triple(i, depth);
+  }
+  if let Ok((rest, x)) = blank_node_label(i) {
+    return Ok((rest, RawTerm::BlankNode(x)));
+  }
+  if let Ok((rest, x)) = literal(i) {
+    return Ok((rest, RawTerm::Literal(x)));
+  }
+  match iri(i) {
+    Err(NomErr::Error(_)) => match blank_node_property_list(i, depth) {
+      Err(NomErr::Error(_)) => collection(i, depth),
+      result => result,
+    },
+    result => result,
+  }
+}
+
+fn object_list(i: &str, depth: usize) -> IResult<&str, Vec<RawTerm<'_>>> {
+  let (mut rest, first) = object(skip_ws(i), depth)?;
+  let mut objects = vec![first];
+  loop {
+    match skip_ws(rest).strip_prefix(',') {
+      Some(r) => {
+        let (r, o) = object(skip_ws(r), depth)?;
+        objects.push(o);
+        rest = r;
+      }
+      None => return Ok((rest, objects)),
+    }
+  }
+}
+
+fn predicate_object_list(i: &str, depth: usize) -> IResult<&str, Vec<RawPredicateObjects<'_>>> {
+  let (rest, v) = verb(skip_ws(i))?;
+  let (mut rest, objects) = object_list(rest, depth)?;
+  let mut predicates = vec![(v, objects)];
+  loop {
+    let r = skip_ws(rest);
+    match r.strip_prefix(';') {
+      Some(r) => {
+        // a `;` may be repeated or be the last element of the list
+        let r = skip_ws(r);
+        match verb(r) {
+          Ok((r, v)) => {
+            let (r, objects) = object_list(r, depth)?;
+            predicates.push((v, objects));
+            rest = r;
+          }
+          Err(_) => rest = r,
+        }
+      }
+      None => return Ok((rest, predicates)),
+    }
+  }
+}
+
+/// parse_directive parses a `@prefix` or `@base` directive terminated by a `.`, or a `PREFIX` or
+/// `BASE` directive whose keyword is case insensitive, returning the unconsumed input. Tokens may
+/// be separated by any whitespace, line breaks and comments included, or by none where the
+/// grammar allows it
+/// example:
+///  @prefix cco:<http://www.ontologyrepository.com/CommonCoreOntologies/>.
+///  PREFIX cco: <http://www.ontologyrepository.com/CommonCoreOntologies/>
+pub(crate) fn parse_directive(i: &str) -> IResult<&str, RawDirective<'_>> {
+  let i = skip_ws(i);
+  let end = i
+    .char_indices()
+    .skip(1)
+    .find(|(_, c)| !c.is_ascii_alphabetic())
+    .map_or(i.len(), |(idx, _)| idx);
+  let (keyword, rest) = i.split_at(end);
+  // the keyword must not be the start of a longer token. e.g -> prefix:agent
+  if !(rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '<')) {
+    return error(i, ErrorKind::Tag);
+  }
+  let (sparql, base) = match keyword {
+    "@prefix" => (false, false),
+    "@base" => (false, true),
+    k if k.eq_ignore_ascii_case("prefix") => (true, false),
+    k if k.eq_ignore_ascii_case("base") => (true, true),
+    _ => return error(i, ErrorKind::Tag),
+  };
+  let (rest, prefix) = match base {
+    true => (rest, None),
+    false => {
+      let (rest, prefix) = pname_ns(skip_ws(rest))?;
+      (rest, Some(prefix))
+    }
+  };
+  let (rest, iri) = iri_ref(skip_ws(rest))?;
+  let rest = match sparql {
+    true => rest,
+    false => match skip_ws(rest).strip_prefix('.') {
+      Some(r) => r,
+      None => return error(rest, ErrorKind::Char),
+    },
+  };
+  Ok((rest, RawDirective { prefix, iri }))
+}
+
+/// parse_statement parses a single statement terminated by a `.`, returning the unconsumed input
+/// example:
+///  cco:agent_in rdf:type owl:ObjectProperty ;
+///               rdfs:label "agent in"@en .
+pub(crate) fn parse_statement(i: &str) -> IResult<&str, RawStatement<'_>> {
+  let i = skip_ws(i);
+  let (rest, statement) = match blank_node_property_list(i, 0) {
+    // blankNodePropertyList predicateObjectList?
+    Ok((rest, s)) => match predicate_object_list(rest, 0) {
+      Ok((rest, predicates)) => (
+        rest,
+        RawStatement {
+          subject: s,
+          predicates,
+        },
+      ),
+      Err(_) => (
+        rest,
+        RawStatement {
+          subject: s,
+          predicates: vec![],
+        },
+      ),
+    },
+    Err(NomErr::Failure(e)) => return Err(NomErr::Failure(e)),
+    Err(_) => {
+      let (rest, s) = subject(i)?;
+      let (rest, predicates) = predicate_object_list(rest, 0)?;
+      (
+        rest,
+        RawStatement {
+          subject: s,
+          predicates,
+        },
+      )
+    }
+  };
+  match skip_ws(rest).strip_prefix('.') {
+    Some(rest) => Ok((rest, statement)),
+    None => error(rest, ErrorKind::Char),
+  }
+}
+
+/// term_spans returns the byte ranges, within a statement, of the IRIs, prefixed names, blank
+/// node labels and literals it is made of, nested ones included, in the order they appear.
+/// It returns no range for a statement which can not be parsed
+/// example:
+///  `cco:Agent a owl:Class .` returns `0..9` and `12..21`
+#[cfg(any(test, feature = "lsp"))]
+pub(crate) fn term_spans(statement: &str) -> Vec<Range<usize>> {
+  let mut spans = vec![];
+  if let Ok((_, s)) = parse_statement(statement) {
+    raw_term_spans(statement, &s.subject, &mut spans);
+    predicate_spans(statement, &s.predicates, &mut spans);
+  }
+  spans
+}
+
+#[cfg(any(test, feature = "lsp"))]
+fn predicate_spans(
+  statement: &str,
+  predicates: &[RawPredicateObjects],
+  spans: &mut Vec<Range<usize>>,
+) {
+  for (p, objects) in predicates.iter() {
+    raw_term_spans(statement, p, spans);
+    for o in objects.iter() {
+      raw_term_spans(statement, o, spans);
+    }
+  }
+}
+
+// raw_term_spans collects the ranges of the terms of a raw term. Raw terms are slices of the
+// statement, which gives their offset
+#[cfg(any(test, feature = "lsp"))]
+fn raw_term_spans(statement: &str, term: &RawTerm, spans: &mut Vec<Range<usize>>) {
+  let token = match term {
+    RawTerm::Iri(x) | RawTerm::PrefixedName(x) | RawTerm::BlankNode(x) | RawTerm::Literal(x) => x,
+    RawTerm::TypeKeyword => return,
+    RawTerm::BlankNodePropertyList(predicates) => {
+      return predicate_spans(statement, predicates, spans)
+    }
+    RawTerm::Collection(members) => {
+      for x in members.iter() {
+        raw_term_spans(statement, x, spans);
+      }
+      return;
+    }
+    RawTerm::QuotedTriple(terms) => {
+      for x in terms.iter() {
+        raw_term_spans(statement, x, spans);
+      }
+      return;
+    }
+  };
+  let start = token.as_ptr() as usize - statement.as_ptr() as usize;
+  spans.push(start..start + token.len());
+}
+
+/// ParsedStatement is a directive or a statement of a turtle document
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedStatement {
+  // e.g -> @base <http://example.org/> . holds `http://example.org/`
+  Base(String),
+
+  // e.g -> @prefix owl: <http://www.w3.org/2002/07/owl#> . holds `owl` and its namespace IRI
+  Prefix { prefix: String, namespace: String },
+
+  // the statement followed by its nested blank nodes and collections, as body items of their own
+  Triples(VecDeque<TurtleBodyItem>),
+}
+
+/// TurtleStatementParser parses the directives and statements of turtle content one at a time,
+/// returning the unconsumed input, so it composes with other nom parsers. Nested blank nodes
+/// are labelled `_:genid{n}`, numbered across the statements parsed by the same parser
+/// example
+/// `many0(|i| parser.parse(i))("@prefix : <http://example.org/> . :a :b :c .")`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TurtleStatementParser {
+  // number of blank node labels generated so far
+  pub blank_node_count: usize,
+
+  // statements holding RDF-star quoted triples, e.g `<< :a :b :c >> :source :d .`, are rejected
+  // unless set
+  pub rdf_star: bool,
+}
+
+impl TurtleStatementParser {
+  pub fn new(blank_node_count: usize, rdf_star: bool) -> TurtleStatementParser {
+    Self {
+      blank_node_count,
+      rdf_star,
+    }
+  }
+
+  /// parse parses the directive or statement following any whitespace and comments. Directives
+  /// are written in the turtle (`@prefix`) or SPARQL (`PREFIX`) form
+  pub fn parse<'a>(&mut self, i: &'a str) -> IResult<&'a str, ParsedStatement> {
+    let i = skip_ws(i);
+    if let Ok((rest, directive)) = parse_directive(i) {
+      let iri = String::from(&directive.iri[1..directive.iri.len() - 1]);
+      let statement = match directive.prefix {
+        Some(prefix) => ParsedStatement::Prefix {
+          prefix: String::from(prefix),
+          namespace: iri,
+        },
+        None => ParsedStatement::Base(iri),
+      };
+      return Ok((rest, statement));
+    }
+    let (rest, statement) = parse_statement(i)?;
+    if !self.rdf_star && statement.has_quoted_triple() {
+      return error(i, ErrorKind::Verify);
+    }
+    let items = into_body_items(&statement, &mut self.blank_node_count);
+    Ok((rest, ParsedStatement::Triples(items)))
+  }
+}
+
+/// parse_turtle_statement parses the next directive or statement of turtle content, returning the
+/// unconsumed input. Use a `TurtleStatementParser` to parse several statements with nested blank nodes
+/// example:
+///  `parse_turtle_statement("cco:Agent a owl:Class . cco:Person a owl:Class .")` returns the
+///  statement of `cco:Agent` and ` cco:Person a owl:Class .`
+pub fn parse_turtle_statement(i: &str) -> IResult<&str, ParsedStatement> {
+  TurtleStatementParser::default().parse(i)
+}
+
+/// split_literal splits a raw literal into its quoted content, datatype and language tag.
+/// Numeric and boolean literals are given their implicit `xsd` datatype
+/// example:
+///  "agent in"@en -> ("agent in", None, Some("en"))
+///  "2021-03-01"^^xsd:date -> ("2021-03-01", Some("xsd:date"), None)
+pub(crate) fn split_literal(raw: &str) -> Option<(String, Option<String>, Option<String>)> {
+  if let Ok((rest, quoted)) = string_literal(raw) {
+    let delimiter =
+      if quoted.len() >= 6 && (quoted.starts_with("\"\"\"") || quoted.starts_with("'''")) {
+        3
+      } else {
+        1
+      };
+    let value = unescape(&quoted[delimiter..quoted.len() - delimiter]);
+    if let Some(lang) = rest.strip_prefix('@') {
+      return Some((value, None, Some(String::from(lang))));
+    }
+    if let Some(datatype) = rest.strip_prefix("^^") {
+      return Some((value, Some(String::from(datatype)), None));
+    }
+    return Some((value, None, None));
+  }
+  if let Ok(("", _)) = boolean_literal(raw) {
+    return Some((
+      String::from(raw),
+      Some(String::from("<http://www.w3.org/2001/XMLSchema#boolean>")),
+      None,
+    ));
+  }
+  if let Ok(("", _)) = numeric_literal(raw) {
+    let datatype = if raw.contains(['e', 'E']) {
+      "<http://www.w3.org/2001/XMLSchema#double>"
+    } else if raw.contains('.') {
+      "<http://www.w3.org/2001/XMLSchema#decimal>"
+    } else {
+      "<http://www.w3.org/2001/XMLSchema#integer>"
+    };
+    return Some((String::from(raw), Some(String::from(datatype)), None));
+  }
+  None
+}
+
+/// split_quoted_triple splits the source form of an RDF-star quoted triple into the source forms
+/// of its subject, predicate and object
+/// example:
+///  << cco:JohnDoe a cco:Person >> -> ("cco:JohnDoe", "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>", "cco:Person")
+pub(crate) fn split_quoted_triple(raw: &str) -> Option<(String, String, String)> {
+  let terms = match quoted_triple(raw, 0) {
+    Ok(("", RawTerm::QuotedTriple(terms))) => terms,
+    _ => return None,
+  };
+  // the source forms are those of a body item, in which `[]` was given a label already
+  let (mut count, mut items) = (0, VecDeque::new());
+  let [s, p, o] = *terms;
+  Some((
+    term_to_raw(&s, &mut count, &mut items),
+    term_to_raw(&p, &mut count, &mut items),
+    term_to_raw(&o, &mut count, &mut items),
+  ))
+}
+
+/// unescape replaces string escape sequences (`ECHAR` and `UCHAR`) with the characters they denote
+pub(crate) fn unescape(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      out.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('t') => out.push('\t'),
+      Some('b') => out.push('\u{8}'),
+      Some('n') => out.push('\n'),
+      Some('r') => out.push('\r'),
+      Some('f') => out.push('\u{c}'),
+      Some(u) if u == 'u' || u == 'U' => {
+        let n = if u == 'u' { 4 } else { 8 };
+        let hex: String = chars.by_ref().take(n).collect();
+        match u32::from_str_radix(&hex, 16)
+          .ok()
+          .and_then(std::char::from_u32)
+        {
+          Some(x) => out.push(x),
+          None => {
+            out.push('\\');
+            out.push(u);
+            out.push_str(&hex);
+          }
+        }
+      }
+      Some(x) => out.push(x),
+      None => out.push('\\'),
+    }
+  }
+  out
+}
+
+/// into_body_items flattens a statement into body items. Nested blank nodes and collections
+/// are given generated labels of the form `_:genid{n}` and become body items of their own.
+/// Labels of the document which could be taken for a generated label are renamed, see
+/// `document_blank_node`
+pub(crate) fn into_body_items(
+  statement: &RawStatement,
+  blank_node_count: &mut usize,
+) -> VecDeque<TurtleBodyItem> {
+  let mut items = VecDeque::new();
+  let (subject, predicates) = match &statement.subject {
+    // e.g -> [ rdf:type owl:Axiom ; owl:annotatedSource cco:Agent ] .
+    // the properties inside and after the brackets belong to the same blank node
+    RawTerm::BlankNodePropertyList(inner) => {
+      let subject = next_blank_node(blank_node_count);
+      let all: Vec<RawPredicateObjects> = inner
+        .iter()
+        .chain(statement.predicates.iter())
+        .cloned()
+        .collect();
+      (
+        subject,
+        predicates_to_body(&all, blank_node_count, &mut items),
+      )
+    }
+    s => {
+      let subject = term_to_raw(s, blank_node_count, &mut items);
+      let predicates = predicates_to_body(&statement.predicates, blank_node_count, &mut items);
+      (subject, predicates)
+    }
+  };
+  items.push_front(TurtleBodyItem {
+    subject: Some(subject),
+    predicate: predicates,
+    span: None,
+  });
+  items
+}
+
+fn next_blank_node(blank_node_count: &mut usize) -> String {
+  *blank_node_count += 1;
+  format!("_:{}{}", GENERATED_BLANK_NODE_PREFIX, blank_node_count)
+}
+
+// document_blank_node returns the label a blank node of the document is held with. A label
+// made of `u`s followed by `genid` is given one more leading `u`, so `_:genid1` of the document
+// becomes `_:ugenid1` and never meets a generated label, while two labels of the document stay
+// apart. e.g -> `_:ugenid1` becomes `_:uugenid1`, `_:b0` is left as is
+fn document_blank_node(label: &str) -> String {
+  let name = label.strip_prefix("_:").unwrap_or(label);
+  match name
+    .trim_start_matches('u')
+    .starts_with(GENERATED_BLANK_NODE_PREFIX)
+  {
+    true => format!("_:u{}", name),
+    false => String::from(label),
+  }
+}
+
+fn predicates_to_body(
+  predicates: &[RawPredicateObjects],
+  blank_node_count: &mut usize,
+  items: &mut VecDeque<TurtleBodyItem>,
+) -> VecDeque<TurtlePredicate> {
+  let mut out = VecDeque::new();
+  for (verb, objects) in predicates {
+    let raw_predicate = term_to_raw(verb, blank_node_count, items);
+    let mut predicate = TurtlePredicate::from_raw(&raw_predicate);
+    for o in objects {
+      let raw_object = term_to_raw(o, blank_node_count, items);
+      predicate
+        .object
+        .push_back(TurtleObject::from_raw(&raw_object));
+    }
+    out.push_back(predicate);
+  }
+  out
+}
+
+// term_to_raw returns the source form of a term. Nested structures are added to `items`
+fn term_to_raw(
+  term: &RawTerm,
+  blank_node_count: &mut usize,
+  items: &mut VecDeque<TurtleBodyItem>,
+) -> String {
+  match term {
+    RawTerm::Iri(x) | RawTerm::PrefixedName(x) | RawTerm::Literal(x) => String::from(*x),
+    RawTerm::BlankNode(x) => document_blank_node(x),
+    RawTerm::TypeKeyword => String::from(RDF_TYPE),
+    RawTerm::QuotedTriple(terms) => {
+      let [s, p, o] = terms.as_ref();
+      format!(
+        "<< {} {} {} >>",
+        term_to_raw(s, blank_node_count, items),
+        term_to_raw(p, blank_node_count, items),
+        term_to_raw(o, blank_node_count, items)
+      )
+    }
+    RawTerm::BlankNodePropertyList(predicates) => {
+      let label = next_blank_node(blank_node_count);
+      let mut nested = VecDeque::new();
+      let predicate = predicates_to_body(predicates, blank_node_count, &mut nested);
+      items.push_back(TurtleBodyItem {
+        subject: Some(label.clone()),
+        predicate,
+        span: None,
+      });
+      items.append(&mut nested);
+      label
+    }
+    RawTerm::Collection(members) => {
+      if members.is_empty() {
+        return String::from(RDF_NIL);
+      }
+      let head = next_blank_node(blank_node_count);
+      let mut current = head.clone();
+      for (idx, member) in members.iter().enumerate() {
+        let mut nested = VecDeque::new();
+        let first = term_to_raw(member, blank_node_count, &mut nested);
+        let rest = if idx + 1 == members.len() {
+          String::from(RDF_NIL)
+        } else {
+          next_blank_node(blank_node_count)
+        };
+        let mut p_first = TurtlePredicate::from_raw(RDF_FIRST);
+        p_first.object.push_back(TurtleObject::from_raw(&first));
+        let mut p_rest = TurtlePredicate::from_raw(RDF_REST);
+        p_rest.object.push_back(TurtleObject::from_raw(&rest));
+        items.push_back(TurtleBodyItem {
+          subject: Some(current.clone()),
+          predicate: VecDeque::from(vec![p_first, p_rest]),
+          span: None,
+        });
+        items.append(&mut nested);
+        current = rest;
+      }
+      head
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_parse_simple_statement() {
+    let res = parse_statement(
+      "cco:agent_in rdf:type owl:ObjectProperty ;\n  rdfs:label \"agent in\"@en . rest",
+    );
+    assert_eq!(
+      res,
+      Ok((
+        " rest",
+        RawStatement {
+          subject: RawTerm::PrefixedName("cco:agent_in"),
+          predicates: vec![
+            (
+              RawTerm::PrefixedName("rdf:type"),
+              vec![RawTerm::PrefixedName("owl:ObjectProperty")]
+            ),
+            (
+              RawTerm::PrefixedName("rdfs:label"),
+              vec![RawTerm::Literal("\"agent in\"@en")]
+            ),
+          ]
+        }
+      ))
+    );
+  }
+
+  #[test]
+  fn should_parse_nested_blank_nodes_and_collections() {
+    let input = "cco:Acceleration a owl:Class ;\n  rdfs:subClassOf [ rdf:type owl:Restriction ;\n   owl:onProperty cco:has_process_part ; # a comment\n   owl:someValuesFrom cco:Velocity\n  ] ;\n  owl:unionOf ( cco:A cco:B ) .";
+    let (rest, statement) = parse_statement(input).unwrap();
+    assert_eq!(rest, "");
+    assert_eq!(statement.predicates.len(), 3);
+    assert_eq!(statement.predicates[0].0, RawTerm::TypeKeyword);
+
+    let mut count = 0;
+    let items = into_body_items(&statement, &mut count);
+    // the statement, the restriction and the two collection nodes
+    assert_eq!(items.len(), 4);
+    assert_eq!(count, 3);
+    assert_eq!(items[0].subject, Some(String::from("cco:Acceleration")));
+    assert_eq!(items[1].subject, Some(String::from("_:genid1")));
+  }
+
+  #[test]
+  fn should_find_term_spans() {
+    let input = "# a comment\nex:a ex:p ex:ab , [ ex:p \"ex:a\"@en ] ; a ( _:b0 42 ) .";
+    let terms: Vec<&str> = term_spans(input).into_iter().map(|x| &input[x]).collect();
+    assert_eq!(
+      terms,
+      vec!["ex:a", "ex:p", "ex:ab", "ex:p", "\"ex:a\"@en", "_:b0", "42"]
+    );
+    assert_eq!(term_spans("@prefix ex: <http://example.org/> ."), vec![]);
+  }
+
+  #[test]
+  fn should_keep_document_blank_nodes_apart_from_generated_ones() {
+    let mut count = 0;
+    let mut subjects = vec![];
+    for input in ["_:genid1 :p :o .", ":s :q [ :r :x ] .", "_:ugenid1 :p :o ."] {
+      let (_, statement) = parse_statement(input).unwrap();
+      for item in into_body_items(&statement, &mut count) {
+        subjects.push(item.subject.unwrap());
+      }
+    }
+    assert_eq!(subjects, vec!["_:ugenid1", ":s", "_:genid1", "_:uugenid1"]);
+    assert_eq!(document_blank_node("_:b0"), "_:b0");
+    assert_eq!(document_blank_node("_:user"), "_:user");
+  }
+
+  #[test]
+  fn should_parse_literals() {
+    assert_eq!(
+      literal("\"a \\\"b\\\" c\"@en-GB ;"),
+      Ok((" ;", "\"a \\\"b\\\" c\"@en-GB"))
+    );
+    assert_eq!(
+      literal("\"http://example.org\"^^xsd:anyURI ."),
+      Ok((" .", "\"http://example.org\"^^xsd:anyURI"))
+    );
+    assert_eq!(
+      literal("\"\"\"multi\nline\"\"\" ."),
+      Ok((" .", "\"\"\"multi\nline\"\"\""))
+    );
+    assert_eq!(literal("-12.5e3 ,"), Ok((" ,", "-12.5e3")));
+    assert_eq!(literal("true ."), Ok((" .", "true")));
+    assert!(literal("trueish").is_err());
+    assert!(literal("\"unterminated").is_err());
+  }
+
+  #[test]
+  fn should_split_literals() {
+    assert_eq!(
+      split_literal("\"agent in\"@en"),
+      Some((String::from("agent in"), None, Some(String::from("en"))))
+    );
+    assert_eq!(
+      split_literal("'a\\tb'^^xsd:string"),
+      Some((String::from("a\tb"), Some(String::from("xsd:string")), None))
+    );
+    assert_eq!(
+      split_literal("42"),
+      Some((
+        String::from("42"),
+        Some(String::from("<http://www.w3.org/2001/XMLSchema#integer>")),
+        None
+      ))
+    );
+    assert_eq!(split_literal("cco:Agent"), None);
+  }
+
+  #[test]
+  fn should_parse_statements_one_at_a_time() {
+    let input = "# agents\n@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .\nBASE <http://example.org/>\ncco:Agent rdfs:subClassOf [ a owl:Restriction ] .\n[] a cco:Agent . rest";
+    let mut parser = TurtleStatementParser::default();
+    let (rest, prefix) = parser.parse(input).unwrap();
+    assert_eq!(
+      prefix,
+      ParsedStatement::Prefix {
+        prefix: String::from("cco"),
+        namespace: String::from("http://www.ontologyrepository.com/CommonCoreOntologies/"),
+      }
+    );
+    let (rest, base) = parser.parse(rest).unwrap();
+    assert_eq!(
+      base,
+      ParsedStatement::Base(String::from("http://example.org/"))
+    );
+    let (rest, agent) = parser.parse(rest).unwrap();
+    assert!(matches!(agent, ParsedStatement::Triples(items) if items.len() == 2));
+    // blank node labels are not reused by the following statements
+    let (rest, anonymous) = parser.parse(rest).unwrap();
+    match anonymous {
+      ParsedStatement::Triples(items) => {
+        assert_eq!(items[0].subject, Some(String::from("_:genid2")))
+      }
+      x => panic!("unexpected statement {:?}", x),
+    }
+    assert_eq!(rest, " rest");
+    assert!(parser.parse(rest).is_err());
+
+    // the parser composes with the combinators of nom
+    let (rest, statements) =
+      nom::multi::many1(|i| parse_turtle_statement(i))("@base <a> . :a :b :c . ").unwrap();
+    assert_eq!((rest, statements.len()), (" ", 2));
+
+    // directives are recognised as `parse_directive` recognises them
+    let (_, prefix) = parse_turtle_statement("PREFIX ex:<http://example.org/>").unwrap();
+    assert!(matches!(prefix, ParsedStatement::Prefix { prefix, .. } if prefix == "ex"));
+    let (_, agent) = parse_turtle_statement("prefix:agent a prefix:Agent .").unwrap();
+    assert!(matches!(agent, ParsedStatement::Triples(_)));
+    assert!(parse_turtle_statement("@prefix ex: <http://example.org/>").is_err());
+  }
+
+  #[test]
+  fn should_not_parse_unterminated_statement() {
+    assert!(parse_statement("cco:a cco:b cco:c ;").is_err());
+    assert!(parse_statement("cco:a cco:b").is_err());
+    assert!(parse_statement("{ \"json\": true }").is_err());
+  }
+}
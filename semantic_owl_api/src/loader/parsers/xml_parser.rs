@@ -0,0 +1,222 @@
+//! Parsers for the subset of XML needed to read XML catalogs
+use nom::{
+  error::{ErrorKind, ParseError},
+  Err as NomErr, IResult,
+};
+
+/// XmlTag is the start or end tag of an element. Names keep their namespace prefix
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum XmlTag<'a> {
+  // e.g -> <uri id="Imports" name="http://purl.obolibrary.org/obo/bfo.owl" uri="imports/bfo.owl"/>
+  // `empty` is true for such self-closing tags
+  Start {
+    name: &'a str,
+    attributes: Vec<(&'a str, String)>,
+    empty: bool,
+  },
+
+  // e.g -> </group>
+  End(&'a str),
+}
+
+impl<'a> XmlTag<'a> {
+  /// local_name returns the name of the element without its namespace prefix
+  pub fn local_name(&self) -> &'a str {
+    let name = match self {
+      XmlTag::Start { name, .. } => name,
+      XmlTag::End(name) => name,
+    };
+    name.rsplit(':').next().unwrap_or(name)
+  }
+
+  /// attribute returns the unescaped value of an attribute of a start tag
+  pub fn attribute(&self, name: &str) -> Option<&str> {
+    match self {
+      XmlTag::Start { attributes, .. } => attributes
+        .iter()
+        .find(|(x, _)| *x == name)
+        .map(|(_, v)| v.as_str()),
+      XmlTag::End(_) => None,
+    }
+  }
+}
+
+fn error<O>(i: &str, e: ErrorKind) -> IResult<&str, O> {
+  Err(NomErr::Error(nom::error::Error::from_error_kind(i, e)))
+}
+
+/// xml_tags parses the tags of a whole XML document in the order they appear
+pub(crate) fn xml_tags(i: &str) -> IResult<&str, Vec<XmlTag<'_>>> {
+  let mut tags = vec![];
+  let mut i = i;
+  loop {
+    i = match i.find('<') {
+      Some(idx) => &i[idx..],
+      None => return Ok(("", tags)),
+    };
+    let skipped = [
+      ("<!--", "-->"),
+      ("<?", "?>"),
+      ("<![CDATA[", "]]>"),
+      ("<!", ">"),
+    ]
+    .iter()
+    .find(|(start, _)| i.starts_with(start));
+    if let Some((_, end)) = skipped {
+      i = match i.find(end) {
+        Some(idx) => &i[idx + end.len()..],
+        None => return error(i, ErrorKind::Eof),
+      };
+      continue;
+    }
+    let (rest, tag) = xml_tag(i)?;
+    tags.push(tag);
+    i = rest;
+  }
+}
+
+/// xml_tag parses a start, end or empty-element tag
+/// example:
+///  <group id="Folder Repository" prefer="public" xml:base="">
+fn xml_tag(i: &str) -> IResult<&str, XmlTag<'_>> {
+  if let Some(r) = i.strip_prefix("</") {
+    let (r, name) = xml_name(r)?;
+    return match r.trim_start().strip_prefix('>') {
+      Some(r) => Ok((r, XmlTag::End(name))),
+      None => error(r, ErrorKind::Char),
+    };
+  }
+  let r = match i.strip_prefix('<') {
+    Some(r) => r,
+    None => return error(i, ErrorKind::Char),
+  };
+  let (mut r, name) = xml_name(r)?;
+  let mut attributes = vec![];
+  loop {
+    r = r.trim_start();
+    if let Some(rest) = r.strip_prefix("/>") {
+      let tag = XmlTag::Start {
+        name,
+        attributes,
+        empty: true,
+      };
+      return Ok((rest, tag));
+    }
+    if let Some(rest) = r.strip_prefix('>') {
+      let tag = XmlTag::Start {
+        name,
+        attributes,
+        empty: false,
+      };
+      return Ok((rest, tag));
+    }
+    let (rest, attribute) = xml_attribute(r)?;
+    attributes.push(attribute);
+    r = rest;
+  }
+}
+
+/// xml_name parses the name of an element or attribute, including its namespace prefix
+fn xml_name(i: &str) -> IResult<&str, &str> {
+  let end = i
+    .find(|c: char| c.is_whitespace() || c == '=' || c == '/' || c == '>' || c == '<')
+    .unwrap_or(i.len());
+  match end {
+    0 => error(i, ErrorKind::AlphaNumeric),
+    _ => Ok((&i[end..], &i[..end])),
+  }
+}
+
+/// xml_attribute parses an attribute with its quoted value
+/// example:
+///  uri="imports/bfo.owl"
+fn xml_attribute(i: &str) -> IResult<&str, (&str, String)> {
+  let (r, name) = xml_name(i)?;
+  let r = match r.trim_start().strip_prefix('=') {
+    Some(r) => r.trim_start(),
+    None => return error(r, ErrorKind::Char),
+  };
+  let quote = match r.chars().next() {
+    Some(c) if c == '"' || c == '\'' => c,
+    _ => return error(r, ErrorKind::Char),
+  };
+  match r[1..].find(quote) {
+    Some(idx) => Ok((&r[idx + 2..], (name, unescape(&r[1..idx + 1])))),
+    None => error(r, ErrorKind::Eof),
+  }
+}
+
+// unescape replaces the predefined entities and character references of an attribute value
+fn unescape(x: &str) -> String {
+  let mut result = String::with_capacity(x.len());
+  let mut rest = x;
+  while let Some(idx) = rest.find('&') {
+    result.push_str(&rest[..idx]);
+    rest = &rest[idx..];
+    let end = match rest.find(';') {
+      Some(end) => end,
+      None => break,
+    };
+    let entity = &rest[1..end];
+    let c = match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      _ => match entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+      {
+        Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+        None => entity
+          .strip_prefix('#')
+          .and_then(|x| x.parse().ok())
+          .and_then(char::from_u32),
+      },
+    };
+    match c {
+      Some(c) => {
+        result.push(c);
+        rest = &rest[end + 1..];
+      }
+      None => {
+        result.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  result.push_str(rest);
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_parse_xml_tags() {
+    let (_, tags) = xml_tags(
+      r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!-- Automatically generated -->
+<catalog prefer="public" xmlns="urn:oasis:names:tc:entity:xmlns:xml:catalog">
+    <uri name="http://example.org/a?x=1&amp;y=2" uri='a &#x26; b.owl'/>
+</catalog>"#,
+    )
+    .unwrap();
+    assert_eq!(tags.len(), 3);
+    assert_eq!(tags[0].local_name(), "catalog");
+    assert_eq!(
+      tags[1].attribute("name"),
+      Some("http://example.org/a?x=1&y=2")
+    );
+    assert_eq!(tags[1].attribute("uri"), Some("a & b.owl"));
+    assert_eq!(tags[2], XmlTag::End("catalog"));
+  }
+
+  #[test]
+  fn should_reject_malformed_tags() {
+    assert!(xml_tags("<uri name=\"unterminated/>").is_err());
+    assert!(xml_tags("<uri name>").is_err());
+  }
+}
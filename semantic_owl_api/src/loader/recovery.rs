@@ -0,0 +1,105 @@
+//! Recovery module lets the loader skip malformed statements in lenient mode
+use serde::{Deserialize, Serialize};
+
+/// ParseDiagnostic describes a statement skipped while loading a document in lenient mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseDiagnostic {
+  // line of the document where the statement starts, counted from 1
+  pub line: usize,
+
+  // e.g -> malformed statement
+  pub message: String,
+
+  // text of the statement skipped, up to and including its terminator
+  pub statement: String,
+}
+
+impl ParseDiagnostic {
+  pub fn new(line: usize, message: &str, statement: &str) -> ParseDiagnostic {
+    Self {
+      line,
+      message: String::from(message),
+      statement: String::from(statement),
+    }
+  }
+}
+
+/// find_terminator returns the byte offset of the `.` terminating the first statement of the
+/// input, i.e the first `.` at nesting level zero followed by whitespace, a comment or the end of
+/// the input. Dots within IRIs, strings, comments, prefixed names and numbers are skipped.
+/// example
+/// `find_terminator(":a :b \"c.d\" . :e :f :g .")` is `Some(12)`
+pub fn find_terminator(i: &str) -> Option<usize> {
+  let bytes = i.as_bytes();
+  let mut depth = 0usize;
+  let mut idx = 0;
+  while idx < bytes.len() {
+    match bytes[idx] {
+      b'#' => idx = skip_until(bytes, idx + 1, |b| b == b'\n'),
+      // an IRI can not hold whitespace, a malformed IRI ends at the first one
+      b'<' => idx = skip_until(bytes, idx + 1, |b| b == b'>' || b.is_ascii_whitespace()),
+      quote @ (b'"' | b'\'') => idx = skip_string(bytes, idx, quote),
+      b'[' | b'(' => depth += 1,
+      b']' | b')' => depth = depth.saturating_sub(1),
+      b'.' if depth == 0 => match bytes.get(idx + 1) {
+        None => return Some(idx),
+        Some(b) if b.is_ascii_whitespace() || *b == b'#' => return Some(idx),
+        _ => {}
+      },
+      _ => {}
+    }
+    idx += 1;
+  }
+  None
+}
+
+// skip_until returns the offset of the first byte from `idx` matching the predicate, or the end
+// of the input
+fn skip_until(bytes: &[u8], idx: usize, end: impl Fn(u8) -> bool) -> usize {
+  (idx..bytes.len())
+    .find(|x| end(bytes[*x]))
+    .unwrap_or(bytes.len())
+}
+
+// skip_string returns the offset of the closing quote of the string starting at `idx`. Short
+// strings end at the end of their line, long strings may span lines
+fn skip_string(bytes: &[u8], idx: usize, quote: u8) -> usize {
+  let long = bytes.get(idx + 1) == Some(&quote) && bytes.get(idx + 2) == Some(&quote);
+  let mut pos = if long { idx + 3 } else { idx + 1 };
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'\\' => pos += 1,
+      b'\n' if !long => return pos,
+      b if b == quote && !long => return pos,
+      b if b == quote
+        && bytes.get(pos + 1) == Some(&quote)
+        && bytes.get(pos + 2) == Some(&quote) =>
+      {
+        return pos + 2
+      }
+      _ => {}
+    }
+    pos += 1;
+  }
+  bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_find_statement_terminator() {
+    assert_eq!(find_terminator(":a :b \"c.d\" . :e :f :g ."), Some(12));
+    assert_eq!(find_terminator(":a :b ex:c.d ."), Some(13));
+    assert_eq!(find_terminator(":a :b 1.5 .\n"), Some(10));
+    assert_eq!(
+      find_terminator(":a :b <http://example.org/c.d> ."),
+      Some(31)
+    );
+    assert_eq!(find_terminator(":a :b \"\"\"c .\nd\"\"\" ."), Some(18));
+    assert_eq!(find_terminator(":a :b [ :c :d . ] ."), Some(18));
+    assert_eq!(find_terminator(":a :b :c # d .\n"), None);
+    assert_eq!(find_terminator(":a :b ( :c ."), None);
+  }
+}
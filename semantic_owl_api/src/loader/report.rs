@@ -0,0 +1,83 @@
+//! Report module describes how a document was loaded
+use crate::loader::recovery::ParseDiagnostic;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+pub const PARSE_PHASE: &str = "parse";
+pub const SKOLEMIZE_PHASE: &str = "skolemize";
+pub const REWRITE_PHASE: &str = "rewrite";
+
+/// PhaseTiming is the time spent in a phase of the loading of a document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTiming {
+  // e.g -> parse, which includes reading the document
+  pub phase: String,
+
+  pub duration: Duration,
+}
+
+impl PhaseTiming {
+  pub fn new(phase: &str, duration: Duration) -> PhaseTiming {
+    Self {
+      phase: String::from(phase),
+      duration,
+    }
+  }
+}
+
+/// LoadReport lists what the loader noticed while loading a document besides its statements
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LoadReport {
+  // path or URL the document was loaded from. `None` for documents loaded from memory
+  pub source: Option<String>,
+
+  // number of lines loaded
+  pub lines: usize,
+
+  // oddities which do not prevent loading. e.g -> prefix `cco:` redeclared
+  pub warnings: Vec<ParseDiagnostic>,
+
+  // malformed statements skipped in lenient mode
+  pub skipped: Vec<ParseDiagnostic>,
+
+  // directives other than `@prefix` and `@base`, skipped in lenient mode. e.g -> @version 1.2 .
+  pub unknown_directives: Vec<ParseDiagnostic>,
+
+  // time spent in each phase, in the order they ran
+  pub timings: Vec<PhaseTiming>,
+}
+
+impl LoadReport {
+  pub fn new(
+    source: Option<String>,
+    lines: usize,
+    warnings: Vec<ParseDiagnostic>,
+    skipped: Vec<ParseDiagnostic>,
+    unknown_directives: Vec<ParseDiagnostic>,
+    timings: Vec<PhaseTiming>,
+  ) -> LoadReport {
+    Self {
+      source,
+      lines,
+      warnings,
+      skipped,
+      unknown_directives,
+      timings,
+    }
+  }
+
+  /// is_clean determines whether the document loaded without warnings nor skipped content
+  pub fn is_clean(&self) -> bool {
+    self.warnings.is_empty() && self.skipped.is_empty() && self.unknown_directives.is_empty()
+  }
+
+  /// duration returns the time spent loading the document
+  pub fn duration(&self) -> Duration {
+    self.timings.iter().map(|x| x.duration).sum()
+  }
+
+  /// time records the time elapsed since `start` as the duration of a phase
+  pub fn time(&mut self, phase: &str, start: Instant) {
+    self.timings.push(PhaseTiming::new(phase, start.elapsed()));
+  }
+}
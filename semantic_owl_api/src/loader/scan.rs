@@ -0,0 +1,156 @@
+//! Scan module reads what a turtle document holds without loading it
+use crate::analysis::signature::Signature;
+use crate::declarations::ontology::OntologyId;
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleBodyItem, TurtleDocument, TurtleHeaderItem, TurtleObject};
+use crate::loader::compression::open_document;
+use crate::loader::load::{read_source, read_str, LoaderOptions, StatementSink};
+use crate::loader::parsers::ttl_statement_parser::split_literal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// SignatureIndex is what a scan tells about a document
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SignatureIndex {
+  // prefixes and base declared by the document
+  pub prefixes: PrefixMap,
+
+  pub ontology_id: OntologyId,
+
+  // IRIs of the ontologies imported by the document, in document order
+  pub imports: Vec<String>,
+
+  pub signature: Signature,
+
+  // number of statements of the document, nested blank nodes and collections included
+  pub statements: usize,
+}
+
+impl SignatureIndex {
+  pub fn new(
+    prefixes: PrefixMap,
+    ontology_id: OntologyId,
+    imports: Vec<String>,
+    signature: Signature,
+    statements: usize,
+  ) -> SignatureIndex {
+    Self {
+      prefixes,
+      ontology_id,
+      imports,
+      signature,
+      statements,
+    }
+  }
+
+  // of builds the index of the skeleton of a document
+  fn of(document: &TurtleDocument) -> SignatureIndex {
+    Self::new(
+      document.prefix_map(),
+      document.ontology_id(),
+      document.imports(),
+      document.signature(),
+      document.body.len(),
+    )
+  }
+}
+
+/// scan_signature scans a turtle document, gzip and zip documents included when the
+/// `compression` feature is enabled. Blank nodes are never skolemized by a scan
+pub fn scan_signature(path: &str, options: &LoaderOptions) -> std::io::Result<SignatureIndex> {
+  let source = open_document(path)?;
+  let mut sink = SkeletonSink::default();
+  read_source(source.reader, source.size, options, &mut sink)?;
+  Ok(SignatureIndex::of(&sink.document))
+}
+
+/// scan_signature_from_str scans turtle content held in memory
+pub fn scan_signature_from_str(content: &str) -> std::io::Result<SignatureIndex> {
+  let mut sink = SkeletonSink::default();
+  read_str(content, &mut sink)?;
+  Ok(SignatureIndex::of(&sink.document))
+}
+
+// SkeletonSink keeps the statements of a document without literal values nor locations
+#[derive(Default)]
+struct SkeletonSink {
+  document: TurtleDocument,
+}
+
+impl StatementSink for SkeletonSink {
+  fn header(&mut self, mut header: TurtleHeaderItem) {
+    header.span = None;
+    self.document.headers.push_back(header);
+  }
+
+  fn body(&mut self, items: VecDeque<TurtleBodyItem>) {
+    for mut item in items.into_iter() {
+      item.span = None;
+      for p in item.predicate.iter_mut() {
+        for o in p.object.iter_mut() {
+          if let Some(raw) = o.raw_object.as_deref().and_then(empty_literal) {
+            *o = TurtleObject::from_raw(&raw);
+          }
+        }
+      }
+      self.document.body.push_back(item);
+    }
+  }
+}
+
+// empty_literal returns a literal of the same datatype and language as a raw literal, with an
+// empty value. It returns `None` if `raw` is not a literal
+fn empty_literal(raw: &str) -> Option<String> {
+  let (_, datatype, language) = split_literal(raw)?;
+  match (datatype, language) {
+    (Some(datatype), _) => Some(format!("\"\"^^{}", datatype)),
+    (None, Some(language)) => Some(format!("\"\"@{}", language)),
+    (None, None) => Some(String::from("\"\"")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::{load_turtle_document, load_turtle_document_from_str};
+  use std::env::current_dir;
+
+  #[test]
+  fn should_scan_signature() -> std::io::Result<()> {
+    let content = r#"@prefix : <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+<http://example.org/ontology> a owl:Ontology ; owl:imports <http://example.org/upper> .
+:definition a owl:AnnotationProperty .
+:Agent a owl:Class ; rdfs:label "Agent"@en ; :definition """An agent,
+  over several lines"""^^xsd:string .
+:JohnDoe :has_age 42 ; :knows [ a :Agent ] .
+"#;
+    let index = scan_signature_from_str(content)?;
+    let document = load_turtle_document_from_str(content)?;
+    assert_eq!(index.signature, document.signature());
+    assert_eq!(index.imports, vec!["http://example.org/upper"]);
+    assert_eq!(
+      index.ontology_id.ontology_iri.as_deref(),
+      Some("http://example.org/ontology")
+    );
+    assert_eq!(
+      index.prefixes.namespace("owl"),
+      document.prefix_map().namespace("owl")
+    );
+    assert_eq!(index.statements, document.body.len());
+
+    let path = current_dir()?
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/CurrencyUnitOntology.ttl");
+    let path = path.to_str().unwrap();
+    let index = scan_signature(path, &LoaderOptions::default())?;
+    let document = load_turtle_document(path)?;
+    assert_eq!(index.signature, document.signature());
+    assert_eq!(index.ontology_id, document.ontology_id());
+    Ok(())
+  }
+}
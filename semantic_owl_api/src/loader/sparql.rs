@@ -0,0 +1,213 @@
+//! Sparql module loads documents from a remote SPARQL endpoint
+use crate::declarations::provenance::DocumentProvenance;
+use crate::declarations::turtle::TurtleDocument;
+use crate::loader::load::load_turtle_document_from_str;
+use crate::transforms::canonical::sha256_hex;
+use std::time::Duration;
+
+/// media types requested from the endpoint. N-Triples is a subset of turtle
+pub const GRAPH_MEDIA_TYPES: &str = "text/turtle, application/n-triples;q=0.9";
+
+/// default time allowed for a query, from connection to the end of the response
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// SparqlClient executes graph queries against a SPARQL 1.1 endpoint
+/// (https://www.w3.org/TR/sparql11-protocol/)
+#[derive(Debug, Clone)]
+pub struct SparqlClient {
+  endpoint: String,
+  client: reqwest::Client,
+}
+
+impl SparqlClient {
+  /// new creates a client of the endpoint. Queries time out after `DEFAULT_QUERY_TIMEOUT`
+  /// example
+  /// `SparqlClient::new("https://query.wikidata.org/sparql")`
+  pub fn new(endpoint: &str) -> std::io::Result<SparqlClient> {
+    Self::with_timeout(endpoint, DEFAULT_QUERY_TIMEOUT)
+  }
+
+  /// with_timeout creates a client of the endpoint whose queries time out after `timeout`
+  pub fn with_timeout(endpoint: &str, timeout: Duration) -> std::io::Result<SparqlClient> {
+    let client = reqwest::Client::builder()
+      .timeout(timeout)
+      .build()
+      .map_err(into_io_error)?;
+    Ok(Self {
+      endpoint: String::from(endpoint),
+      client,
+    })
+  }
+
+  /// construct executes a `CONSTRUCT` or `DESCRIBE` query and loads the resulting graph.
+  /// Other query forms are rejected with `std::io::ErrorKind::InvalidInput`
+  pub async fn construct(&self, query: &str) -> std::io::Result<TurtleDocument> {
+    if !is_graph_query(query) {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "only CONSTRUCT and DESCRIBE queries return a graph",
+      ));
+    }
+    let response = self
+      .client
+      .post(&self.endpoint)
+      .header(reqwest::header::ACCEPT, GRAPH_MEDIA_TYPES)
+      .header(reqwest::header::CONTENT_TYPE, "application/sparql-query")
+      .body(String::from(query))
+      .send()
+      .await
+      .map_err(into_io_error)?;
+    let status = response.status();
+    if !status.is_success() {
+      return Err(std::io::Error::other(format!(
+        "the endpoint answered the query with {}",
+        status
+      )));
+    }
+    let body = response.text().await.map_err(into_io_error)?;
+    let mut document = load_turtle_document_from_str(&body)?;
+    document.provenance = Some(DocumentProvenance::loaded_now(
+      &self.endpoint,
+      body.len() as u64,
+      &sha256_hex(&body),
+    ));
+    Ok(document)
+  }
+
+  /// describe loads the description of a resource, given as a full IRI, held by the endpoint
+  pub async fn describe(&self, iri: &str) -> std::io::Result<TurtleDocument> {
+    self.construct(&format!("DESCRIBE <{}>", iri)).await
+  }
+}
+
+// is_graph_query determines whether the query form, following its prologue, is `CONSTRUCT` or `DESCRIBE`
+fn is_graph_query(query: &str) -> bool {
+  let mut rest = query;
+  loop {
+    rest = skip_ignored(rest);
+    let end = rest
+      .find(|c: char| !c.is_ascii_alphabetic())
+      .unwrap_or(rest.len());
+    let (keyword, after) = rest.split_at(end);
+    let skipped = match keyword.to_ascii_uppercase().as_str() {
+      // skip the prefix name, which runs up to its colon, and the namespace IRI of a prefix
+      // declaration. e.g -> PREFIX ex:<http://example.org/>
+      "PREFIX" => after
+        .split_once(':')
+        .and_then(|(_, x)| skip_iri(skip_ignored(x))),
+      // skip the IRI of the base declaration
+      "BASE" => skip_iri(skip_ignored(after)),
+      "CONSTRUCT" | "DESCRIBE" => return true,
+      _ => return false,
+    };
+    rest = match skipped {
+      Some(x) => x,
+      None => return false,
+    };
+  }
+}
+
+// skip_iri skips an IRI written within angle brackets
+fn skip_iri(i: &str) -> Option<&str> {
+  let (_, rest) = i.strip_prefix('<')?.split_once('>')?;
+  Some(rest)
+}
+
+// skip_ignored skips whitespace and comments
+fn skip_ignored(mut i: &str) -> &str {
+  loop {
+    let trimmed = i.trim_start();
+    match trimmed.strip_prefix('#') {
+      Some(comment) => i = comment.split_once('\n').map(|x| x.1).unwrap_or_default(),
+      None => return trimmed,
+    }
+  }
+}
+
+fn into_io_error(err: reqwest::Error) -> std::io::Error {
+  let kind = match err.is_timeout() {
+    true => std::io::ErrorKind::TimedOut,
+    false => std::io::ErrorKind::Other,
+  };
+  std::io::Error::new(kind, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  const RESULT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+cco:Agent a owl:Class .
+"#;
+
+  // serve answers a single request with the given status and body
+  async fn serve(status: &'static str, body: &'static str) -> std::io::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}/sparql", listener.local_addr()?);
+    tokio::spawn(async move {
+      if let Ok((mut socket, _)) = listener.accept().await {
+        let mut buffer = vec![0; 4096];
+        let _ = socket.read(&mut buffer).await;
+        let response = format!(
+          "HTTP/1.1 {}\r\ncontent-type: text/turtle\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+          status,
+          body.len(),
+          body
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+      }
+    });
+    Ok(endpoint)
+  }
+
+  #[test]
+  fn should_detect_graph_queries() {
+    assert!(is_graph_query("CONSTRUCT { ?s ?p ?o } WHERE { ?s ?p ?o }"));
+    assert!(is_graph_query(
+      "# agents\nPREFIX cco: <http://www.ontologyrepository.com/CommonCoreOntologies/>\nBASE <http://example.org/#>\ndescribe cco:Agent"
+    ));
+    assert!(!is_graph_query("SELECT * WHERE { ?s ?p ?o }"));
+    assert!(!is_graph_query("ASK { ?s ?p ?o }"));
+    assert!(!is_graph_query(""));
+    assert!(is_graph_query(
+      "PREFIX ex:<http://example.org/#a>\nprefix owl: <http://www.w3.org/2002/07/owl#> # owl\nBASE<http://example.org/>CONSTRUCT{ ?s ?p ?o } WHERE { ?s ?p ?o }"
+    ));
+    assert!(!is_graph_query(
+      "PREFIX ex:<http://example.org/> SELECT * WHERE { ?s ex:construct ?o }"
+    ));
+  }
+
+  #[tokio::test]
+  async fn should_load_construct_results() -> std::io::Result<()> {
+    let endpoint = serve("200 OK", RESULT).await?;
+    let client = SparqlClient::new(&endpoint)?;
+    let document = client
+      .describe("http://www.ontologyrepository.com/CommonCoreOntologies/Agent")
+      .await?;
+    assert_eq!(document.triples().len(), 1);
+    let provenance = document.provenance.unwrap();
+    assert_eq!(provenance.source, endpoint);
+    assert_eq!(provenance.size, RESULT.len() as u64);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn should_report_endpoint_errors() -> std::io::Result<()> {
+    let endpoint = serve("400 Bad Request", "malformed query").await?;
+    let client = SparqlClient::new(&endpoint)?;
+    let err = client
+      .construct("CONSTRUCT WHERE { ?s ?p ?o ")
+      .await
+      .unwrap_err();
+    assert!(err.to_string().contains("400"));
+    let err = client
+      .construct("SELECT * WHERE { ?s ?p ?o }")
+      .await
+      .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    Ok(())
+  }
+}
@@ -0,0 +1,184 @@
+//! Version policy module settles the conflicts between versions of an imported ontology
+use crate::declarations::turtle::TurtleDocument;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// VersionPolicy tells which version of an ontology is kept when an import closure holds several
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VersionPolicy {
+  // keep the version with the greatest `owl:versionInfo`, or version IRI when a version has no
+  // `owl:versionInfo`. e.g -> 2.1 is kept over 2.0.3
+  PreferLatest,
+
+  // keep the version loaded first, i.e the version nearest to the ontology being loaded
+  #[default]
+  PreferLocal,
+
+  // fail the load
+  Error,
+}
+
+/// OntologyVersion is a version of an ontology, as found in a loaded document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OntologyVersion {
+  // key of the document in the manager
+  pub key: String,
+
+  // e.g -> http://www.ontologyrepository.com/CommonCoreOntologies/Mid/2021-03-01/AgentOntology
+  pub version_iri: Option<String>,
+
+  // e.g -> Version 1.3
+  pub version_info: Option<String>,
+}
+
+impl OntologyVersion {
+  pub fn new(
+    key: &str,
+    version_iri: Option<String>,
+    version_info: Option<String>,
+  ) -> OntologyVersion {
+    Self {
+      key: String::from(key),
+      version_iri,
+      version_info,
+    }
+  }
+
+  /// of returns the version of the ontology of a document
+  pub fn of(key: &str, document: &TurtleDocument) -> OntologyVersion {
+    Self::new(
+      key,
+      document.ontology_id().version_iri,
+      document.version_info(),
+    )
+  }
+
+  /// is_later_than determines whether the version is more recent than another. Versions are
+  /// compared by `owl:versionInfo` when both have one, otherwise by version IRI
+  pub fn is_later_than(&self, other: &OntologyVersion) -> bool {
+    let ordering = match (&self.version_info, &other.version_info) {
+      (Some(a), Some(b)) => compare_versions(a, b),
+      _ => compare_versions(
+        self.version_iri.as_deref().unwrap_or_default(),
+        other.version_iri.as_deref().unwrap_or_default(),
+      ),
+    };
+    ordering == Ordering::Greater
+  }
+}
+
+/// VersionConflict records the versions of an ontology found in an import closure and the one kept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionConflict {
+  pub ontology_iri: String,
+
+  // versions in the order they were loaded
+  pub versions: Vec<OntologyVersion>,
+
+  pub chosen: OntologyVersion,
+}
+
+impl VersionConflict {
+  pub fn new(
+    ontology_iri: &str,
+    versions: Vec<OntologyVersion>,
+    chosen: OntologyVersion,
+  ) -> VersionConflict {
+    Self {
+      ontology_iri: String::from(ontology_iri),
+      versions,
+      chosen,
+    }
+  }
+}
+
+/// LoadReport describes the outcome of loading an ontology and its import closure
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LoadReport {
+  // key of the ontology loaded
+  pub key: String,
+
+  // keys of the documents loaded, in the order they were loaded
+  pub loaded: Vec<String>,
+
+  pub conflicts: Vec<VersionConflict>,
+}
+
+impl LoadReport {
+  pub fn new(key: &str, loaded: Vec<String>, conflicts: Vec<VersionConflict>) -> LoadReport {
+    Self {
+      key: String::from(key),
+      loaded,
+      conflicts,
+    }
+  }
+}
+
+/// compare_versions compares two version strings segment by segment, numbers by value and
+/// other segments alphabetically.
+/// example
+/// `compare_versions("1.10", "1.9")` is `Ordering::Greater`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+  let (a, b) = (segments(a), segments(b));
+  for (x, y) in a.iter().zip(b.iter()) {
+    let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+      (Ok(x), Ok(y)) => x.cmp(&y),
+      _ => x.cmp(y),
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+  a.len().cmp(&b.len())
+}
+
+// segments splits a version into its runs of digits and its runs of letters
+fn segments(version: &str) -> Vec<&str> {
+  let mut segments = vec![];
+  let mut start = None;
+  let mut digits = false;
+  for (idx, c) in version.char_indices() {
+    let is_part = c.is_alphanumeric();
+    match start {
+      Some(s) if !is_part || c.is_ascii_digit() != digits => {
+        segments.push(&version[s..idx]);
+        start = if is_part { Some(idx) } else { None };
+      }
+      None if is_part => start = Some(idx),
+      _ => {}
+    }
+    digits = c.is_ascii_digit();
+  }
+  if let Some(s) = start {
+    segments.push(&version[s..]);
+  }
+  segments
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_compare_versions() {
+    assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+    assert_eq!(
+      compare_versions("2021-03-01", "2020-12-31"),
+      Ordering::Greater
+    );
+    assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+    assert_eq!(
+      compare_versions("Version 1.3", "version 1.3"),
+      Ordering::Less
+    );
+    assert_eq!(compare_versions("2.0rc1", "2.0rc2"), Ordering::Less);
+    assert_eq!(compare_versions("v2", "v2"), Ordering::Equal);
+
+    let version = |x: &str| OntologyVersion::new("", None, Some(String::from(x)));
+    assert!(version("1.3").is_later_than(&version("1.2.9")));
+    let iri = |x: &str| OntologyVersion::new("", Some(String::from(x)), None);
+    assert!(
+      iri("http://example.org/2021-03-01/a").is_later_than(&iri("http://example.org/2020-08-01/a"))
+    );
+  }
+}
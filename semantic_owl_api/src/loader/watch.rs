@@ -0,0 +1,379 @@
+//! Watch module keeps a turtle document up to date with the file it was loaded from
+use crate::declarations::turtle::{TurtleBodyItem, TurtleDocument, TurtleHeaderItem};
+use crate::loader::load::{load_block, not_a_turtle_document, statement_span_at};
+use crate::loader::parsers::ttl_statement_parser::skip_ws;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// StatementBlock is a directive or a statement of a document along with its location
+/// and the items it was loaded into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementBlock {
+  // byte offset of the first character of the block in the file
+  pub start: usize,
+
+  // byte offset following the last character of the block in the file
+  pub end: usize,
+
+  // source text of the block
+  pub raw: String,
+
+  // the header of a directive. Empty for statements
+  pub headers: VecDeque<TurtleHeaderItem>,
+
+  // the body items of a statement. Nested blank nodes and collections are separate items
+  pub body: VecDeque<TurtleBodyItem>,
+}
+
+impl StatementBlock {
+  pub fn new(
+    start: usize,
+    end: usize,
+    raw: String,
+    headers: VecDeque<TurtleHeaderItem>,
+    body: VecDeque<TurtleBodyItem>,
+  ) -> StatementBlock {
+    Self {
+      start,
+      end,
+      raw,
+      headers,
+      body,
+    }
+  }
+}
+
+/// DocumentChange is emitted when the watched file changes.
+/// A modified statement is reported as the removal of its former block followed by the addition of the new one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DocumentChange {
+  Added(StatementBlock),
+  Removed(StatementBlock),
+}
+
+/// DocumentWatcher watches a turtle file and re-parses the statements which changed
+#[derive(Debug)]
+pub struct DocumentWatcher {
+  path: String,
+
+  // modification time and length of the file when it was last read
+  modified: Option<SystemTime>,
+  len: u64,
+
+  // content of the file when it was last read, and its blocks
+  content: String,
+  blocks: Vec<StatementBlock>,
+
+  // number of blank nodes generated so far. Keeps generated labels unique across re-parses
+  blank_node_count: usize,
+}
+
+impl DocumentWatcher {
+  /// new loads the file at `path` and starts watching it
+  pub fn new(path: &str) -> std::io::Result<DocumentWatcher> {
+    let mut watcher = Self {
+      path: String::from(path),
+      modified: None,
+      len: 0,
+      content: String::new(),
+      blocks: vec![],
+      blank_node_count: 0,
+    };
+    watcher.poll()?;
+    Ok(watcher)
+  }
+
+  /// blocks returns the statement blocks of the document in their order in the file
+  pub fn blocks(&self) -> &[StatementBlock] {
+    &self.blocks
+  }
+
+  /// document returns the document as of the last read of the file
+  pub fn document(&self) -> TurtleDocument {
+    let mut document = TurtleDocument::new();
+    for b in self.blocks.iter() {
+      document.headers.extend(b.headers.iter().cloned());
+      document.body.extend(b.body.iter().cloned());
+    }
+    document
+  }
+
+  /// poll reads the file again if its modification time or length changed since it was last read
+  /// and returns the changes of the document. No change is returned for an untouched file
+  pub fn poll(&mut self) -> std::io::Result<Vec<DocumentChange>> {
+    let metadata = std::fs::metadata(&self.path)?;
+    let modified = metadata.modified().ok();
+    if modified.is_some() && modified == self.modified && metadata.len() == self.len {
+      return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&self.path)?;
+    let changes = self.update(&content)?;
+    self.modified = modified;
+    self.len = metadata.len();
+    Ok(changes)
+  }
+
+  /// update replaces the content of the document. Only the text which changed, i.e what lies
+  /// between the start and the end the former content and the new one share, is scanned again:
+  /// blocks before it are kept as they are and blocks after it are kept with their offsets updated. Blocks within it whose
+  /// source text is unchanged are kept without being parsed again. The document is left untouched
+  /// when the new content is not valid turtle
+  pub fn update(&mut self, content: &str) -> std::io::Result<Vec<DocumentChange>> {
+    let (prefix, suffix) = common_affixes(&self.content, content);
+    let old_end = self.content.len() - suffix;
+    let new_end = content.len() - suffix;
+
+    // blocks followed by an unchanged character are not affected by the change
+    let before = self.blocks.iter().take_while(|b| b.end < prefix).count();
+    // blocks starting after the change are kept once the scan reaches the start of one of them
+    let after = before + self.blocks[before..].partition_point(|b| b.start < old_end);
+
+    // blocks within the change are found by their source text
+    let mut previous: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (idx, b) in self.blocks.iter().enumerate().take(after).skip(before) {
+      previous.entry(b.raw.as_str()).or_default().push_back(idx);
+    }
+
+    let mut blank_node_count = self.blank_node_count;
+    let mut kept: Vec<bool> = (0..self.blocks.len()).map(|idx| idx < before).collect();
+    let mut blocks: Vec<StatementBlock> = self.blocks[..before].to_vec();
+    let mut added: Vec<DocumentChange> = vec![];
+    let mut pos = blocks.last().map(|b| b.end).unwrap_or(0);
+    loop {
+      let rest = skip_ws(&content[pos..]);
+      if rest.is_empty() {
+        break;
+      }
+      let start = content.len() - rest.len();
+      // past the change, the scan goes on as it did over the former content
+      let resumed = match start >= new_end {
+        true => {
+          let old_start = start - new_end + old_end;
+          self.blocks[after..]
+            .binary_search_by_key(&old_start, |b| b.start)
+            .ok()
+        }
+        false => None,
+      };
+      if let Some(idx) = resumed {
+        for (idx, b) in self.blocks.iter().enumerate().skip(after + idx) {
+          kept[idx] = true;
+          let mut block = b.clone();
+          block.start = b.start - old_end + new_end;
+          block.end = b.end - old_end + new_end;
+          blocks.push(block);
+        }
+        break;
+      }
+
+      let span = statement_span_at(content, start).ok_or_else(not_a_turtle_document)?;
+      pos = span.end;
+      let raw = &content[span.clone()];
+      match previous.get_mut(raw).and_then(|x| x.pop_front()) {
+        Some(idx) => {
+          kept[idx] = true;
+          let mut block = self.blocks[idx].clone();
+          block.start = span.start;
+          block.end = span.end;
+          blocks.push(block);
+        }
+        None => {
+          let document = load_block(raw, &mut blank_node_count)?;
+          let block = StatementBlock::new(
+            span.start,
+            span.end,
+            String::from(raw),
+            document.headers,
+            document.body,
+          );
+          added.push(DocumentChange::Added(block.clone()));
+          blocks.push(block);
+        }
+      }
+    }
+
+    let mut changes: Vec<DocumentChange> = self
+      .blocks
+      .iter()
+      .zip(kept.iter())
+      .filter(|(_, kept)| !**kept)
+      .map(|(b, _)| DocumentChange::Removed(b.clone()))
+      .collect();
+    changes.append(&mut added);
+    self.content = String::from(content);
+    self.blocks = blocks;
+    self.blank_node_count = blank_node_count;
+    Ok(changes)
+  }
+
+  /// watch polls the file every `interval` and calls `on_change` with the changes and the updated document.
+  /// Watching stops when `on_change` returns `false` or when the file can not be read.
+  /// A file which is not valid turtle, e.g while it is being edited, is skipped until the next change
+  pub fn watch<F>(&mut self, interval: Duration, mut on_change: F) -> std::io::Result<()>
+  where
+    F: FnMut(&[DocumentChange], &TurtleDocument) -> bool,
+  {
+    loop {
+      std::thread::sleep(interval);
+      let changes = match self.poll() {
+        Ok(x) => x,
+        Err(err) if err.kind() == std::io::ErrorKind::InvalidInput => continue,
+        Err(err) => return Err(err),
+      };
+      if !changes.is_empty() && !on_change(&changes, &self.document()) {
+        return Ok(());
+      }
+    }
+  }
+}
+
+// common_affixes returns the length in bytes of the text two contents start with and of the text
+// they end with, the latter not overlapping the former
+fn common_affixes(old: &str, new: &str) -> (usize, usize) {
+  let mut prefix = old
+    .bytes()
+    .zip(new.bytes())
+    .take_while(|(a, b)| a == b)
+    .count();
+  while !new.is_char_boundary(prefix) {
+    prefix -= 1;
+  }
+  let mut suffix = old
+    .bytes()
+    .rev()
+    .zip(new.bytes().rev())
+    .take(old.len().min(new.len()) - prefix)
+    .take_while(|(a, b)| a == b)
+    .count();
+  while !new.is_char_boundary(new.len() - suffix) {
+    suffix -= 1;
+  }
+  (prefix, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class .
+cco:Person a owl:Class ;
+           rdfs:subClassOf cco:Agent , [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:someValuesFrom cco:Agent ] .
+"#;
+
+  fn watcher(content: &str) -> std::io::Result<DocumentWatcher> {
+    let mut watcher = DocumentWatcher {
+      path: String::new(),
+      modified: None,
+      len: 0,
+      content: String::new(),
+      blocks: vec![],
+      blank_node_count: 0,
+    };
+    watcher.update(content)?;
+    Ok(watcher)
+  }
+
+  #[test]
+  fn should_split_document_into_blocks() -> std::io::Result<()> {
+    let watcher = watcher(DOCUMENT)?;
+    assert_eq!(watcher.blocks().len(), 5);
+    let first = &watcher.blocks()[3];
+    assert_eq!(&DOCUMENT[first.start..first.end], "cco:Agent a owl:Class .");
+    assert_eq!(
+      watcher.document().triples(),
+      load_turtle_document_from_str(DOCUMENT)?.triples()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_reparse_changed_blocks_only() -> std::io::Result<()> {
+    let mut watcher = watcher(DOCUMENT)?;
+    let person = watcher.blocks()[4].clone();
+    let changed = DOCUMENT.replace(
+      "cco:Agent a owl:Class .",
+      "cco:Agent a owl:Class ; rdfs:label \"Agent\" .",
+    );
+    let changes = watcher.update(&changed)?;
+    assert_eq!(changes.len(), 2);
+    assert!(
+      matches!(&changes[0], DocumentChange::Removed(b) if b.raw == "cco:Agent a owl:Class .")
+    );
+    assert!(matches!(&changes[1], DocumentChange::Added(b) if b.body.len() == 1));
+
+    // the untouched statement keeps its parsed items and moves with the edit
+    let moved = &watcher.blocks()[4];
+    assert_eq!(moved.body, person.body);
+    assert_eq!(moved.start, person.start + changed.len() - DOCUMENT.len());
+    assert_eq!(&changed[moved.start..moved.end], person.raw);
+
+    // no change, no event
+    assert_eq!(watcher.update(&changed)?.len(), 0);
+    Ok(())
+  }
+
+  #[test]
+  fn should_scan_the_changed_text_only() -> std::io::Result<()> {
+    let mut watcher = watcher(DOCUMENT)?;
+    let count = watcher.blank_node_count;
+
+    // commenting out a statement removes it, the statements after it are kept
+    let changed = DOCUMENT.replace("cco:Agent a owl:Class .", "# cco:Agent a owl:Class .");
+    let changes = watcher.update(&changed)?;
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(&changes[0], DocumentChange::Removed(_)));
+    assert_eq!(watcher.blocks().len(), 4);
+    assert_eq!(watcher.blank_node_count, count);
+    let person = &watcher.blocks()[3];
+    assert_eq!(&changed[person.start..person.end], person.raw);
+
+    // the statements found are the ones of a full scan
+    for content in [
+      DOCUMENT,
+      "",
+      &changed,
+      DOCUMENT,
+      "cco:Agent a owl:Class .\n",
+    ] {
+      let document = watcher.update(content).map(|_| watcher.document())?;
+      let fresh = self::watcher(content)?;
+      let spans = |w: &DocumentWatcher| -> Vec<(usize, usize)> {
+        w.blocks().iter().map(|b| (b.start, b.end)).collect()
+      };
+      assert_eq!(spans(&watcher), spans(&fresh));
+      assert_eq!(document.triples().len(), fresh.document().triples().len());
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn should_keep_document_on_invalid_content() -> std::io::Result<()> {
+    let mut watcher = watcher(DOCUMENT)?;
+    let document = watcher.document();
+    assert!(watcher.update("cco:Agent a ").is_err());
+    assert_eq!(watcher.document(), document);
+    Ok(())
+  }
+
+  #[test]
+  fn should_poll_watched_file() -> std::io::Result<()> {
+    let path = std::env::temp_dir().join(format!("semantic_owl_watch_{}.ttl", std::process::id()));
+    let path = path.to_str().unwrap();
+    std::fs::write(path, DOCUMENT)?;
+    let mut watcher = DocumentWatcher::new(path)?;
+    assert_eq!(watcher.blocks().len(), 5);
+    assert_eq!(watcher.poll()?.len(), 0);
+    std::fs::write(path, format!("{}cco:Thing a owl:Class .\n", DOCUMENT))?;
+    let changes = watcher.poll()?;
+    std::fs::remove_file(path)?;
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(&changes[0], DocumentChange::Added(_)));
+    Ok(())
+  }
+}
@@ -0,0 +1,482 @@
+//! Service module answers the requests of an editor about a turtle document
+use crate::analysis::reasoner::PropertyValues;
+use crate::analysis::validation::UNUSED_PREFIX;
+use crate::declarations::language_tag::is_valid_language_tag;
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::std_prefix_names::StdPrefixes;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::load::{load_block, scan_statement_spans};
+use crate::loader::parsers::ttl_statement_parser::{split_literal, term_spans};
+use crate::loader::watch::StatementBlock;
+use crate::vocab::{owl, rdf, rdfs};
+use lsp_types::{
+  Diagnostic, DiagnosticSeverity, DocumentSymbol, Hover, HoverContents, Location, MarkupContent,
+  MarkupKind, Position, Range, SymbolKind, Url,
+};
+use std::collections::BTreeMap;
+
+/// name of the service reported as the source of diagnostics
+pub const DIAGNOSTIC_SOURCE: &str = "semantic_owl";
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+// entity types and the kind of symbol they are shown as
+const SYMBOL_KINDS: [(&str, SymbolKind); 8] = [
+  (owl::ONTOLOGY.as_str(), SymbolKind::MODULE),
+  (owl::CLASS.as_str(), SymbolKind::CLASS),
+  (rdfs::CLASS.as_str(), SymbolKind::CLASS),
+  (owl::OBJECT_PROPERTY.as_str(), SymbolKind::PROPERTY),
+  (owl::DATATYPE_PROPERTY.as_str(), SymbolKind::PROPERTY),
+  (owl::ANNOTATION_PROPERTY.as_str(), SymbolKind::PROPERTY),
+  (rdf::PROPERTY.as_str(), SymbolKind::PROPERTY),
+  (owl::NAMED_INDIVIDUAL.as_str(), SymbolKind::OBJECT),
+];
+
+// annotation properties shown as the definition of an entity on hover
+const DEFINITION_PROPERTIES: [&str; 4] = [
+  "http://www.w3.org/2004/02/skos/core#definition",
+  "http://purl.obolibrary.org/obo/IAO_0000115",
+  "http://www.ontologyrepository.com/CommonCoreOntologies/definition",
+  rdfs::COMMENT.as_str(),
+];
+
+/// TurtleLanguageService holds the state of an open turtle document. Create a new service
+/// whenever the text of the document changes.
+/// Positions are expressed in UTF-16 code units as required by the protocol
+#[derive(Debug)]
+pub struct TurtleLanguageService {
+  uri: Url,
+  text: String,
+
+  // byte offset of the first character of each line
+  line_starts: Vec<usize>,
+
+  // the directives and statements of the document which could be loaded
+  blocks: Vec<StatementBlock>,
+
+  // the document made of all loaded blocks
+  document: TurtleDocument,
+  prefix_map: PrefixMap,
+
+  // labels and definitions of the entities of the document
+  values: PropertyValues,
+
+  // problems found while loading the document
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl TurtleLanguageService {
+  /// new loads the text of the document identified by `uri`. A document which is not valid
+  /// turtle is loaded up to the first statement which can not be parsed
+  pub fn new(uri: Url, text: &str) -> TurtleLanguageService {
+    let mut line_starts = vec![0];
+    line_starts.extend(text.match_indices('\n').map(|(idx, _)| idx + 1));
+    let mut service = Self {
+      uri,
+      text: String::from(text),
+      line_starts,
+      blocks: vec![],
+      document: TurtleDocument::new(),
+      prefix_map: PrefixMap::new(),
+      values: PropertyValues::default(),
+      diagnostics: vec![],
+    };
+
+    let (spans, error) = scan_statement_spans(text);
+    let mut blank_node_count = 0;
+    for span in spans {
+      let raw = &text[span.clone()];
+      match load_block(raw, &mut blank_node_count) {
+        Ok(d) => {
+          service.document.headers.extend(d.headers.iter().cloned());
+          service.document.body.extend(d.body.iter().cloned());
+          let block =
+            StatementBlock::new(span.start, span.end, String::from(raw), d.headers, d.body);
+          service.blocks.push(block);
+        }
+        Err(_) => {
+          let d = service.diagnostic(
+            span,
+            DiagnosticSeverity::ERROR,
+            "directive can not be parsed",
+          );
+          service.diagnostics.push(d);
+        }
+      }
+    }
+    if let Some(offset) = error {
+      let end = text[offset..]
+        .find('\n')
+        .map(|x| offset + x)
+        .unwrap_or_else(|| text.len());
+      let d = service.diagnostic(
+        offset..end,
+        DiagnosticSeverity::ERROR,
+        "statement can not be parsed",
+      );
+      service.diagnostics.push(d);
+    }
+    service.prefix_map = service.document.prefix_map();
+    service.values = PropertyValues::new(&service.document);
+    service.check_terms();
+    // the prefixes of a statement which can not be parsed are not known to be used
+    if error.is_none() {
+      service.check_prefixes();
+    }
+    service
+  }
+
+  /// document returns the loaded document
+  pub fn document(&self) -> &TurtleDocument {
+    &self.document
+  }
+
+  /// diagnostics returns the syntax errors, undeclared and unused prefixes and malformed language tags of the document
+  pub fn diagnostics(&self) -> Vec<Diagnostic> {
+    self.diagnostics.clone()
+  }
+
+  /// document_symbols returns a symbol for each statement about a declared class, property,
+  /// individual or ontology, in the order of the document. The detail of a symbol is its label
+  pub fn document_symbols(&self) -> Vec<DocumentSymbol> {
+    let mut kinds: BTreeMap<String, SymbolKind> = BTreeMap::new();
+    for t in self.document.triples().iter() {
+      if t.predicate.as_iri() != Some(RDF_TYPE) {
+        continue;
+      }
+      let kind = SYMBOL_KINDS
+        .iter()
+        .find(|(iri, _)| t.object.as_iri() == Some(*iri));
+      if let (Some(s), Some((_, kind))) = (t.subject.as_iri(), kind) {
+        kinds.entry(String::from(s)).or_insert(*kind);
+      }
+    }
+
+    let mut symbols = vec![];
+    for b in self.blocks.iter() {
+      let subject = match b.body.front().and_then(|x| x.subject.as_deref()) {
+        Some(x) if b.raw.starts_with(x) => x,
+        _ => continue,
+      };
+      let iri = match self.prefix_map.resolve_term(subject) {
+        Some(TurtleTerm::Iri(x)) => x,
+        _ => continue,
+      };
+      let kind = match kinds.get(&iri) {
+        Some(x) => *x,
+        None => continue,
+      };
+      let label = self.values.labels_of(&iri);
+      let detail = match label.best_match(&["en", "*"]) {
+        Some(TurtleTerm::Literal { value, .. }) => value.clone(),
+        _ => iri.clone(),
+      };
+      #[allow(deprecated)]
+      symbols.push(DocumentSymbol {
+        name: String::from(subject),
+        detail: Some(detail),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: self.range(b.start..b.end),
+        selection_range: self.range(b.start..b.start + subject.len()),
+        children: None,
+      });
+    }
+    symbols
+  }
+
+  /// hover returns the IRI, labels and definitions of the entity at a position
+  pub fn hover(&self, position: Position) -> Option<Hover> {
+    let token = self.token_at(self.offset_at(position))?;
+    let iri = match self.prefix_map.resolve_term(&self.text[token.clone()])? {
+      TurtleTerm::Iri(x) => x,
+      _ => return None,
+    };
+    let mut lines = vec![];
+    let labels = self.values.labels_of(&iri);
+    for l in labels.values() {
+      lines.push(format!("**{}**", l));
+    }
+    lines.push(format!("`<{}>`", iri));
+    for p in DEFINITION_PROPERTIES.iter() {
+      for d in self.values.annotations_of(&iri, p).values() {
+        lines.push(String::from(d));
+      }
+    }
+    Some(Hover {
+      contents: HoverContents::Markup(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: lines.join("\n\n"),
+      }),
+      range: Some(self.range(token)),
+    })
+  }
+
+  /// goto_definition returns the location of the statement describing the entity at a position.
+  /// For a prefixed name whose entity is not described by the document, or when the position is
+  /// on the prefix of the name, the location of the prefix declaration is returned
+  pub fn goto_definition(&self, position: Position) -> Option<Location> {
+    let offset = self.offset_at(position);
+    let token = self.token_at(offset)?;
+    let raw = &self.text[token.clone()];
+    let prefix = match raw.starts_with('<') || raw.starts_with("_:") {
+      true => None,
+      false => raw.find(':').map(|idx| (&raw[..idx], token.start + idx)),
+    };
+    let on_prefix = matches!(prefix, Some((_, colon)) if offset <= colon);
+
+    if !on_prefix {
+      if let Some(term @ TurtleTerm::Iri(_)) = self.prefix_map.resolve_term(raw) {
+        let described =
+          self.blocks.iter().find(
+            |b| match b.body.front().and_then(|x| x.subject.as_deref()) {
+              Some(s) if b.raw.starts_with(s) => {
+                self.prefix_map.resolve_term(s) == Some(term.clone())
+              }
+              _ => false,
+            },
+          );
+        if let Some(b) = described {
+          let subject = b.body.front()?.subject.as_deref()?;
+          return Some(self.location(b.start..b.start + subject.len()));
+        }
+      }
+    }
+
+    let (prefix, _) = prefix?;
+    let declaration = self.blocks.iter().find(|b| {
+      b.headers
+        .iter()
+        .any(|h| h.prefix_namespace.as_deref() == Some(prefix))
+    })?;
+    Some(self.location(declaration.start..declaration.end))
+  }
+
+  /// position_at returns the position of a byte offset of the text
+  pub fn position_at(&self, offset: usize) -> Position {
+    let line = self.line_starts.partition_point(|x| *x <= offset) - 1;
+    let start = self.line_starts[line];
+    let character: usize = self.text[start..offset]
+      .chars()
+      .map(|c| c.len_utf16())
+      .sum();
+    Position::new(line as u32, character as u32)
+  }
+
+  /// offset_at returns the byte offset of a position of the text. Positions past the end of
+  /// a line are moved to its end
+  pub fn offset_at(&self, position: Position) -> usize {
+    let start = match self.line_starts.get(position.line as usize) {
+      Some(x) => *x,
+      None => return self.text.len(),
+    };
+    let mut units = 0;
+    for (idx, c) in self.text[start..].char_indices() {
+      if units >= position.character as usize || c == '\n' {
+        return start + idx;
+      }
+      units += c.len_utf16();
+    }
+    self.text.len()
+  }
+
+  // check_terms reports terms whose prefix is not declared and literals with a malformed language tag
+  fn check_terms(&mut self) {
+    let mut diagnostics = vec![];
+    for b in self.blocks.iter() {
+      for span in term_spans(&b.raw) {
+        let term = &b.raw[span.clone()];
+        let span = b.start + span.start..b.start + span.end;
+        if self.prefix_map.resolve_term(term).is_none() {
+          diagnostics.push(self.diagnostic(
+            span.clone(),
+            DiagnosticSeverity::ERROR,
+            &format!("`{}` uses a prefix which is not declared", term),
+          ));
+        }
+        if let Some((_, _, Some(language))) = split_literal(term) {
+          if !is_valid_language_tag(&language) {
+            diagnostics.push(self.diagnostic(
+              span,
+              DiagnosticSeverity::WARNING,
+              &format!("`{}` is not a well-formed BCP 47 language tag", language),
+            ));
+          }
+        }
+      }
+    }
+    self.diagnostics.append(&mut diagnostics);
+  }
+
+  // check_prefixes reports the prefix declarations which no statement uses
+  fn check_prefixes(&mut self) {
+    let mut diagnostics = vec![];
+    let unused = self
+      .document
+      .prefix_diagnostics(&StdPrefixes::default())
+      .into_iter()
+      .filter(|x| x.rule == UNUSED_PREFIX);
+    for d in unused {
+      let declaration = self.blocks.iter().find(|b| {
+        b.headers
+          .iter()
+          .any(|h| !h.is_base && h.prefix_namespace.as_deref().unwrap_or_default() == d.prefix)
+      });
+      if let Some(b) = declaration {
+        diagnostics.push(self.diagnostic(b.start..b.end, DiagnosticSeverity::WARNING, &d.message));
+      }
+    }
+    self.diagnostics.append(&mut diagnostics);
+  }
+
+  // token_at returns the byte range of the IRI, prefixed name, blank node or keyword at an offset
+  fn token_at(&self, offset: usize) -> Option<std::ops::Range<usize>> {
+    let is_delimiter =
+      |c: char| c.is_whitespace() || matches!(c, ';' | ',' | '(' | ')' | '[' | ']' | '"');
+    let start = self.text[..offset]
+      .char_indices()
+      .rev()
+      .find(|(_, c)| is_delimiter(*c))
+      .map(|(idx, c)| idx + c.len_utf8())
+      .unwrap_or(0);
+    let mut end = self.text[offset..]
+      .find(is_delimiter)
+      .map(|x| offset + x)
+      .unwrap_or_else(|| self.text.len());
+    while end > start && self.text[start..end].ends_with('.') {
+      end -= 1;
+    }
+    match start < end {
+      true => Some(start..end),
+      false => None,
+    }
+  }
+
+  fn range(&self, span: std::ops::Range<usize>) -> Range {
+    Range::new(self.position_at(span.start), self.position_at(span.end))
+  }
+
+  fn location(&self, span: std::ops::Range<usize>) -> Location {
+    Location::new(self.uri.clone(), self.range(span))
+  }
+
+  fn diagnostic(
+    &self,
+    span: std::ops::Range<usize>,
+    severity: DiagnosticSeverity,
+    message: &str,
+  ) -> Diagnostic {
+    Diagnostic {
+      range: self.range(span),
+      severity: Some(severity),
+      source: Some(String::from(DIAGNOSTIC_SOURCE)),
+      message: String::from(message),
+      ..Diagnostic::default()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ;
+          rdfs:label "Agent"@en ;
+          cco:definition "A Material Entity that is capable of performing actions"@en .
+cco:has_agent a owl:ObjectProperty ;
+              rdfs:range cco:Agent .
+cco:Person a owl:Class ;
+           rdfs:subClassOf cco:Agent ;
+           rdfs:label "Person"@en-gb-oed-oed ;
+           ex:unknown cco:Agent .
+"#;
+
+  fn service(text: &str) -> TurtleLanguageService {
+    let uri = Url::parse("file:///tmp/agents.ttl").unwrap();
+    TurtleLanguageService::new(uri, text)
+  }
+
+  #[test]
+  fn should_convert_offsets_and_positions() {
+    let s = service("a\n\u{1F600}b\n");
+    assert_eq!(s.position_at(2), Position::new(1, 0));
+    assert_eq!(s.position_at(6), Position::new(1, 2));
+    assert_eq!(s.offset_at(Position::new(1, 2)), 6);
+    assert_eq!(s.offset_at(Position::new(0, 9)), 1);
+  }
+
+  #[test]
+  fn should_report_diagnostics() {
+    let s = service(DOCUMENT);
+    let diagnostics = s.diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    assert!(diagnostics[1].message.contains("ex:unknown"));
+    assert_eq!(diagnostics[1].range.start, Position::new(12, 11));
+
+    let s = service("@prefix cco: <http://example.org/> .\ncco:Agent a .\n");
+    let diagnostics = s.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start, Position::new(1, 0));
+
+    let s = service("@prefix cco: <http://example.org/> .\n@prefix dc: <http://purl.org/dc/terms/> .\ncco:Agent a cco:Class .\n");
+    let diagnostics = s.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    assert_eq!(diagnostics[0].range.start, Position::new(1, 0));
+    assert!(diagnostics[0].message.contains("`dc:`"));
+
+    // the term is located where the parser found it, not where its text first appears
+    let s =
+      service("@prefix ex: <http://example.org/> .\nex:Agent ex:note \"foo:b\" ; ex:rel foo:b .\n");
+    let diagnostics = s.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start, Position::new(1, 34));
+  }
+
+  #[test]
+  fn should_list_document_symbols() {
+    let s = service(DOCUMENT);
+    let symbols = s.document_symbols();
+    let names: Vec<&str> = symbols.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, vec!["cco:Agent", "cco:has_agent", "cco:Person"]);
+    assert_eq!(symbols[0].kind, SymbolKind::CLASS);
+    assert_eq!(symbols[0].detail.as_deref(), Some("Agent"));
+    assert_eq!(symbols[1].kind, SymbolKind::PROPERTY);
+    assert_eq!(symbols[2].range.start, Position::new(9, 0));
+    assert_eq!(symbols[2].selection_range.end, Position::new(9, 10));
+  }
+
+  #[test]
+  fn should_hover_entities() {
+    let s = service(DOCUMENT);
+    let hover = s.hover(Position::new(8, 30)).unwrap();
+    match hover.contents {
+      HoverContents::Markup(m) => {
+        assert!(m.value.contains("**Agent**"));
+        assert!(m.value.contains("capable of performing actions"));
+      }
+      _ => panic!("did not expect"),
+    }
+    assert!(s.hover(Position::new(5, 22)).is_none());
+  }
+
+  #[test]
+  fn should_go_to_definition() {
+    let s = service(DOCUMENT);
+    // the entity is described by the document
+    let location = s.goto_definition(Position::new(8, 30)).unwrap();
+    assert_eq!(location.range.start, Position::new(4, 0));
+    // the prefix of the name
+    let location = s.goto_definition(Position::new(8, 26)).unwrap();
+    assert_eq!(location.range.start, Position::new(0, 0));
+    // an entity which is not described falls back to its prefix
+    let location = s.goto_definition(Position::new(4, 16)).unwrap();
+    assert_eq!(location.range.start, Position::new(1, 0));
+  }
+}
@@ -0,0 +1,299 @@
+//! Service module publishes the ontologies of an `OntologyManager` over HTTP
+use crate::declarations::curie::CurieResolver;
+use crate::declarations::turtle::{TurtleDocument, TurtleTriple};
+use crate::loader::manager::OntologyManager;
+use crate::writer::format::{format_turtle, Style};
+use crate::writer::jsonld::format_json_ld;
+use crate::writer::rdfxml::format_rdf_xml;
+use crate::writer::stream::{TurtleStreamWriter, DEFAULT_WINDOW};
+use hyper::header::{HeaderValue, ACCEPT, CONTENT_TYPE, VARY};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+pub const TURTLE: &str = "text/turtle";
+pub const JSON_LD: &str = "application/ld+json";
+pub const RDF_XML: &str = "application/rdf+xml";
+
+// path under which terms are described
+const TERMS: &str = "/terms/";
+
+/// OntologyService answers HTTP requests with the documents of an ontology manager
+pub struct OntologyService {
+  pub manager: OntologyManager,
+}
+
+impl OntologyService {
+  pub fn new(manager: OntologyManager) -> OntologyService {
+    Self { manager }
+  }
+
+  /// serve answers the requests received on `addr` until the server fails
+  /// example
+  /// `serve(([127, 0, 0, 1], 8080).into())`
+  pub async fn serve(self, addr: SocketAddr) -> hyper::Result<()> {
+    let service = Arc::new(self);
+    let make_service = make_service_fn(move |_| {
+      let service = service.clone();
+      async move {
+        Ok::<_, Infallible>(service_fn(move |request| {
+          let response = service.respond(&request);
+          async move { Ok::<_, Infallible>(response) }
+        }))
+      }
+    });
+    Server::bind(&addr).serve(make_service).await
+  }
+
+  /// respond answers a request. Only `GET` and `HEAD` requests are answered
+  pub fn respond(&self, request: &Request<Body>) -> Response<Body> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+      return status(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "only GET requests are served",
+      );
+    }
+    let accept = request.headers().get(ACCEPT).and_then(|x| x.to_str().ok());
+    let media_type = match negotiate(accept) {
+      Some(x) => x,
+      None => {
+        let message = format!(
+          "documents are served as {}, {} or {}",
+          TURTLE, JSON_LD, RDF_XML
+        );
+        return status(StatusCode::NOT_ACCEPTABLE, &message);
+      }
+    };
+    let path = request.uri().path();
+    let body = match path.strip_prefix(TERMS) {
+      Some(curie) => self.describe(&percent_decode(curie), media_type),
+      None => self.ontology(path, media_type),
+    };
+    let mut response = match body {
+      Some(Ok(body)) => Response::new(Body::from(body)),
+      Some(Err(err)) => {
+        let message = format!(
+          "the statements can not be written as {}: {}",
+          media_type, err
+        );
+        return status(StatusCode::NOT_ACCEPTABLE, &message);
+      }
+      None => {
+        return status(
+          StatusCode::NOT_FOUND,
+          "no ontology nor term is served at this path",
+        )
+      }
+    };
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(media_type));
+    headers.insert(VARY, HeaderValue::from_static("Accept"));
+    if request.method() == Method::HEAD {
+      *response.body_mut() = Body::empty();
+    }
+    response
+  }
+
+  // ontology writes the document of the ontology whose IRI or version IRI has the given path
+  fn ontology(&self, path: &str, media_type: &str) -> Option<std::io::Result<String>> {
+    let document = self.manager.documents.iter().find_map(|(iri, document)| {
+      let id = document.ontology_id();
+      let mut iris = std::iter::once(iri.as_str()).chain(id.version_iri.as_deref());
+      match iris.any(|x| path_of(x) == Some(path)) {
+        true => Some(document),
+        false => None,
+      }
+    })?;
+    Some(match media_type {
+      JSON_LD => Ok(format_json_ld(&document.triples())),
+      RDF_XML => format_rdf_xml(&document.triples(), &document.prefix_map()),
+      _ => Ok(format_turtle(document, &Style::default())),
+    })
+  }
+
+  // describe writes the description of a term given as a CURIE, using the prefixes of the first
+  // document saying something about the term
+  fn describe(&self, curie: &str, media_type: &str) -> Option<std::io::Result<String>> {
+    let (document, statements) = self.manager.documents.values().find_map(|document| {
+      let iri = CurieResolver::from_document(document).resolve(curie)?;
+      let statements = document.describe(&iri, 0);
+      match statements.is_empty() {
+        true => None,
+        false => Some((document, statements)),
+      }
+    })?;
+    Some(match media_type {
+      JSON_LD => Ok(format_json_ld(&statements)),
+      RDF_XML => format_rdf_xml(&statements, &document.prefix_map()),
+      _ => write_turtle(document, &statements),
+    })
+  }
+}
+
+// write_turtle writes statements with the prefixes of the document they come from
+fn write_turtle(document: &TurtleDocument, statements: &[TurtleTriple]) -> std::io::Result<String> {
+  let mut writer = TurtleStreamWriter::new(vec![], Style::compact(), DEFAULT_WINDOW);
+  for (prefix, namespace) in document.prefix_map().prefixes.iter() {
+    writer.prefix(prefix, namespace)?;
+  }
+  for t in statements.iter() {
+    writer.write_triple(t)?;
+  }
+  let out = writer.finish()?;
+  Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+fn status(code: StatusCode, message: &str) -> Response<Body> {
+  let mut response = Response::new(Body::from(format!("{}\n", message)));
+  *response.status_mut() = code;
+  response
+    .headers_mut()
+    .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+  response
+}
+
+/// negotiate returns the media type a response is written in given the `Accept` header of the
+/// request, or `None` if no media type accepted can be written. Turtle is preferred when the
+/// header is missing or accepts any type
+/// example
+/// `application/ld+json, text/turtle;q=0.5` returns Option of `application/ld+json`
+pub fn negotiate(accept: Option<&str>) -> Option<&'static str> {
+  let accept = match accept {
+    Some(x) if !x.trim().is_empty() => x,
+    _ => return Some(TURTLE),
+  };
+  let mut ranges: Vec<(&str, f32)> = accept
+    .split(',')
+    .map(|range| {
+      let mut parts = range.split(';').map(|x| x.trim());
+      let media_range = parts.next().unwrap_or_default();
+      let quality = parts
+        .filter_map(|x| x.strip_prefix("q="))
+        .find_map(|x| x.parse::<f32>().ok())
+        .unwrap_or(1.0);
+      (media_range, quality)
+    })
+    .filter(|(_, quality)| *quality > 0.0)
+    .collect();
+  // the order of the header settles equal qualities
+  ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+  ranges.into_iter().find_map(
+    |(media_range, _)| match media_range.to_ascii_lowercase().as_str() {
+      TURTLE | "application/x-turtle" | "text/*" | "*/*" => Some(TURTLE),
+      JSON_LD | "application/json" => Some(JSON_LD),
+      RDF_XML | "application/xml" => Some(RDF_XML),
+      _ => None,
+    },
+  )
+}
+
+// path_of returns the path of an absolute IRI, without its query nor fragment.
+// e.g -> /ontologies/agent
+fn path_of(iri: &str) -> Option<&str> {
+  let (_, rest) = iri.split_once("://")?;
+  let path = rest.find('/').map(|x| &rest[x..]).unwrap_or("/");
+  path.split(['?', '#']).next()
+}
+
+// percent_decode decodes the escaped characters of a path segment. e.g -> cco%3AAgent
+fn percent_decode(segment: &str) -> String {
+  let bytes = segment.as_bytes();
+  let mut out = Vec::with_capacity(bytes.len());
+  let mut idx = 0;
+  while idx < bytes.len() {
+    let escaped = segment
+      .get(idx + 1..idx + 3)
+      .filter(|_| bytes[idx] == b'%')
+      .and_then(|x| u8::from_str_radix(x, 16).ok());
+    match escaped {
+      Some(byte) => {
+        out.push(byte);
+        idx += 3;
+      }
+      None => {
+        out.push(bytes[idx]);
+        idx += 1;
+      }
+    }
+  }
+  String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+<http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology> a owl:Ontology .
+cco:Agent a owl:Class ; rdfs:label "Agent"@en .
+"#;
+
+  fn get(service: &OntologyService, path: &str, accept: Option<&str>) -> Response<Body> {
+    let mut request = Request::get(path);
+    if let Some(accept) = accept {
+      request = request.header(ACCEPT, accept);
+    }
+    service.respond(&request.body(Body::empty()).unwrap())
+  }
+
+  async fn body_of(response: Response<Body>) -> String {
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+  }
+
+  #[tokio::test]
+  async fn should_serve_ontologies_and_terms() -> std::io::Result<()> {
+    let mut manager = OntologyManager::new();
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let iri = document.ontology_id().ontology_iri.unwrap();
+    manager.documents.insert(iri, document);
+    let service = OntologyService::new(manager);
+
+    let response = get(&service, "/Mid/AgentOntology", None);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = get(
+      &service,
+      "/CommonCoreOntologies/Mid/AgentOntology",
+      Some("*/*"),
+    );
+    assert_eq!(response.headers()[CONTENT_TYPE], TURTLE);
+    assert!(body_of(response).await.contains("cco:Agent"));
+
+    let response = get(&service, "/terms/cco%3AAgent", Some("application/ld+json"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], JSON_LD);
+    let json: serde_json::Value = serde_json::from_str(&body_of(response).await)?;
+    assert_eq!(json.as_array().map(|x| x.len()), Some(1));
+    let response = get(&service, "/terms/cco:Agent", Some("text/turtle"));
+    assert_eq!(
+      body_of(response).await.lines().last(),
+      Some("cco:Agent a owl:Class ; rdfs:label \"Agent\"@en .")
+    );
+
+    let response = get(&service, "/terms/cco:Agent", Some(RDF_XML));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[CONTENT_TYPE], RDF_XML);
+    assert!(body_of(response)
+      .await
+      .contains("<rdfs:label xml:lang=\"en\">Agent</rdfs:label>"));
+    assert_eq!(
+      negotiate(Some(
+        "application/rdf+xml;q=0.5, application/json;q=0.8, text/turtle;q=0.9"
+      )),
+      Some(TURTLE)
+    );
+    assert_eq!(
+      negotiate(Some("application/rdf+xml, text/turtle;q=0.9")),
+      Some(RDF_XML)
+    );
+    let response = get(&service, "/terms/cco:Agent", Some("application/n-quads"));
+    assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    Ok(())
+  }
+}
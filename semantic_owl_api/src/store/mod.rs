@@ -0,0 +1,183 @@
+//! Store module defines the query interface shared by the triple stores
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::loader::handler::{parse_turtle_with_handler, TurtleHandler};
+
+#[cfg(feature = "store-sqlite")]
+pub mod sqlite;
+
+/// TripleStore is a set of triples which can be matched against triple patterns.
+/// Stores backed by a database return the errors of the database
+pub trait TripleStore {
+  /// insert adds a triple to the store. It returns `false` if the store already holds the triple
+  fn insert(&mut self, triple: TurtleTriple) -> std::io::Result<bool>;
+
+  /// len returns the number of triples of the store
+  fn len(&self) -> std::io::Result<usize>;
+
+  fn is_empty(&self) -> std::io::Result<bool> {
+    Ok(self.len()? == 0)
+  }
+
+  /// triples_matching returns the triples matching a pattern, where `None` matches any term,
+  /// in the order they were inserted
+  fn triples_matching(
+    &self,
+    subject: Option<&TurtleTerm>,
+    predicate: Option<&TurtleTerm>,
+    object: Option<&TurtleTerm>,
+  ) -> std::io::Result<Vec<TurtleTriple>>;
+
+  /// contains determines whether the store holds a triple
+  fn contains(
+    &self,
+    subject: &TurtleTerm,
+    predicate: &TurtleTerm,
+    object: &TurtleTerm,
+  ) -> std::io::Result<bool> {
+    Ok(
+      !self
+        .triples_matching(Some(subject), Some(predicate), Some(object))?
+        .is_empty(),
+    )
+  }
+
+  /// object returns an object of a subject and predicate, if any
+  fn object(&self, subject: &TurtleTerm, predicate: &str) -> std::io::Result<Option<TurtleTerm>> {
+    let predicate = TurtleTerm::Iri(String::from(predicate));
+    let triples = self.triples_matching(Some(subject), Some(&predicate), None)?;
+    Ok(triples.into_iter().next().map(|t| t.object))
+  }
+}
+
+impl TripleStore for TripleGraph {
+  fn insert(&mut self, triple: TurtleTriple) -> std::io::Result<bool> {
+    Ok(TripleGraph::insert(self, triple))
+  }
+
+  fn len(&self) -> std::io::Result<usize> {
+    Ok(TripleGraph::len(self))
+  }
+
+  fn triples_matching(
+    &self,
+    subject: Option<&TurtleTerm>,
+    predicate: Option<&TurtleTerm>,
+    object: Option<&TurtleTerm>,
+  ) -> std::io::Result<Vec<TurtleTriple>> {
+    Ok(
+      TripleGraph::triples_matching(self, subject, predicate, object)
+        .cloned()
+        .collect(),
+    )
+  }
+}
+
+/// load_into_store streams the triples of a turtle document into a store, without building the
+/// document. It returns the number of triples added
+pub fn load_into_store<S: TripleStore>(path: &str, store: &mut S) -> std::io::Result<usize> {
+  load_scoped(path, store, None)
+}
+
+/// load_into_store_with_scope streams the triples of a turtle document into a store, prefixing
+/// the labels of its blank nodes with `scope`. Loading two documents with distinct scopes keeps
+/// their blank nodes apart even when they share a label. It returns the number of triples added
+/// example
+/// `load_into_store_with_scope("cco.ttl", &mut store, "load1_")` stores `_:genid1` as `_:load1_genid1`
+pub fn load_into_store_with_scope<S: TripleStore>(
+  path: &str,
+  store: &mut S,
+  scope: &str,
+) -> std::io::Result<usize> {
+  load_scoped(path, store, Some(scope))
+}
+
+fn load_scoped<S: TripleStore>(
+  path: &str,
+  store: &mut S,
+  scope: Option<&str>,
+) -> std::io::Result<usize> {
+  let mut loader = StoreLoader {
+    store,
+    scope,
+    added: 0,
+    error: None,
+  };
+  parse_turtle_with_handler(path, &mut loader)?;
+  match loader.error {
+    Some(e) => Err(e),
+    None => Ok(loader.added),
+  }
+}
+
+// StoreLoader inserts the triples parsed into a store. Insertion stops at the first error of the store
+struct StoreLoader<'a, S: TripleStore> {
+  store: &'a mut S,
+
+  // prefix of the labels of the blank nodes stored, if any
+  scope: Option<&'a str>,
+
+  added: usize,
+  error: Option<std::io::Error>,
+}
+
+impl<'a, S: TripleStore> TurtleHandler for StoreLoader<'a, S> {
+  fn on_triple(&mut self, triple: &TurtleTriple) {
+    if self.error.is_some() {
+      return;
+    }
+    let triple = match self.scope {
+      Some(scope) => TurtleTriple::new(
+        scoped(&triple.subject, scope),
+        triple.predicate.clone(),
+        scoped(&triple.object, scope),
+      ),
+      None => triple.clone(),
+    };
+    match self.store.insert(triple) {
+      Ok(true) => self.added += 1,
+      Ok(false) => {}
+      Err(e) => self.error = Some(e),
+    }
+  }
+}
+
+// scoped prefixes the labels of the blank nodes of a term, including those of a quoted triple
+fn scoped(term: &TurtleTerm, scope: &str) -> TurtleTerm {
+  match term {
+    TurtleTerm::BlankNode(x) => TurtleTerm::BlankNode(format!("{}{}", scope, x)),
+    TurtleTerm::QuotedTriple(t) => TurtleTerm::QuotedTriple(Box::new(TurtleTriple::new(
+      scoped(&t.subject, scope),
+      t.predicate.clone(),
+      scoped(&t.object, scope),
+    ))),
+    t => t.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_load_document_into_graph() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/AgentOntology.ttl");
+    let path = path.to_str().unwrap();
+    let mut graph = TripleGraph::new();
+    let added = load_into_store(path, &mut graph)?;
+    let document = crate::loader::load::load_turtle_document(path)?;
+    assert_eq!(added, document.graph().len());
+    assert_eq!(TripleStore::len(&graph)?, added);
+
+    let agent = TurtleTerm::Iri(String::from(
+      "http://www.ontologyrepository.com/CommonCoreOntologies/Agent",
+    ));
+    let label = TripleStore::object(&graph, &agent, "http://www.w3.org/2000/01/rdf-schema#label")?;
+    assert!(label.is_some());
+    Ok(())
+  }
+}
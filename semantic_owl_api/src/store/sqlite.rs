@@ -0,0 +1,376 @@
+//! Sqlite module persists triples in a SQLite database
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::store::{load_into_store_with_scope, TripleStore};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use std::borrow::Cow;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS terms (
+  id INTEGER PRIMARY KEY,
+  kind INTEGER NOT NULL,
+  value TEXT NOT NULL,
+  datatype TEXT NOT NULL,
+  language TEXT NOT NULL,
+  UNIQUE (kind, value, datatype, language)
+);
+CREATE TABLE IF NOT EXISTS triples (
+  subject INTEGER NOT NULL REFERENCES terms (id),
+  predicate INTEGER NOT NULL REFERENCES terms (id),
+  object INTEGER NOT NULL REFERENCES terms (id),
+  UNIQUE (subject, predicate, object)
+);
+CREATE INDEX IF NOT EXISTS triples_by_predicate ON triples (predicate, object);
+CREATE INDEX IF NOT EXISTS triples_by_object ON triples (object);
+CREATE TABLE IF NOT EXISTS loads (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  path TEXT NOT NULL
+);
+";
+
+// selects the terms of the triples, four columns per term
+const SELECT_TRIPLES: &str = "SELECT
+  s.kind, s.value, s.datatype, s.language,
+  p.kind, p.value, p.datatype, p.language,
+  o.kind, o.value, o.datatype, o.language
+FROM triples t
+JOIN terms s ON s.id = t.subject
+JOIN terms p ON p.id = t.predicate
+JOIN terms o ON o.id = t.object";
+
+// kinds of the terms table
+const IRI: i64 = 0;
+const BLANK_NODE: i64 = 1;
+const LITERAL: i64 = 2;
+// quoted triples are stored in their N-Triples form
+const QUOTED_TRIPLE: i64 = 3;
+
+/// SqliteTripleStore is a triple store persisted in a SQLite database
+#[derive(Debug)]
+pub struct SqliteTripleStore {
+  connection: Connection,
+}
+
+impl SqliteTripleStore {
+  /// open opens the store held in a SQLite database file, creating it if needed
+  pub fn open(path: &str) -> std::io::Result<SqliteTripleStore> {
+    Self::new(Connection::open(path).map_err(into_io_error)?)
+  }
+
+  /// open_in_memory opens a store held in memory, which is dropped along with the store
+  pub fn open_in_memory() -> std::io::Result<SqliteTripleStore> {
+    Self::new(Connection::open_in_memory().map_err(into_io_error)?)
+  }
+
+  /// new creates a store in a SQLite database, creating its tables if needed
+  pub fn new(connection: Connection) -> std::io::Result<SqliteTripleStore> {
+    connection.execute_batch(SCHEMA).map_err(into_io_error)?;
+    Ok(Self { connection })
+  }
+
+  /// load streams the triples of a turtle document into the store within a single transaction.
+  /// Nothing is stored if the document can not be loaded. It returns the number of triples added.
+  /// Blank nodes are scoped to the load: their labels are prefixed with the id of the load, so
+  /// `_:genid1` of two documents, or of two loads of a document, are two blank nodes.
+  /// e.g -> `_:genid1` of the third load is stored as `_:load3_genid1`
+  pub fn load(&mut self, path: &str) -> std::io::Result<usize> {
+    self
+      .connection
+      .execute_batch("BEGIN")
+      .map_err(into_io_error)?;
+    let loaded = self
+      .connection
+      .execute("INSERT INTO loads (path) VALUES (?1)", params![path])
+      .map_err(into_io_error)
+      .and_then(|_| {
+        let scope = format!("load{}_", self.connection.last_insert_rowid());
+        load_into_store_with_scope(path, self, &scope)
+      });
+    match loaded {
+      Ok(added) => {
+        self
+          .connection
+          .execute_batch("COMMIT")
+          .map_err(into_io_error)?;
+        Ok(added)
+      }
+      Err(e) => {
+        self
+          .connection
+          .execute_batch("ROLLBACK")
+          .map_err(into_io_error)?;
+        Err(e)
+      }
+    }
+  }
+
+  // term_id returns the id of a term, if the store holds it
+  fn term_id(&self, term: &TurtleTerm) -> std::io::Result<Option<i64>> {
+    let (kind, value, datatype, language) = columns_of(term);
+    self
+      .connection
+      .prepare_cached(
+        "SELECT id FROM terms WHERE kind = ?1 AND value = ?2 AND datatype = ?3 AND language = ?4",
+      )
+      .and_then(|mut x| {
+        x.query_row(params![kind, value, datatype, language], |row| row.get(0))
+          .optional()
+      })
+      .map_err(into_io_error)
+  }
+
+  // intern returns the id of a term, adding the term to the store if needed
+  fn intern(&self, term: &TurtleTerm) -> std::io::Result<i64> {
+    if let Some(id) = self.term_id(term)? {
+      return Ok(id);
+    }
+    let (kind, value, datatype, language) = columns_of(term);
+    self
+      .connection
+      .prepare_cached("INSERT INTO terms (kind, value, datatype, language) VALUES (?1, ?2, ?3, ?4)")
+      .and_then(|mut x| x.execute(params![kind, value, datatype, language]))
+      .map_err(into_io_error)?;
+    Ok(self.connection.last_insert_rowid())
+  }
+}
+
+impl TripleStore for SqliteTripleStore {
+  fn insert(&mut self, triple: TurtleTriple) -> std::io::Result<bool> {
+    let subject = self.intern(&triple.subject)?;
+    let predicate = self.intern(&triple.predicate)?;
+    let object = self.intern(&triple.object)?;
+    let changed = self
+      .connection
+      .prepare_cached(
+        "INSERT OR IGNORE INTO triples (subject, predicate, object) VALUES (?1, ?2, ?3)",
+      )
+      .and_then(|mut x| x.execute(params![subject, predicate, object]))
+      .map_err(into_io_error)?;
+    Ok(changed == 1)
+  }
+
+  fn len(&self) -> std::io::Result<usize> {
+    self
+      .connection
+      .query_row("SELECT COUNT(*) FROM triples", [], |row| row.get(0))
+      .map_err(into_io_error)
+  }
+
+  fn triples_matching(
+    &self,
+    subject: Option<&TurtleTerm>,
+    predicate: Option<&TurtleTerm>,
+    object: Option<&TurtleTerm>,
+  ) -> std::io::Result<Vec<TurtleTriple>> {
+    let mut clauses = vec![];
+    let mut ids = vec![];
+    for (column, term) in [
+      ("subject", subject),
+      ("predicate", predicate),
+      ("object", object),
+    ] {
+      if let Some(term) = term {
+        // a term the store does not hold matches no triple
+        let id = match self.term_id(term)? {
+          Some(x) => x,
+          None => return Ok(vec![]),
+        };
+        ids.push(id);
+        clauses.push(format!("t.{} = ?{}", column, ids.len()));
+      }
+    }
+    let sql = match clauses.is_empty() {
+      true => format!("{} ORDER BY t.rowid", SELECT_TRIPLES),
+      false => format!(
+        "{} WHERE {} ORDER BY t.rowid",
+        SELECT_TRIPLES,
+        clauses.join(" AND ")
+      ),
+    };
+    let mut statement = self
+      .connection
+      .prepare_cached(&sql)
+      .map_err(into_io_error)?;
+    let rows = statement
+      .query_map(params_from_iter(ids.iter()), |row| {
+        Ok(TurtleTriple::new(
+          term_at(row, 0)?,
+          term_at(row, 4)?,
+          term_at(row, 8)?,
+        ))
+      })
+      .map_err(into_io_error)?;
+    rows
+      .collect::<rusqlite::Result<Vec<_>>>()
+      .map_err(into_io_error)
+  }
+}
+
+// columns_of returns the kind, value, datatype and language columns of a term.
+// Columns a term does not have are empty
+fn columns_of(term: &TurtleTerm) -> (i64, Cow<'_, str>, &str, &str) {
+  match term {
+    TurtleTerm::Iri(x) => (IRI, Cow::from(x), "", ""),
+    TurtleTerm::BlankNode(x) => (BLANK_NODE, Cow::from(x), "", ""),
+    TurtleTerm::QuotedTriple(_) => (QUOTED_TRIPLE, Cow::from(term.to_string()), "", ""),
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => (
+      LITERAL,
+      Cow::from(value),
+      datatype,
+      language.as_deref().unwrap_or_default(),
+    ),
+  }
+}
+
+// term_at reads the term whose four columns start at `idx`
+fn term_at(row: &Row, idx: usize) -> rusqlite::Result<TurtleTerm> {
+  let kind: i64 = row.get(idx)?;
+  let value: String = row.get(idx + 1)?;
+  match kind {
+    IRI => Ok(TurtleTerm::Iri(value)),
+    BLANK_NODE => Ok(TurtleTerm::BlankNode(value)),
+    LITERAL => {
+      let language: String = row.get(idx + 3)?;
+      Ok(TurtleTerm::Literal {
+        value,
+        datatype: row.get(idx + 2)?,
+        language: match language.is_empty() {
+          true => None,
+          false => Some(language),
+        },
+      })
+    }
+    QUOTED_TRIPLE => {
+      PrefixMap::new()
+        .resolve_term(&value)
+        .ok_or(rusqlite::Error::InvalidColumnType(
+          idx + 1,
+          value,
+          rusqlite::types::Type::Text,
+        ))
+    }
+    _ => Err(rusqlite::Error::IntegralValueOutOfRange(idx, kind)),
+  }
+}
+
+fn into_io_error(err: rusqlite::Error) -> std::io::Error {
+  std::io::Error::other(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::declarations::graph::TripleGraph;
+
+  fn iri(x: &str) -> TurtleTerm {
+    TurtleTerm::Iri(String::from(x))
+  }
+
+  #[test]
+  fn should_match_triple_patterns() -> std::io::Result<()> {
+    let mut store = SqliteTripleStore::open_in_memory()?;
+    let agent = iri("http://example.org/Agent");
+    let label = iri("http://www.w3.org/2000/01/rdf-schema#label");
+    let literal = |language: Option<&str>| TurtleTerm::Literal {
+      value: String::from("agent"),
+      datatype: String::from("http://www.w3.org/1999/02/22-rdf-syntax-ns#langString"),
+      language: language.map(String::from),
+    };
+    let triples = vec![
+      TurtleTriple::new(agent.clone(), label.clone(), literal(Some("en"))),
+      TurtleTriple::new(agent.clone(), label.clone(), literal(None)),
+      TurtleTriple::new(
+        TurtleTerm::BlankNode(String::from("b0")),
+        label.clone(),
+        agent.clone(),
+      ),
+    ];
+    for t in triples.iter() {
+      assert!(store.insert(t.clone())?);
+    }
+    assert!(!store.insert(triples[0].clone())?);
+    assert_eq!(store.len()?, 3);
+    assert_eq!(store.triples_matching(None, None, None)?, triples);
+    assert_eq!(
+      store.triples_matching(Some(&agent), Some(&label), None)?,
+      triples[..2].to_vec()
+    );
+    assert_eq!(
+      store.triples_matching(None, None, Some(&agent))?,
+      triples[2..].to_vec()
+    );
+    assert!(store
+      .triples_matching(Some(&iri("http://example.org/unknown")), None, None)?
+      .is_empty());
+    assert!(store.contains(&agent, &label, &literal(Some("en")))?);
+    assert!(!store.contains(&agent, &label, &literal(Some("fr")))?);
+    Ok(())
+  }
+
+  #[test]
+  fn should_persist_loaded_document() -> std::io::Result<()> {
+    let wd = std::env::current_dir()?;
+    let path = wd
+      .parent()
+      .unwrap()
+      .join("testdata/turtle/AgentOntology.ttl");
+    let path = path.to_str().unwrap();
+    let db = std::env::temp_dir().join(format!("semantic_owl_store_{}.db", std::process::id()));
+    let db = db.to_str().unwrap();
+    let _ = std::fs::remove_file(db);
+
+    let added = SqliteTripleStore::open(db)?.load(path)?;
+    // a document which can not be loaded leaves the store untouched
+    let mut store = SqliteTripleStore::open(db)?;
+    assert!(store.load("/not/a/document.ttl").is_err());
+    let mut graph = TripleGraph::new();
+    load_into_store_with_scope(path, &mut graph, "load1_")?;
+    assert_eq!(store.len()?, added);
+    assert_eq!(added, graph.len());
+    assert!(graph
+      .triples()
+      .eq(store.triples_matching(None, None, None)?.iter()));
+    drop(store);
+    std::fs::remove_file(db)?;
+    Ok(())
+  }
+
+  #[test]
+  fn should_keep_blank_nodes_of_loads_apart() -> std::io::Result<()> {
+    let dir = std::env::temp_dir();
+    let mut paths = vec![];
+    for (idx, object) in ["A", "B"].iter().enumerate() {
+      let path = dir.join(format!(
+        "semantic_owl_store_{}_{}.ttl",
+        std::process::id(),
+        idx
+      ));
+      let content = format!(
+        "@prefix : <http://example.org/> .\n:s{} :p [ :q :{} ] .\n",
+        idx, object
+      );
+      std::fs::write(&path, content)?;
+      paths.push(path);
+    }
+    let mut store = SqliteTripleStore::open_in_memory()?;
+    for path in paths.iter() {
+      assert_eq!(store.load(path.to_str().unwrap())?, 2);
+    }
+    let q = iri("http://example.org/q");
+    let nodes: Vec<TurtleTerm> = store
+      .triples_matching(None, Some(&q), None)?
+      .into_iter()
+      .map(|t| t.subject)
+      .collect();
+    assert_eq!(nodes.len(), 2);
+    assert_ne!(nodes[0], nodes[1]);
+    for path in paths {
+      std::fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,329 @@
+//! Conformance module runs the W3C Turtle test suite against the loader
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::load::{load_turtle_document, load_turtle_document_from_str};
+use crate::vocab::rdf;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// base IRI the documents of the W3C Turtle test suite are loaded against
+pub const TURTLE_TESTS_BASE: &str = "http://www.w3.org/2013/TurtleTests/";
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const MF_ENTRIES: &str = "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#entries";
+const MF_NAME: &str = "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#name";
+const MF_ACTION: &str = "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#action";
+const MF_RESULT: &str = "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#result";
+const RDFT: &str = "http://www.w3.org/ns/rdftest#";
+
+/// TestKind is the kind of a test of the suite, read from its `rdft` type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TestKind {
+  // the document must be accepted. e.g -> rdft:TestTurtlePositiveSyntax
+  PositiveSyntax,
+
+  // the document must be rejected. e.g -> rdft:TestTurtleNegativeSyntax
+  NegativeSyntax,
+
+  // the document must state the triples of the result. e.g -> rdft:TestTurtleEval
+  Eval,
+
+  // the document must be rejected although it is syntactically valid. e.g -> rdft:TestTurtleNegativeEval
+  NegativeEval,
+}
+
+impl TestKind {
+  /// from_iri returns the kind of a test type, for Turtle and N-Triples tests
+  /// example
+  /// `http://www.w3.org/ns/rdftest#TestTurtleEval` -> `TestKind::Eval`
+  pub fn from_iri(iri: &str) -> Option<TestKind> {
+    let name = iri.strip_prefix(RDFT)?;
+    let name = name
+      .strip_prefix("TestTurtle")
+      .or_else(|| name.strip_prefix("TestNTriples"))?;
+    match name {
+      "PositiveSyntax" => Some(TestKind::PositiveSyntax),
+      "NegativeSyntax" => Some(TestKind::NegativeSyntax),
+      "Eval" => Some(TestKind::Eval),
+      "NegativeEval" => Some(TestKind::NegativeEval),
+      _ => None,
+    }
+  }
+}
+
+/// ConformanceTest is a test of a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceTest {
+  // e.g -> IRI_subject
+  pub name: String,
+
+  pub kind: TestKind,
+
+  // path of the document under test
+  pub action: String,
+
+  // path of the N-Triples document of the expected triples, for evaluation tests
+  pub result: Option<String>,
+}
+
+impl ConformanceTest {
+  pub fn new(name: &str, kind: TestKind, action: &str, result: Option<String>) -> ConformanceTest {
+    Self {
+      name: String::from(name),
+      kind,
+      action: String::from(action),
+      result,
+    }
+  }
+
+  /// run runs the test, loading its documents against the base IRI of the suite. The base is
+  /// followed by the file name of each document
+  pub fn run(&self, base: &str) -> TestResult {
+    let outcome = match (self.kind, load_test_document(&self.action, base)) {
+      (TestKind::PositiveSyntax, Ok(_)) => Ok(()),
+      (TestKind::PositiveSyntax, Err(err)) | (TestKind::Eval, Err(err)) => {
+        Err(format!("the document was rejected: {}", err))
+      }
+      (TestKind::NegativeSyntax, Ok(_)) | (TestKind::NegativeEval, Ok(_)) => {
+        Err(String::from("the document was accepted"))
+      }
+      (TestKind::NegativeSyntax, Err(_)) | (TestKind::NegativeEval, Err(_)) => Ok(()),
+      (TestKind::Eval, Ok(document)) => self.compare(&document, base),
+    };
+    TestResult::new(&self.name, self.kind, outcome.err())
+  }
+
+  // compare compares the triples of a document with the triples of the expected result
+  fn compare(&self, document: &TurtleDocument, base: &str) -> Result<(), String> {
+    let result = match &self.result {
+      Some(x) => x,
+      None => return Err(String::from("the test has no result")),
+    };
+    let expected = load_test_document(result, base)
+      .map_err(|err| format!("the result could not be loaded: {}", err))?;
+    match document.isomorphic_eq(&expected) {
+      Ok(true) => Ok(()),
+      Ok(false) => Err(String::from("the triples differ from the result")),
+      Err(err) => Err(format!("the triples could not be compared: {}", err)),
+    }
+  }
+}
+
+/// TestResult is the outcome of a test. A failed test holds the reason of the failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestResult {
+  pub name: String,
+  pub kind: TestKind,
+  pub failure: Option<String>,
+}
+
+impl TestResult {
+  pub fn new(name: &str, kind: TestKind, failure: Option<String>) -> TestResult {
+    Self {
+      name: String::from(name),
+      kind,
+      failure,
+    }
+  }
+
+  pub fn is_passed(&self) -> bool {
+    self.failure.is_none()
+  }
+}
+
+/// ConformanceReport holds the results of the tests of a manifest, in the order of the manifest.
+/// It is serializable as JSON, which makes runs comparable over time
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ConformanceReport {
+  pub results: Vec<TestResult>,
+
+  // names of the entries of the manifest whose type is not a supported test kind
+  pub skipped: Vec<String>,
+}
+
+impl ConformanceReport {
+  pub fn new(results: Vec<TestResult>, skipped: Vec<String>) -> ConformanceReport {
+    Self { results, skipped }
+  }
+
+  /// passed returns the number of tests passed
+  pub fn passed(&self) -> usize {
+    self.results.iter().filter(|x| x.is_passed()).count()
+  }
+
+  /// failures returns the tests which failed
+  pub fn failures(&self) -> Vec<&TestResult> {
+    self.results.iter().filter(|x| !x.is_passed()).collect()
+  }
+
+  /// pass_rate returns the share of the tests passed, between 0 and 1
+  pub fn pass_rate(&self) -> f64 {
+    match self.results.len() {
+      0 => 1.0,
+      n => self.passed() as f64 / n as f64,
+    }
+  }
+}
+
+/// read_manifest reads the tests listed by the `mf:entries` of a manifest. Relative IRIs of the
+/// manifest resolve against its location, so the documents of the tests are found next to it
+/// example
+/// `read_manifest("rdf-tests/turtle/manifest.ttl")`
+pub fn read_manifest(path: &str) -> std::io::Result<(Vec<ConformanceTest>, Vec<String>)> {
+  let location = std::fs::canonicalize(path)?;
+  let base = format!("file://{}", location.display());
+  let content = std::fs::read_to_string(&location)?;
+  let manifest = load_turtle_document_from_str(&format!("@base <{}> .\n{}", base, content))?;
+  let graph = manifest.graph();
+  let entries = graph
+    .triples()
+    .find(|t| t.predicate.as_iri() == Some(MF_ENTRIES))
+    .and_then(|t| graph.list(&t.object))
+    .ok_or_else(|| {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "the manifest lists no entries",
+      )
+    })?;
+
+  let mut tests = vec![];
+  let mut skipped = vec![];
+  for entry in entries.iter() {
+    let name = match graph.object(entry, MF_NAME) {
+      Some(TurtleTerm::Literal { value, .. }) => value.clone(),
+      _ => entry.to_string(),
+    };
+    let kind = graph
+      .object(entry, RDF_TYPE)
+      .and_then(|x| x.as_iri())
+      .and_then(TestKind::from_iri);
+    let action = graph.object(entry, MF_ACTION).and_then(|x| x.as_iri());
+    match (kind, action) {
+      (Some(kind), Some(action)) => {
+        let result = graph
+          .object(entry, MF_RESULT)
+          .and_then(|x| x.as_iri())
+          .map(file_path);
+        tests.push(ConformanceTest::new(
+          &name,
+          kind,
+          &file_path(action),
+          result,
+        ));
+      }
+      _ => skipped.push(name),
+    }
+  }
+  Ok((tests, skipped))
+}
+
+/// run_manifest runs the tests of a manifest and reports their results
+/// example
+/// `run_manifest("rdf-tests/turtle/manifest.ttl", TURTLE_TESTS_BASE)`
+pub fn run_manifest(path: &str, base: &str) -> std::io::Result<ConformanceReport> {
+  let (tests, skipped) = read_manifest(path)?;
+  let results = tests.iter().map(|x| x.run(base)).collect();
+  Ok(ConformanceReport::new(results, skipped))
+}
+
+// file_path returns the path of a `file://` IRI
+fn file_path(iri: &str) -> String {
+  String::from(iri.strip_prefix("file://").unwrap_or(iri))
+}
+
+// load_test_document loads a document of the suite against the base IRI followed by its file name
+fn load_test_document(path: &str, base: &str) -> std::io::Result<TurtleDocument> {
+  let name = Path::new(path)
+    .file_name()
+    .and_then(|x| x.to_str())
+    .unwrap_or_default();
+  let content = match std::fs::read_to_string(path) {
+    Ok(x) => x,
+    // e.g a document which is not UTF-8
+    Err(_) => return load_turtle_document(path),
+  };
+  load_turtle_document_from_str(&format!("@base <{}{}> .\n{}", base, name, content))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MANIFEST: &str = r#"@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix mf: <http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#> .
+@prefix rdft: <http://www.w3.org/ns/rdftest#> .
+
+<> rdf:type mf:Manifest ;
+   rdfs:comment "Turtle tests" ;
+   mf:entries ( <#IRI_subject> <#bad_syntax> <#relative_IRI> <#wrong_result> <#bad_negative> <#manifest_only> ) .
+
+<#IRI_subject> rdf:type rdft:TestTurtleEval ;
+   mf:name "IRI_subject" ;
+   mf:action <IRI_subject.ttl> ;
+   mf:result <IRI_spo.nt> .
+
+<#bad_syntax> rdf:type rdft:TestTurtleNegativeSyntax ;
+   mf:name "turtle-syntax-bad-struct-01" ;
+   mf:action <turtle-syntax-bad-struct-01.ttl> .
+
+<#relative_IRI> rdf:type rdft:TestTurtleEval ;
+   mf:name "relative_IRI" ;
+   mf:action <relative_IRI.ttl> ;
+   mf:result <relative_IRI.nt> .
+
+<#wrong_result> rdf:type rdft:TestTurtleEval ;
+   mf:name "wrong_result" ;
+   mf:action <IRI_subject.ttl> ;
+   mf:result <relative_IRI.nt> .
+
+<#bad_negative> rdf:type rdft:TestTurtleNegativeSyntax ;
+   mf:name "bad_negative" ;
+   mf:action <IRI_subject.ttl> .
+
+<#manifest_only> rdf:type mf:Manifest ;
+   mf:name "manifest_only" .
+"#;
+
+  #[test]
+  fn should_run_conformance_manifest() -> std::io::Result<()> {
+    let dir = std::env::temp_dir().join(format!("semantic_owl_conformance_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    for (name, content) in [
+      ("manifest.ttl", MANIFEST),
+      (
+        "IRI_subject.ttl",
+        "<http://www.w3.org/2013/TurtleTests/s> <http://www.w3.org/2013/TurtleTests/p> <http://www.w3.org/2013/TurtleTests/o> .",
+      ),
+      (
+        "IRI_spo.nt",
+        "<http://www.w3.org/2013/TurtleTests/s> <http://www.w3.org/2013/TurtleTests/p> <http://www.w3.org/2013/TurtleTests/o> .",
+      ),
+      ("turtle-syntax-bad-struct-01.ttl", "<http://www.w3.org/2013/TurtleTests/s> <http://www.w3.org/2013/TurtleTests/p> <http://www.w3.org/2013/TurtleTests/o> <http://www.w3.org/2013/TurtleTests/o2> ."),
+      ("relative_IRI.ttl", "<s> <p> [ <p> <o> ] ."),
+      (
+        "relative_IRI.nt",
+        "<http://www.w3.org/2013/TurtleTests/s> <http://www.w3.org/2013/TurtleTests/p> _:b1 .\n_:b1 <http://www.w3.org/2013/TurtleTests/p> <http://www.w3.org/2013/TurtleTests/o> .",
+      ),
+    ] {
+      std::fs::write(dir.join(name), content)?;
+    }
+    let report = run_manifest(
+      dir.join("manifest.ttl").to_str().unwrap(),
+      TURTLE_TESTS_BASE,
+    );
+    std::fs::remove_dir_all(&dir)?;
+    let report = report?;
+
+    assert_eq!(report.results.len(), 5);
+    assert_eq!(report.skipped, vec!["manifest_only"]);
+    assert_eq!(report.passed(), 3);
+    let failures: Vec<&str> = report.failures().iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(failures, vec!["wrong_result", "bad_negative"]);
+    assert_eq!(report.pass_rate(), 0.6);
+    assert_eq!(
+      TestKind::from_iri("http://www.w3.org/ns/rdftest#TestNTriplesPositiveSyntax"),
+      Some(TestKind::PositiveSyntax)
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,254 @@
+//! Testing module generates random, valid turtle documents for property-based tests
+pub mod conformance;
+
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::loader::load::load_turtle_document_from_str;
+use crate::transforms::canonical::canonical_triples;
+use crate::vocab::{owl, rdf, rdfs, xsd};
+use crate::writer::format::{format_turtle, render_term, Style};
+
+const RDF_LANG_STRING: &str = rdf::LANG_STRING.as_str();
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+/// prefixes declared by generated documents
+pub const TEST_PREFIXES: [(&str, &str); 4] = [
+  ("", "http://example.org/"),
+  ("owl", owl::NAMESPACE),
+  ("rdfs", rdfs::NAMESPACE),
+  ("xsd", xsd::NAMESPACE),
+];
+
+// namespaces of generated IRIs. The last one is not declared, so its IRIs are written in full
+const NAMESPACES: [&str; 4] = [
+  "http://example.org/",
+  owl::NAMESPACE,
+  rdfs::NAMESPACE,
+  "http://example.com/undeclared#",
+];
+
+// local names of generated IRIs, some of which can not be written as prefixed names
+const LOCAL_NAMES: [&str; 8] = [
+  "Agent",
+  "has_part",
+  "part-of",
+  "label",
+  "a.b",
+  "0",
+  "Class",
+  "%C3%A9t%C3%A9",
+];
+
+// fragments of literal values, including characters which must be escaped
+const LITERAL_PARTS: [&str; 10] = [
+  "agent",
+  " ",
+  "\"",
+  "\\",
+  "\n",
+  "\r",
+  "\t",
+  "é",
+  "# not a comment",
+  "'",
+];
+
+const LANGUAGES: [&str; 3] = ["en", "en-GB", "fr"];
+
+// upper bound of the triples generated from a slice of bytes
+const MAX_TRIPLES: usize = 256;
+
+/// random_triples returns `count` triples generated from `seed`. A seed always yields the same triples
+pub fn random_triples(seed: u64, count: usize) -> Vec<TurtleTriple> {
+  // xorshift, which never leaves the zero state
+  let mut state = seed | 1;
+  let source = std::iter::repeat_with(move || {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    (state >> 32) as u8
+  });
+  let mut generator = Generator::new(source);
+  (0..count).map(|_| generator.triple()).collect()
+}
+
+/// triples_from_bytes returns the triples generated from a slice of bytes, e.g the input of a
+/// fuzzer. Triples are generated until the bytes are consumed
+pub fn triples_from_bytes(bytes: &[u8]) -> Vec<TurtleTriple> {
+  let mut generator = Generator::new(bytes.iter().copied());
+  let mut triples = vec![];
+  while triples.len() < MAX_TRIPLES && generator.source.peek().is_some() {
+    triples.push(generator.triple());
+  }
+  triples
+}
+
+/// write_document writes the triples as a turtle document declaring `TEST_PREFIXES`, one
+/// statement per triple
+pub fn write_document(triples: &[TurtleTriple]) -> String {
+  let mut map = PrefixMap::new();
+  let mut out = String::new();
+  for (prefix, namespace) in TEST_PREFIXES.iter() {
+    map.insert(prefix, namespace);
+    out.push_str(&format!("@prefix {}: <{}> .\n", prefix, namespace));
+  }
+  for t in triples {
+    out.push_str(&format!(
+      "{} {} {} .\n",
+      render_term(&t.subject, &map),
+      render_term(&t.predicate, &map),
+      render_term(&t.object, &map)
+    ));
+  }
+  out
+}
+
+/// check_round_trip writes the triples as a document, parses it, formats it with each `Style`
+/// profile and parses it again. The triples of every document must be the triples generated,
+/// otherwise an InvalidData error holds the first document which differs
+pub fn check_round_trip(triples: &[TurtleTriple]) -> std::io::Result<()> {
+  let expected = canonical_triples(triples);
+  let written = write_document(triples);
+  let document = load_turtle_document_from_str(&written)?;
+  check_triples(&expected, &document.triples(), &written)?;
+  for style in [Style::compact(), Style::protege(), Style::obo()].iter() {
+    let formatted = format_turtle(&document, style);
+    let reloaded = load_turtle_document_from_str(&formatted)?;
+    check_triples(&expected, &reloaded.triples(), &formatted)?;
+  }
+  Ok(())
+}
+
+// check_triples compares the triples parsed from `source` with the expected canonical triples
+fn check_triples(
+  expected: &[TurtleTriple],
+  triples: &[TurtleTriple],
+  source: &str,
+) -> std::io::Result<()> {
+  match canonical_triples(triples) == expected {
+    true => Ok(()),
+    false => Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      format!("the triples did not survive the round trip of\n{}", source),
+    )),
+  }
+}
+
+// Generator draws terms from the vocabulary, one byte per choice. An exhausted source chooses the first option
+struct Generator<I: Iterator<Item = u8>> {
+  source: std::iter::Peekable<I>,
+}
+
+impl<I: Iterator<Item = u8>> Generator<I> {
+  fn new(source: I) -> Generator<I> {
+    Self {
+      source: source.peekable(),
+    }
+  }
+
+  fn choose(&mut self, n: usize) -> usize {
+    self.source.next().map(|x| x as usize % n).unwrap_or(0)
+  }
+
+  fn triple(&mut self) -> TurtleTriple {
+    let subject = match self.choose(4) {
+      0 => self.blank_node(),
+      _ => self.iri(),
+    };
+    let predicate = self.iri();
+    let object = match self.choose(3) {
+      0 => self.blank_node(),
+      1 => self.iri(),
+      _ => self.literal(),
+    };
+    TurtleTriple::new(subject, predicate, object)
+  }
+
+  fn iri(&mut self) -> TurtleTerm {
+    let namespace = NAMESPACES[self.choose(NAMESPACES.len())];
+    let local = LOCAL_NAMES[self.choose(LOCAL_NAMES.len())];
+    TurtleTerm::Iri(format!("{}{}", namespace, local))
+  }
+
+  fn blank_node(&mut self) -> TurtleTerm {
+    TurtleTerm::BlankNode(format!("b{}", self.choose(8)))
+  }
+
+  fn literal(&mut self) -> TurtleTerm {
+    let (value, datatype, language) = match self.choose(5) {
+      0 => (self.string(), RDF_LANG_STRING, {
+        Some(String::from(LANGUAGES[self.choose(LANGUAGES.len())]))
+      }),
+      1 => (
+        format!("{}", self.choose(256) as i64 - 128),
+        xsd::INTEGER.as_str(),
+        None,
+      ),
+      2 => (
+        String::from(["true", "false"][self.choose(2)]),
+        xsd::BOOLEAN.as_str(),
+        None,
+      ),
+      _ => (self.string(), XSD_STRING, None),
+    };
+    TurtleTerm::Literal {
+      value,
+      datatype: String::from(datatype),
+      language,
+    }
+  }
+
+  fn string(&mut self) -> String {
+    (0..self.choose(6))
+      .map(|_| LITERAL_PARTS[self.choose(LITERAL_PARTS.len())])
+      .collect()
+  }
+}
+
+/// ArbitraryTriples are triples generated from the input of an `arbitrary` based fuzzer or
+/// property testing framework
+#[cfg(feature = "testing")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbitraryTriples(pub Vec<TurtleTriple>);
+
+#[cfg(feature = "testing")]
+impl<'a> arbitrary::Arbitrary<'a> for ArbitraryTriples {
+  fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+    let len = u.arbitrary_len::<u8>()?;
+    Ok(Self(triples_from_bytes(u.bytes(len)?)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_generate_the_same_triples_from_a_seed() {
+    assert_eq!(random_triples(7, 16), random_triples(7, 16));
+    assert_ne!(random_triples(7, 16), random_triples(8, 16));
+    assert_eq!(random_triples(7, 16).len(), 16);
+    assert!(triples_from_bytes(&[]).is_empty());
+  }
+
+  #[test]
+  fn should_round_trip_random_documents() -> std::io::Result<()> {
+    for seed in 0..200 {
+      check_round_trip(&random_triples(seed, 24))?;
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "testing")]
+  #[test]
+  fn should_round_trip_arbitrary_triples() -> std::io::Result<()> {
+    use arbitrary::Arbitrary;
+    let bytes: Vec<u8> = (0..4096u32).map(|x| (x * 31 % 251) as u8).collect();
+    let mut u = arbitrary::Unstructured::new(&bytes);
+    while !u.is_empty() {
+      let triples = ArbitraryTriples::arbitrary(&mut u).unwrap();
+      check_round_trip(&triples.0)?;
+    }
+    Ok(())
+  }
+}
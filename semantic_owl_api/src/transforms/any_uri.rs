@@ -0,0 +1,193 @@
+//! Any URI module normalizes how a document refers to other resources in its annotations
+use crate::analysis::validation::EntityType;
+use crate::declarations::prefix_map::has_scheme;
+use crate::declarations::turtle::{TurtleDocument, TurtleObject, TurtleTerm, TurtleTriple};
+use crate::vocab::xsd;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+const ANY_URI: &str = xsd::ANY_URI.as_str();
+
+/// AnyUriPolicy is the form references are normalized to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AnyUriPolicy {
+  // `"http://example.org/agents"^^xsd:anyURI` becomes `<http://example.org/agents>`
+  #[default]
+  ToIri,
+
+  // `<http://example.org/agents>` becomes `"http://example.org/agents"^^xsd:anyURI`
+  ToLiteral,
+}
+
+/// AnyUriOptions selects the statements normalized and the form they are normalized to
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AnyUriOptions {
+  pub policy: AnyUriPolicy,
+
+  // full IRIs of the predicates whose objects are normalized. Empty means the annotation
+  // properties of the document, which keeps the objects of object and data properties as they are
+  pub predicates: Vec<String>,
+}
+
+impl AnyUriOptions {
+  pub fn new(policy: AnyUriPolicy, predicates: Vec<String>) -> AnyUriOptions {
+    Self { policy, predicates }
+  }
+}
+
+/// RewrittenStatement is a statement whose object was converted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewrittenStatement {
+  pub before: TurtleTriple,
+  pub after: TurtleTriple,
+}
+
+impl RewrittenStatement {
+  pub fn new(before: TurtleTriple, after: TurtleTriple) -> RewrittenStatement {
+    Self { before, after }
+  }
+}
+
+/// AnyUriNormalization is a document whose references are normalized, with the statements rewritten
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnyUriNormalization {
+  pub document: TurtleDocument,
+
+  // statements rewritten, in the order they are stated
+  pub rewritten: Vec<RewrittenStatement>,
+}
+
+impl AnyUriNormalization {
+  pub fn new(document: TurtleDocument, rewritten: Vec<RewrittenStatement>) -> AnyUriNormalization {
+    Self {
+      document,
+      rewritten,
+    }
+  }
+}
+
+/// normalize_any_uris returns a copy of the document in which the objects of the selected
+/// predicates are written in the form of the policy. Statements keep their place in the document
+pub fn normalize_any_uris(
+  document: &TurtleDocument,
+  options: &AnyUriOptions,
+) -> AnyUriNormalization {
+  let predicates: BTreeSet<String> = match options.predicates.is_empty() {
+    true => document
+      .entities_of(EntityType::AnnotationProperty)
+      .collect(),
+    false => options.predicates.iter().cloned().collect(),
+  };
+  let map = document.prefix_map();
+  let mut result = document.clone();
+  let mut rewritten = vec![];
+  for item in result.body.iter_mut() {
+    let subject = match item.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+      Some(x) => x,
+      None => continue,
+    };
+    for p in item.predicate.iter_mut() {
+      let predicate = match p
+        .raw_predicate_object
+        .as_deref()
+        .and_then(|x| map.resolve_term(x))
+      {
+        Some(x) => x,
+        None => continue,
+      };
+      if !predicate.as_iri().is_some_and(|x| predicates.contains(x)) {
+        continue;
+      }
+      for o in p.object.iter_mut() {
+        let object = match o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+          Some(x) => x,
+          None => continue,
+        };
+        let converted = match convert(&object, options.policy) {
+          Some(x) => x,
+          None => continue,
+        };
+        *o = TurtleObject::from_raw(&converted.to_string());
+        let before = TurtleTriple::new(subject.clone(), predicate.clone(), object);
+        let after = TurtleTriple::new(subject.clone(), predicate.clone(), converted);
+        rewritten.push(RewrittenStatement::new(before, after));
+      }
+    }
+  }
+  AnyUriNormalization::new(result, rewritten)
+}
+
+// convert returns an object in the form of the policy, or `None` if it is in that form already
+// or can not be converted
+fn convert(object: &TurtleTerm, policy: AnyUriPolicy) -> Option<TurtleTerm> {
+  match (policy, object) {
+    (
+      AnyUriPolicy::ToIri,
+      TurtleTerm::Literal {
+        value, datatype, ..
+      },
+    ) if datatype == ANY_URI && has_scheme(value.trim()) => {
+      Some(TurtleTerm::Iri(String::from(value.trim())))
+    }
+    (AnyUriPolicy::ToLiteral, TurtleTerm::Iri(iri)) => Some(TurtleTerm::Literal {
+      value: iri.clone(),
+      datatype: String::from(ANY_URI),
+      language: None,
+    }),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+cco:is_curated_in_ontology a owl:AnnotationProperty .
+cco:has_url a owl:DatatypeProperty .
+cco:Agent a owl:Class ;
+  cco:is_curated_in_ontology "http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology"^^xsd:anyURI ;
+  rdfs:seeAlso "not an IRI"^^xsd:anyURI .
+cco:JohnDoe cco:has_url "http://example.org/johndoe"^^xsd:anyURI .
+"#;
+
+  #[test]
+  fn should_normalize_any_uris() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let normalized = normalize_any_uris(&document, &AnyUriOptions::default());
+    // the data property and the value which is not an IRI are kept
+    assert_eq!(normalized.rewritten.len(), 1);
+    let after = &normalized.rewritten[0].after;
+    assert_eq!(
+      after.object.as_iri(),
+      Some("http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology")
+    );
+    assert!(normalized.document.triples().contains(after));
+    assert_eq!(
+      normalized.document.triples().len(),
+      document.triples().len()
+    );
+
+    // converting back restores the document
+    let back = normalize_any_uris(
+      &normalized.document,
+      &AnyUriOptions::new(AnyUriPolicy::ToLiteral, vec![]),
+    );
+    assert_eq!(back.rewritten.len(), 1);
+    assert_eq!(back.document.triples(), document.triples());
+
+    let all = AnyUriOptions::new(
+      AnyUriPolicy::ToIri,
+      vec![String::from(
+        "http://www.ontologyrepository.com/CommonCoreOntologies/has_url",
+      )],
+    );
+    assert_eq!(normalize_any_uris(&document, &all).rewritten.len(), 1);
+    Ok(())
+  }
+}
@@ -0,0 +1,763 @@
+//! Canonical module computes a deterministic form of a document and its hash
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// prefix of the labels given to blank nodes in the canonical form
+pub const CANONICAL_BLANK_NODE_PREFIX: &str = "c14n";
+
+// prefix of the labels issued while hashing the paths from a blank node
+const TEMPORARY_BLANK_NODE_PREFIX: &str = "b";
+
+/// IsomorphismLimits bounds the search of a mapping between the blank nodes of two documents,
+/// which may take exponential time on graphs with many alike blank nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IsomorphismLimits {
+  // documents with more blank nodes are not compared
+  pub max_blank_nodes: usize,
+
+  // number of blank nodes mapped, undone mappings included, before the search is abandoned
+  pub max_steps: usize,
+}
+
+impl IsomorphismLimits {
+  pub fn new(max_blank_nodes: usize, max_steps: usize) -> IsomorphismLimits {
+    Self {
+      max_blank_nodes,
+      max_steps,
+    }
+  }
+}
+
+impl Default for IsomorphismLimits {
+  fn default() -> Self {
+    Self::new(100_000, 1_000_000)
+  }
+}
+
+/// canonical_triples returns the distinct triples of the document, sorted,
+/// with blank nodes relabeled as `c14n0`, `c14n1` ..
+pub fn canonical_triples(triples: &[TurtleTriple]) -> Vec<TurtleTriple> {
+  let labels = canonical_blank_node_labels(triples);
+  let relabel = |term: &TurtleTerm| -> TurtleTerm {
+    match term {
+      TurtleTerm::BlankNode(x) => TurtleTerm::BlankNode(labels[x].clone()),
+      t => t.clone(),
+    }
+  };
+  // sorting by the N-Triples form makes the order independent of the term model
+  let mut by_line: BTreeMap<String, TurtleTriple> = BTreeMap::new();
+  for t in triples.iter() {
+    let c = TurtleTriple::new(
+      relabel(&t.subject),
+      relabel(&t.predicate),
+      relabel(&t.object),
+    );
+    by_line.insert(c.to_string(), c);
+  }
+  by_line.into_values().collect()
+}
+
+/// canonical_blank_node_labels maps the blank node labels of the triples to canonical labels,
+/// following the RDFC-1.0 algorithm: nodes are ordered by the hash of the triples they appear
+/// in, and nodes whose hashes are alike by the hash of the paths reaching out to the other
+/// blank nodes, so isomorphic triples are given the same labels
+pub fn canonical_blank_node_labels(triples: &[TurtleTriple]) -> BTreeMap<String, String> {
+  // a triple stated twice is the same triple, it must not weigh twice in the hashes
+  let mut distinct_triples: BTreeSet<&TurtleTriple> = BTreeSet::new();
+  let triples: Vec<&TurtleTriple> = triples
+    .iter()
+    .filter(|t| distinct_triples.insert(t))
+    .collect();
+  let index = BlankNodeIndex::new(&triples);
+  let mut by_hash: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+  for node in index.nodes.iter() {
+    by_hash
+      .entry(index.first_degree_hash(node))
+      .or_default()
+      .push(node);
+  }
+
+  // a node whose hash is unique is labeled in the order of the hashes
+  let mut labeling = Labeling {
+    index: &index,
+    first_degree: by_hash
+      .iter()
+      .flat_map(|(hash, nodes)| nodes.iter().map(move |x| (*x, hash.as_str())))
+      .collect(),
+    canonical: IdentifierIssuer::new(CANONICAL_BLANK_NODE_PREFIX),
+  };
+  for nodes in by_hash.values().filter(|x| x.len() == 1) {
+    labeling.canonical.issue(nodes[0]);
+  }
+
+  // the others are labeled in the order of the hashes of their paths
+  for nodes in by_hash.values().filter(|x| x.len() > 1) {
+    let mut results: Vec<(String, IdentifierIssuer)> = vec![];
+    for node in nodes.iter() {
+      if labeling.canonical.get(node).is_some() {
+        continue;
+      }
+      let mut issuer = IdentifierIssuer::new(TEMPORARY_BLANK_NODE_PREFIX);
+      issuer.issue(node);
+      results.push(labeling.n_degree_hash(node, issuer));
+    }
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, issuer) in results {
+      for node in issuer.issued {
+        labeling.canonical.issue(node);
+      }
+    }
+  }
+
+  let canonical = labeling.canonical;
+  index
+    .nodes
+    .iter()
+    .map(|x| {
+      (
+        String::from(*x),
+        String::from(canonical.get(x).unwrap_or_default()),
+      )
+    })
+    .collect()
+}
+
+// BlankNodeIndex holds the blank nodes of distinct triples in order of appearance, with the
+// triples each appears in as subject or object
+struct BlankNodeIndex<'a> {
+  nodes: Vec<&'a str>,
+  triples: HashMap<&'a str, Vec<&'a TurtleTriple>>,
+}
+
+impl<'a> BlankNodeIndex<'a> {
+  fn new(triples: &[&'a TurtleTriple]) -> BlankNodeIndex<'a> {
+    let mut nodes: Vec<&str> = vec![];
+    let mut by_node: HashMap<&str, Vec<&TurtleTriple>> = HashMap::new();
+    for t in triples.iter() {
+      for term in [&t.subject, &t.object] {
+        if let TurtleTerm::BlankNode(x) = term {
+          let of_node = by_node.entry(x.as_str()).or_insert_with(|| {
+            nodes.push(x);
+            vec![]
+          });
+          // a triple from a node to itself is listed once
+          if !of_node.last().is_some_and(|last| std::ptr::eq(*last, *t)) {
+            of_node.push(t);
+          }
+        }
+      }
+    }
+    Self {
+      nodes,
+      triples: by_node,
+    }
+  }
+
+  // first_degree_hash hashes the triples of a node, where the node itself is `_:a` and any
+  // other blank node is `_:z`
+  fn first_degree_hash(&self, node: &str) -> String {
+    let mask = |term: &TurtleTerm| -> String {
+      match term {
+        TurtleTerm::BlankNode(x) if x == node => String::from("_:a"),
+        TurtleTerm::BlankNode(_) => String::from("_:z"),
+        t => t.to_string(),
+      }
+    };
+    let mut lines: Vec<String> = self.triples[node]
+      .iter()
+      .map(|t| {
+        format!(
+          "{} {} {} .",
+          mask(&t.subject),
+          mask(&t.predicate),
+          mask(&t.object)
+        )
+      })
+      .collect();
+    lines.sort();
+    sha256_hex(&lines.join("\n"))
+  }
+}
+
+// IdentifierIssuer issues labels made of a prefix and a counter, remembering the order of issue
+#[derive(Clone)]
+struct IdentifierIssuer<'a> {
+  prefix: &'static str,
+  issued: Vec<&'a str>,
+  labels: HashMap<&'a str, String>,
+}
+
+impl<'a> IdentifierIssuer<'a> {
+  fn new(prefix: &'static str) -> IdentifierIssuer<'a> {
+    Self {
+      prefix,
+      issued: vec![],
+      labels: HashMap::new(),
+    }
+  }
+
+  fn get(&self, node: &str) -> Option<&str> {
+    self.labels.get(node).map(|x| x.as_str())
+  }
+
+  // issue returns the label of a node, issuing one if the node has none
+  fn issue(&mut self, node: &'a str) -> String {
+    if let Some(x) = self.labels.get(node) {
+      return x.clone();
+    }
+    let label = format!("{}{}", self.prefix, self.issued.len());
+    self.issued.push(node);
+    self.labels.insert(node, label.clone());
+    label
+  }
+}
+
+// Labeling is the state of the canonical labeling of blank nodes
+struct Labeling<'a, 'b> {
+  index: &'b BlankNodeIndex<'a>,
+  first_degree: HashMap<&'a str, &'b str>,
+  canonical: IdentifierIssuer<'a>,
+}
+
+impl<'a> Labeling<'a, '_> {
+  // n_degree_hash hashes the paths from a node to the blank nodes it is related to, choosing for
+  // alike related nodes the order giving the least path. It returns the hash with the issuer
+  // of the temporary labels of the chosen paths
+  fn n_degree_hash(
+    &self,
+    node: &'a str,
+    mut issuer: IdentifierIssuer<'a>,
+  ) -> (String, IdentifierIssuer<'a>) {
+    let mut related: BTreeMap<String, Vec<&'a str>> = BTreeMap::new();
+    for t in self.index.triples[node].iter() {
+      for (position, term) in [("s", &t.subject), ("o", &t.object)] {
+        match term {
+          TurtleTerm::BlankNode(x) if x != node => {
+            let hash = self.related_hash(x, &t.predicate, &issuer, position);
+            related.entry(hash).or_default().push(x);
+          }
+          _ => {}
+        }
+      }
+    }
+
+    let mut data = String::new();
+    for (hash, nodes) in related {
+      data.push_str(&hash);
+      let mut chosen: Option<(String, IdentifierIssuer)> = None;
+      let mut order: Vec<usize> = (0..nodes.len()).collect();
+      loop {
+        let permutation = order.iter().map(|x| nodes[*x]);
+        if let Some(x) = self.path(permutation, &issuer, chosen.as_ref().map(|x| x.0.as_str())) {
+          if chosen.as_ref().is_none_or(|c| x.0 < c.0) {
+            chosen = Some(x);
+          }
+        }
+        if !next_permutation(&mut order) {
+          break;
+        }
+      }
+      if let Some((path, chosen_issuer)) = chosen {
+        data.push_str(&path);
+        issuer = chosen_issuer;
+      }
+    }
+    (sha256_hex(&data), issuer)
+  }
+
+  // path returns the path through related nodes in the order given, with the issuer of its
+  // labels. None is returned as soon as the path can not be less than the one chosen
+  fn path(
+    &self,
+    nodes: impl Iterator<Item = &'a str>,
+    issuer: &IdentifierIssuer<'a>,
+    chosen: Option<&str>,
+  ) -> Option<(String, IdentifierIssuer<'a>)> {
+    let exceeds = |path: &str| chosen.is_some_and(|c| path.len() >= c.len() && path > c);
+    let mut issuer = issuer.clone();
+    let mut path = String::new();
+    let mut recursion: Vec<&str> = vec![];
+    for x in nodes {
+      match self.canonical.get(x) {
+        Some(label) => path.push_str(&format!("_:{}", label)),
+        None => {
+          if issuer.get(x).is_none() {
+            recursion.push(x);
+          }
+          path.push_str(&format!("_:{}", issuer.issue(x)));
+        }
+      }
+      if exceeds(&path) {
+        return None;
+      }
+    }
+    for x in recursion {
+      let (hash, result) = self.n_degree_hash(x, issuer.clone());
+      path.push_str(&format!("_:{}<{}>", issuer.issue(x), hash));
+      issuer = result;
+      if exceeds(&path) {
+        return None;
+      }
+    }
+    Some((path, issuer))
+  }
+
+  // related_hash hashes a blank node related to another by a triple, from its position in the
+  // triple, the predicate and its label, or its first degree hash when it has none yet
+  fn related_hash(
+    &self,
+    node: &str,
+    predicate: &TurtleTerm,
+    issuer: &IdentifierIssuer,
+    position: &str,
+  ) -> String {
+    let id = match (self.canonical.get(node), issuer.get(node)) {
+      (Some(x), _) | (None, Some(x)) => format!("_:{}", x),
+      (None, None) => String::from(self.first_degree[node]),
+    };
+    sha256_hex(&format!("{}{}{}", position, predicate, id))
+  }
+}
+
+// next_permutation rearranges indexes into the next permutation in lexicographic order. It
+// returns false, leaving them sorted, after the last permutation
+fn next_permutation(order: &mut [usize]) -> bool {
+  let pivot = match (1..order.len()).rev().find(|x| order[x - 1] < order[*x]) {
+    Some(x) => x - 1,
+    None => {
+      order.reverse();
+      return false;
+    }
+  };
+  let successor = (pivot + 1..order.len())
+    .rev()
+    .find(|x| order[*x] > order[pivot])
+    .unwrap_or(pivot);
+  order.swap(pivot, successor);
+  order[pivot + 1..].reverse();
+  true
+}
+
+// blank_node_hashes returns the blank nodes of distinct triples in order of appearance, with a hash
+// of the surroundings of each. The hashes do not depend on the labels of the blank nodes
+fn blank_node_hashes<'a>(triples: &[&'a TurtleTriple]) -> (Vec<&'a str>, HashMap<&'a str, String>) {
+  let index = BlankNodeIndex::new(triples);
+  let mut hashes: HashMap<&str, String> = index
+    .nodes
+    .iter()
+    .map(|x| (*x, index.first_degree_hash(x)))
+    .collect();
+
+  // neighbouring blank nodes of each node, by the predicate relating them
+  let neighbours: HashMap<&str, Vec<(&str, String, &str)>> = index
+    .nodes
+    .iter()
+    .map(|node| {
+      let of_node = index.triples[node]
+        .iter()
+        .flat_map(|t| match (&t.subject, &t.object) {
+          (TurtleTerm::BlankNode(s), TurtleTerm::BlankNode(o)) => {
+            let mut out = vec![];
+            if s == node {
+              out.push(("o", t.predicate.to_string(), o.as_str()));
+            }
+            if o == node {
+              out.push(("s", t.predicate.to_string(), s.as_str()));
+            }
+            out
+          }
+          _ => vec![],
+        })
+        .collect();
+      (*node, of_node)
+    })
+    .collect();
+
+  // refine the hashes with those of the neighbouring blank nodes until the partition is stable
+  let mut distinct = hashes.values().collect::<HashSet<_>>().len();
+  for _ in 0..index.nodes.len() {
+    let mut refined: HashMap<&str, String> = HashMap::with_capacity(hashes.len());
+    for node in index.nodes.iter() {
+      let mut lines: Vec<String> = neighbours[node]
+        .iter()
+        .map(|(position, predicate, x)| format!("{} {} {}", position, predicate, hashes[x]))
+        .collect();
+      lines.sort();
+      lines.insert(0, hashes[node].clone());
+      refined.insert(node, sha256_hex(&lines.join("\n")));
+    }
+    let refined_distinct = refined.values().collect::<HashSet<_>>().len();
+    hashes = refined;
+    if refined_distinct == distinct {
+      break;
+    }
+    distinct = refined_distinct;
+  }
+  (index.nodes, hashes)
+}
+
+/// sha256_hex returns the lowercase hexadecimal SHA-256 digest of the input
+pub fn sha256_hex(input: &str) -> String {
+  let digest = Sha256::digest(input.as_bytes());
+  digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl TurtleDocument {
+  /// canonical_form returns the document as sorted N-Triples with canonical blank node labels.
+  /// Documents stating the same triples have the same canonical form, regardless of
+  /// statement ordering, prefixes used or blank node labels
+  pub fn canonical_form(&self) -> String {
+    let mut out = String::new();
+    for t in canonical_triples(&self.triples()) {
+      out.push_str(&t.to_string());
+      out.push('\n');
+    }
+    out
+  }
+
+  /// canonical_hash returns the hexadecimal SHA-256 digest of the canonical form of the document
+  pub fn canonical_hash(&self) -> String {
+    sha256_hex(&self.canonical_form())
+  }
+
+  /// isomorphic_eq determines whether two documents state the same graph, whatever the order of
+  /// their statements, the prefixes they use and the labels of their blank nodes. Fails with
+  /// `InvalidInput` when the comparison exceeds the default `IsomorphismLimits`
+  pub fn isomorphic_eq(&self, other: &TurtleDocument) -> std::io::Result<bool> {
+    self.isomorphic_eq_with_limits(other, &IsomorphismLimits::default())
+  }
+
+  /// isomorphic_eq_with_limits determines whether two documents state the same graph, bounding
+  /// the search of a mapping between their blank nodes
+  pub fn isomorphic_eq_with_limits(
+    &self,
+    other: &TurtleDocument,
+    limits: &IsomorphismLimits,
+  ) -> std::io::Result<bool> {
+    let (triples, other_triples) = (self.triples(), other.triples());
+    let left: BTreeSet<&TurtleTriple> = triples.iter().collect();
+    let right: HashSet<&TurtleTriple> = other_triples.iter().collect();
+    if left.len() != right.len() {
+      return Ok(false);
+    }
+    // triples without blank nodes are compared as they are
+    let is_ground = |t: &TurtleTriple| !t.subject.is_blank_node() && !t.object.is_blank_node();
+    if left.iter().any(|t| is_ground(t) && !right.contains(*t)) {
+      return Ok(false);
+    }
+    let left: Vec<&TurtleTriple> = left.into_iter().collect();
+    let mut right_sorted: Vec<&TurtleTriple> = right.iter().copied().collect();
+    right_sorted.sort();
+    let (left_nodes, left_hashes) = blank_node_hashes(&left);
+    let (right_nodes, right_hashes) = blank_node_hashes(&right_sorted);
+    if left_nodes.len() > limits.max_blank_nodes || right_nodes.len() > limits.max_blank_nodes {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!(
+          "documents hold more than the {} blank nodes which can be compared",
+          limits.max_blank_nodes
+        ),
+      ));
+    }
+    let mut left_classes: Vec<&String> = left_hashes.values().collect();
+    let mut right_classes: Vec<&String> = right_hashes.values().collect();
+    left_classes.sort_unstable();
+    right_classes.sort_unstable();
+    if left_classes != right_classes {
+      return Ok(false);
+    }
+
+    // blank nodes can only be mapped onto nodes whose surroundings hash the same
+    let mut candidates: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for node in right_nodes.iter() {
+      candidates
+        .entry(right_hashes[node].as_str())
+        .or_default()
+        .push(node);
+    }
+    let mut by_node: BTreeMap<&str, Vec<&TurtleTriple>> = BTreeMap::new();
+    for t in left.iter() {
+      for term in [&t.subject, &t.object] {
+        if let TurtleTerm::BlankNode(x) = term {
+          by_node.entry(x.as_str()).or_default().push(t);
+        }
+      }
+    }
+    // nodes with the fewest candidates are mapped first, narrowing the search early
+    let mut order = left_nodes;
+    order.sort_by_key(|x| candidates[left_hashes[x].as_str()].len());
+    let mut search = MappingSearch {
+      other: right,
+      candidates: order
+        .iter()
+        .map(|x| candidates[left_hashes[x].as_str()].clone())
+        .collect(),
+      triples: order
+        .iter()
+        .map(|x| by_node.remove(x).unwrap_or_default())
+        .collect(),
+      order,
+      mapping: BTreeMap::new(),
+      used: BTreeSet::new(),
+    };
+    search.run(limits.max_steps)
+  }
+}
+
+// MappingSearch searches, by backtracking, a bijection between the blank nodes of two sets of
+// distinct triples of the same size, under which the first set is the second
+struct MappingSearch<'a> {
+  other: HashSet<&'a TurtleTriple>,
+
+  // blank nodes of the first set in the order they are mapped, with the nodes each may be mapped
+  // onto and the triples it appears in
+  order: Vec<&'a str>,
+  candidates: Vec<Vec<&'a str>>,
+  triples: Vec<Vec<&'a TurtleTriple>>,
+
+  mapping: BTreeMap<&'a str, &'a str>,
+  used: BTreeSet<&'a str>,
+}
+
+impl MappingSearch<'_> {
+  // run returns whether a bijection exists, trying at most `max_steps` mappings of a node.
+  // The search keeps its own stack, as documents may hold many thousands of blank nodes
+  fn run(&mut self, max_steps: usize) -> std::io::Result<bool> {
+    // index of the next candidate tried for each node
+    let mut next = vec![0; self.order.len()];
+    let mut steps = 0;
+    let mut idx = 0;
+    while idx < self.order.len() {
+      let node = self.order[idx];
+      if let Some(previous) = self.mapping.remove(node) {
+        self.used.remove(previous);
+      }
+      let mut mapped = false;
+      while next[idx] < self.candidates[idx].len() {
+        let candidate = self.candidates[idx][next[idx]];
+        next[idx] += 1;
+        if self.used.contains(candidate) {
+          continue;
+        }
+        steps += 1;
+        if steps > max_steps {
+          return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+              "no mapping between the blank nodes found within {} steps",
+              max_steps
+            ),
+          ));
+        }
+        self.mapping.insert(node, candidate);
+        if self.is_consistent(idx) {
+          self.used.insert(candidate);
+          mapped = true;
+          break;
+        }
+        self.mapping.remove(node);
+      }
+      match (mapped, idx) {
+        (true, _) => idx += 1,
+        (false, 0) => return Ok(false),
+        (false, _) => {
+          next[idx] = 0;
+          idx -= 1;
+        }
+      }
+    }
+    Ok(true)
+  }
+
+  // is_consistent determines whether the triples of the node at `idx`, whose blank nodes are all
+  // mapped, are in the other set. The others are checked when their last blank node is mapped
+  fn is_consistent(&self, idx: usize) -> bool {
+    self.triples[idx]
+      .iter()
+      .all(|t| match (self.map(&t.subject), self.map(&t.object)) {
+        (Some(subject), Some(object)) => {
+          let mapped = TurtleTriple::new(subject, t.predicate.clone(), object);
+          self.other.contains(&mapped)
+        }
+        _ => true,
+      })
+  }
+
+  fn map(&self, term: &TurtleTerm) -> Option<TurtleTerm> {
+    match term {
+      TurtleTerm::BlankNode(x) => self
+        .mapping
+        .get(x.as_str())
+        .map(|x| TurtleTerm::BlankNode(String::from(*x))),
+      t => Some(t.clone()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT0: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Acceleration a owl:Class ;
+                 rdfs:label "Acceleration"@en ;
+                 rdfs:subClassOf [ a owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Velocity ] ,
+                                 [ a owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Speed ] .
+cco:Velocity a owl:Class .
+"#;
+
+  // the same statements in a different order, with other prefixes and blank node labels
+  const DOCUMENT1: &str = r#"
+@prefix c: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+c:Velocity a owl:Class .
+_:x owl:someValuesFrom c:Speed ;
+    owl:onProperty c:has_process_part ;
+    a owl:Restriction .
+_:y a owl:Restriction ; owl:onProperty c:has_process_part ; owl:someValuesFrom c:Velocity .
+c:Acceleration rdfs:subClassOf _:x , _:y ;
+               rdfs:label "Acceleration"@en ;
+               a owl:Class .
+c:Velocity a owl:Class .
+"#;
+
+  #[test]
+  fn should_hash_equivalent_documents_equally() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(DOCUMENT0)?;
+    let d1 = load_turtle_document_from_str(DOCUMENT1)?;
+    assert_eq!(d0.canonical_form(), d1.canonical_form());
+    assert_eq!(d0.canonical_hash(), d1.canonical_hash());
+    assert_eq!(d0.canonical_hash().len(), 64);
+    Ok(())
+  }
+
+  #[test]
+  fn should_hash_changed_documents_differently() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(DOCUMENT0)?;
+    let d1 = load_turtle_document_from_str(&DOCUMENT1.replace("c:Speed", "c:Acceleration"))?;
+    assert_ne!(d0.canonical_hash(), d1.canonical_hash());
+    Ok(())
+  }
+
+  #[test]
+  fn should_produce_sorted_canonical_form() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(DOCUMENT0)?;
+    let form = d0.canonical_form();
+    let lines: Vec<&str> = form.lines().collect();
+    let mut sorted = lines.clone();
+    sorted.sort_unstable();
+    assert_eq!(lines, sorted);
+    assert!(form.contains("_:c14n0"));
+    assert!(!form.contains("genid"));
+    Ok(())
+  }
+
+  #[test]
+  fn should_ignore_repeated_statements() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(
+      "_:a <http://e/p> <http://e/c> .\n_:a <http://e/p> <http://e/c> .\n_:b <http://e/q> _:c .",
+    )?;
+    let d1 =
+      load_turtle_document_from_str("_:x <http://e/p> <http://e/c> .\n_:y <http://e/q> [] .")?;
+    assert_eq!(d0.canonical_form(), d1.canonical_form());
+    Ok(())
+  }
+
+  // ring states a ring of blank nodes, each the next of the one before
+  fn ring(nodes: &[&str]) -> String {
+    let mut out = String::new();
+    for (idx, x) in nodes.iter().enumerate() {
+      let next = nodes[(idx + 1) % nodes.len()];
+      out.push_str(&format!("_:{} <http://e/next> _:{} .\n", x, next));
+    }
+    out
+  }
+
+  #[test]
+  fn should_hash_documents_with_alike_blank_nodes_equally() -> std::io::Result<()> {
+    // every node of a ring of six and of two rings of three has the same first degree hash
+    let d0 = format!(
+      "{}{}{}",
+      ring(&["a", "b", "c", "d", "e", "f"]),
+      ring(&["g", "h", "i"]),
+      ring(&["j", "k", "l"])
+    );
+    let d1 = format!(
+      "{}{}{}",
+      ring(&["x", "y", "z"]),
+      ring(&["f", "a", "d", "c", "b", "e"]),
+      ring(&["l", "j", "k"])
+    );
+    let mut lines: Vec<&str> = d1.lines().collect();
+    lines.reverse();
+    let d1 = lines.join("\n");
+    let d0 = load_turtle_document_from_str(&d0)?;
+    let d1 = load_turtle_document_from_str(&d1)?;
+    assert_eq!(d0.canonical_form(), d1.canonical_form());
+    assert!(d0.isomorphic_eq(&d1)?);
+
+    let threes = format!("{}{}", ring(&["a", "b", "c"]), ring(&["d", "e", "f"]));
+    let six = ring(&["a", "b", "c", "d", "e", "f"]);
+    assert_ne!(
+      load_turtle_document_from_str(&threes)?.canonical_hash(),
+      load_turtle_document_from_str(&six)?.canonical_hash()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_compare_documents_modulo_blank_node_labels() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(DOCUMENT0)?;
+    let d1 = load_turtle_document_from_str(DOCUMENT1)?;
+    assert_ne!(d0, d1);
+    assert!(d0.isomorphic_eq(&d1)?);
+    let changed = load_turtle_document_from_str(&DOCUMENT1.replace("c:Speed", "c:Motion"))?;
+    assert!(!d0.isomorphic_eq(&changed)?);
+
+    // every node of a ring of six and of two rings of three hashes the same
+    let six = load_turtle_document_from_str(&ring(&["a", "b", "c", "d", "e", "f"]))?;
+    let shuffled = load_turtle_document_from_str(&ring(&["f", "a", "d", "c", "b", "e"]))?;
+    let threes = format!("{}{}", ring(&["a", "b", "c"]), ring(&["d", "e", "f"]));
+    let threes = load_turtle_document_from_str(&threes)?;
+    assert!(six.isomorphic_eq(&shuffled)?);
+    assert!(!six.isomorphic_eq(&threes)?);
+
+    // a search exceeding its limits fails rather than answering
+    let limits = IsomorphismLimits::new(100, 2);
+    assert!(six.isomorphic_eq_with_limits(&threes, &limits).is_err());
+    let limits = IsomorphismLimits::new(5, 1_000);
+    assert!(six.isomorphic_eq_with_limits(&shuffled, &limits).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn should_compare_documents_with_many_blank_nodes() -> std::io::Result<()> {
+    let restriction = |idx: usize| {
+      format!(
+        "<http://e/C{}> <http://e/sub> [ <http://e/on> <http://e/p{}> ; <http://e/some> <http://e/D{}> ] .\n",
+        idx,
+        idx % 7,
+        idx % 13
+      )
+    };
+    let d0: String = (0..5_000).map(restriction).collect();
+    let d1: String = (0..5_000).rev().map(restriction).collect();
+    let d0 = load_turtle_document_from_str(&d0)?;
+    let d1 = load_turtle_document_from_str(&d1)?;
+    assert!(d0.isomorphic_eq(&d1)?);
+    Ok(())
+  }
+}
@@ -0,0 +1,216 @@
+//! Declarations module declares the entities a document uses without declaring them
+use crate::analysis::entities::is_reserved_iri;
+use crate::analysis::validation::{EntityType, Violation, ENTITY_TYPES, ILLEGAL_PUNS};
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::vocab::{owl, rdf, rdfs, Iri};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+pub const AMBIGUOUS_ENTITY: &str = "AmbiguousEntity";
+
+/// DeclarationInference is a document completed with the declarations inferred from its statements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeclarationInference {
+  // the document with one statement added per entity declared
+  pub document: TurtleDocument,
+
+  // declarations added. e.g -> <http://example.org/Person> rdf:type owl:Class .
+  pub declarations: Vec<TurtleTriple>,
+
+  // entities whose kind could not be inferred, with the statements using them
+  pub ambiguities: Vec<Violation>,
+}
+
+impl DeclarationInference {
+  pub fn new(
+    document: TurtleDocument,
+    declarations: Vec<TurtleTriple>,
+    ambiguities: Vec<Violation>,
+  ) -> DeclarationInference {
+    Self {
+      document,
+      declarations,
+      ambiguities,
+    }
+  }
+}
+
+/// infer_declarations returns a copy of the document in which the entities used without being
+/// declared are declared, with a report of the entities whose kind is ambiguous. Declarations are
+/// appended to the document, sorted by IRI. IRIs of the RDF, RDFS, OWL and XSD vocabularies are
+/// never declared
+pub fn infer_declarations(document: &TurtleDocument) -> DeclarationInference {
+  let triples = document.triples();
+
+  // kinds declared by the document
+  let mut declared: BTreeMap<&str, BTreeSet<EntityType>> = BTreeMap::new();
+  for t in triples.iter() {
+    if t.predicate.as_iri() != Some(rdf::TYPE.as_str()) {
+      continue;
+    }
+    let declaration = t
+      .object
+      .as_iri()
+      .and_then(|o| ENTITY_TYPES.iter().find(|(x, _)| *x == o));
+    if let (Some(s), Some((_, kind))) = (t.subject.as_iri(), declaration) {
+      declared.entry(s).or_default().insert(*kind);
+    }
+  }
+
+  // kinds implied by the use of the entities, properties being typed by their objects when their
+  // use implies no kind
+  let mut inferred = document.entities();
+  let typed: BTreeSet<String> = inferred
+    .iter()
+    .filter(|(_, kinds)| kinds.iter().any(|x| is_property(*x)))
+    .map(|(iri, _)| iri.clone())
+    .collect();
+  for t in triples.iter() {
+    let predicate = match t.predicate.as_iri() {
+      Some(x) if !is_reserved_iri(x) && !typed.contains(x) => x,
+      _ => continue,
+    };
+    let kind = match t.object.is_literal() {
+      true => EntityType::DataProperty,
+      false => EntityType::ObjectProperty,
+    };
+    inferred
+      .entry(String::from(predicate))
+      .or_default()
+      .insert(kind);
+  }
+
+  let mut document = document.clone();
+  let mut declarations = vec![];
+  let mut ambiguities = vec![];
+  for (iri, kinds) in inferred.iter() {
+    let known = declared.get(iri.as_str()).cloned().unwrap_or_default();
+    let conflicts: Vec<&(EntityType, EntityType)> = ILLEGAL_PUNS
+      .iter()
+      .filter(|(a, b)| kinds.contains(a) && kinds.contains(b))
+      .filter(|(a, b)| !known.contains(a) || !known.contains(b))
+      .collect();
+    if !conflicts.is_empty() {
+      let message = conflicts
+        .iter()
+        .map(|(a, b)| format!("<{}> is used both as {} and as {}", iri, a, b))
+        .collect::<Vec<_>>()
+        .join(", ");
+      let statements = triples
+        .iter()
+        .filter(|t| {
+          [&t.subject, &t.predicate, &t.object]
+            .iter()
+            .any(|x| x.as_iri() == Some(iri.as_str()))
+        })
+        .cloned()
+        .collect();
+      ambiguities.push(Violation::new(AMBIGUOUS_ENTITY, iri, message, statements));
+      continue;
+    }
+    let missing: Vec<Iri> = kinds
+      .difference(&known)
+      .map(|x| declaration_type(*x))
+      .collect();
+    if missing.is_empty() {
+      continue;
+    }
+    for x in missing.iter() {
+      declarations.push(TurtleTriple::new(
+        TurtleTerm::Iri(iri.clone()),
+        rdf::TYPE.into(),
+        (*x).into(),
+      ));
+    }
+    document.body.push_back(declaration_item(iri, &missing));
+  }
+  DeclarationInference::new(document, declarations, ambiguities)
+}
+
+// is_property determines whether a kind of entity is a kind of property
+fn is_property(kind: EntityType) -> bool {
+  matches!(
+    kind,
+    EntityType::ObjectProperty | EntityType::DataProperty | EntityType::AnnotationProperty
+  )
+}
+
+// declaration_type returns the type declaring an entity of a kind
+fn declaration_type(kind: EntityType) -> Iri {
+  match kind {
+    EntityType::Class => owl::CLASS,
+    EntityType::Datatype => rdfs::DATATYPE,
+    EntityType::ObjectProperty => owl::OBJECT_PROPERTY,
+    EntityType::DataProperty => owl::DATATYPE_PROPERTY,
+    EntityType::AnnotationProperty => owl::ANNOTATION_PROPERTY,
+    EntityType::NamedIndividual => owl::NAMED_INDIVIDUAL,
+  }
+}
+
+// declaration_item creates the statement declaring an entity of several types
+fn declaration_item(iri: &str, types: &[Iri]) -> TurtleBodyItem {
+  let mut p = TurtlePredicate::from_raw(&format!("<{}>", rdf::TYPE));
+  for x in types.iter() {
+    p.object
+      .push_back(TurtleObject::from_raw(&format!("<{}>", x)));
+  }
+  let mut predicates = VecDeque::new();
+  predicates.push_back(p);
+  TurtleBodyItem {
+    subject: Some(format!("<{}>", iri)),
+    predicate: predicates,
+    span: None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/> .
+
+:Person a owl:Class ; rdfs:subClassOf :Agent .
+:has_parent a owl:ObjectProperty .
+:JohnDoe a :Person ; :has_parent :JaneDoe ; :has_age 42 ; :knows :JaneDoe .
+:JaneDoe a owl:NamedIndividual ; :code "JD" .
+:Team :code :Blue .
+"#;
+
+  #[test]
+  fn should_infer_declarations() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let inference = infer_declarations(&document);
+    let declarations: Vec<String> = inference
+      .declarations
+      .iter()
+      .map(|t| t.to_string().replace("http://example.org/", ":"))
+      .collect();
+    assert_eq!(
+      declarations,
+      vec![
+        "<:Agent> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> .",
+        "<:JohnDoe> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#NamedIndividual> .",
+        "<:has_age> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#DatatypeProperty> .",
+        "<:knows> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#ObjectProperty> .",
+      ]
+    );
+    assert_eq!(inference.ambiguities.len(), 1);
+    assert_eq!(inference.ambiguities[0].rule, AMBIGUOUS_ENTITY);
+    assert_eq!(inference.ambiguities[0].iri, "http://example.org/code");
+    assert_eq!(inference.ambiguities[0].statements.len(), 2);
+
+    // the completed document declares every entity it uses
+    let completed = infer_declarations(&inference.document);
+    assert!(completed.declarations.is_empty());
+    assert_eq!(
+      inference.document.triples().len(),
+      document.triples().len() + 4
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,134 @@
+//! Defined by module annotates the entities of a document with the ontology defining them
+use crate::analysis::entities::is_reserved_iri;
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::vocab::rdfs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+const IS_DEFINED_BY: &str = rdfs::IS_DEFINED_BY.as_str();
+
+/// DefinedByStamp is a document whose entities are annotated with the ontology defining them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinedByStamp {
+  // the document with one statement added per entity annotated
+  pub document: TurtleDocument,
+
+  // annotations added. e.g -> <http://example.org/Person> rdfs:isDefinedBy <http://example.org/agents> .
+  pub annotations: Vec<TurtleTriple>,
+}
+
+impl DefinedByStamp {
+  pub fn new(document: TurtleDocument, annotations: Vec<TurtleTriple>) -> DefinedByStamp {
+    Self {
+      document,
+      annotations,
+    }
+  }
+}
+
+/// stamp_defined_by returns a copy of the document in which each entity without an
+/// `rdfs:isDefinedBy` annotation is annotated with `ontology_iri`, or with the IRI of the ontology
+/// of the document when `None`. Annotations are appended to the document, sorted by IRI. It will
+/// return an error when no IRI is given and the ontology of the document is anonymous
+/// example
+/// `stamp_defined_by(&document, Some("http://www.ontologyrepository.com/CommonCoreOntologies/Mid/AgentOntology"))`
+pub fn stamp_defined_by(
+  document: &TurtleDocument,
+  ontology_iri: Option<&str>,
+) -> std::io::Result<DefinedByStamp> {
+  let ontology_iri = match ontology_iri {
+    Some(x) => String::from(x),
+    None => document.ontology_id().ontology_iri.ok_or_else(|| {
+      std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "the ontology of the document is anonymous and no IRI is given",
+      )
+    })?,
+  };
+  let triples = document.triples();
+  let subjects: BTreeSet<&str> = triples.iter().filter_map(|t| t.subject.as_iri()).collect();
+  let annotated: BTreeSet<&str> = triples
+    .iter()
+    .filter(|t| t.predicate.as_iri() == Some(IS_DEFINED_BY))
+    .filter_map(|t| t.subject.as_iri())
+    .collect();
+
+  let mut document = document.clone();
+  let mut annotations = vec![];
+  for iri in document.entities().into_keys() {
+    if !subjects.contains(iri.as_str())
+      || annotated.contains(iri.as_str())
+      || is_reserved_iri(&iri)
+      || iri == ontology_iri
+    {
+      continue;
+    }
+    let mut p = TurtlePredicate::from_raw(&format!("<{}>", IS_DEFINED_BY));
+    p.object
+      .push_back(TurtleObject::from_raw(&format!("<{}>", ontology_iri)));
+    document.body.push_back(TurtleBodyItem {
+      subject: Some(format!("<{}>", iri)),
+      predicate: VecDeque::from(vec![p]),
+      span: None,
+    });
+    annotations.push(TurtleTriple::new(
+      TurtleTerm::Iri(iri),
+      rdfs::IS_DEFINED_BY.into(),
+      TurtleTerm::Iri(ontology_iri.clone()),
+    ));
+  }
+  Ok(DefinedByStamp::new(document, annotations))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix : <http://example.org/> .
+
+<http://example.org/agents> a owl:Ontology .
+:Agent a owl:Class ; rdfs:subClassOf obo:BFO_0000040 .
+:Person a owl:Class ; rdfs:subClassOf :Agent ; rdfs:isDefinedBy <http://example.org/people> .
+:has_part a owl:ObjectProperty .
+"#;
+
+  #[test]
+  fn should_stamp_entities_with_their_ontology() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let stamped = stamp_defined_by(&document, None)?;
+    // the imported BFO class and the annotated person are left out
+    let iris: Vec<&str> = stamped
+      .annotations
+      .iter()
+      .filter_map(|t| t.subject.as_iri())
+      .collect();
+    assert_eq!(
+      iris,
+      vec!["http://example.org/Agent", "http://example.org/has_part"]
+    );
+    let triples = stamped.document.triples();
+    assert_eq!(triples.len(), document.triples().len() + 2);
+    assert_eq!(triples.last(), stamped.annotations.last());
+    assert_eq!(
+      stamped.annotations[0].object.as_iri(),
+      Some("http://example.org/agents")
+    );
+
+    // stamping is idempotent
+    let again = stamp_defined_by(&stamped.document, None)?;
+    assert!(again.annotations.is_empty());
+
+    let anonymous =
+      load_turtle_document_from_str(&DOCUMENT.replace("a owl:Ontology", "rdfs:label \"x\""))?;
+    assert!(stamp_defined_by(&anonymous, None).is_err());
+    let named = stamp_defined_by(&anonymous, Some("http://example.org/release"))?;
+    assert_eq!(named.annotations.len(), 2);
+    Ok(())
+  }
+}
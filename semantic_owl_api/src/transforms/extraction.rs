@@ -0,0 +1,200 @@
+//! Extraction module computes the subset of a document needed by a set of seed entities
+use crate::declarations::turtle::{TurtleBodyItem, TurtleDocument, TurtleTerm};
+use crate::vocab::{owl, rdf, rdfs};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+// predicates whose IRI objects are pulled into the module
+const CLOSURE_PREDICATES: [&str; 9] = [
+  rdf::TYPE.as_str(),
+  rdfs::SUB_CLASS_OF.as_str(),
+  rdfs::SUB_PROPERTY_OF.as_str(),
+  rdfs::DOMAIN.as_str(),
+  rdfs::RANGE.as_str(),
+  owl::EQUIVALENT_CLASS.as_str(),
+  owl::EQUIVALENT_PROPERTY.as_str(),
+  owl::INVERSE_OF.as_str(),
+  owl::PROPERTY_CHAIN_AXIOM.as_str(),
+];
+
+const OWL_ANNOTATED_SOURCE: &str = owl::ANNOTATED_SOURCE.as_str();
+
+/// extract_module returns a new document with the statements needed to preserve the meaning
+/// of the `seeds`, given as full IRIs. Starting from the seeds, the module is closed over
+/// - the super classes, super properties, types, domains, ranges, equivalents and inverses of its entities
+/// - the declarations of the properties used by its statements, e.g annotation properties
+/// - the anonymous class expressions (blank nodes) referenced by its statements
+/// - the axiom annotations (`owl:Axiom`) whose source is one of its entities
+///
+/// The headers of the document are kept as they are and statements keep their original order
+pub fn extract_module(document: &TurtleDocument, seeds: &[&str]) -> TurtleDocument {
+  let map = document.prefix_map();
+
+  // body items indexed by their resolved subject
+  let mut subjects: Vec<Option<TurtleTerm>> = Vec::with_capacity(document.body.len());
+  let mut index: HashMap<TurtleTerm, Vec<usize>> = HashMap::new();
+  // axiom annotations indexed by their annotated source
+  let mut annotations: HashMap<TurtleTerm, Vec<TurtleTerm>> = HashMap::new();
+  for (idx, item) in document.body.iter().enumerate() {
+    let subject = item.subject.as_deref().and_then(|x| map.resolve_term(x));
+    if let Some(s) = &subject {
+      index.entry(s.clone()).or_default().push(idx);
+      for source in objects_of(item, &map, OWL_ANNOTATED_SOURCE) {
+        annotations.entry(source).or_default().push(s.clone());
+      }
+    }
+    subjects.push(subject);
+  }
+
+  let mut included: BTreeSet<TurtleTerm> = BTreeSet::new();
+  let mut pending: VecDeque<TurtleTerm> = seeds
+    .iter()
+    .map(|x| TurtleTerm::Iri(String::from(*x)))
+    .collect();
+
+  while let Some(term) = pending.pop_front() {
+    if !included.insert(term.clone()) {
+      continue;
+    }
+    if let Some(axioms) = annotations.get(&term) {
+      pending.extend(axioms.iter().cloned());
+    }
+    for idx in index.get(&term).into_iter().flatten() {
+      let item = &document.body[*idx];
+      for p in item.predicate.iter() {
+        let predicate = match p
+          .raw_predicate_object
+          .as_deref()
+          .and_then(|x| map.resolve_term(x))
+        {
+          Some(x) => x,
+          None => continue,
+        };
+        let follow_iris = term.is_blank_node()
+          || CLOSURE_PREDICATES
+            .iter()
+            .any(|x| predicate.as_iri() == Some(*x));
+        for o in p.object.iter() {
+          match o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+            Some(object @ TurtleTerm::BlankNode(_)) => pending.push_back(object),
+            Some(object @ TurtleTerm::Iri(_)) if follow_iris => pending.push_back(object),
+            _ => {}
+          }
+        }
+        pending.push_back(predicate);
+      }
+    }
+  }
+
+  let mut module = TurtleDocument::new();
+  module.headers = document.headers.clone();
+  for (idx, item) in document.body.iter().enumerate() {
+    if let Some(s) = &subjects[idx] {
+      if included.contains(s) {
+        module.body.push_back(item.clone());
+      }
+    }
+  }
+  module
+}
+
+// objects_of returns the resolved objects of a predicate in a body item
+fn objects_of(
+  item: &TurtleBodyItem,
+  map: &crate::declarations::prefix_map::PrefixMap,
+  predicate: &str,
+) -> Vec<TurtleTerm> {
+  let mut objects = vec![];
+  for p in item.predicate.iter() {
+    let is_predicate = p
+      .raw_predicate_object
+      .as_deref()
+      .and_then(|x| map.resolve_term(x))
+      .map(|x| x.as_iri() == Some(predicate))
+      .unwrap_or(false);
+    if is_predicate {
+      objects.extend(
+        p.object
+          .iter()
+          .filter_map(|o| o.raw_object.as_deref().and_then(|x| map.resolve_term(x))),
+      );
+    }
+  }
+  objects
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:definition rdf:type owl:AnnotationProperty .
+cco:has_process_part rdf:type owl:ObjectProperty .
+cco:unrelated_property rdf:type owl:ObjectProperty .
+
+cco:Acceleration rdf:type owl:Class ;
+                 rdfs:subClassOf cco:ProcessProfile ,
+                                 [ rdf:type owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Velocity
+                                 ] ;
+                 cco:definition "A Process Profile that is the rate of change of the Velocity of an object."@en .
+
+cco:ProcessProfile rdf:type owl:Class ;
+                   rdfs:subClassOf cco:Process .
+
+cco:Velocity rdf:type owl:Class .
+
+cco:Unrelated rdf:type owl:Class ;
+              rdfs:subClassOf cco:Process .
+
+[ rdf:type owl:Axiom ;
+  owl:annotatedSource cco:Acceleration ;
+  owl:annotatedProperty cco:definition ;
+  owl:annotatedTarget "A Process Profile that is the rate of change of the Velocity of an object."@en ;
+  rdfs:comment "provenance"
+] .
+"#;
+
+  fn subjects(document: &TurtleDocument) -> Vec<String> {
+    document
+      .body
+      .iter()
+      .map(|x| x.subject.clone().unwrap_or_default())
+      .collect()
+  }
+
+  #[test]
+  fn should_extract_module_of_seed() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let module = extract_module(
+      &document,
+      &["http://www.ontologyrepository.com/CommonCoreOntologies/Acceleration"],
+    );
+    let s = subjects(&module);
+    assert!(s.contains(&String::from("cco:Acceleration")));
+    assert!(s.contains(&String::from("cco:ProcessProfile")));
+    assert!(s.contains(&String::from("cco:Velocity")));
+    assert!(s.contains(&String::from("cco:has_process_part")));
+    assert!(s.contains(&String::from("cco:definition")));
+    assert!(!s.contains(&String::from("cco:Unrelated")));
+    assert!(!s.contains(&String::from("cco:unrelated_property")));
+    // the restriction and the axiom annotation
+    assert_eq!(s.iter().filter(|x| x.starts_with("_:")).count(), 2);
+    assert_eq!(module.headers, document.headers);
+    Ok(())
+  }
+
+  #[test]
+  fn should_extract_empty_module_of_unknown_seed() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let module = extract_module(&document, &["http://example.org/Unknown"]);
+    assert_eq!(module.body.len(), 0);
+    Ok(())
+  }
+}
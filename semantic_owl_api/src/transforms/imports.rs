@@ -0,0 +1,335 @@
+//! Imports module inlines the imports of an ontology into its document
+use crate::declarations::axiom_annotation::{AnnotatedTriple, Annotation};
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::loader::manager::OntologyManager;
+use crate::vocab::owl;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+const OWL_IMPORTS: &str = owl::IMPORTS.as_str();
+
+// prefix of the labels given to the blank nodes of inlined documents. e.g -> imported0_genid1
+const IMPORTED_NODE_PREFIX: &str = "imported";
+
+/// MaterializeOptions selects the imports inlined and how their statements are traced back
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MaterializeOptions {
+  // IRIs of the ontologies inlined, imported directly or not. Empty means the whole import closure
+  pub imports: Vec<String>,
+
+  // annotation property stating the ontology an inlined statement comes from, on an `owl:Axiom`
+  // node of each statement whose subject is an IRI. e.g -> http://purl.org/dc/terms/source
+  pub provenance: Option<String>,
+}
+
+impl MaterializeOptions {
+  pub fn new(imports: Vec<String>, provenance: Option<String>) -> MaterializeOptions {
+    Self {
+      imports,
+      provenance,
+    }
+  }
+}
+
+/// MaterializedImports reports the ontologies inlined into a document
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MaterializedImports {
+  // IRIs of the ontologies inlined, in breadth first order
+  pub inlined: Vec<String>,
+
+  // IRIs of the ontologies the document still imports, i.e those not selected which the document
+  // or an inlined ontology imports
+  pub imports: Vec<String>,
+
+  // number of statements inlined, provenance annotations left out
+  pub triples: usize,
+}
+
+impl MaterializedImports {
+  pub fn new(inlined: Vec<String>, imports: Vec<String>, triples: usize) -> MaterializedImports {
+    Self {
+      inlined,
+      imports,
+      triples,
+    }
+  }
+}
+
+/// materialize_imports inlines the statements of the imports of a document, as loaded by a
+/// manager, and replaces its `owl:imports` statements with imports of the ontologies not inlined.
+/// Statements the document already holds are not repeated. Fails with `NotFound` when an
+/// ontology to inline is not loaded, and with `InvalidInput` when a selected ontology is not
+/// imported by the document
+pub fn materialize_imports(
+  document: &mut TurtleDocument,
+  manager: &OntologyManager,
+  options: &MaterializeOptions,
+) -> std::io::Result<MaterializedImports> {
+  let root = match document.ontology_id().ontology_iri {
+    Some(x) => x,
+    None => return Ok(MaterializedImports::default()),
+  };
+  let direct = document.imports();
+
+  // import closure of the document in breadth first order, through the ontologies loaded
+  let mut closure: Vec<String> = vec![];
+  let mut visited = BTreeSet::from([root.clone()]);
+  let mut pending: VecDeque<String> = direct.iter().cloned().collect();
+  while let Some(iri) = pending.pop_front() {
+    if visited.insert(iri.clone()) {
+      pending.extend(manager.imports_of(&iri));
+      closure.push(iri);
+    }
+  }
+  if let Some(x) = options.imports.iter().find(|x| !closure.contains(x)) {
+    return Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidInput,
+      format!("<{}> is not imported by <{}>", x, root),
+    ));
+  }
+  let inlined: Vec<String> = closure
+    .into_iter()
+    .filter(|x| options.imports.is_empty() || options.imports.contains(x))
+    .collect();
+  let mut documents: Vec<&TurtleDocument> = vec![];
+  for iri in inlined.iter() {
+    match manager.document(iri) {
+      Some(x) => documents.push(x),
+      None => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::NotFound,
+          format!("the ontology <{}> to inline is not loaded", iri),
+        ))
+      }
+    }
+  }
+
+  // ontologies still imported, in the order they are first imported
+  let mut imports: Vec<String> = vec![];
+  let indirect: Vec<String> = inlined.iter().flat_map(|x| manager.imports_of(x)).collect();
+  for iri in direct.iter().chain(indirect.iter()) {
+    if *iri != root && !inlined.contains(iri) && !imports.contains(iri) {
+      imports.push(iri.clone());
+    }
+  }
+  retain_imports(document, &root, &imports);
+
+  let existing = document.triples();
+  let labels: BTreeSet<&str> = existing
+    .iter()
+    .flat_map(|t| [&t.subject, &t.object])
+    .filter_map(|x| match x {
+      TurtleTerm::BlankNode(label) => Some(label.as_str()),
+      _ => None,
+    })
+    .collect();
+  let mut stated: HashSet<TurtleTriple> = existing.iter().cloned().collect();
+  let mut annotated: Vec<AnnotatedTriple> = vec![];
+  let mut count = 0;
+  for (idx, (iri, imported)) in inlined.iter().zip(documents).enumerate() {
+    // statements about the imported ontology stay with it
+    let ontology = imported.ontology_id().ontology_iri;
+    let mut prefix = format!("{}{}_", IMPORTED_NODE_PREFIX, idx);
+    while labels.iter().any(|x| x.starts_with(prefix.as_str())) {
+      prefix.push('_');
+    }
+    let relabel = |term: TurtleTerm| -> TurtleTerm {
+      match term {
+        TurtleTerm::BlankNode(x) => TurtleTerm::BlankNode(format!("{}{}", prefix, x)),
+        t => t,
+      }
+    };
+    let mut triples = vec![];
+    for t in imported.triples() {
+      let subject = t.subject.as_iri();
+      if subject == Some(iri.as_str()) || (subject.is_some() && subject == ontology.as_deref()) {
+        continue;
+      }
+      let t = TurtleTriple::new(relabel(t.subject), t.predicate, relabel(t.object));
+      if stated.insert(t.clone()) {
+        triples.push(t);
+      }
+    }
+    if let Some(property) = options.provenance.as_deref() {
+      let source = Annotation::new(property, TurtleTerm::Iri(iri.clone()));
+      annotated.extend(
+        triples
+          .iter()
+          .filter(|t| t.subject.as_iri().is_some())
+          .map(|t| AnnotatedTriple::new(t.clone(), vec![source.clone()])),
+      );
+    }
+    count += triples.len();
+    document.body.extend(statement_items(&triples));
+    document.declare_prefixes(&imported.prefix_map());
+  }
+  document.annotate_all(&annotated);
+  Ok(MaterializedImports::new(inlined, imports, count))
+}
+
+// retain_imports removes the `owl:imports` statements of the ontology of a document, then imports
+// the ontologies given
+fn retain_imports(document: &mut TurtleDocument, root: &str, imports: &[String]) {
+  let map = document.prefix_map();
+  let ontology = TurtleTerm::Iri(String::from(root));
+  for item in document.body.iter_mut() {
+    if item.subject.as_deref().and_then(|x| map.resolve_term(x)) != Some(ontology.clone()) {
+      continue;
+    }
+    item.predicate.retain(|p| {
+      let predicate = p
+        .raw_predicate_object
+        .as_deref()
+        .and_then(|x| map.resolve_term(x));
+      predicate.as_ref().and_then(|x| x.as_iri()) != Some(OWL_IMPORTS)
+    });
+  }
+  document.body.retain(|x| !x.predicate.is_empty());
+  let triples: Vec<TurtleTriple> = imports
+    .iter()
+    .map(|x| {
+      TurtleTriple::new(
+        ontology.clone(),
+        TurtleTerm::Iri(String::from(OWL_IMPORTS)),
+        TurtleTerm::Iri(x.clone()),
+      )
+    })
+    .collect();
+  document.body.extend(statement_items(&triples));
+}
+
+// statement_items writes triples as statements, one per run of triples sharing a subject
+fn statement_items(triples: &[TurtleTriple]) -> Vec<TurtleBodyItem> {
+  let mut items: Vec<TurtleBodyItem> = vec![];
+  let mut subject: Option<&TurtleTerm> = None;
+  for t in triples.iter() {
+    if subject != Some(&t.subject) {
+      subject = Some(&t.subject);
+      items.push(TurtleBodyItem {
+        subject: Some(t.subject.to_string()),
+        predicate: VecDeque::new(),
+        span: None,
+      });
+    }
+    if let Some(item) = items.last_mut() {
+      let mut predicate = TurtlePredicate::from_raw(&t.predicate.to_string());
+      predicate
+        .object
+        .push_back(TurtleObject::from_raw(&t.object.to_string()));
+      item.predicate.push_back(predicate);
+    }
+  }
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const ROOT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/root#> .
+
+<http://example.org/root> a owl:Ontology ;
+  owl:imports <http://example.org/a> , <http://example.org/c> ;
+  rdfs:label "root" .
+:Robot rdfs:subClassOf <http://example.org/a#Agent> .
+"#;
+
+  const A: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix a: <http://example.org/a#> .
+
+<http://example.org/a> a owl:Ontology ; owl:imports <http://example.org/b> ; owl:versionInfo "1" .
+a:Agent a owl:Class ; rdfs:subClassOf [ owl:onProperty a:has_part ; owl:someValuesFrom a:Part ] .
+"#;
+
+  const B: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+<http://example.org/b> a owl:Ontology .
+<http://example.org/b#Part> a owl:Class ; rdfs:subClassOf [ owl:onProperty <http://example.org/b#of> ] .
+"#;
+
+  const C: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+
+<http://example.org/c> a owl:Ontology .
+<http://example.org/c#Thing> a owl:Class .
+<http://example.org/root#Robot> <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://example.org/a#Agent> .
+"#;
+
+  fn manager() -> std::io::Result<OntologyManager> {
+    let mut manager = OntologyManager::new();
+    for (iri, content) in [("a", A), ("b", B), ("c", C)] {
+      let document = load_turtle_document_from_str(content)?;
+      let key = format!("http://example.org/{}", iri);
+      manager.documents.insert(key, document);
+    }
+    Ok(manager)
+  }
+
+  #[test]
+  fn should_materialize_imports() -> std::io::Result<()> {
+    let manager = manager()?;
+    let mut document = load_turtle_document_from_str(ROOT)?;
+    let merged = materialize_imports(&mut document, &manager, &MaterializeOptions::default())?;
+    assert_eq!(
+      merged.inlined,
+      vec![
+        "http://example.org/a",
+        "http://example.org/c",
+        "http://example.org/b"
+      ]
+    );
+    assert!(merged.imports.is_empty());
+    assert!(document.imports().is_empty());
+    // the statement of `c` already in the root is not repeated
+    assert_eq!(merged.triples, 8);
+    let triples = document.triples();
+    assert_eq!(triples.len(), 11);
+    assert!(triples
+      .iter()
+      .all(|t| t.subject.as_iri() != Some("http://example.org/a")));
+    // the blank nodes of `a` and `b` stay distinct
+    let blank_nodes: BTreeSet<&TurtleTerm> = triples
+      .iter()
+      .map(|t| &t.subject)
+      .filter(|x| x.is_blank_node())
+      .collect();
+    assert_eq!(blank_nodes.len(), 2);
+    assert_eq!(
+      document.prefix_map().namespace("a"),
+      Some("http://example.org/a#")
+    );
+
+    // `b` stays imported when only `a` is inlined
+    let mut document = load_turtle_document_from_str(ROOT)?;
+    let options = MaterializeOptions::new(
+      vec![String::from("http://example.org/a")],
+      Some(String::from("http://purl.org/dc/terms/source")),
+    );
+    let merged = materialize_imports(&mut document, &manager, &options)?;
+    assert_eq!(merged.inlined, vec!["http://example.org/a"]);
+    assert_eq!(
+      document.imports(),
+      vec!["http://example.org/c", "http://example.org/b"]
+    );
+    let annotated = document.annotated_triples();
+    assert_eq!(annotated.len(), 2);
+    assert_eq!(
+      annotated[0].annotations,
+      vec![Annotation::new(
+        "http://purl.org/dc/terms/source",
+        TurtleTerm::Iri(String::from("http://example.org/a"))
+      )]
+    );
+
+    let options = MaterializeOptions::new(vec![String::from("http://example.org/z")], None);
+    let err = materialize_imports(&mut document, &manager, &options).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    Ok(())
+  }
+}
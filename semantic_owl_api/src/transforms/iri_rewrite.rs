@@ -0,0 +1,222 @@
+//! IRI rewrite module normalizes the IRIs of a document with rules
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleDocument, TurtlePredicate, TurtleTerm, TurtleTriple};
+use crate::transforms::skolem::rewrite;
+use serde::{Deserialize, Serialize};
+
+// path segment under which OBO ontologies publish their releases. e.g -> bfo/releases/2019-08-26/bfo.owl
+const RELEASES: &str = "releases";
+
+/// IriRewrite is a rule rewriting the IRIs it matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IriRewrite {
+  // replaces the leading `from` of an IRI with `to`.
+  // e.g -> http://purl.obolibrary.org/obo/ to http://mirror.example.org/obo/
+  Prefix { from: String, to: String },
+
+  // removes the version segments from the path of the IRIs starting with `namespace`.
+  // e.g -> http://purl.obolibrary.org/obo/bfo/2019-08-26/bfo.owl becomes http://purl.obolibrary.org/obo/bfo/bfo.owl
+  StripVersions { namespace: String },
+}
+
+impl IriRewrite {
+  pub fn prefix(from: &str, to: &str) -> IriRewrite {
+    IriRewrite::Prefix {
+      from: String::from(from),
+      to: String::from(to),
+    }
+  }
+
+  pub fn strip_versions(namespace: &str) -> IriRewrite {
+    IriRewrite::StripVersions {
+      namespace: String::from(namespace),
+    }
+  }
+
+  /// apply returns the IRI rewritten by the rule, or `None` if the rule does not match it
+  /// example
+  /// `strip_versions("http://purl.obolibrary.org/obo/")` rewrites
+  /// `http://purl.obolibrary.org/obo/bfo/releases/2019-08-26/bfo.owl` as `http://purl.obolibrary.org/obo/bfo/bfo.owl`
+  pub fn apply(&self, iri: &str) -> Option<String> {
+    match self {
+      IriRewrite::Prefix { from, to } => iri
+        .strip_prefix(from.as_str())
+        .map(|x| format!("{}{}", to, x)),
+      IriRewrite::StripVersions { namespace } => {
+        let path = iri.strip_prefix(namespace.as_str())?;
+        let segments: Vec<&str> = path.split('/').collect();
+        let mut kept: Vec<&str> = vec![];
+        for (idx, segment) in segments.iter().enumerate() {
+          // the last segment names the resource, never its version
+          let last = idx + 1 == segments.len();
+          let next_is_version = segments.get(idx + 1).is_some_and(|x| is_version(x));
+          match *segment {
+            x if !last && is_version(x) => {}
+            RELEASES if idx + 2 < segments.len() && next_is_version => {}
+            x => kept.push(x),
+          }
+        }
+        match kept.len() == segments.len() {
+          true => None,
+          false => Some(format!("{}{}", namespace, kept.join("/"))),
+        }
+      }
+    }
+  }
+}
+
+// is_version tells whether a path segment is a version or a release date. e.g -> 2019-08-26, v1.2
+fn is_version(segment: &str) -> bool {
+  let digits = segment.strip_prefix('v').unwrap_or(segment);
+  digits.starts_with(|c: char| c.is_ascii_digit())
+    && digits
+      .chars()
+      .all(|c| c.is_ascii_digit() || c == '.' || c == '-' || c == '_')
+}
+
+/// rewrite_iri applies every rule in turn to an IRI. It returns `None` if no rule matches it
+pub fn rewrite_iri(rules: &[IriRewrite], iri: &str) -> Option<String> {
+  let mut rewritten: Option<String> = None;
+  for rule in rules.iter() {
+    if let Some(x) = rule.apply(rewritten.as_deref().unwrap_or(iri)) {
+      rewritten = Some(x);
+    }
+  }
+  rewritten
+}
+
+// rewrite_term rewrites the IRIs of a term, including the datatypes of literals and the terms of
+// quoted triples
+fn rewrite_term(rules: &[IriRewrite], term: &TurtleTerm) -> Option<TurtleTerm> {
+  match term {
+    TurtleTerm::Iri(iri) => rewrite_iri(rules, iri).map(TurtleTerm::Iri),
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => rewrite_iri(rules, datatype).map(|datatype| TurtleTerm::Literal {
+      value: value.clone(),
+      datatype,
+      language: language.clone(),
+    }),
+    TurtleTerm::BlankNode(_) => None,
+    TurtleTerm::QuotedTriple(t) => {
+      let s = rewrite_term(rules, &t.subject);
+      let p = rewrite_term(rules, &t.predicate);
+      let o = rewrite_term(rules, &t.object);
+      if s.is_none() && p.is_none() && o.is_none() {
+        return None;
+      }
+      let triple = TurtleTriple::new(
+        s.unwrap_or_else(|| t.subject.clone()),
+        p.unwrap_or_else(|| t.predicate.clone()),
+        o.unwrap_or_else(|| t.object.clone()),
+      );
+      Some(TurtleTerm::QuotedTriple(Box::new(triple)))
+    }
+  }
+}
+
+/// rewrite_iris returns a copy of the document in which the IRIs matched by the rules are rewritten
+pub fn rewrite_iris(document: &TurtleDocument, rules: &[IriRewrite]) -> TurtleDocument {
+  let mut headers = document.headers.clone();
+  for h in headers.iter_mut() {
+    let iri = match &h.prefix_iri {
+      Some(x) => x.trim_start_matches('<').trim_end_matches('>'),
+      None => continue,
+    };
+    if let Some(rewritten) = rewrite_iri(rules, iri) {
+      h.raw_header = h.raw_header.as_ref().map(|x| x.replace(iri, &rewritten));
+      h.prefix_iri = Some(format!("<{}>", rewritten));
+    }
+  }
+  let mut rewritten = TurtleDocument::new();
+  rewritten.headers = headers;
+  let rewritten_map = rewritten.prefix_map();
+
+  // a raw term is kept when the rewritten prefixes already resolve it to its rewritten form
+  let f = |raw: &str, map: &PrefixMap| {
+    let term = map.resolve_term(raw)?;
+    let term = rewrite_term(rules, &term)?;
+    match rewritten_map.resolve_term(raw) {
+      Some(x) if x == term => None,
+      _ => Some(term.to_string()),
+    }
+  };
+  let mut result = rewrite(document, f);
+  let map = document.prefix_map();
+  for item in result.body.iter_mut() {
+    for p in item.predicate.iter_mut() {
+      let raw = match p.raw_predicate_object.as_deref().and_then(|x| f(x, &map)) {
+        Some(x) => x,
+        None => continue,
+      };
+      let objects = std::mem::take(&mut p.object);
+      *p = TurtlePredicate::from_raw(&raw);
+      p.object = objects;
+    }
+  }
+  result.headers = rewritten.headers;
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::{load_turtle_document_from_str_with_report, LoaderOptions};
+
+  const DOCUMENT: &str = r#"@prefix obo: <http://purl.obolibrary.org/obo/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix ex: <http://example.org/> .
+
+<http://purl.obolibrary.org/obo/bfo/releases/2019-08-26/bfo.owl> a owl:Ontology .
+obo:BFO_0000001 a owl:Class ; obo:IAO_0000115 "An entity"^^<http://purl.obolibrary.org/obo/string> .
+ex:JohnDoe ex:age 42 ; <http://purl.obolibrary.org/obo/RO_0000053> ex:Role .
+"#;
+
+  #[test]
+  fn should_rewrite_iris_on_load() -> std::io::Result<()> {
+    let options = LoaderOptions {
+      iri_rewrites: vec![
+        IriRewrite::prefix(
+          "http://purl.obolibrary.org/obo/",
+          "http://mirror.example.org/obo/",
+        ),
+        IriRewrite::strip_versions("http://mirror.example.org/obo/"),
+      ],
+      ..LoaderOptions::default()
+    };
+    let (document, _) = load_turtle_document_from_str_with_report(DOCUMENT, &options)?;
+    assert_eq!(
+      document.ontology_id().ontology_iri.as_deref(),
+      Some("http://mirror.example.org/obo/bfo/bfo.owl")
+    );
+    assert_eq!(
+      document.prefix_map().namespace("obo"),
+      Some("http://mirror.example.org/obo/")
+    );
+    // prefixed names are kept
+    assert_eq!(document.body[1].subject.as_deref(), Some("obo:BFO_0000001"));
+    let triples = document.triples();
+    assert!(triples
+      .iter()
+      .all(|t| !t.to_string().contains("http://purl.obolibrary.org")));
+    assert_eq!(
+      triples[2].object,
+      TurtleTerm::Literal {
+        value: String::from("An entity"),
+        datatype: String::from("http://mirror.example.org/obo/string"),
+        language: None,
+      }
+    );
+    assert_eq!(
+      triples[4].predicate.as_iri(),
+      Some("http://mirror.example.org/obo/RO_0000053")
+    );
+    assert_eq!(
+      IriRewrite::strip_versions("http://example.org/").apply("http://example.org/items/42"),
+      None
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,347 @@
+//! Literals module rewrites literals in the canonical lexical form of their datatype
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::transforms::skolem::rewrite;
+use crate::vocab::xsd;
+
+const XSD: &str = xsd::NAMESPACE;
+
+// integer datatypes, all of which share the canonical form of `xsd:integer`
+const INTEGER_TYPES: [&str; 13] = [
+  "integer",
+  "long",
+  "int",
+  "short",
+  "byte",
+  "nonNegativeInteger",
+  "positiveInteger",
+  "nonPositiveInteger",
+  "negativeInteger",
+  "unsignedLong",
+  "unsignedInt",
+  "unsignedShort",
+  "unsignedByte",
+];
+
+// datatypes whose whitespace is collapsed but whose lexical form is otherwise kept
+const TOKEN_TYPES: [&str; 6] = ["token", "language", "Name", "NCName", "NMTOKEN", "anyURI"];
+
+/// normalize_literals returns a copy of the document in which every literal is written in the
+/// canonical lexical form of its datatype, and language tags are lowercased. Literals of other
+/// datatypes, or whose lexical form is not valid, are kept as they are
+pub fn normalize_literals(document: &TurtleDocument) -> TurtleDocument {
+  rewrite(document, |raw, map| {
+    let term = map.resolve_term(raw)?;
+    normalize_literal(&term)
+      .filter(|x| *x != term)
+      .map(|x| x.to_string())
+  })
+}
+
+/// normalize_literal returns a literal in the canonical lexical form of its datatype, with its
+/// language tag lowercased. Terms which are not literals are not normalized
+/// example
+/// `"+007"^^xsd:int` returns Option of `"7"^^xsd:int`
+pub fn normalize_literal(term: &TurtleTerm) -> Option<TurtleTerm> {
+  match term {
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => Some(TurtleTerm::Literal {
+      value: canonical_lexical_form(value, datatype).unwrap_or_else(|| value.clone()),
+      datatype: datatype.clone(),
+      language: language.as_ref().map(|x| x.to_ascii_lowercase()),
+    }),
+    _ => None,
+  }
+}
+
+/// canonical_lexical_form returns the canonical lexical form of a value of an XSD datatype.
+/// Values of other datatypes, and values which are not valid for their datatype, have none
+/// example
+/// `canonical_lexical_form("1", "http://www.w3.org/2001/XMLSchema#boolean")` returns Option of `true`
+pub fn canonical_lexical_form(value: &str, datatype: &str) -> Option<String> {
+  let local = datatype.strip_prefix(XSD)?;
+  // every datatype but the string types collapses whitespace
+  let collapsed = value.split_whitespace().collect::<Vec<_>>().join(" ");
+  match local {
+    "normalizedString" => Some(value.replace(['\t', '\n', '\r'], " ")),
+    "boolean" => match collapsed.as_str() {
+      "true" | "1" => Some(String::from("true")),
+      "false" | "0" => Some(String::from("false")),
+      _ => None,
+    },
+    "decimal" => canonical_decimal(&collapsed),
+    "double" if is_floating_point(&collapsed) => collapsed
+      .parse::<f64>()
+      .ok()
+      .map(|x| canonical_floating_point(format!("{:E}", x), x.is_nan(), x.is_infinite())),
+    "float" if is_floating_point(&collapsed) => collapsed
+      .parse::<f32>()
+      .ok()
+      .map(|x| canonical_floating_point(format!("{:E}", x), x.is_nan(), x.is_infinite())),
+    "dateTime" | "dateTimeStamp" => canonical_date_time(&collapsed),
+    x if INTEGER_TYPES.contains(&x) => canonical_integer(&collapsed),
+    x if TOKEN_TYPES.contains(&x) => Some(collapsed),
+    _ => None,
+  }
+}
+
+// split_sign splits a number into its sign, `-` or nothing, and its digits
+fn split_sign(v: &str) -> (&str, &str) {
+  match v.strip_prefix('-') {
+    Some(digits) => ("-", digits),
+    None => ("", v.strip_prefix('+').unwrap_or(v)),
+  }
+}
+
+fn is_digits(v: &str) -> bool {
+  v.bytes().all(|b| b.is_ascii_digit())
+}
+
+// canonical_integer drops the `+` sign and the leading zeros of an integer
+fn canonical_integer(v: &str) -> Option<String> {
+  let (sign, digits) = split_sign(v);
+  if digits.is_empty() || !is_digits(digits) {
+    return None;
+  }
+  match digits.trim_start_matches('0') {
+    "" => Some(String::from("0")),
+    digits => Some(format!("{}{}", sign, digits)),
+  }
+}
+
+// canonical_decimal writes a decimal with at least one digit on each side of its point, without
+// leading or trailing zeros
+fn canonical_decimal(v: &str) -> Option<String> {
+  let (sign, digits) = split_sign(v);
+  let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+  if (int.is_empty() && frac.is_empty()) || !is_digits(int) || !is_digits(frac) {
+    return None;
+  }
+  let int = match int.trim_start_matches('0') {
+    "" => "0",
+    x => x,
+  };
+  let frac = match frac.trim_end_matches('0') {
+    "" => "0",
+    x => x,
+  };
+  let sign = match (int, frac) {
+    ("0", "0") => "",
+    _ => sign,
+  };
+  Some(format!("{}{}.{}", sign, int, frac))
+}
+
+// is_floating_point determines whether a value is a lexical form of `xsd:double` and `xsd:float`.
+// Rust also parses forms such as `inf` or `infinity` which XSD does not allow
+fn is_floating_point(v: &str) -> bool {
+  matches!(v, "INF" | "+INF" | "-INF" | "NaN")
+    || (v.bytes().any(|b| b.is_ascii_digit())
+      && v
+        .bytes()
+        .all(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.' | b'e' | b'E')))
+}
+
+// canonical_floating_point writes a floating point number, formatted with `{:E}`, with a single
+// digit before the point and at least one after it
+fn canonical_floating_point(formatted: String, is_nan: bool, is_infinite: bool) -> String {
+  match (is_nan, is_infinite, formatted.split_once('E')) {
+    (true, _, _) => String::from("NaN"),
+    (_, true, _) if formatted.starts_with('-') => String::from("-INF"),
+    (_, true, _) => String::from("INF"),
+    (_, _, Some((mantissa, exponent))) if !mantissa.contains('.') => {
+      format!("{}.0E{}", mantissa, exponent)
+    }
+    _ => formatted,
+  }
+}
+
+// canonical_date_time writes a date time in UTC, with a `Z` timezone, when it has a timezone.
+// Trailing zeros of the fractional seconds are dropped, and `24:00:00` is written as the first
+// instant of the next day
+fn canonical_date_time(v: &str) -> Option<String> {
+  let (date, time) = v.split_once('T')?;
+  let (time, offset) = split_timezone(time)?;
+
+  let (year_sign, date) = match date.strip_prefix('-') {
+    Some(x) => (-1, x),
+    None => (1, date),
+  };
+  let mut parts = date.split('-');
+  let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+  if parts.next().is_some() || year.len() < 4 || month.len() != 2 || day.len() != 2 {
+    return None;
+  }
+  let year = year_sign * number(year)?;
+  let (month, day) = (number(month)?, number(day)?);
+
+  let mut parts = time.split(':');
+  let (hour, minute, second) = (parts.next()?, parts.next()?, parts.next()?);
+  let (second, fraction) = second.split_once('.').unwrap_or((second, ""));
+  if parts.next().is_some() || hour.len() != 2 || minute.len() != 2 || second.len() != 2 {
+    return None;
+  }
+  let (hour, minute, second) = (number(hour)?, number(minute)?, number(second)?);
+  let fraction = fraction.trim_end_matches('0');
+  if !is_digits(fraction)
+    || !(1..=12).contains(&month)
+    || day < 1
+    || day > days_in_month(year, month)
+    || minute > 59
+    || second > 59
+    || hour > 24
+    || (hour == 24 && (minute, second, fraction) != (0, 0, ""))
+  {
+    return None;
+  }
+
+  let minutes = days_from_civil(year, month, day) * 1440 + hour * 60 + minute - offset.unwrap_or(0);
+  let (year, month, day) = civil_from_days(minutes.div_euclid(1440));
+  let minutes = minutes.rem_euclid(1440);
+  let year = match year < 0 {
+    true => format!("-{:04}", -year),
+    false => format!("{:04}", year),
+  };
+  let fraction = match fraction.is_empty() {
+    true => String::new(),
+    false => format!(".{}", fraction),
+  };
+  let timezone = offset.map(|_| "Z").unwrap_or_default();
+  Some(format!(
+    "{}-{:02}-{:02}T{:02}:{:02}:{:02}{}{}",
+    year,
+    month,
+    day,
+    minutes / 60,
+    minutes % 60,
+    second,
+    fraction,
+    timezone
+  ))
+}
+
+// split_timezone splits the time of a date time from its timezone, as an offset in minutes from UTC
+fn split_timezone(time: &str) -> Option<(&str, Option<i64>)> {
+  if let Some(time) = time.strip_suffix('Z') {
+    return Some((time, Some(0)));
+  }
+  let bytes = time.as_bytes();
+  match bytes.len().checked_sub(6) {
+    Some(idx) if matches!(bytes[idx], b'+' | b'-') && bytes[idx + 3] == b':' => {
+      let (hours, minutes) = (number(&time[idx + 1..idx + 3])?, number(&time[idx + 4..])?);
+      if hours > 14 || minutes > 59 {
+        return None;
+      }
+      let sign = if bytes[idx] == b'-' { -1 } else { 1 };
+      Some((&time[..idx], Some(sign * (hours * 60 + minutes))))
+    }
+    _ => Some((time, None)),
+  }
+}
+
+fn number(v: &str) -> Option<i64> {
+  match !v.is_empty() && is_digits(v) {
+    true => v.parse().ok(),
+    false => None,
+  }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+  let (next_year, next_month) = if month == 12 {
+    (year + 1, 1)
+  } else {
+    (year, month + 1)
+  };
+  days_from_civil(next_year, next_month, 1) - days_from_civil(year, month, 1)
+}
+
+// days_from_civil returns the number of days from 1970-01-01 to a date of the proleptic Gregorian
+// calendar. See http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let yoe = year - era * 400;
+  let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  era * 146097 + doe - 719468
+}
+
+// civil_from_days is the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let doe = days - era * 146097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  fn canonical(value: &str, datatype: &str) -> Option<String> {
+    canonical_lexical_form(value, &format!("{}{}", XSD, datatype))
+  }
+
+  #[test]
+  fn should_write_canonical_lexical_forms() {
+    assert_eq!(canonical("+007", "integer").as_deref(), Some("7"));
+    assert_eq!(canonical("-000", "int").as_deref(), Some("0"));
+    assert_eq!(canonical("1.2.3", "integer"), None);
+    assert_eq!(canonical(" 1 ", "boolean").as_deref(), Some("true"));
+    assert_eq!(canonical("010.500", "decimal").as_deref(), Some("10.5"));
+    assert_eq!(canonical("-.0", "decimal").as_deref(), Some("0.0"));
+    assert_eq!(canonical("5", "decimal").as_deref(), Some("5.0"));
+    assert_eq!(canonical("100", "double").as_deref(), Some("1.0E2"));
+    assert_eq!(canonical("0.00125", "float").as_deref(), Some("1.25E-3"));
+    assert_eq!(canonical("-INF", "double").as_deref(), Some("-INF"));
+    assert_eq!(canonical("inf", "double"), None);
+    assert_eq!(
+      canonical("2021-06-01T01:30:00.500+02:00", "dateTime").as_deref(),
+      Some("2021-05-31T23:30:00.5Z")
+    );
+    assert_eq!(
+      canonical("2020-12-31T24:00:00-00:00", "dateTime").as_deref(),
+      Some("2021-01-01T00:00:00Z")
+    );
+    assert_eq!(
+      canonical("2021-06-01T10:00:00", "dateTime").as_deref(),
+      Some("2021-06-01T10:00:00")
+    );
+    assert_eq!(canonical("2021-02-29T10:00:00Z", "dateTime"), None);
+    assert_eq!(canonical(" a  b ", "token").as_deref(), Some("a b"));
+    assert_eq!(canonical(" a ", "string"), None);
+  }
+
+  #[test]
+  fn should_hash_normalized_documents_equally() -> std::io::Result<()> {
+    let d0 = load_turtle_document_from_str(
+      r#"@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@prefix : <http://example.org/> .
+:a :count "01"^^xsd:integer ; :at "2021-06-01T10:00:00+02:00"^^xsd:dateTime ; :name "A"@EN-gb .
+"#,
+    )?;
+    let d1 = load_turtle_document_from_str(
+      r#"@prefix : <http://example.org/> .
+:a :count 1 ; :at "2021-06-01T08:00:00Z"^^<http://www.w3.org/2001/XMLSchema#dateTime> ; :name "A"@en-GB .
+"#,
+    )?;
+    assert_ne!(d0.canonical_hash(), d1.canonical_hash());
+    let (n0, n1) = (normalize_literals(&d0), normalize_literals(&d1));
+    assert_eq!(n0.canonical_hash(), n1.canonical_hash());
+    assert_eq!(n0.triples().len(), 3);
+    assert!(n0
+      .triples()
+      .iter()
+      .any(|t| t.object.to_string() == "\"A\"@en-gb"));
+    Ok(())
+  }
+}
@@ -0,0 +1,11 @@
+pub mod any_uri;
+pub mod canonical;
+pub mod declarations;
+pub mod defined_by;
+pub mod extraction;
+pub mod imports;
+pub mod iri_rewrite;
+pub mod literals;
+pub mod partition;
+pub mod profile;
+pub mod skolem;
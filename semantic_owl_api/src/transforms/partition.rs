@@ -0,0 +1,381 @@
+//! Partition module splits a document into its schema and its data
+use crate::analysis::namespaces::namespace_of;
+use crate::declarations::turtle::{
+  TurtleBodyItem, TurtleDocument, TurtleObject, TurtlePredicate, TurtleTerm, TurtleTriple,
+};
+use crate::vocab::{owl, rdf, rdfs};
+use crate::writer::format::{format_turtle, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const OWL_ONTOLOGY: &str = owl::ONTOLOGY.as_str();
+const OWL_IMPORTS: &str = owl::IMPORTS.as_str();
+const OWL_NAMED_INDIVIDUAL: &str = owl::NAMED_INDIVIDUAL.as_str();
+const OWL_THING: &str = owl::THING.as_str();
+const OWL_OBJECT_PROPERTY: &str = owl::OBJECT_PROPERTY.as_str();
+const OWL_DATATYPE_PROPERTY: &str = owl::DATATYPE_PROPERTY.as_str();
+const OWL_AXIOM: &str = owl::AXIOM.as_str();
+const OWL_ANNOTATED_SOURCE: &str = owl::ANNOTATED_SOURCE.as_str();
+
+// namespaces of the vocabulary types of schema entities, e.g `owl:Class` or `rdf:Property`
+const VOCABULARY_NAMESPACES: [&str; 3] = [rdf::NAMESPACE, rdfs::NAMESPACE, owl::NAMESPACE];
+
+// types of the anonymous nodes stating facts about individuals
+const ABOX_NODE_TYPES: [&str; 2] = [
+  owl::ALL_DIFFERENT.as_str(),
+  owl::NEGATIVE_PROPERTY_ASSERTION.as_str(),
+];
+
+// predicates relating individuals to each other
+const ABOX_PREDICATES: [&str; 2] = [owl::SAME_AS.as_str(), owl::DIFFERENT_FROM.as_str()];
+
+/// PartitionOptions tunes the documents of a partition
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PartitionOptions {
+  // IRI of the ontology of the ABox document. When set, the ABox declares the ontology along
+  // with an `owl:imports` of the ontology of the TBox, if the document has one.
+  // e.g -> http://www.ontologyrepository.com/CommonCoreOntologies/Data
+  pub abox_ontology: Option<String>,
+}
+
+impl PartitionOptions {
+  pub fn new(abox_ontology: Option<String>) -> PartitionOptions {
+    Self { abox_ontology }
+  }
+}
+
+/// Partition is a document split into its schema and its data. Both documents keep the headers
+/// of the document, and statements keep their original order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Partition {
+  // the ontology header, and the statements about classes and properties
+  pub tbox: TurtleDocument,
+
+  // the statements about individuals
+  pub abox: TurtleDocument,
+}
+
+impl Partition {
+  pub fn new(tbox: TurtleDocument, abox: TurtleDocument) -> Partition {
+    Self { tbox, abox }
+  }
+
+  /// write writes the TBox and the ABox to separate turtle files in the given style
+  /// example
+  /// `write("schema.ttl", "data.ttl", &Style::default())`
+  pub fn write(&self, tbox_path: &str, abox_path: &str, style: &Style) -> std::io::Result<()> {
+    std::fs::write(tbox_path, format_turtle(&self.tbox, style))?;
+    std::fs::write(abox_path, format_turtle(&self.abox, style))
+  }
+}
+
+/// partition splits a document into its TBox and its ABox. A subject belongs to the ABox when
+/// it is typed as an individual, i.e with `owl:NamedIndividual` or a class outside the RDF, RDFS
+/// and OWL vocabularies, or when it is related to another individual with `owl:sameAs`,
+/// `owl:differentFrom` or a declared object or data property. Other subjects, as well as those
+/// typed with a vocabulary class such as `owl:Class`, belong to the TBox.
+///
+/// Nested blank nodes follow the statement referring to them, and axiom annotations follow their
+/// annotated source
+pub fn partition(document: &TurtleDocument, options: &PartitionOptions) -> Partition {
+  let map = document.prefix_map();
+  let triples = document.triples();
+  let boxes = Boxes::new(&triples);
+
+  let mut tbox = TurtleDocument::new();
+  let mut abox = TurtleDocument::new();
+  tbox.headers = document.headers.clone();
+  abox.headers = document.headers.clone();
+  if let Some(iri) = &options.abox_ontology {
+    abox
+      .body
+      .push_back(ontology_item(iri, document.ontology_id().ontology_iri));
+  }
+  for item in document.body.iter() {
+    let subject = item.subject.as_deref().and_then(|x| map.resolve_term(x));
+    match subject {
+      Some(s) if boxes.is_abox(&s) => abox.body.push_back(item.clone()),
+      _ => tbox.body.push_back(item.clone()),
+    }
+  }
+  Partition::new(tbox, abox)
+}
+
+// Boxes decides the partition of the subjects of a set of triples
+struct Boxes<'a> {
+  types: HashMap<&'a TurtleTerm, Vec<&'a str>>,
+
+  // the subject referring to each blank node, and the annotated source of each axiom annotation
+  parents: HashMap<&'a TurtleTerm, &'a TurtleTerm>,
+
+  // subjects related to another individual
+  related: BTreeSet<&'a TurtleTerm>,
+}
+
+impl<'a> Boxes<'a> {
+  fn new(triples: &'a [TurtleTriple]) -> Boxes<'a> {
+    let mut types: HashMap<&TurtleTerm, Vec<&str>> = HashMap::new();
+    for t in triples.iter() {
+      if let (Some(RDF_TYPE), Some(o)) = (t.predicate.as_iri(), t.object.as_iri()) {
+        types.entry(&t.subject).or_default().push(o);
+      }
+    }
+    let properties: BTreeSet<&TurtleTerm> = triples
+      .iter()
+      .filter(|t| {
+        t.predicate.as_iri() == Some(RDF_TYPE)
+          && matches!(
+            t.object.as_iri(),
+            Some(OWL_OBJECT_PROPERTY) | Some(OWL_DATATYPE_PROPERTY)
+          )
+      })
+      .map(|t| &t.subject)
+      .collect();
+
+    let mut related: BTreeSet<&TurtleTerm> = BTreeSet::new();
+    for t in triples.iter() {
+      let relates = t
+        .predicate
+        .as_iri()
+        .map(|x| ABOX_PREDICATES.contains(&x))
+        .unwrap_or(false)
+        || properties.contains(&t.predicate);
+      if relates {
+        related.insert(&t.subject);
+      }
+    }
+    Self {
+      parents: parents_of(triples, &types),
+      types,
+      related,
+    }
+  }
+
+  // is_abox determines whether a subject belongs to the ABox. Blank nodes are decided by the
+  // subject they are nested in
+  fn is_abox(&self, subject: &TurtleTerm) -> bool {
+    let root = root_of(&self.parents, subject);
+    let types = self.types.get(root).map(|x| x.as_slice()).unwrap_or(&[]);
+    let is_vocabulary = |x: &str| VOCABULARY_NAMESPACES.iter().any(|ns| x.starts_with(ns));
+    if types
+      .iter()
+      .any(|x| ABOX_NODE_TYPES.contains(x) || *x == OWL_NAMED_INDIVIDUAL)
+    {
+      return true;
+    }
+    if types.iter().any(|x| is_vocabulary(x) && *x != OWL_THING) {
+      return false;
+    }
+    !types.is_empty() || self.related.contains(root)
+  }
+}
+
+// parents_of returns the subject referring to each blank node, and the annotated source of each
+// axiom annotation, given the types of the subjects
+fn parents_of<'a>(
+  triples: &'a [TurtleTriple],
+  types: &HashMap<&'a TurtleTerm, Vec<&'a str>>,
+) -> HashMap<&'a TurtleTerm, &'a TurtleTerm> {
+  let mut parents: HashMap<&TurtleTerm, &TurtleTerm> = HashMap::new();
+  for t in triples.iter() {
+    let is_axiom = types
+      .get(&t.subject)
+      .map(|x| x.contains(&OWL_AXIOM))
+      .unwrap_or(false);
+    if is_axiom && t.predicate.as_iri() == Some(OWL_ANNOTATED_SOURCE) {
+      parents.insert(&t.subject, &t.object);
+    } else if t.object.is_blank_node() {
+      parents.entry(&t.object).or_insert(&t.subject);
+    }
+  }
+  parents
+}
+
+// root_of returns the subject a blank node is nested in, or the subject itself when it is not
+// a nested blank node
+fn root_of<'a>(
+  parents: &HashMap<&'a TurtleTerm, &'a TurtleTerm>,
+  subject: &'a TurtleTerm,
+) -> &'a TurtleTerm {
+  let mut root = subject;
+  let mut seen: BTreeSet<&TurtleTerm> = BTreeSet::new();
+  while let Some(parent) = parents.get(root) {
+    // blank nodes referring to each other are decided by the first one met twice
+    if !root.is_blank_node() || !seen.insert(root) {
+      break;
+    }
+    root = parent;
+  }
+  root
+}
+
+impl TurtleDocument {
+  /// partition_by_namespace splits the document into one document per namespace of its subjects,
+  /// keyed by namespace IRI. Every document keeps the headers of the document, and statements keep
+  /// their original order. Nested blank nodes follow the statement referring to them and axiom
+  /// annotations follow their annotated source. Statements about blank nodes nested in no
+  /// statement about an IRI, e.g `owl:AllDifferent` axioms, are keyed by the empty string
+  /// example
+  /// `partition_by_namespace()["http://www.ontologyrepository.com/CommonCoreOntologies/"]`
+  pub fn partition_by_namespace(&self) -> BTreeMap<String, TurtleDocument> {
+    let map = self.prefix_map();
+    let triples = self.triples();
+    let mut types: HashMap<&TurtleTerm, Vec<&str>> = HashMap::new();
+    for t in triples.iter() {
+      if let (Some(RDF_TYPE), Some(o)) = (t.predicate.as_iri(), t.object.as_iri()) {
+        types.entry(&t.subject).or_default().push(o);
+      }
+    }
+    let parents = parents_of(&triples, &types);
+
+    let mut documents: BTreeMap<String, TurtleDocument> = BTreeMap::new();
+    for item in self.body.iter() {
+      let subject = item.subject.as_deref().and_then(|x| map.resolve_term(x));
+      let namespace = match &subject {
+        Some(s) => root_of(&parents, s).as_iri().map(namespace_of),
+        None => None,
+      };
+      documents
+        .entry(String::from(namespace.unwrap_or_default()))
+        .or_insert_with(|| TurtleDocument {
+          headers: self.headers.clone(),
+          ..TurtleDocument::new()
+        })
+        .body
+        .push_back(item.clone());
+    }
+    documents
+  }
+}
+
+// ontology_item returns the statement declaring an ontology, importing another ontology if any
+fn ontology_item(iri: &str, imports: Option<String>) -> TurtleBodyItem {
+  let mut predicates = VecDeque::new();
+  let mut property = |predicate: &str, object: &str| {
+    let mut p = TurtlePredicate::from_raw(&format!("<{}>", predicate));
+    p.object
+      .push_back(TurtleObject::from_raw(&format!("<{}>", object)));
+    predicates.push_back(p);
+  };
+  property(RDF_TYPE, OWL_ONTOLOGY);
+  if let Some(x) = imports {
+    property(OWL_IMPORTS, &x);
+  }
+  TurtleBodyItem {
+    subject: Some(format!("<{}>", iri)),
+    predicate: predicates,
+    span: None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+<http://example.org/schema> a owl:Ontology .
+ex:Person a owl:Class ; rdfs:subClassOf [ a owl:Restriction ; owl:onProperty ex:knows ; owl:someValuesFrom ex:Person ] .
+ex:knows a owl:ObjectProperty .
+ex:JohnDoe a ex:Person ; ex:knows ex:JaneDoe .
+ex:JaneDoe a owl:NamedIndividual ; rdfs:label "Jane" .
+ex:JDoe ex:knows ex:JohnDoe .
+[ a owl:AllDifferent ; owl:members ( ex:JohnDoe ex:JaneDoe ) ] .
+[ a owl:Axiom ; owl:annotatedSource ex:JaneDoe ; owl:annotatedProperty rdfs:label ; owl:annotatedTarget "Jane" ; rdfs:comment "from the registry" ] .
+ex:Person rdfs:comment "a human being" .
+"#;
+
+  fn subjects(document: &TurtleDocument) -> Vec<&str> {
+    document
+      .body
+      .iter()
+      .filter_map(|x| x.subject.as_deref())
+      .filter(|x| !x.starts_with("_:"))
+      .collect()
+  }
+
+  #[test]
+  fn should_partition_tbox_and_abox() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let result = partition(&document, &PartitionOptions::default());
+    assert_eq!(
+      subjects(&result.tbox),
+      vec![
+        "<http://example.org/schema>",
+        "ex:Person",
+        "ex:knows",
+        "ex:Person"
+      ]
+    );
+    assert_eq!(
+      subjects(&result.abox),
+      vec!["ex:JohnDoe", "ex:JaneDoe", "ex:JDoe"]
+    );
+    assert_eq!(
+      result.tbox.body.len() + result.abox.body.len(),
+      document.body.len()
+    );
+    assert_eq!(
+      result.tbox.triples().len() + result.abox.triples().len(),
+      document.triples().len()
+    );
+    // the individuals, the list of different members and the axiom annotation
+    assert_eq!(result.abox.triples().len(), 16);
+    assert_eq!(result.abox.headers, document.headers);
+    Ok(())
+  }
+
+  #[test]
+  fn should_partition_by_namespace() -> std::io::Result<()> {
+    let mut document = load_turtle_document_from_str(DOCUMENT)?;
+    let extra = load_turtle_document_from_str(
+      "@prefix ex: <http://example.org/> .\n<http://example.org/people/Jim> a ex:Person .\n",
+    )?;
+    document.body.extend(extra.body);
+    let modules = document.partition_by_namespace();
+    assert_eq!(
+      modules.keys().collect::<Vec<_>>(),
+      vec!["", "http://example.org/", "http://example.org/people/"]
+    );
+    assert_eq!(
+      subjects(&modules["http://example.org/people/"]),
+      vec!["<http://example.org/people/Jim>"]
+    );
+    // the restriction and the axiom annotation follow their subjects
+    let module = &modules["http://example.org/"];
+    assert_eq!(module.headers, document.headers);
+    assert_eq!(module.triples().len(), document.triples().len() - 7);
+    // the list of different members
+    assert_eq!(modules[""].triples().len(), 6);
+    Ok(())
+  }
+
+  #[test]
+  fn should_import_tbox_into_abox() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let options = PartitionOptions::new(Some(String::from("http://example.org/data")));
+    let result = partition(&document, &options);
+    assert_eq!(
+      result.abox.ontology_id().ontology_iri.as_deref(),
+      Some("http://example.org/data")
+    );
+    assert_eq!(result.abox.imports(), vec!["http://example.org/schema"]);
+
+    let dir = std::env::temp_dir();
+    let tbox = dir.join(format!("semantic_owl_tbox_{}.ttl", std::process::id()));
+    let abox = dir.join(format!("semantic_owl_abox_{}.ttl", std::process::id()));
+    let (tbox, abox) = (tbox.to_str().unwrap(), abox.to_str().unwrap());
+    result.write(tbox, abox, &Style::default())?;
+    let written = (std::fs::read_to_string(tbox), std::fs::read_to_string(abox));
+    std::fs::remove_file(tbox)?;
+    std::fs::remove_file(abox)?;
+    let reloaded = load_turtle_document_from_str(&written.1?)?;
+    assert_eq!(reloaded.triples().len(), result.abox.triples().len());
+    assert!(written.0?.contains("owl:Class"));
+    Ok(())
+  }
+}
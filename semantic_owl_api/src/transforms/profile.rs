@@ -0,0 +1,245 @@
+//! Profile module extracts the largest subset of a document within the OWL 2 EL profile
+use crate::analysis::validation::Violation;
+use crate::declarations::graph::TripleGraph;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::vocab::{owl, rdf};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const NOT_IN_EL_PROFILE: &str = "NotInElProfile";
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const OWL_ONE_OF: &str = owl::ONE_OF.as_str();
+const OWL_FUNCTIONAL_PROPERTY: &str = owl::FUNCTIONAL_PROPERTY.as_str();
+const OWL_DATATYPE_PROPERTY: &str = owl::DATATYPE_PROPERTY.as_str();
+
+// predicates of the constructs the EL profile leaves out
+const NON_EL_PREDICATES: [&str; 15] = [
+  owl::UNION_OF.as_str(),
+  owl::COMPLEMENT_OF.as_str(),
+  owl::ALL_VALUES_FROM.as_str(),
+  owl::CARDINALITY.as_str(),
+  owl::MIN_CARDINALITY.as_str(),
+  owl::MAX_CARDINALITY.as_str(),
+  owl::QUALIFIED_CARDINALITY.as_str(),
+  owl::MIN_QUALIFIED_CARDINALITY.as_str(),
+  owl::MAX_QUALIFIED_CARDINALITY.as_str(),
+  owl::DISJOINT_UNION_OF.as_str(),
+  owl::PROPERTY_DISJOINT_WITH.as_str(),
+  owl::INVERSE_OF.as_str(),
+  owl::DATATYPE_COMPLEMENT_OF.as_str(),
+  owl::WITH_RESTRICTIONS.as_str(),
+  owl::ON_PROPERTIES.as_str(),
+];
+
+// types of the axioms the EL profile leaves out. Functional properties are only allowed for data
+// properties
+const NON_EL_TYPES: [&str; 6] = [
+  owl::INVERSE_FUNCTIONAL_PROPERTY.as_str(),
+  owl::SYMMETRIC_PROPERTY.as_str(),
+  owl::ASYMMETRIC_PROPERTY.as_str(),
+  owl::IRREFLEXIVE_PROPERTY.as_str(),
+  owl::ALL_DISJOINT_PROPERTIES.as_str(),
+  owl::NEGATIVE_PROPERTY_ASSERTION.as_str(),
+];
+
+/// ProfileSubset is the subset of a document within a profile along with the axioms left out
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ProfileSubset {
+  pub document: TurtleDocument,
+
+  // one violation per axiom left out, holding its statements
+  pub dropped: Vec<Violation>,
+}
+
+impl ProfileSubset {
+  pub fn new(document: TurtleDocument, dropped: Vec<Violation>) -> ProfileSubset {
+    Self { document, dropped }
+  }
+}
+
+/// el_subset returns a new document without the axioms of the document using constructs outside
+/// the OWL 2 EL profile, e.g unions, universal restrictions, cardinalities or inverse properties.
+/// The headers of the document are kept as they are and statements keep their original order
+pub fn el_subset(document: &TurtleDocument) -> ProfileSubset {
+  let graph = document.graph();
+  let data_properties: BTreeSet<&TurtleTerm> = graph
+    .triples()
+    .filter(|t| t.predicate.as_iri() == Some(RDF_TYPE))
+    .filter(|t| t.object.as_iri() == Some(OWL_DATATYPE_PROPERTY))
+    .map(|t| &t.subject)
+    .collect();
+  let nested: BTreeSet<&TurtleTerm> = graph
+    .triples()
+    .filter(|t| t.object.is_blank_node())
+    .map(|t| &t.object)
+    .collect();
+
+  // axioms start with a statement about an entity, or about a blank node nested in no statement
+  let mut dropped = vec![];
+  let mut dropped_roots: BTreeSet<&TurtleTriple> = BTreeSet::new();
+  let mut dropped_nodes: BTreeSet<&TurtleTerm> = BTreeSet::new();
+  for root in graph.triples().filter(|t| !nested.contains(&t.subject)) {
+    let (statements, nodes) = axiom_of(&graph, root);
+    let reasons: BTreeSet<String> = statements
+      .iter()
+      .filter_map(|t| non_el_construct(&graph, &data_properties, t))
+      .collect();
+    if reasons.is_empty() {
+      continue;
+    }
+    let message = format!(
+      "{} is outside the OWL 2 EL profile",
+      reasons.into_iter().collect::<Vec<String>>().join(", ")
+    );
+    dropped.push(Violation::new(
+      NOT_IN_EL_PROFILE,
+      root.subject.as_iri().unwrap_or_default(),
+      message,
+      statements.into_iter().cloned().collect(),
+    ));
+    dropped_roots.insert(root);
+    dropped_nodes.extend(nodes);
+  }
+
+  let map = document.prefix_map();
+  let mut subset = TurtleDocument::new();
+  subset.headers = document.headers.clone();
+  for item in document.body.iter() {
+    let subject = match item.subject.as_deref().and_then(|x| map.resolve_term(x)) {
+      Some(x) => x,
+      None => continue,
+    };
+    if dropped_nodes.contains(&subject) {
+      continue;
+    }
+    let mut item = item.clone();
+    for p in item.predicate.iter_mut() {
+      let predicate = match p
+        .raw_predicate_object
+        .as_deref()
+        .and_then(|x| map.resolve_term(x))
+      {
+        Some(x) => x,
+        None => continue,
+      };
+      p.object.retain(
+        |o| match o.raw_object.as_deref().and_then(|x| map.resolve_term(x)) {
+          Some(object) => {
+            let t = TurtleTriple::new(subject.clone(), predicate.clone(), object);
+            !dropped_roots.contains(&t)
+          }
+          None => true,
+        },
+      );
+    }
+    item.predicate.retain(|p| !p.object.is_empty());
+    if !item.predicate.is_empty() {
+      subset.body.push_back(item);
+    }
+  }
+  ProfileSubset::new(subset, dropped)
+}
+
+// axiom_of returns the statements of the axiom starting with `root`, i.e `root` and the
+// statements about the blank nodes nested in it, along with those blank nodes
+fn axiom_of<'a>(
+  graph: &'a TripleGraph,
+  root: &'a TurtleTriple,
+) -> (Vec<&'a TurtleTriple>, Vec<&'a TurtleTerm>) {
+  let mut statements = vec![root];
+  let mut nodes: Vec<&TurtleTerm> = vec![];
+  let mut pending: Vec<&TurtleTerm> = vec![&root.object];
+  while let Some(node) = pending.pop() {
+    if !node.is_blank_node() || nodes.contains(&node) {
+      continue;
+    }
+    nodes.push(node);
+    for t in graph.triples_matching(Some(node), None, None) {
+      statements.push(t);
+      pending.push(&t.object);
+    }
+  }
+  (statements, nodes)
+}
+
+// non_el_construct returns the construct outside the EL profile a statement uses, if any.
+// e.g -> owl:unionOf
+fn non_el_construct(
+  graph: &TripleGraph,
+  data_properties: &BTreeSet<&TurtleTerm>,
+  t: &TurtleTriple,
+) -> Option<String> {
+  let predicate = t.predicate.as_iri()?;
+  if NON_EL_PREDICATES.contains(&predicate) {
+    return Some(format!("<{}>", predicate));
+  }
+  // enumerations are allowed with a single individual
+  if predicate == OWL_ONE_OF && graph.list(&t.object).map(|x| x.len()) != Some(1) {
+    return Some(format!("<{}> with several individuals", predicate));
+  }
+  match (predicate, t.object.as_iri()) {
+    (RDF_TYPE, Some(kind)) if NON_EL_TYPES.contains(&kind) => Some(format!("<{}>", kind)),
+    (RDF_TYPE, Some(OWL_FUNCTIONAL_PROPERTY)) if !data_properties.contains(&t.subject) => {
+      Some(format!("<{}> object property", OWL_FUNCTIONAL_PROPERTY))
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix ex: <http://example.org/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+ex:Tank a owl:Class ;
+  rdfs:subClassOf ex:Vehicle ,
+    [ a owl:Restriction ; owl:onProperty ex:has_part ; owl:someValuesFrom ex:Turret ] ,
+    [ a owl:Restriction ; owl:onProperty ex:has_part ; owl:allValuesFrom ex:Component ] .
+ex:Crew owl:equivalentClass [ owl:unionOf ( ex:Pilot ex:Gunner ) ] .
+ex:Vehicle owl:equivalentClass [ owl:intersectionOf ( ex:Machine [ a owl:Restriction ; owl:onProperty ex:has_part ; owl:someValuesFrom ex:Engine ] ) ] .
+ex:has_part a owl:ObjectProperty , owl:TransitiveProperty , owl:FunctionalProperty .
+ex:has_serial a owl:DatatypeProperty , owl:FunctionalProperty .
+ex:part_of a owl:ObjectProperty ; owl:inverseOf ex:has_part .
+"#;
+
+  #[test]
+  fn should_extract_el_subset() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let subset = el_subset(&document);
+    let ex = |x: &str| format!("http://example.org/{}", x);
+    let iris: Vec<&str> = subset.dropped.iter().map(|x| x.iri.as_str()).collect();
+    assert_eq!(
+      iris,
+      vec![ex("Tank"), ex("Crew"), ex("has_part"), ex("part_of")]
+    );
+    assert!(subset.dropped[0].message.contains("allValuesFrom"));
+    assert_eq!(subset.dropped[0].statements.len(), 4);
+
+    let triples = subset.document.triples();
+    let kept = document.triples().len()
+      - subset
+        .dropped
+        .iter()
+        .map(|x| x.statements.len())
+        .sum::<usize>();
+    assert_eq!(triples.len(), kept);
+    // the other statements about the entities are kept
+    assert!(subset.document.is_subclass_of(&ex("Tank"), &ex("Vehicle")));
+    assert!(triples
+      .iter()
+      .any(|t| t.object.as_iri() == Some(ex("Turret").as_str())));
+    assert!(triples
+      .iter()
+      .any(|t| t.object.as_iri() == Some(ex("Engine").as_str())));
+    assert!(!triples
+      .iter()
+      .any(|t| t.subject.as_iri() == Some(ex("Crew").as_str())));
+    assert!(el_subset(&subset.document).dropped.is_empty());
+    Ok(())
+  }
+}
@@ -0,0 +1,179 @@
+//! Skolem module replaces blank nodes with well-known IRIs and back
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleBodyItem, TurtleDocument, TurtleObject, TurtleTerm};
+
+/// path segment identifying skolem IRIs
+pub const WELL_KNOWN_GENID: &str = "/.well-known/genid/";
+
+// number of hex digits of the canonical hash of a document kept as its discriminator
+const DISCRIMINATOR_LENGTH: usize = 16;
+
+/// skolem_iri returns the skolem IRI, without angle brackets, of a blank node label within the
+/// document identified by `discriminator`
+/// example
+/// `skolem_iri("http://example.org", "3f2a", "genid1")` returns `http://example.org/.well-known/genid/3f2a/genid1`
+pub fn skolem_iri(authority: &str, discriminator: &str, label: &str) -> String {
+  format!(
+    "{}{}{}/{}",
+    authority.trim_end_matches('/'),
+    WELL_KNOWN_GENID,
+    discriminator,
+    label
+  )
+}
+
+/// skolem_discriminator returns the segment identifying a document in its skolem IRIs: the
+/// leading digits of its canonical hash, which is the same for isomorphic documents
+pub fn skolem_discriminator(document: &TurtleDocument) -> String {
+  let mut hash = document.canonical_hash();
+  hash.truncate(DISCRIMINATOR_LENGTH);
+  hash
+}
+
+/// skolem_label returns the blank node label of a skolem IRI, with or without angle brackets:
+/// the last segment of its path
+/// example
+/// `http://example.org/.well-known/genid/3f2a/genid1` returns Option of `genid1`
+pub fn skolem_label(iri: &str) -> Option<&str> {
+  let iri = iri.trim_start_matches('<').trim_end_matches('>');
+  let idx = iri.find(WELL_KNOWN_GENID)?;
+  let path = &iri[idx + WELL_KNOWN_GENID.len()..];
+  let label = path.rsplit('/').next().unwrap_or_default();
+  match label.is_empty() {
+    true => None,
+    false => Some(label),
+  }
+}
+
+/// skolemize returns a copy of the document in which every blank node, as subject or object,
+/// is replaced with a skolem IRI minted under `authority` and the discriminator of the document
+/// example
+/// `_:genid1` with authority `http://example.org` becomes `<http://example.org/.well-known/genid/3f2a…/genid1>`
+pub fn skolemize(document: &TurtleDocument, authority: &str) -> TurtleDocument {
+  let discriminator = skolem_discriminator(document);
+  rewrite(document, |raw, _| {
+    let label = raw.strip_prefix("_:")?;
+    Some(format!(
+      "<{}>",
+      skolem_iri(authority, &discriminator, label)
+    ))
+  })
+}
+
+/// deskolemize is the inverse of `skolemize`. Every skolem IRI, whatever its authority,
+/// is replaced with a blank node labeled after the last segment of the IRI
+pub fn deskolemize(document: &TurtleDocument) -> TurtleDocument {
+  rewrite(document, |raw, map| match map.resolve_term(raw)? {
+    TurtleTerm::Iri(iri) => skolem_label(&iri).map(|label| format!("_:{}", label)),
+    _ => None,
+  })
+}
+
+// rewrite copies the document replacing the subjects and objects for which `f` returns a new raw form
+pub(crate) fn rewrite<F>(document: &TurtleDocument, f: F) -> TurtleDocument
+where
+  F: Fn(&str, &PrefixMap) -> Option<String>,
+{
+  let map = document.prefix_map();
+  let mut result = TurtleDocument::new();
+  result.headers = document.headers.clone();
+  for item in document.body.iter() {
+    let mut item: TurtleBodyItem = item.clone();
+    if let Some(s) = item.subject.as_deref().and_then(|x| f(x, &map)) {
+      item.subject = Some(s);
+    }
+    for p in item.predicate.iter_mut() {
+      for o in p.object.iter_mut() {
+        if let Some(x) = o.raw_object.as_deref().and_then(|x| f(x, &map)) {
+          *o = TurtleObject::from_raw(&x);
+        }
+      }
+    }
+    result.body.push_back(item);
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Acceleration a owl:Class ;
+                 rdfs:subClassOf [ a owl:Restriction ;
+                                   owl:onProperty cco:has_process_part ;
+                                   owl:someValuesFrom cco:Velocity ] .
+"#;
+
+  #[test]
+  fn should_skolemize_blank_nodes() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let skolemized = skolemize(&document, "http://example.org/");
+    let triples = skolemized.triples();
+    assert_eq!(triples.len(), document.triples().len());
+    assert!(triples
+      .iter()
+      .all(|t| !t.subject.is_blank_node() && !t.object.is_blank_node()));
+    assert!(triples.iter().any(|t| t
+      .subject
+      .as_iri()
+      .map(|x| x.starts_with("http://example.org/.well-known/genid/"))
+      .unwrap_or(false)));
+    Ok(())
+  }
+
+  #[test]
+  fn should_round_trip_skolemization() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let round_trip = deskolemize(&skolemize(&document, "http://example.org"));
+    assert_eq!(round_trip.triples(), document.triples());
+    assert_eq!(round_trip.canonical_hash(), document.canonical_hash());
+    Ok(())
+  }
+
+  #[test]
+  fn should_discriminate_documents() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let other = load_turtle_document_from_str(
+      "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n[ a owl:Restriction ] .",
+    )?;
+    let subjects = |x: &TurtleDocument| -> Vec<String> {
+      skolemize(x, "http://example.org")
+        .triples()
+        .into_iter()
+        .filter_map(|t| t.subject.as_iri().map(String::from))
+        .filter(|x| skolem_label(x).is_some())
+        .collect()
+    };
+    let minted = subjects(&document);
+    assert!(!minted.is_empty());
+    assert!(subjects(&other).iter().all(|x| !minted.contains(x)));
+
+    // the discriminator only depends on the content of a document
+    assert_eq!(minted, subjects(&load_turtle_document_from_str(DOCUMENT)?));
+    assert_eq!(
+      skolem_iri("http://example.org/", "3f2a", "genid1"),
+      "http://example.org/.well-known/genid/3f2a/genid1"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_find_skolem_label() {
+    assert_eq!(
+      skolem_label("<http://example.org/.well-known/genid/3f2a/b0>"),
+      Some("b0")
+    );
+    assert_eq!(
+      skolem_label("<http://example.org/.well-known/genid/b0>"),
+      Some("b0")
+    );
+    assert_eq!(skolem_label("http://example.org/.well-known/genid/"), None);
+    assert_eq!(skolem_label("http://example.org/b0"), None);
+  }
+}
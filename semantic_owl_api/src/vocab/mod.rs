@@ -0,0 +1,119 @@
+//! Vocab module holds the IRIs of the RDF, RDFS, OWL and XSD terms as typed constants
+use crate::declarations::turtle::TurtleTerm;
+use std::fmt;
+
+// vocabulary declares the namespace of a vocabulary and the IRI of each of its terms
+macro_rules! vocabulary {
+  ($namespace:literal, $($name:ident => $local:literal),* $(,)?) => {
+    use crate::vocab::Iri;
+
+    /// namespace IRI of the vocabulary
+    pub const NAMESPACE: &str = $namespace;
+
+    $(
+      #[doc = concat!("`", $namespace, $local, "`")]
+      pub const $name: Iri = Iri::new(concat!($namespace, $local));
+    )*
+  };
+}
+
+pub mod owl;
+pub mod rdf;
+pub mod rdfs;
+pub mod xsd;
+
+/// Iri is the IRI of a term of a well known vocabulary, without angle brackets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Iri(&'static str);
+
+impl Iri {
+  pub const fn new(iri: &'static str) -> Iri {
+    Self(iri)
+  }
+
+  /// as_str returns the IRI as a string slice
+  pub const fn as_str(&self) -> &'static str {
+    self.0
+  }
+
+  /// local_name returns the part of the IRI following its namespace
+  /// example
+  /// `owl::CLASS.local_name()` is `Class`
+  pub fn local_name(&self) -> &'static str {
+    match self.0.rfind(['#', '/']) {
+      Some(idx) => &self.0[idx + 1..],
+      None => self.0,
+    }
+  }
+}
+
+impl AsRef<str> for Iri {
+  fn as_ref(&self) -> &str {
+    self.0
+  }
+}
+
+impl PartialEq<str> for Iri {
+  fn eq(&self, other: &str) -> bool {
+    self.0 == other
+  }
+}
+
+impl PartialEq<&str> for Iri {
+  fn eq(&self, other: &&str) -> bool {
+    self.0 == *other
+  }
+}
+
+impl PartialEq<String> for Iri {
+  fn eq(&self, other: &String) -> bool {
+    self.0 == other.as_str()
+  }
+}
+
+impl From<Iri> for String {
+  fn from(iri: Iri) -> String {
+    String::from(iri.0)
+  }
+}
+
+impl From<Iri> for TurtleTerm {
+  fn from(iri: Iri) -> TurtleTerm {
+    TurtleTerm::Iri(String::from(iri.0))
+  }
+}
+
+/// formats the IRI without angle brackets
+impl fmt::Display for Iri {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  #[test]
+  fn should_compare_vocabulary_terms() -> std::io::Result<()> {
+    assert_eq!(owl::CLASS, "http://www.w3.org/2002/07/owl#Class");
+    assert_eq!(rdfs::SUB_CLASS_OF.local_name(), "subClassOf");
+    assert_eq!(
+      xsd::STRING.to_string(),
+      "http://www.w3.org/2001/XMLSchema#string"
+    );
+    assert!(rdf::TYPE.as_str().starts_with(rdf::NAMESPACE));
+
+    let document = load_turtle_document_from_str(
+      "@prefix owl: <http://www.w3.org/2002/07/owl#> .\n<http://example.org/A> a owl:Class .\n",
+    )?;
+    let classes = document
+      .triples()
+      .into_iter()
+      .filter(|t| t.predicate == rdf::TYPE.into() && t.object == owl::CLASS.into())
+      .count();
+    assert_eq!(classes, 1);
+    Ok(())
+  }
+}
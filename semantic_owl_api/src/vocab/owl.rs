@@ -0,0 +1,80 @@
+//! Owl module holds the terms of the OWL 2 vocabulary
+vocabulary!(
+  "http://www.w3.org/2002/07/owl#",
+  ONTOLOGY => "Ontology",
+  IMPORTS => "imports",
+  VERSION_IRI => "versionIRI",
+  VERSION_INFO => "versionInfo",
+  PRIOR_VERSION => "priorVersion",
+  BACKWARD_COMPATIBLE_WITH => "backwardCompatibleWith",
+  INCOMPATIBLE_WITH => "incompatibleWith",
+  DEPRECATED => "deprecated",
+  CLASS => "Class",
+  THING => "Thing",
+  NOTHING => "Nothing",
+  NAMED_INDIVIDUAL => "NamedIndividual",
+  OBJECT_PROPERTY => "ObjectProperty",
+  DATATYPE_PROPERTY => "DatatypeProperty",
+  ANNOTATION_PROPERTY => "AnnotationProperty",
+  ONTOLOGY_PROPERTY => "OntologyProperty",
+  TOP_OBJECT_PROPERTY => "topObjectProperty",
+  BOTTOM_OBJECT_PROPERTY => "bottomObjectProperty",
+  TOP_DATA_PROPERTY => "topDataProperty",
+  BOTTOM_DATA_PROPERTY => "bottomDataProperty",
+  FUNCTIONAL_PROPERTY => "FunctionalProperty",
+  INVERSE_FUNCTIONAL_PROPERTY => "InverseFunctionalProperty",
+  TRANSITIVE_PROPERTY => "TransitiveProperty",
+  SYMMETRIC_PROPERTY => "SymmetricProperty",
+  ASYMMETRIC_PROPERTY => "AsymmetricProperty",
+  REFLEXIVE_PROPERTY => "ReflexiveProperty",
+  IRREFLEXIVE_PROPERTY => "IrreflexiveProperty",
+  RESTRICTION => "Restriction",
+  ON_PROPERTY => "onProperty",
+  ON_PROPERTIES => "onProperties",
+  ON_CLASS => "onClass",
+  ON_DATA_RANGE => "onDataRange",
+  ON_DATATYPE => "onDatatype",
+  WITH_RESTRICTIONS => "withRestrictions",
+  DATATYPE_COMPLEMENT_OF => "datatypeComplementOf",
+  SOME_VALUES_FROM => "someValuesFrom",
+  ALL_VALUES_FROM => "allValuesFrom",
+  HAS_VALUE => "hasValue",
+  HAS_SELF => "hasSelf",
+  CARDINALITY => "cardinality",
+  MIN_CARDINALITY => "minCardinality",
+  MAX_CARDINALITY => "maxCardinality",
+  QUALIFIED_CARDINALITY => "qualifiedCardinality",
+  MIN_QUALIFIED_CARDINALITY => "minQualifiedCardinality",
+  MAX_QUALIFIED_CARDINALITY => "maxQualifiedCardinality",
+  INTERSECTION_OF => "intersectionOf",
+  UNION_OF => "unionOf",
+  COMPLEMENT_OF => "complementOf",
+  ONE_OF => "oneOf",
+  EQUIVALENT_CLASS => "equivalentClass",
+  DISJOINT_WITH => "disjointWith",
+  DISJOINT_UNION_OF => "disjointUnionOf",
+  ALL_DISJOINT_CLASSES => "AllDisjointClasses",
+  EQUIVALENT_PROPERTY => "equivalentProperty",
+  PROPERTY_DISJOINT_WITH => "propertyDisjointWith",
+  ALL_DISJOINT_PROPERTIES => "AllDisjointProperties",
+  INVERSE_OF => "inverseOf",
+  PROPERTY_CHAIN_AXIOM => "propertyChainAxiom",
+  HAS_KEY => "hasKey",
+  SAME_AS => "sameAs",
+  DIFFERENT_FROM => "differentFrom",
+  ALL_DIFFERENT => "AllDifferent",
+  MEMBERS => "members",
+  DISTINCT_MEMBERS => "distinctMembers",
+  NEGATIVE_PROPERTY_ASSERTION => "NegativePropertyAssertion",
+  SOURCE_INDIVIDUAL => "sourceIndividual",
+  ASSERTION_PROPERTY => "assertionProperty",
+  TARGET_INDIVIDUAL => "targetIndividual",
+  TARGET_VALUE => "targetValue",
+  AXIOM => "Axiom",
+  ANNOTATION => "Annotation",
+  ANNOTATED_SOURCE => "annotatedSource",
+  ANNOTATED_PROPERTY => "annotatedProperty",
+  ANNOTATED_TARGET => "annotatedTarget",
+  RATIONAL => "rational",
+  REAL => "real",
+);
@@ -0,0 +1,18 @@
+//! Rdf module holds the terms of the RDF vocabulary
+vocabulary!(
+  "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+  TYPE => "type",
+  PROPERTY => "Property",
+  STATEMENT => "Statement",
+  SUBJECT => "subject",
+  PREDICATE => "predicate",
+  OBJECT => "object",
+  FIRST => "first",
+  REST => "rest",
+  NIL => "nil",
+  LIST => "List",
+  VALUE => "value",
+  LANG_STRING => "langString",
+  PLAIN_LITERAL => "PlainLiteral",
+  XML_LITERAL => "XMLLiteral",
+);
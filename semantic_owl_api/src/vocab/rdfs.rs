@@ -0,0 +1,17 @@
+//! Rdfs module holds the terms of the RDF Schema vocabulary
+vocabulary!(
+  "http://www.w3.org/2000/01/rdf-schema#",
+  RESOURCE => "Resource",
+  CLASS => "Class",
+  LITERAL => "Literal",
+  DATATYPE => "Datatype",
+  SUB_CLASS_OF => "subClassOf",
+  SUB_PROPERTY_OF => "subPropertyOf",
+  DOMAIN => "domain",
+  RANGE => "range",
+  LABEL => "label",
+  COMMENT => "comment",
+  SEE_ALSO => "seeAlso",
+  IS_DEFINED_BY => "isDefinedBy",
+  MEMBER => "member",
+);
@@ -0,0 +1,45 @@
+//! Xsd module holds the datatypes of XML Schema which OWL 2 supports, and their facets
+vocabulary!(
+  "http://www.w3.org/2001/XMLSchema#",
+  STRING => "string",
+  NORMALIZED_STRING => "normalizedString",
+  TOKEN => "token",
+  LANGUAGE => "language",
+  NAME => "Name",
+  NC_NAME => "NCName",
+  NMTOKEN => "NMTOKEN",
+  BOOLEAN => "boolean",
+  DECIMAL => "decimal",
+  INTEGER => "integer",
+  NON_NEGATIVE_INTEGER => "nonNegativeInteger",
+  NON_POSITIVE_INTEGER => "nonPositiveInteger",
+  POSITIVE_INTEGER => "positiveInteger",
+  NEGATIVE_INTEGER => "negativeInteger",
+  LONG => "long",
+  INT => "int",
+  SHORT => "short",
+  BYTE => "byte",
+  UNSIGNED_LONG => "unsignedLong",
+  UNSIGNED_INT => "unsignedInt",
+  UNSIGNED_SHORT => "unsignedShort",
+  UNSIGNED_BYTE => "unsignedByte",
+  DOUBLE => "double",
+  FLOAT => "float",
+  DATE => "date",
+  DATE_TIME => "dateTime",
+  DATE_TIME_STAMP => "dateTimeStamp",
+  TIME => "time",
+  DURATION => "duration",
+  HEX_BINARY => "hexBinary",
+  BASE64_BINARY => "base64Binary",
+  ANY_URI => "anyURI",
+  // constraining facets of datatype restrictions
+  MIN_INCLUSIVE => "minInclusive",
+  MAX_INCLUSIVE => "maxInclusive",
+  MIN_EXCLUSIVE => "minExclusive",
+  MAX_EXCLUSIVE => "maxExclusive",
+  LENGTH => "length",
+  MIN_LENGTH => "minLength",
+  MAX_LENGTH => "maxLength",
+  PATTERN => "pattern",
+);
@@ -0,0 +1,241 @@
+//! Convert module converts the ontology documents of a directory tree to another syntax
+use crate::declarations::owl::OwlSyntax;
+use crate::declarations::turtle::TurtleDocument;
+use crate::loader::cancellation::{check_cancelled, CancellationToken};
+use crate::loader::load::{load_document_with_options, syntax_of_extension, LoaderOptions};
+use crate::writer::format::{format_turtle, Style};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// FileConversion is the outcome of the conversion of a single document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileConversion {
+  // path of the document converted. e.g -> testdata/turtle/AgentOntology.ttl
+  pub source: String,
+
+  // path of the converted document. `None` when the conversion failed
+  pub target: Option<String>,
+
+  // reason of the failure. e.g -> the provided file is not a turtle document
+  pub error: Option<String>,
+}
+
+impl FileConversion {
+  pub fn new(source: &str, target: Option<String>, error: Option<String>) -> FileConversion {
+    Self {
+      source: String::from(source),
+      target,
+      error,
+    }
+  }
+
+  /// is_converted determines whether the document was converted
+  pub fn is_converted(&self) -> bool {
+    self.error.is_none()
+  }
+}
+
+/// ConversionReport lists the outcome of the conversion of each document, ordered by path
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ConversionReport {
+  pub results: Vec<FileConversion>,
+}
+
+impl ConversionReport {
+  pub fn new(results: Vec<FileConversion>) -> ConversionReport {
+    Self { results }
+  }
+
+  /// converted returns the documents which were converted
+  pub fn converted(&self) -> impl Iterator<Item = &FileConversion> {
+    self.results.iter().filter(|x| x.is_converted())
+  }
+
+  /// failures returns the documents which could not be converted
+  pub fn failures(&self) -> impl Iterator<Item = &FileConversion> {
+    self.results.iter().filter(|x| !x.is_converted())
+  }
+}
+
+/// convert_directory converts the ontology documents found in `in_dir` and its sub directories,
+/// recognized by their file extension, to the `target` syntax. Converted documents are written
+/// under `out_dir`, at the same relative path, with the usual extension of the target syntax.
+/// It will return an error when `in_dir` can not be read or the target syntax is not supported yet
+/// example
+/// `convert_directory("testdata/turtle", "target/turtle", OwlSyntax::Turtle)`
+pub fn convert_directory(
+  in_dir: &str,
+  out_dir: &str,
+  target: OwlSyntax,
+) -> std::io::Result<ConversionReport> {
+  convert_directory_with_cancellation(in_dir, out_dir, target, None)
+}
+
+/// convert_directory_with_cancellation converts a directory tree like `convert_directory` until a
+/// token is cancelled. Documents being converted then stop between lines, no other document is
+/// started and a cancellation error is returned. Documents already written are left in place
+pub fn convert_directory_with_cancellation(
+  in_dir: &str,
+  out_dir: &str,
+  target: OwlSyntax,
+  cancellation: Option<&CancellationToken>,
+) -> std::io::Result<ConversionReport> {
+  let options = LoaderOptions {
+    cancellation: cancellation.cloned(),
+    ..LoaderOptions::default()
+  };
+  let write = writer_of(target)?;
+  let (in_dir, out_dir) = (Path::new(in_dir), Path::new(out_dir));
+  let mut files = vec![];
+  find_documents(in_dir, out_dir, &mut files)?;
+  files.sort();
+
+  // workers take the next document to convert until none is left
+  let next = AtomicUsize::new(0);
+  let results = Mutex::new(Vec::with_capacity(files.len()));
+  let workers = std::thread::available_parallelism()
+    .map(|x| x.get())
+    .unwrap_or(1)
+    .min(files.len());
+  std::thread::scope(|scope| {
+    for _ in 0..workers {
+      scope.spawn(|| {
+        while let Some(path) = files.get(next.fetch_add(1, Ordering::SeqCst)) {
+          if check_cancelled(cancellation).is_err() {
+            break;
+          }
+          let result = convert_file(path, in_dir, out_dir, target, write, &options);
+          results.lock().unwrap().push(result);
+        }
+      });
+    }
+  });
+
+  check_cancelled(cancellation)?;
+  let mut results = results.into_inner().unwrap();
+  results.sort_by(|a, b| a.source.cmp(&b.source));
+  Ok(ConversionReport::new(results))
+}
+
+// writer_of returns the function writing a document in a syntax
+fn writer_of(syntax: OwlSyntax) -> std::io::Result<fn(&TurtleDocument) -> String> {
+  match syntax {
+    OwlSyntax::Turtle => Ok(|d| format_turtle(d, &Style::default())),
+    syntax => Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidInput,
+      format!("{:?} documents can not be written yet", syntax),
+    )),
+  }
+}
+
+// extension_of returns the usual file extension of a syntax
+fn extension_of(syntax: OwlSyntax) -> &'static str {
+  match syntax {
+    OwlSyntax::Turtle => "ttl",
+    OwlSyntax::RdfXml => "rdf",
+    OwlSyntax::OwlXml => "owx",
+    OwlSyntax::Functional => "ofn",
+    OwlSyntax::Manchester => "omn",
+  }
+}
+
+// find_documents collects the paths of the ontology documents of a directory tree. The output
+// directory is skipped when it is part of the tree
+fn find_documents(dir: &Path, out_dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      if path != out_dir {
+        find_documents(&path, out_dir, files)?;
+      }
+    } else if path.to_str().and_then(syntax_of_extension).is_some() {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+// convert_file converts a single document of the input directory
+fn convert_file(
+  path: &Path,
+  in_dir: &Path,
+  out_dir: &Path,
+  target: OwlSyntax,
+  write: fn(&TurtleDocument) -> String,
+  options: &LoaderOptions,
+) -> FileConversion {
+  let source = path.to_string_lossy();
+  let relative = path.strip_prefix(in_dir).unwrap_or(path);
+  let output = out_dir.join(relative).with_extension(extension_of(target));
+  let converted = load_document_with_options(&source, options).and_then(|document| {
+    if let Some(parent) = output.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output, write(&document))
+  });
+  match converted {
+    Ok(_) => FileConversion::new(&source, Some(output.to_string_lossy().into_owned()), None),
+    Err(e) => FileConversion::new(&source, None, Some(e.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document;
+
+  #[test]
+  fn should_convert_directory() -> std::io::Result<()> {
+    let root = std::env::temp_dir().join(format!("semantic_owl_convert_{}", std::process::id()));
+    let (in_dir, out_dir) = (root.join("in"), root.join("out"));
+    std::fs::create_dir_all(in_dir.join("modules"))?;
+    let agent = "@prefix : <http://example.org/> .\n:Agent a :Class .\n";
+    std::fs::write(in_dir.join("agent.ttl"), agent)?;
+    std::fs::write(in_dir.join("modules/person.n3"), ":a :b")?;
+    std::fs::write(
+      in_dir.join("modules/event.owl"),
+      "<?xml version=\"1.0\"?>\n<rdf:RDF/>",
+    )?;
+    std::fs::write(in_dir.join("README.md"), "# ontologies")?;
+
+    let (in_path, out_path) = (in_dir.to_str().unwrap(), out_dir.to_str().unwrap());
+    let report = convert_directory(in_path, out_path, OwlSyntax::Turtle);
+    let unsupported = convert_directory(in_path, out_path, OwlSyntax::RdfXml);
+    let token = CancellationToken::new();
+    token.cancel();
+    let cancelled =
+      convert_directory_with_cancellation(in_path, out_path, OwlSyntax::Turtle, Some(&token));
+    let converted = load_turtle_document(out_dir.join("agent.ttl").to_str().unwrap());
+    let written = out_dir.join("modules").exists();
+    std::fs::remove_dir_all(&root)?;
+
+    let report = report?;
+    assert_eq!(report.results.len(), 3);
+    assert_eq!(report.converted().count(), 1);
+    let failures: Vec<&str> = report
+      .failures()
+      .map(|x| x.source.rsplit('/').next().unwrap())
+      .collect();
+    assert_eq!(failures, vec!["event.owl", "person.n3"]);
+    assert!(report.results[0]
+      .target
+      .as_deref()
+      .unwrap()
+      .ends_with("out/agent.ttl"));
+    assert_eq!(
+      converted?.triples(),
+      crate::loader::load::load_turtle_document_from_str(agent)?.triples()
+    );
+    assert!(!written);
+    assert_eq!(
+      unsupported.unwrap_err().kind(),
+      std::io::ErrorKind::InvalidInput
+    );
+    assert!(crate::loader::cancellation::is_cancelled(
+      &cancelled.unwrap_err()
+    ));
+    Ok(())
+  }
+}
@@ -0,0 +1,812 @@
+//! Format module rewrites a turtle document with a consistent layout
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm, TurtleTriple};
+use crate::loader::parsers::ttl_pname_parser::is_prefixed_name;
+use crate::vocab::{owl, rdf, rdfs, xsd};
+use crate::writer::prefixes::optimal_prefixes;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+const RDF_FIRST: &str = rdf::FIRST.as_str();
+const RDF_REST: &str = rdf::REST.as_str();
+const RDF_NIL: &str = rdf::NIL.as_str();
+const OWL_ONTOLOGY: &str = owl::ONTOLOGY.as_str();
+const RDFS_LABEL: &str = rdfs::LABEL.as_str();
+
+// label predicates, written right after rdf:type when predicates are sorted
+const LABELS: [&str; 3] = [
+  RDFS_LABEL,
+  "http://www.w3.org/2004/02/skos/core#prefLabel",
+  "http://www.w3.org/2004/02/skos/core#altLabel",
+];
+
+// sections of a document grouped by entity type, in the order Protégé writes them
+const SECTIONS: [(&str, &str); 6] = [
+  (owl::ANNOTATION_PROPERTY.as_str(), "Annotation properties"),
+  (owl::OBJECT_PROPERTY.as_str(), "Object Properties"),
+  (owl::DATATYPE_PROPERTY.as_str(), "Data properties"),
+  (rdfs::DATATYPE.as_str(), "Datatypes"),
+  (owl::CLASS.as_str(), "Classes"),
+  (owl::NAMED_INDIVIDUAL.as_str(), "Individuals"),
+];
+
+/// PredicateLayout is the placement of the predicates of a subject
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PredicateLayout {
+  // all predicates on the line of the subject. Example -> `cco:Agent a owl:Class ; rdfs:label "Agent" .`
+  SameLine,
+
+  // the first predicate on the line of the subject, the others aligned under it
+  Aligned,
+
+  // each predicate on its own line, indented by `Style::indent`
+  Indented,
+}
+
+/// BlankNodePolicy is the way blank nodes are written. Blank nodes referenced several times or
+/// belonging to a cycle always keep their label, since they can not be nested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BlankNodePolicy {
+  // blank nodes referenced once are nested where they are referenced. e.g -> `[ a owl:Restriction ]`
+  #[default]
+  InlineSingleUse,
+
+  // every blank node is written with its label. e.g -> `_:b1`
+  AlwaysLabel,
+}
+
+/// SubjectOrder is the key subjects are sorted by when `Style::sort_subjects` is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SubjectOrder {
+  // e.g -> `cco:Agent` before `cco:Person`
+  #[default]
+  Iri,
+
+  // the lowest `rdfs:label` of the subject. Subjects without a label follow, sorted by IRI
+  Label,
+}
+
+/// Style tunes the layout of a formatted document.
+/// Use one of the profiles `compact`, `protege` or `obo`, or build your own over one of them
+/// example
+/// `Style { sort_predicates: true, ..Style::obo() }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+  // number of spaces of an indentation level, used by the `Indented` layout
+  pub indent: usize,
+
+  pub predicate_layout: PredicateLayout,
+
+  // write each object of a predicate on its own line, aligned under the first one
+  pub object_per_line: bool,
+
+  // write prefixes sorted by name instead of in their declaration order
+  pub sort_prefixes: bool,
+
+  // write subjects sorted by `subject_order` instead of in the order they first appear
+  pub sort_subjects: bool,
+
+  #[serde(default)]
+  pub subject_order: SubjectOrder,
+
+  // write `rdf:type` first, then labels, then the other predicates sorted by IRI, instead of
+  // `rdf:type` first and the others in the order they first appear
+  #[serde(default)]
+  pub sort_predicates: bool,
+
+  // write the objects of a predicate sorted instead of in the order they first appear. Items of
+  // collections keep their order
+  #[serde(default)]
+  pub sort_objects: bool,
+
+  // write `a` instead of `rdf:type`
+  pub use_a: bool,
+
+  // separate the statements of subjects with an empty line
+  pub blank_line_between_subjects: bool,
+
+  // write `###  <iri>` before the statements of a named subject
+  pub subject_comments: bool,
+
+  // group subjects by entity type under a banner. Example -> `#    Classes`
+  pub entity_sections: bool,
+
+  // write the optimal prefixes instead of the declared ones: unused prefixes are dropped and
+  // namespaces repeated in full IRIs get a prefix. See `optimal_prefixes`
+  #[serde(default)]
+  pub optimize_prefixes: bool,
+
+  #[serde(default)]
+  pub blank_nodes: BlankNodePolicy,
+}
+
+impl Default for Style {
+  /// default is the `protege` profile
+  fn default() -> Self {
+    Self {
+      indent: 0,
+      predicate_layout: PredicateLayout::Aligned,
+      object_per_line: true,
+      sort_prefixes: true,
+      sort_subjects: true,
+      subject_order: SubjectOrder::Iri,
+      sort_predicates: false,
+      sort_objects: false,
+      use_a: false,
+      blank_line_between_subjects: true,
+      subject_comments: true,
+      entity_sections: true,
+      optimize_prefixes: false,
+      blank_nodes: BlankNodePolicy::InlineSingleUse,
+    }
+  }
+}
+
+impl Style {
+  /// compact writes each subject on a single line, in document order
+  pub fn compact() -> Style {
+    Style {
+      predicate_layout: PredicateLayout::SameLine,
+      object_per_line: false,
+      sort_subjects: false,
+      use_a: true,
+      blank_line_between_subjects: false,
+      subject_comments: false,
+      entity_sections: false,
+      ..Style::default()
+    }
+  }
+
+  /// protege writes documents the way Protégé saves them: sections per entity type,
+  /// a comment with the IRI of each entity and predicates aligned under the first one
+  pub fn protege() -> Style {
+    Style::default()
+  }
+
+  /// obo writes sorted subjects with their predicates indented on the following lines,
+  /// which keeps diffs of OBO ontologies line oriented
+  pub fn obo() -> Style {
+    Style {
+      indent: 4,
+      predicate_layout: PredicateLayout::Indented,
+      use_a: true,
+      subject_comments: false,
+      entity_sections: false,
+      ..Style::default()
+    }
+  }
+}
+
+/// format_turtle writes the document as turtle using the given style.
+/// Terms whose prefix is not declared can not be resolved and are left out
+pub fn format_turtle(document: &TurtleDocument, style: &Style) -> String {
+  let map = match style.optimize_prefixes {
+    true => optimal_prefixes(document, style),
+    false => document.prefix_map(),
+  };
+  let mut formatter = Formatter::new(document.triples(), &map, style);
+  formatter.write_document(document);
+  formatter.out
+}
+
+/// format_triples writes triples grouped by subject using the given style, without directives
+/// nor sections. Blank nodes are written with their labels, since the triples may be a part of a
+/// larger graph referencing them elsewhere
+pub(crate) fn format_triples(triples: Vec<TurtleTriple>, map: &PrefixMap, style: &Style) -> String {
+  let mut formatter = Formatter::new(triples, map, style);
+  formatter.inline.clear();
+  let subjects = formatter.sorted_subjects();
+  formatter.write_subjects(&subjects);
+  formatter.out
+}
+
+/// render_term writes a term in its shortest turtle form using the prefixes of the map
+/// example
+/// `http://www.w3.org/2002/07/owl#Class` is written `owl:Class`, a simple literal `"agent"`
+pub fn render_term(term: &TurtleTerm, map: &PrefixMap) -> String {
+  match term {
+    TurtleTerm::Iri(iri) => render_iri(iri, map),
+    TurtleTerm::BlankNode(x) => format!("_:{}", x),
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => {
+      let mut out = String::from("\"");
+      for c in value.chars() {
+        match c {
+          '"' => out.push_str("\\\""),
+          '\\' => out.push_str("\\\\"),
+          '\n' => out.push_str("\\n"),
+          '\r' => out.push_str("\\r"),
+          _ => out.push(c),
+        }
+      }
+      out.push('"');
+      match language {
+        Some(l) => out.push_str(&format!("@{}", l)),
+        None if datatype == XSD_STRING => {}
+        None => out.push_str(&format!("^^{}", render_iri(datatype, map))),
+      }
+      out
+    }
+    TurtleTerm::QuotedTriple(t) => format!(
+      "<< {} {} {} >>",
+      render_term(&t.subject, map),
+      render_term(&t.predicate, map),
+      render_term(&t.object, map)
+    ),
+  }
+}
+
+// render_iri writes an IRI as a prefixed name when its local part is a valid `PN_LOCAL`
+pub(crate) fn render_iri(iri: &str, map: &PrefixMap) -> String {
+  match map.shorten(iri) {
+    Some(x) if is_prefixed_name(&x) => x,
+    _ => format!("<{}>", iri),
+  }
+}
+
+// predicates of a subject with their objects, in the order they first appear
+type PredicateObjects = Vec<(TurtleTerm, Vec<TurtleTerm>)>;
+
+struct Formatter<'a> {
+  map: &'a PrefixMap,
+  style: &'a Style,
+
+  // subjects in the order they first appear
+  subjects: Vec<TurtleTerm>,
+  statements: HashMap<TurtleTerm, PredicateObjects>,
+
+  // blank nodes written inline, where they are referenced
+  inline: BTreeSet<TurtleTerm>,
+
+  // prefixes of the prefixed names written
+  used: BTreeSet<String>,
+
+  out: String,
+}
+
+impl<'a> Formatter<'a> {
+  fn new(triples: Vec<TurtleTriple>, map: &'a PrefixMap, style: &'a Style) -> Formatter<'a> {
+    let mut subjects = vec![];
+    let mut statements: HashMap<TurtleTerm, PredicateObjects> = HashMap::new();
+    let mut references: HashMap<TurtleTerm, usize> = HashMap::new();
+    for t in triples {
+      if t.object.is_blank_node() {
+        *references.entry(t.object.clone()).or_default() += 1;
+      }
+      let predicates = statements.entry(t.subject.clone()).or_insert_with(|| {
+        subjects.push(t.subject.clone());
+        vec![]
+      });
+      match predicates.iter_mut().find(|(p, _)| *p == t.predicate) {
+        Some((_, objects)) if !objects.contains(&t.object) => objects.push(t.object),
+        Some(_) => {}
+        None => predicates.push((t.predicate, vec![t.object])),
+      }
+    }
+    // rdf:type comes first
+    for predicates in statements.values_mut() {
+      match style.sort_predicates {
+        true => {
+          predicates.sort_by(|(a, _), (b, _)| (predicate_rank(a), a).cmp(&(predicate_rank(b), b)))
+        }
+        false => predicates.sort_by_key(|(p, _)| p.as_iri() != Some(RDF_TYPE)),
+      }
+      if style.sort_objects {
+        for (_, objects) in predicates.iter_mut() {
+          objects.sort();
+        }
+      }
+    }
+
+    // blank nodes referenced once are written inline, unless they belong to a cycle
+    let candidates: BTreeSet<TurtleTerm> = references
+      .into_iter()
+      .filter(|(_, n)| *n == 1)
+      .map(|(b, _)| b)
+      .collect();
+    let mut inline: BTreeSet<TurtleTerm> = BTreeSet::new();
+    let mut roots: Vec<&TurtleTerm> = subjects
+      .iter()
+      .filter(|s| !candidates.contains(*s))
+      .collect();
+    loop {
+      let mut pending = roots.clone();
+      while let Some(s) = pending.pop() {
+        for (_, objects) in statements.get(s).into_iter().flatten() {
+          for o in objects.iter() {
+            if candidates.contains(o) && inline.insert(o.clone()) {
+              pending.push(o);
+            }
+          }
+        }
+      }
+      // a candidate which is not reachable is part of a cycle. It is written with its label
+      match subjects
+        .iter()
+        .find(|s| candidates.contains(*s) && !inline.contains(*s) && !roots.contains(s))
+      {
+        Some(s) => roots.push(s),
+        None => break,
+      }
+    }
+    for r in roots.iter() {
+      inline.remove(*r);
+    }
+    if style.blank_nodes == BlankNodePolicy::AlwaysLabel {
+      inline.clear();
+    }
+
+    Self {
+      map,
+      style,
+      subjects,
+      statements,
+      inline,
+      used: BTreeSet::new(),
+      out: String::new(),
+    }
+  }
+
+  fn write_document(&mut self, document: &TurtleDocument) {
+    // the statements are written first, so the prefixes they use are known
+    self.write_statements();
+    let statements = std::mem::take(&mut self.out);
+
+    // prefixes in declaration order, followed by the ones the document does not declare
+    let mut prefixes: Vec<(String, String)> = vec![];
+    let declared = document
+      .headers
+      .iter()
+      .filter(|h| !h.is_base)
+      .filter_map(|h| h.prefix_namespace.as_deref());
+    for ns in declared.chain(self.map.prefixes.keys().map(|x| x.as_str())) {
+      if let Some(iri) = self.map.namespace(ns) {
+        if !prefixes.iter().any(|(p, _)| p == ns) {
+          prefixes.push((String::from(ns), String::from(iri)));
+        }
+      }
+    }
+    if self.style.optimize_prefixes {
+      prefixes.retain(|(p, _)| self.used.contains(p));
+    }
+    if self.style.sort_prefixes {
+      prefixes.sort();
+    }
+    for (prefix, iri) in prefixes.iter() {
+      self
+        .out
+        .push_str(&format!("@prefix {}: <{}> .\n", prefix, iri));
+    }
+    if let Some(base) = &self.map.base {
+      self.out.push_str(&format!("@base <{}> .\n", base));
+    }
+    self.out.push_str(&statements);
+  }
+
+  // sorted_subjects returns the subjects which are not written inline, in the order of the style
+  fn sorted_subjects(&self) -> Vec<TurtleTerm> {
+    let mut subjects: Vec<TurtleTerm> = self
+      .subjects
+      .iter()
+      .filter(|s| !self.inline.contains(*s))
+      .cloned()
+      .collect();
+    match (self.style.sort_subjects, self.style.subject_order) {
+      // named subjects first
+      (true, SubjectOrder::Iri) => {
+        subjects.sort_by(|a, b| (a.is_blank_node(), a).cmp(&(b.is_blank_node(), b)))
+      }
+      (true, SubjectOrder::Label) => subjects.sort_by(|a, b| {
+        let (x, y) = (self.label_of(a), self.label_of(b));
+        (a.is_blank_node(), x.is_none(), x, a).cmp(&(b.is_blank_node(), y.is_none(), y, b))
+      }),
+      (false, _) => {}
+    }
+    subjects
+  }
+
+  // label_of returns the lowest `rdfs:label` value of a subject
+  fn label_of(&self, subject: &TurtleTerm) -> Option<&str> {
+    self
+      .statements
+      .get(subject)?
+      .iter()
+      .filter(|(p, _)| p.as_iri() == Some(RDFS_LABEL))
+      .flat_map(|(_, objects)| objects.iter())
+      .filter_map(|o| match o {
+        TurtleTerm::Literal { value, .. } => Some(value.as_str()),
+        _ => None,
+      })
+      .min()
+  }
+
+  fn write_statements(&mut self) {
+    let subjects = self.sorted_subjects();
+
+    if !self.style.entity_sections {
+      if !subjects.is_empty() {
+        self.out.push('\n');
+      }
+      self.write_subjects(&subjects);
+      return;
+    }
+
+    // ontology header, sections per entity type and then everything else
+    let (ontologies, mut rest): (Vec<TurtleTerm>, Vec<TurtleTerm>) = subjects
+      .into_iter()
+      .partition(|s| self.has_type(s, OWL_ONTOLOGY));
+    if !ontologies.is_empty() {
+      self.out.push('\n');
+      self.write_subjects(&ontologies);
+    }
+    for (kind, title) in SECTIONS.iter() {
+      let (section, others): (Vec<TurtleTerm>, Vec<TurtleTerm>) =
+        rest.into_iter().partition(|s| self.has_type(s, kind));
+      rest = others;
+      if !section.is_empty() {
+        self.write_banner(title);
+        self.write_subjects(&section);
+      }
+    }
+    if !rest.is_empty() {
+      self.write_banner("General axioms");
+      self.write_subjects(&rest);
+    }
+  }
+
+  // render writes a term in its shortest form, recording the prefix it is written with
+  fn render(&mut self, term: &TurtleTerm) -> String {
+    if let TurtleTerm::QuotedTriple(t) = term {
+      let (s, p, o) = (
+        self.render(&t.subject),
+        self.render(&t.predicate),
+        self.render(&t.object),
+      );
+      return format!("<< {} {} {} >>", s, p, o);
+    }
+    let out = render_term(term, self.map);
+    let name = match term {
+      TurtleTerm::Iri(_) => Some(out.as_str()),
+      TurtleTerm::Literal {
+        datatype, language, ..
+      } if language.is_none() && datatype != XSD_STRING => out.rsplit_once("\"^^").map(|(_, x)| x),
+      _ => None,
+    };
+    if let Some((prefix, _)) = name
+      .filter(|x| !x.starts_with('<'))
+      .and_then(|x| x.split_once(':'))
+    {
+      self.used.insert(String::from(prefix));
+    }
+    out
+  }
+
+  fn write_banner(&mut self, title: &str) {
+    let line = "#".repeat(65);
+    self
+      .out
+      .push_str(&format!("\n{}\n#    {}\n{}\n\n", line, title, line));
+  }
+
+  fn write_subjects(&mut self, subjects: &[TurtleTerm]) {
+    for (idx, s) in subjects.iter().enumerate() {
+      if idx > 0 && self.style.blank_line_between_subjects {
+        self.out.push('\n');
+      }
+      self.write_subject(s);
+    }
+  }
+
+  fn write_subject(&mut self, subject: &TurtleTerm) {
+    if let (true, Some(iri)) = (self.style.subject_comments, subject.as_iri()) {
+      self.out.push_str(&format!("###  {}\n", iri));
+    }
+    let s = self.render(subject);
+    self.out.push_str(&s);
+    let predicates = self.statements.get(subject).cloned().unwrap_or_default();
+    let column = match self.style.predicate_layout {
+      PredicateLayout::SameLine | PredicateLayout::Aligned => {
+        self.out.push(' ');
+        self.column()
+      }
+      PredicateLayout::Indented => {
+        self.new_line(self.style.indent);
+        self.style.indent
+      }
+    };
+    self.write_predicate_objects(&predicates, column);
+    self.out.push_str(" .\n");
+  }
+
+  fn write_predicate_objects(
+    &mut self,
+    predicates: &[(TurtleTerm, Vec<TurtleTerm>)],
+    column: usize,
+  ) {
+    for (idx, (p, objects)) in predicates.iter().enumerate() {
+      if idx > 0 {
+        self.out.push_str(" ;");
+        match self.style.predicate_layout {
+          PredicateLayout::SameLine => self.out.push(' '),
+          _ => self.new_line(column),
+        }
+      }
+      match (self.style.use_a, p.as_iri()) {
+        (true, Some(RDF_TYPE)) => self.out.push('a'),
+        _ => {
+          let p = self.render(p);
+          self.out.push_str(&p);
+        }
+      }
+      self.out.push(' ');
+      let object_column = self.column();
+      for (idx, o) in objects.iter().enumerate() {
+        if idx > 0 {
+          self.out.push_str(" ,");
+          match self.style.object_per_line
+            && self.style.predicate_layout != PredicateLayout::SameLine
+          {
+            true => self.new_line(object_column),
+            false => self.out.push(' '),
+          }
+        }
+        self.write_object(o);
+      }
+    }
+  }
+
+  fn write_object(&mut self, object: &TurtleTerm) {
+    if !self.inline.contains(object) {
+      let o = self.render(object);
+      self.out.push_str(&o);
+      return;
+    }
+    if let Some(items) = self.collection(object) {
+      self.out.push('(');
+      for o in items.iter() {
+        self.out.push(' ');
+        self.write_object(o);
+      }
+      self.out.push_str(" )");
+      return;
+    }
+    let predicates = self.statements.get(object).cloned().unwrap_or_default();
+    if predicates.is_empty() {
+      self.out.push_str("[]");
+      return;
+    }
+    let column = self.column();
+    self.out.push_str("[ ");
+    self.write_predicate_objects(&predicates, column + 2);
+    match self.style.predicate_layout {
+      PredicateLayout::SameLine => self.out.push_str(" ]"),
+      _ => {
+        self.new_line(column);
+        self.out.push(']');
+      }
+    }
+  }
+
+  // collection returns the items of a well-formed RDF collection whose nodes are all written inline
+  fn collection(&self, head: &TurtleTerm) -> Option<Vec<TurtleTerm>> {
+    let mut items = vec![];
+    let mut node = head;
+    loop {
+      if node.as_iri() == Some(RDF_NIL) {
+        return Some(items);
+      }
+      if !self.inline.contains(node) {
+        return None;
+      }
+      let predicates = self.statements.get(node)?;
+      let single = |iri: &str| -> Option<&TurtleTerm> {
+        match predicates.iter().find(|(p, _)| p.as_iri() == Some(iri)) {
+          Some((_, objects)) if objects.len() == 1 => Some(&objects[0]),
+          _ => None,
+        }
+      };
+      if predicates.len() != 2 {
+        return None;
+      }
+      items.push(single(RDF_FIRST)?.clone());
+      node = single(RDF_REST)?;
+    }
+  }
+
+  fn has_type(&self, subject: &TurtleTerm, kind: &str) -> bool {
+    match self.statements.get(subject) {
+      Some(predicates) => predicates.iter().any(|(p, objects)| {
+        p.as_iri() == Some(RDF_TYPE) && objects.iter().any(|o| o.as_iri() == Some(kind))
+      }),
+      None => false,
+    }
+  }
+
+  fn new_line(&mut self, column: usize) {
+    self.out.push('\n');
+    self.out.push_str(&" ".repeat(column));
+  }
+
+  // column returns the number of characters written since the last line break
+  fn column(&self) -> usize {
+    let start = self.out.rfind('\n').map(|x| x + 1).unwrap_or(0);
+    self.out[start..].chars().count()
+  }
+}
+
+// predicate_rank ranks `rdf:type` first, then the label predicates, then the others
+fn predicate_rank(predicate: &TurtleTerm) -> usize {
+  match predicate.as_iri() {
+    Some(RDF_TYPE) => 0,
+    Some(iri) if LABELS.contains(&iri) => 1,
+    _ => 2,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+cco:Person rdfs:subClassOf cco:Agent ; a owl:Class ;
+  rdfs:label "Person"@en , "Personne"@fr .
+<http://www.ontologyrepository.com/CommonCoreOntologies> a owl:Ontology .
+cco:has_part a owl:ObjectProperty .
+cco:Agent a owl:Class ;
+  owl:equivalentClass [ a owl:Class ; owl:unionOf ( cco:Person cco:Organization ) ] ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_part ; owl:minCardinality "1"^^xsd:nonNegativeInteger ] .
+_:x rdfs:seeAlso _:y .
+_:y rdfs:seeAlso _:x .
+"#;
+
+  #[test]
+  fn should_format_compact() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let formatted = format_turtle(&document, &Style::compact());
+    assert!(formatted.starts_with("@prefix cco:"));
+    assert!(formatted.contains(
+      "cco:Person a owl:Class ; rdfs:subClassOf cco:Agent ; rdfs:label \"Person\"@en , \"Personne\"@fr .\n"
+    ));
+    assert!(formatted.contains(
+      "owl:equivalentClass [ a owl:Class ; owl:unionOf ( cco:Person cco:Organization ) ]"
+    ));
+    assert!(formatted.contains("owl:minCardinality \"1\"^^xsd:nonNegativeInteger ]"));
+    // a blank node of a cycle keeps its label
+    assert!(formatted.contains("_:x rdfs:seeAlso [ rdfs:seeAlso _:x ] .\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn should_format_protege_like() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let formatted = format_turtle(&document, &Style::protege());
+    let expected = r#"###  http://www.ontologyrepository.com/CommonCoreOntologies/Person
+cco:Person rdf:type owl:Class ;
+           rdfs:subClassOf cco:Agent ;
+           rdfs:label "Person"@en ,
+                      "Personne"@fr .
+"#;
+    let ontology = formatted.find("owl:Ontology").unwrap();
+    let properties = formatted.find("#    Object Properties").unwrap();
+    let classes = formatted.find("#    Classes").unwrap();
+    assert!(ontology < properties && properties < classes);
+    assert!(formatted.contains("#    General axioms"));
+    // rdf is not declared by the document, so rdf:type is written as an IRI
+    assert!(!formatted.contains(expected));
+    let document = load_turtle_document_from_str(&format!(
+      "@prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .\n{}",
+      DOCUMENT
+    ))?;
+    let formatted = format_turtle(&document, &Style::protege());
+    assert!(formatted.contains(expected));
+    assert!(formatted.contains(
+      "          rdfs:subClassOf [ rdf:type owl:Restriction ;\n                            owl:onProperty cco:has_part ;"
+    ));
+    Ok(())
+  }
+
+  #[test]
+  fn should_format_obo_style() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let formatted = format_turtle(&document, &Style::obo());
+    assert!(
+      formatted.contains("cco:Agent\n    a owl:Class ;\n    owl:equivalentClass [ a owl:Class ;")
+    );
+    // the agent sorts before the person
+    assert!(formatted.find("cco:Agent\n").unwrap() < formatted.find("cco:Person\n").unwrap());
+    Ok(())
+  }
+
+  #[test]
+  fn should_format_in_a_deterministic_order() -> std::io::Result<()> {
+    let style = Style {
+      subject_order: SubjectOrder::Label,
+      sort_predicates: true,
+      sort_objects: true,
+      ..Style::compact()
+    };
+    let shuffled = r#"
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+
+cco:Agent a owl:Class .
+cco:has_part a owl:ObjectProperty .
+cco:Person rdfs:label "Personne"@fr , "Person"@en ; rdfs:subClassOf cco:Agent ; a owl:Class .
+cco:Agent rdfs:label "Agent"@en ; rdfs:comment "An agent" .
+"#;
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let formatted = format_turtle(
+      &document,
+      &Style {
+        sort_subjects: true,
+        ..style.clone()
+      },
+    );
+    let person = "cco:Person a owl:Class ; rdfs:label \"Person\"@en , \"Personne\"@fr ; rdfs:subClassOf cco:Agent .\n";
+    assert!(formatted.contains(person));
+    // labelled subjects first, by label
+    let shuffled = load_turtle_document_from_str(shuffled)?;
+    let formatted = format_turtle(
+      &shuffled,
+      &Style {
+        sort_subjects: true,
+        ..style
+      },
+    );
+    let expected = r#"
+cco:Agent a owl:Class ; rdfs:label "Agent"@en ; rdfs:comment "An agent" .
+cco:Person a owl:Class ; rdfs:label "Person"@en , "Personne"@fr ; rdfs:subClassOf cco:Agent .
+cco:has_part a owl:ObjectProperty .
+"#;
+    assert!(formatted.ends_with(expected));
+    Ok(())
+  }
+
+  #[test]
+  fn should_label_blank_nodes_on_request() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(&format!(
+      "{}\ncco:Organization rdfs:seeAlso _:shared .\ncco:Group rdfs:seeAlso _:shared .\n_:shared rdfs:label \"shared\" .\n",
+      DOCUMENT
+    ))?;
+    let formatted = format_turtle(&document, &Style::compact());
+    // a blank node referenced twice keeps its label
+    assert!(formatted.contains("cco:Group rdfs:seeAlso _:shared .\n"));
+    assert!(formatted.contains("_:shared rdfs:label \"shared\" .\n"));
+
+    let style = Style {
+      blank_nodes: BlankNodePolicy::AlwaysLabel,
+      ..Style::compact()
+    };
+    let formatted = format_turtle(&document, &style);
+    assert!(!formatted.contains('[') && !formatted.contains('('));
+    let reloaded = load_turtle_document_from_str(&formatted)?;
+    assert_eq!(reloaded.canonical_hash(), document.canonical_hash());
+    Ok(())
+  }
+
+  #[test]
+  fn should_format_to_an_equivalent_document() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    for style in [Style::compact(), Style::protege(), Style::obo()].iter() {
+      let formatted = format_turtle(&document, style);
+      let reloaded = load_turtle_document_from_str(&formatted)?;
+      assert_eq!(reloaded.canonical_hash(), document.canonical_hash());
+      // formatting is idempotent
+      assert_eq!(format_turtle(&reloaded, style), formatted);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,249 @@
+//! JSON Lines module writes triples as JSON Lines, one object per subject
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::loader::handler::TurtleHandler;
+use crate::vocab::{rdf, xsd};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+/// JsonLinesRecord is the object written for a subject
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct JsonLinesRecord {
+  // IRI of the subject, or its label for a blank node. e.g -> _:genid1
+  pub id: String,
+
+  // IRIs of the types of the subject, in the order they are stated
+  pub types: Vec<String>,
+
+  // values of the properties, by full IRI of the property. A value is an object holding the `id`
+  // of a resource, or the `value` of a literal with its `language` or `datatype`.
+  // e.g -> {"value": "Agent", "language": "en"}
+  pub properties: BTreeMap<String, Vec<Value>>,
+}
+
+impl JsonLinesRecord {
+  pub fn new(
+    id: String,
+    types: Vec<String>,
+    properties: BTreeMap<String, Vec<Value>>,
+  ) -> JsonLinesRecord {
+    Self {
+      id,
+      types,
+      properties,
+    }
+  }
+}
+
+/// json_lines_records groups triples into one record per subject. Subjects are in the order they
+/// first appear, and a value stated twice is held once
+pub fn json_lines_records(triples: &[TurtleTriple]) -> Vec<JsonLinesRecord> {
+  // position of the record of each subject
+  let mut subjects: HashMap<&TurtleTerm, usize> = HashMap::new();
+  let mut records: Vec<JsonLinesRecord> = vec![];
+  for t in triples.iter() {
+    let idx = *subjects.entry(&t.subject).or_insert_with(|| {
+      records.push(JsonLinesRecord::new(
+        term_id(&t.subject),
+        vec![],
+        BTreeMap::new(),
+      ));
+      records.len() - 1
+    });
+    let record = &mut records[idx];
+    match (t.predicate.as_iri(), &t.object) {
+      // types are listed apart, unless the type is not an IRI
+      (Some(RDF_TYPE), TurtleTerm::Iri(iri)) => {
+        if !record.types.contains(iri) {
+          record.types.push(iri.clone());
+        }
+      }
+      (Some(iri), object) => {
+        let value = value_object(object);
+        let values = record.properties.entry(String::from(iri)).or_default();
+        if !values.contains(&value) {
+          values.push(value);
+        }
+      }
+      // a predicate is always an IRI
+      (None, _) => {}
+    }
+  }
+  records
+}
+
+/// format_json_lines writes triples as JSON Lines, one line per subject, each line terminated by
+/// a line feed
+pub fn format_json_lines(triples: &[TurtleTriple]) -> String {
+  let mut out = String::new();
+  for record in json_lines_records(triples) {
+    out.push_str(&serde_json::to_string(&record).unwrap_or_default());
+    out.push('\n');
+  }
+  out
+}
+
+// value_object returns the value of a property. e.g -> {"id": "http://example.org/Agent"}
+fn value_object(term: &TurtleTerm) -> Value {
+  match term {
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => match (language, datatype.as_str()) {
+      (Some(language), _) => json!({ "value": value, "language": language }),
+      (None, XSD_STRING) => json!({ "value": value }),
+      (None, datatype) => json!({ "value": value, "datatype": datatype }),
+    },
+    term => json!({ "id": term_id(term) }),
+  }
+}
+
+// term_id returns the identifier of an IRI or a blank node, or the N-Triples form of a quoted
+// triple. e.g -> _:b0
+fn term_id(term: &TurtleTerm) -> String {
+  match term {
+    TurtleTerm::Iri(iri) => iri.clone(),
+    TurtleTerm::BlankNode(label) => format!("_:{}", label),
+    term => term.to_string(),
+  }
+}
+
+/// JsonLinesWriter writes the triples it is given as JSON Lines, grouped by subject within a window
+/// of triples
+pub struct JsonLinesWriter<W: Write> {
+  out: W,
+
+  // maximum number of triples held before they are written
+  window: usize,
+
+  // triples held, in the order they were given
+  triples: Vec<TurtleTriple>,
+
+  // first error met by the methods of the handler, which can not return it. Returned by `finish`
+  error: Option<std::io::Error>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+  /// new creates a writer holding at most `window` triples, and at least one, in memory
+  /// example
+  /// `JsonLinesWriter::new(std::io::stdout(), DEFAULT_WINDOW)`
+  pub fn new(out: W, window: usize) -> JsonLinesWriter<W> {
+    Self {
+      out,
+      window: window.max(1),
+      triples: vec![],
+      error: None,
+    }
+  }
+
+  /// write_triple holds a triple, writing the triples held when the window is full
+  pub fn write_triple(&mut self, triple: &TurtleTriple) -> std::io::Result<()> {
+    self.triples.push(triple.clone());
+    match self.triples.len() >= self.window {
+      true => self.flush(),
+      false => Ok(()),
+    }
+  }
+
+  /// flush writes the triples held, one line per subject
+  pub fn flush(&mut self) -> std::io::Result<()> {
+    if self.triples.is_empty() {
+      return Ok(());
+    }
+    let triples = std::mem::take(&mut self.triples);
+    for record in json_lines_records(&triples) {
+      serde_json::to_writer(&mut self.out, &record)?;
+      self.out.write_all(b"\n")?;
+    }
+    Ok(())
+  }
+
+  /// finish writes the triples held and returns the output. It returns the first error met while
+  /// the writer was used as a handler, if any
+  pub fn finish(mut self) -> std::io::Result<W> {
+    if let Some(e) = self.error.take() {
+      return Err(e);
+    }
+    self.flush()?;
+    self.out.flush()?;
+    Ok(self.out)
+  }
+}
+
+impl<W: Write> TurtleHandler for JsonLinesWriter<W> {
+  fn on_triple(&mut self, triple: &TurtleTriple) {
+    if self.error.is_none() {
+      if let Err(e) = self.write_triple(triple) {
+        self.error = Some(e);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::handler::parse_turtle_str_with_handler;
+  use crate::writer::stream::DEFAULT_WINDOW;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/> .
+
+:Agent a owl:Class ; rdfs:label "Agent"@en , "Agent"@en ; rdfs:comment "An agent" .
+:Person a owl:Class ; rdfs:subClassOf :Agent , [ owl:onProperty :has_part ; owl:minCardinality 1 ] .
+:Agent rdfs:seeAlso :Person .
+"#;
+
+  // stream writes the document through a writer holding `window` triples
+  fn stream(window: usize) -> std::io::Result<Vec<JsonLinesRecord>> {
+    let mut writer = JsonLinesWriter::new(vec![], window);
+    parse_turtle_str_with_handler(DOCUMENT, &mut writer)?;
+    let out = String::from_utf8(writer.finish()?).unwrap();
+    out
+      .lines()
+      .map(|x| serde_json::from_str(x).map_err(std::io::Error::from))
+      .collect()
+  }
+
+  #[test]
+  fn should_write_json_lines_grouped_by_subject() -> std::io::Result<()> {
+    let records = stream(DEFAULT_WINDOW)?;
+    let ids: Vec<&str> = records.iter().map(|x| x.id.as_str()).collect();
+    assert_eq!(ids.len(), 3);
+    assert_eq!(
+      ids[..2],
+      ["http://example.org/Agent", "http://example.org/Person"]
+    );
+    assert!(ids[2].starts_with("_:"));
+    let agent = &records[0];
+    assert_eq!(agent.types, vec!["http://www.w3.org/2002/07/owl#Class"]);
+    assert_eq!(
+      agent.properties["http://www.w3.org/2000/01/rdf-schema#label"],
+      vec![json!({ "value": "Agent", "language": "en" })]
+    );
+    assert_eq!(
+      agent.properties["http://www.w3.org/2000/01/rdf-schema#seeAlso"],
+      vec![json!({ "id": "http://example.org/Person" })]
+    );
+    assert_eq!(
+      records[2].properties["http://www.w3.org/2002/07/owl#minCardinality"],
+      vec![json!({ "value": "1", "datatype": "http://www.w3.org/2001/XMLSchema#integer" })]
+    );
+
+    // a subject is written once per window it is found in
+    let records = stream(4)?;
+    let agents = records
+      .iter()
+      .filter(|x| x.id == "http://example.org/Agent")
+      .count();
+    assert_eq!(agents, 2);
+    Ok(())
+  }
+}
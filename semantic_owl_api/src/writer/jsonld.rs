@@ -0,0 +1,127 @@
+//! JSON-LD module writes triples as an expanded JSON-LD document
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::vocab::{rdf, xsd};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+/// format_json_ld writes triples as an expanded JSON-LD document. Subjects and their properties
+/// are written in the order they first appear
+pub fn format_json_ld(triples: &[TurtleTriple]) -> String {
+  serde_json::to_string_pretty(&Value::Array(node_objects(triples))).unwrap_or_default()
+}
+
+// node_objects groups triples per subject into node objects
+fn node_objects(triples: &[TurtleTriple]) -> Vec<Value> {
+  // position of the node object of each subject
+  let mut subjects: HashMap<&TurtleTerm, usize> = HashMap::new();
+  let mut nodes: Vec<Map<String, Value>> = vec![];
+  for t in triples.iter() {
+    let idx = *subjects.entry(&t.subject).or_insert_with(|| {
+      let mut node = Map::new();
+      node.insert(String::from("@id"), node_id(&t.subject));
+      nodes.push(node);
+      nodes.len() - 1
+    });
+    let (key, value) = match (t.predicate.as_iri(), &t.object) {
+      // types are written with `@type`, unless the type is not an IRI
+      (Some(RDF_TYPE), TurtleTerm::Iri(iri)) => (String::from("@type"), json!(iri)),
+      (Some(iri), object) => (String::from(iri), value_object(object)),
+      // a predicate is always an IRI
+      (None, _) => continue,
+    };
+    let values = nodes[idx]
+      .entry(key)
+      .or_insert_with(|| Value::Array(vec![]));
+    if let Value::Array(values) = values {
+      if !values.contains(&value) {
+        values.push(value);
+      }
+    }
+  }
+  nodes.into_iter().map(Value::Object).collect()
+}
+
+// node_id returns the `@id` of a subject, an embedded node object for a quoted triple
+fn node_id(term: &TurtleTerm) -> Value {
+  match term {
+    TurtleTerm::QuotedTriple(t) => node_objects(&[TurtleTriple::clone(t)])
+      .pop()
+      .unwrap_or(Value::Null),
+    term => json!(term_id(term)),
+  }
+}
+
+// value_object returns the value of a property. e.g -> {"@value": "Agent", "@language": "en"}
+fn value_object(term: &TurtleTerm) -> Value {
+  match term {
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => match (language, datatype.as_str()) {
+      (Some(language), _) => json!({ "@value": value, "@language": language }),
+      (None, XSD_STRING) => json!({ "@value": value }),
+      (None, datatype) => json!({ "@value": value, "@type": datatype }),
+    },
+    term => json!({ "@id": node_id(term) }),
+  }
+}
+
+// term_id returns the identifier of an IRI or a blank node. e.g -> _:b0
+fn term_id(term: &TurtleTerm) -> String {
+  match term {
+    TurtleTerm::Iri(iri) => iri.clone(),
+    TurtleTerm::BlankNode(label) => format!("_:{}", label),
+    term => term.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  #[test]
+  fn should_format_json_ld() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en ; rdfs:comment "An agent" ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:minCardinality 1 ] .
+"#,
+    )?;
+    let json: Value = serde_json::from_str(&format_json_ld(&document.triples()))?;
+    let nodes = json.as_array().unwrap();
+    assert_eq!(nodes.len(), 2);
+    let agent = &nodes[0];
+    assert_eq!(
+      agent["@id"],
+      "http://www.ontologyrepository.com/CommonCoreOntologies/Agent"
+    );
+    assert_eq!(
+      agent["@type"],
+      json!(["http://www.w3.org/2002/07/owl#Class"])
+    );
+    assert_eq!(
+      agent["http://www.w3.org/2000/01/rdf-schema#label"],
+      json!([{ "@value": "Agent", "@language": "en" }])
+    );
+    assert_eq!(
+      agent["http://www.w3.org/2000/01/rdf-schema#comment"],
+      json!([{ "@value": "An agent" }])
+    );
+    let restriction = agent["http://www.w3.org/2000/01/rdf-schema#subClassOf"][0]["@id"].clone();
+    assert_eq!(nodes[1]["@id"], restriction);
+    assert_eq!(
+      nodes[1]["http://www.w3.org/2002/07/owl#minCardinality"][0]["@type"],
+      "http://www.w3.org/2001/XMLSchema#integer"
+    );
+    Ok(())
+  }
+}
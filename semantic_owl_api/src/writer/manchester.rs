@@ -0,0 +1,272 @@
+//! Manchester module renders class expressions as Manchester syntax strings
+use crate::declarations::class_expression::{ClassExpression, DataRange, FacetRestriction};
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::xsd;
+use crate::writer::renderer::{CurieRenderer, LabelRenderer, TermRenderer};
+
+// datatypes whose literals are written without quotes nor datatype. e.g -> 42, true
+const BARE_DATATYPES: [&str; 3] = [
+  xsd::INTEGER.as_str(),
+  xsd::DECIMAL.as_str(),
+  xsd::BOOLEAN.as_str(),
+];
+
+/// ManchesterRenderer renders class expressions and data ranges in Manchester syntax
+pub struct ManchesterRenderer<'a> {
+  pub terms: Box<dyn TermRenderer + 'a>,
+}
+
+impl<'a> ManchesterRenderer<'a> {
+  pub fn new(terms: Box<dyn TermRenderer + 'a>) -> ManchesterRenderer<'a> {
+    Self { terms }
+  }
+
+  /// for_document creates a renderer writing entities as their label which best matches the
+  /// language preferences, or as their prefixed name when they have none
+  pub fn for_document(
+    document: &'a TurtleDocument,
+    languages: Vec<String>,
+  ) -> ManchesterRenderer<'a> {
+    let curie = CurieRenderer::new(document.prefix_map());
+    Self::new(Box::new(LabelRenderer::new(
+      document,
+      languages,
+      Box::new(curie),
+    )))
+  }
+
+  /// render returns the Manchester syntax of a class expression. Operands which are not a single
+  /// entity are parenthesized
+  /// example
+  /// `Person and ('has role' some Student)`
+  pub fn render(&self, expression: &ClassExpression) -> String {
+    match expression {
+      ClassExpression::Class(x) => self.entity(x),
+      ClassExpression::ObjectIntersectionOf(x) => self.join(x, " and "),
+      ClassExpression::ObjectUnionOf(x) => self.join(x, " or "),
+      ClassExpression::ObjectComplementOf(x) => format!("not {}", self.operand(x)),
+      ClassExpression::ObjectOneOf(x) => {
+        let individuals: Vec<String> = x.iter().map(|i| self.entity(i)).collect();
+        format!("{{{}}}", individuals.join(", "))
+      }
+      ClassExpression::ObjectSomeValuesFrom { property, filler } => {
+        format!("{} some {}", self.entity(property), self.operand(filler))
+      }
+      ClassExpression::ObjectAllValuesFrom { property, filler } => {
+        format!("{} only {}", self.entity(property), self.operand(filler))
+      }
+      ClassExpression::ObjectHasValue {
+        property,
+        individual,
+      } => format!(
+        "{} value {}",
+        self.entity(property),
+        self.entity(individual)
+      ),
+      ClassExpression::ObjectHasSelf { property } => format!("{} Self", self.entity(property)),
+      ClassExpression::ObjectMinCardinality {
+        cardinality,
+        property,
+        filler,
+      } => self.cardinality("min", *cardinality, property, filler.as_deref()),
+      ClassExpression::ObjectMaxCardinality {
+        cardinality,
+        property,
+        filler,
+      } => self.cardinality("max", *cardinality, property, filler.as_deref()),
+      ClassExpression::ObjectExactCardinality {
+        cardinality,
+        property,
+        filler,
+      } => self.cardinality("exactly", *cardinality, property, filler.as_deref()),
+      ClassExpression::DataSomeValuesFrom { property, range } => {
+        format!(
+          "{} some {}",
+          self.entity(property),
+          self.render_range(range)
+        )
+      }
+      ClassExpression::DataAllValuesFrom { property, range } => {
+        format!(
+          "{} only {}",
+          self.entity(property),
+          self.render_range(range)
+        )
+      }
+      ClassExpression::DataHasValue { property, value } => {
+        format!("{} value {}", self.entity(property), self.literal(value))
+      }
+      ClassExpression::DataMinCardinality {
+        cardinality,
+        property,
+        range,
+      } => self.data_cardinality("min", *cardinality, property, range.as_ref()),
+      ClassExpression::DataMaxCardinality {
+        cardinality,
+        property,
+        range,
+      } => self.data_cardinality("max", *cardinality, property, range.as_ref()),
+      ClassExpression::DataExactCardinality {
+        cardinality,
+        property,
+        range,
+      } => self.data_cardinality("exactly", *cardinality, property, range.as_ref()),
+    }
+  }
+
+  /// render_range returns the Manchester syntax of a data range
+  /// example
+  /// `xsd:integer[>= 0, < 150]`
+  pub fn render_range(&self, range: &DataRange) -> String {
+    match range {
+      DataRange::Datatype(x) => self.entity(x),
+      DataRange::DatatypeRestriction {
+        datatype,
+        restrictions,
+      } => {
+        let facets: Vec<String> = restrictions.iter().map(|x| self.facet(x)).collect();
+        format!("{}[{}]", self.entity(datatype), facets.join(", "))
+      }
+    }
+  }
+
+  // entity writes the short form of an IRI, quoted when it is not a single word
+  fn entity(&self, iri: &str) -> String {
+    let name = self.terms.render_iri(iri);
+    let is_word = !name.is_empty()
+      && name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/' | '#'));
+    match is_word {
+      true => name,
+      false => format!("'{}'", name.replace('\'', "\\'")),
+    }
+  }
+
+  // operand writes a nested expression, parenthesized unless it is a single entity or enumeration
+  fn operand(&self, expression: &ClassExpression) -> String {
+    match expression {
+      ClassExpression::Class(_) | ClassExpression::ObjectOneOf(_) => self.render(expression),
+      _ => format!("({})", self.render(expression)),
+    }
+  }
+
+  fn join(&self, operands: &[ClassExpression], separator: &str) -> String {
+    let operands: Vec<String> = operands.iter().map(|x| self.operand(x)).collect();
+    operands.join(separator)
+  }
+
+  fn cardinality(
+    &self,
+    keyword: &str,
+    cardinality: u32,
+    property: &str,
+    filler: Option<&ClassExpression>,
+  ) -> String {
+    let restriction = format!("{} {} {}", self.entity(property), keyword, cardinality);
+    match filler {
+      Some(x) => format!("{} {}", restriction, self.operand(x)),
+      None => restriction,
+    }
+  }
+
+  fn data_cardinality(
+    &self,
+    keyword: &str,
+    cardinality: u32,
+    property: &str,
+    range: Option<&DataRange>,
+  ) -> String {
+    let restriction = format!("{} {} {}", self.entity(property), keyword, cardinality);
+    match range {
+      Some(x) => format!("{} {}", restriction, self.render_range(x)),
+      None => restriction,
+    }
+  }
+
+  // facet writes a facet restriction with the symbol of its facet. e.g -> >= 0
+  fn facet(&self, restriction: &FacetRestriction) -> String {
+    let facet = restriction
+      .facet
+      .strip_prefix(xsd::NAMESPACE)
+      .unwrap_or(&restriction.facet);
+    let symbol = match facet {
+      "minInclusive" => ">=",
+      "minExclusive" => ">",
+      "maxInclusive" => "<=",
+      "maxExclusive" => "<",
+      "length" | "minLength" | "maxLength" | "pattern" | "langRange" => facet,
+      _ => {
+        return format!(
+          "{} {}",
+          self.entity(&restriction.facet),
+          self.literal(&restriction.value)
+        )
+      }
+    };
+    format!("{} {}", symbol, self.literal(&restriction.value))
+  }
+
+  // literal writes numbers and booleans bare, and other literals as in turtle
+  fn literal(&self, term: &TurtleTerm) -> String {
+    match term {
+      TurtleTerm::Literal {
+        value,
+        datatype,
+        language: None,
+      } if BARE_DATATYPES.contains(&datatype.as_str()) => value.clone(),
+      t => self.terms.render(t),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::declarations::class_expression::ClassExpressionParser;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::vocab::rdfs;
+
+  const DOCUMENT: &str = r#"
+@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+cco:has_process_part a owl:ObjectProperty ; rdfs:label "has process part"@en .
+cco:has_age a owl:DatatypeProperty .
+cco:Velocity a owl:Class ; rdfs:label "Velocity"@en .
+cco:Acceleration rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_process_part ; owl:someValuesFrom cco:Velocity ] .
+cco:Acceleration rdfs:subClassOf [ a owl:Class ;
+  owl:intersectionOf ( cco:Motion [ a owl:Class ; owl:complementOf [ a owl:Class ; owl:unionOf ( cco:Rest cco:Stop ) ] ] ) ] .
+cco:Acceleration rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_process_part ;
+  owl:maxQualifiedCardinality "2"^^xsd:nonNegativeInteger ; owl:onClass [ a owl:Class ; owl:oneOf ( cco:A cco:B ) ] ] .
+cco:Acceleration rdfs:subClassOf [ a owl:Restriction ; owl:onProperty cco:has_age ;
+  owl:someValuesFrom [ a rdfs:Datatype ; owl:onDatatype xsd:integer ;
+                       owl:withRestrictions ( [ xsd:minInclusive 0 ] [ xsd:maxExclusive 150 ] ) ] ] .
+"#;
+
+  #[test]
+  fn should_render_class_expressions_in_manchester_syntax() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let graph = document.graph();
+    let parser = ClassExpressionParser::new(&graph);
+    let renderer = ManchesterRenderer::for_document(&document, vec![String::from("en")]);
+    let sub_class_of = TurtleTerm::from(rdfs::SUB_CLASS_OF);
+    let rendered: Vec<String> = graph
+      .triples_matching(None, Some(&sub_class_of), None)
+      .filter_map(|t| parser.parse(&t.object))
+      .map(|x| renderer.render(&x))
+      .collect();
+    assert_eq!(
+      rendered,
+      vec![
+        "'has process part' some Velocity",
+        "cco:Motion and (not (cco:Rest or cco:Stop))",
+        "'has process part' max 2 {cco:A, cco:B}",
+        "cco:has_age some xsd:integer[>= 0, < 150]",
+      ]
+    );
+    Ok(())
+  }
+}
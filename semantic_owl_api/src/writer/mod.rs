@@ -0,0 +1,9 @@
+pub mod convert;
+pub mod format;
+pub mod jsonl;
+pub mod jsonld;
+pub mod manchester;
+pub mod prefixes;
+pub mod rdfxml;
+pub mod renderer;
+pub mod stream;
@@ -0,0 +1,177 @@
+//! Prefixes module computes the prefixes which make a written document the shortest
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::std_prefix_names::StdPrefixes;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::loader::parsers::ttl_pname_parser::is_prefixed_name;
+use crate::vocab::{rdf, xsd};
+use crate::writer::format::{render_iri, Style};
+use std::collections::{BTreeMap, BTreeSet};
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+const RDF_TYPE: &str = rdf::TYPE.as_str();
+
+// upper bound of the length of a derived prefix name
+const MAX_PREFIX_LENGTH: usize = 10;
+
+/// optimal_prefixes returns the prefixes to write the document with in the given style: the
+/// declared prefixes which are used, and prefixes for the namespaces repeated in full IRIs.
+/// The base of the document is kept
+pub fn optimal_prefixes(document: &TurtleDocument, style: &Style) -> PrefixMap {
+  let mut map = document.prefix_map();
+  let iris = written_iris(document, style);
+
+  // namespaces of the IRIs written in full, with the number of IRIs
+  let mut namespaces: BTreeMap<&str, usize> = BTreeMap::new();
+  for iri in iris.iter() {
+    if render_iri(iri, &map).starts_with('<') {
+      if let Some(ns) = namespace_of(iri) {
+        *namespaces.entry(ns).or_default() += 1;
+      }
+    }
+  }
+  let registry = StdPrefixes::default();
+  let mut candidates: Vec<(&str, usize)> = namespaces.into_iter().collect();
+  // the most repeated namespaces pick their names first
+  candidates.sort_by_key(|(_, uses)| std::cmp::Reverse(*uses));
+  for (ns, uses) in candidates {
+    let prefix = prefix_name(ns, &map, &registry);
+    // `prefix:` replaces `<namespace` and `>`
+    let saved = uses * (ns.len() + 1).saturating_sub(prefix.len());
+    let cost = format!("@prefix {}: <{}> .\n", prefix, ns).len();
+    if uses > 1 && saved > cost {
+      map.insert(&prefix, ns);
+    }
+  }
+
+  let used: BTreeSet<String> = iris
+    .iter()
+    .map(|x| render_iri(x, &map))
+    .filter(|x| !x.starts_with('<'))
+    .filter_map(|x| x.split_once(':').map(|(p, _)| String::from(p)))
+    .collect();
+  map.prefixes.retain(|p, _| used.contains(p));
+  map
+}
+
+// written_iris returns the IRIs written for the triples of the document, including the datatypes
+// of literals, once per occurrence
+fn written_iris(document: &TurtleDocument, style: &Style) -> Vec<String> {
+  let mut iris = vec![];
+  for t in document.triples() {
+    for term in [&t.subject, &t.predicate, &t.object] {
+      match term {
+        TurtleTerm::Iri(x) if style.use_a && term == &t.predicate && x == RDF_TYPE => {}
+        TurtleTerm::Iri(x) => iris.push(x.clone()),
+        TurtleTerm::Literal {
+          datatype,
+          language: None,
+          ..
+        } if datatype != XSD_STRING => iris.push(datatype.clone()),
+        _ => {}
+      }
+    }
+  }
+  iris
+}
+
+// namespace_of returns the namespace of an IRI, i.e the IRI up to its last `#` or `/`, when the
+// rest of the IRI can be written as the local part of a prefixed name
+fn namespace_of(iri: &str) -> Option<&str> {
+  let idx = iri.rfind(['#', '/'])?;
+  let (ns, local) = iri.split_at(idx + 1);
+  // a namespace has more than a scheme, e.g `http://`
+  let authority = ns.find("://").map(|x| &ns[x + 3..]).unwrap_or(ns);
+  match authority.len() > 1 && is_prefixed_name(&format!("p:{}", local)) {
+    true => Some(ns),
+    false => None,
+  }
+}
+
+// prefix_name returns a name, not declared in the map, for the prefix of a namespace
+fn prefix_name(ns: &str, map: &PrefixMap, registry: &StdPrefixes) -> String {
+  if let Some(prefix) = registry.prefix(ns) {
+    if map.namespace(prefix).is_none() {
+      return String::from(prefix);
+    }
+  }
+  // the last segment of the namespace, e.g `agents` for `http://example.org/agents/`
+  let segment = ns
+    .trim_end_matches(['#', '/'])
+    .rsplit(['/', '#', ':'])
+    .next()
+    .unwrap_or_default();
+  let mut name: String = segment
+    .chars()
+    .filter(|c| c.is_ascii_alphanumeric())
+    .map(|c| c.to_ascii_lowercase())
+    .take(MAX_PREFIX_LENGTH)
+    .collect();
+  if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    name = format!("ns{}", name);
+  }
+  let mut candidate = name.clone();
+  let mut n = 1;
+  while map.namespace(&candidate).is_some() || !is_prefixed_name(&format!("{}:", candidate)) {
+    candidate = format!("{}{}", name, n);
+    n += 1;
+  }
+  candidate
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+  use crate::writer::format::format_turtle;
+
+  const DOCUMENT: &str = r#"
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix foaf: <http://xmlns.com/foaf/0.1/> .
+@prefix unused: <http://example.org/unused#> .
+
+<http://www.ontologyrepository.com/CommonCoreOntologies/Agent> a owl:Class ;
+  <http://www.w3.org/2004/02/skos/core#prefLabel> "agent" ;
+  <http://www.w3.org/2004/02/skos/core#altLabel> "actor" .
+<http://www.ontologyrepository.com/CommonCoreOntologies/Person> a owl:Class ;
+  <http://www.w3.org/2000/01/rdf-schema#subClassOf> <http://www.ontologyrepository.com/CommonCoreOntologies/Agent> .
+<http://www.ontologyrepository.com/CommonCoreOntologies/Organization> a owl:Class .
+<http://example.org/once> a owl:Class .
+"#;
+
+  #[test]
+  fn should_compute_optimal_prefixes() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let map = optimal_prefixes(&document, &Style::compact());
+    assert_eq!(
+      map.prefixes.keys().collect::<Vec<_>>(),
+      vec!["commoncore", "owl", "skos"]
+    );
+    assert_eq!(
+      map.namespace("commoncore"),
+      Some("http://www.ontologyrepository.com/CommonCoreOntologies/")
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn should_write_optimal_prefixes() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    for style in [Style::compact(), Style::protege(), Style::obo()].iter() {
+      let mut optimized = style.clone();
+      optimized.optimize_prefixes = true;
+      let formatted = format_turtle(&document, style);
+      let shrunk = format_turtle(&document, &optimized);
+      assert!(shrunk.len() < formatted.len());
+      assert!(!shrunk.contains("@prefix unused:"));
+      assert!(!shrunk.contains("@prefix foaf:"));
+      assert!(shrunk.contains("commoncore:Person"));
+      let reloaded: BTreeSet<_> = load_turtle_document_from_str(&shrunk)?
+        .triples()
+        .into_iter()
+        .collect();
+      assert_eq!(reloaded, document.triples().into_iter().collect());
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,241 @@
+//! RDF/XML module writes triples as an RDF/XML document
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleTerm, TurtleTriple};
+use crate::vocab::{rdf, xsd};
+use std::collections::{BTreeMap, HashMap};
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+const RDF_NAMESPACE: &str = rdf::NAMESPACE;
+
+/// format_rdf_xml writes triples as RDF/XML. Subjects and their properties are written in the
+/// order they first appear.
+/// It will return an error for triples RDF/XML can not express: quoted triples, and predicates
+/// which can not be split into a namespace and an XML name, e.g `http://example.org/1`
+pub fn format_rdf_xml(triples: &[TurtleTriple], map: &PrefixMap) -> std::io::Result<String> {
+  // namespaces of the predicates by prefix, and the qualified name of each predicate
+  let mut namespaces: BTreeMap<String, String> = BTreeMap::new();
+  let mut qnames: HashMap<&str, String> = HashMap::new();
+  for t in triples.iter() {
+    let predicate = match t.predicate.as_iri() {
+      Some(x) => x,
+      None => continue,
+    };
+    if qnames.contains_key(predicate) {
+      continue;
+    }
+    let (namespace, name) = split_iri(predicate).ok_or_else(|| {
+      unsupported(&format!(
+        "the predicate <{}> can not be written as an XML name",
+        predicate
+      ))
+    })?;
+    let prefix = match namespaces.iter().find(|(_, x)| *x == namespace) {
+      Some((prefix, _)) => prefix.clone(),
+      None => {
+        let prefix = prefix_of(namespace, map, &namespaces);
+        namespaces.insert(prefix.clone(), String::from(namespace));
+        prefix
+      }
+    };
+    qnames.insert(predicate, format!("{}:{}", prefix, name));
+  }
+
+  // statements grouped per subject
+  let mut subjects: HashMap<&TurtleTerm, usize> = HashMap::new();
+  let mut descriptions: Vec<(&TurtleTerm, Vec<&TurtleTriple>)> = vec![];
+  for t in triples.iter() {
+    let idx = *subjects.entry(&t.subject).or_insert_with(|| {
+      descriptions.push((&t.subject, vec![]));
+      descriptions.len() - 1
+    });
+    descriptions[idx].1.push(t);
+  }
+
+  let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<rdf:RDF");
+  out.push_str(&format!(" xmlns:rdf=\"{}\"", RDF_NAMESPACE));
+  for (prefix, namespace) in namespaces.iter().filter(|(x, _)| *x != "rdf") {
+    out.push_str(&format!(
+      "\n         xmlns:{}=\"{}\"",
+      prefix,
+      escape(namespace)
+    ));
+  }
+  out.push_str(">\n");
+  for (subject, statements) in descriptions {
+    out.push_str(&format!(
+      "  <rdf:Description {}>\n",
+      node_attribute(subject, "about")?
+    ));
+    for t in statements {
+      let qname = match t.predicate.as_iri() {
+        Some(x) => &qnames[x],
+        None => continue,
+      };
+      out.push_str(&format!("    {}\n", property_element(qname, &t.object)?));
+    }
+    out.push_str("  </rdf:Description>\n");
+  }
+  out.push_str("</rdf:RDF>\n");
+  Ok(out)
+}
+
+// property_element writes the element of a property and its value.
+// e.g -> <rdfs:label xml:lang="en">Agent</rdfs:label>
+fn property_element(qname: &str, object: &TurtleTerm) -> std::io::Result<String> {
+  Ok(match object {
+    TurtleTerm::Literal {
+      value,
+      datatype,
+      language,
+    } => {
+      let attribute = match (language, datatype.as_str()) {
+        (Some(language), _) => format!(" xml:lang=\"{}\"", escape(language)),
+        (None, XSD_STRING) => String::new(),
+        (None, datatype) => format!(" rdf:datatype=\"{}\"", escape(datatype)),
+      };
+      format!("<{}{}>{}</{}>", qname, attribute, escape(value), qname)
+    }
+    object => format!("<{} {}/>", qname, node_attribute(object, "resource")?),
+  })
+}
+
+// node_attribute writes the attribute naming a resource: `rdf:about` or `rdf:resource` for an
+// IRI, `rdf:nodeID` for a blank node
+fn node_attribute(term: &TurtleTerm, iri_attribute: &str) -> std::io::Result<String> {
+  match term {
+    TurtleTerm::Iri(iri) => Ok(format!("rdf:{}=\"{}\"", iri_attribute, escape(iri))),
+    TurtleTerm::BlankNode(label) => Ok(format!("rdf:nodeID=\"b{}\"", escape(label))),
+    term => Err(unsupported(&format!(
+      "{} can not be written in RDF/XML",
+      term
+    ))),
+  }
+}
+
+// split_iri splits an IRI into a namespace and the longest XML name ending it.
+// e.g -> http://www.w3.org/2000/01/rdf-schema# and label
+fn split_iri(iri: &str) -> Option<(&str, &str)> {
+  let mut start = None;
+  for (idx, c) in iri.char_indices().rev() {
+    if is_name_start(c) {
+      start = Some(idx);
+    } else if !is_name_char(c) {
+      break;
+    }
+  }
+  let start = start?;
+  match start {
+    0 => None,
+    _ => Some((&iri[..start], &iri[start..])),
+  }
+}
+
+fn is_name_start(c: char) -> bool {
+  c == '_' || c.is_alphabetic()
+}
+
+fn is_name_char(c: char) -> bool {
+  is_name_start(c) || c == '-' || c == '.' || c.is_ascii_digit() || c == '\u{b7}'
+}
+
+// prefix_of returns the prefix of a namespace: the one given, when it is an XML name not yet
+// taken, a generated one otherwise. e.g -> ns1
+fn prefix_of(namespace: &str, map: &PrefixMap, taken: &BTreeMap<String, String>) -> String {
+  if namespace == RDF_NAMESPACE {
+    return String::from("rdf");
+  }
+  let given = map.prefixes.iter().find_map(|(prefix, x)| {
+    let is_name = prefix.starts_with(is_name_start)
+      && prefix.chars().all(is_name_char)
+      && !prefix.to_ascii_lowercase().starts_with("xml");
+    match x == namespace && is_name && prefix != "rdf" && !taken.contains_key(prefix) {
+      true => Some(prefix.clone()),
+      false => None,
+    }
+  });
+  given.unwrap_or_else(|| {
+    (1..)
+      .map(|x| format!("ns{}", x))
+      .find(|x| !taken.contains_key(x) && !map.prefixes.contains_key(x))
+      .unwrap_or_default()
+  })
+}
+
+// escape escapes the characters of a text or an attribute value which are markup in XML
+fn escape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' => out.push_str("&quot;"),
+      '\r' => out.push_str("&#13;"),
+      c => out.push(c),
+    }
+  }
+  out
+}
+
+fn unsupported(reason: &str) -> std::io::Error {
+  std::io::Error::new(std::io::ErrorKind::InvalidData, reason)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  #[test]
+  fn should_format_rdf_xml() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(
+      r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en ; rdfs:comment "An <agent> & more" ;
+  rdfs:subClassOf [ a owl:Restriction ; owl:minCardinality 1 ] .
+"#,
+    )?;
+    let xml = format_rdf_xml(&document.triples(), &document.prefix_map())?;
+    let lines: Vec<&str> = xml.lines().map(|x| x.trim()).collect();
+    assert_eq!(lines[0], "<?xml version=\"1.0\" encoding=\"utf-8\"?>");
+    assert!(xml.contains(" xmlns:rdfs=\"http://www.w3.org/2000/01/rdf-schema#\""));
+    assert!(lines.contains(
+      &"<rdf:Description rdf:about=\"http://www.ontologyrepository.com/CommonCoreOntologies/Agent\">"
+    ));
+    assert!(lines.contains(&"<rdf:type rdf:resource=\"http://www.w3.org/2002/07/owl#Class\"/>"));
+    assert!(lines.contains(&"<rdfs:label xml:lang=\"en\">Agent</rdfs:label>"));
+    assert!(lines.contains(&"<rdfs:comment>An &lt;agent&gt; &amp; more</rdfs:comment>"));
+    assert!(lines.contains(&"<rdfs:subClassOf rdf:nodeID=\"bgenid1\"/>"));
+    assert!(lines.contains(&"<rdf:Description rdf:nodeID=\"bgenid1\">"));
+    assert!(lines.contains(
+      &"<owl:minCardinality rdf:datatype=\"http://www.w3.org/2001/XMLSchema#integer\">1</owl:minCardinality>"
+    ));
+    assert_eq!(lines.last(), Some(&"</rdf:RDF>"));
+    Ok(())
+  }
+
+  #[test]
+  fn should_generate_prefixes_and_reject_unwritable_triples() -> std::io::Result<()> {
+    let iri = |x: &str| TurtleTerm::Iri(String::from(x));
+    let triples = vec![TurtleTriple::new(
+      iri("http://example.org/a"),
+      iri("http://example.org/vocab#name"),
+      iri("http://example.org/b"),
+    )];
+    let xml = format_rdf_xml(&triples, &PrefixMap::new())?;
+    assert!(xml.contains("xmlns:ns1=\"http://example.org/vocab#\""));
+    assert!(xml.contains("<ns1:name rdf:resource=\"http://example.org/b\"/>"));
+
+    let triples = vec![TurtleTriple::new(
+      iri("http://example.org/a"),
+      iri("http://example.org/1"),
+      iri("http://example.org/b"),
+    )];
+    let err = format_rdf_xml(&triples, &PrefixMap::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+  }
+}
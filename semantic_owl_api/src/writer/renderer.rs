@@ -0,0 +1,167 @@
+//! Renderer module turns the terms of a document into the short text shown to people
+use crate::analysis::reasoner::PropertyValues;
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::{TurtleDocument, TurtleTerm};
+use crate::vocab::xsd;
+
+const XSD_STRING: &str = xsd::STRING.as_str();
+
+/// TermRenderer renders the terms of a document in a short form
+pub trait TermRenderer {
+  /// render_iri returns the short form of an IRI
+  fn render_iri(&self, iri: &str) -> String;
+
+  /// render returns the short form of a term. Blank nodes are written as in turtle, and literals
+  /// are written with the short form of their datatype
+  /// example
+  /// `"42"^^xsd:integer`, `"agent"@en` or `_:genid1`
+  fn render(&self, term: &TurtleTerm) -> String {
+    match term {
+      TurtleTerm::Iri(x) => self.render_iri(x),
+      TurtleTerm::Literal {
+        datatype,
+        language: None,
+        ..
+      } if datatype != XSD_STRING => {
+        let literal = term.to_string();
+        let value = &literal[..literal.len() - datatype.len() - 4];
+        format!("{}^^{}", value, self.render_iri(datatype))
+      }
+      t => t.to_string(),
+    }
+  }
+}
+
+/// CurieRenderer renders an IRI as a prefixed name, using the longest matching namespace.
+/// IRIs outside the namespaces of the map are rendered in full
+/// example
+/// `http://www.w3.org/2002/07/owl#Class` -> `owl:Class`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CurieRenderer {
+  pub map: PrefixMap,
+}
+
+impl CurieRenderer {
+  pub fn new(map: PrefixMap) -> CurieRenderer {
+    Self { map }
+  }
+}
+
+impl TermRenderer for CurieRenderer {
+  fn render_iri(&self, iri: &str) -> String {
+    self.map.shorten(iri).unwrap_or_else(|| String::from(iri))
+  }
+}
+
+/// FragmentRenderer renders an IRI as its fragment, or its last path segment when it has none.
+/// IRIs ending with `#` or `/` are rendered in full
+/// example
+/// `http://www.w3.org/2002/07/owl#Class` -> `Class`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FragmentRenderer;
+
+impl TermRenderer for FragmentRenderer {
+  fn render_iri(&self, iri: &str) -> String {
+    match iri.rsplit(['#', '/']).next() {
+      Some(x) if !x.is_empty() && x.len() < iri.len() => String::from(x),
+      _ => String::from(iri),
+    }
+  }
+}
+
+/// LabelRenderer renders an entity as its `rdfs:label` which best matches a list of language
+/// preferences, most preferred first. Entities without a label are rendered by the fallback
+pub struct LabelRenderer<'a> {
+  // labels of the document, indexed once when the renderer is created
+  values: PropertyValues,
+
+  // language ranges, most preferred first. e.g -> en-GB, en, *
+  pub languages: Vec<String>,
+
+  pub fallback: Box<dyn TermRenderer + 'a>,
+}
+
+impl<'a> LabelRenderer<'a> {
+  pub fn new(
+    document: &'a TurtleDocument,
+    languages: Vec<String>,
+    fallback: Box<dyn TermRenderer + 'a>,
+  ) -> LabelRenderer<'a> {
+    Self {
+      values: PropertyValues::new(document),
+      languages,
+      fallback,
+    }
+  }
+}
+
+impl<'a> TermRenderer for LabelRenderer<'a> {
+  fn render_iri(&self, iri: &str) -> String {
+    let preferences: Vec<&str> = self.languages.iter().map(|x| x.as_str()).collect();
+    match self.values.labels_of(iri).best_match(&preferences) {
+      Some(TurtleTerm::Literal { value, .. }) => value.clone(),
+      _ => self.fallback.render_iri(iri),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix cco: <http://www.ontologyrepository.com/CommonCoreOntologies/> .
+@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+
+cco:Agent a owl:Class ; rdfs:label "Agent"@en , "Agent \"fr\""@fr .
+cco:Person a owl:Class ; cco:has_value "42"^^xsd:integer .
+"#;
+
+  #[test]
+  fn should_render_terms_in_short_form() -> std::io::Result<()> {
+    let document = load_turtle_document_from_str(DOCUMENT)?;
+    let agent = "http://www.ontologyrepository.com/CommonCoreOntologies/Agent";
+    let person = "http://www.ontologyrepository.com/CommonCoreOntologies/Person";
+    let curie = CurieRenderer::new(document.prefix_map());
+    assert_eq!(curie.render_iri(person), "cco:Person");
+    assert_eq!(
+      curie.render_iri("http://example.org/Thing"),
+      "http://example.org/Thing"
+    );
+
+    assert_eq!(FragmentRenderer.render_iri(person), "Person");
+    assert_eq!(
+      FragmentRenderer.render_iri("http://www.w3.org/2002/07/owl#Class"),
+      "Class"
+    );
+    assert_eq!(
+      FragmentRenderer.render_iri("http://example.org/"),
+      "http://example.org/"
+    );
+
+    let languages = |x: &[&str]| x.iter().map(|x| String::from(*x)).collect();
+    let label = LabelRenderer::new(&document, languages(&["fr", "*"]), Box::new(curie.clone()));
+    assert_eq!(label.render_iri(agent), "Agent \"fr\"");
+    assert_eq!(label.render_iri(person), "cco:Person");
+    let label = LabelRenderer::new(&document, languages(&["de"]), Box::new(FragmentRenderer));
+    assert_eq!(label.render_iri(agent), "Agent");
+
+    let literals: Vec<String> = document
+      .triples()
+      .iter()
+      .filter(|t| t.object.is_literal())
+      .map(|t| curie.render(&t.object))
+      .collect();
+    assert_eq!(
+      literals,
+      vec![
+        "\"Agent\"@en",
+        "\"Agent \\\"fr\\\"\"@fr",
+        "\"42\"^^xsd:integer"
+      ]
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,176 @@
+//! Stream module writes turtle with bounded memory
+use crate::declarations::prefix_map::PrefixMap;
+use crate::declarations::turtle::TurtleTriple;
+use crate::loader::handler::TurtleHandler;
+use crate::writer::format::{format_triples, Style};
+use std::io::Write;
+
+// number of triples held by default before they are written
+pub const DEFAULT_WINDOW: usize = 10_000;
+
+/// TurtleStreamWriter writes the triples it is given as turtle, grouped by subject within a
+/// window of triples. Directives are written as they are declared. The sections, the inline
+/// blank nodes and the prefix optimization of the style need the whole document and are not applied
+pub struct TurtleStreamWriter<W: Write> {
+  out: W,
+  style: Style,
+
+  // prefixes and base declared so far
+  map: PrefixMap,
+
+  // maximum number of triples held before they are written
+  window: usize,
+
+  // triples held, in the order they were given
+  triples: Vec<TurtleTriple>,
+
+  // determines whether a statement was written, to separate the groups of statements
+  written: bool,
+
+  // first error met by the methods of the handler, which can not return it. Returned by `finish`
+  error: Option<std::io::Error>,
+}
+
+impl<W: Write> TurtleStreamWriter<W> {
+  /// new creates a writer holding at most `window` triples, and at least one, in memory
+  /// example
+  /// `TurtleStreamWriter::new(std::io::stdout(), Style::compact(), DEFAULT_WINDOW)`
+  pub fn new(out: W, style: Style, window: usize) -> TurtleStreamWriter<W> {
+    Self {
+      out,
+      style,
+      map: PrefixMap::new(),
+      window: window.max(1),
+      triples: vec![],
+      written: false,
+      error: None,
+    }
+  }
+
+  /// prefix writes a `@prefix` directive, after the triples held, and shortens the IRIs of the
+  /// following triples with it
+  pub fn prefix(&mut self, prefix: &str, namespace: &str) -> std::io::Result<()> {
+    self.flush()?;
+    self
+      .map
+      .prefixes
+      .insert(String::from(prefix), String::from(namespace));
+    writeln!(self.out, "@prefix {}: <{}> .", prefix, namespace)
+  }
+
+  /// base writes a `@base` directive, after the triples held
+  pub fn base(&mut self, iri: &str) -> std::io::Result<()> {
+    self.flush()?;
+    self.map.base = Some(String::from(iri));
+    writeln!(self.out, "@base <{}> .", iri)
+  }
+
+  /// write_triple holds a triple, writing the triples held when the window is full
+  pub fn write_triple(&mut self, triple: &TurtleTriple) -> std::io::Result<()> {
+    self.triples.push(triple.clone());
+    match self.triples.len() >= self.window {
+      true => self.flush(),
+      false => Ok(()),
+    }
+  }
+
+  /// flush writes the triples held, grouped by subject
+  pub fn flush(&mut self) -> std::io::Result<()> {
+    if self.triples.is_empty() {
+      return Ok(());
+    }
+    let triples = std::mem::take(&mut self.triples);
+    if !self.written || self.style.blank_line_between_subjects {
+      writeln!(self.out)?;
+    }
+    self.written = true;
+    let statements = format_triples(triples, &self.map, &self.style);
+    self.out.write_all(statements.as_bytes())
+  }
+
+  /// finish writes the triples held and returns the output. It returns the first error met while
+  /// the writer was used as a handler, if any
+  pub fn finish(mut self) -> std::io::Result<W> {
+    if let Some(e) = self.error.take() {
+      return Err(e);
+    }
+    self.flush()?;
+    self.out.flush()?;
+    Ok(self.out)
+  }
+
+  // record keeps the first error met by a method of the handler. Nothing is written after it
+  fn record(&mut self, result: std::io::Result<()>) {
+    if let (None, Err(e)) = (&self.error, result) {
+      self.error = Some(e);
+    }
+  }
+}
+
+impl<W: Write> TurtleHandler for TurtleStreamWriter<W> {
+  fn on_base(&mut self, iri: &str) {
+    if self.error.is_none() {
+      let result = self.base(iri);
+      self.record(result);
+    }
+  }
+
+  fn on_prefix(&mut self, prefix: &str, namespace: &str) {
+    if self.error.is_none() {
+      let result = self.prefix(prefix, namespace);
+      self.record(result);
+    }
+  }
+
+  fn on_triple(&mut self, triple: &TurtleTriple) {
+    if self.error.is_none() {
+      let result = self.write_triple(triple);
+      self.record(result);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::loader::handler::parse_turtle_str_with_handler;
+  use crate::loader::load::load_turtle_document_from_str;
+
+  const DOCUMENT: &str = r#"@prefix owl: <http://www.w3.org/2002/07/owl#> .
+@prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+@prefix : <http://example.org/> .
+
+:Person a owl:Class ; rdfs:label "Person" .
+:Agent a owl:Class .
+:Person rdfs:subClassOf :Agent , [ owl:onProperty :has_part ; owl:someValuesFrom :Heart ] .
+"#;
+
+  // stream writes the document through a writer holding `window` triples
+  fn stream(window: usize) -> std::io::Result<String> {
+    let mut writer = TurtleStreamWriter::new(vec![], Style::compact(), window);
+    parse_turtle_str_with_handler(DOCUMENT, &mut writer)?;
+    let out = writer.finish()?;
+    Ok(String::from_utf8(out).unwrap())
+  }
+
+  #[test]
+  fn should_stream_turtle() -> std::io::Result<()> {
+    let expected = load_turtle_document_from_str(DOCUMENT)?;
+
+    // the whole document fits in the window
+    let written = stream(DEFAULT_WINDOW)?;
+    assert!(written.starts_with("@prefix owl: <http://www.w3.org/2002/07/owl#> .\n"));
+    assert!(written
+      .contains("\n\n:Person a owl:Class ; rdfs:label \"Person\" ; rdfs:subClassOf :Agent , _:"));
+    // generated blank node labels are read back under other labels
+    assert!(load_turtle_document_from_str(&written)?.isomorphic_eq(&expected)?);
+
+    // a subject is written once per window it is found in
+    let written = stream(3)?;
+    assert_eq!(written.matches(":Person a owl:Class").count(), 1);
+    assert_eq!(written.matches("\n:Person ").count(), 2);
+    // generated blank node labels are read back under other labels
+    assert!(load_turtle_document_from_str(&written)?.isomorphic_eq(&expected)?);
+    Ok(())
+  }
+}
@@ -13,7 +13,10 @@ fn should_parse_turle_document() -> std::io::Result<()> {
     let path = path.to_str().unwrap();
     if path.ends_with(".ttl") {
       match load_turtle_document(path) {
-        Ok(d) => assert_ne!(d.headers.len(), 0),
+        Ok(d) => {
+          assert_ne!(d.headers.len(), 0);
+          assert_ne!(d.triples().len(), 0);
+        }
         Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput),
       }
     }